@@ -0,0 +1,53 @@
+//! Build script generating the gRPC service of the `grpc` feature.
+//!
+//! The service is described in Rust with the manual builder of `tonic-build`, so no `protoc` is needed.
+//! Its messages are defined in `src/grpc.rs`, `proto/simulator.proto` is the matching definition for clients.
+
+fn main() {
+    #[cfg(feature = "grpc")]
+    grpc();
+}
+
+/// Generate the server and client of the simulator service.
+#[cfg(feature = "grpc")]
+fn grpc() {
+    use tonic_build::manual::{Builder, Method, Service};
+
+    let method = |name: &str, route: &str, input: &str, output: &str| {
+        Method::builder()
+            .name(name)
+            .route_name(route)
+            .input_type(format!("crate::grpc::proto::{}", input))
+            .output_type(format!("crate::grpc::proto::{}", output))
+            .codec_path("tonic::codec::ProstCodec")
+    };
+
+    let service = Service::builder()
+        .name("Simulator")
+        .package("tokenomics.simulator")
+        .method(
+            method(
+                "create_simulation",
+                "CreateSimulation",
+                "CreateSimulationRequest",
+                "SimulationHandle",
+            )
+            .build(),
+        )
+        .method(method("run", "Run", "SimulationRequest", "Report").build())
+        .method(
+            method(
+                "stream_intervals",
+                "StreamIntervals",
+                "SimulationRequest",
+                "Report",
+            )
+            .server_streaming()
+            .build(),
+        )
+        .method(method("get_report", "GetReport", "SimulationRequest", "Report").build())
+        .build();
+
+    println!("cargo:rerun-if-changed=build.rs");
+    Builder::new().compile(&[service]);
+}
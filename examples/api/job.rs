@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::json;
+use tokenomics_simulator::{JobQueue, SimulationBuilder};
+use uuid::Uuid;
+
+use crate::{simulation, Exception};
+
+/// Enqueue a new simulation job.
+///
+/// # Arguments
+///
+/// * `queue` - Job queue running the simulations.
+/// * `data` - Simulation input data.
+///
+/// # Returns
+///
+/// ID of the enqueued job.
+pub async fn create(
+    State(queue): State<Arc<JobQueue>>,
+    Json(data): Json<SimulationBuilder>,
+) -> impl IntoResponse {
+    let simulation = match simulation::build(data) {
+        Ok(simulation) => simulation,
+        Err(response) => return response,
+    };
+
+    let id = queue.enqueue(simulation);
+
+    (StatusCode::ACCEPTED, Json(json!({ "id": id }))).into_response()
+}
+
+/// Poll a simulation job.
+/// Finished jobs are returned with their simulation and removed from the queue.
+///
+/// # Arguments
+///
+/// * `queue` - Job queue running the simulations.
+/// * `id` - ID of the job.
+///
+/// # Returns
+///
+/// Status of the job, and the simulation once the job is finished.
+pub async fn get(State(queue): State<Arc<JobQueue>>, Path(id): Path<Uuid>) -> impl IntoResponse {
+    match queue.status(id) {
+        Some(status) if status.is_finished() => {
+            let simulation = queue.take(id);
+
+            (
+                StatusCode::OK,
+                Json(json!({ "status": status, "simulation": simulation })),
+            )
+                .into_response()
+        }
+        Some(status) => (StatusCode::OK, Json(json!({ "status": status }))).into_response(),
+        None => Exception::SimulationNotFound.into_response(),
+    }
+}
+
+/// Cancel a simulation job.
+///
+/// # Arguments
+///
+/// * `queue` - Job queue running the simulations.
+/// * `id` - ID of the job.
+///
+/// # Returns
+///
+/// Status of the job after the cancellation request.
+pub async fn cancel(State(queue): State<Arc<JobQueue>>, Path(id): Path<Uuid>) -> impl IntoResponse {
+    queue.cancel(id);
+
+    match queue.status(id) {
+        Some(status) => (StatusCode::OK, Json(json!({ "status": status }))).into_response(),
+        None => Exception::SimulationNotFound.into_response(),
+    }
+}
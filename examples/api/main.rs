@@ -1,16 +1,18 @@
-use std::net::SocketAddr;
+use std::{net::SocketAddr, sync::Arc};
 
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
-    routing::post,
+    routing::{get, post},
     Json, Router,
 };
 use serde::Serialize;
 use serde_json::json;
 use thiserror::Error;
+use tokenomics_simulator::JobQueue;
 use tokio::net::TcpListener;
 
+mod job;
 mod simulation;
 mod validator;
 
@@ -69,7 +71,16 @@ impl IntoResponse for Exception {
 ///
 /// The application router.
 pub async fn app() -> Router {
-    Router::new().route("/simulation", post(simulation::create))
+    // Background jobs run at most 4 simulations at the same time
+    let queue = Arc::new(JobQueue::new(4));
+
+    Router::new()
+        .route("/simulation", post(simulation::create))
+        .route("/simulation/stream", post(simulation::stream))
+        .route("/jobs", post(job::create))
+        .route("/jobs/{id}", get(job::get))
+        .route("/jobs/{id}/cancel", post(job::cancel))
+        .with_state(queue)
 }
 
 #[tokio::main]
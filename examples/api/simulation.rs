@@ -1,9 +1,21 @@
-use axum::{extract::Json, http::StatusCode, response::IntoResponse};
-use tokenomics_simulator::{Simulation, SimulationBuilder};
+use std::convert::Infallible;
+
+use axum::{
+    extract::Json,
+    http::StatusCode,
+    response::{
+        sse::{Event, Sse},
+        IntoResponse, Response,
+    },
+};
+use futures_util::{stream, Stream};
+use tokenomics_simulator::{
+    report_stream, CancellationToken, ReportReceiver, Simulation, SimulationBuilder,
+};
 
 use crate::{validator::Validator, Exception};
 
-/// Create a new simulation.
+/// Build and validate a simulation from the input data.
 ///
 /// # Arguments
 ///
@@ -11,14 +23,14 @@ use crate::{validator::Validator, Exception};
 ///
 /// # Returns
 ///
-/// Created simulation.
-pub async fn create(Json(data): Json<SimulationBuilder>) -> impl IntoResponse {
+/// Built simulation, or the error response.
+pub fn build(data: SimulationBuilder) -> Result<Simulation, Response> {
     let token = match data.token {
         Some(token) => match token.validate() {
             Ok(_) => token,
-            Err(err) => return err.into_response(),
+            Err(err) => return Err(err.into_response()),
         },
-        _ => return Exception::TokenNotFound.into_response(),
+        _ => return Err(Exception::TokenNotFound.into_response()),
     };
 
     let mut simulation_builder = Simulation::builder().token(token);
@@ -33,21 +45,35 @@ pub async fn create(Json(data): Json<SimulationBuilder>) -> impl IntoResponse {
 
     if let Some(options) = data.options {
         if let Err(err) = options.validate() {
-            return err.into_response();
+            return Err(err.into_response());
         }
 
         simulation_builder = simulation_builder.options(options);
     }
 
-    let mut simulation = match simulation_builder.build() {
+    simulation_builder.build().map_err(|err| {
+        println!("Failed to build simulation: {:?}", err);
+        Exception::InternalError.into_response()
+    })
+}
+
+/// Create a new simulation.
+///
+/// # Arguments
+///
+/// * `data` - Simulation input data.
+///
+/// # Returns
+///
+/// Created simulation.
+pub async fn create(Json(data): Json<SimulationBuilder>) -> impl IntoResponse {
+    let mut simulation = match build(data) {
         Ok(simulation) => simulation,
-        Err(err) => {
-            println!("Failed to build simulation: {:?}", err);
-            return Exception::InternalError.into_response();
-        }
+        Err(response) => return response,
     };
 
-    match simulation.run() {
+    // Yields between intervals, so the worker thread keeps serving other requests
+    match simulation.run_async(&CancellationToken::new()).await {
         Ok(_) => (StatusCode::CREATED, Json(simulation)).into_response(),
         Err(err) => {
             println!("Failed to run simulation: {:?}", err);
@@ -55,3 +81,65 @@ pub async fn create(Json(data): Json<SimulationBuilder>) -> impl IntoResponse {
         }
     }
 }
+
+/// Run a new simulation, streaming the report of every interval as Server-Sent Events.
+/// The last event holds the final report.
+///
+/// # Arguments
+///
+/// * `data` - Simulation input data.
+///
+/// # Returns
+///
+/// Stream of the reports.
+pub async fn stream(Json(data): Json<SimulationBuilder>) -> Response {
+    let mut simulation = match build(data) {
+        Ok(simulation) => simulation,
+        Err(response) => return response,
+    };
+
+    let (sender, receiver) = report_stream();
+    simulation.add_observer(sender);
+
+    // The run stops once the client disconnects and the stream is dropped
+    let cancellation = CancellationToken::new();
+    let guard = CancelOnDrop(cancellation.clone());
+    tokio::spawn(async move {
+        if let Err(err) = simulation.run_async(&cancellation).await {
+            println!("Failed to run simulation: {:?}", err);
+        }
+    });
+
+    Sse::new(events(receiver, guard)).into_response()
+}
+
+/// Cancels the simulation when dropped.
+struct CancelOnDrop(CancellationToken);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
+
+/// Convert the report stream into Server-Sent Events.
+///
+/// # Arguments
+///
+/// * `receiver` - Receiving end of the report stream.
+/// * `guard` - Guard cancelling the simulation when the events are dropped.
+///
+/// # Returns
+///
+/// Stream of the events.
+fn events(
+    receiver: ReportReceiver,
+    guard: CancelOnDrop,
+) -> impl Stream<Item = Result<Event, Infallible>> {
+    stream::unfold((receiver, guard), |(mut receiver, guard)| async move {
+        let event = receiver.recv().await?;
+        let event = Event::default().json_data(event).unwrap_or_default();
+
+        Some((Ok(event), (receiver, guard)))
+    })
+}
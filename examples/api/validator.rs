@@ -1,5 +1,5 @@
 use rust_decimal::Decimal;
-use tokenomics_simulator::{SimulationInterval, SimulationOptions, Token};
+use tokenomics_simulator::{Percentage, SimulationOptions, TimeSpan, Token};
 
 use crate::Exception;
 
@@ -21,15 +21,15 @@ impl Validator for Token {
     /// Result of the validation.
     fn validate(&self) -> Result<(), Exception> {
         if let Some(airdrop) = self.airdrop_percentage {
-            if airdrop <= Decimal::default() || airdrop > Decimal::new(100, 0) {
+            if airdrop <= Percentage::default() || airdrop > Percentage(Decimal::ONE_HUNDRED) {
                 return Err(Exception::ValidationFailed(
                     "Airdrop percentage must be more 0 and less than or equal to 100.".to_string(),
                 ));
             }
         }
 
-        if self.initial_supply_percentage <= Decimal::default()
-            || self.initial_supply_percentage > Decimal::new(100, 0)
+        if self.initial_supply_percentage <= Percentage::default()
+            || self.initial_supply_percentage > Percentage(Decimal::ONE_HUNDRED)
         {
             return Err(Exception::ValidationFailed(
                 "Initial supply percentage must be more than 0 and less than or equal to 100."
@@ -61,40 +61,14 @@ impl Validator for SimulationOptions {
             ));
         }
 
-        match self.interval_type {
-            SimulationInterval::Daily => {
-                if self.duration < 1 || self.duration > 365 {
-                    return Err(Exception::ValidationFailed(
-                        "Duration value must be more than 0 and less than or equal to 365."
-                            .to_string(),
-                    ));
-                }
-            }
-            SimulationInterval::Hourly => {
-                if self.duration < 1 || self.duration > 24 {
-                    return Err(Exception::ValidationFailed(
-                        "Duration value must be more than 0 and less than or equal to 24."
-                            .to_string(),
-                    ));
-                }
-            }
-            SimulationInterval::Weekly => {
-                if self.duration < 1 || self.duration > 52 {
-                    return Err(Exception::ValidationFailed(
-                        "Duration value must be more than 0 and less than or equal to 52."
-                            .to_string(),
-                    ));
-                }
-            }
-            SimulationInterval::Monthly => {
-                if self.duration < 1 || self.duration > 12 {
-                    return Err(Exception::ValidationFailed(
-                        "Duration value must be more than 0 and less than or equal to 12."
-                            .to_string(),
-                    ));
-                }
-            }
-        };
+        // Cap the simulated period at one year, whatever the interval type
+        let max_duration = TimeSpan::Years(1).intervals(&self.interval_type);
+        if self.duration < 1 || self.duration > max_duration {
+            return Err(Exception::ValidationFailed(format!(
+                "Duration value must be more than 0 and less than or equal to {}.",
+                max_duration
+            )));
+        }
 
         if self.market_volatility < Decimal::default()
             || self.market_volatility > Decimal::new(1, 0)
@@ -106,7 +80,7 @@ impl Validator for SimulationOptions {
         }
 
         if let Some(fee) = self.transaction_fee_percentage {
-            if fee <= Decimal::default() || fee > Decimal::new(1, 0) {
+            if fee <= Percentage::default() || fee > Percentage(Decimal::ONE) {
                 return Err(Exception::ValidationFailed(
                     "Transaction fee percentage must be more than 0 and less than or equal to 100."
                         .to_string(),
@@ -1,10 +1,104 @@
-use clap::{Arg, Command};
-use tokenomics_simulator::{Simulation, SimulationError};
+use std::{
+    env, fs,
+    io::{self, Write},
+    path::Path,
+};
+
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use tokenomics_simulator::{
+    ConfigOverride, EnsembleMetric, Locale, NumberFormat, Simulation, SimulationError, Sweep,
+    SweepParameter, SweepProgress, SweepSampling,
+};
+
+/// Width of the progress bar of a sweep, in characters.
+const PROGRESS_WIDTH: usize = 40;
 
 fn main() -> Result<(), SimulationError> {
     let matches = Command::new("Tokenomics Simulator CLI")
         .version("1.0")
         .about("Run tokenomics simulation")
+        .subcommand_negates_reqs(true)
+        .arg(
+            Arg::new("set")
+                .long("set")
+                .value_name("PATH=VALUE")
+                .help("Override a scenario value, e.g. options.total_users=5000, after the TOKENOMICS_ environment variables")
+                .action(ArgAction::Append)
+                .global(true),
+        )
+        .subcommand(
+            Command::new("compare")
+                .about("Compare two serialized simulations side by side")
+                .arg(
+                    Arg::new("baseline")
+                        .value_name("BASELINE")
+                        .help("Path to the baseline simulation, as JSON")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("variant")
+                        .value_name("VARIANT")
+                        .help("Path to the variant simulation, as JSON")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("metrics")
+                        .short('m')
+                        .long("metrics")
+                        .value_name("METRICS")
+                        .help("Comma-separated metrics to compare, e.g. price,retention")
+                        .default_value("price,retention,users"),
+                )
+                .arg(
+                    Arg::new("csv")
+                        .long("csv")
+                        .value_name("PATH")
+                        .help("Also write the comparison as CSV to a file"),
+                ),
+        )
+        .subcommand(
+            Command::new("sweep")
+                .about("Run a scenario for every combination of parameter values, resuming a previous run")
+                .arg(
+                    Arg::new("scenario")
+                        .value_name("SCENARIO")
+                        .help("Path to the scenario, as TOML or JSON, with optional token and options tables")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("param")
+                        .short('p')
+                        .long("param")
+                        .value_name("NAME=START..END:STEP")
+                        .help("Parameter to sweep, e.g. burn_rate=0..5:0.5")
+                        .action(ArgAction::Append)
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("out")
+                        .short('o')
+                        .long("out")
+                        .value_name("DIRECTORY")
+                        .help("Directory of the result files")
+                        .default_value("sweep"),
+                )
+                .arg(
+                    Arg::new("sampling")
+                        .long("sampling")
+                        .value_name("SAMPLING")
+                        .help("Combinations to run: grid, lhs:SAMPLES[:SEED], or sobol:SAMPLES")
+                        .default_value("grid"),
+                )
+                .arg(
+                    Arg::new("concurrency")
+                        .short('c')
+                        .long("concurrency")
+                        .value_name("CONCURRENCY")
+                        .help("Maximum number of simulations running at the same time, 0 for all cores")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("0"),
+                ),
+        )
         .arg(
             Arg::new("name")
                 .short('n')
@@ -63,6 +157,13 @@ fn main() -> Result<(), SimulationError> {
         )
         .get_matches();
 
+    if let Some(matches) = matches.subcommand_matches("compare") {
+        return compare(matches);
+    }
+    if let Some(matches) = matches.subcommand_matches("sweep") {
+        return sweep(matches);
+    }
+
     let token = Simulation::token_builder()
         .name(matches.get_one::<String>("name").unwrap().to_string())
         .symbol(matches.get_one::<String>("symbol").unwrap().to_string())
@@ -111,11 +212,185 @@ fn main() -> Result<(), SimulationError> {
         .description("Initial simulation".to_string())
         .token(token)
         .options(options)
-        .build()?;
+        .build()?
+        .with_overrides(&overrides(&matches)?)?;
 
     simulation.run()?;
 
     println!("Final report: {:#?}", simulation.report);
+    println!(
+        "{}",
+        simulation
+            .summary()
+            .render(&NumberFormat::readable(Locale::En))
+    );
 
     Ok(())
 }
+
+fn compare(matches: &ArgMatches) -> Result<(), SimulationError> {
+    let load = |name: &str| {
+        let path = matches.get_one::<String>(name).unwrap();
+        let json = fs::read_to_string(path)
+            .map_err(|err| SimulationError::InvalidData(format!("{path}: {err}")))?;
+
+        Simulation::from_json(&json)
+    };
+    let (baseline, variant) = (load("baseline")?, load("variant")?);
+    let metrics = EnsembleMetric::parse_list(matches.get_one::<String>("metrics").unwrap())?;
+
+    let comparison = baseline.compare(&variant, &metrics);
+    print!(
+        "{}",
+        comparison.to_table(&NumberFormat::new(Locale::En).significant_digits(6))
+    );
+
+    if let Some(path) = matches.get_one::<String>("csv") {
+        fs::write(path, comparison.to_csv())
+            .map_err(|err| SimulationError::InvalidData(format!("{path}: {err}")))?;
+    }
+
+    Ok(())
+}
+
+fn sweep(matches: &ArgMatches) -> Result<(), SimulationError> {
+    let scenario = load_scenario(
+        matches.get_one::<String>("scenario").unwrap(),
+        &overrides(matches)?,
+    )?;
+    let parameters = matches
+        .get_many::<String>("param")
+        .unwrap()
+        .map(|parameter| parameter.parse())
+        .collect::<Result<Vec<SweepParameter>, _>>()?;
+    let sampling: SweepSampling = matches.get_one::<String>("sampling").unwrap().parse()?;
+    let directory = Path::new(matches.get_one::<String>("out").unwrap());
+    let concurrency = *matches.get_one::<usize>("concurrency").unwrap();
+
+    let progress = Sweep::new(parameters).sampling(sampling).run(
+        &scenario,
+        directory,
+        concurrency,
+        draw_progress,
+    )?;
+    eprintln!();
+    println!(
+        "{} combinations: {} run, {} already done, results in {}",
+        progress.total,
+        progress.completed,
+        progress.skipped,
+        directory.display()
+    );
+
+    Ok(())
+}
+
+/// Draw the progress bar of a sweep on one line of the standard error.
+fn draw_progress(progress: &SweepProgress) {
+    let filled = match progress.total {
+        0 => PROGRESS_WIDTH,
+        total => progress.done() * PROGRESS_WIDTH / total,
+    };
+
+    eprint!(
+        "\r[{}{}] {}/{}",
+        "#".repeat(filled),
+        " ".repeat(PROGRESS_WIDTH - filled),
+        progress.done(),
+        progress.total
+    );
+    let _ = io::stderr().flush();
+}
+
+/// Collect the overrides of the scenario values, by precedence: environment variables, then `--set` flags.
+fn overrides(matches: &ArgMatches) -> Result<Vec<ConfigOverride>, SimulationError> {
+    let mut overrides = ConfigOverride::from_env(env::vars());
+    for value in matches.get_many::<String>("set").into_iter().flatten() {
+        overrides.push(value.parse()?);
+    }
+
+    Ok(overrides)
+}
+
+/// Load a scenario, its fields merged over a default simulation so only the changed ones need to be written.
+fn load_scenario(path: &str, overrides: &[ConfigOverride]) -> Result<Simulation, SimulationError> {
+    let invalid = |err: String| SimulationError::InvalidData(format!("{path}: {err}"));
+    let content = fs::read_to_string(path).map_err(|err| invalid(err.to_string()))?;
+
+    let scenario = match Path::new(path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+    {
+        Some("toml") => {
+            let document = content
+                .parse::<toml_edit::DocumentMut>()
+                .map_err(|err| invalid(err.to_string()))?;
+            toml_table_to_json(document.as_table())
+        }
+        _ => serde_json::from_str(&content).map_err(|err| invalid(err.to_string()))?,
+    };
+
+    let default = Simulation::builder()
+        .name("Sweep".to_string())
+        .token(
+            Simulation::token_builder()
+                .name("Token".to_string())
+                .build()?,
+        )
+        .options(Simulation::options_builder().total_users(100).build()?)
+        .build()?;
+    let mut simulation = serde_json::to_value(&default).map_err(|err| invalid(err.to_string()))?;
+    merge(&mut simulation, scenario);
+    for config_override in overrides {
+        config_override.apply(&mut simulation)?;
+    }
+
+    serde_json::from_value(simulation).map_err(|err| invalid(err.to_string()))
+}
+
+/// Merge the fields of a JSON object over another, recursively.
+fn merge(target: &mut serde_json::Value, source: serde_json::Value) {
+    match (target, source) {
+        (serde_json::Value::Object(target), serde_json::Value::Object(source)) => {
+            for (key, value) in source {
+                merge(target.entry(key).or_insert(serde_json::Value::Null), value);
+            }
+        }
+        (target, source) => *target = source,
+    }
+}
+
+fn toml_table_to_json(table: &toml_edit::Table) -> serde_json::Value {
+    table
+        .iter()
+        .filter_map(|(key, item)| Some((key.to_string(), toml_item_to_json(item)?)))
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
+
+fn toml_item_to_json(item: &toml_edit::Item) -> Option<serde_json::Value> {
+    match item {
+        toml_edit::Item::None => None,
+        toml_edit::Item::Value(value) => Some(toml_value_to_json(value)),
+        toml_edit::Item::Table(table) => Some(toml_table_to_json(table)),
+        toml_edit::Item::ArrayOfTables(tables) => {
+            Some(tables.iter().map(toml_table_to_json).collect())
+        }
+    }
+}
+
+fn toml_value_to_json(value: &toml_edit::Value) -> serde_json::Value {
+    match value {
+        toml_edit::Value::String(value) => value.value().clone().into(),
+        toml_edit::Value::Integer(value) => (*value.value()).into(),
+        toml_edit::Value::Float(value) => (*value.value()).into(),
+        toml_edit::Value::Boolean(value) => (*value.value()).into(),
+        toml_edit::Value::Datetime(value) => value.value().to_string().into(),
+        toml_edit::Value::Array(values) => values.iter().map(toml_value_to_json).collect(),
+        toml_edit::Value::InlineTable(table) => table
+            .iter()
+            .map(|(key, value)| (key.to_string(), toml_value_to_json(value)))
+            .collect::<serde_json::Map<_, _>>()
+            .into(),
+    }
+}
@@ -0,0 +1,53 @@
+use rust_decimal::Decimal;
+use tokenomics_simulator::{
+    EthereumFee, NetworkPriceSource, NetworkPrices, Simulation, SimulationError,
+    SimulationTransactionFee,
+};
+
+/// Network price source returning fixed prices.
+/// A real source would query a node or a price API.
+struct FixedPrices;
+
+impl NetworkPriceSource for FixedPrices {
+    fn prices(&self) -> Result<NetworkPrices, SimulationError> {
+        Ok(NetworkPrices {
+            gas_price: Decimal::new(20, 0),
+            native_token_price: Decimal::new(2_500, 0),
+        })
+    }
+}
+
+fn main() -> Result<(), SimulationError> {
+    // Build a new token
+    let token = Simulation::token_builder()
+        .name("EthereumToken".to_string())
+        .symbol("ETK".to_string())
+        .total_supply(1_000_000)
+        .build()?;
+
+    // Pay the gas fee of a simple transfer on each trade, the gas price is resolved before the run
+    let options = Simulation::options_builder()
+        .total_users(100)
+        .transaction_fee(SimulationTransactionFee::Ethereum(EthereumFee {
+            gas_used: 21_000,
+            gas_price: None,
+            ether_price: None,
+        }))
+        .build()?;
+
+    // Build a new simulation with the token and options
+    let mut simulation = Simulation::builder()
+        .name("Ethereum Fee Simulation".to_string())
+        .token(token)
+        .options(options)
+        .build()?;
+
+    // Resolve the gas fee and run the simulation
+    simulation.resolve_transaction_fee(&FixedPrices)?;
+    simulation.run()?;
+
+    // Print the gas spent by users
+    println!("Total gas spent: {}", simulation.report.gas_spent);
+
+    Ok(())
+}
@@ -0,0 +1,18 @@
+use std::net::SocketAddr;
+
+use tokenomics_simulator::{proto::simulator_server::SimulatorServer, SimulatorService};
+use tonic::transport::Server;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let address = SocketAddr::from(([0, 0, 0, 0], 50051));
+    println!("gRPC server is running on {}", address);
+
+    // Serve the simulator service, described in `proto/simulator.proto`
+    Server::builder()
+        .add_service(SimulatorServer::new(SimulatorService::default()))
+        .serve(address)
+        .await?;
+
+    Ok(())
+}
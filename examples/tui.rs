@@ -0,0 +1,181 @@
+use std::{error::Error, time::Duration};
+
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Color, Style},
+    symbols::Marker,
+    text::Line,
+    widgets::{Axis, Block, Chart, Dataset, GraphType, Paragraph},
+    DefaultTerminal, Frame,
+};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use tokenomics_simulator::{Simulation, SimulationStepper};
+
+/// Time between two intervals while the run is playing.
+const TICK: Duration = Duration::from_millis(100);
+
+/// Values of a chart, one point per interval.
+#[derive(Default)]
+struct Series {
+    price: Vec<(f64, f64)>,
+    users: Vec<(f64, f64)>,
+    supply: Vec<(f64, f64)>,
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    // Build a new token
+    let token = Simulation::token_builder()
+        .name("DemoToken".to_string())
+        .symbol("DMO".to_string())
+        .total_supply(1_000_000)
+        .airdrop_percentage(5.0)
+        .burn_rate(0.01)
+        .inflation_rate(0.02)
+        .build()?;
+
+    // Build the simulation options, daily intervals over eight months
+    let options = Simulation::options_builder()
+        .total_users(500)
+        .market_volatility(0.5)
+        .duration(240)
+        .build()?;
+
+    let mut simulation = Simulation::builder()
+        .name("Interactive Simulation".to_string())
+        .token(token)
+        .options(options)
+        .build()?;
+
+    // Restore the terminal even if the run fails
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, &mut simulation);
+    ratatui::restore();
+    result?;
+
+    println!("{}", simulation.summary());
+
+    Ok(())
+}
+
+fn run(terminal: &mut DefaultTerminal, simulation: &mut Simulation) -> Result<(), Box<dyn Error>> {
+    let duration = simulation.options.duration;
+    let mut stepper = simulation.stepper()?;
+    let mut series = Series::default();
+    let mut paused = false;
+
+    loop {
+        terminal.draw(|frame| draw(frame, &stepper, &series, duration, paused))?;
+
+        let mut step = !paused;
+        if event::poll(TICK)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    match key.code {
+                        KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                        KeyCode::Char(' ') | KeyCode::Char('p') => paused = !paused,
+                        KeyCode::Char('s') | KeyCode::Right => step = paused,
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        if step && !stepper.is_finished() {
+            if let Some(report) = stepper.step()? {
+                let interval = series.price.len() as f64;
+                series
+                    .price
+                    .push((interval, to_f64(report.token_price.value())));
+                series
+                    .users
+                    .push((interval, report.final_user_count as f64));
+            }
+            let interval = series.supply.len() as f64;
+            series
+                .supply
+                .push((interval, to_f64(stepper.circulating_supply().value())));
+        }
+    }
+}
+
+fn draw(
+    frame: &mut Frame,
+    stepper: &SimulationStepper,
+    series: &Series,
+    duration: u64,
+    paused: bool,
+) {
+    let [status, price, users, supply, help] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Fill(1),
+        Constraint::Fill(1),
+        Constraint::Fill(1),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let state = match (stepper.is_finished(), paused) {
+        (true, _) => "finished",
+        (false, true) => "paused",
+        (false, false) => "running",
+    };
+    frame.render_widget(
+        Paragraph::new(format!(
+            "{}: interval {}/{} ({state})",
+            stepper.simulation().name,
+            stepper.intervals_run(),
+            duration
+        )),
+        status,
+    );
+
+    frame.render_widget(chart("Price", &series.price, duration, Color::Green), price);
+    frame.render_widget(chart("Users", &series.users, duration, Color::Cyan), users);
+    frame.render_widget(
+        chart(
+            "Circulating supply",
+            &series.supply,
+            duration,
+            Color::Yellow,
+        ),
+        supply,
+    );
+
+    frame.render_widget(
+        Paragraph::new("space: pause/resume  s/→: step while paused  q: quit"),
+        help,
+    );
+}
+
+fn chart<'a>(title: &'a str, points: &'a [(f64, f64)], duration: u64, color: Color) -> Chart<'a> {
+    let (min, max) = points
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(min, max), (_, y)| {
+            (min.min(*y), max.max(*y))
+        });
+    let (min, max) = match points.is_empty() {
+        true => (0.0, 1.0),
+        false if min == max => (min - 1.0, max + 1.0),
+        false => (min, max),
+    };
+
+    let dataset = Dataset::default()
+        .marker(Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(color))
+        .data(points);
+
+    Chart::new(vec![dataset])
+        .block(Block::bordered().title(Line::from(title)))
+        .x_axis(Axis::default().bounds([0.0, duration as f64]))
+        .y_axis(
+            Axis::default()
+                .bounds([min, max])
+                .labels([format!("{min:.2}"), format!("{max:.2}")]),
+        )
+}
+
+fn to_f64(value: Decimal) -> f64 {
+    value.to_f64().unwrap_or_default()
+}
@@ -0,0 +1,171 @@
+//! # Activity module
+//!
+//! This module contains the rolling active user metrics of the simulation.
+//!
+//! Active users are the users with at least one executed trade.
+//! Daily, weekly, and monthly active users are counted over rolling windows of intervals,
+//! so the same user is counted once per window.
+
+use std::collections::{HashSet, VecDeque};
+
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+/// Length of the daily window, in hours.
+const DAY: u64 = 24;
+
+/// Length of the weekly window, in hours.
+const WEEK: u64 = 7 * DAY;
+
+/// Length of the monthly window, in hours.
+const MONTH: u64 = 30 * DAY;
+
+/// Tracker of the users active in the recent intervals.
+#[derive(Debug)]
+pub(crate) struct ActivityTracker {
+    /// Duration of an interval, in hours.
+    interval: u64,
+
+    /// Users active in each of the recent intervals, the latest last.
+    history: VecDeque<HashSet<Uuid>>,
+}
+
+impl ActivityTracker {
+    /// Create a new activity tracker.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - Duration of an interval, in hours.
+    ///
+    /// # Returns
+    ///
+    /// New activity tracker.
+    pub(crate) fn new(interval: u64) -> Self {
+        ActivityTracker {
+            interval: interval.max(1),
+            history: VecDeque::new(),
+        }
+    }
+
+    /// Record the users active in an interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `active` - IDs of the users active in the interval.
+    pub(crate) fn record(&mut self, active: HashSet<Uuid>) {
+        self.history.push_back(active);
+
+        // Keep only the intervals of the longest window
+        while self.history.len() as u64 > self.window(MONTH) {
+            self.history.pop_front();
+        }
+    }
+
+    /// Count the users active in a rolling window ending with the latest interval.
+    /// Windows shorter than an interval count the users of the latest interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `hours` - Length of the window, in hours.
+    ///
+    /// # Returns
+    ///
+    /// Number of unique users active in the window.
+    pub(crate) fn active_users(&self, hours: u64) -> u64 {
+        let window = self.window(hours) as usize;
+
+        self.history
+            .iter()
+            .rev()
+            .take(window)
+            .flatten()
+            .collect::<HashSet<_>>()
+            .len() as u64
+    }
+
+    /// Count the daily, weekly, and monthly active users.
+    ///
+    /// # Returns
+    ///
+    /// Daily, weekly, and monthly active users.
+    pub(crate) fn rolling(&self) -> (u64, u64, u64) {
+        (
+            self.active_users(DAY),
+            self.active_users(WEEK),
+            self.active_users(MONTH),
+        )
+    }
+
+    /// Get the number of intervals in a window.
+    ///
+    /// # Arguments
+    ///
+    /// * `hours` - Length of the window, in hours.
+    ///
+    /// # Returns
+    ///
+    /// Number of intervals, at least one.
+    fn window(&self, hours: u64) -> u64 {
+        hours.div_ceil(self.interval).max(1)
+    }
+}
+
+/// Calculate the stickiness of the token: the share of monthly active users active on a given day.
+///
+/// # Arguments
+///
+/// * `daily` - Daily active users.
+/// * `monthly` - Monthly active users.
+/// * `decimals` - Number of decimal places to round to.
+///
+/// # Returns
+///
+/// DAU/MAU ratio, between 0 and 1.
+pub(crate) fn stickiness(daily: Decimal, monthly: Decimal, decimals: u32) -> Decimal {
+    daily
+        .checked_div(monthly)
+        .unwrap_or_default()
+        .round_dp(decimals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rolling_active_users() {
+        let users: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+        let mut tracker = ActivityTracker::new(DAY);
+
+        tracker.record(HashSet::from([users[0], users[1]]));
+        for _ in 0..6 {
+            tracker.record(HashSet::from([users[1]]));
+        }
+        tracker.record(HashSet::from([users[2]]));
+
+        assert_eq!(tracker.rolling(), (1, 2, 3));
+    }
+
+    #[test]
+    fn test_windows_shorter_than_interval() {
+        let mut tracker = ActivityTracker::new(WEEK);
+
+        tracker.record(HashSet::from([Uuid::new_v4()]));
+        tracker.record(HashSet::from([Uuid::new_v4()]));
+
+        assert_eq!(tracker.rolling(), (1, 1, 2));
+        assert_eq!(tracker.history.len(), 2);
+    }
+
+    #[test]
+    fn test_stickiness() {
+        assert_eq!(
+            stickiness(Decimal::new(20, 0), Decimal::new(80, 0), 4),
+            Decimal::new(25, 2)
+        );
+        assert_eq!(
+            stickiness(Decimal::new(20, 0), Decimal::default(), 4),
+            Decimal::default()
+        );
+    }
+}
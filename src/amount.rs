@@ -0,0 +1,348 @@
+//! # Amount module
+//!
+//! This module contains the typed amounts of the simulation: tokens, fiat, gwei, and percentages.
+//!
+//! Each amount wraps a `Decimal`, and only amounts of the same unit can be added or compared.
+//! Combining units goes through a named conversion, e.g. a token amount valued at a fiat price,
+//! or a percentage of an amount, so a percentage cannot be applied as a fraction by mistake.
+
+use std::{
+    fmt,
+    iter::Sum,
+    ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign},
+};
+
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Number of gwei in one ether.
+const GWEI_PER_ETHER: i64 = 1_000_000_000;
+
+/// Amount of tokens, in whole tokens.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct TokenAmount(
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))] pub Decimal,
+);
+
+/// Amount of fiat, or price of one token in fiat.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct FiatAmount(
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))] pub Decimal,
+);
+
+/// Amount of gwei, e.g. a gas price.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct GweiAmount(
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))] pub Decimal,
+);
+
+/// Percentage, e.g. 2.5 for 2.5%.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Percentage(
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))] pub Decimal,
+);
+
+/// Arithmetic between amounts of the same unit, and scaling by a plain number.
+macro_rules! amount_ops {
+    ($($amount:ident),*) => {$(
+        impl $amount {
+            /// Get the value of the amount.
+            ///
+            /// # Returns
+            ///
+            /// Value of the amount, without its unit.
+            pub fn value(self) -> Decimal {
+                self.0
+            }
+
+            /// Check whether the amount is zero.
+            ///
+            /// # Returns
+            ///
+            /// Whether the amount is zero.
+            pub fn is_zero(self) -> bool {
+                self.0.is_zero()
+            }
+
+            /// Round the amount to a number of decimal places, half-even.
+            ///
+            /// # Arguments
+            ///
+            /// * `dp` - Number of decimal places.
+            ///
+            /// # Returns
+            ///
+            /// Rounded amount.
+            pub fn round_dp(self, dp: u32) -> Self {
+                $amount(self.0.round_dp(dp))
+            }
+
+            /// Round the amount to a whole number, half-even.
+            ///
+            /// # Returns
+            ///
+            /// Rounded amount.
+            pub fn round(self) -> Self {
+                $amount(self.0.round())
+            }
+        }
+
+        impl From<$amount> for Decimal {
+            fn from(amount: $amount) -> Decimal {
+                amount.0
+            }
+        }
+
+        impl fmt::Display for $amount {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impl Add for $amount {
+            type Output = $amount;
+
+            fn add(self, other: $amount) -> $amount {
+                $amount(self.0 + other.0)
+            }
+        }
+
+        impl Sub for $amount {
+            type Output = $amount;
+
+            fn sub(self, other: $amount) -> $amount {
+                $amount(self.0 - other.0)
+            }
+        }
+
+        impl AddAssign for $amount {
+            fn add_assign(&mut self, other: $amount) {
+                self.0 += other.0;
+            }
+        }
+
+        impl SubAssign for $amount {
+            fn sub_assign(&mut self, other: $amount) {
+                self.0 -= other.0;
+            }
+        }
+
+        impl Neg for $amount {
+            type Output = $amount;
+
+            fn neg(self) -> $amount {
+                $amount(-self.0)
+            }
+        }
+
+        impl Mul<Decimal> for $amount {
+            type Output = $amount;
+
+            fn mul(self, factor: Decimal) -> $amount {
+                $amount(self.0 * factor)
+            }
+        }
+
+        impl Div<Decimal> for $amount {
+            type Output = $amount;
+
+            fn div(self, divisor: Decimal) -> $amount {
+                $amount(self.0 / divisor)
+            }
+        }
+
+        impl Sum for $amount {
+            fn sum<I: Iterator<Item = $amount>>(iter: I) -> $amount {
+                $amount(iter.map(|amount| amount.0).sum())
+            }
+        }
+    )*};
+}
+
+amount_ops!(TokenAmount, FiatAmount, GweiAmount, Percentage);
+
+impl TokenAmount {
+    /// Value the tokens at a price.
+    ///
+    /// # Arguments
+    ///
+    /// * `price` - Price of one token.
+    ///
+    /// # Returns
+    ///
+    /// Value of the tokens in fiat.
+    pub fn value_at(self, price: FiatAmount) -> FiatAmount {
+        FiatAmount(self.0 * price.0)
+    }
+}
+
+impl FiatAmount {
+    /// Convert the fiat to tokens at a price.
+    ///
+    /// # Arguments
+    ///
+    /// * `price` - Price of one token.
+    ///
+    /// # Returns
+    ///
+    /// Amount of tokens, or `None` if the price is zero.
+    pub fn in_tokens(self, price: FiatAmount) -> Option<TokenAmount> {
+        self.0.checked_div(price.0).map(TokenAmount)
+    }
+}
+
+impl GweiAmount {
+    /// Calculate the fiat cost of the gas used by a transaction at this gas price.
+    ///
+    /// # Arguments
+    ///
+    /// * `gas_used` - Gas used by the transaction.
+    /// * `ether_price` - Price of one ether.
+    ///
+    /// # Returns
+    ///
+    /// Cost of the gas in fiat.
+    pub fn gas_cost(self, gas_used: u64, ether_price: FiatAmount) -> FiatAmount {
+        FiatAmount(Decimal::from(gas_used) * self.0 / Decimal::from(GWEI_PER_ETHER) * ether_price.0)
+    }
+}
+
+impl Percentage {
+    /// Create a percentage from a fraction, e.g. 0.025 for 2.5%.
+    ///
+    /// # Arguments
+    ///
+    /// * `fraction` - Fraction, where 1 is 100%.
+    ///
+    /// # Returns
+    ///
+    /// Percentage.
+    pub fn from_fraction(fraction: Decimal) -> Self {
+        Percentage(fraction * Decimal::ONE_HUNDRED)
+    }
+
+    /// Get the fraction of the percentage, e.g. 0.025 for 2.5%.
+    ///
+    /// # Returns
+    ///
+    /// Fraction, where 1 is 100%.
+    pub fn fraction(self) -> Decimal {
+        self.0 / Decimal::ONE_HUNDRED
+    }
+
+    /// Take the percentage of an amount.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - Amount of any unit.
+    ///
+    /// # Returns
+    ///
+    /// Percentage of the amount, in the unit of the amount.
+    pub fn of<T: Mul<Decimal, Output = T>>(self, amount: T) -> T {
+        amount * self.fraction()
+    }
+}
+
+/// Serialization of token amounts as arbitrary precision numbers, for amounts beyond the precision of a float.
+#[cfg(feature = "serde")]
+pub(crate) mod arbitrary_precision {
+    use serde::{Deserializer, Serializer};
+
+    use super::TokenAmount;
+
+    /// Serialize a token amount as an arbitrary precision number.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - Amount of tokens.
+    /// * `serializer` - Serializer.
+    ///
+    /// # Returns
+    ///
+    /// Result of the serialization.
+    pub fn serialize<S>(amount: &TokenAmount, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        rust_decimal::serde::arbitrary_precision::serialize(&amount.0, serializer)
+    }
+
+    /// Deserialize a token amount from an arbitrary precision number.
+    ///
+    /// # Arguments
+    ///
+    /// * `deserializer` - Deserializer.
+    ///
+    /// # Returns
+    ///
+    /// Amount of tokens.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<TokenAmount, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        rust_decimal::serde::arbitrary_precision::deserialize(deserializer).map(TokenAmount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_amount_arithmetic() {
+        let balance = TokenAmount(Decimal::new(100, 0));
+        let mut total = [balance, balance].into_iter().sum::<TokenAmount>();
+        total -= TokenAmount(Decimal::new(50, 0));
+
+        assert_eq!(total, TokenAmount(Decimal::new(150, 0)));
+        assert_eq!(balance * Decimal::TWO, TokenAmount(Decimal::new(200, 0)));
+        assert_eq!(-balance, TokenAmount(Decimal::new(-100, 0)));
+        assert_eq!(balance.to_string(), "100");
+        assert!(TokenAmount::default().is_zero());
+    }
+
+    #[test]
+    fn test_conversions() {
+        let price = FiatAmount(Decimal::new(25, 1));
+        let value = TokenAmount(Decimal::new(10, 0)).value_at(price);
+
+        assert_eq!(value, FiatAmount(Decimal::new(25, 0)));
+        assert_eq!(
+            value.in_tokens(price),
+            Some(TokenAmount(Decimal::new(10, 0)))
+        );
+        assert_eq!(value.in_tokens(FiatAmount::default()), None);
+
+        // 21,000 gas * 20 gwei = 0.00042 ETH, at 2,000 per ETH
+        assert_eq!(
+            GweiAmount(Decimal::new(20, 0)).gas_cost(21_000, FiatAmount(Decimal::new(2_000, 0))),
+            FiatAmount(Decimal::new(84, 2))
+        );
+    }
+
+    #[test]
+    fn test_percentage() {
+        let percentage = Percentage(Decimal::new(25, 1));
+
+        assert_eq!(percentage.fraction(), Decimal::new(25, 3));
+        assert_eq!(Percentage::from_fraction(Decimal::new(25, 3)), percentage);
+        assert_eq!(
+            percentage.of(TokenAmount(Decimal::new(200, 0))),
+            TokenAmount(Decimal::new(5, 0))
+        );
+        assert_eq!(
+            percentage.of(FiatAmount(Decimal::new(40, 0))),
+            FiatAmount(Decimal::ONE)
+        );
+    }
+}
@@ -0,0 +1,453 @@
+//! # Analytics module
+//!
+//! This module contains the importer of query results exported from analytics platforms, such as Dune or Flipside.
+//!
+//! An export is read as a table, from CSV or, with the `serde` feature, from the JSON of the query APIs.
+//! Columns are found by their usual names, e.g. `balance` or `daily_active_addresses`, or mapped explicitly,
+//! and turned into the initial state of a simulation, the holder balances, or into calibration targets,
+//! the active users and the volumes. Rows with a date column are read oldest first.
+
+use std::str::FromStr;
+
+use rust_decimal::Decimal;
+
+use crate::{SimulationError, SimulationOptionsBuilder, UserHistory};
+
+/// Column of an analytics export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnalyticsColumn {
+    /// Date of the row, to order a time series.
+    Date,
+
+    /// Balance of a holder.
+    HolderBalance,
+
+    /// Active addresses of a day or interval.
+    ActiveAddresses,
+
+    /// Trading volume of a day or interval.
+    Volume,
+}
+
+/// Table of a query result exported from an analytics platform.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AnalyticsTable {
+    /// Names of the columns.
+    pub columns: Vec<String>,
+
+    /// Values of each row, in the order of the columns.
+    pub rows: Vec<Vec<String>>,
+
+    /// Columns mapped explicitly, used instead of the usual names.
+    mappings: Vec<(AnalyticsColumn, String)>,
+}
+
+impl AnalyticsColumn {
+    /// Get the usual names of the column in the exports, compared without case.
+    ///
+    /// # Returns
+    ///
+    /// Names of the column, the preferred first.
+    pub fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            AnalyticsColumn::Date => &["date", "day", "block_date", "block_day", "dt", "time"],
+            AnalyticsColumn::HolderBalance => &[
+                "balance",
+                "holder_balance",
+                "token_balance",
+                "current_balance",
+                "amount",
+            ],
+            AnalyticsColumn::ActiveAddresses => &[
+                "active_addresses",
+                "daily_active_addresses",
+                "active_wallets",
+                "active_users",
+                "dau",
+                "users",
+            ],
+            AnalyticsColumn::Volume => &[
+                "volume",
+                "daily_volume",
+                "token_volume",
+                "volume_usd",
+                "amount_usd",
+            ],
+        }
+    }
+}
+
+impl AnalyticsTable {
+    /// Parse a table from CSV, the first line holding the names of the columns.
+    /// Quoted fields may contain commas and doubled quotes.
+    ///
+    /// # Arguments
+    ///
+    /// * `csv` - CSV of the export.
+    ///
+    /// # Returns
+    ///
+    /// Table, or an error if a row does not have one value per column.
+    pub fn from_csv(csv: &str) -> Result<Self, SimulationError> {
+        let mut lines = csv.lines().filter(|line| !line.trim().is_empty());
+        let columns = lines.next().map(split_csv_line).unwrap_or_default();
+
+        let rows = lines
+            .enumerate()
+            .map(|(index, line)| {
+                let row = split_csv_line(line);
+                match row.len() == columns.len() {
+                    true => Ok(row),
+                    false => Err(SimulationError::InvalidImport(format!(
+                        "row {}: expected {} values, got {}",
+                        index + 1,
+                        columns.len(),
+                        row.len()
+                    ))),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(AnalyticsTable {
+            columns,
+            rows,
+            mappings: vec![],
+        })
+    }
+
+    /// Map a column to a name of the export, when it does not use one of the usual names.
+    ///
+    /// # Arguments
+    ///
+    /// * `column` - Column to map.
+    /// * `name` - Name of the column in the export.
+    ///
+    /// # Returns
+    ///
+    /// The table with the mapping.
+    pub fn map_column(mut self, column: AnalyticsColumn, name: &str) -> Self {
+        self.mappings.retain(|(mapped, _)| *mapped != column);
+        self.mappings.push((column, name.to_string()));
+        self
+    }
+
+    /// Find the index of a column, by its mapping or its usual names.
+    ///
+    /// # Arguments
+    ///
+    /// * `column` - Column to find.
+    ///
+    /// # Returns
+    ///
+    /// Index of the column, if the export has it.
+    pub fn find_column(&self, column: AnalyticsColumn) -> Option<usize> {
+        let position = |name: &str| {
+            self.columns
+                .iter()
+                .position(|candidate| candidate.trim().eq_ignore_ascii_case(name))
+        };
+
+        match self.mappings.iter().find(|(mapped, _)| *mapped == column) {
+            Some((_, name)) => position(name),
+            None => column.aliases().iter().find_map(|name| position(name)),
+        }
+    }
+
+    /// Get the values of a numeric column, oldest first when the export has a date column.
+    /// Empty values, e.g. nulls of the query, are read as zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `column` - Column to read.
+    ///
+    /// # Returns
+    ///
+    /// Values of the column, or an error if the column is missing or a value is not a number.
+    pub fn values(&self, column: AnalyticsColumn) -> Result<Vec<Decimal>, SimulationError> {
+        let index = self.find_column(column).ok_or_else(|| {
+            SimulationError::InvalidImport(format!(
+                "missing column, expected one of {}",
+                column.aliases().join(", ")
+            ))
+        })?;
+
+        let mut rows: Vec<_> = self.rows.iter().collect();
+        if let Some(date) = self.find_column(AnalyticsColumn::Date) {
+            rows.sort_by(|a, b| a[date].cmp(&b[date]));
+        }
+
+        rows.iter()
+            .map(|row| {
+                let value = row[index].trim();
+                match value.is_empty() || value.eq_ignore_ascii_case("null") {
+                    true => Ok(Decimal::default()),
+                    false => Decimal::from_str(value)
+                        .or_else(|_| Decimal::from_scientific(value))
+                        .map_err(|_| {
+                            SimulationError::InvalidImport(format!(
+                                "{}: expected a number, got {value}",
+                                self.columns[index]
+                            ))
+                        }),
+                }
+            })
+            .collect()
+    }
+
+    /// Get the balances of the holders, largest first.
+    ///
+    /// # Returns
+    ///
+    /// Balance of each holder, or an error if the balances cannot be read.
+    pub fn holder_balances(&self) -> Result<Vec<Decimal>, SimulationError> {
+        let mut balances = self.values(AnalyticsColumn::HolderBalance)?;
+        balances.sort_by(|a, b| b.cmp(a));
+
+        Ok(balances)
+    }
+
+    /// Get the history of the active addresses, to calibrate the adoption and the lifecycle.
+    ///
+    /// # Returns
+    ///
+    /// Active users of each row, or an error if the active addresses cannot be read.
+    pub fn active_users(&self) -> Result<UserHistory, SimulationError> {
+        let active_users = self
+            .values(AnalyticsColumn::ActiveAddresses)?
+            .into_iter()
+            .map(|users| u64::try_from(users.round()).unwrap_or_default())
+            .collect();
+
+        Ok(UserHistory::new(active_users))
+    }
+
+    /// Get the volume of each row, to compare with the volume of the interval reports.
+    ///
+    /// # Returns
+    ///
+    /// Volume of each row, or an error if the volumes cannot be read.
+    pub fn volumes(&self) -> Result<Vec<Decimal>, SimulationError> {
+        self.values(AnalyticsColumn::Volume)
+    }
+
+    /// Start the users of a simulation from the holders of the export:
+    /// one user per holder, each with its balance.
+    ///
+    /// # Arguments
+    ///
+    /// * `builder` - Options builder to complete.
+    ///
+    /// # Returns
+    ///
+    /// The options builder with the holders, or an error if the balances cannot be read.
+    pub fn apply_holders(
+        &self,
+        builder: SimulationOptionsBuilder,
+    ) -> Result<SimulationOptionsBuilder, SimulationError> {
+        let balances = self.holder_balances()?;
+
+        Ok(builder
+            .total_users(balances.len() as u64)
+            .holder_balances(balances))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl AnalyticsTable {
+    /// Parse a table from the JSON of a query result: an array of rows,
+    /// or an object with the rows under `result.rows` (Dune), `rows`, `records`, or `data` (Flipside).
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - JSON of the export.
+    ///
+    /// # Returns
+    ///
+    /// Table, with the columns of the first row, or an error if the JSON has no rows of objects.
+    pub fn from_json(json: &str) -> Result<Self, SimulationError> {
+        let invalid = |reason: &str| SimulationError::InvalidImport(reason.to_string());
+        let value: serde_json::Value =
+            serde_json::from_str(json).map_err(|err| invalid(&err.to_string()))?;
+
+        let rows = match &value {
+            serde_json::Value::Array(rows) => Some(rows),
+            _ => ["/result/rows", "/rows", "/records", "/data"]
+                .iter()
+                .find_map(|path| value.pointer(path).and_then(|rows| rows.as_array())),
+        }
+        .ok_or_else(|| invalid("expected an array of rows"))?;
+
+        let mut columns: Vec<String> = vec![];
+        for row in rows {
+            let row = row
+                .as_object()
+                .ok_or_else(|| invalid("expected rows of objects"))?;
+            for name in row.keys() {
+                if !columns.contains(name) {
+                    columns.push(name.clone());
+                }
+            }
+        }
+
+        let rows = rows
+            .iter()
+            .filter_map(|row| row.as_object())
+            .map(|row| {
+                columns
+                    .iter()
+                    .map(|name| match row.get(name) {
+                        Some(serde_json::Value::String(value)) => value.clone(),
+                        Some(serde_json::Value::Null) | None => String::new(),
+                        Some(value) => value.to_string(),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Ok(AnalyticsTable {
+            columns,
+            rows,
+            mappings: vec![],
+        })
+    }
+}
+
+/// Split a line of CSV into its fields.
+///
+/// # Arguments
+///
+/// * `line` - Line of CSV.
+///
+/// # Returns
+///
+/// Fields of the line, unquoted.
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.trim_end_matches('\r').chars().peekable();
+
+    while let Some(char) = chars.next() {
+        match (char, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', _) => quoted = !quoted,
+            (',', false) => fields.push(std::mem::take(&mut field)),
+            (char, _) => field.push(char),
+        }
+    }
+    fields.push(field);
+
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Simulation;
+
+    use super::*;
+
+    #[test]
+    fn test_from_csv() {
+        let csv =
+            "address,label,Balance\n0xa,\"Exchange, hot wallet\",150\n0xb,,\"2.5e3\"\n0xc,null,\n";
+
+        let table = AnalyticsTable::from_csv(csv).unwrap();
+
+        assert_eq!(table.rows[0][1], "Exchange, hot wallet");
+        assert_eq!(
+            table.holder_balances().unwrap(),
+            vec![
+                Decimal::new(2500, 0),
+                Decimal::new(150, 0),
+                Decimal::default()
+            ]
+        );
+        assert!(matches!(
+            table.volumes(),
+            Err(SimulationError::InvalidImport(_))
+        ));
+        assert!(matches!(
+            AnalyticsTable::from_csv("a,b\n1\n"),
+            Err(SimulationError::InvalidImport(_))
+        ));
+
+        let options = table
+            .apply_holders(Simulation::options_builder())
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(options.total_users, 3);
+        assert_eq!(options.holder_balances.unwrap()[0], Decimal::new(2500, 0));
+    }
+
+    #[test]
+    fn test_time_series() {
+        // Exports are often sorted newest first
+        let csv = "day,dau,wallets\n2024-01-03,120,9\n2024-01-01,100,7\n2024-01-02,110,8\n";
+
+        let table = AnalyticsTable::from_csv(csv).unwrap();
+
+        assert_eq!(
+            table.active_users().unwrap().active_users,
+            vec![100, 110, 120]
+        );
+
+        let table = table.map_column(AnalyticsColumn::ActiveAddresses, "wallets");
+        assert_eq!(table.active_users().unwrap().active_users, vec![7, 8, 9]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_from_json() {
+        let dune = r#"{"execution_id": "01", "result": {"rows": [
+            {"block_date": "2024-01-02", "volume_usd": 20.5},
+            {"block_date": "2024-01-01", "volume_usd": "10"}
+        ]}}"#;
+        let table = AnalyticsTable::from_json(dune).unwrap();
+        assert_eq!(
+            table.volumes().unwrap(),
+            vec![Decimal::new(10, 0), Decimal::new(205, 1)]
+        );
+
+        let flipside = r#"[{"balance": 5, "holder": "0xa"}, {"balance": null, "holder": "0xb"}]"#;
+        let table = AnalyticsTable::from_json(flipside).unwrap();
+        assert_eq!(
+            table.holder_balances().unwrap(),
+            vec![Decimal::new(5, 0), Decimal::default()]
+        );
+
+        assert!(matches!(
+            AnalyticsTable::from_json(r#"{"rows": 1}"#),
+            Err(SimulationError::InvalidImport(_))
+        ));
+    }
+
+    #[test]
+    fn test_run_with_holder_balances() {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let table = AnalyticsTable::from_csv("balance\n1000\n10\n").unwrap();
+        let options = table
+            .apply_holders(Simulation::options_builder().duration(1))
+            .unwrap()
+            .build()
+            .unwrap();
+        let mut simulation = Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap();
+
+        let mut state = simulation.start_run().unwrap();
+
+        assert_eq!(state.users.balances[0], Decimal::new(1000, 0));
+        assert_eq!(state.users.balances[1], Decimal::new(10, 0));
+        simulation.run_interval(&mut state).unwrap();
+    }
+}
@@ -0,0 +1,127 @@
+//! # Async runner module
+//!
+//! This module contains the asynchronous entry point of the simulation, enabled by the `async` feature.
+//!
+//! The simulation yields to the executor between intervals, so long runs share the worker threads
+//! of an async runtime, e.g. tokio, instead of blocking them. The yielding future only relies on
+//! the standard library, so any executor can drive it.
+
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::{CancellationToken, Simulation, SimulationError};
+
+impl Simulation {
+    /// Run the simulation asynchronously.
+    /// The simulation yields to the executor after each interval and stops before the next interval once cancelled.
+    ///
+    /// # Arguments
+    ///
+    /// * `cancellation` - Token used to cancel the run.
+    ///
+    /// # Returns
+    ///
+    /// Result of the simulation, or `SimulationError::Cancelled` if the run was cancelled.
+    pub async fn run_async(
+        &mut self,
+        cancellation: &CancellationToken,
+    ) -> Result<(), SimulationError> {
+        let mut state = self
+            .start_run()
+            .map_err(|error| self.fail_run(None, error))?;
+
+        while state.index < self.options.duration {
+            if cancellation.is_cancelled() {
+                #[cfg(feature = "log")]
+                log::debug!("Simulation cancelled: {}", self.name);
+
+                return Err(self.cancel_run(state, cancellation));
+            }
+
+            if let Err(error) = self.run_interval(&mut state) {
+                return Err(self.fail_run(Some(state), error));
+            }
+            YieldNow::default().await;
+        }
+
+        self.finish_run(state);
+
+        Ok(())
+    }
+}
+
+/// Future yielding to the executor once before completing.
+#[derive(Debug, Default)]
+struct YieldNow {
+    /// Whether the future already yielded.
+    yielded: bool,
+}
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.yielded {
+            return Poll::Ready(());
+        }
+
+        self.yielded = true;
+        cx.waker().wake_by_ref();
+
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SimulationStatus;
+
+    fn setup() -> Simulation {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .total_supply(1_000_000)
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(10)
+            .duration(5)
+            .build()
+            .unwrap();
+
+        Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_run_async() {
+        let mut simulation = setup();
+
+        simulation
+            .run_async(&CancellationToken::new())
+            .await
+            .unwrap();
+
+        assert_eq!(simulation.status, SimulationStatus::Completed);
+        assert_eq!(simulation.interval_reports.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_run_async_cancelled() {
+        let mut simulation = setup();
+        let cancellation = CancellationToken::new();
+        cancellation.cancel();
+
+        let result = simulation.run_async(&cancellation).await;
+
+        assert_eq!(result, Err(SimulationError::Cancelled));
+        assert!(simulation.interval_reports.is_empty());
+    }
+}
@@ -0,0 +1,577 @@
+//! # Bench module
+//!
+//! This module contains the benchmark scenarios and the cost estimate of a simulation run.
+//!
+//! `BenchScenario` builds the workloads of the internal benchmarks from library code, so users can
+//! benchmark their own user counts, durations, and features, e.g. with criterion.
+//! `Simulation::estimated_cost` predicts the run time and memory of a run before starting it,
+//! so callers can reject or queue workloads that are too large.
+
+use std::{collections::BTreeSet, mem, time::Duration};
+
+use rust_decimal::{prelude::*, Decimal};
+
+use crate::{
+    CapitalInflow, MevAgent, PopulationModel, Simulation, SimulationError, SimulationReport, User,
+    UserBehaviour, UserLifecycle,
+};
+
+/// Time of one trade round of one agent, in nanoseconds.
+/// Measured on the default AMM market, with a release build.
+const NANOS_PER_AGENT_ROUND: u64 = 300;
+
+/// Engine features a benchmark scenario can toggle, each with a representative configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BenchFeature {
+    /// Users adopt the token at 1% per interval.
+    Adoption,
+
+    /// Population mix of traders, holders, and whales.
+    BehaviourMix,
+
+    /// Users move between the active, dormant, and churned states.
+    Lifecycle,
+
+    /// New users buy on joining and existing users top up.
+    CapitalInflow,
+
+    /// MEV agent sandwiching the large trades.
+    Mev,
+
+    /// Fee of 0.3% charged on each trade.
+    TransactionFee,
+}
+
+/// Benchmark workload, building fresh simulations to run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchScenario {
+    /// Name of the scenario.
+    pub name: String,
+
+    /// Number of users in the simulation.
+    pub total_users: u64,
+
+    /// Duration of the simulation, in days.
+    pub duration: u64,
+
+    /// Total supply of the token.
+    pub total_supply: i64,
+
+    /// Volatility level of the market.
+    pub market_volatility: f64,
+
+    /// Airdrop amount of the token, in percentage of total supply.
+    pub airdrop_percentage: f64,
+
+    /// Percentage of tokens burned during each transaction.
+    pub burn_rate: f64,
+
+    /// Population model used to represent the users.
+    pub population_model: PopulationModel,
+
+    /// Engine features enabled in the simulation.
+    pub features: BTreeSet<BenchFeature>,
+
+    /// Seed of the random number generator, to benchmark the same runs.
+    pub seed: Option<u64>,
+}
+
+/// Builder for creating a new benchmark scenario.
+#[derive(Debug, Default, PartialEq)]
+pub struct BenchScenarioBuilder {
+    /// Name of the scenario.
+    /// Default value: "Benchmark".
+    pub name: Option<String>,
+
+    /// Number of users in the simulation.
+    /// Default value: 100.
+    pub total_users: Option<u64>,
+
+    /// Duration of the simulation, in days.
+    /// Default value: 7.
+    pub duration: Option<u64>,
+
+    /// Total supply of the token.
+    /// Default value: 1,000,000.
+    pub total_supply: Option<i64>,
+
+    /// Volatility level of the market.
+    /// Default value: 0.5.
+    pub market_volatility: Option<f64>,
+
+    /// Airdrop amount of the token, in percentage of total supply.
+    /// Default value: 5%.
+    pub airdrop_percentage: Option<f64>,
+
+    /// Percentage of tokens burned during each transaction.
+    /// Default value: 1%.
+    pub burn_rate: Option<f64>,
+
+    /// Population model used to represent the users.
+    /// Default value: one agent per user.
+    pub population_model: Option<PopulationModel>,
+
+    /// Engine features enabled in the simulation.
+    /// Default value: none.
+    pub features: BTreeSet<BenchFeature>,
+
+    /// Seed of the random number generator.
+    /// Optional field.
+    pub seed: Option<u64>,
+}
+
+/// Estimated cost of a simulation run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CostEstimate {
+    /// Number of trade rounds played by all agents over the run.
+    pub agent_rounds: u64,
+
+    /// Estimated run time, for a release build.
+    pub duration: Duration,
+
+    /// Estimated peak memory held by the users and reports, in bytes.
+    pub memory_bytes: u64,
+}
+
+impl BenchScenarioBuilder {
+    /// Create a new benchmark scenario builder.
+    ///
+    /// # Returns
+    ///
+    /// New benchmark scenario builder.
+    pub fn new() -> Self {
+        BenchScenarioBuilder::default()
+    }
+
+    /// Set the name of the scenario.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the scenario.
+    ///
+    /// # Returns
+    ///
+    /// The benchmark scenario builder.
+    pub fn name(mut self, name: String) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Set the number of users in the simulation.
+    ///
+    /// # Arguments
+    ///
+    /// * `total_users` - Number of users.
+    ///
+    /// # Returns
+    ///
+    /// The benchmark scenario builder.
+    pub fn total_users(mut self, total_users: u64) -> Self {
+        self.total_users = Some(total_users);
+        self
+    }
+
+    /// Set the duration of the simulation, in days.
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - Duration of the simulation.
+    ///
+    /// # Returns
+    ///
+    /// The benchmark scenario builder.
+    pub fn duration(mut self, duration: u64) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    /// Set the total supply of the token.
+    ///
+    /// # Arguments
+    ///
+    /// * `total_supply` - Total supply of the token.
+    ///
+    /// # Returns
+    ///
+    /// The benchmark scenario builder.
+    pub fn total_supply(mut self, total_supply: i64) -> Self {
+        self.total_supply = Some(total_supply);
+        self
+    }
+
+    /// Set the volatility level of the market.
+    ///
+    /// # Arguments
+    ///
+    /// * `market_volatility` - Volatility level, 0.0 is no volatility.
+    ///
+    /// # Returns
+    ///
+    /// The benchmark scenario builder.
+    pub fn market_volatility(mut self, market_volatility: f64) -> Self {
+        self.market_volatility = Some(market_volatility);
+        self
+    }
+
+    /// Set the airdrop amount of the token.
+    ///
+    /// # Arguments
+    ///
+    /// * `airdrop_percentage` - Airdrop amount, in percentage of total supply.
+    ///
+    /// # Returns
+    ///
+    /// The benchmark scenario builder.
+    pub fn airdrop_percentage(mut self, airdrop_percentage: f64) -> Self {
+        self.airdrop_percentage = Some(airdrop_percentage);
+        self
+    }
+
+    /// Set the percentage of tokens burned during each transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `burn_rate` - Burn rate, in percentage.
+    ///
+    /// # Returns
+    ///
+    /// The benchmark scenario builder.
+    pub fn burn_rate(mut self, burn_rate: f64) -> Self {
+        self.burn_rate = Some(burn_rate);
+        self
+    }
+
+    /// Set the population model used to represent the users.
+    ///
+    /// # Arguments
+    ///
+    /// * `population_model` - Population model.
+    ///
+    /// # Returns
+    ///
+    /// The benchmark scenario builder.
+    pub fn population_model(mut self, population_model: PopulationModel) -> Self {
+        self.population_model = Some(population_model);
+        self
+    }
+
+    /// Enable an engine feature in the simulation.
+    ///
+    /// # Arguments
+    ///
+    /// * `feature` - Feature to enable.
+    ///
+    /// # Returns
+    ///
+    /// The benchmark scenario builder.
+    pub fn feature(mut self, feature: BenchFeature) -> Self {
+        self.features.insert(feature);
+        self
+    }
+
+    /// Set the seed of the random number generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - Seed of the random number generator.
+    ///
+    /// # Returns
+    ///
+    /// The benchmark scenario builder.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Build the benchmark scenario.
+    ///
+    /// # Returns
+    ///
+    /// Built benchmark scenario, or an error if the scenario has no users.
+    pub fn build(self) -> Result<BenchScenario, SimulationError> {
+        let total_users = self.total_users.unwrap_or(100);
+        if total_users == 0 {
+            return Err(SimulationError::MissingTotalUsers);
+        }
+
+        Ok(BenchScenario {
+            name: self.name.unwrap_or_else(|| "Benchmark".to_string()),
+            total_users,
+            duration: self.duration.unwrap_or(7),
+            total_supply: self.total_supply.unwrap_or(1_000_000),
+            market_volatility: self.market_volatility.unwrap_or(0.5),
+            airdrop_percentage: self.airdrop_percentage.unwrap_or(5.0),
+            burn_rate: self.burn_rate.unwrap_or(1.0),
+            population_model: self.population_model.unwrap_or_default(),
+            features: self.features,
+            seed: self.seed,
+        })
+    }
+}
+
+impl BenchScenario {
+    /// Create a new benchmark scenario builder.
+    ///
+    /// # Returns
+    ///
+    /// New benchmark scenario builder.
+    pub fn builder() -> BenchScenarioBuilder {
+        BenchScenarioBuilder::new()
+    }
+
+    /// Scenario of the small internal benchmark: 100 users.
+    ///
+    /// # Returns
+    ///
+    /// Small benchmark scenario.
+    pub fn small() -> Self {
+        BenchScenario::preset("Small Simulation", 100, 1_000_000, 0.5, 5.0, 1.0)
+    }
+
+    /// Scenario of the large internal benchmark: 500,000 users.
+    ///
+    /// # Returns
+    ///
+    /// Large benchmark scenario.
+    pub fn large() -> Self {
+        BenchScenario::preset("Large Simulation", 500_000, 1_000_000_000, 0.8, 10.0, 2.0)
+    }
+
+    /// Scenario of the extreme internal benchmark: 1,000,000 users.
+    ///
+    /// # Returns
+    ///
+    /// Extreme benchmark scenario.
+    pub fn extreme() -> Self {
+        BenchScenario::preset(
+            "Extreme Simulation",
+            1_000_000,
+            10_000_000_000,
+            1.0,
+            20.0,
+            5.0,
+        )
+    }
+
+    /// Create a scenario of an internal benchmark.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the scenario.
+    /// * `total_users` - Number of users.
+    /// * `total_supply` - Total supply of the token.
+    /// * `market_volatility` - Volatility level of the market.
+    /// * `airdrop_percentage` - Airdrop amount, in percentage of total supply.
+    /// * `burn_rate` - Burn rate, in percentage.
+    ///
+    /// # Returns
+    ///
+    /// Benchmark scenario.
+    fn preset(
+        name: &str,
+        total_users: u64,
+        total_supply: i64,
+        market_volatility: f64,
+        airdrop_percentage: f64,
+        burn_rate: f64,
+    ) -> Self {
+        BenchScenario {
+            name: name.to_string(),
+            total_users,
+            duration: 7,
+            total_supply,
+            market_volatility,
+            airdrop_percentage,
+            burn_rate,
+            population_model: PopulationModel::Agents,
+            features: BTreeSet::new(),
+            seed: None,
+        }
+    }
+
+    /// Build a fresh simulation of the scenario, in the pending state.
+    ///
+    /// # Returns
+    ///
+    /// Simulation of the scenario.
+    pub fn simulation(&self) -> Result<Simulation, SimulationError> {
+        let token = Simulation::token_builder()
+            .name(format!("{} Token", self.name))
+            .total_supply(self.total_supply)
+            .airdrop_percentage(self.airdrop_percentage)
+            .burn_rate(self.burn_rate);
+
+        let mut options = Simulation::options_builder()
+            .total_users(self.total_users)
+            .duration(self.duration)
+            .market_volatility(self.market_volatility)
+            .population_model(self.population_model);
+        if let Some(seed) = self.seed {
+            options = options.seed(seed);
+        }
+
+        for feature in &self.features {
+            options = match feature {
+                BenchFeature::Adoption => options.adoption_rate(0.01),
+                BenchFeature::BehaviourMix => options.behaviour_mix(
+                    [
+                        (UserBehaviour::Trader, 3.0),
+                        (UserBehaviour::Holder, 1.0),
+                        (UserBehaviour::Whale, 0.2),
+                    ]
+                    .into(),
+                ),
+                BenchFeature::Lifecycle => options.lifecycle(UserLifecycle {
+                    active_to_dormant: 0.05,
+                    dormant_to_churned: 0.1,
+                    dormant_to_active: 0.2,
+                }),
+                BenchFeature::CapitalInflow => options.capital_inflow(CapitalInflow {
+                    new_user_purchase: Decimal::new(100, 0),
+                    top_up_probability: 0.1,
+                    top_up_amount: Decimal::new(50, 0),
+                }),
+                BenchFeature::Mev => options.mev(MevAgent {
+                    min_trade_amount: Decimal::new(1_000, 0),
+                    capture_probability: 0.5,
+                    max_extraction_percentage: Decimal::new(2, 0),
+                    victim_churn_probability: 0.01,
+                }),
+                BenchFeature::TransactionFee => options.transaction_fee_percentage(0.3),
+            };
+        }
+
+        Simulation::builder()
+            .name(self.name.clone())
+            .token(token.build()?)
+            .options(options.build()?)
+            .build()
+    }
+}
+
+impl Simulation {
+    /// Estimate the cost of running the simulation, before starting it.
+    /// The estimate is a heuristic: it scales with the number of agents, trade rounds, and intervals,
+    /// and is calibrated on the default AMM market, so order books, venues, and MEV take longer.
+    ///
+    /// # Returns
+    ///
+    /// Estimated run time and memory.
+    pub fn estimated_cost(&self) -> CostEstimate {
+        let options = &self.options;
+        let rounds = options
+            .rounds_per_interval
+            .unwrap_or_else(|| self.get_interval());
+        let adoption_rate = options
+            .adoption_rate
+            .and_then(|rate| rate.to_f64())
+            .unwrap_or_default()
+            .max(0.0);
+
+        // Users grow with the adoption rate, like in the run
+        let mut users = options.total_users;
+        let mut peak_agents = 0;
+        let mut agent_rounds: u64 = 0;
+        for _ in 0..options.duration {
+            users = users.saturating_add((users as f64 * adoption_rate).round() as u64);
+
+            let agents = options.population_model.agents(users);
+            peak_agents = agents;
+            agent_rounds = agent_rounds.saturating_add(agents.saturating_mul(rounds));
+        }
+
+        // Users are copied into the columnar store while trading, and into the final report if selected
+        let copies = 2 + options.report_fields.users as u64;
+        let user_bytes = peak_agents
+            .saturating_mul(mem::size_of::<User>() as u64)
+            .saturating_mul(copies);
+        let report_bytes = (options.duration + 1) * mem::size_of::<SimulationReport>() as u64;
+
+        CostEstimate {
+            agent_rounds,
+            duration: Duration::from_nanos(agent_rounds.saturating_mul(NANOS_PER_AGENT_ROUND)),
+            memory_bytes: user_bytes.saturating_add(report_bytes),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_defaults() {
+        let scenario = BenchScenario::builder().build().unwrap();
+
+        assert_eq!(scenario.name, "Benchmark");
+        assert_eq!(scenario.total_users, 100);
+        assert_eq!(scenario.duration, 7);
+        assert!(scenario.features.is_empty());
+        assert_eq!(
+            BenchScenario::builder().total_users(0).build(),
+            Err(SimulationError::MissingTotalUsers)
+        );
+    }
+
+    #[test]
+    fn test_presets() {
+        assert_eq!(BenchScenario::small().total_users, 100);
+        assert_eq!(BenchScenario::large().total_users, 500_000);
+        assert_eq!(BenchScenario::extreme().total_supply, 10_000_000_000);
+    }
+
+    #[test]
+    fn test_simulation_with_features() {
+        let scenario = BenchScenario::builder()
+            .total_users(20)
+            .duration(3)
+            .feature(BenchFeature::Adoption)
+            .feature(BenchFeature::BehaviourMix)
+            .feature(BenchFeature::Lifecycle)
+            .feature(BenchFeature::CapitalInflow)
+            .feature(BenchFeature::Mev)
+            .feature(BenchFeature::TransactionFee)
+            .seed(7)
+            .build()
+            .unwrap();
+
+        let mut simulation = scenario.simulation().unwrap();
+        assert!(simulation.options.adoption_rate.is_some());
+        assert!(simulation.options.behaviour_mix.is_some());
+        assert!(simulation.options.lifecycle.is_some());
+        assert!(simulation.options.capital_inflow.is_some());
+        assert!(simulation.options.mev.is_some());
+        assert!(simulation.options.transaction_fee_percentage.is_some());
+        assert_eq!(simulation.options.seed, Some(7));
+
+        simulation.run().unwrap();
+    }
+
+    #[test]
+    fn test_estimated_cost() {
+        let small = BenchScenario::small()
+            .simulation()
+            .unwrap()
+            .estimated_cost();
+        let large = BenchScenario::large()
+            .simulation()
+            .unwrap()
+            .estimated_cost();
+
+        // 100 users, 24 rounds per day, 7 days
+        assert_eq!(small.agent_rounds, 16_800);
+        assert_eq!(large.agent_rounds, small.agent_rounds * 5_000);
+        assert!(large.duration > small.duration);
+        assert!(large.memory_bytes > small.memory_bytes);
+
+        let cohorts = BenchScenario::builder()
+            .total_users(500_000)
+            .population_model(PopulationModel::Cohorts(1_000))
+            .build()
+            .unwrap()
+            .simulation()
+            .unwrap()
+            .estimated_cost();
+        assert_eq!(cohorts.agent_rounds, 500 * 24 * 7);
+    }
+}
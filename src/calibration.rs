@@ -0,0 +1,316 @@
+//! # Calibration module
+//!
+//! This module contains the calibration, which fits the adoption rate and the user lifecycle to a historical
+//! series of active users, to start a simulation from parameters grounded in real data.
+//!
+//! The fit follows the expected path of the engine: each interval, the users grow by the adoption rate and join active,
+//! then active users become dormant, and dormant users churn or become active again.
+//! The parameters minimizing the squared error between the expected and the observed active users are found
+//! with a pattern search, starting from the first observation.
+
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Simulation, SimulationError, SimulationOptionsBuilder, UserLifecycle};
+
+/// Minimum number of observations of a history.
+pub const MIN_OBSERVATIONS: usize = 3;
+
+/// Initial step of the pattern search.
+const INITIAL_STEP: f64 = 0.1;
+
+/// Step of the pattern search at which the fit stops.
+const MIN_STEP: f64 = 1e-7;
+
+/// Historical series of active users, one observation per interval.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct UserHistory {
+    /// Active users of each interval, oldest first.
+    pub active_users: Vec<u64>,
+}
+
+/// Parameters fitted to a history of active users.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Calibration {
+    /// Users at the start of the simulation: the first observation.
+    pub initial_users: u64,
+
+    /// Number of intervals after the first observation.
+    pub intervals: u64,
+
+    /// Fitted adoption rate, per interval.
+    pub adoption_rate: f64,
+
+    /// Fitted transition probabilities of the user lifecycle.
+    pub lifecycle: UserLifecycle,
+
+    /// Root mean squared error between the expected and the observed active users.
+    pub rmse: f64,
+}
+
+impl UserHistory {
+    /// Create a new history of active users.
+    ///
+    /// # Arguments
+    ///
+    /// * `active_users` - Active users of each interval, oldest first.
+    ///
+    /// # Returns
+    ///
+    /// New history.
+    pub fn new(active_users: Vec<u64>) -> Self {
+        UserHistory { active_users }
+    }
+
+    /// Parse a history from CSV, one observation per line, the active users in the last column,
+    /// e.g. `date,active_users`. A first line that is not a number is read as a header.
+    ///
+    /// # Arguments
+    ///
+    /// * `csv` - CSV of the history.
+    ///
+    /// # Returns
+    ///
+    /// History, or an error if a line has no number of active users.
+    pub fn from_csv(csv: &str) -> Result<Self, SimulationError> {
+        let mut active_users = vec![];
+
+        for (index, line) in csv.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let value = line.rsplit(',').next().unwrap_or_default().trim();
+            match (value.parse::<f64>(), index) {
+                (Ok(users), _) if users >= 0.0 => active_users.push(users.round() as u64),
+                (Err(_), 0) => continue,
+                _ => {
+                    return Err(SimulationError::InvalidHistory(format!(
+                        "line {}: expected a number of active users, got {value}",
+                        index + 1
+                    )))
+                }
+            }
+        }
+
+        Ok(UserHistory { active_users })
+    }
+
+    /// Fit the adoption rate and the user lifecycle to the history.
+    ///
+    /// # Returns
+    ///
+    /// Fitted parameters, or an error if the history is too short or starts without users.
+    pub fn calibrate(&self) -> Result<Calibration, SimulationError> {
+        if self.active_users.len() < MIN_OBSERVATIONS {
+            return Err(SimulationError::InvalidHistory(format!(
+                "expected at least {MIN_OBSERVATIONS} observations, got {}",
+                self.active_users.len()
+            )));
+        }
+        if self.active_users[0] == 0 {
+            return Err(SimulationError::InvalidHistory(
+                "the first observation has no active users".to_string(),
+            ));
+        }
+
+        let observed: Vec<f64> = self
+            .active_users
+            .iter()
+            .map(|users| *users as f64)
+            .collect();
+        let error = |parameters: &[f64; 4]| {
+            expected_active_users(observed[0], parameters, observed.len())
+                .iter()
+                .zip(&observed)
+                .map(|(expected, observed)| (expected - observed).powi(2))
+                .sum::<f64>()
+        };
+
+        // Hooke-Jeeves pattern search: move along each axis while the error decreases, then refine the step
+        let mut parameters = constrain([0.01, 0.05, 0.05, 0.05]);
+        let mut best = error(&parameters);
+        let mut step = INITIAL_STEP;
+        while step > MIN_STEP {
+            let mut improved = false;
+            for axis in 0..parameters.len() {
+                for direction in [1.0, -1.0] {
+                    let mut candidate = parameters;
+                    candidate[axis] += direction * step;
+                    let candidate = constrain(candidate);
+                    let candidate_error = error(&candidate);
+                    if candidate_error < best {
+                        (parameters, best) = (candidate, candidate_error);
+                        improved = true;
+                        break;
+                    }
+                }
+            }
+            if !improved {
+                step /= 2.0;
+            }
+        }
+
+        let [adoption_rate, active_to_dormant, dormant_to_churned, dormant_to_active] = parameters;
+
+        Ok(Calibration {
+            initial_users: self.active_users[0],
+            intervals: self.active_users.len() as u64 - 1,
+            adoption_rate,
+            lifecycle: UserLifecycle {
+                active_to_dormant,
+                dormant_to_churned,
+                dormant_to_active,
+            },
+            rmse: (best / (observed.len() - 1) as f64).sqrt(),
+        })
+    }
+}
+
+impl FromStr for UserHistory {
+    type Err = SimulationError;
+
+    fn from_str(csv: &str) -> Result<Self, Self::Err> {
+        UserHistory::from_csv(csv)
+    }
+}
+
+impl Calibration {
+    /// Get the expected active users of each interval with the fitted parameters.
+    ///
+    /// # Returns
+    ///
+    /// Expected active users, starting with the first observation.
+    pub fn expected_active_users(&self) -> Vec<f64> {
+        expected_active_users(
+            self.initial_users as f64,
+            &[
+                self.adoption_rate,
+                self.lifecycle.active_to_dormant,
+                self.lifecycle.dormant_to_churned,
+                self.lifecycle.dormant_to_active,
+            ],
+            self.intervals as usize + 1,
+        )
+    }
+
+    /// Get an options builder with the fitted parameters, to be completed or built as is.
+    /// The builder has the initial users, one interval per observation after the first,
+    /// the fitted adoption rate, and the fitted lifecycle.
+    ///
+    /// # Returns
+    ///
+    /// Simulation options builder.
+    pub fn options_builder(&self) -> SimulationOptionsBuilder {
+        Simulation::options_builder()
+            .total_users(self.initial_users)
+            .duration(self.intervals)
+            .adoption_rate(self.adoption_rate)
+            .lifecycle(self.lifecycle.clone())
+    }
+}
+
+/// Keep the parameters in the range of probabilities, the dormant transitions summing to at most 1.
+///
+/// # Arguments
+///
+/// * `parameters` - Adoption rate, active to dormant, dormant to churned, and dormant to active.
+///
+/// # Returns
+///
+/// Constrained parameters.
+fn constrain(parameters: [f64; 4]) -> [f64; 4] {
+    let [adoption_rate, active_to_dormant, dormant_to_churned, dormant_to_active] =
+        parameters.map(|parameter| parameter.clamp(0.0, 1.0));
+
+    [
+        adoption_rate,
+        active_to_dormant,
+        dormant_to_churned,
+        dormant_to_active.min(1.0 - dormant_to_churned),
+    ]
+}
+
+/// Get the expected active users of each interval.
+///
+/// # Arguments
+///
+/// * `initial` - Active users of the first interval, all users at that point.
+/// * `parameters` - Adoption rate, active to dormant, dormant to churned, and dormant to active.
+/// * `intervals` - Number of intervals, including the first.
+///
+/// # Returns
+///
+/// Expected active users of each interval.
+fn expected_active_users(initial: f64, parameters: &[f64; 4], intervals: usize) -> Vec<f64> {
+    let [adoption_rate, active_to_dormant, dormant_to_churned, dormant_to_active] = *parameters;
+    let (mut total, mut active, mut dormant) = (initial, initial, 0.0);
+    let mut series = vec![initial];
+
+    for _ in 1..intervals {
+        let joined = total * adoption_rate;
+        total += joined;
+        active += joined;
+        (active, dormant) = (
+            active * (1.0 - active_to_dormant) + dormant * dormant_to_active,
+            active * active_to_dormant + dormant * (1.0 - dormant_to_churned - dormant_to_active),
+        );
+        series.push(active);
+    }
+
+    series
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_csv() {
+        let history =
+            UserHistory::from_csv("date,active_users\n2024-01-01,100\n\n2024-01-02,110.4\n")
+                .unwrap();
+        assert_eq!(history.active_users, vec![100, 110]);
+        assert_eq!(
+            "5\n6\n".parse::<UserHistory>().unwrap().active_users,
+            vec![5, 6]
+        );
+
+        assert!(matches!(
+            UserHistory::from_csv("active_users\n100\nunknown\n"),
+            Err(SimulationError::InvalidHistory(_))
+        ));
+        assert!(matches!(
+            UserHistory::new(vec![100, 110]).calibrate(),
+            Err(SimulationError::InvalidHistory(_))
+        ));
+    }
+
+    #[test]
+    fn test_calibrate() {
+        let truth = [0.03, 0.1, 0.2, 0.1];
+        let active_users = expected_active_users(1_000.0, &truth, 60)
+            .into_iter()
+            .map(|users| users.round() as u64)
+            .collect();
+
+        let calibration = UserHistory::new(active_users).calibrate().unwrap();
+
+        assert_eq!(calibration.initial_users, 1_000);
+        assert_eq!(calibration.intervals, 59);
+        assert!((calibration.adoption_rate - 0.03).abs() < 0.005);
+        assert!((calibration.lifecycle.active_to_dormant - 0.1).abs() < 0.02);
+        assert!(calibration.rmse < 5.0);
+        assert_eq!(calibration.expected_active_users().len(), 60);
+
+        let options = calibration.options_builder().build().unwrap();
+        assert_eq!(options.total_users, 1_000);
+        assert_eq!(options.duration, 59);
+        assert_eq!(options.lifecycle, Some(calibration.lifecycle));
+    }
+}
@@ -0,0 +1,168 @@
+//! # Cancellation module
+//!
+//! This module contains the cancellation token used to stop a running simulation.
+//!
+//! The token is cheap to clone and thread-safe: one clone is handed to the run,
+//! another one is kept by the caller, e.g. a web handler, to cancel the run when the client goes away.
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex, PoisonError,
+};
+
+use crate::{Simulation, SimulationError};
+
+/// Token used to cancel a running simulation.
+/// All clones of a token share the same state.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    /// Whether the cancellation was requested.
+    cancelled: Arc<AtomicBool>,
+
+    /// Reason of the cancellation, if given.
+    reason: Arc<Mutex<Option<String>>>,
+}
+
+impl CancellationToken {
+    /// Create a new cancellation token.
+    ///
+    /// # Returns
+    ///
+    /// New cancellation token, not cancelled.
+    pub fn new() -> Self {
+        CancellationToken::default()
+    }
+
+    /// Request the cancellation.
+    /// Runs using the token stop before their next interval.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Request the cancellation, with a reason recorded in the status of the cancelled simulation.
+    ///
+    /// # Arguments
+    ///
+    /// * `reason` - Reason of the cancellation.
+    pub fn cancel_with_reason(&self, reason: impl Into<String>) {
+        *self.reason.lock().unwrap_or_else(PoisonError::into_inner) = Some(reason.into());
+        self.cancel();
+    }
+
+    /// Get the reason of the cancellation.
+    ///
+    /// # Returns
+    ///
+    /// Reason of the cancellation, if given.
+    pub fn reason(&self) -> Option<String> {
+        self.reason
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Check whether the cancellation was requested.
+    ///
+    /// # Returns
+    ///
+    /// Whether the token is cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+impl Simulation {
+    /// Run the simulation, stopping before the next interval once cancelled.
+    ///
+    /// # Arguments
+    ///
+    /// * `cancellation` - Token used to cancel the run.
+    ///
+    /// # Returns
+    ///
+    /// Result of the simulation, or `SimulationError::Cancelled` if the run was cancelled.
+    pub fn run_with_cancellation(
+        &mut self,
+        cancellation: &CancellationToken,
+    ) -> Result<(), SimulationError> {
+        let mut state = self
+            .start_run()
+            .map_err(|error| self.fail_run(None, error))?;
+
+        while state.index < self.options.duration {
+            if cancellation.is_cancelled() {
+                #[cfg(feature = "log")]
+                log::debug!("Simulation cancelled: {}", self.name);
+
+                return Err(self.cancel_run(state, cancellation));
+            }
+
+            if let Err(error) = self.run_interval(&mut state) {
+                return Err(self.fail_run(Some(state), error));
+            }
+        }
+
+        self.finish_run(state);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancellation_token() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert!(!token.is_cancelled());
+
+        clone.cancel();
+
+        assert!(token.is_cancelled());
+        assert!(clone.is_cancelled());
+        assert_eq!(token.reason(), None);
+
+        clone.cancel_with_reason("client disconnected");
+        assert_eq!(token.reason(), Some("client disconnected".to_string()));
+    }
+
+    #[test]
+    fn test_run_with_cancellation() {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(10)
+            .duration(5)
+            .build()
+            .unwrap();
+        let mut simulation = Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap();
+
+        let cancellation = CancellationToken::new();
+        simulation.run_with_cancellation(&cancellation).unwrap();
+        assert_eq!(simulation.interval_reports.len(), 5);
+
+        cancellation.cancel_with_reason("shutdown");
+        assert_eq!(
+            simulation.run_with_cancellation(&cancellation),
+            Err(SimulationError::Cancelled)
+        );
+        assert!(simulation.interval_reports.is_empty());
+        assert_eq!(
+            simulation.status,
+            crate::SimulationStatus::Cancelled {
+                at_interval: 0,
+                reason: "shutdown".to_string(),
+                cancelled_at: simulation.updated_at,
+            }
+        );
+    }
+}
@@ -0,0 +1,216 @@
+//! # CoinGecko module
+//!
+//! This module contains the client of the CoinGecko API, which fetches the daily price history of a token
+//! as a price feed, to backtest a simulation against the history of a comparable token.
+//!
+//! The client calls the `market_chart` endpoint, which returns one price per day for ranges longer than 90 days,
+//! and keeps the first price of each day for shorter ranges.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::{prelude::FromPrimitive, Decimal};
+
+use crate::{PriceFeed, PricePoint, SimulationError};
+
+/// Base URL of the public CoinGecko API.
+pub const COINGECKO_API_URL: &str = "https://api.coingecko.com/api/v3";
+
+/// Client of the CoinGecko API.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoinGecko {
+    /// Base URL of the API.
+    pub base_url: String,
+
+    /// Demo API key, sent in the `x-cg-demo-api-key` header.
+    pub api_key: Option<String>,
+}
+
+impl Default for CoinGecko {
+    fn default() -> Self {
+        CoinGecko {
+            base_url: COINGECKO_API_URL.to_string(),
+            api_key: None,
+        }
+    }
+}
+
+impl CoinGecko {
+    /// Create a new client of the public API.
+    ///
+    /// # Returns
+    ///
+    /// New client, without an API key.
+    pub fn new() -> Self {
+        CoinGecko::default()
+    }
+
+    /// Set the demo API key, to raise the rate limit of the public API.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - Demo API key.
+    ///
+    /// # Returns
+    ///
+    /// The client with the API key.
+    pub fn api_key(mut self, api_key: &str) -> Self {
+        self.api_key = Some(api_key.to_string());
+        self
+    }
+
+    /// Fetch the daily prices of a token.
+    ///
+    /// # Arguments
+    ///
+    /// * `coin_id` - CoinGecko ID of the token, e.g. `uniswap`.
+    /// * `vs_currency` - Currency of the prices, e.g. `usd`.
+    /// * `days` - Number of days of history, up to today.
+    ///
+    /// # Returns
+    ///
+    /// Price feed of the token, oldest first, or an error if the request fails or the response is invalid.
+    pub fn daily_prices(
+        &self,
+        coin_id: &str,
+        vs_currency: &str,
+        days: u32,
+    ) -> Result<PriceFeed, SimulationError> {
+        let url = format!(
+            "{}/coins/{coin_id}/market_chart",
+            self.base_url.trim_end_matches('/')
+        );
+        let mut request = ureq::get(&url)
+            .query("vs_currency", vs_currency)
+            .query("days", &days.to_string())
+            .query("interval", "daily");
+        if let Some(api_key) = &self.api_key {
+            request = request.set("x-cg-demo-api-key", api_key);
+        }
+
+        let body = request
+            .call()
+            .and_then(|response| Ok(response.into_string()?))
+            .map_err(|err| SimulationError::FetchFailed(format!("{url}: {err}")))?;
+
+        parse_market_chart(&body)
+    }
+}
+
+/// Parse the response of the `market_chart` endpoint into a daily price feed.
+///
+/// # Arguments
+///
+/// * `json` - Response, with the prices as `[timestamp in milliseconds, price]` pairs.
+///
+/// # Returns
+///
+/// Price feed with the first price of each day, or an error if the response has no valid prices.
+pub fn parse_market_chart(json: &str) -> Result<PriceFeed, SimulationError> {
+    let invalid = |reason: &str| SimulationError::InvalidImport(format!("CoinGecko: {reason}"));
+    let value: serde_json::Value =
+        serde_json::from_str(json).map_err(|err| invalid(&err.to_string()))?;
+
+    let points = value
+        .get("prices")
+        .and_then(|prices| prices.as_array())
+        .ok_or_else(|| invalid("missing prices"))?
+        .iter()
+        .map(|pair| {
+            let timestamp = pair.get(0).and_then(|timestamp| timestamp.as_f64());
+            let price = pair.get(1).and_then(|price| price.as_f64());
+
+            match (timestamp, price) {
+                (Some(timestamp), Some(price)) => Ok(PricePoint {
+                    date: DateTime::<Utc>::from_timestamp_millis(timestamp as i64)
+                        .ok_or_else(|| invalid("invalid timestamp"))?,
+                    price: Decimal::from_f64(price).ok_or_else(|| invalid("invalid price"))?,
+                }),
+                _ => Err(invalid("expected [timestamp, price] pairs")),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut feed = PriceFeed::new(points);
+    feed.prices.dedup_by_key(|point| point.date.date_naive());
+
+    Ok(feed)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    use super::*;
+
+    const MARKET_CHART: &str = r#"{
+        "prices": [[1704067200000, 1.5], [1704153600000, 3.0], [1704196800000, 3.3], [1704240000000, 1.5]],
+        "market_caps": [],
+        "total_volumes": []
+    }"#;
+
+    #[test]
+    fn test_parse_market_chart() {
+        let feed = parse_market_chart(MARKET_CHART).unwrap();
+
+        // The intraday price of the second day is dropped
+        assert_eq!(feed.intervals(), 2);
+        assert_eq!(feed.change_at(0), Some(Decimal::new(2, 0)));
+        assert_eq!(feed.change_at(1), Some(Decimal::new(5, 1)));
+
+        for invalid in [
+            r#"{"prices": [[1]]}"#,
+            r#"{"error": "rate limited"}"#,
+            "<html>",
+        ] {
+            assert!(matches!(
+                parse_market_chart(invalid),
+                Err(SimulationError::InvalidImport(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_daily_prices() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = vec![0; 1024];
+            let read = stream.read(&mut request).unwrap();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{MARKET_CHART}",
+                MARKET_CHART.len()
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+
+            String::from_utf8_lossy(&request[..read]).to_string()
+        });
+
+        let client = CoinGecko {
+            base_url: format!("http://127.0.0.1:{port}/api/v3/"),
+            ..CoinGecko::new()
+        }
+        .api_key("demo");
+        let feed = client.daily_prices("uniswap", "usd", 30).unwrap();
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with(
+            "GET /api/v3/coins/uniswap/market_chart?vs_currency=usd&days=30&interval=daily HTTP/1.1\r\n"
+        ));
+        assert!(request.contains("x-cg-demo-api-key: demo\r\n"));
+        assert_eq!(feed.prices.len(), 3);
+
+        assert!(matches!(
+            CoinGecko {
+                base_url: "http://127.0.0.1:1".to_string(),
+                api_key: None,
+            }
+            .daily_prices("uniswap", "usd", 30),
+            Err(SimulationError::FetchFailed(_))
+        ));
+    }
+}
@@ -0,0 +1,329 @@
+//! # Comparison module
+//!
+//! This module contains the side-by-side comparison of the final reports of two simulations, e.g. for A/B discussions.
+//!
+//! Each compared metric shows the baseline and variant values, their difference, and the relative change.
+//! The comparison can be rendered as an aligned text table for terminals, or exported as CSV.
+
+use std::{fmt::Write, str::FromStr};
+
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{EnsembleMetric, NumberFormat, Simulation, SimulationError};
+
+/// Difference of a metric between two simulations.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct MetricDelta {
+    /// Compared metric.
+    pub metric: EnsembleMetric,
+
+    /// Value of the baseline simulation.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub baseline: Decimal,
+
+    /// Value of the variant simulation.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub variant: Decimal,
+
+    /// Difference of the variant from the baseline.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub delta: Decimal,
+
+    /// Relative change of the variant from the baseline, in percentage, if the baseline is not zero.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float_option"))]
+    pub change: Option<Decimal>,
+}
+
+/// Side-by-side comparison of two simulations.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct SimulationComparison {
+    /// Name of the baseline simulation.
+    pub baseline: String,
+
+    /// Name of the variant simulation.
+    pub variant: String,
+
+    /// Difference of each compared metric, in the requested order.
+    pub deltas: Vec<MetricDelta>,
+}
+
+impl EnsembleMetric {
+    /// Get the name of the metric, as accepted by `FromStr`.
+    ///
+    /// # Returns
+    ///
+    /// Name of the metric, in snake case.
+    pub fn name(&self) -> &'static str {
+        match self {
+            EnsembleMetric::TokenPrice => "token_price",
+            EnsembleMetric::ProfitLoss => "profit_loss",
+            EnsembleMetric::Trades => "trades",
+            EnsembleMetric::Liquidity => "liquidity",
+            EnsembleMetric::AdoptionRate => "adoption_rate",
+            EnsembleMetric::BurnRate => "burn_rate",
+            EnsembleMetric::InflationRate => "inflation_rate",
+            EnsembleMetric::UserRetention => "user_retention",
+            EnsembleMetric::NetworkActivity => "network_activity",
+            EnsembleMetric::FinalUserCount => "final_user_count",
+        }
+    }
+
+    /// Parse a comma-separated list of metrics, e.g. `price,retention`.
+    ///
+    /// # Arguments
+    ///
+    /// * `metrics` - Names of the metrics.
+    ///
+    /// # Returns
+    ///
+    /// Parsed metrics, or an error naming the first unknown metric.
+    pub fn parse_list(metrics: &str) -> Result<Vec<EnsembleMetric>, SimulationError> {
+        metrics
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(EnsembleMetric::from_str)
+            .collect()
+    }
+}
+
+impl FromStr for EnsembleMetric {
+    type Err = SimulationError;
+
+    /// Parse a metric from its name, or from a short name such as `price` or `retention`.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let short = match name {
+            "price" => Some(EnsembleMetric::TokenPrice),
+            "pnl" => Some(EnsembleMetric::ProfitLoss),
+            "adoption" => Some(EnsembleMetric::AdoptionRate),
+            "burn" => Some(EnsembleMetric::BurnRate),
+            "inflation" => Some(EnsembleMetric::InflationRate),
+            "retention" => Some(EnsembleMetric::UserRetention),
+            "activity" => Some(EnsembleMetric::NetworkActivity),
+            "users" => Some(EnsembleMetric::FinalUserCount),
+            _ => None,
+        };
+
+        short
+            .or_else(|| {
+                EnsembleMetric::ALL
+                    .into_iter()
+                    .find(|metric| metric.name() == name)
+            })
+            .ok_or_else(|| SimulationError::UnknownMetric(name.to_string()))
+    }
+}
+
+impl Simulation {
+    /// Compare the final report of the simulation with another simulation.
+    ///
+    /// # Arguments
+    ///
+    /// * `variant` - Simulation to compare with.
+    /// * `metrics` - Metrics to compare, in the order of the rows.
+    ///
+    /// # Returns
+    ///
+    /// Side-by-side comparison, with this simulation as the baseline.
+    pub fn compare(
+        &self,
+        variant: &Simulation,
+        metrics: &[EnsembleMetric],
+    ) -> SimulationComparison {
+        let deltas = metrics
+            .iter()
+            .map(|metric| {
+                let baseline = metric.value(&self.report);
+                let value = metric.value(&variant.report);
+                let delta = value - baseline;
+
+                MetricDelta {
+                    metric: *metric,
+                    baseline,
+                    variant: value,
+                    delta,
+                    change: (delta * Decimal::ONE_HUNDRED)
+                        .checked_div(baseline)
+                        .map(|change| change.normalize()),
+                }
+            })
+            .collect();
+
+        SimulationComparison {
+            baseline: self.name.clone(),
+            variant: variant.name.clone(),
+            deltas,
+        }
+    }
+}
+
+impl SimulationComparison {
+    /// Render the comparison as a text table with aligned columns.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - Formatting of the numbers.
+    ///
+    /// # Returns
+    ///
+    /// Table with a header row and one row per metric.
+    pub fn to_table(&self, format: &NumberFormat) -> String {
+        let mut rows = vec![[
+            "metric".to_string(),
+            self.baseline.clone(),
+            self.variant.clone(),
+            "delta".to_string(),
+            "change".to_string(),
+        ]];
+        for delta in &self.deltas {
+            let sign = |value: Decimal| match value.is_sign_negative() {
+                true => "",
+                false => "+",
+            };
+
+            rows.push([
+                delta.metric.name().to_string(),
+                format.format(delta.baseline),
+                format.format(delta.variant),
+                format!("{}{}", sign(delta.delta), format.format(delta.delta)),
+                delta.change.map_or("n/a".to_string(), |change| {
+                    format!("{}{}", sign(change), format.format_percentage(change))
+                }),
+            ]);
+        }
+
+        let mut widths = [0; 5];
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let mut table = String::new();
+        for (index, row) in rows.iter().enumerate() {
+            // The metric names are aligned left, the numbers right
+            let _ = write!(table, "{:<width$}", row[0], width = widths[0]);
+            for (cell, width) in row.iter().zip(widths).skip(1) {
+                let padding = width - cell.chars().count();
+                let _ = write!(table, "  {}{cell}", " ".repeat(padding));
+            }
+            table.push('\n');
+
+            if index == 0 {
+                let rule: usize = widths.iter().sum::<usize>() + 2 * (widths.len() - 1);
+                let _ = writeln!(table, "{}", "-".repeat(rule));
+            }
+        }
+
+        table
+    }
+
+    /// Export the comparison as CSV, one metric per line.
+    ///
+    /// # Returns
+    ///
+    /// CSV with a `metric,baseline,variant,delta,change` header, the change empty if not defined.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("metric,baseline,variant,delta,change\n");
+
+        for delta in &self.deltas {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{}",
+                delta.metric.name(),
+                delta.baseline,
+                delta.variant,
+                delta.delta,
+                delta
+                    .change
+                    .map(|change| change.to_string())
+                    .unwrap_or_default()
+            );
+        }
+
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FiatAmount, SimulationReport};
+
+    use super::*;
+
+    fn setup(name: &str, price: Decimal, retention: Decimal) -> Simulation {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(10)
+            .build()
+            .unwrap();
+        let mut simulation = Simulation::builder()
+            .name(name.to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap();
+        simulation.report = SimulationReport {
+            token_price: FiatAmount(price),
+            user_retention: retention,
+            ..Default::default()
+        };
+
+        simulation
+    }
+
+    #[test]
+    fn test_parse_metrics() {
+        assert_eq!(
+            EnsembleMetric::parse_list("price, retention,final_user_count").unwrap(),
+            vec![
+                EnsembleMetric::TokenPrice,
+                EnsembleMetric::UserRetention,
+                EnsembleMetric::FinalUserCount
+            ]
+        );
+        assert_eq!(
+            EnsembleMetric::parse_list("price,volume"),
+            Err(SimulationError::UnknownMetric("volume".to_string()))
+        );
+        assert!(EnsembleMetric::ALL
+            .iter()
+            .all(|metric| metric.name().parse::<EnsembleMetric>() == Ok(*metric)));
+    }
+
+    #[test]
+    fn test_compare() {
+        let baseline = setup("baseline", Decimal::new(2, 0), Decimal::default());
+        let variant = setup("variant", Decimal::new(25, 1), Decimal::new(5, 1));
+
+        let comparison = baseline.compare(
+            &variant,
+            &[EnsembleMetric::TokenPrice, EnsembleMetric::UserRetention],
+        );
+
+        assert_eq!(comparison.deltas[0].delta, Decimal::new(5, 1));
+        assert_eq!(comparison.deltas[0].change, Some(Decimal::new(25, 0)));
+        assert_eq!(comparison.deltas[1].change, None);
+
+        let table = comparison.to_table(&NumberFormat::default());
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("metric"));
+        assert!(lines[2].starts_with("token_price"));
+        assert!(lines[2].ends_with(" +0.5    +25%"));
+        assert!(lines[3].ends_with("n/a"));
+        assert!(lines.iter().all(|line| line.len() == lines[1].len()));
+
+        assert_eq!(
+            comparison.to_csv().lines().nth(1).unwrap(),
+            "token_price,2,2.5,0.5,25"
+        );
+    }
+}
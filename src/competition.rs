@@ -0,0 +1,258 @@
+//! # Competition module
+//!
+//! This module contains the competitor model, a rival token launched during the simulation.
+//!
+//! From its launch, the rival pulls users away when it pays a higher yield than the token.
+//! The yield of the token is the holder income of the previous interval, the fees redirected to the holders and the gauge emission,
+//! against the circulating supply. The gap to the yield of the rival sets the share of the active users drained in each interval,
+//! who leave for the rival and no longer trade, taking their volume with them.
+
+use rand::Rng;
+use rust_decimal::{prelude::*, Decimal};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{SimulationError, UserState, UserStore};
+
+/// Rival token competing for the users.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Competitor {
+    /// Name of the rival token.
+    pub name: String,
+
+    /// Index of the interval the rival launches in.
+    pub launch_interval: u64,
+
+    /// Yield paid by the rival in each interval, in percentage.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub yield_rate: Decimal,
+
+    /// Share of the active users drained per percentage point of yield gap, in percentage.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub sensitivity: Decimal,
+
+    /// Largest share of the active users drained in an interval, in percentage.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub max_drain: Decimal,
+}
+
+impl Competitor {
+    /// Create a new competitor, draining up to 100% of the active users in an interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the rival token.
+    /// * `launch_interval` - Index of the interval the rival launches in.
+    /// * `yield_rate` - Yield paid by the rival in each interval, in percentage.
+    /// * `sensitivity` - Share of the active users drained per percentage point of yield gap, in percentage.
+    ///
+    /// # Returns
+    ///
+    /// New competitor.
+    pub fn new(
+        name: &str,
+        launch_interval: u64,
+        yield_rate: Decimal,
+        sensitivity: Decimal,
+    ) -> Self {
+        Competitor {
+            name: name.to_string(),
+            launch_interval,
+            yield_rate,
+            sensitivity,
+            max_drain: Decimal::ONE_HUNDRED,
+        }
+    }
+
+    /// Validate the competitor.
+    ///
+    /// # Returns
+    ///
+    /// Result of the validation, or an error if the sensitivity or the largest drain is out of range.
+    pub fn validate(&self) -> Result<(), SimulationError> {
+        if self.sensitivity < Decimal::ZERO {
+            return Err(SimulationError::InvalidData(
+                "competitor: sensitivity must not be negative".to_string(),
+            ));
+        }
+        if !(Decimal::ZERO..=Decimal::ONE_HUNDRED).contains(&self.max_drain) {
+            return Err(SimulationError::InvalidData(
+                "competitor: largest drain must be between 0 and 100".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Get the yield gap of an interval, the yield of the rival above the yield of the token.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the interval.
+    /// * `holder_yield` - Yield of the token, in percentage.
+    ///
+    /// # Returns
+    ///
+    /// Yield gap in percentage points, `None` before the launch of the rival.
+    pub fn yield_gap(&self, index: u64, holder_yield: Decimal) -> Option<Decimal> {
+        (index >= self.launch_interval).then(|| self.yield_rate - holder_yield)
+    }
+
+    /// Get the share of the active users drained in an interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `yield_gap` - Yield gap in percentage points.
+    ///
+    /// # Returns
+    ///
+    /// Drained share of the active users, in percentage.
+    pub fn drain_share(&self, yield_gap: Decimal) -> Decimal {
+        (yield_gap * self.sensitivity).clamp(Decimal::ZERO, self.max_drain)
+    }
+
+    /// Drain the active users of an interval to the rival.
+    ///
+    /// # Arguments
+    ///
+    /// * `yield_gap` - Yield gap in percentage points.
+    /// * `users` - Users of the simulation.
+    /// * `rng` - Random number generator.
+    ///
+    /// # Returns
+    ///
+    /// Number of users drained, or an error if the drained share cannot be converted.
+    pub(crate) fn drain<R: Rng + ?Sized>(
+        &self,
+        yield_gap: Decimal,
+        users: &mut UserStore,
+        rng: &mut R,
+    ) -> Result<u64, SimulationError> {
+        let probability = (self.drain_share(yield_gap) / Decimal::ONE_HUNDRED)
+            .to_f64()
+            .ok_or(SimulationError::InvalidDecimal)?;
+
+        let mut drained = 0;
+        for (state, weight) in users.states.iter_mut().zip(&users.weights) {
+            if *state == UserState::Active && rng.random_bool(probability.clamp(0.0, 1.0)) {
+                *state = UserState::Churned;
+                drained += weight;
+            }
+        }
+
+        Ok(drained)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+    use uuid::Uuid;
+
+    use crate::{Percentage, Simulation, User};
+
+    use super::*;
+
+    #[test]
+    fn test_drain() {
+        let competitor = Competitor {
+            max_drain: Decimal::new(50, 0),
+            ..Competitor::new("Rival", 2, Decimal::new(3, 0), Decimal::new(10, 0))
+        };
+        let mut users = UserStore::from(vec![User::new(Uuid::new_v4(), Decimal::new(100, 0)); 10]);
+        let mut rng = StdRng::seed_from_u64(1);
+
+        assert_eq!(competitor.yield_gap(1, Decimal::ONE), None);
+        assert_eq!(
+            competitor.yield_gap(2, Decimal::ONE),
+            Some(Decimal::new(2, 0))
+        );
+        assert_eq!(
+            competitor.drain_share(Decimal::new(2, 0)),
+            Decimal::new(20, 0)
+        );
+        assert_eq!(
+            competitor.drain_share(Decimal::new(9, 0)),
+            Decimal::new(50, 0)
+        );
+        // A token out-yielding the rival keeps its users
+        assert_eq!(competitor.drain_share(Decimal::new(-1, 0)), Decimal::ZERO);
+        assert_eq!(
+            competitor
+                .drain(Decimal::new(-1, 0), &mut users, &mut rng)
+                .unwrap(),
+            0
+        );
+
+        let competitor = Competitor::new("Rival", 0, Decimal::new(10, 0), Decimal::new(10, 0));
+        assert_eq!(
+            competitor
+                .drain(Decimal::new(10, 0), &mut users, &mut rng)
+                .unwrap(),
+            10
+        );
+        assert!(users
+            .states
+            .iter()
+            .all(|state| *state == UserState::Churned));
+    }
+
+    #[test]
+    fn test_run_with_competitor() {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(50)
+            .duration(5)
+            .seed(3)
+            .competitor(Competitor::new(
+                "Rival",
+                2,
+                Decimal::new(2, 0),
+                Decimal::new(10, 0),
+            ))
+            .build()
+            .unwrap();
+        let mut simulation = Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap();
+
+        simulation.run().unwrap();
+
+        let reports = &simulation.interval_reports;
+        assert!(reports[..2]
+            .iter()
+            .all(|report| report.yield_gap.is_none() && report.users_drained == 0));
+        assert_eq!(reports[2].yield_gap, Some(Percentage(Decimal::new(2, 0))));
+        assert!(reports[2].users_drained > 0);
+        assert_eq!(
+            simulation.report.users_drained,
+            reports
+                .iter()
+                .map(|report| report.users_drained)
+                .sum::<u64>()
+        );
+        assert!(simulation.report.churned_users >= simulation.report.users_drained);
+    }
+
+    #[test]
+    fn test_validate() {
+        let competitor = Competitor::new("Rival", 0, Decimal::ONE, Decimal::ONE);
+        assert!(competitor.validate().is_ok());
+
+        let competitor = Competitor {
+            sensitivity: Decimal::new(-1, 0),
+            ..competitor
+        };
+        assert!(matches!(
+            competitor.validate(),
+            Err(SimulationError::InvalidData(_))
+        ));
+    }
+}
@@ -0,0 +1,253 @@
+//! # Compliance module
+//!
+//! This module contains the holder cap, a compliance scenario limiting the share of the supply a single holder may keep.
+//!
+//! Exchange listing rules or regulators may require a distributed supply. From the enforcement interval on,
+//! each holder above the cap sells a share of the excess in every interval, so large positions are reduced over time
+//! instead of at once. The forced sales are sold on the market like any other sell, adding to the sell volume
+//! and pushing the price down, a sustained sell pressure until all the holders comply.
+
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{SimulationError, UserStore};
+
+/// Cap on the share of the circulating supply held by a single holder.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct HolderCap {
+    /// Largest share of the circulating supply a holder may keep, in percentage.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub max_share: Decimal,
+
+    /// Share of the excess sold by a holder above the cap in each interval, in percentage.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub reduction_rate: Decimal,
+
+    /// Index of the interval the cap is enforced from.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub start_interval: u64,
+
+    /// Drop of the price, in percentage, per percentage of the circulating supply sold by the holders above the cap.
+    /// The drop persists under the valuation models too, although they recalculate the price every interval.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub price_impact: Decimal,
+}
+
+/// Forced sales of an interval.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct ForcedSales {
+    /// Number of holders above the cap.
+    pub(crate) holders: u64,
+
+    /// Tokens sold by the holders above the cap.
+    pub(crate) sold: Decimal,
+}
+
+impl HolderCap {
+    /// Create a new holder cap, enforced from the first interval, with a price impact of 1.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_share` - Largest share of the circulating supply a holder may keep, in percentage.
+    /// * `reduction_rate` - Share of the excess sold in each interval, in percentage.
+    ///
+    /// # Returns
+    ///
+    /// New holder cap.
+    pub fn new(max_share: Decimal, reduction_rate: Decimal) -> Self {
+        HolderCap {
+            max_share,
+            reduction_rate,
+            start_interval: 0,
+            price_impact: Decimal::ONE,
+        }
+    }
+
+    /// Validate the holder cap.
+    ///
+    /// # Returns
+    ///
+    /// Result of the validation, or an error if a percentage is out of range or the price impact is negative.
+    pub fn validate(&self) -> Result<(), SimulationError> {
+        let percentage = |value: Decimal| (Decimal::ZERO..=Decimal::ONE_HUNDRED).contains(&value);
+        if !percentage(self.max_share) || !percentage(self.reduction_rate) {
+            return Err(SimulationError::InvalidData(
+                "holder cap: percentages must be between 0 and 100".to_string(),
+            ));
+        }
+        if self.price_impact < Decimal::ZERO {
+            return Err(SimulationError::InvalidData(
+                "holder cap: price impact must not be negative".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Get the largest balance a holder may keep.
+    ///
+    /// # Arguments
+    ///
+    /// * `circulating_supply` - Circulating supply of the token.
+    ///
+    /// # Returns
+    ///
+    /// Largest balance of a holder.
+    pub fn max_balance(&self, circulating_supply: Decimal) -> Decimal {
+        circulating_supply * self.max_share / Decimal::ONE_HUNDRED
+    }
+
+    /// Get the drop of the price caused by the forced sales.
+    ///
+    /// # Arguments
+    ///
+    /// * `sold` - Tokens sold by the holders above the cap.
+    /// * `circulating_supply` - Circulating supply of the token.
+    ///
+    /// # Returns
+    ///
+    /// Drop of the price, between 0 and 1.
+    pub fn price_drop(&self, sold: Decimal, circulating_supply: Decimal) -> Decimal {
+        (sold * self.price_impact)
+            .checked_div(circulating_supply)
+            .unwrap_or_default()
+            .clamp(Decimal::ZERO, Decimal::ONE)
+    }
+
+    /// Enforce the cap in an interval: the holders above it sell a share of their excess.
+    /// The cap applies to each member of a cohort.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the interval.
+    /// * `users` - Users of the simulation.
+    /// * `circulating_supply` - Circulating supply of the token.
+    /// * `precision` - Decimal precision of the balances.
+    ///
+    /// # Returns
+    ///
+    /// Forced sales of the interval.
+    pub(crate) fn enforce(
+        &self,
+        index: u64,
+        users: &mut UserStore,
+        circulating_supply: Decimal,
+        precision: u32,
+    ) -> ForcedSales {
+        let mut sales = ForcedSales::default();
+        if index < self.start_interval {
+            return sales;
+        }
+
+        let max_balance = self.max_balance(circulating_supply);
+        for (balance, weight) in users.balances.iter_mut().zip(&users.weights) {
+            let excess = *balance - max_balance * Decimal::from(*weight);
+            if excess <= Decimal::ZERO {
+                continue;
+            }
+
+            let amount = (excess * self.reduction_rate / Decimal::ONE_HUNDRED).round_dp(precision);
+            *balance -= amount;
+            sales.holders += weight;
+            sales.sold += amount;
+        }
+
+        sales
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::{Simulation, TokenAmount, User, ValuationModel};
+
+    use super::*;
+
+    #[test]
+    fn test_enforce() {
+        let mut users = UserStore::from(vec![User::new(Uuid::new_v4(), Decimal::new(10, 0)); 3]);
+        users.balances[0] = Decimal::new(100, 0);
+        users.weights[1] = 4;
+        users.balances[1] = Decimal::new(40, 0);
+        let cap = HolderCap {
+            start_interval: 1,
+            ..HolderCap::new(Decimal::new(5, 0), Decimal::new(50, 0))
+        };
+
+        assert_eq!(
+            cap.enforce(0, &mut users, Decimal::new(200, 0), 4),
+            ForcedSales::default()
+        );
+
+        // The holder of 100 sells half of the 90 above the cap of 10, the cohort keeps 10 per member
+        let sales = cap.enforce(1, &mut users, Decimal::new(200, 0), 4);
+        assert_eq!(sales.holders, 1);
+        assert_eq!(sales.sold, Decimal::new(45, 0));
+        assert_eq!(users.balances[0], Decimal::new(55, 0));
+        assert_eq!(users.balances[1], Decimal::new(40, 0));
+
+        assert_eq!(
+            cap.price_drop(sales.sold, Decimal::new(450, 0)),
+            Decimal::new(1, 1)
+        );
+        assert!(HolderCap::new(Decimal::new(101, 0), Decimal::ONE)
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_run_with_holder_cap() {
+        let run = |cap: Option<HolderCap>, model: ValuationModel| {
+            let token = Simulation::token_builder()
+                .name("Test Token".to_string())
+                .build()
+                .unwrap();
+            let mut options = Simulation::options_builder()
+                .valuation_model(model)
+                .total_users(10)
+                .duration(4)
+                .seed(2)
+                .build()
+                .unwrap();
+            options.holder_cap = cap;
+            let mut simulation = Simulation::builder()
+                .name("Test Simulation".to_string())
+                .token(token)
+                .options(options)
+                .build()
+                .unwrap();
+
+            simulation.run().unwrap();
+            simulation
+        };
+
+        let cap = HolderCap::new(Decimal::new(5, 0), Decimal::new(25, 0));
+        let usual = run(None, ValuationModel::RandomWalk);
+        let simulation = run(Some(cap.clone()), ValuationModel::RandomWalk);
+
+        // With 10 users, the average holder starts with 10% of the supply, above the cap of 5%
+        let reports = &simulation.interval_reports;
+        assert!(reports[0].forced_sales > TokenAmount::default());
+        assert!(reports[0].capped_holders > 0);
+        assert_eq!(
+            simulation.report.forced_sales,
+            reports.iter().map(|report| report.forced_sales).sum()
+        );
+        // The forced sales are sold on the market, pushing the price of the next interval down
+        assert!(reports[0].sell_volume >= reports[0].forced_sales);
+        assert!(reports[1].token_price < usual.interval_reports[1].token_price);
+        assert!(simulation.report.sell_volume > usual.report.sell_volume);
+
+        // The valuation models are recalculated every interval, and still carry the impact of the sales
+        let usual = run(None, ValuationModel::Linear);
+        let simulation = run(Some(cap), ValuationModel::Linear);
+        assert_eq!(
+            simulation.interval_reports[0].token_price,
+            usual.interval_reports[0].token_price
+        );
+        assert!(simulation.interval_reports[3].token_price < usual.interval_reports[3].token_price);
+    }
+}
@@ -0,0 +1,222 @@
+//! # Context module
+//!
+//! This module contains the interval context, the state of the simulation during an interval.
+//!
+//! The context is built once per interval, before the trades, and passed to the engine, the observers,
+//! and the metric plugins, so every extension point sees the same date, price, fees, and supply
+//! instead of recomputing them.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    FiatAmount, Percentage, RoundAs, Simulation, SimulationError, SimulationTransactionFee,
+    TokenAmount, ValueKind,
+};
+
+/// State of the simulation during an interval.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct IntervalContext {
+    /// ID of the simulation.
+    pub simulation_id: Uuid,
+
+    /// Index of the interval, starting at 0.
+    /// For the final report, this is the number of intervals.
+    pub index: u64,
+
+    /// Simulated date of the interval.
+    pub date: DateTime<Utc>,
+
+    /// Duration of the interval, in hours.
+    pub interval: u64,
+
+    /// Price of the token in the interval.
+    pub price: Decimal,
+
+    /// Fees charged on the trades of the interval.
+    pub fees: FeeSnapshot,
+
+    /// Supply of the token at the start of the trades.
+    pub supply: SupplySnapshot,
+}
+
+/// Fees charged on the trades of an interval.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct FeeSnapshot {
+    /// Fee charged on each trade, in percentage of the trade amount.
+    pub transaction_fee_percentage: Option<Percentage>,
+
+    /// Network fee charged on each trade, in tokens at the price of the interval.
+    pub network_fee: Decimal,
+
+    /// Network fees sampled for each on-chain trade, in tokens at the price of the interval.
+    /// Empty when the network fee does not follow a window of gas prices.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub network_fee_samples: Vec<Decimal>,
+}
+
+/// Supply of the token at a point of the simulation.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct SupplySnapshot {
+    /// Total supply of the token.
+    pub total_supply: TokenAmount,
+
+    /// Current supply of the token, in circulation.
+    pub current_supply: TokenAmount,
+}
+
+impl Simulation {
+    /// Build the context of an interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the interval.
+    /// * `date` - Simulated date of the interval.
+    /// * `interval` - Duration of the interval, in hours.
+    /// * `price` - Price of the token in the interval.
+    ///
+    /// # Returns
+    ///
+    /// Context of the interval, or an error if the network fee cannot be resolved.
+    pub(crate) fn interval_context(
+        &self,
+        index: u64,
+        date: DateTime<Utc>,
+        interval: u64,
+        price: Decimal,
+    ) -> Result<IntervalContext, SimulationError> {
+        // Network fee per transaction, converted from fiat to tokens at the current price
+        let in_tokens = |fee: FiatAmount| {
+            fee.in_tokens(FiatAmount(price))
+                .unwrap_or_default()
+                .round_as(&self.options, ValueKind::Balance)
+                .value()
+        };
+        let network_fee = match &self.options.transaction_fee {
+            Some(fee) => in_tokens(fee.per_transaction()?),
+            None => Decimal::default(),
+        };
+        let network_fee_samples = match &self.options.transaction_fee {
+            Some(fee @ SimulationTransactionFee::EthereumHistory(_)) => {
+                fee.samples()?.into_iter().map(in_tokens).collect()
+            }
+            _ => vec![],
+        };
+
+        Ok(IntervalContext {
+            simulation_id: self.id,
+            index,
+            date,
+            interval,
+            price,
+            fees: FeeSnapshot {
+                transaction_fee_percentage: self.options.transaction_fee_percentage,
+                network_fee,
+                network_fee_samples,
+            },
+            supply: SupplySnapshot {
+                total_supply: self.token.total_supply,
+                current_supply: self.token.current_supply,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::{SimulationObserver, SimulationReport};
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct ContextRecorder {
+        contexts: Mutex<Vec<IntervalContext>>,
+    }
+
+    impl SimulationObserver for Arc<ContextRecorder> {
+        fn on_interval(&self, context: &IntervalContext, _report: &SimulationReport) {
+            self.contexts.lock().unwrap().push(context.clone());
+        }
+    }
+
+    #[test]
+    fn test_observer_receives_interval_context() {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(10)
+            .duration(3)
+            .transaction_fee_percentage(0.5)
+            .build()
+            .unwrap();
+        let mut simulation = Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap();
+
+        let recorder = Arc::new(ContextRecorder::default());
+        simulation.add_observer(Arc::clone(&recorder));
+        simulation.run().unwrap();
+
+        let contexts = recorder.contexts.lock().unwrap();
+        assert_eq!(contexts.len(), 3);
+        for (index, (context, report)) in contexts
+            .iter()
+            .zip(&simulation.interval_reports)
+            .enumerate()
+        {
+            assert_eq!(context.simulation_id, simulation.id);
+            assert_eq!(context.index, index as u64);
+            assert_eq!(context.interval, 24);
+            assert_eq!(context.price, report.token_price.value());
+            assert_eq!(context.date.timestamp_millis(), report.interval);
+            assert_eq!(
+                context.fees.transaction_fee_percentage,
+                Some(Percentage(Decimal::new(5, 1)))
+            );
+        }
+        assert!(contexts[1].date > contexts[0].date);
+    }
+
+    #[test]
+    fn test_interval_context_with_unresolved_fee() {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(10)
+            .transaction_fee(crate::SimulationTransactionFee::Ethereum(
+                crate::EthereumFee {
+                    gas_used: 21_000,
+                    gas_price: None,
+                    ether_price: None,
+                },
+            ))
+            .build()
+            .unwrap();
+        let simulation = Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            simulation.interval_context(0, Utc::now(), 24, Decimal::ONE),
+            Err(SimulationError::UnresolvedTransactionFee)
+        );
+    }
+}
@@ -0,0 +1,299 @@
+//! # Controller module
+//!
+//! This module contains the supply controller, which adjusts the supply each interval with a PID loop towards a target price.
+//!
+//! The error of an interval is the relative gap of the price to the target.
+//! The controller burns tokens from the active users when the price is below the target, and emits tokens to them when it is above,
+//! in percentage of the circulating supply given by the proportional, integral and derivative terms of the error.
+//! The errors over the run show whether the designed loop settles, or oscillates around the target.
+
+use rust_decimal::{prelude::*, Decimal};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{SimulationError, UserState, UserStore};
+
+/// PID controller of the supply towards a target price.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct SupplyController {
+    /// Target price of the token.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub target_price: Decimal,
+
+    /// Proportional gain.
+    pub kp: f64,
+
+    /// Integral gain.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub ki: f64,
+
+    /// Derivative gain.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub kd: f64,
+
+    /// Largest adjustment of an interval, in percentage of the circulating supply.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub max_adjustment: Decimal,
+}
+
+/// State of the supply controller between intervals.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct ControllerState {
+    /// Sum of the errors of the previous intervals.
+    integral: f64,
+
+    /// Error of the previous interval.
+    previous_error: Option<f64>,
+}
+
+impl SupplyController {
+    /// Create a new proportional controller.
+    ///
+    /// # Arguments
+    ///
+    /// * `target_price` - Target price of the token.
+    /// * `kp` - Proportional gain.
+    /// * `max_adjustment` - Largest adjustment of an interval, in percentage of the circulating supply.
+    ///
+    /// # Returns
+    ///
+    /// New supply controller.
+    pub fn new(target_price: Decimal, kp: f64, max_adjustment: Decimal) -> Self {
+        SupplyController {
+            target_price,
+            kp,
+            ki: 0.0,
+            kd: 0.0,
+            max_adjustment,
+        }
+    }
+
+    /// Validate the supply controller.
+    ///
+    /// # Returns
+    ///
+    /// Result of the validation, or an error if the target price, a gain or the largest adjustment is out of range.
+    pub fn validate(&self) -> Result<(), SimulationError> {
+        if self.target_price <= Decimal::ZERO {
+            return Err(SimulationError::InvalidData(
+                "supply controller: target price must be positive".to_string(),
+            ));
+        }
+        if [self.kp, self.ki, self.kd]
+            .iter()
+            .any(|gain| !gain.is_finite())
+        {
+            return Err(SimulationError::InvalidData(
+                "supply controller: gains must be finite".to_string(),
+            ));
+        }
+        if !(Decimal::ZERO..=Decimal::ONE_HUNDRED).contains(&self.max_adjustment) {
+            return Err(SimulationError::InvalidData(
+                "supply controller: largest adjustment must be between 0 and 100".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Get the error of a price, its relative gap to the target.
+    ///
+    /// # Arguments
+    ///
+    /// * `price` - Price of the token.
+    ///
+    /// # Returns
+    ///
+    /// Error of the price, positive below the target.
+    pub fn error(&self, price: Decimal) -> Decimal {
+        ((self.target_price - price) / self.target_price).normalize()
+    }
+
+    /// Get the output of the controller for an interval, and update its state.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - State of the controller.
+    /// * `error` - Error of the interval.
+    ///
+    /// # Returns
+    ///
+    /// Adjustment in percentage of the circulating supply, positive to burn, clamped to the largest adjustment.
+    pub(crate) fn output(&self, state: &mut ControllerState, error: f64) -> Decimal {
+        state.integral += error;
+        let derivative = state
+            .previous_error
+            .map_or(0.0, |previous| error - previous);
+        state.previous_error = Some(error);
+
+        let output = self.kp * error + self.ki * state.integral + self.kd * derivative;
+        let max = self.max_adjustment.to_f64().unwrap_or_default();
+
+        Decimal::from_f64(output.clamp(-max, max)).unwrap_or_default()
+    }
+
+    /// Adjust the supply of an interval: burn from the active users below the target price, emit to them above.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - State of the controller.
+    /// * `price` - Price of the token in the interval.
+    /// * `users` - Users of the simulation.
+    /// * `circulating_supply` - Circulating supply of the token.
+    /// * `mintable` - Tokens left to mint within the total supply.
+    /// * `precision` - Decimal precision of the balances.
+    ///
+    /// # Returns
+    ///
+    /// Tokens emitted, negative when burned, or an error if the error cannot be converted.
+    pub(crate) fn adjust(
+        &self,
+        state: &mut ControllerState,
+        price: Decimal,
+        users: &mut UserStore,
+        circulating_supply: Decimal,
+        mintable: Decimal,
+        precision: u32,
+    ) -> Result<Decimal, SimulationError> {
+        let error = self
+            .error(price)
+            .to_f64()
+            .ok_or(SimulationError::InvalidDecimal)?;
+        let output = self.output(state, error);
+        let amount = circulating_supply * output.abs() / Decimal::ONE_HUNDRED;
+
+        let held: Decimal = (0..users.len())
+            .filter(|&index| users.is_active(index))
+            .map(|index| users.balances[index])
+            .sum();
+        let rate = match output.is_sign_positive() {
+            true => amount.min(held),
+            false => amount.min(mintable.max(Decimal::ZERO)),
+        }
+        .checked_div(held)
+        .unwrap_or_default();
+
+        // Burns and emissions are split by the share of the balances of the active users
+        let mut adjusted = Decimal::default();
+        for (balance, state) in users.balances.iter_mut().zip(&users.states) {
+            if *state != UserState::Active {
+                continue;
+            }
+
+            let change = (*balance * rate).round_dp(precision);
+            match output.is_sign_positive() {
+                true => *balance -= change,
+                false => *balance += change,
+            }
+            adjusted += change;
+        }
+
+        Ok(match output.is_sign_positive() {
+            true => -adjusted,
+            false => adjusted,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::{Simulation, TokenAmount, User};
+
+    use super::*;
+
+    #[test]
+    fn test_output() {
+        let controller = SupplyController {
+            ki: 0.5,
+            kd: 1.0,
+            ..SupplyController::new(Decimal::ONE, 2.0, Decimal::new(5, 0))
+        };
+        let mut state = ControllerState::default();
+
+        assert_eq!(controller.error(Decimal::new(8, 1)), Decimal::new(2, 1));
+        assert_eq!(controller.output(&mut state, 1.0), Decimal::new(25, 1));
+        // The derivative term damps the loop when the error falls
+        assert_eq!(controller.output(&mut state, 0.5), Decimal::new(125, 2));
+        // The output is clamped to the largest adjustment
+        assert_eq!(controller.output(&mut state, -4.0), Decimal::new(-5, 0));
+    }
+
+    #[test]
+    fn test_adjust() {
+        let mut users = UserStore::from(vec![User::new(Uuid::new_v4(), Decimal::new(100, 0)); 2]);
+        let controller = SupplyController::new(Decimal::ONE, 10.0, Decimal::new(5, 0));
+        let mut state = ControllerState::default();
+
+        // Below the target, 1% of the circulating supply is burned
+        let burned = controller
+            .adjust(
+                &mut state,
+                Decimal::new(9, 1),
+                &mut users,
+                Decimal::new(1_000, 0),
+                Decimal::new(1_000, 0),
+                4,
+            )
+            .unwrap();
+        assert_eq!(burned, Decimal::new(-10, 0));
+        assert_eq!(users.balances[0], Decimal::new(95, 0));
+
+        // Above the target, the emission is capped by the mintable supply
+        let emitted = controller
+            .adjust(
+                &mut state,
+                Decimal::new(2, 0),
+                &mut users,
+                Decimal::new(1_000, 0),
+                Decimal::new(19, 0),
+                4,
+            )
+            .unwrap();
+        assert_eq!(emitted, Decimal::new(19, 0));
+        assert_eq!(users.balances[1], Decimal::new(1045, 1));
+    }
+
+    #[test]
+    fn test_run_with_supply_controller() {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .initial_price(1.0)
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(20)
+            .duration(5)
+            .seed(9)
+            .supply_controller(SupplyController {
+                ki: 0.1,
+                ..SupplyController::new(Decimal::new(2, 0), 5.0, Decimal::new(2, 0))
+            })
+            .build()
+            .unwrap();
+        let mut simulation = Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap();
+
+        simulation.run().unwrap();
+
+        let reports = &simulation.interval_reports;
+        assert!(reports
+            .iter()
+            .all(|report| report.controller_error.is_some()));
+        // The price stays below the target, so the controller only burns
+        assert!(reports
+            .iter()
+            .all(|report| report.supply_adjustment <= TokenAmount::default()));
+        assert!(simulation.report.total_burned >= -simulation.report.supply_adjustment);
+        assert_eq!(
+            simulation.report.supply_adjustment,
+            reports.iter().map(|report| report.supply_adjustment).sum()
+        );
+    }
+}
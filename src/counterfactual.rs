@@ -0,0 +1,286 @@
+//! # Counterfactual module
+//!
+//! This module contains the counterfactual analysis, which reruns a completed simulation with one mechanism disabled.
+//!
+//! The rerun starts from the token at the start of the run and draws from the same seed,
+//! so the difference of each metric between the run and its rerun is the contribution of the mechanism,
+//! e.g. the contribution of the burn to the final price.
+//! Disabling a mechanism that draws random numbers, such as MEV, shifts the draws that follow it,
+//! so its contribution also includes a change of the random path.
+
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    EnsembleMetric, Simulation, SimulationComparison, SimulationError, SimulationOptions,
+    SimulationStatus, Token,
+};
+
+/// Mechanism of the tokenomics that can be disabled in a counterfactual run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Mechanism {
+    /// Burn of a part of each trade.
+    Burn,
+
+    /// Mint of new tokens on each trade.
+    Inflation,
+
+    /// Unlock schedule, including the vesting of the allocations.
+    Unlocks,
+
+    /// Airdrop to the initial users.
+    Airdrop,
+
+    /// Buy and sell taxes.
+    Taxes,
+
+    /// Emission to the contributors.
+    ContributorEmission,
+
+    /// Block rewards of the halving schedule.
+    BlockRewards,
+
+    /// Protocol and network fees of the trades.
+    TransactionFees,
+
+    /// Sandwich attacks of the MEV agent.
+    Mev,
+}
+
+/// Contribution of a mechanism to the metrics of a simulation.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Counterfactual {
+    /// Disabled mechanism.
+    pub mechanism: Mechanism,
+
+    /// Comparison of the rerun without the mechanism, as the baseline, with the simulation, as the variant.
+    /// The delta of each metric is the contribution of the mechanism.
+    pub comparison: SimulationComparison,
+}
+
+impl Mechanism {
+    /// All mechanisms, in the order of the enum.
+    pub const ALL: [Mechanism; 9] = [
+        Mechanism::Burn,
+        Mechanism::Inflation,
+        Mechanism::Unlocks,
+        Mechanism::Airdrop,
+        Mechanism::Taxes,
+        Mechanism::ContributorEmission,
+        Mechanism::BlockRewards,
+        Mechanism::TransactionFees,
+        Mechanism::Mev,
+    ];
+
+    /// Get the name of the mechanism.
+    ///
+    /// # Returns
+    ///
+    /// Name of the mechanism, in snake case.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mechanism::Burn => "burn",
+            Mechanism::Inflation => "inflation",
+            Mechanism::Unlocks => "unlocks",
+            Mechanism::Airdrop => "airdrop",
+            Mechanism::Taxes => "taxes",
+            Mechanism::ContributorEmission => "contributor_emission",
+            Mechanism::BlockRewards => "block_rewards",
+            Mechanism::TransactionFees => "transaction_fees",
+            Mechanism::Mev => "mev",
+        }
+    }
+
+    /// Disable the mechanism in a token and options.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - Token to change.
+    /// * `options` - Options to change.
+    pub fn disable(&self, token: &mut Token, options: &mut SimulationOptions) {
+        match self {
+            Mechanism::Burn => token.burn_rate = None,
+            Mechanism::Inflation => token.inflation_rate = None,
+            Mechanism::Unlocks => token.unlock_schedule = None,
+            Mechanism::Airdrop => token.airdrop_percentage = None,
+            Mechanism::Taxes => {
+                token.buy_tax = None;
+                token.sell_tax = None;
+            }
+            Mechanism::ContributorEmission => token.contributor_emission = None,
+            Mechanism::BlockRewards => token.halving_schedule = None,
+            Mechanism::TransactionFees => {
+                options.transaction_fee_percentage = None;
+                options.transaction_fee = None;
+            }
+            Mechanism::Mev => options.mev = None,
+        }
+    }
+}
+
+impl Counterfactual {
+    /// Get the contribution of the mechanism to a metric.
+    ///
+    /// # Arguments
+    ///
+    /// * `metric` - Metric to get.
+    ///
+    /// # Returns
+    ///
+    /// Value of the metric in the simulation minus its value without the mechanism, if the metric was compared.
+    pub fn contribution(&self, metric: EnsembleMetric) -> Option<Decimal> {
+        self.comparison
+            .deltas
+            .iter()
+            .find(|delta| delta.metric == metric)
+            .map(|delta| delta.delta)
+    }
+}
+
+impl Simulation {
+    /// Rerun the completed simulation with one mechanism disabled, and compare the metrics.
+    /// Observers and metric plugins are not attached to the rerun.
+    ///
+    /// # Arguments
+    ///
+    /// * `mechanism` - Mechanism to disable.
+    /// * `metrics` - Metrics to compare.
+    ///
+    /// # Returns
+    ///
+    /// Contribution of the mechanism to each metric, or an error if the simulation is not completed,
+    /// has no seed, or the rerun fails.
+    pub fn counterfactual(
+        &self,
+        mechanism: Mechanism,
+        metrics: &[EnsembleMetric],
+    ) -> Result<Counterfactual, SimulationError> {
+        if self.options.seed.is_none() {
+            return Err(SimulationError::NotReproducible(
+                "the simulation has no seed".to_string(),
+            ));
+        }
+        let (SimulationStatus::Completed, Some(token)) = (&self.status, &self.initial_token) else {
+            return Err(SimulationError::NotReproducible(
+                "the simulation is not completed".to_string(),
+            ));
+        };
+
+        let mut token = token.clone();
+        let mut options = self.options.clone();
+        mechanism.disable(&mut token, &mut options);
+
+        let mut without = Simulation::builder()
+            .name(format!("without {}", mechanism.name()))
+            .token(token)
+            .options(options)
+            .build()?;
+        without.run()?;
+
+        Ok(Counterfactual {
+            mechanism,
+            comparison: without.compare(self, metrics),
+        })
+    }
+
+    /// Rerun the completed simulation once per mechanism, each time with that mechanism disabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `mechanisms` - Mechanisms to disable, one at a time.
+    /// * `metrics` - Metrics to compare.
+    ///
+    /// # Returns
+    ///
+    /// Contribution of each mechanism, in the order of the mechanisms, or the first error.
+    pub fn counterfactuals(
+        &self,
+        mechanisms: &[Mechanism],
+        metrics: &[EnsembleMetric],
+    ) -> Result<Vec<Counterfactual>, SimulationError> {
+        mechanisms
+            .iter()
+            .map(|mechanism| self.counterfactual(*mechanism, metrics))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup(seed: Option<u64>) -> Simulation {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .burn_rate(0.05)
+            .build()
+            .unwrap();
+        let mut options = Simulation::options_builder()
+            .total_users(20)
+            .duration(5)
+            .build()
+            .unwrap();
+        options.seed = seed;
+
+        Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_counterfactual_burn() {
+        let mut simulation = setup(Some(42));
+        simulation.run().unwrap();
+
+        let metrics = [EnsembleMetric::BurnRate, EnsembleMetric::TokenPrice];
+        let burn = simulation
+            .counterfactual(Mechanism::Burn, &metrics)
+            .unwrap();
+
+        assert_eq!(burn.comparison.baseline, "without burn");
+        assert_eq!(burn.comparison.deltas[0].baseline, Decimal::default());
+        assert!(burn.contribution(EnsembleMetric::BurnRate).unwrap() > Decimal::default());
+        assert_eq!(burn.contribution(EnsembleMetric::Trades), None);
+
+        // A mechanism that is not configured contributes nothing
+        let taxes = simulation
+            .counterfactual(Mechanism::Taxes, &metrics)
+            .unwrap();
+        assert!(taxes
+            .comparison
+            .deltas
+            .iter()
+            .all(|delta| delta.delta.is_zero()));
+
+        assert_eq!(
+            simulation
+                .counterfactuals(&Mechanism::ALL, &metrics)
+                .unwrap()
+                .len(),
+            Mechanism::ALL.len()
+        );
+    }
+
+    #[test]
+    fn test_counterfactual_not_reproducible() {
+        let mut simulation = setup(None);
+        simulation.run().unwrap();
+        assert!(matches!(
+            simulation.counterfactual(Mechanism::Burn, &[EnsembleMetric::TokenPrice]),
+            Err(SimulationError::NotReproducible(_))
+        ));
+
+        let pending = setup(Some(42));
+        assert!(matches!(
+            pending.counterfactual(Mechanism::Burn, &[EnsembleMetric::TokenPrice]),
+            Err(SimulationError::NotReproducible(_))
+        ));
+    }
+}
@@ -0,0 +1,187 @@
+//! # Demand module
+//!
+//! This module contains the demand series, an exogenous multiplier of the trading demand of each interval.
+//!
+//! The series injects known future events into an otherwise stochastic simulation,
+//! e.g. a conference or a product launch doubling the demand of an interval.
+//! The multiplier scales the probability of each user to trade, capped at certain trading.
+//! Intervals after the end of the series have a multiplier of 1.
+
+use std::str::FromStr;
+
+use rust_decimal::{prelude::*, Decimal};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::SimulationError;
+
+/// Multiplier of the trading demand of each interval, oldest first.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct DemandSeries {
+    /// Multiplier of each interval, 1 for the usual demand.
+    pub multipliers: Vec<Decimal>,
+}
+
+impl DemandSeries {
+    /// Create a new demand series.
+    ///
+    /// # Arguments
+    ///
+    /// * `multipliers` - Multiplier of each interval, oldest first.
+    ///
+    /// # Returns
+    ///
+    /// New demand series, or an error if a multiplier is negative.
+    pub fn new(multipliers: Vec<Decimal>) -> Result<Self, SimulationError> {
+        if let Some(index) = multipliers.iter().position(|m| m.is_sign_negative()) {
+            return Err(SimulationError::InvalidData(format!(
+                "demand series: negative multiplier at interval {index}"
+            )));
+        }
+
+        Ok(DemandSeries { multipliers })
+    }
+
+    /// Parse a demand series from CSV, one interval per line, the multiplier in the last column,
+    /// e.g. `date,multiplier`. A first line that is not a number is read as a header.
+    ///
+    /// # Arguments
+    ///
+    /// * `csv` - CSV of the series.
+    ///
+    /// # Returns
+    ///
+    /// Demand series, or an error if a line has no valid multiplier.
+    pub fn from_csv(csv: &str) -> Result<Self, SimulationError> {
+        let mut multipliers = vec![];
+
+        for (index, line) in csv.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let value = line.rsplit(',').next().unwrap_or_default().trim();
+            match (Decimal::from_str(value), index) {
+                (Ok(multiplier), _) if !multiplier.is_sign_negative() => {
+                    multipliers.push(multiplier)
+                }
+                (Err(_), 0) => continue,
+                _ => {
+                    return Err(SimulationError::InvalidData(format!(
+                        "demand series: line {}: expected a non-negative multiplier, got {value}",
+                        index + 1
+                    )))
+                }
+            }
+        }
+
+        Ok(DemandSeries { multipliers })
+    }
+
+    /// Get the multiplier of an interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the interval.
+    ///
+    /// # Returns
+    ///
+    /// Multiplier of the interval, 1 after the end of the series.
+    pub fn multiplier_at(&self, index: u64) -> Decimal {
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| self.multipliers.get(index))
+            .copied()
+            .unwrap_or(Decimal::ONE)
+    }
+
+    /// Scale a trade probability with the multiplier of an interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `probability` - Usual trade probability.
+    /// * `index` - Index of the interval.
+    ///
+    /// # Returns
+    ///
+    /// Scaled trade probability, between 0 and 1.
+    pub(crate) fn scale(&self, probability: f64, index: u64) -> f64 {
+        let multiplier = self.multiplier_at(index).to_f64().unwrap_or(1.0);
+
+        (probability * multiplier).clamp(0.0, 1.0)
+    }
+}
+
+impl FromStr for DemandSeries {
+    type Err = SimulationError;
+
+    fn from_str(csv: &str) -> Result<Self, Self::Err> {
+        DemandSeries::from_csv(csv)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Simulation;
+
+    use super::*;
+
+    #[test]
+    fn test_demand_series() {
+        let series = DemandSeries::from_csv(
+            "date,multiplier\n2025-01-01,1\n2025-01-02,2.5\n\n2025-01-03,0\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            series.multipliers,
+            vec![Decimal::ONE, Decimal::new(25, 1), Decimal::ZERO]
+        );
+        assert_eq!(series.multiplier_at(1), Decimal::new(25, 1));
+        assert_eq!(series.multiplier_at(3), Decimal::ONE);
+        assert_eq!(series.scale(0.5, 1), 1.0);
+        assert_eq!(series.scale(0.5, 2), 0.0);
+
+        assert!(DemandSeries::from_csv("1\nx\n").is_err());
+        assert!(DemandSeries::from_csv("-1\n").is_err());
+        assert!(DemandSeries::new(vec![Decimal::NEGATIVE_ONE]).is_err());
+    }
+
+    #[test]
+    fn test_run_with_demand_series() {
+        let run = |series: Option<DemandSeries>| {
+            let token = Simulation::token_builder()
+                .name("Test Token".to_string())
+                .build()
+                .unwrap();
+            let mut options = Simulation::options_builder()
+                .total_users(50)
+                .duration(3)
+                .seed(7)
+                .build()
+                .unwrap();
+            options.demand_series = series;
+            let mut simulation = Simulation::builder()
+                .name("Test Simulation".to_string())
+                .token(token)
+                .options(options)
+                .build()
+                .unwrap();
+
+            simulation.run().unwrap();
+            simulation.interval_reports
+        };
+
+        let usual = run(None);
+        let reports = run(Some(
+            DemandSeries::new(vec![Decimal::ONE, Decimal::ZERO, Decimal::new(10, 0)]).unwrap(),
+        ));
+
+        // A multiplier of 1 keeps the interval as is, no demand stops the trading, a spike increases it
+        assert_eq!(reports[0].successful_trades, usual[0].successful_trades);
+        assert_eq!(reports[1].successful_trades, 0);
+        assert!(reports[2].successful_trades > usual[2].successful_trades);
+    }
+}
@@ -0,0 +1,248 @@
+//! # Distribution module
+//!
+//! This module contains the analysis of the token distribution among the holders.
+//!
+//! The Lorenz curve plots the share of the balances held by the poorest share of the users,
+//! and the Gini coefficient summarizes it: 0 when every user holds the same balance, close to 1 when one user holds everything.
+//! The members of a cohort are counted as holding an equal part of the cohort balance.
+
+use std::fmt::Write;
+
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::UserStore;
+
+/// Number of segments of the Lorenz curve in the reports.
+pub const LORENZ_RESOLUTION: usize = 100;
+
+/// Point of the Lorenz curve.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct LorenzPoint {
+    /// Share of the users, poorest first, between 0 and 1.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub population_share: Decimal,
+
+    /// Share of the balances held by these users, between 0 and 1.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub balance_share: Decimal,
+}
+
+/// Analysis of the token distribution among the users.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct DistributionAnalysis {
+    /// Points of the Lorenz curve, at evenly spaced population shares from 0 to 1.
+    pub lorenz_curve: Vec<LorenzPoint>,
+
+    /// Gini coefficient of the balances, between 0 and 1.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub gini: Decimal,
+}
+
+impl DistributionAnalysis {
+    /// Analyze the distribution of the balances of the users.
+    ///
+    /// # Arguments
+    ///
+    /// * `users` - A list of users.
+    /// * `resolution` - Number of segments of the Lorenz curve.
+    /// * `decimals` - Number of decimal places to round to.
+    ///
+    /// # Returns
+    ///
+    /// Analysis of the distribution, empty if the users hold no balance.
+    pub fn new(users: &UserStore, resolution: usize, decimals: u32) -> Self {
+        let population = Decimal::from(users.count());
+        let total: Decimal = users
+            .balances
+            .iter()
+            .map(|balance| (*balance).max(Decimal::default()))
+            .sum();
+        if population.is_zero() || total.is_zero() || resolution == 0 {
+            return DistributionAnalysis::default();
+        }
+
+        let mut agents: Vec<(Decimal, Decimal)> = users
+            .weights
+            .iter()
+            .zip(&users.balances)
+            .filter(|(weight, _)| **weight > 0)
+            .map(|(weight, balance)| {
+                (
+                    Decimal::from(*weight) / population,
+                    (*balance).max(Decimal::default()) / total,
+                )
+            })
+            .collect();
+        // Sort by the balance of each member, the poorest first
+        agents.sort_by(|(a_weight, a_share), (b_weight, b_share)| {
+            (*a_share * *b_weight).cmp(&(*b_share * *a_weight))
+        });
+
+        // Exact curve at the boundaries of the agents, linear within an agent
+        let mut boundaries = Vec::with_capacity(agents.len() + 1);
+        let mut area = Decimal::default();
+        let (mut population_share, mut balance_share) = (Decimal::default(), Decimal::default());
+        boundaries.push((population_share, balance_share));
+        for (weight, share) in &agents {
+            area += *weight * (balance_share + balance_share + *share) / Decimal::TWO;
+            population_share += *weight;
+            balance_share += *share;
+            boundaries.push((population_share, balance_share));
+        }
+
+        let mut segment = 1;
+        let lorenz_curve = (0..=resolution)
+            .map(|step| {
+                let x = Decimal::from(step) / Decimal::from(resolution);
+                while segment < boundaries.len() - 1 && boundaries[segment].0 < x {
+                    segment += 1;
+                }
+
+                let (x0, y0) = boundaries[segment - 1];
+                let (x1, y1) = boundaries[segment];
+                let y = match x1 - x0 {
+                    width if width.is_zero() => y1,
+                    width => y0 + (y1 - y0) * (x - x0) / width,
+                };
+
+                LorenzPoint {
+                    population_share: x.round_dp(decimals),
+                    balance_share: y.min(Decimal::ONE).round_dp(decimals),
+                }
+            })
+            .collect();
+
+        DistributionAnalysis {
+            lorenz_curve,
+            gini: (Decimal::ONE - Decimal::TWO * area)
+                .max(Decimal::default())
+                .round_dp(decimals),
+        }
+    }
+
+    /// Get the share of the balances held by the richest share of the users.
+    ///
+    /// # Arguments
+    ///
+    /// * `population_share` - Share of the richest users, between 0 and 1, e.g. 0.1 for the top 10%.
+    ///
+    /// # Returns
+    ///
+    /// Share of the balances, read from the closest point of the curve.
+    pub fn top_share(&self, population_share: Decimal) -> Decimal {
+        let x = Decimal::ONE - population_share;
+
+        self.lorenz_curve
+            .iter()
+            .min_by_key(|point| (point.population_share - x).abs())
+            .map(|point| Decimal::ONE - point.balance_share)
+            .unwrap_or_default()
+    }
+
+    /// Export the Lorenz curve as CSV, one point per line.
+    ///
+    /// # Returns
+    ///
+    /// CSV with a `population_share,balance_share` header.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("population_share,balance_share\n");
+
+        for point in &self.lorenz_curve {
+            let _ = writeln!(csv, "{},{}", point.population_share, point.balance_share);
+        }
+
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::{Simulation, User};
+
+    use super::*;
+
+    #[test]
+    fn test_equal_distribution() {
+        let users: UserStore = (0..4)
+            .map(|_| User::new(Uuid::new_v4(), Decimal::new(10, 0)))
+            .collect();
+
+        let analysis = DistributionAnalysis::new(&users, 4, 4);
+
+        assert_eq!(analysis.gini, Decimal::default());
+        assert_eq!(analysis.lorenz_curve.len(), 5);
+        assert_eq!(analysis.lorenz_curve[2].balance_share, Decimal::new(5, 1));
+        assert_eq!(analysis.top_share(Decimal::new(25, 2)), Decimal::new(25, 2));
+    }
+
+    #[test]
+    fn test_unequal_distribution() {
+        let mut users: UserStore = (0..3)
+            .map(|_| User::new(Uuid::new_v4(), Decimal::default()))
+            .collect();
+        users.push(User::new(Uuid::new_v4(), Decimal::new(100, 0)));
+
+        let analysis = DistributionAnalysis::new(&users, 4, 4);
+
+        assert_eq!(analysis.gini, Decimal::new(75, 2));
+        assert_eq!(analysis.lorenz_curve[3].balance_share, Decimal::default());
+        assert_eq!(analysis.lorenz_curve[4].balance_share, Decimal::ONE);
+        assert_eq!(analysis.to_csv().lines().last().unwrap(), "1,1");
+    }
+
+    #[test]
+    fn test_cohort_matches_agents() {
+        let mut cohort = User::new(Uuid::new_v4(), Decimal::new(30, 0));
+        cohort.weight = 3;
+        let cohorts = UserStore::from(vec![cohort, User::new(Uuid::new_v4(), Decimal::new(70, 0))]);
+        let agents: UserStore = [10, 10, 10, 70]
+            .into_iter()
+            .map(|balance| User::new(Uuid::new_v4(), Decimal::new(balance, 0)))
+            .collect();
+
+        assert_eq!(
+            DistributionAnalysis::new(&cohorts, 8, 4),
+            DistributionAnalysis::new(&agents, 8, 4)
+        );
+        assert_eq!(
+            DistributionAnalysis::new(&UserStore::default(), 8, 4),
+            DistributionAnalysis::default()
+        );
+    }
+
+    #[test]
+    fn test_run_reports_distribution() {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(50)
+            .duration(3)
+            .distribution_per_interval(true)
+            .build()
+            .unwrap();
+        let mut simulation = Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap();
+
+        simulation.run().unwrap();
+
+        let distribution = simulation.report.distribution.as_ref().unwrap();
+        assert_eq!(distribution.lorenz_curve.len(), LORENZ_RESOLUTION + 1);
+        assert!(distribution.gini > Decimal::default());
+        assert!(simulation
+            .interval_reports
+            .iter()
+            .all(|report| report.distribution.is_some()));
+    }
+}
@@ -0,0 +1,350 @@
+//! # Dual token module
+//!
+//! This module contains the dual token economy, a ready-made setup of a utility token and a governance token.
+//!
+//! The users earn and spend the utility token, e.g. in a game economy, and convert part of it into the governance token.
+//! Converted utility tokens are burned, and the governance tokens are minted within the total supply.
+//! The users stake a share of their governance tokens, and the stake of the active users votes.
+//!
+//! Each token runs in its own simulation, stepped in lockstep: the conversion and the staking happen before each interval,
+//! the users of the two simulations being paired by their position.
+//! The economy reports both tokens together, with the conversion and the staking, in each interval and over the whole run.
+
+use rust_decimal::{prelude::*, Decimal};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    GameEconomy, GameSink, Simulation, SimulationError, SimulationOptions, SimulationReport, Token,
+    TokenAmount, UserState, ValueKind,
+};
+
+/// Mechanism converting utility tokens into governance tokens.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ConversionMechanism {
+    /// Fixed amount of governance tokens per utility token.
+    FixedRate(#[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))] Decimal),
+
+    /// Ratio of the prices of the two tokens, at the end of the last interval.
+    MarketPrice,
+}
+
+/// Conversion of utility tokens into governance tokens.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct TokenConversion {
+    /// Mechanism of the conversion.
+    pub mechanism: ConversionMechanism,
+
+    /// Share of the utility tokens converted by an active user before each interval, between 0 and 1.
+    pub share: f64,
+
+    /// Fee of the conversion, in percentage of the governance tokens.
+    #[cfg_attr(feature = "serde", serde(default, with = "rust_decimal::serde::float"))]
+    pub fee: Decimal,
+}
+
+/// Economy of a utility token and a governance token.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct DualTokenEconomy {
+    /// Name of the economy.
+    pub name: String,
+
+    /// Utility token, earned and spent by the users.
+    pub utility: Token,
+
+    /// Input parameters of the utility token simulation.
+    pub utility_options: SimulationOptions,
+
+    /// Governance token, staked and voted by the users.
+    pub governance: Token,
+
+    /// Input parameters of the governance token simulation.
+    pub governance_options: SimulationOptions,
+
+    /// Conversion of utility tokens into governance tokens.
+    pub conversion: TokenConversion,
+
+    /// Share of the governance tokens of each user kept staked, between 0 and 1.
+    pub stake_share: f64,
+}
+
+/// Report of a dual token economy.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct DualTokenReport {
+    /// Report of the utility token.
+    pub utility: SimulationReport,
+
+    /// Report of the governance token.
+    pub governance: SimulationReport,
+
+    /// Utility tokens converted, and burned.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub converted: Decimal,
+
+    /// Governance tokens minted by the conversion, after the fee.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub conversion_output: Decimal,
+
+    /// Governance tokens staked.
+    /// In the final report, the stake at the start of the last interval.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub staked: Decimal,
+
+    /// Governance tokens staked by the active users, voting.
+    /// In the final report, the votes of the last interval.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub votes: Decimal,
+}
+
+/// Results of a dual token economy.
+#[derive(Debug)]
+pub struct DualTokenRun {
+    /// Completed simulation of the utility token.
+    pub utility: Simulation,
+
+    /// Completed simulation of the governance token.
+    pub governance: Simulation,
+
+    /// Report of each interval.
+    pub interval_reports: Vec<DualTokenReport>,
+
+    /// Final report over the whole run.
+    pub report: DualTokenReport,
+}
+
+impl DualTokenEconomy {
+    /// Create a ready-made dual token economy.
+    /// The utility token is earned in gameplay and spent on upgrades, and converted into the governance token at a fixed rate of 100 to 1.
+    /// Half of the governance tokens are staked.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the economy.
+    /// * `total_users` - Number of users.
+    /// * `duration` - Number of intervals.
+    ///
+    /// # Returns
+    ///
+    /// New dual token economy, or an error if the tokens or the options cannot be built.
+    pub fn template(name: &str, total_users: u64, duration: u64) -> Result<Self, SimulationError> {
+        let utility = Simulation::token_builder()
+            .name(format!("{name} Utility"))
+            .symbol("UTIL".to_string())
+            .total_supply(1_000_000_000)
+            .initial_supply_percentage(10.0)
+            .initial_price(0.01)
+            .build()?;
+        let governance = Simulation::token_builder()
+            .name(format!("{name} Governance"))
+            .symbol("GOV".to_string())
+            .total_supply(100_000_000)
+            .initial_supply_percentage(50.0)
+            .initial_price(1.0)
+            .build()?;
+        let options = Simulation::options_builder()
+            .total_users(total_users)
+            .duration(duration)
+            .seed(1)
+            .build()?;
+
+        Ok(DualTokenEconomy {
+            name: name.to_string(),
+            utility,
+            utility_options: SimulationOptions {
+                game_economy: Some(GameEconomy::new(Decimal::new(100, 0)).sink(GameSink::new(
+                    "upgrades",
+                    Decimal::new(25, 0),
+                    2.0,
+                ))),
+                ..options.clone()
+            },
+            governance,
+            governance_options: options,
+            conversion: TokenConversion {
+                mechanism: ConversionMechanism::FixedRate(Decimal::new(1, 2)),
+                share: 0.1,
+                fee: Decimal::default(),
+            },
+            stake_share: 0.5,
+        })
+    }
+
+    /// Run the two token simulations in lockstep, converting and staking before each interval.
+    ///
+    /// # Returns
+    ///
+    /// Results of the economy, or an error if a simulation fails.
+    pub fn run(&self) -> Result<DualTokenRun, SimulationError> {
+        let build = |token: &Token, options: &SimulationOptions, kind: &str| {
+            let mut options = options.clone();
+            options.duration = self.utility_options.duration;
+
+            Simulation::builder()
+                .name(format!("{} - {kind}", self.name))
+                .token(token.clone())
+                .options(options)
+                .build()
+        };
+        let mut utility = build(&self.utility, &self.utility_options, "utility")?;
+        let mut governance = build(&self.governance, &self.governance_options, "governance")?;
+
+        let mut staked: Vec<Decimal> = vec![];
+        let mut interval_reports = vec![];
+        {
+            let mut utility_stepper = utility.stepper()?;
+            let mut governance_stepper = governance.stepper()?;
+
+            while !utility_stepper.is_finished() && !governance_stepper.is_finished() {
+                let mut report = DualTokenReport::default();
+                if let (
+                    Some((utility_simulation, utility_state)),
+                    Some((governance_simulation, governance_state)),
+                ) = (utility_stepper.parts_mut(), governance_stepper.parts_mut())
+                {
+                    let precision = governance_simulation.options.precision(ValueKind::Balance);
+                    let rate = match self.conversion.mechanism {
+                        ConversionMechanism::FixedRate(rate) => rate,
+                        ConversionMechanism::MarketPrice => utility_state
+                            .price
+                            .checked_div(governance_state.price)
+                            .unwrap_or_default(),
+                    } * (Decimal::ONE_HUNDRED - self.conversion.fee)
+                        / Decimal::ONE_HUNDRED;
+                    let share = Decimal::from_f64(self.conversion.share.clamp(0.0, 1.0))
+                        .ok_or(SimulationError::InvalidDecimal)?;
+                    let stake_share = Decimal::from_f64(self.stake_share.clamp(0.0, 1.0))
+                        .ok_or(SimulationError::InvalidDecimal)?;
+                    let mut mintable = (governance_simulation.token.total_supply
+                        - governance_simulation.token.current_supply)
+                        .value()
+                        .max(Decimal::ZERO);
+
+                    let pairs = utility_state.users.len().min(governance_state.users.len());
+                    staked.resize(governance_state.users.len(), Decimal::ZERO);
+                    for position in 0..pairs {
+                        let users = &mut utility_state.users;
+
+                        // Active users convert a share of their utility tokens, within the mintable supply
+                        if users.is_active(position) && rate > Decimal::ZERO {
+                            let balance = &mut users.balances[position];
+                            let output =
+                                (*balance * share * rate).round_dp(precision).min(mintable);
+                            let converted = (output / rate).round_dp(precision).min(*balance);
+                            *balance -= converted;
+                            governance_state.users.balances[position] += output;
+                            mintable -= output;
+                            report.converted += converted;
+                            report.conversion_output += output;
+                        }
+                    }
+
+                    // Each user keeps a share of the governance tokens staked, the stake of the active users votes
+                    let holders = &mut governance_state.users;
+                    for ((balance, state), stake) in holders
+                        .balances
+                        .iter_mut()
+                        .zip(&holders.states)
+                        .zip(staked.iter_mut())
+                    {
+                        let target = ((*balance + *stake) * stake_share).round_dp(precision);
+                        *balance -= target - *stake;
+                        governance_state.circulating_supply -= target - *stake;
+                        *stake = target;
+                        report.staked += target;
+                        if *state == UserState::Active {
+                            report.votes += target;
+                        }
+                    }
+
+                    utility_simulation.token.current_supply -= TokenAmount(report.converted);
+                    utility_state.circulating_supply -= report.converted;
+                    governance_simulation.token.current_supply +=
+                        TokenAmount(report.conversion_output);
+                    governance_state.circulating_supply += report.conversion_output;
+                }
+
+                report.utility = utility_stepper.step()?.cloned().unwrap_or_default();
+                report.governance = governance_stepper.step()?.cloned().unwrap_or_default();
+                interval_reports.push(report);
+            }
+        }
+
+        let mut report = DualTokenReport {
+            utility: utility.report.clone(),
+            governance: governance.report.clone(),
+            ..Default::default()
+        };
+        for interval in &interval_reports {
+            report.converted += interval.converted;
+            report.conversion_output += interval.conversion_output;
+        }
+        if let Some(last) = interval_reports.last() {
+            report.staked = last.staked;
+            report.votes = last.votes;
+        }
+
+        Ok(DualTokenRun {
+            utility,
+            governance,
+            interval_reports,
+            report,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SimulationStatus;
+
+    use super::*;
+
+    #[test]
+    fn test_run_template() {
+        let economy = DualTokenEconomy::template("Game", 20, 4).unwrap();
+        let run = economy.run().unwrap();
+
+        assert_eq!(run.utility.status, SimulationStatus::Completed);
+        assert_eq!(run.governance.status, SimulationStatus::Completed);
+        assert_eq!(run.interval_reports.len(), 4);
+
+        // Utility tokens earned in the first interval are converted before the second one
+        let second = &run.interval_reports[1];
+        assert!(second.converted > Decimal::ZERO);
+        assert!(
+            (second.conversion_output - second.converted * Decimal::new(1, 2)).abs()
+                < Decimal::new(1, 3)
+        );
+        assert!(second.staked > Decimal::ZERO);
+        assert!(second.votes <= second.staked);
+
+        let converted: Decimal = run
+            .interval_reports
+            .iter()
+            .map(|report| report.converted)
+            .sum();
+        assert_eq!(run.report.converted, converted);
+        assert_eq!(run.report.utility, run.utility.report);
+    }
+
+    #[test]
+    fn test_run_with_market_price_conversion() {
+        let mut economy = DualTokenEconomy::template("Game", 10, 3).unwrap();
+        economy.conversion = TokenConversion {
+            mechanism: ConversionMechanism::MarketPrice,
+            share: 1.0,
+            fee: Decimal::new(50, 0),
+        };
+        economy.stake_share = 0.0;
+
+        let run = economy.run().unwrap();
+
+        assert!(run.report.converted > Decimal::ZERO);
+        assert!(run.report.conversion_output > Decimal::ZERO);
+        assert_eq!(run.report.staked, Decimal::ZERO);
+    }
+}
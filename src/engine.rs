@@ -5,22 +5,43 @@
 //! This module provides the simulation struct and related types to simulate the tokenomics of a token.
 //! The simulation contains the input parameters, token, and reports for the simulation.
 
+use std::collections::{HashMap, HashSet};
+
 use chrono::{DateTime, Utc};
-use rand::Rng;
-use rust_decimal::{prelude::*, Decimal, MathematicalOps};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rust_decimal::{prelude::*, Decimal};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
-    SimulationBuilder, SimulationError, SimulationOptions, SimulationOptionsBuilder,
-    SimulationReport, Token, TokenBuilder, User, ValuationModel,
+    activity::{stickiness, ActivityTracker},
+    aggregate_venues,
+    cancellation::CancellationToken,
+    controller::ControllerState,
+    gaming::sink_ratio,
+    gauge::aggregate_gauges,
+    population::sample_members,
+    tax::TaxTracker,
+    DefaultEngine, DistributionAnalysis, EffectiveOptions, EnsembleMetric, FiatAmount, GaugeVoting,
+    IntervalContext, MarketModel, MarketRegime, MetricPlugin, MetricSummary, Order, OrderBook,
+    Percentage, RandomSource, RetentionTracker, RoiTracker, RoundAs, SimulationBuilder,
+    SimulationEngine, SimulationError, SimulationObserver, SimulationOptions,
+    SimulationOptionsBuilder, SimulationReport, SinkDestination, SupplyEventKind, TaxDestination,
+    TaxRoute, Token, TokenAmount, TokenBuilder, Trade, TradeSide, User, UserIdStrategy, UserState,
+    UserStore, UtilityLocks, ValuationModel, ValueKind, VenueBook, WebhookTransport,
+    LORENZ_RESOLUTION, UNROUNDED,
 };
 
 /// Simulation.
 #[derive(Debug)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Simulation {
+    /// Version of the serialization format.
+    /// Missing in data written before versioning, which is treated as version 0.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub schema_version: u32,
+
     /// ID of the simulation.
     pub id: Uuid,
 
@@ -32,6 +53,22 @@ pub struct Simulation {
     /// This token is used to simulate the tokenomics.
     pub token: Token,
 
+    /// Token at the start of the last run, before the run changed its supply and unlock schedule.
+    /// Is used to rerun a completed simulation from the same state, `None` before the first run.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub initial_token: Option<Token>,
+
+    /// Input parameters of the last run as the engine applied them, with the defaults and derived values resolved.
+    /// `None` before the first run.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub effective_options: Option<EffectiveOptions>,
+
     /// Description of the simulation.
     /// This is used to provide additional information about the simulation.
     pub description: Option<String>,
@@ -57,11 +94,35 @@ pub struct Simulation {
 
     /// Date and time the simulation was last updated.
     pub updated_at: DateTime<Utc>,
+
+    /// Observers notified about the simulation events.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub observers: Vec<Box<dyn SimulationObserver>>,
+
+    /// Plugins computing custom metrics for the reports.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub metric_plugins: Vec<Box<dyn MetricPlugin>>,
+
+    /// Transport delivering the webhook payloads.
+    /// If not set, payloads are posted over plain HTTP.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub webhook_transport: Option<Box<dyn WebhookTransport>>,
+
+    /// Engine running the steps of the intervals.
+    /// If not set, `DefaultEngine` is used.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub engine: Option<Box<dyn SimulationEngine>>,
+
+    /// Source of the random numbers drawn by the runs.
+    /// If not set, every run draws from a `StdRng`, seeded from the options if set.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub rng: Option<Box<dyn RandomSource>>,
 }
 
 /// Status of a simulation.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum SimulationStatus {
     /// Simulation has not started.
     Pending,
@@ -71,12 +132,115 @@ pub enum SimulationStatus {
 
     /// Simulation has completed.
     Completed,
+
+    /// Simulation has failed.
+    /// The reports of the intervals before the failure are kept.
+    Failed {
+        /// Index of the interval the run failed at.
+        at_interval: u64,
+
+        /// Error of the run, the reason of the failure.
+        error: SimulationError,
+
+        /// Date and time the simulation failed.
+        failed_at: DateTime<Utc>,
+    },
+
+    /// Simulation was cancelled.
+    /// The reports of the intervals before the cancellation are kept.
+    Cancelled {
+        /// Index of the interval the run stopped before.
+        at_interval: u64,
+
+        /// Reason of the cancellation.
+        reason: String,
+
+        /// Date and time the simulation was cancelled.
+        cancelled_at: DateTime<Utc>,
+    },
+}
+
+impl SimulationStatus {
+    /// Get the date and time the simulation reached the status, for the statuses carrying it.
+    ///
+    /// # Returns
+    ///
+    /// Date and time of the failure or cancellation, `None` for the other statuses.
+    pub fn timestamp(&self) -> Option<DateTime<Utc>> {
+        match self {
+            SimulationStatus::Failed { failed_at, .. } => Some(*failed_at),
+            SimulationStatus::Cancelled { cancelled_at, .. } => Some(*cancelled_at),
+            _ => None,
+        }
+    }
+}
+
+/// State of a simulation run, carried from one interval to the next.
+#[derive(Debug)]
+pub(crate) struct RunState {
+    /// Users taking part in the simulation, stored by column.
+    pub(crate) users: UserStore,
+
+    /// Price of the token at the end of the last interval.
+    pub(crate) price: Decimal,
+
+    /// Duration of an interval, in hours.
+    pub(crate) interval: u64,
+
+    /// Index of the next interval to run.
+    pub(crate) index: u64,
+
+    /// Users active in the recent intervals.
+    pub(crate) activity: ActivityTracker,
+
+    /// Retention of the user cohorts.
+    pub(crate) retention: RetentionTracker,
+
+    /// Entry of the holder cohorts.
+    pub(crate) roi: RoiTracker,
+
+    /// Random number generator of the run.
+    pub(crate) rng: Box<dyn RandomSource>,
+
+    /// Whether the random number generator is the source set on the simulation, to be returned after the run.
+    pub(crate) custom_rng: bool,
+
+    /// Circulating supply at the start of the run: the initial supply and the airdrop.
+    pub(crate) initial_supply: Decimal,
+
+    /// Circulating supply at the end of the last interval.
+    pub(crate) circulating_supply: Decimal,
+
+    /// Market regime of the current interval, if the regime switches.
+    pub(crate) regime: Option<MarketRegime>,
+
+    /// Network activity of the last interval, in transactions per hour.
+    pub(crate) network_activity: Option<u64>,
+
+    /// Tokens locked by the utility sink, until their release.
+    pub(crate) utility_locks: UtilityLocks,
+
+    /// Balance of the insurance fund.
+    pub(crate) insurance_balance: Decimal,
+
+    /// State of the supply controller.
+    pub(crate) controller: ControllerState,
+
+    /// Yield of the token in the last interval, in percentage.
+    pub(crate) holder_yield: Decimal,
+
+    /// Tax positions of the users, if a tax policy is set.
+    pub(crate) tax: Option<TaxTracker>,
+
+    /// Cumulative impact of the forced and tax sales on the price of the valuation models, as a factor.
+    pub(crate) price_impact: Decimal,
 }
 
 /// Interval type for the simulation.
 /// This is used to determine the duration of each interval in the simulation.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum SimulationInterval {
     /// Hourly interval.
     Hourly,
@@ -91,6 +255,23 @@ pub enum SimulationInterval {
     Monthly,
 }
 
+impl SimulationInterval {
+    /// Get the duration of the interval, in hours.
+    /// A month is counted as 30 days.
+    ///
+    /// # Returns
+    ///
+    /// The duration of the interval, in hours.
+    pub fn hours(&self) -> u64 {
+        match self {
+            SimulationInterval::Hourly => 1,
+            SimulationInterval::Daily => 24,
+            SimulationInterval::Weekly => 24 * 7,
+            SimulationInterval::Monthly => 24 * 30,
+        }
+    }
+}
+
 impl Simulation {
     /// Create a new simulation with the given token and options.
     ///
@@ -119,7 +300,101 @@ impl Simulation {
         TokenBuilder::new()
     }
 
-    /// Update the status of the simulation.   
+    /// Add an observer to be notified about the simulation events.
+    ///
+    /// # Arguments
+    ///
+    /// * `observer` - The observer to add.
+    pub fn add_observer<O: SimulationObserver + 'static>(&mut self, observer: O) {
+        self.observers.push(Box::new(observer));
+    }
+
+    /// Add a plugin computing a custom metric for every report.
+    ///
+    /// # Arguments
+    ///
+    /// * `plugin` - The plugin to add.
+    pub fn add_metric_plugin<P: MetricPlugin + 'static>(&mut self, plugin: P) {
+        self.metric_plugins.push(Box::new(plugin));
+    }
+
+    /// Set the engine running the steps of the intervals.
+    /// Default is `DefaultEngine`.
+    ///
+    /// # Arguments
+    ///
+    /// * `engine` - The engine to use.
+    pub fn set_engine<E: SimulationEngine + 'static>(&mut self, engine: E) {
+        self.engine = Some(Box::new(engine));
+    }
+
+    /// Get the engine running the steps of the intervals.
+    ///
+    /// # Returns
+    ///
+    /// The engine of the simulation, or `DefaultEngine` if not set.
+    fn engine(&self) -> &dyn SimulationEngine {
+        self.engine.as_deref().unwrap_or(&DefaultEngine)
+    }
+
+    /// Get the prices of the past intervals.
+    ///
+    /// # Returns
+    ///
+    /// Price of each past interval, oldest first.
+    fn past_prices(&self) -> Vec<Decimal> {
+        self.interval_reports
+            .iter()
+            .map(|report| report.token_price.value())
+            .collect()
+    }
+
+    /// Get the sentiment index of the current interval, from the prices of the past intervals.
+    ///
+    /// # Arguments
+    ///
+    /// * `price` - Price of the token at the start of the interval.
+    ///
+    /// # Returns
+    ///
+    /// Sentiment index, `None` if no sentiment model is set.
+    fn sentiment_index(&self, price: Decimal) -> Option<Decimal> {
+        let sentiment = self.options.sentiment.as_ref()?;
+
+        Some(
+            sentiment
+                .index(price, &self.past_prices())
+                .round_as(&self.options, ValueKind::Rate),
+        )
+    }
+
+    /// Compute the custom metrics of a report.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - Context of the interval, or of the end of the simulation.
+    /// * `users` - Users at the end of the interval or simulation.
+    /// * `report` - Report to store the custom metrics in.
+    fn compute_custom_metrics(
+        &self,
+        context: &IntervalContext,
+        users: &UserStore,
+        report: &mut SimulationReport,
+    ) {
+        if !self.options.report_fields.custom_metrics {
+            return;
+        }
+
+        for plugin in &self.metric_plugins {
+            let value = plugin.compute(context, users, report);
+            report
+                .custom_metrics
+                .insert(plugin.name().to_string(), value);
+        }
+    }
+
+    /// Update the status of the simulation.
+    /// The simulation is updated at the timestamp of the status if it carries one, now otherwise.
     ///
     /// # Arguments
     ///
@@ -128,8 +403,8 @@ impl Simulation {
         #[cfg(feature = "log")]
         log::debug!("Updating simulation status: {:?}", status);
 
+        self.updated_at = status.timestamp().unwrap_or_else(Utc::now);
         self.status = status;
-        self.updated_at = Utc::now();
     }
 
     /// Simulate user adoption based on the current number of users and the adoption rate.
@@ -143,24 +418,7 @@ impl Simulation {
     ///
     /// The new number of users after adoption.
     pub fn simulate_adoption(&self, current_users: u64) -> Result<u64, SimulationError> {
-        match self.options.adoption_rate {
-            Some(rate) => {
-                #[cfg(feature = "log")]
-                log::debug!("Simulating user adoption for simulation: {}", self.name);
-
-                let new_users = (current_users as f64
-                    * rate.to_f64().ok_or(SimulationError::InvalidDecimal)?)
-                .round() as u64;
-
-                let total = current_users + new_users;
-
-                #[cfg(feature = "log")]
-                log::debug!("User adoption simulated: {}", total);
-
-                Ok(total)
-            }
-            None => Ok(current_users),
-        }
+        self.engine().simulate_adoption(self, current_users)
     }
 
     /// Calculate the valuation of the token based on the number of users and the initial price.
@@ -176,39 +434,133 @@ impl Simulation {
     ///
     /// The calculated token valuation.
     pub fn calculate_valuation(&self, token: &Token, users: u64) -> Decimal {
-        match self.options.valuation_model {
-            Some(ValuationModel::Linear) => {
-                #[cfg(feature = "log")]
-                log::debug!("Calculating linear valuation for simulation: {}", self.name);
-
-                let valuation = Decimal::from(users) * token.initial_price;
+        self.engine().calculate_valuation(self, token, users)
+    }
 
-                #[cfg(feature = "log")]
-                log::debug!("Linear valuation calculated: {}", valuation);
+    /// Fork the simulation for a what-if run.
+    /// The returned builder is pre-populated with the name, description, token, and options of the simulation.
+    /// The built simulation gets a new ID and starts in the pending state.
+    ///
+    /// The token is copied as is, so fork a simulation before running it to start from the same supply.
+    ///
+    /// # Returns
+    ///
+    /// Simulation builder pre-populated from the simulation.
+    pub fn fork(&self) -> SimulationBuilder {
+        let mut builder = Simulation::builder()
+            .name(self.name.clone())
+            .token(self.token.clone())
+            .options(self.options.clone());
+        builder.description = self.description.clone();
+
+        builder
+    }
 
-                valuation
-            }
-            Some(ValuationModel::Exponential(factor)) => {
-                #[cfg(feature = "log")]
-                log::debug!("Calculating exponential valuation with factor: {}", factor);
+    /// Simulate the market price of the token for the next interval.
+    /// The price follows a geometric random walk, where the market volatility is the annualized volatility.
+    ///
+    /// # Arguments
+    ///
+    /// * `price` - The price of the token in the previous interval.
+    /// * `interval` - Duration of the interval, in hours.
+    ///
+    /// # Returns
+    ///
+    /// The simulated token price.
+    pub fn simulate_price(
+        &self,
+        price: Decimal,
+        interval: u64,
+    ) -> Result<Decimal, SimulationError> {
+        self.simulate_price_with_rng(
+            price,
+            interval,
+            self.options.market_regime,
+            &mut rand::rng(),
+        )
+    }
 
-                let exponent = match Decimal::from_f64(factor) {
-                    Some(factor) => Decimal::from(users) / factor,
-                    None => Decimal::from(users),
-                };
+    /// Simulate the token price, drawing the shock from the given random number generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `price` - The price of the token in the previous interval.
+    /// * `interval` - Duration of the interval, in hours.
+    /// * `regime` - Market regime of the interval.
+    /// * `rng` - Random number generator.
+    ///
+    /// # Returns
+    ///
+    /// The simulated token price.
+    fn simulate_price_with_rng<R: Rng + ?Sized>(
+        &self,
+        price: Decimal,
+        interval: u64,
+        regime: Option<MarketRegime>,
+        rng: &mut R,
+    ) -> Result<Decimal, SimulationError> {
+        let (drift, volatility_multiplier) = match regime {
+            Some(regime) => (regime.drift(), regime.volatility_multiplier()),
+            None => (0.0, 1.0),
+        };
+        let volatility = self
+            .options
+            .market_volatility
+            .to_f64()
+            .ok_or(SimulationError::InvalidDecimal)?
+            * volatility_multiplier;
+        let years = interval as f64 / (24.0 * 365.0);
+        let sigma = volatility * years.sqrt();
+
+        // Standard normal shock, using the Box-Muller transform
+        let u1: f64 = rng.random_range(f64::EPSILON..1.0);
+        let u2: f64 = rng.random_range(0.0..1.0);
+        let shock = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+
+        let factor = Decimal::from_f64((drift * years + sigma * shock - sigma * sigma / 2.0).exp())
+            .ok_or(SimulationError::InvalidDecimal)?;
+
+        Ok(price * factor)
+    }
 
-                let valuation = match exponent.checked_exp() {
-                    Some(exp) => token.initial_price * exp,
-                    None => token.initial_price,
-                };
+    /// Generate users joining the simulation.
+    /// Starting balances are sampled like the initial distribution, sharing the given supply.
+    ///
+    /// # Arguments
+    ///
+    /// * `total_users` - Number of users to generate.
+    /// * `supply` - Tokens shared by the generated users.
+    /// * `first_index` - Index of the first generated user in the population, used to assign IDs.
+    /// * `rng` - Random number generator.
+    ///
+    /// # Returns
+    ///
+    /// List of generated users, with behaviours and IDs assigned.
+    fn generate_users<R: Rng + ?Sized>(
+        &self,
+        total_users: u64,
+        supply: Decimal,
+        first_index: u64,
+        rng: &mut R,
+    ) -> Vec<User> {
+        let mut users = self.options.population_model.generate_with_rng(
+            total_users,
+            supply,
+            self.token.initial_price.value(),
+            self.options.precision(ValueKind::Balance),
+            rng,
+        );
 
-                #[cfg(feature = "log")]
-                log::debug!("Exponential valuation calculated: {}", valuation);
+        if let Some(mix) = &self.options.behaviour_mix {
+            User::assign_behaviours_with_rng(&mut users, mix, rng);
+        }
 
-                valuation
-            }
-            _ => Decimal::default(),
+        // Generated users already have random IDs
+        if self.options.user_id_strategy != UserIdStrategy::Random {
+            User::assign_ids_from(&mut users, self.options.user_id_strategy, first_index);
         }
+
+        users
     }
 
     /// Run the simulation.
@@ -220,12 +572,94 @@ impl Simulation {
     ///
     /// Result of the simulation.
     pub fn run(&mut self) -> Result<(), SimulationError> {
+        self.run_with_cancellation(&CancellationToken::new())
+    }
+
+    /// Prepare a run of the simulation: generate the initial users and reset the reports.
+    ///
+    /// # Returns
+    ///
+    /// State of the run, before the first interval.
+    pub(crate) fn start_run(&mut self) -> Result<RunState, SimulationError> {
         #[cfg(feature = "log")]
         log::debug!("Running simulation: {}", self.name);
 
+        for observer in &self.observers {
+            observer.on_start(self);
+        }
+        self.initial_token = Some(self.token.clone());
+        if let Some(routes) = &self.token.tax_routing {
+            TaxRoute::validate_shares(routes)?;
+        }
+
+        // Network fees depending on live data must be resolved before the run
+        if let Some(fee) = &self.options.transaction_fee {
+            fee.per_transaction()?;
+        }
+
+        // The switching regime replaces the preset, so they cannot be combined
+        if let Some(switching) = &self.options.regime_switching {
+            switching.validate()?;
+            if self.options.market_regime.is_some() {
+                return Err(SimulationError::InvalidData(
+                    "market regime and regime switching cannot both be set".to_string(),
+                ));
+            }
+        }
+
+        if let Some(lifecycle) = &self.options.lifecycle {
+            lifecycle.validate()?;
+        }
+        if let Some(congestion) = &self.options.fee_congestion {
+            congestion.validate()?;
+        }
+        if let Some(market) = &self.options.nft_market {
+            market.validate()?;
+        }
+        if let Some(voting) = &self.options.gauge_voting {
+            voting.validate()?;
+        }
+        if let Some(fund) = &self.options.insurance_fund {
+            fund.validate()?;
+        }
+        if let Some(controller) = &self.options.supply_controller {
+            controller.validate()?;
+        }
+        if let Some(competitor) = &self.options.competitor {
+            competitor.validate()?;
+        }
+        if let Some(rates) = &self.options.interest_rates {
+            rates.validate()?;
+        }
+        if let Some(policy) = &self.options.tax_policy {
+            policy.validate()?;
+        }
+        if let Some(cap) = &self.options.holder_cap {
+            cap.validate()?;
+        }
+        if let Some(sentiment) = &self.options.sentiment {
+            sentiment.validate()?;
+        }
+        if let Some(effects) = &self.options.network_effects {
+            effects.validate()?;
+        }
+        for profile in self.options.behaviour_profiles.values() {
+            profile.validate()?;
+        }
+        if let Some(switch) = &self.options.fee_switch {
+            switch.validate()?;
+        }
+        self.effective_options = Some(self.options.effective()?);
+
         self.update_status(SimulationStatus::Running);
 
-        let decimal_precision = self.options.decimal_precision;
+        // The source set on the simulation is lent to the run, and returned when it ends
+        let custom_rng = self.rng.is_some();
+        let mut rng: Box<dyn RandomSource> = match (self.rng.take(), self.options.seed) {
+            (Some(rng), _) => rng,
+            (None, Some(seed)) => Box::new(StdRng::seed_from_u64(seed)),
+            (None, None) => Box::new(StdRng::from_rng(&mut rand::rng())),
+        };
 
         #[cfg(feature = "log")]
         log::debug!(
@@ -234,199 +668,1279 @@ impl Simulation {
         );
 
         let airdrop_amount = match self.token.airdrop_percentage {
-            Some(percentage) => self.token.airdrop(percentage),
+            Some(percentage) => self.token.airdrop(percentage).value(),
             None => Decimal::default(),
         };
+        self.token.ledger.record(
+            Utc::now(),
+            SupplyEventKind::Airdrop,
+            airdrop_amount,
+            "airdrop",
+        );
 
-        let mut users = User::generate(
+        let mut users = self.generate_users(
             self.options.total_users,
-            self.token.initial_supply(),
-            self.token.initial_price,
-            decimal_precision,
+            self.token.initial_supply().value(),
+            0,
+            &mut rng,
         );
 
+        // Imported holders start with their balances instead of random ones
+        if let Some(balances) = &self.options.holder_balances {
+            for (user, balance) in users.iter_mut().zip(balances) {
+                user.balance = balance.round_as(&self.options, ValueKind::Balance);
+            }
+        }
+        let mut users = UserStore::from(users);
+
         #[cfg(feature = "log")]
         log::debug!("Initial user distribution generated");
 
+        // The users acquire their starting balances at the initial price
+        let mut tax = self
+            .options
+            .tax_policy
+            .as_ref()
+            .map(|_| TaxTracker::new(&users, self.token.initial_price.value()));
+
         // Distribute airdrop amount among users, if available
         if !airdrop_amount.is_zero() {
             #[cfg(feature = "log")]
             log::debug!("Distributing airdrop amount: {}", airdrop_amount);
 
-            let airdrop_per_user = airdrop_amount / Decimal::new(users.len() as i64, 0);
+            let airdrop_per_user = airdrop_amount / Decimal::from(users.count());
 
             #[cfg(feature = "log")]
             log::debug!("Airdrop amount per user: {}", airdrop_per_user);
 
-            for user in &mut users {
-                user.balance += airdrop_per_user.round_dp(decimal_precision);
+            for (balance, weight) in users.balances.iter_mut().zip(&users.weights) {
+                *balance += (airdrop_per_user * Decimal::from(*weight))
+                    .round_as(&self.options, ValueKind::Balance);
             }
 
             #[cfg(feature = "log")]
             log::debug!("Airdrop amount distributed");
         }
 
+        // The airdrop is income of the users
+        if let Some(tax) = &mut tax {
+            tax.record(&users, self.token.initial_price.value(), true);
+        }
+
         self.interval_reports = vec![];
 
+        // The initial users enter at the initial price
+        let mut roi = RoiTracker::default();
+        if self.options.report_fields.cohort_roi {
+            roi.record(0, &users, self.token.initial_price.value());
+        }
+
         let interval = self.get_interval();
 
         #[cfg(feature = "log")]
         log::debug!("Simulation interval: {}", interval);
 
-        for time in (0..self.options.duration * interval).step_by(interval as usize) {
-            #[cfg(feature = "log")]
-            log::debug!("Processing interval: {}", time);
-
-            // Process unlock events up to the current time
-            let current_date = Utc::now() + chrono::Duration::hours(time as i64);
-            self.token.process_unlocks(current_date);
-
-            // Simulate user adoption
-            let current_users = self.simulate_adoption(users.len() as u64)?;
-            users = User::generate(
-                current_users,
-                self.token.initial_supply(),
-                self.token.initial_price,
-                decimal_precision,
-            );
-
-            let valuation = self.calculate_valuation(&self.token, current_users);
-            let mut report = self.process_interval(&mut users, interval)?;
-            report.token_price = valuation;
-            report.interval = current_date.timestamp_millis();
-
-            self.interval_reports.push(report);
-
-            #[cfg(feature = "log")]
-            log::debug!("Interval processed: {}", time);
-        }
-
-        self.generate_final_report(users);
-        self.update_status(SimulationStatus::Completed);
+        Ok(RunState {
+            users,
+            price: self.token.initial_price.value(),
+            interval,
+            index: 0,
+            activity: ActivityTracker::new(interval),
+            retention: RetentionTracker::default(),
+            roi,
+            rng,
+            custom_rng,
+            initial_supply: self.token.initial_supply().value() + airdrop_amount,
+            circulating_supply: self.token.initial_supply().value() + airdrop_amount,
+            regime: self
+                .options
+                .regime_switching
+                .as_ref()
+                .map(|switching| switching.initial),
+            network_activity: None,
+            utility_locks: UtilityLocks::default(),
+            insurance_balance: self
+                .options
+                .insurance_fund
+                .as_ref()
+                .map(|fund| fund.initial_balance)
+                .unwrap_or_default(),
+            controller: ControllerState::default(),
+            holder_yield: Decimal::default(),
+            tax,
+            price_impact: Decimal::ONE,
+        })
+    }
 
-        #[cfg(feature = "log")]
-        log::debug!("Simulation completed: {}", self.name);
+    /// Handle the failure of a run.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - State of the run, if it has started.
+    /// * `error` - Error of the run.
+    ///
+    /// # Returns
+    ///
+    /// The error, to be returned by the run.
+    pub(crate) fn fail_run(
+        &mut self,
+        state: Option<RunState>,
+        error: SimulationError,
+    ) -> SimulationError {
+        let status = SimulationStatus::Failed {
+            at_interval: state.as_ref().map_or(0, |state| state.index),
+            error: error.clone(),
+            failed_at: Utc::now(),
+        };
 
-        Ok(())
+        self.abort_run(state, status, error)
     }
 
-    /// Simulate trades for a given interval.
-    /// This will simulate trades for each user in the list and generate a report for the interval.
+    /// Handle the cancellation of a run.
     ///
     /// # Arguments
     ///
-    /// * `users` - A list of users.
-    /// * `interval` - Duration of the interval.
+    /// * `state` - State of the run.
+    /// * `cancellation` - Token used to cancel the run.
     ///
     /// # Returns
     ///
-    /// A report of the simulation results for the interval.
-    pub fn process_interval(
-        &self,
-        users: &mut [User],
-        interval: u64,
-    ) -> Result<SimulationReport, SimulationError> {
-        let mut rng = rand::rng();
+    /// `SimulationError::Cancelled`, to be returned by the run.
+    pub(crate) fn cancel_run(
+        &mut self,
+        state: RunState,
+        cancellation: &CancellationToken,
+    ) -> SimulationError {
+        let status = SimulationStatus::Cancelled {
+            at_interval: state.index,
+            reason: cancellation
+                .reason()
+                .unwrap_or_else(|| SimulationError::Cancelled.to_string()),
+            cancelled_at: Utc::now(),
+        };
 
-        let decimal_precision = self.options.decimal_precision;
-        let mut total_burned = Decimal::default();
-        let mut total_new_tokens = Decimal::default();
-        let mut report = SimulationReport::default();
+        self.abort_run(Some(state), status, SimulationError::Cancelled)
+    }
 
-        for _ in 0..interval {
-            for user in users.iter_mut() {
-                // Skip users with zero balance
-                if user.balance.is_zero() {
-                    continue;
-                }
+    /// Stop a run before its end, notifying the observers and the webhook.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - State of the run, if it has started.
+    /// * `status` - Status of the stopped simulation.
+    /// * `error` - Error of the run.
+    ///
+    /// # Returns
+    ///
+    /// The error, to be returned by the run.
+    fn abort_run(
+        &mut self,
+        state: Option<RunState>,
+        status: SimulationStatus,
+        error: SimulationError,
+    ) -> SimulationError {
+        if let Some(state) = state {
+            self.end_run(state);
+        }
 
-                if rng.random_bool(0.5) {
-                    // Simulate a successful trade and randomize the fraction between 1% and 10% of the user's balance
-                    let trade_fraction = rng.random_range(0.01..0.1);
-                    let max_trade_amount = user
-                        .balance
-                        .to_f64()
-                        .ok_or(SimulationError::InvalidDecimal)?
-                        * trade_fraction;
+        self.update_status(status);
 
-                    // Ensure the range is valid
-                    if max_trade_amount > 0.0 {
-                        let trade_amount =
-                            Decimal::from_f64(rng.random_range(0.0..max_trade_amount))
-                                .ok_or(SimulationError::InvalidDecimal)?
-                                .round_dp(decimal_precision);
+        #[cfg(feature = "log")]
+        log::debug!("Simulation failed: {} {}", self.name, error);
 
-                        user.balance -= trade_amount;
-                        report.profit_loss += trade_amount;
-                        report.successful_trades += 1;
+        for observer in &self.observers {
+            observer.on_fail(&error);
+        }
 
-                        if let Some(burn_rate) = self.token.burn_rate {
-                            let burned = trade_amount * burn_rate;
-                            user.balance -= burned;
-                            total_burned += burned;
-                        }
+        self.notify_webhook(Some(&error));
 
-                        if let Some(inflation_rate) = self.token.inflation_rate {
-                            let new_tokens = trade_amount * inflation_rate;
-                            user.balance += new_tokens;
-                            total_new_tokens += new_tokens;
-                        }
+        error
+    }
 
-                        if let Some(fee) = self.options.transaction_fee_percentage {
-                            let fee = trade_amount * (fee / Decimal::new(100, 0));
-                            user.balance -= fee.round_dp(decimal_precision);
-                        }
-                    } else {
-                        report.failed_trades += 1;
-                    }
-                } else {
-                    report.failed_trades += 1;
-                }
-            }
+    /// End a run, returning the randomness source lent to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - State of the run.
+    fn end_run(&mut self, state: RunState) {
+        if state.custom_rng {
+            self.rng = Some(state.rng);
         }
-
-        self.generate_interval_report(users, &mut report, interval);
-
-        Ok(report)
     }
 
-    /// Generate the interval report for the simulation.
+    /// Run the next interval of the simulation and store its report.
     ///
     /// # Arguments
     ///
-    /// * `users` - A list of users.
-    /// * `report` - The simulation report for the interval.
-    /// * `interval` - Duration of the interval.
-    pub fn generate_interval_report(
-        &self,
-        users: &[User],
-        report: &mut SimulationReport,
-        interval: u64,
+    /// * `state` - State of the run.
+    pub(crate) fn run_interval(&mut self, state: &mut RunState) -> Result<(), SimulationError> {
+        let interval = state.interval;
+        let index = state.index as usize;
+        let time = state.index * interval;
+
+        #[cfg(feature = "log")]
+        log::debug!("Processing interval: {}", time);
+
+        // Process unlock events up to the current time
+        let current_date = Utc::now() + chrono::Duration::hours(time as i64);
+        let supply_before_unlocks = self.token.current_supply;
+        self.token.process_unlocks(current_date);
+        let unlocked = (self.token.current_supply - supply_before_unlocks).value();
+        self.token.ledger.record(
+            current_date,
+            SupplyEventKind::Unlock,
+            unlocked,
+            "unlock schedule",
+        );
+        // The holders receiving the unlocked tokens sell the sell-through of their profile right away
+        let unlock_sold = state.users.distribute_unlock(
+            unlocked,
+            |behaviour| {
+                self.options
+                    .behaviour_profile(behaviour)
+                    .unlock_sell_through
+            },
+            self.options.precision(ValueKind::Balance),
+        );
+
+        // Emit the contributor stream, tracked separately from inflation
+        let (contributor_emission, contributor_sold) = self.token.emit_to_contributors();
+        let (contributor_emission, contributor_sold) =
+            (contributor_emission.value(), contributor_sold.value());
+        self.token.ledger.record(
+            current_date,
+            SupplyEventKind::Mint,
+            contributor_emission,
+            "contributor emission",
+        );
+
+        // Emit the block rewards, according to the halving schedule
+        let block_rewards = self.token.emit_block_rewards(index as u64).value();
+        self.token.ledger.record(
+            current_date,
+            SupplyEventKind::Mint,
+            block_rewards,
+            "block rewards",
+        );
+
+        // Switch the market regime, the first interval runs in the initial regime
+        if let (Some(switching), Some(regime)) = (&self.options.regime_switching, state.regime) {
+            if index > 0 {
+                state.regime = Some(switching.next(regime, &mut state.rng));
+            }
+        }
+
+        // Simulate user adoption, existing users keep their balances and lifecycle state
+        let previous_users = state.users.count();
+        let mut current_users = self.simulate_adoption(previous_users)?;
+        if let Some(regime) = state.regime {
+            let growth = current_users.saturating_sub(previous_users) as f64;
+            current_users = previous_users + (growth * regime.adoption_multiplier()).round() as u64;
+        }
+        // The recent price performance and network activity feed back into the growth
+        let adoption_multiplier = self.options.network_effects.as_ref().map(|effects| {
+            let activity: Vec<Decimal> = self
+                .interval_reports
+                .iter()
+                .map(|report| Decimal::from(report.network_activity))
+                .collect();
+
+            effects
+                .multiplier(state.price, &self.past_prices(), &activity)
+                .round_as(&self.options, ValueKind::Rate)
+        });
+        if let Some(multiplier) = adoption_multiplier {
+            let growth = Decimal::from(current_users.saturating_sub(previous_users));
+            current_users = previous_users
+                + (growth * multiplier)
+                    .round()
+                    .to_u64()
+                    .ok_or(SimulationError::InvalidDecimal)?;
+        }
+        let joined_users = current_users.saturating_sub(previous_users);
+        if joined_users > 0 {
+            // Joining users get the average starting balance, issued from the supply not minted yet
+            let joined_supply = (self.token.initial_supply().value() * Decimal::from(joined_users))
+                .checked_div(Decimal::from(self.options.total_users))
+                .unwrap_or_default()
+                .round_as(&self.options, ValueKind::Balance);
+            let joined_supply = self.token.issue(TokenAmount(joined_supply)).value();
+            self.token.ledger.record(
+                current_date,
+                SupplyEventKind::Airdrop,
+                joined_supply,
+                "joining users",
+            );
+            state.circulating_supply += joined_supply;
+
+            let new_users = self.generate_users(
+                joined_users,
+                joined_supply,
+                state.users.len() as u64,
+                &mut state.rng,
+            );
+            state.users.extend(new_users);
+        }
+
+        let mut price = match (&self.options.market_model, &self.options.valuation_model) {
+            // The order book discovers the price during the interval
+            (MarketModel::OrderBook(_), _) => state.price,
+            (_, model) => match self
+                .options
+                .price_feed
+                .as_ref()
+                .and_then(|feed| feed.change_at(index as u64))
+            {
+                // The historical feed is replayed as returns, from the price of the token
+                Some(change) => state.price * change,
+                None if model == &Some(ValuationModel::RandomWalk) => self
+                    .simulate_price_with_rng(
+                        state.price,
+                        interval,
+                        state.regime.or(self.options.market_regime),
+                        &mut state.rng,
+                    )?,
+                // The valuation is recalculated, so the past sales keep pushing it down
+                None => self.calculate_valuation(&self.token, current_users) * state.price_impact,
+            },
+        }
+        .round_as(&self.options, ValueKind::Price);
+        if self.options.report_fields.cohort_roi {
+            state.roi.record(index as u64, &state.users, price);
+        }
+        let capital_inflow = self.apply_capital_inflow_with_rng(
+            &mut state.users,
+            current_users.saturating_sub(previous_users),
+            price,
+            &mut state.rng,
+        )?;
+        // Once launched, the competitor drains the active users by its yield gap of the last interval
+        let mut drained = (None, 0);
+        if let Some(competitor) = &self.options.competitor {
+            if let Some(gap) = competitor.yield_gap(index as u64, state.holder_yield) {
+                drained = (
+                    Some(Percentage(gap).round_as(&self.options, ValueKind::Rate)),
+                    competitor.drain(gap, &mut state.users, &mut state.rng)?,
+                );
+            }
+        }
+        let mut context = self.interval_context(index as u64, current_date, interval, price)?;
+        // The activity of the previous interval congests the network, and scales the fee of this one
+        let network_fee_multiplier = self.options.fee_congestion.as_ref().map(|congestion| {
+            state
+                .network_activity
+                .map_or(Decimal::ONE, |activity| congestion.multiplier(activity))
+        });
+        if let Some(multiplier) = network_fee_multiplier {
+            let fees = &mut context.fees;
+            for fee in std::iter::once(&mut fees.network_fee).chain(&mut fees.network_fee_samples) {
+                *fee = (*fee * multiplier).round_as(&self.options, ValueKind::Balance);
+            }
+        }
+        let (mut report, active) =
+            self.engine()
+                .process_interval(self, &context, &mut state.users, &mut state.rng)?;
+        state.activity.record(active);
+        if self.options.report_fields.retention_curve {
+            state.retention.record(&state.users);
+        }
+        (
+            report.daily_active_users,
+            report.weekly_active_users,
+            report.monthly_active_users,
+        ) = state.activity.rolling();
+        report.stickiness = stickiness(
+            Decimal::from(report.daily_active_users),
+            Decimal::from(report.monthly_active_users),
+            UNROUNDED,
+        );
+        if let MarketModel::OrderBook(_) = self.options.market_model {
+            price = report.token_price.value();
+        }
+        report.token_price = FiatAmount(price);
+        report.capital_inflow = FiatAmount(capital_inflow);
+        (report.yield_gap, report.users_drained) = drained;
+        report.risk_free_rate = self
+            .options
+            .interest_rates
+            .as_ref()
+            .map(|rates| Percentage(rates.rate_at(index as u64)));
+        report.sentiment = self.sentiment_index(context.price);
+        report.adoption_multiplier = adoption_multiplier;
+
+        // Active users spend or lock tokens to use the product
+        let mut utility_burned = TokenAmount::default();
+        if let Some(utility) = &self.options.utility {
+            let locked = state.utility_locks.locked();
+            let collection = utility.collect(
+                index as u64,
+                &mut state.users,
+                price,
+                self.options.precision(ValueKind::Balance),
+                &mut state.utility_locks,
+                &mut state.rng,
+            )?;
+            report.utility_consumed = TokenAmount(collection.consumed);
+            report.utility_locked = TokenAmount(state.utility_locks.locked());
+            match (utility.lock_intervals, utility.destination) {
+                (Some(_), _) => state.circulating_supply -= state.utility_locks.locked() - locked,
+                (None, SinkDestination::Burn) => {
+                    utility_burned = report.utility_consumed;
+                    report.total_burned += utility_burned;
+                    report.burn_rate = report.calculate_burn_rate(
+                        report.total_burned,
+                        Decimal::from(state.users.count()),
+                        UNROUNDED,
+                    );
+                }
+                (None, SinkDestination::Treasury) => {}
+            }
+        }
+
+        // Subscribers pay their recurring fee
+        let mut subscription_burned = TokenAmount::default();
+        if let Some(subscription) = &self.options.subscription {
+            let payments = subscription.collect(
+                &mut state.users,
+                price,
+                self.options.precision(ValueKind::Balance),
+                &mut state.rng,
+            );
+            report.subscribers = payments.subscribers;
+            report.recurring_revenue = TokenAmount(payments.revenue);
+            report.recurring_revenue_fiat = report.recurring_revenue.value_at(FiatAmount(price));
+            if subscription.destination == SinkDestination::Burn {
+                subscription_burned = report.recurring_revenue;
+                report.total_burned += subscription_burned;
+                report.burn_rate = report.calculate_burn_rate(
+                    report.total_burned,
+                    Decimal::from(state.users.count()),
+                    UNROUNDED,
+                );
+            }
+        }
+
+        // Users mint and resell NFTs
+        let mut nft_burned = TokenAmount::default();
+        if let Some(market) = &self.options.nft_market {
+            let activity = market.trade(
+                &mut state.users,
+                price,
+                self.options.precision(ValueKind::Balance),
+                &mut state.rng,
+            );
+            report.nft_mints = activity.mints;
+            report.nft_volume = TokenAmount(activity.mint_volume + activity.resale_volume);
+            report.nft_royalties = TokenAmount(activity.royalties);
+            if market.mint_destination == SinkDestination::Burn {
+                nft_burned = TokenAmount(activity.mint_volume);
+                report.total_burned += nft_burned;
+                report.burn_rate = report.calculate_burn_rate(
+                    report.total_burned,
+                    Decimal::from(state.users.count()),
+                    UNROUNDED,
+                );
+            }
+        }
+
+        // The vote-escrow holders direct the emission to the gauges, which is income of the users
+        if let Some(voting) = &self.options.gauge_voting {
+            if let Some(tax) = &mut state.tax {
+                tax.record(&state.users, price, false);
+            }
+            // High rates lower the share of the balances locked for the votes
+            let scaled;
+            let voting = match &self.options.interest_rates {
+                Some(rates) => {
+                    scaled = GaugeVoting {
+                        lock_share: rates.lock_share(voting.lock_share, index as u64),
+                        ..voting.clone()
+                    };
+                    &scaled
+                }
+                None => voting,
+            };
+            report.gauges = voting.allocate(
+                &mut state.users,
+                |behaviour| self.options.behaviour_profile(behaviour).stake_propensity,
+                price,
+                (self.token.total_supply - self.token.current_supply).value(),
+                self.options.precision(ValueKind::Balance),
+            )?;
+            report.gauge_emission = report
+                .gauges
+                .iter()
+                .map(|gauge| TokenAmount(gauge.emission))
+                .sum();
+            self.token.current_supply += report.gauge_emission;
+            if let Some(tax) = &mut state.tax {
+                tax.record(&state.users, price, true);
+            }
+        }
+
+        // The insurance fund collects its share of the fees, and covers the shortfall events
+        if let Some(fund) = &self.options.insurance_fund {
+            state.insurance_balance += fund
+                .fee_contribution(report.fees.value())
+                .round_as(&self.options, ValueKind::Balance);
+            let activity = fund.cover(
+                index as u64,
+                &mut state.insurance_balance,
+                &mut state.users,
+                state.circulating_supply,
+                self.options.precision(ValueKind::Balance),
+                &mut state.rng,
+            );
+            report.insurance_fund = TokenAmount(state.insurance_balance);
+            report.shortfall_losses = TokenAmount(activity.losses);
+            report.insurance_payouts = TokenAmount(activity.payouts);
+            report.coverage_ratio = fund
+                .coverage_ratio(state.insurance_balance, state.circulating_supply)
+                .map(|ratio| ratio.round_as(&self.options, ValueKind::Rate));
+        }
+
+        // Once the fee switch is activated, the holders receive their share of the fees, as income
+        if let Some(switch) = &self.options.fee_switch {
+            if let Some(tax) = &mut state.tax {
+                tax.record(&state.users, price, false);
+            }
+            report.holder_fees = TokenAmount(switch.distribute(
+                index as u64,
+                report.fees.value(),
+                &mut state.users,
+                self.options.precision(ValueKind::Balance),
+            ));
+            if let Some(tax) = &mut state.tax {
+                tax.record(&state.users, price, true);
+            }
+        }
+        report.treasury_fees = report.fees - report.holder_fees;
+        state.holder_yield = ((report.holder_fees + report.gauge_emission).value()
+            * Decimal::ONE_HUNDRED)
+            .checked_div(state.circulating_supply)
+            .unwrap_or_default();
+
+        // The supply controller burns or emits tokens towards its target price
+        let mut controller_burned = TokenAmount::default();
+        if let Some(controller) = &self.options.supply_controller {
+            report.supply_adjustment = TokenAmount(controller.adjust(
+                &mut state.controller,
+                price,
+                &mut state.users,
+                state.circulating_supply,
+                (self.token.total_supply - self.token.current_supply).value(),
+                self.options.precision(ValueKind::Balance),
+            )?);
+            report.controller_error = Some(
+                controller
+                    .error(price)
+                    .round_as(&self.options, ValueKind::Rate),
+            );
+            if report.supply_adjustment < TokenAmount::default() {
+                controller_burned = -report.supply_adjustment;
+                report.total_burned += controller_burned;
+                report.burn_rate = report.calculate_burn_rate(
+                    report.total_burned,
+                    Decimal::from(state.users.count()),
+                    UNROUNDED,
+                );
+            } else {
+                self.token.current_supply += report.supply_adjustment;
+            }
+        }
+
+        // The game rewards the active users, who spend tokens in its sinks
+        let mut game_burned = TokenAmount::default();
+        if let Some(economy) = &self.options.game_economy {
+            let flows = economy.play(
+                index as u64,
+                &mut state.users,
+                (self.token.total_supply - self.token.current_supply).value(),
+                self.options.precision(ValueKind::Balance),
+                &mut state.rng,
+            )?;
+            report.game_rewards = TokenAmount(flows.rewards);
+            report.game_sinks = TokenAmount(flows.spent);
+            self.token.current_supply += report.game_rewards;
+            report.sink_ratio = sink_ratio(
+                flows.rewards,
+                flows.spent,
+                self.options.precision(ValueKind::Rate),
+            );
+            game_burned = TokenAmount(flows.burned);
+            report.total_burned += game_burned;
+            report.burn_rate = report.calculate_burn_rate(
+                report.total_burned,
+                Decimal::from(state.users.count()),
+                UNROUNDED,
+            );
+        }
+        // The holders above the cap sell a share of their excess on the market, pushing the next price down
+        let mut price_drop = Decimal::default();
+        if let Some(cap) = &self.options.holder_cap {
+            let sales = cap.enforce(
+                index as u64,
+                &mut state.users,
+                state.circulating_supply,
+                self.options.precision(ValueKind::Balance),
+            );
+            report.capped_holders = sales.holders;
+            report.forced_sales = TokenAmount(sales.sold);
+            report.sell_volume += report.forced_sales;
+            price_drop = cap.price_drop(sales.sold, state.circulating_supply);
+        }
+
+        // At the end of a tax year, the users sell tokens to cover their tax, pushing the next price down
+        if let (Some(policy), Some(tax)) = (&self.options.tax_policy, &mut state.tax) {
+            if policy.is_year_end(index as u64) {
+                report.tax_sold = TokenAmount(tax.close_year(
+                    policy,
+                    &mut state.users,
+                    price,
+                    self.options.precision(ValueKind::Balance),
+                ));
+                price_drop = (price_drop
+                    + policy.price_drop(report.tax_sold.value(), state.circulating_supply))
+                .min(Decimal::ONE);
+            } else {
+                tax.record(&state.users, price, false);
+            }
+        }
+
+        // Every address is new in the first interval
+        report.new_addresses = match index {
+            0 => current_users,
+            _ => current_users.saturating_sub(previous_users),
+        };
+        report.net_inflow = (report.capital_inflow
+            - report.sell_volume.value_at(FiatAmount(price)))
+        .round_as(&self.options, ValueKind::Balance);
+        report.contributor_emission = TokenAmount(contributor_emission);
+        report.contributor_sold = TokenAmount(contributor_sold);
+        report.unlock_sold = TokenAmount(unlock_sold);
+        report.market_regime = state.regime;
+        report.network_fee_multiplier = network_fee_multiplier;
+        state.network_activity = Some(report.network_activity);
+        report.block_rewards = TokenAmount(block_rewards);
+        report.security_budget = report.calculate_security_budget(FiatAmount(price), UNROUNDED);
+        report.security_budget_below_threshold = self
+            .options
+            .security_budget_threshold
+            .is_some_and(|threshold| report.security_budget < threshold);
+        report.interval = current_date.timestamp_millis();
+        state.circulating_supply += (TokenAmount(unlocked)
+            + report.contributor_emission
+            + report.block_rewards
+            + report.game_rewards
+            + report.gauge_emission
+            + report.supply_adjustment.max(TokenAmount::default())
+            + report.total_new_tokens
+            - report.total_burned)
+            .value();
+        report.cumulative_dilution = report.calculate_dilution(
+            TokenAmount(state.initial_supply),
+            TokenAmount(state.circulating_supply),
+            UNROUNDED,
+        );
+        report.holder_value =
+            report.calculate_holder_value(&state.users, FiatAmount(price), UNROUNDED);
+        if self.options.distribution_per_interval && self.options.report_fields.distribution {
+            report.distribution = Some(DistributionAnalysis::new(
+                &state.users,
+                LORENZ_RESOLUTION,
+                self.options.precision(ValueKind::Rate),
+            ));
+        }
+        report.real_token_price = report.calculate_real_value(FiatAmount(price), UNROUNDED);
+        report.real_holder_value = report.calculate_real_value(report.holder_value, UNROUNDED);
+        self.token.ledger.record(
+            current_date,
+            SupplyEventKind::Burn,
+            (report.total_burned
+                - utility_burned
+                - subscription_burned
+                - nft_burned
+                - game_burned
+                - controller_burned)
+                .value(),
+            "transaction burn",
+        );
+        self.token.ledger.record(
+            current_date,
+            SupplyEventKind::Burn,
+            utility_burned.value(),
+            "utility burn",
+        );
+        self.token.ledger.record(
+            current_date,
+            SupplyEventKind::Burn,
+            subscription_burned.value(),
+            "subscription burn",
+        );
+        self.token.ledger.record(
+            current_date,
+            SupplyEventKind::Burn,
+            nft_burned.value(),
+            "nft mint burn",
+        );
+        self.token.ledger.record(
+            current_date,
+            SupplyEventKind::Mint,
+            report.game_rewards.value(),
+            "game rewards",
+        );
+        self.token.ledger.record(
+            current_date,
+            SupplyEventKind::Mint,
+            report.gauge_emission.value(),
+            "gauge emission",
+        );
+        self.token.ledger.record(
+            current_date,
+            SupplyEventKind::Mint,
+            report.supply_adjustment.max(TokenAmount::default()).value(),
+            "supply controller emission",
+        );
+        self.token.ledger.record(
+            current_date,
+            SupplyEventKind::Burn,
+            controller_burned.value(),
+            "supply controller burn",
+        );
+        self.token.ledger.record(
+            current_date,
+            SupplyEventKind::Burn,
+            game_burned.value(),
+            "game sink burn",
+        );
+        self.token.ledger.record(
+            current_date,
+            SupplyEventKind::Mint,
+            report.total_new_tokens.value(),
+            "inflation",
+        );
+
+        // The order book discovers the price during the interval
+        let context = IntervalContext { price, ..context };
+
+        self.compute_custom_metrics(&context, &state.users, &mut report);
+
+        self.options.round_report(&mut report);
+        self.options.report_fields.apply(&mut report);
+
+        for observer in &self.observers {
+            observer.on_interval(&context, &report);
+        }
+
+        self.interval_reports.push(report);
+
+        #[cfg(feature = "log")]
+        log::debug!("Interval processed: {}", time);
+
+        state.price =
+            (price * (Decimal::ONE - price_drop)).round_as(&self.options, ValueKind::Price);
+        state.price_impact = (state.price_impact * (Decimal::ONE - price_drop))
+            .round_as(&self.options, ValueKind::Rate);
+        state.index += 1;
+
+        Ok(())
+    }
+
+    /// Finish a run of the simulation: generate the final report and complete the simulation.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - State of the run, after the last interval.
+    pub(crate) fn finish_run(&mut self, mut state: RunState) {
+        let cohort_roi = state.roi.roi(
+            &state.users,
+            &self.token,
+            state.price,
+            self.options.precision(ValueKind::Balance),
+        );
+        let tax = state
+            .tax
+            .as_ref()
+            .map(|tax| tax.report(&state.users, self.options.precision(ValueKind::Balance)));
+        self.generate_final_report(std::mem::take(&mut state.users));
+        self.report.tax_records = tax;
+        self.report.retention_curve = Some(
+            state
+                .retention
+                .curve(self.options.precision(ValueKind::Rate)),
+        );
+        self.report.cohort_roi = cohort_roi;
+        self.options.report_fields.apply(&mut self.report);
+        self.end_run(state);
+        self.update_status(SimulationStatus::Completed);
+
+        for observer in &self.observers {
+            observer.on_complete(&self.report);
+        }
+
+        self.notify_webhook(None);
+
+        #[cfg(feature = "log")]
+        log::debug!("Simulation completed: {}", self.name);
+    }
+
+    /// Simulate trades for a given interval.
+    /// This will simulate trades for each user in the list and generate a report for the interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `users` - A list of users.
+    /// * `interval` - Duration of the interval, in hours.
+    /// * `price` - Price of the token in the interval.
+    ///
+    /// # Returns
+    ///
+    /// A report of the simulation results for the interval.
+    pub fn process_interval(
+        &self,
+        users: &mut UserStore,
+        interval: u64,
+        price: Decimal,
+    ) -> Result<SimulationReport, SimulationError> {
+        let context = self.interval_context(0, Utc::now(), interval, price)?;
+
+        self.engine()
+            .process_interval(self, &context, users, &mut rand::rng())
+            .map(|(report, _)| report)
+    }
+
+    /// Simulate trades for a given interval, keeping track of the users who traded.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - Context of the interval.
+    /// * `users` - A list of users.
+    /// * `rng` - Random number generator.
+    ///
+    /// # Returns
+    ///
+    /// A report of the simulation results for the interval, and the IDs of the users with an executed trade.
+    pub(crate) fn trade_interval<R: Rng + ?Sized>(
+        &self,
+        context: &IntervalContext,
+        users: &mut UserStore,
+        rng: &mut R,
+    ) -> Result<(SimulationReport, HashSet<Uuid>), SimulationError> {
+        let interval = context.interval;
+        let price = context.price;
+        let network_fee = context.fees.network_fee;
+        let mut total_burned = Decimal::default();
+        let mut total_new_tokens = Decimal::default();
+        let mut total_slippage = Decimal::default();
+        let mut report = SimulationReport::default();
+
+        // Venues listed so far share the volume of this interval
+        let mut venues = self
+            .options
+            .venues
+            .as_ref()
+            .map(|config| VenueBook::new(config, self.interval_reports.len() as u64));
+
+        // Market makers quote the order book around the price at the start of the interval
+        let mut order_book = match &self.options.market_model {
+            MarketModel::OrderBook(config) => Some((config, OrderBook::new(config, price))),
+            MarketModel::Amm => None,
+        };
+        let mut maker_fills = vec![];
+
+        // The momentum of the price up to this interval shifts the buy probability
+        let sentiment = self
+            .options
+            .sentiment
+            .as_ref()
+            .zip(self.sentiment_index(price));
+
+        // Move users through the lifecycle once per interval
+        if let Some(lifecycle) = &self.options.lifecycle {
+            for state in users.states.iter_mut() {
+                *state = state.next(lifecycle, rng);
+            }
+        }
+
+        // Trade rounds are decoupled from the interval length when configured
+        let rounds = self.options.rounds_per_interval.unwrap_or(interval);
+
+        // Tokens bought and sold by each user, to tell round trips from real volume
+        let mut flows = vec![(Decimal::default(), Decimal::default()); users.len()];
+        let mut traded = vec![0; users.len()];
+
+        for _ in 0..rounds {
+            for index in 0..users.len() {
+                // Skip users with zero balance and users who are not active
+                if !users.can_trade(index) {
+                    continue;
+                }
+                let behaviour = users.behaviours[index];
+                let profile = self.options.behaviour_profile(behaviour);
+                let weight = users.weights[index];
+                let mut trade_probability = match &self.options.demand_series {
+                    Some(series) => series.scale(profile.trade_probability, context.index),
+                    None => profile.trade_probability,
+                };
+                if let Some(rates) = &self.options.interest_rates {
+                    trade_probability = rates.scale(trade_probability, behaviour, context.index);
+                }
+
+                // Cohorts draw how many of their members trade, individual agents trade or not
+                let traders = match weight {
+                    1 => rng.random_bool(trade_probability) as u64,
+                    _ => sample_members(weight, trade_probability, rng),
+                };
+                report.failed_trades += weight - traders;
+
+                if traders > 0 {
+                    // Simulate a successful trade and randomize the fraction of the user's balance, depending on the behaviour
+                    let (min_fraction, max_fraction) = profile.trade_size;
+                    let trade_fraction = rng.random_range(min_fraction..max_fraction);
+                    let max_trade_amount = users.balances[index]
+                        .to_f64()
+                        .ok_or(SimulationError::InvalidDecimal)?
+                        * trade_fraction;
+
+                    // Ensure the range is valid
+                    if max_trade_amount > 0.0 {
+                        let mut trade_amount =
+                            Decimal::from_f64(rng.random_range(0.0..max_trade_amount))
+                                .ok_or(SimulationError::InvalidDecimal)?
+                                .round_as(&self.options, ValueKind::Balance);
+
+                        // A cohort trades the aggregate amount of its trading members
+                        if weight > 1 {
+                            trade_amount = (trade_amount * Decimal::from(traders)
+                                / Decimal::from(weight))
+                            .round_as(&self.options, ValueKind::Balance);
+                        }
+
+                        let buy_probability = sentiment
+                            .map_or(profile.buy_probability, |(model, index)| {
+                                model.bias(profile.buy_probability, index)
+                            });
+                        let side = if rng.random_bool(buy_probability) {
+                            TradeSide::Buy
+                        } else {
+                            TradeSide::Sell
+                        };
+                        // Users holding for several intervals let most of their drawn sells pass
+                        if side == TradeSide::Sell
+                            && profile.hold_intervals > 1
+                            && !rng.random_bool(1.0 / profile.hold_intervals as f64)
+                        {
+                            continue;
+                        }
+                        let venue = venues.as_ref().map_or(0, |book| book.pick(rng));
+                        let mut slippage = venues
+                            .as_ref()
+                            .map_or(Decimal::default(), |book| {
+                                book.slippage(venue, trade_amount)
+                            })
+                            .round_as(&self.options, ValueKind::Rate);
+
+                        // On the order book, on-chain volume is matched against resting orders instead
+                        let mut matching = order_book.as_mut().filter(|_| venue == 0);
+                        if let Some((config, book)) = matching.as_mut() {
+                            let mid_price = book.mid_price();
+
+                            if rng.random_bool(config.limit_order_probability) {
+                                // Post a passive limit order a few ticks away from the mid price, filled by later market orders
+                                let ticks =
+                                    Decimal::from(rng.random_range(1..=config.levels.max(1)));
+                                let limit_price = match side {
+                                    TradeSide::Buy => mid_price - book.tick_size() * ticks,
+                                    TradeSide::Sell => mid_price + book.tick_size() * ticks,
+                                };
+                                if limit_price > Decimal::default() {
+                                    book.place(Order {
+                                        user_id: users.ids[index],
+                                        side,
+                                        price: limit_price,
+                                        amount: trade_amount,
+                                    });
+                                }
+                                continue;
+                            }
+
+                            let quote = book.quote(side, trade_amount);
+                            if quote.amount.is_zero() {
+                                report.failed_trades += traders;
+                                continue;
+                            }
+                            trade_amount = quote.amount.round_as(&self.options, ValueKind::Balance);
+                            slippage = quote
+                                .slippage(mid_price)
+                                .round_as(&self.options, ValueKind::Rate);
+                        }
+
+                        // Users abort trades that move the price more than they accept
+                        if let Some(tolerance) = self.options.slippage_tolerance {
+                            if slippage > tolerance.value() {
+                                report.aborted_trades += traders;
+                                report.failed_trades += traders;
+                                continue;
+                            }
+                        }
+
+                        // On-chain trades pay a network fee, sampled from the recent gas prices when available,
+                        // and users skip the trades whose fee exceeds the traded amount
+                        let gas_fee = match (venue, context.fees.network_fee_samples.as_slice()) {
+                            (0, []) => network_fee,
+                            (0, samples) => samples[rng.random_range(0..samples.len())],
+                            _ => Decimal::default(),
+                        };
+                        if gas_fee > trade_amount {
+                            report.priced_out_trades += traders;
+                            report.failed_trades += traders;
+                            continue;
+                        }
+
+                        if let Some((_, book)) = matching {
+                            maker_fills.extend(book.execute(side, trade_amount).fills);
+                        }
+
+                        let mut trade = Trade {
+                            user_id: users.ids[index],
+                            side,
+                            amount: trade_amount,
+                            slippage,
+                            ..Default::default()
+                        };
+                        total_slippage += slippage;
+
+                        let amount = TokenAmount(trade_amount);
+                        match side {
+                            TradeSide::Buy => {
+                                users.balances[index] += trade_amount;
+                                report.profit_loss -= amount;
+                                report.buy_volume += amount;
+                            }
+                            TradeSide::Sell => {
+                                users.balances[index] -= trade_amount;
+                                report.profit_loss += amount;
+                                report.sell_volume += amount;
+                            }
+                        }
+                        report.successful_trades += traders;
+                        traded[index] = traded[index].max(traders);
+
+                        if behaviour.is_organic() {
+                            report.organic_volume += amount;
+                            match side {
+                                TradeSide::Buy => flows[index].0 += trade_amount,
+                                TradeSide::Sell => flows[index].1 += trade_amount,
+                            }
+                        } else {
+                            report.inorganic_volume += amount;
+                        }
+
+                        // Large on-chain trades on the AMM can be sandwiched by the MEV agent
+                        if let (Some(mev), MarketModel::Amm, 0) =
+                            (&self.options.mev, &self.options.market_model, venue)
+                        {
+                            trade.mev_extracted = mev
+                                .extract(
+                                    trade_amount,
+                                    slippage,
+                                    self.options.slippage_tolerance.map(Percentage::value),
+                                    rng,
+                                )
+                                .round_as(&self.options, ValueKind::Balance);
+
+                            if !trade.mev_extracted.is_zero() {
+                                users.balances[index] = (users.balances[index]
+                                    - trade.mev_extracted)
+                                    .max(Decimal::default());
+                                report.mev_extracted += TokenAmount(trade.mev_extracted);
+                                report.sandwiched_trades += traders;
+
+                                if rng.random_bool(mev.victim_churn_probability) {
+                                    users.states[index] = UserState::Churned;
+                                }
+                            }
+                        }
+
+                        trade.burned = self
+                            .token
+                            .calculate_burn(amount)
+                            .round_as(&self.options, ValueKind::Balance)
+                            .value();
+                        users.balances[index] -= trade.burned;
+                        total_burned += trade.burned;
+
+                        trade.minted = self
+                            .token
+                            .calculate_mint(amount)
+                            .round_as(&self.options, ValueKind::Balance)
+                            .value();
+                        users.balances[index] += trade.minted;
+                        total_new_tokens += trade.minted;
+
+                        if venue > 0 {
+                            // Trades on an external exchange pay its fee instead of the on-chain fees
+                            if let Some(book) = &venues {
+                                let (name, fee) = book.venue(venue);
+                                trade.venue = Some(name.to_string());
+                                trade.fee = Percentage(fee)
+                                    .of(amount)
+                                    .round_as(&self.options, ValueKind::Balance)
+                                    .value();
+                                users.balances[index] -= trade.fee;
+                            }
+                        } else {
+                            if let Some(fee) = self.options.transaction_fee_percentage {
+                                let fee =
+                                    fee.of(amount).round_as(&self.options, ValueKind::Balance);
+                                trade.fee = fee.value();
+                                users.balances[index] -= trade.fee;
+                                report.fees += fee;
+                            }
+
+                            if !gas_fee.is_zero() {
+                                trade.gas_fee = gas_fee;
+                                users.balances[index] -= trade.gas_fee;
+                                report.gas_spent += TokenAmount(gas_fee);
+                            }
+                        }
+
+                        if let Some(book) = &mut venues {
+                            book.record(venue, side, trade_amount, trade.fee);
+                        }
+
+                        let tax = self
+                            .token
+                            .calculate_tax(side, amount)
+                            .round_as(&self.options, ValueKind::Balance);
+                        trade.tax = tax.value();
+                        if !tax.is_zero() {
+                            users.balances[index] -= trade.tax;
+                            report.tax_revenue += tax;
+
+                            for (destination, amount) in self.token.route_tax(tax) {
+                                *report.tax_distribution.entry(destination).or_default() +=
+                                    amount.value();
+                                // The burned share leaves the supply like the burn of the trade
+                                if destination == TaxDestination::Burn {
+                                    total_burned += amount.value();
+                                }
+                            }
+                        }
+
+                        if !self.observers.is_empty() {
+                            let user = users.user(index);
+                            for observer in &self.observers {
+                                observer.on_trade(&user, &trade);
+                            }
+                        }
+                    } else {
+                        report.failed_trades += traders;
+                    }
+                }
+            }
+        }
+
+        report.total_burned = TokenAmount(total_burned);
+        report.total_new_tokens = TokenAmount(total_new_tokens);
+
+        report.average_slippage = Percentage(
+            total_slippage
+                .checked_div(Decimal::from(report.successful_trades))
+                .unwrap_or_default(),
+        )
+        .round_as(&self.options, ValueKind::Rate);
+
+        let round_trips: TokenAmount = flows
+            .iter()
+            .map(|(bought, sold)| TokenAmount(Decimal::TWO * bought.min(sold)))
+            .sum();
+        report.real_volume = report.organic_volume - round_trips;
+        report.active_addresses = traded.iter().sum();
+
+        if let Some((_, book)) = &order_book {
+            // Settle the resting orders of users filled by market orders; market makers are not users
+            let index: HashMap<Uuid, usize> = users
+                .ids
+                .iter()
+                .enumerate()
+                .map(|(index, id)| (*id, index))
+                .collect();
+            for fill in maker_fills.iter() {
+                if let Some(balance) = index
+                    .get(&fill.maker_id)
+                    .map(|&index| &mut users.balances[index])
+                {
+                    match fill.side {
+                        TradeSide::Buy => *balance += fill.amount,
+                        TradeSide::Sell => {
+                            *balance = (*balance - fill.amount).max(Decimal::default())
+                        }
+                    }
+                }
+            }
+
+            report.token_price =
+                FiatAmount(book.mid_price()).round_as(&self.options, ValueKind::Price);
+        }
+
+        if let Some(book) = &venues {
+            report.venues = book.reports(price, self.options.precision(ValueKind::Balance));
+        }
+
+        self.generate_interval_report(users, &mut report, interval);
+
+        let active = users
+            .ids
+            .iter()
+            .zip(traded)
+            .filter(|(_, traded)| *traded > 0)
+            .map(|(id, _)| *id)
+            .collect();
+
+        Ok((report, active))
+    }
+
+    /// Generate the interval report for the simulation.
+    ///
+    /// # Arguments
+    ///
+    /// * `users` - A list of users.
+    /// * `report` - The simulation report for the interval.
+    /// * `interval` - Duration of the interval.
+    pub fn generate_interval_report(
+        &self,
+        users: &UserStore,
+        report: &mut SimulationReport,
+        interval: u64,
     ) {
         #[cfg(feature = "log")]
         log::debug!("Generating interval report for simulation: {}", self.name);
 
-        let decimal_precision = self.options.decimal_precision;
-
         report.trades = report.successful_trades + report.failed_trades;
         report.liquidity = report.calculate_liquidity(
             Decimal::new(report.trades as i64, 0),
             Decimal::new(interval as i64, 0),
-            decimal_precision,
+            UNROUNDED,
         );
-        report.adoption_rate = report.calculate_adoption_rate(users, decimal_precision);
+        report.adoption_rate = report.calculate_adoption_rate(users, UNROUNDED);
         report.burn_rate = report.calculate_burn_rate(
             report.total_burned,
-            Decimal::new(users.len() as i64, 0),
-            decimal_precision,
+            Decimal::from(users.count()),
+            UNROUNDED,
         );
-        report.user_retention = report.calculate_user_retention(users, decimal_precision);
+        report.user_retention = report.calculate_user_retention(users, UNROUNDED);
+        report.active_users = report.calculate_active_users(users);
+        report.churned_users = report.calculate_churned_users(users);
+        report.holders = report.calculate_holders(users);
+        report.final_user_count = users.count();
+        report.peak_user_count = report.final_user_count;
         report.market_volatility = self.options.market_volatility;
         report.network_activity = report.trades / interval;
         report.inflation_rate = report.calculate_inflation_rate(
             report.total_new_tokens,
-            Decimal::new(users.len() as i64, 0),
-            decimal_precision,
+            Decimal::from(users.count()),
+            UNROUNDED,
         );
 
         #[cfg(feature = "log")]
@@ -439,20 +1953,21 @@ impl Simulation {
     /// # Arguments
     ///
     /// * `users` - A list of users.
-    pub fn generate_final_report(&mut self, users: Vec<User>) {
+    pub fn generate_final_report(&mut self, users: UserStore) {
         #[cfg(feature = "log")]
         log::debug!("Generating final report for simulation: {}", self.name);
 
         let mut report = SimulationReport {
             market_volatility: self.options.market_volatility,
+            token_metadata: self.token.metadata.clone(),
             ..Default::default()
         };
 
-        let mut total_burned = Decimal::default();
-        let mut total_new_tokens = Decimal::default();
-        let mut total_token_price = Decimal::default();
-        let decimal_precision = self.options.decimal_precision;
-        let total_users = Decimal::new(self.options.total_users as i64, 0);
+        let mut total_burned = TokenAmount::default();
+        let mut total_new_tokens = TokenAmount::default();
+        let mut total_token_price = FiatAmount::default();
+        let mut total_real_token_price = FiatAmount::default();
+        let mut total_slippage = Percentage::default();
 
         #[cfg(feature = "log")]
         log::debug!("Total interval reports: {}", self.interval_reports.len());
@@ -462,28 +1977,188 @@ impl Simulation {
             report.trades += result.trades;
             report.successful_trades += result.successful_trades;
             report.failed_trades += result.failed_trades;
-
-            total_burned += result.burn_rate * total_users;
-            total_new_tokens += result.inflation_rate * total_users;
+            report.aborted_trades += result.aborted_trades;
+            report.priced_out_trades += result.priced_out_trades;
+            report.active_addresses += result.active_addresses;
+            report.daily_active_users += result.daily_active_users;
+            report.weekly_active_users += result.weekly_active_users;
+            report.monthly_active_users += result.monthly_active_users;
+            report.new_addresses += result.new_addresses;
+            report.mev_extracted += result.mev_extracted;
+            report.sandwiched_trades += result.sandwiched_trades;
+            total_slippage += result.average_slippage * Decimal::from(result.successful_trades);
+
+            total_burned += result.total_burned;
+            total_new_tokens += result.total_new_tokens;
+            report.peak_user_count = report.peak_user_count.max(result.peak_user_count);
             report.liquidity += result.liquidity;
             report.adoption_rate += result.adoption_rate;
             report.user_retention += result.user_retention;
             total_token_price += result.token_price;
+            total_real_token_price += result.real_token_price;
+            report.buy_volume += result.buy_volume;
+            report.sell_volume += result.sell_volume;
+            report.organic_volume += result.organic_volume;
+            report.inorganic_volume += result.inorganic_volume;
+            report.real_volume += result.real_volume;
+            report.contributor_emission += result.contributor_emission;
+            report.contributor_sold += result.contributor_sold;
+            report.unlock_sold += result.unlock_sold;
+            report.fees += result.fees;
+            report.gas_spent += result.gas_spent;
+            report.capital_inflow += result.capital_inflow;
+            report.net_inflow += result.net_inflow;
+            report.block_rewards += result.block_rewards;
+            report.security_budget += result.security_budget;
+            report.security_budget_below_threshold |= result.security_budget_below_threshold;
+            report.tax_revenue += result.tax_revenue;
+            report.utility_consumed += result.utility_consumed;
+            report.recurring_revenue += result.recurring_revenue;
+            report.recurring_revenue_fiat += result.recurring_revenue_fiat;
+            report.nft_mints += result.nft_mints;
+            report.nft_volume += result.nft_volume;
+            report.nft_royalties += result.nft_royalties;
+            report.game_rewards += result.game_rewards;
+            report.game_sinks += result.game_sinks;
+            report.gauge_emission += result.gauge_emission;
+            report.shortfall_losses += result.shortfall_losses;
+            report.insurance_payouts += result.insurance_payouts;
+            report.supply_adjustment += result.supply_adjustment;
+            report.holder_fees += result.holder_fees;
+            report.treasury_fees += result.treasury_fees;
+            report.users_drained += result.users_drained;
+            report.tax_sold += result.tax_sold;
+            report.forced_sales += result.forced_sales;
+
+            for (destination, amount) in result.tax_distribution.iter() {
+                *report.tax_distribution.entry(*destination).or_default() += amount;
+            }
         }
 
-        let total_trades = Decimal::new(report.trades as i64, 0);
+        // Average per user over the actual final user count, which includes adoption growth
+        let final_user_count = Decimal::from(users.count());
         let total_intervals = Decimal::new(self.interval_reports.len() as i64, 0);
 
-        report.liquidity = (report.liquidity / total_intervals).round_dp(decimal_precision);
-        report.adoption_rate = (report.adoption_rate / total_intervals).round_dp(decimal_precision);
+        report.liquidity =
+            (report.liquidity / total_intervals).round_as(&self.options, ValueKind::Rate);
+        report.adoption_rate =
+            (report.adoption_rate / total_intervals).round_as(&self.options, ValueKind::Rate);
         report.user_retention =
-            (report.user_retention / total_intervals).round_dp(decimal_precision);
-        report.burn_rate =
-            report.calculate_burn_rate(total_burned, total_trades, self.options.decimal_precision);
-        report.inflation_rate = (total_new_tokens / total_trades).round_dp(decimal_precision);
+            (report.user_retention / total_intervals).round_as(&self.options, ValueKind::Rate);
+        report.total_burned = total_burned;
+        report.total_new_tokens = total_new_tokens;
+        report.burn_rate = report.calculate_burn_rate(total_burned, final_user_count, UNROUNDED);
+        report.inflation_rate =
+            report.calculate_inflation_rate(total_new_tokens, final_user_count, UNROUNDED);
         report.network_activity = report.trades / self.options.duration;
-        report.token_price = (total_token_price / total_intervals).round_dp(decimal_precision);
-        report.users = Some(users);
+        report.token_price =
+            (total_token_price / total_intervals).round_as(&self.options, ValueKind::Price);
+        report.real_token_price =
+            (total_real_token_price / total_intervals).round_as(&self.options, ValueKind::Price);
+        if let Some(last) = self.interval_reports.last() {
+            report.cumulative_dilution = last.cumulative_dilution;
+            report.utility_locked = last.utility_locked;
+            report.subscribers = last.subscribers;
+            report.insurance_fund = last.insurance_fund;
+            report.coverage_ratio = last.coverage_ratio;
+            report.yield_gap = last.yield_gap;
+            report.risk_free_rate = last.risk_free_rate;
+            report.sentiment = last.sentiment;
+            report.adoption_multiplier = last.adoption_multiplier;
+            report.capped_holders = last.capped_holders;
+            report.controller_error = last.controller_error.map(|_| {
+                let errors: Vec<Decimal> = self
+                    .interval_reports
+                    .iter()
+                    .filter_map(|result| result.controller_error)
+                    .collect();
+                (errors.iter().map(|error| error.abs()).sum::<Decimal>()
+                    / Decimal::from(errors.len()))
+                .round_as(&self.options, ValueKind::Rate)
+            });
+            report.sink_ratio = sink_ratio(
+                report.game_rewards.value(),
+                report.game_sinks.value(),
+                self.options.precision(ValueKind::Rate),
+            );
+            report.holder_value =
+                report.calculate_holder_value(&users, last.token_price, UNROUNDED);
+            report.real_holder_value = report.calculate_real_value(report.holder_value, UNROUNDED);
+        }
+        report.active_users = report.calculate_active_users(&users);
+        report.churned_users = report.calculate_churned_users(&users);
+        report.holders = report.calculate_holders(&users);
+        if self.options.report_fields.distribution {
+            report.distribution = Some(DistributionAnalysis::new(
+                &users,
+                LORENZ_RESOLUTION,
+                self.options.precision(ValueKind::Rate),
+            ));
+        }
+        let intervals = self.interval_reports.len() as u64;
+        report.active_addresses = report
+            .active_addresses
+            .checked_div(intervals)
+            .unwrap_or_default();
+        report.daily_active_users = report
+            .daily_active_users
+            .checked_div(intervals)
+            .unwrap_or_default();
+        report.weekly_active_users = report
+            .weekly_active_users
+            .checked_div(intervals)
+            .unwrap_or_default();
+        report.monthly_active_users = report
+            .monthly_active_users
+            .checked_div(intervals)
+            .unwrap_or_default();
+        report.stickiness = stickiness(
+            Decimal::from(report.daily_active_users),
+            Decimal::from(report.monthly_active_users),
+            UNROUNDED,
+        );
+        report.final_user_count = users.count();
+        report.peak_user_count = report.peak_user_count.max(report.final_user_count);
+        report.average_slippage = Percentage(
+            total_slippage
+                .value()
+                .checked_div(Decimal::from(report.successful_trades))
+                .unwrap_or_default(),
+        )
+        .round_as(&self.options, ValueKind::Rate);
+        if self.options.report_fields.interval_distribution {
+            report.interval_distribution = EnsembleMetric::ALL
+                .iter()
+                .map(|metric| {
+                    let values = self
+                        .interval_reports
+                        .iter()
+                        .map(|result| metric.value(result))
+                        .collect();
+
+                    MetricSummary::from_values(*metric, values)
+                })
+                .collect();
+        }
+        report.venues = aggregate_venues(
+            &self.interval_reports,
+            self.options.precision(ValueKind::Balance),
+        );
+        report.gauges = aggregate_gauges(
+            &self.interval_reports,
+            self.options.precision(ValueKind::Balance),
+        );
+        // Fees are resolved before the run, so the context of the end of the run can be built
+        let interval = self.get_interval();
+        let intervals = self.interval_reports.len() as u64;
+        let date = Utc::now() + chrono::Duration::hours((intervals * interval) as i64);
+        if let Ok(context) =
+            self.interval_context(intervals, date, interval, report.token_price.value())
+        {
+            self.compute_custom_metrics(&context, &users, &mut report);
+        }
+        self.options.round_report(&mut report);
+        report.users = Some(users.into());
 
         self.report = report;
 
@@ -497,17 +2172,21 @@ impl Simulation {
     ///
     /// The duration of the simulation interval.
     pub fn get_interval(&self) -> u64 {
-        match self.options.interval_type {
-            SimulationInterval::Hourly => 1,
-            SimulationInterval::Daily => 24,
-            SimulationInterval::Weekly => 24 * 7,
-            SimulationInterval::Monthly => 24 * 30,
-        }
+        self.options.interval_type.hours()
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::{
+        BehaviourProfile, ContributorEmission, EthereumFee, ExchangeListing, HalvingSchedule,
+        MevAgent, OrderBookConfig, PopulationModel, ReportFields, RoundingPolicy,
+        SimulationTransactionFee, TokenMetadata, UnlockEvent, UserBehaviour, UserLifecycle,
+        VenueConfig, DEX_VENUE, SCHEMA_VERSION,
+    };
+
     use super::*;
 
     fn setup() -> Simulation {
@@ -518,6 +2197,7 @@ mod tests {
             .unwrap();
 
         Simulation {
+            schema_version: SCHEMA_VERSION,
             id: Uuid::new_v4(),
             name: "Test Simulation".to_string(),
             token,
@@ -532,11 +2212,56 @@ mod tests {
                 interval_type: SimulationInterval::Daily,
                 adoption_rate: None,
                 valuation_model: Some(ValuationModel::Exponential(0.1)),
+                behaviour_mix: None,
+                lifecycle: None,
+                user_id_strategy: UserIdStrategy::Random,
+                security_budget_threshold: None,
+                rounds_per_interval: None,
+                transaction_fee: None,
+                capital_inflow: None,
+                venues: None,
+                slippage_tolerance: None,
+                market_model: MarketModel::Amm,
+                mev: None,
+                report_fields: ReportFields::all(),
+                population_model: PopulationModel::Agents,
+                webhook: None,
+                seed: None,
+                distribution_per_interval: false,
+                rounding: RoundingPolicy::default(),
+                market_regime: None,
+                regime_switching: None,
+                holder_balances: None,
+                price_feed: None,
+                fee_congestion: None,
+                demand_series: None,
+                utility: None,
+                subscription: None,
+                nft_market: None,
+                game_economy: None,
+                gauge_voting: None,
+                insurance_fund: None,
+                supply_controller: None,
+                fee_switch: None,
+                competitor: None,
+                interest_rates: None,
+                tax_policy: None,
+                holder_cap: None,
+                behaviour_profiles: Default::default(),
+                sentiment: None,
+                network_effects: None,
             },
+            initial_token: None,
+            effective_options: None,
             interval_reports: vec![],
             report: SimulationReport::default(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            observers: vec![],
+            metric_plugins: vec![],
+            webhook_transport: None,
+            engine: None,
+            rng: None,
         }
     }
 
@@ -558,6 +2283,26 @@ mod tests {
         assert_eq!(builder, TokenBuilder::new());
     }
 
+    #[test]
+    fn test_fork() {
+        let mut simulation = setup();
+        simulation.description = Some("Base case".to_string());
+
+        let fork = simulation
+            .fork()
+            .map_options(|options| options.adoption_rate = Some(Decimal::new(2, 1)))
+            .build()
+            .unwrap();
+
+        assert_ne!(fork.id, simulation.id);
+        assert_eq!(fork.status, SimulationStatus::Pending);
+        assert_eq!(fork.name, simulation.name);
+        assert_eq!(fork.description, simulation.description);
+        assert_eq!(fork.token, simulation.token);
+        assert_eq!(fork.options.adoption_rate, Some(Decimal::new(2, 1)));
+        assert_eq!(fork.options.total_users, simulation.options.total_users);
+    }
+
     #[test]
     fn test_get_interval() {
         let daily_simulation = setup();
@@ -598,7 +2343,7 @@ mod tests {
     #[test]
     fn test_run_with_airdrop() {
         let mut simulation = setup();
-        simulation.token.airdrop_percentage = Some(Decimal::new(10, 0));
+        simulation.token.airdrop_percentage = Some(Percentage(Decimal::new(10, 0)));
 
         simulation.run().unwrap();
 
@@ -608,28 +2353,811 @@ mod tests {
     }
 
     #[test]
-    fn test_calculate_valuation_linear() {
+    fn test_run_reports_real_metrics() {
         let mut simulation = setup();
-        simulation.token.initial_price = Decimal::new(1, 2);
-        simulation.options.valuation_model = Some(ValuationModel::Linear);
+        simulation.options.duration = 5;
+        simulation.token.contributor_emission = Some(ContributorEmission {
+            amount_per_interval: Decimal::new(10_000, 0),
+            sell_through: Decimal::default(),
+        });
 
-        let token = &simulation.token;
-        let users = 99;
-        let valuation = simulation.calculate_valuation(token, users);
+        simulation.run().unwrap();
 
-        assert_eq!(valuation, Decimal::new(99, 2));
+        let last = simulation.interval_reports.last().unwrap();
+        assert!(last.cumulative_dilution > Percentage::default());
+        assert!(last.real_token_price < last.token_price);
+        assert!(last.real_holder_value < last.holder_value);
+        assert_eq!(
+            simulation.report.cumulative_dilution,
+            last.cumulative_dilution
+        );
+        assert!(simulation.report.real_holder_value < simulation.report.holder_value);
     }
 
     #[test]
-    fn test_calculate_valuation_exponential() {
+    fn test_run_with_adoption_tracks_user_count() {
         let mut simulation = setup();
-        simulation.options.valuation_model = Some(ValuationModel::Exponential(2.0));
+        simulation.options.duration = 5;
+        simulation.options.adoption_rate = Some(Decimal::new(1, 1));
 
-        let token = &simulation.token;
-        let users = 100;
-        let valuation = simulation.calculate_valuation(token, users);
+        simulation.run().unwrap();
 
-        assert_eq!(valuation, Decimal::new(1, 0));
+        let report = &simulation.report;
+        assert!(report.final_user_count > simulation.options.total_users);
+        assert_eq!(
+            report.final_user_count,
+            simulation.interval_reports.last().unwrap().final_user_count
+        );
+        assert_eq!(report.peak_user_count, report.final_user_count);
+    }
+
+    #[test]
+    fn test_run_tracks_rolling_active_users() {
+        let mut simulation = setup();
+        simulation.options.interval_type = SimulationInterval::Hourly;
+        simulation.options.duration = 48;
+        simulation.options.user_id_strategy = UserIdStrategy::Sequential;
+
+        simulation.run().unwrap();
+
+        // Sequential IDs are stable across intervals, so users are counted once per window
+        for report in simulation.interval_reports.iter() {
+            assert!(report.active_addresses <= report.daily_active_users);
+            assert!(report.daily_active_users <= report.weekly_active_users);
+            assert!(report.weekly_active_users <= report.monthly_active_users);
+            assert!(report.monthly_active_users <= simulation.options.total_users);
+        }
+
+        let report = &simulation.report;
+        assert!(report.stickiness > Decimal::default());
+        assert!(report.stickiness <= Decimal::ONE);
+    }
+
+    #[test]
+    fn test_run_with_retention_curve() {
+        let mut simulation = setup();
+        simulation.options.user_id_strategy = UserIdStrategy::Sequential;
+        simulation.options.lifecycle = Some(UserLifecycle {
+            active_to_dormant: 0.2,
+            dormant_to_churned: 0.5,
+            dormant_to_active: 0.1,
+        });
+
+        simulation.run().unwrap();
+
+        let curve = simulation.report.retention_curve.as_ref().unwrap();
+        assert_eq!(curve.initial().len() as u64, simulation.options.duration);
+        assert!(curve
+            .average(4)
+            .iter()
+            .all(|retention| *retention <= Decimal::new(100, 0)));
+        assert!(simulation.interval_reports[0].retention_curve.is_none());
+    }
+
+    #[test]
+    fn test_run_summarizes_interval_metrics() {
+        let mut simulation = setup();
+
+        simulation.run().unwrap();
+
+        let prices: Vec<Decimal> = simulation
+            .interval_reports
+            .iter()
+            .map(|report| report.token_price.value())
+            .collect();
+        let summary = simulation
+            .report
+            .interval_summary(EnsembleMetric::TokenPrice)
+            .unwrap();
+
+        assert_eq!(summary.min, prices.iter().copied().min().unwrap());
+        assert_eq!(summary.max, prices.iter().copied().max().unwrap());
+        assert!(summary.min <= summary.p50 && summary.p50 <= summary.p95);
+        assert_eq!(
+            simulation.report.interval_distribution.len(),
+            EnsembleMetric::ALL.len()
+        );
+    }
+
+    #[test]
+    fn test_run_with_report_fields() {
+        let mut simulation = setup();
+        simulation.options.report_fields = ReportFields {
+            interval_distribution: true,
+            ..ReportFields::none()
+        };
+
+        simulation.run().unwrap();
+
+        assert!(simulation.report.users.is_none());
+        assert!(simulation.report.retention_curve.is_none());
+        assert!(!simulation.report.interval_distribution.is_empty());
+    }
+
+    #[test]
+    fn test_run_with_cohorts() {
+        let mut simulation = setup();
+        simulation.options.duration = 5;
+        simulation.options.total_users = 1_000_000;
+        simulation.options.population_model = PopulationModel::Cohorts(10_000);
+
+        simulation.run().unwrap();
+
+        // Reports count the represented users, not the cohorts
+        let report = &simulation.report;
+        assert_eq!(report.users.as_ref().unwrap().len(), 100);
+        assert_eq!(report.final_user_count, 1_000_000);
+        assert_eq!(report.trades, 1_000_000 * 24 * 5);
+        assert!(report.successful_trades > 0);
+        assert!(report.active_addresses > 100);
+        assert!(simulation
+            .interval_reports
+            .iter()
+            .all(|interval| interval.active_addresses <= interval.final_user_count));
+    }
+
+    #[test]
+    fn test_run_conserves_supply_with_adoption() {
+        let mut simulation = setup();
+        simulation.options.duration = 10;
+        simulation.options.adoption_rate = Some(Decimal::new(2, 1));
+
+        simulation.run().unwrap();
+
+        // The balances of the joining users are issued and recorded, not created out of nothing
+        let held = simulation
+            .report
+            .users
+            .iter()
+            .flatten()
+            .map(|user| user.balance)
+            .sum::<Decimal>();
+        let report = &simulation.report;
+        assert_eq!(
+            held + (report.sell_volume - report.buy_volume).value(),
+            simulation.token.initial_supply().value() + simulation.token.ledger.net_change()
+        );
+        assert!(simulation.token.current_supply <= simulation.token.total_supply);
+        assert!(report.cumulative_dilution > Percentage::default());
+    }
+
+    #[test]
+    fn test_run_tracks_addresses() {
+        let mut simulation = setup();
+        simulation.options.duration = 5;
+        simulation.options.adoption_rate = Some(Decimal::new(1, 1));
+
+        simulation.run().unwrap();
+
+        // New addresses add up to the final user count
+        let report = &simulation.report;
+        assert_eq!(report.new_addresses, report.final_user_count);
+        assert!(report.holders <= report.final_user_count);
+        assert!(report.active_addresses > 0);
+        assert!(simulation
+            .interval_reports
+            .iter()
+            .all(|interval| interval.active_addresses <= interval.final_user_count));
+    }
+
+    #[test]
+    fn test_run_with_burn_and_inflation() {
+        let mut simulation = setup();
+        simulation.token.burn_rate = Some(Decimal::new(1, 2));
+        simulation.token.inflation_rate = Some(Decimal::new(1, 2));
+
+        simulation.run().unwrap();
+
+        let total_burned: TokenAmount = simulation
+            .interval_reports
+            .iter()
+            .map(|report| report.total_burned)
+            .sum();
+        let final_user_count = Decimal::new(simulation.report.final_user_count as i64, 0);
+
+        assert!(total_burned > TokenAmount::default());
+        assert_eq!(simulation.report.total_burned, total_burned);
+        assert_eq!(
+            simulation.report.burn_rate,
+            (total_burned.value() / final_user_count).round_dp(4)
+        );
+        assert!(simulation.report.inflation_rate > Decimal::default());
+    }
+
+    #[test]
+    fn test_run_with_behaviour_mix() {
+        let mut simulation = setup();
+        simulation.options.behaviour_mix = Some(BTreeMap::from([
+            (UserBehaviour::Whale, 1.0),
+            (UserBehaviour::Bot, 1.0),
+        ]));
+
+        simulation.run().unwrap();
+
+        let users = simulation.report.users.unwrap();
+        assert_eq!(simulation.status, SimulationStatus::Completed);
+        assert!(users
+            .iter()
+            .all(|user| matches!(user.behaviour, UserBehaviour::Whale | UserBehaviour::Bot)));
+    }
+
+    #[test]
+    fn test_run_with_seed_is_reproducible() {
+        let run = |seed| {
+            let mut simulation = setup();
+            simulation.options.valuation_model = Some(ValuationModel::RandomWalk);
+            simulation.options.adoption_rate = Some(Decimal::new(1, 2));
+            simulation.options.seed = Some(seed);
+            simulation.run().unwrap();
+
+            let mut report = simulation.report;
+            report.interval = 0;
+            report
+        };
+
+        let first = run(7);
+
+        assert_eq!(first, run(7));
+        assert_ne!(first.token_price, run(8).token_price);
+    }
+
+    #[test]
+    fn test_run_carries_token_metadata() {
+        let mut simulation = setup();
+        simulation.options.duration = 2;
+        simulation.token.metadata = Some(TokenMetadata {
+            chain_id: Some(8453),
+            tags: vec!["memecoin".to_string()],
+            ..Default::default()
+        });
+
+        simulation.run().unwrap();
+
+        assert_eq!(simulation.report.token_metadata, simulation.token.metadata);
+        assert_eq!(simulation.interval_reports[0].token_metadata, None);
+    }
+
+    #[test]
+    fn test_run_keeps_partial_results_on_failure() {
+        #[derive(Debug)]
+        struct FailingEngine;
+
+        impl SimulationEngine for FailingEngine {
+            fn simulate_adoption(
+                &self,
+                simulation: &Simulation,
+                current_users: u64,
+            ) -> Result<u64, SimulationError> {
+                DefaultEngine.simulate_adoption(simulation, current_users)
+            }
+
+            fn calculate_valuation(
+                &self,
+                simulation: &Simulation,
+                token: &Token,
+                users: u64,
+            ) -> Decimal {
+                DefaultEngine.calculate_valuation(simulation, token, users)
+            }
+
+            fn process_interval(
+                &self,
+                simulation: &Simulation,
+                context: &IntervalContext,
+                users: &mut UserStore,
+                rng: &mut dyn rand::RngCore,
+            ) -> Result<(SimulationReport, HashSet<Uuid>), SimulationError> {
+                match context.index {
+                    3 => Err(SimulationError::InvalidDecimal),
+                    _ => DefaultEngine.process_interval(simulation, context, users, rng),
+                }
+            }
+        }
+
+        let mut simulation = setup();
+        simulation.set_engine(FailingEngine);
+
+        assert_eq!(simulation.run(), Err(SimulationError::InvalidDecimal));
+        assert_eq!(simulation.interval_reports.len(), 3);
+        assert_eq!(
+            simulation.status,
+            SimulationStatus::Failed {
+                at_interval: 3,
+                error: SimulationError::InvalidDecimal,
+                failed_at: simulation.updated_at,
+            }
+        );
+    }
+
+    #[test]
+    fn test_run_with_lifecycle() {
+        let mut simulation = setup();
+        simulation.options.lifecycle = Some(UserLifecycle {
+            active_to_dormant: 1.0,
+            dormant_to_churned: 0.0,
+            dormant_to_active: 0.0,
+        });
+
+        simulation.run().unwrap();
+
+        assert!(simulation
+            .interval_reports
+            .iter()
+            .all(|report| report.active_users == 0 && report.trades == 0));
+        assert_eq!(simulation.report.active_users, 0);
+
+        for lifecycle in [
+            UserLifecycle {
+                active_to_dormant: 1.5,
+                dormant_to_churned: 0.0,
+                dormant_to_active: 0.0,
+            },
+            UserLifecycle {
+                active_to_dormant: f64::NAN,
+                dormant_to_churned: 0.0,
+                dormant_to_active: 0.0,
+            },
+            UserLifecycle {
+                active_to_dormant: 0.1,
+                dormant_to_churned: 0.6,
+                dormant_to_active: 0.6,
+            },
+        ] {
+            let mut simulation = setup();
+            simulation.options.lifecycle = Some(lifecycle);
+
+            assert!(matches!(
+                simulation.run(),
+                Err(SimulationError::InvalidData(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_run_with_rounds_per_interval() {
+        let mut simulation = setup();
+        simulation.options.rounds_per_interval = Some(1);
+
+        simulation.run().unwrap();
+
+        let total_users = simulation.options.total_users;
+        assert!(simulation
+            .interval_reports
+            .iter()
+            .all(|report| report.trades <= total_users));
+
+        simulation.options.rounds_per_interval = Some(0);
+        simulation.run().unwrap();
+
+        assert_eq!(simulation.report.trades, 0);
+    }
+
+    #[test]
+    fn test_run_with_transaction_fee() {
+        let mut simulation = setup();
+        simulation.options.transaction_fee_percentage = Some(Percentage(Decimal::ONE));
+        simulation.options.transaction_fee = Some(SimulationTransactionFee::Fixed(Decimal::ONE));
+
+        simulation.run().unwrap();
+
+        // Price stays at 1, so each successful trade spends 1 token of gas
+        let gas_spent: TokenAmount = simulation
+            .interval_reports
+            .iter()
+            .map(|report| report.gas_spent)
+            .sum();
+        assert_eq!(
+            simulation.report.gas_spent,
+            TokenAmount(Decimal::from(simulation.report.successful_trades))
+        );
+        assert_eq!(simulation.report.gas_spent, gas_spent);
+        assert!(simulation.report.fees > TokenAmount::default());
+
+        simulation.options.transaction_fee =
+            Some(SimulationTransactionFee::Ethereum(EthereumFee {
+                gas_used: 21_000,
+                gas_price: None,
+                ether_price: None,
+            }));
+
+        assert_eq!(
+            simulation.run().unwrap_err(),
+            SimulationError::UnresolvedTransactionFee
+        );
+    }
+
+    #[test]
+    fn test_run_with_exchange_listing() {
+        let mut simulation = setup();
+        simulation.options.transaction_fee_percentage = Some(Percentage(Decimal::ONE));
+        simulation.options.venues = Some(VenueConfig {
+            dex_depth: Decimal::new(1_000, 0),
+            listings: vec![ExchangeListing {
+                name: "cex".to_string(),
+                listing_interval: 2,
+                fee_percentage: Decimal::new(1, 1),
+                depth: Decimal::new(1_000, 0),
+            }],
+        });
+
+        simulation.run().unwrap();
+
+        // Only the DEX is active before the listing
+        assert!(simulation.interval_reports[..2]
+            .iter()
+            .all(|report| report.venues.len() == 1));
+        assert!(simulation.interval_reports[2..]
+            .iter()
+            .all(|report| report.venues.len() == 2));
+
+        let venues = &simulation.report.venues;
+        assert_eq!(venues[0].name, DEX_VENUE);
+        assert_eq!(venues[1].name, "cex");
+        assert_eq!(
+            venues[0].volume + venues[1].volume,
+            (simulation.report.buy_volume + simulation.report.sell_volume).value()
+        );
+        assert!(venues[1].volume_share > Decimal::default());
+        assert!(venues[1].price_divergence >= Decimal::default());
+    }
+
+    #[test]
+    fn test_run_with_order_book() {
+        let mut simulation = setup();
+        simulation.options.market_model = MarketModel::OrderBook(OrderBookConfig {
+            tick_size: Decimal::new(1, 2),
+            levels: 20,
+            level_size: Decimal::new(1_000, 0),
+            limit_order_probability: 0.3,
+        });
+
+        simulation.run().unwrap();
+
+        // Each interval closes at the mid price of the book
+        assert!(simulation.report.successful_trades > 0);
+        assert!(simulation.report.average_slippage > Percentage::default());
+        assert!(simulation
+            .interval_reports
+            .iter()
+            .all(|report| report.token_price > FiatAmount::default()));
+    }
+
+    #[test]
+    fn test_run_with_inorganic_volume() {
+        let mut simulation = setup();
+        simulation.options.behaviour_mix = Some(BTreeMap::from([
+            (UserBehaviour::Trader, 0.5),
+            (UserBehaviour::Bot, 0.5),
+        ]));
+
+        simulation.run().unwrap();
+
+        let report = &simulation.report;
+        assert_eq!(
+            report.organic_volume + report.inorganic_volume,
+            report.buy_volume + report.sell_volume
+        );
+        assert!(report.inorganic_volume > TokenAmount::default());
+        assert!(report.real_volume <= report.organic_volume);
+        assert!(report.organic_volume_share(4) < Decimal::ONE);
+    }
+
+    #[test]
+    fn test_run_with_mev() {
+        let mut simulation = setup();
+        simulation.options.mev = Some(MevAgent {
+            min_trade_amount: Decimal::default(),
+            capture_probability: 1.0,
+            max_extraction_percentage: Decimal::ONE,
+            victim_churn_probability: 0.0,
+        });
+
+        simulation.run().unwrap();
+
+        // Every executed trade is targeted, tiny ones round down to nothing extracted
+        assert!(simulation.report.sandwiched_trades > 0);
+        assert!(simulation.report.sandwiched_trades <= simulation.report.successful_trades);
+        assert!(simulation.report.mev_extracted > TokenAmount::default());
+
+        // Every trading member of a cohort is sandwiched, not one trade per cohort
+        let mut cohorts = setup();
+        cohorts.options.duration = 1;
+        cohorts.options.total_users = 1_000_000;
+        cohorts.options.population_model = PopulationModel::Cohorts(10_000);
+        cohorts.options.mev = simulation.options.mev.clone();
+        cohorts.run().unwrap();
+        assert!(cohorts.report.sandwiched_trades > 100 * 24);
+        assert!(cohorts.report.sandwiched_trades <= cohorts.report.successful_trades);
+
+        // The agent only targets the AMM
+        simulation.options.market_model = MarketModel::OrderBook(OrderBookConfig {
+            tick_size: Decimal::new(1, 2),
+            levels: 20,
+            level_size: Decimal::new(1_000, 0),
+            limit_order_probability: 0.3,
+        });
+        simulation.run().unwrap();
+
+        assert_eq!(simulation.report.mev_extracted, TokenAmount::default());
+    }
+
+    #[test]
+    fn test_run_with_slippage_tolerance() {
+        let mut simulation = setup();
+        simulation.options.venues = Some(VenueConfig {
+            dex_depth: Decimal::new(100, 0),
+            listings: vec![],
+        });
+
+        simulation.run().unwrap();
+
+        assert!(simulation.report.average_slippage > Percentage::default());
+        assert_eq!(simulation.report.aborted_trades, 0);
+
+        simulation.options.slippage_tolerance = Some(Percentage(Decimal::new(1, 0)));
+        simulation.run().unwrap();
+
+        assert!(simulation.report.aborted_trades > 0);
+        assert!(simulation.report.failed_trades >= simulation.report.aborted_trades);
+        assert!(simulation
+            .interval_reports
+            .iter()
+            .all(|report| report.average_slippage <= Percentage(Decimal::new(1, 0))));
+    }
+
+    #[test]
+    fn test_run_with_sequential_user_ids() {
+        let mut simulation = setup();
+        simulation.options.user_id_strategy = UserIdStrategy::Sequential;
+
+        simulation.run().unwrap();
+
+        let users = simulation.report.users.unwrap();
+        assert_eq!(users[0].id, Uuid::from_u128(1));
+        assert_eq!(users[99].id, Uuid::from_u128(100));
+    }
+
+    #[test]
+    fn test_run_adds_only_new_users() {
+        let mut simulation = setup();
+        simulation.options.duration = 3;
+        simulation.options.adoption_rate = Some(Decimal::new(1, 1));
+        simulation.options.lifecycle = Some(UserLifecycle {
+            active_to_dormant: 1.0,
+            dormant_to_churned: 1.0,
+            dormant_to_active: 0.0,
+        });
+
+        simulation.run().unwrap();
+
+        // Users keep their state: only the users joining in the last interval have not churned yet
+        let report = &simulation.report;
+        assert_eq!(report.final_user_count, 133);
+        assert_eq!(report.churned_users, 121);
+
+        let users = report.users.as_ref().unwrap();
+        let ids = users.iter().map(|user| user.id).collect::<HashSet<_>>();
+        assert_eq!(ids.len(), 133);
+    }
+
+    #[test]
+    fn test_run_with_taxes() {
+        let mut simulation = setup();
+        simulation.token.buy_tax = Some(Percentage(Decimal::new(2, 0)));
+        simulation.token.sell_tax = Some(Percentage(Decimal::new(5, 0)));
+        simulation.token.tax_routing = Some(vec![
+            TaxRoute {
+                destination: TaxDestination::Treasury,
+                share: Decimal::new(50, 0),
+            },
+            TaxRoute {
+                destination: TaxDestination::Marketing,
+                share: Decimal::new(50, 0),
+            },
+        ]);
+
+        simulation.run().unwrap();
+
+        let report = &simulation.report;
+        let routed = report.tax_distribution.values().sum::<Decimal>();
+
+        assert!(report.tax_revenue > TokenAmount::default());
+        assert_eq!(routed, report.tax_revenue.value());
+        assert_eq!(
+            report.tax_distribution[&TaxDestination::Treasury],
+            report.tax_distribution[&TaxDestination::Marketing]
+        );
+
+        // The burned share is removed from the supply, without a burn rate it is the only burn
+        let mut simulation = setup();
+        simulation.token.sell_tax = Some(Percentage(Decimal::new(5, 0)));
+        simulation.token.tax_routing = Some(vec![TaxRoute {
+            destination: TaxDestination::Burn,
+            share: Decimal::ONE_HUNDRED,
+        }]);
+
+        simulation.run().unwrap();
+
+        let report = &simulation.report;
+        assert!(report.total_burned > TokenAmount::default());
+        assert_eq!(report.total_burned, report.tax_revenue);
+
+        // Routes that do not split the whole tax are rejected
+        let mut simulation = setup();
+        simulation.token.sell_tax = Some(Percentage(Decimal::new(5, 0)));
+        simulation.token.tax_routing = Some(vec![TaxRoute {
+            destination: TaxDestination::Treasury,
+            share: Decimal::new(30, 0),
+        }]);
+
+        assert!(matches!(
+            simulation.run(),
+            Err(SimulationError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn test_simulate_price() {
+        let mut simulation = setup();
+        let price = Decimal::new(2, 0);
+
+        simulation.options.market_volatility = Decimal::default();
+        assert_eq!(simulation.simulate_price(price, 24).unwrap(), price);
+
+        simulation.options.market_volatility = Decimal::new(1, 0);
+        let simulated = simulation.simulate_price(price, 24).unwrap();
+        assert!(simulated > Decimal::default());
+    }
+
+    #[test]
+    fn test_run_with_random_walk() {
+        let mut simulation = setup();
+        simulation.options.valuation_model = Some(ValuationModel::RandomWalk);
+
+        simulation.run().unwrap();
+
+        assert!(simulation
+            .interval_reports
+            .iter()
+            .all(|report| report.token_price > FiatAmount::default()));
+
+        // Without a valuation model, the token has no valuation
+        let mut simulation = setup();
+        simulation.options.valuation_model = None;
+
+        simulation.run().unwrap();
+
+        assert!(simulation
+            .interval_reports
+            .iter()
+            .all(|report| report.token_price.is_zero()));
+    }
+
+    #[test]
+    fn test_run_with_contributor_emission() {
+        let mut simulation = setup();
+        simulation.token.contributor_emission = Some(ContributorEmission {
+            amount_per_interval: Decimal::new(1_000, 0),
+            sell_through: Decimal::new(5, 1),
+        });
+
+        simulation.run().unwrap();
+
+        let report = &simulation.report;
+        assert_eq!(
+            report.contributor_emission,
+            TokenAmount(Decimal::new(30_000, 0))
+        );
+        assert_eq!(
+            report.contributor_sold,
+            TokenAmount(Decimal::new(15_000, 0))
+        );
+        assert_eq!(
+            simulation.token.current_supply,
+            TokenAmount(Decimal::new(30_000, 0))
+        );
+        assert!(report.sell_pressure() >= report.contributor_sold);
+    }
+
+    #[test]
+    fn test_run_with_unlock_sells() {
+        let mut simulation = setup();
+        simulation.options.behaviour_mix =
+            Some(BTreeMap::from([(UserBehaviour::AirdropFarmer, 1.0)]));
+        let unlock_schedule = Some(vec![UnlockEvent {
+            date: Utc::now() - chrono::Duration::hours(1),
+            amount: Decimal::new(10_000, 0),
+        }]);
+        simulation.token.unlock_schedule = unlock_schedule.clone();
+
+        simulation.run().unwrap();
+
+        // Farmers sell 95% of the tokens unlocked in the first interval
+        let reports = &simulation.interval_reports;
+        assert!(reports[0].unlock_sold > TokenAmount(Decimal::new(9_400, 0)));
+        assert!(reports[0].unlock_sold <= TokenAmount(Decimal::new(9_500, 0)));
+        assert!(reports[1..]
+            .iter()
+            .all(|report| report.unlock_sold.is_zero()));
+        assert!(simulation.report.sell_pressure() >= simulation.report.unlock_sold);
+
+        // A higher configured sell-through sells more of the same unlock
+        let mut eager = setup();
+        eager.options.behaviour_mix = simulation.options.behaviour_mix.clone();
+        eager.token.unlock_schedule = unlock_schedule.clone();
+        eager.options.behaviour_profiles.insert(
+            UserBehaviour::AirdropFarmer,
+            BehaviourProfile {
+                unlock_sell_through: 1.0,
+                ..UserBehaviour::AirdropFarmer.profile()
+            },
+        );
+        eager.run().unwrap();
+        assert!(eager.interval_reports[0].unlock_sold > reports[0].unlock_sold);
+
+        let mut holding = setup();
+        holding.options.behaviour_mix = simulation.options.behaviour_mix.clone();
+        holding.token.unlock_schedule = unlock_schedule;
+        holding.options.behaviour_profiles.insert(
+            UserBehaviour::AirdropFarmer,
+            BehaviourProfile {
+                unlock_sell_through: 0.0,
+                ..UserBehaviour::AirdropFarmer.profile()
+            },
+        );
+        holding.run().unwrap();
+        assert!(holding.interval_reports[0].unlock_sold.is_zero());
+    }
+
+    #[test]
+    fn test_run_with_halving_schedule() {
+        let mut simulation = setup();
+        simulation.options.valuation_model = Some(ValuationModel::RandomWalk);
+        simulation.options.market_volatility = Decimal::default();
+        simulation.options.security_budget_threshold = Some(FiatAmount(Decimal::new(60, 0)));
+        simulation.token.halving_schedule = Some(HalvingSchedule {
+            initial_reward: Decimal::new(100, 0),
+            halving_interval: 10,
+        });
+
+        simulation.run().unwrap();
+
+        let reports = &simulation.interval_reports;
+        assert_eq!(reports[0].block_rewards, TokenAmount(Decimal::new(100, 0)));
+        assert_eq!(reports[10].block_rewards, TokenAmount(Decimal::new(50, 0)));
+        assert_eq!(reports[20].block_rewards, TokenAmount(Decimal::new(25, 0)));
+        assert!(!reports[0].security_budget_below_threshold);
+        assert!(reports[20].security_budget_below_threshold);
+        assert_eq!(
+            simulation.report.block_rewards,
+            TokenAmount(Decimal::new(1_750, 0))
+        );
+        assert!(simulation.report.security_budget_below_threshold);
+    }
+
+    #[test]
+    fn test_calculate_valuation_linear() {
+        let mut simulation = setup();
+        simulation.token.initial_price = FiatAmount(Decimal::new(1, 2));
+        simulation.options.valuation_model = Some(ValuationModel::Linear);
+
+        let token = &simulation.token;
+        let users = 99;
+        let valuation = simulation.calculate_valuation(token, users);
+
+        assert_eq!(valuation, Decimal::new(99, 2));
+    }
+
+    #[test]
+    fn test_calculate_valuation_exponential() {
+        let mut simulation = setup();
+        simulation.options.valuation_model = Some(ValuationModel::Exponential(2.0));
+
+        let token = &simulation.token;
+        let users = 100;
+        let valuation = simulation.calculate_valuation(token, users);
+
+        assert_eq!(valuation, Decimal::new(1, 0));
     }
 
     #[test]
@@ -679,4 +3207,35 @@ mod tests {
         let new_users = simulation.simulate_adoption(current_users).unwrap();
         assert_eq!(new_users, 100);
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let mut simulation = setup();
+        simulation.options.duration = 2;
+        simulation.run().unwrap();
+
+        let json = serde_json::to_string(&simulation).unwrap();
+        let decoded = serde_json::from_str::<Simulation>(&json).unwrap();
+
+        assert_eq!(decoded.id, simulation.id);
+        assert_eq!(decoded.status, SimulationStatus::Completed);
+        assert_eq!(decoded.token, simulation.token);
+        assert_eq!(decoded.options, simulation.options);
+        assert_eq!(
+            decoded.effective_options,
+            Some(simulation.options.effective().unwrap())
+        );
+        assert_eq!(decoded.interval_reports, simulation.interval_reports);
+        assert!(decoded.observers.is_empty());
+
+        // Users are not serialized
+        assert_eq!(
+            decoded.report,
+            SimulationReport {
+                users: None,
+                ..simulation.report.clone()
+            }
+        );
+    }
 }
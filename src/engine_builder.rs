@@ -6,17 +6,20 @@
 //! The builder is used to ensure that all required fields are provided when creating a new simulation.
 
 use chrono::Utc;
+use rust_decimal::Decimal;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
-    Simulation, SimulationError, SimulationOptions, SimulationReport, SimulationStatus, Token,
+    Percentage, Simulation, SimulationError, SimulationOptions, SimulationReport, SimulationStatus,
+    Token, SCHEMA_VERSION,
 };
 
 /// Builder for creating a new simulation.
 #[derive(Debug, Default, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "strict_serde", serde(deny_unknown_fields))]
 pub struct SimulationBuilder {
     /// Name of the simulation.
     /// Required field.
@@ -33,6 +36,14 @@ pub struct SimulationBuilder {
     /// Input parameters for the simulation.
     /// Required field.
     pub options: Option<SimulationOptions>,
+
+    /// Whether to reject invalid or unused combinations of the token and the input parameters.
+    /// Optional field.
+    pub strict: Option<bool>,
+
+    /// Balances of the users continued from a previous run, one user per balance.
+    /// Optional field, overrides the number of users and the holder balances of the input parameters.
+    pub continued_balances: Option<Vec<Decimal>>,
 }
 
 impl SimulationBuilder {
@@ -101,23 +112,172 @@ impl SimulationBuilder {
         self
     }
 
+    /// Continue from the final state of a completed simulation, e.g. a launch phase followed by a growth phase.
+    /// The token starts from the final price and circulating supply of the previous run, without a new airdrop,
+    /// and the users start from the final balances of the previous users.
+    /// The input parameters of the previous run are used unless other ones are set, before or after.
+    ///
+    /// # Arguments
+    ///
+    /// * `previous` - Completed simulation, with the users in its final report.
+    ///
+    /// # Returns
+    ///
+    /// The simulation builder, or an error if the previous simulation is not completed or has no users in its final report.
+    pub fn continue_from(mut self, previous: &Simulation) -> Result<Self, SimulationError> {
+        let (SimulationStatus::Completed, Some(users), Some(last)) = (
+            &previous.status,
+            &previous.report.users,
+            previous.interval_reports.last(),
+        ) else {
+            return Err(SimulationError::InvalidData(
+                "only a completed simulation with the users in its final report can be continued"
+                    .to_string(),
+            ));
+        };
+
+        let balances: Vec<Decimal> = users.iter().map(|user| user.balance).collect();
+        let mut token = previous.token.clone();
+        token.initial_price = last.token_price;
+        token.airdrop_percentage = None;
+        if !token.total_supply.is_zero() {
+            token.initial_supply_percentage = Percentage::from_fraction(
+                balances.iter().sum::<Decimal>() / token.total_supply.value(),
+            );
+        }
+
+        self.token = Some(token);
+        self.options.get_or_insert_with(|| previous.options.clone());
+        self.continued_balances = Some(balances);
+
+        Ok(self)
+    }
+
+    /// Override the token used to run the simulation in place, e.g. after a fork.
+    /// Does nothing if the token is not set.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - Function modifying the token.
+    ///
+    /// # Returns
+    ///
+    /// The simulation builder.
+    pub fn map_token<F: FnOnce(&mut Token)>(mut self, f: F) -> Self {
+        if let Some(token) = self.token.as_mut() {
+            f(token);
+        }
+        self
+    }
+
+    /// Override the input parameters for the simulation in place, e.g. after a fork.
+    /// Does nothing if the input parameters are not set.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - Function modifying the input parameters.
+    ///
+    /// # Returns
+    ///
+    /// The simulation builder.
+    pub fn map_options<F: FnOnce(&mut SimulationOptions)>(mut self, f: F) -> Self {
+        if let Some(options) = self.options.as_mut() {
+            f(options);
+        }
+        self
+    }
+
+    /// Set the strict mode, which rejects invalid or unused combinations of the token and the input parameters
+    /// when building, e.g. an airdrop without users.
+    ///
+    /// # Arguments
+    ///
+    /// * `strict` - Whether to reject invalid or unused combinations.
+    ///
+    /// # Returns
+    ///
+    /// The simulation builder.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = Some(strict);
+        self
+    }
+
+    /// Check the token and the input parameters in strict mode.
+    ///
+    /// # Returns
+    ///
+    /// Error describing the first invalid or unused combination.
+    fn check_strict(&self) -> Result<(), SimulationError> {
+        let reject = |reason: &str| Err(SimulationError::StrictViolation(reason.to_string()));
+
+        if self
+            .name
+            .as_ref()
+            .is_some_and(|name| name.trim().is_empty())
+        {
+            return reject("name must not be empty");
+        }
+        if let (Some(token), Some(options)) = (&self.token, &self.options) {
+            if token.airdrop_percentage.is_some() && options.total_users == 0 {
+                return reject("airdrop is set without users");
+            }
+            if let Some(balances) = &options.holder_balances {
+                if balances.iter().sum::<Decimal>() > token.initial_supply().value() {
+                    return reject("holder balances exceed the initial supply");
+                }
+            }
+        }
+        if let Some(lifecycle) = self
+            .options
+            .as_ref()
+            .and_then(|options| options.lifecycle.as_ref())
+        {
+            if lifecycle.validate().is_err() {
+                return reject("lifecycle probabilities are out of range");
+            }
+        }
+
+        Ok(())
+    }
+
     /// Build the simulation.
     ///
     /// # Returns
     ///
-    /// Built simulation or an error if required fields are missing.
+    /// Built simulation or an error if required fields are missing,
+    /// or if the token and the input parameters are rejected in strict mode.
     pub fn build(self) -> Result<Simulation, SimulationError> {
+        if self.strict.unwrap_or_default() {
+            self.check_strict()?;
+        }
+
+        let name = self.name.ok_or(SimulationError::MissingName)?;
+        let token = self.token.ok_or(SimulationError::MissingToken)?;
+        let mut options = self.options.ok_or(SimulationError::MissingOptions)?;
+        if let Some(balances) = self.continued_balances {
+            options.total_users = balances.len() as u64;
+            options.holder_balances = Some(balances);
+        }
+
         Ok(Simulation {
+            schema_version: SCHEMA_VERSION,
             id: Uuid::new_v4(),
             description: self.description,
             status: SimulationStatus::Pending,
-            name: self.name.ok_or(SimulationError::MissingName)?,
-            token: self.token.ok_or(SimulationError::MissingToken)?,
-            options: self.options.ok_or(SimulationError::MissingOptions)?,
+            name,
+            token,
+            initial_token: None,
+            effective_options: None,
+            options,
             interval_reports: vec![],
             report: SimulationReport::default(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            observers: vec![],
+            metric_plugins: vec![],
+            webhook_transport: None,
+            engine: None,
+            rng: None,
         })
     }
 }
@@ -126,7 +286,10 @@ impl SimulationBuilder {
 mod tests {
     use rust_decimal::Decimal;
 
-    use crate::{SimulationInterval, TokenBuilder, ValuationModel};
+    use crate::{
+        MarketModel, PopulationModel, ReportFields, RoundingPolicy, SimulationInterval,
+        TokenBuilder, UserIdStrategy, ValuationModel,
+    };
 
     use super::*;
 
@@ -138,6 +301,8 @@ mod tests {
         assert_eq!(builder.token, None);
         assert_eq!(builder.description, None);
         assert_eq!(builder.options, None);
+        assert_eq!(builder.strict, None);
+        assert_eq!(builder.continued_balances, None);
     }
 
     #[test]
@@ -156,6 +321,44 @@ mod tests {
             interval_type: SimulationInterval::Daily,
             adoption_rate: None,
             valuation_model: Some(ValuationModel::Exponential(1.0)),
+            behaviour_mix: None,
+            lifecycle: None,
+            user_id_strategy: UserIdStrategy::Random,
+            security_budget_threshold: None,
+            rounds_per_interval: None,
+            transaction_fee: None,
+            capital_inflow: None,
+            venues: None,
+            slippage_tolerance: None,
+            market_model: MarketModel::Amm,
+            mev: None,
+            report_fields: ReportFields::all(),
+            population_model: PopulationModel::Agents,
+            webhook: None,
+            seed: None,
+            distribution_per_interval: false,
+            rounding: RoundingPolicy::default(),
+            market_regime: None,
+            regime_switching: None,
+            holder_balances: None,
+            price_feed: None,
+            fee_congestion: None,
+            demand_series: None,
+            utility: None,
+            subscription: None,
+            nft_market: None,
+            game_economy: None,
+            gauge_voting: None,
+            insurance_fund: None,
+            supply_controller: None,
+            fee_switch: None,
+            competitor: None,
+            interest_rates: None,
+            tax_policy: None,
+            holder_cap: None,
+            behaviour_profiles: Default::default(),
+            sentiment: None,
+            network_effects: None,
         };
 
         let simulation = SimulationBuilder::default()
@@ -187,6 +390,44 @@ mod tests {
             interval_type: SimulationInterval::Daily,
             adoption_rate: None,
             valuation_model: Some(ValuationModel::Exponential(1.0)),
+            behaviour_mix: None,
+            lifecycle: None,
+            user_id_strategy: UserIdStrategy::Random,
+            security_budget_threshold: None,
+            rounds_per_interval: None,
+            transaction_fee: None,
+            capital_inflow: None,
+            venues: None,
+            slippage_tolerance: None,
+            market_model: MarketModel::Amm,
+            mev: None,
+            report_fields: ReportFields::all(),
+            population_model: PopulationModel::Agents,
+            webhook: None,
+            seed: None,
+            distribution_per_interval: false,
+            rounding: RoundingPolicy::default(),
+            market_regime: None,
+            regime_switching: None,
+            holder_balances: None,
+            price_feed: None,
+            fee_congestion: None,
+            demand_series: None,
+            utility: None,
+            subscription: None,
+            nft_market: None,
+            game_economy: None,
+            gauge_voting: None,
+            insurance_fund: None,
+            supply_controller: None,
+            fee_switch: None,
+            competitor: None,
+            interest_rates: None,
+            tax_policy: None,
+            holder_cap: None,
+            behaviour_profiles: Default::default(),
+            sentiment: None,
+            network_effects: None,
         };
 
         let simulation = SimulationBuilder::default()
@@ -209,6 +450,44 @@ mod tests {
             interval_type: SimulationInterval::Daily,
             adoption_rate: None,
             valuation_model: Some(ValuationModel::Exponential(1.0)),
+            behaviour_mix: None,
+            lifecycle: None,
+            user_id_strategy: UserIdStrategy::Random,
+            security_budget_threshold: None,
+            rounds_per_interval: None,
+            transaction_fee: None,
+            capital_inflow: None,
+            venues: None,
+            slippage_tolerance: None,
+            market_model: MarketModel::Amm,
+            mev: None,
+            report_fields: ReportFields::all(),
+            population_model: PopulationModel::Agents,
+            webhook: None,
+            seed: None,
+            distribution_per_interval: false,
+            rounding: RoundingPolicy::default(),
+            market_regime: None,
+            regime_switching: None,
+            holder_balances: None,
+            price_feed: None,
+            fee_congestion: None,
+            demand_series: None,
+            utility: None,
+            subscription: None,
+            nft_market: None,
+            game_economy: None,
+            gauge_voting: None,
+            insurance_fund: None,
+            supply_controller: None,
+            fee_switch: None,
+            competitor: None,
+            interest_rates: None,
+            tax_policy: None,
+            holder_cap: None,
+            behaviour_profiles: Default::default(),
+            sentiment: None,
+            network_effects: None,
         };
 
         let simulation = SimulationBuilder::default()
@@ -236,4 +515,139 @@ mod tests {
         assert!(simulation.is_err());
         assert_eq!(simulation.unwrap_err(), SimulationError::MissingOptions);
     }
+
+    #[test]
+    fn test_build_simulation_strict() {
+        let token = TokenBuilder::new()
+            .name("Test Token".to_string())
+            .airdrop_percentage(5.0)
+            .build()
+            .unwrap();
+        let options = crate::SimulationOptionsBuilder::new()
+            .total_users(0)
+            .build()
+            .unwrap();
+        let builder = || {
+            SimulationBuilder::new()
+                .name("Test Simulation".to_string())
+                .token(token.clone())
+                .options(options.clone())
+        };
+
+        assert!(builder().build().is_ok());
+        assert!(matches!(
+            builder().strict(true).build(),
+            Err(SimulationError::StrictViolation(_))
+        ));
+        assert!(builder()
+            .strict(true)
+            .map_options(|options| options.total_users = 10)
+            .build()
+            .is_ok());
+        assert!(matches!(
+            builder()
+                .strict(true)
+                .map_options(|options| {
+                    options.total_users = 10;
+                    options.lifecycle = Some(crate::UserLifecycle {
+                        active_to_dormant: -0.1,
+                        dormant_to_churned: 0.0,
+                        dormant_to_active: 0.0,
+                    });
+                })
+                .build(),
+            Err(SimulationError::StrictViolation(_))
+        ));
+    }
+
+    #[test]
+    fn test_continue_from() {
+        let token = TokenBuilder::new()
+            .name("Test Token".to_string())
+            .airdrop_percentage(5.0)
+            .build()
+            .unwrap();
+        let options = crate::SimulationOptionsBuilder::new()
+            .total_users(20)
+            .duration(3)
+            .adoption_rate(0.1)
+            .seed(1)
+            .build()
+            .unwrap();
+        let mut launch = SimulationBuilder::new()
+            .name("Launch".to_string())
+            .token(token)
+            .options(options.clone())
+            .build()
+            .unwrap();
+
+        assert!(SimulationBuilder::new().continue_from(&launch).is_err());
+        launch.run().unwrap();
+
+        // The growth phase runs longer, from the final state of the launch
+        let mut growth = SimulationBuilder::new()
+            .name("Growth".to_string())
+            .options(SimulationOptions {
+                duration: 5,
+                ..options
+            })
+            .continue_from(&launch)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let users = launch.report.users.as_ref().unwrap();
+        assert_eq!(growth.options.duration, 5);
+        assert_eq!(growth.options.total_users, users.len() as u64);
+        assert_eq!(
+            growth.options.holder_balances,
+            Some(users.iter().map(|user| user.balance).collect())
+        );
+        assert_eq!(
+            growth.token.initial_price,
+            launch.interval_reports.last().unwrap().token_price
+        );
+        assert_eq!(growth.token.airdrop_percentage, None);
+
+        growth.run().unwrap();
+        assert!(growth.report.final_user_count >= users.len() as u64);
+    }
+
+    #[test]
+    fn test_map_token_and_options() {
+        let token = TokenBuilder::new()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = crate::SimulationOptionsBuilder::new()
+            .total_users(100)
+            .build()
+            .unwrap();
+
+        let builder = SimulationBuilder::new()
+            .token(token)
+            .options(options)
+            .map_token(|token| token.burn_rate = Some(Decimal::new(1, 2)))
+            .map_options(|options| options.total_users = 200);
+
+        assert_eq!(builder.token.unwrap().burn_rate, Some(Decimal::new(1, 2)));
+        assert_eq!(builder.options.unwrap().total_users, 200);
+
+        let builder = SimulationBuilder::new().map_options(|options| options.total_users = 200);
+        assert_eq!(builder.options, None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let builder = SimulationBuilder::new()
+            .name("Test Simulation".to_string())
+            .description("Test Description".to_string());
+
+        let json = serde_json::to_string(&builder).unwrap();
+        assert_eq!(
+            serde_json::from_str::<SimulationBuilder>(&json).unwrap(),
+            builder
+        );
+    }
 }
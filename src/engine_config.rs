@@ -3,15 +3,25 @@
 //! This module contains the configuration for the simulation engine.
 //! It includes the input parameters for the simulation and the builder to create the configuration.
 
+use std::collections::BTreeMap;
+
 use rust_decimal::{prelude::*, Decimal};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::{SimulationError, SimulationInterval};
+use crate::{
+    BehaviourProfile, CapitalInflow, Competitor, DemandSeries, FeeCongestion, FeeSwitch,
+    FiatAmount, GameEconomy, GaugeVoting, HolderCap, InsuranceFund, InterestRates, MarketModel,
+    MarketRegime, MevAgent, NetworkEffects, NftMarket, Percentage, PopulationModel, PriceFeed,
+    RegimeSwitching, ReportFields, RoundingPolicy, Sentiment, SimulationError, SimulationInterval,
+    SimulationTransactionFee, Subscription, SupplyController, TaxPolicy, UserBehaviour,
+    UserIdStrategy, UserLifecycle, UtilitySink, ValueKind, VenueConfig, Webhook,
+};
 
 /// Input parameters for a simulation.
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "strict_serde", serde(deny_unknown_fields))]
 pub struct SimulationOptions {
     /// Duration of the simulation, depending on the interval type.
     /// For daily interval, this is the number of days.
@@ -23,11 +33,13 @@ pub struct SimulationOptions {
 
     /// Volatility level. 0.0 is no volatility, 1.0 is maximum volatility.
     /// This is used to simulate the price volatility in the market.
+    /// With the random walk valuation model, it is the annualized volatility of the price.
     #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
     pub market_volatility: Decimal,
 
     /// Decimal precision for the simulation.
     /// Default value is 4.
+    /// Used for every kind of value whose precision is not set by the rounding policy.
     pub decimal_precision: u32,
 
     /// Interval type for the simulation.
@@ -36,8 +48,7 @@ pub struct SimulationOptions {
 
     /// Transaction fee for each trade, in percentage.
     /// This is the fee that will be charged for each trade in the simulation.
-    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float_option"))]
-    pub transaction_fee_percentage: Option<Decimal>,
+    pub transaction_fee_percentage: Option<Percentage>,
 
     /// Rate at which users adopt the token.
     /// This is the rate at which users will adopt the token.
@@ -47,11 +58,199 @@ pub struct SimulationOptions {
     /// Valuation model for the token.
     /// This is the model used to calculate the valuation of the token.
     pub valuation_model: Option<ValuationModel>,
+
+    /// Population mix of user behaviours, as relative weights.
+    /// If not set, all users are traders.
+    pub behaviour_mix: Option<BTreeMap<UserBehaviour, f64>>,
+
+    /// Transition probabilities of the user lifecycle.
+    /// If not set, users stay active for the whole simulation.
+    pub lifecycle: Option<UserLifecycle>,
+
+    /// Strategy used to assign IDs to generated users.
+    /// Default is `UserIdStrategy::Random`.
+    pub user_id_strategy: UserIdStrategy,
+
+    /// Minimum security budget (block rewards, fees, and gas, in fiat) per interval.
+    /// Intervals with a lower budget are flagged in the report.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub security_budget_threshold: Option<FiatAmount>,
+
+    /// Number of trade rounds simulated in each interval.
+    /// If not set, one round is simulated per hour of the interval.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rounds_per_interval: Option<u64>,
+
+    /// Network fee paid on each transaction, e.g. the gas fee of a blockchain.
+    /// Charged in addition to the percentage fee, converted to tokens at the current price.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub transaction_fee: Option<SimulationTransactionFee>,
+
+    /// External capital entering the system, from new users and top-ups.
+    /// If not set, the simulation is a closed system.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub capital_inflow: Option<CapitalInflow>,
+
+    /// Trading venues of the token, including exchange listings.
+    /// If not set, all trades are made on-chain.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub venues: Option<VenueConfig>,
+
+    /// Maximum slippage users accept on a trade, in percentage.
+    /// Trades exceeding it are aborted. Slippage is only computed when venues are configured.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub slippage_tolerance: Option<Percentage>,
+
+    /// Market model used to execute trades.
+    /// Default is `MarketModel::Amm`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub market_model: MarketModel,
+
+    /// MEV agent sandwiching large trades on the AMM.
+    /// If not set, trades are not sandwiched.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub mev: Option<MevAgent>,
+
+    /// Selection of the bulky report fields to produce.
+    /// Default is all fields.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub report_fields: ReportFields,
+
+    /// Population model used to represent the users.
+    /// Default is `PopulationModel::Agents`, one agent per user.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub population_model: PopulationModel,
+
+    /// Webhook notified when the simulation completes or fails.
+    /// If not set, no notification is sent.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub webhook: Option<Webhook>,
+
+    /// Seed of the random number generator.
+    /// Runs with the same seed and input parameters produce the same reports, except for their timestamps.
+    /// If not set, every run draws a new seed.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub seed: Option<u64>,
+
+    /// Whether to analyze the token distribution in every interval report, not only in the final report.
+    /// Default is `false`, the analysis sorts the users every interval.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub distribution_per_interval: bool,
+
+    /// Rounding policy: rounding mode, and precision of the prices, balances, and rates.
+    /// Default is half-even rounding at `decimal_precision` for every kind of value.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rounding: RoundingPolicy,
+
+    /// Market regime preset, setting the drift of the price and scaling the volatility and the adoption rate.
+    /// If not set, the price has no drift and the volatility and adoption rate are used as is.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub market_regime: Option<MarketRegime>,
+
+    /// Markov chain switching the market regime at each interval, instead of a fixed market regime preset.
+    /// The regime of each interval is recorded in its report.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub regime_switching: Option<RegimeSwitching>,
+
+    /// Initial balances of the holders, e.g. imported from an analytics export.
+    /// Each balance is given to one generated user, in order, instead of a random balance.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub holder_balances: Option<Vec<Decimal>>,
+
+    /// Historical prices replayed as returns instead of the random walk, e.g. the history of a comparable token.
+    /// Intervals after the end of the feed fall back to the random walk.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub price_feed: Option<PriceFeed>,
+
+    /// Congestion of the simulated network, scaling the network fee of each interval with the network activity of the previous one.
+    /// If not set, the network fee does not depend on the activity.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fee_congestion: Option<FeeCongestion>,
+
+    /// Exogenous multiplier of the trading demand of each interval, e.g. for a known conference or product launch.
+    /// If not set, the demand of each interval is the usual one.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub demand_series: Option<DemandSeries>,
+
+    /// Utility sink: tokens spent or locked by the active users to use the product, with a growing product usage.
+    /// If not set, users only trade the token.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub utility: Option<UtilitySink>,
+
+    /// Subscription: recurring token payments of a fraction of the active users, burned or sent to the treasury.
+    /// If not set, users make no recurring payments.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub subscription: Option<Subscription>,
+
+    /// NFT side market: tokens spent by the active users on NFT mints and resales, with royalties.
+    /// If not set, the token has no NFT market.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub nft_market: Option<NftMarket>,
+
+    /// Game economy: gameplay rewards minted to the active users, and sinks where they spend tokens.
+    /// If not set, the token has no game economy.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub game_economy: Option<GameEconomy>,
+
+    /// Gauge voting: emission directed to pools by the votes of the vote-escrow holders, swayed by bribes.
+    /// If not set, there is no gauge emission.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub gauge_voting: Option<GaugeVoting>,
+
+    /// Insurance fund: backstop accumulating a share of the fees, and covering the shortfall events.
+    /// If not set, the protocol has no insurance fund and no shortfall events.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub insurance_fund: Option<InsuranceFund>,
+
+    /// Supply controller: PID loop burning or emitting tokens each interval towards a target price.
+    /// If not set, the supply is not controlled.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub supply_controller: Option<SupplyController>,
+
+    /// Fee switch: share of the fees redirected from the treasury to the token holders from an activation interval on.
+    /// If not set, the treasury keeps all the fees.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fee_switch: Option<FeeSwitch>,
+
+    /// Competitor: rival token launched during the simulation, draining the users when it pays a higher yield.
+    /// If not set, the token has no competition.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub competitor: Option<Competitor>,
+
+    /// Interest rates: exogenous risk-free rate of each interval, shifting the behaviour of the users.
+    /// If not set, the users do not react to the macro environment.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub interest_rates: Option<InterestRates>,
+
+    /// Tax policy: yearly tax on the gains and income of the users, who may sell tokens to cover it.
+    /// If not set, the users pay no tax, and no tax records are produced.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tax_policy: Option<TaxPolicy>,
+
+    /// Holder cap: largest share of the circulating supply a holder may keep, the holders above it being forced to sell over time.
+    /// If not set, the holdings are not capped.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub holder_cap: Option<HolderCap>,
+
+    /// Market parameters of the user behaviours, overriding the default profile of each listed behaviour.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub behaviour_profiles: BTreeMap<UserBehaviour, BehaviourProfile>,
+
+    /// Sentiment: global market mood following the momentum of the price, shifting the buy probability of the users.
+    /// If not set, the users buy and sell regardless of the recent performance of the price.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub sentiment: Option<Sentiment>,
+
+    /// Network effects: coupling of the adoption growth to the recent price performance and network activity.
+    /// If not set, the users grow at the exogenous adoption rate only.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub network_effects: Option<NetworkEffects>,
 }
 
 /// Builder for creating a new simulation options.
 #[derive(Debug, Default, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "strict_serde", serde(deny_unknown_fields))]
 pub struct SimulationOptionsBuilder {
     /// Duration of the simulation, depending on the interval type.
     pub duration: Option<u64>,
@@ -78,6 +277,128 @@ pub struct SimulationOptionsBuilder {
 
     /// Valuation model for the token.
     pub valuation_model: Option<ValuationModel>,
+
+    /// Population mix of user behaviours, as relative weights.
+    pub behaviour_mix: Option<BTreeMap<UserBehaviour, f64>>,
+
+    /// Transition probabilities of the user lifecycle.
+    pub lifecycle: Option<UserLifecycle>,
+
+    /// Strategy used to assign IDs to generated users.
+    pub user_id_strategy: Option<UserIdStrategy>,
+
+    /// Minimum security budget (block rewards, fees, and gas, in fiat) per interval.
+    pub security_budget_threshold: Option<f64>,
+
+    /// Number of trade rounds simulated in each interval.
+    pub rounds_per_interval: Option<u64>,
+
+    /// Network fee paid on each transaction.
+    pub transaction_fee: Option<SimulationTransactionFee>,
+
+    /// External capital entering the system.
+    pub capital_inflow: Option<CapitalInflow>,
+
+    /// Trading venues of the token.
+    pub venues: Option<VenueConfig>,
+
+    /// Maximum slippage users accept on a trade, in percentage.
+    pub slippage_tolerance: Option<f64>,
+
+    /// Market model used to execute trades.
+    pub market_model: Option<MarketModel>,
+
+    /// MEV agent sandwiching large trades.
+    pub mev: Option<MevAgent>,
+
+    /// Selection of the bulky report fields to produce.
+    pub report_fields: Option<ReportFields>,
+
+    /// Population model used to represent the users.
+    pub population_model: Option<PopulationModel>,
+
+    /// Webhook notified when the simulation completes or fails.
+    pub webhook: Option<Webhook>,
+
+    /// Seed of the random number generator.
+    pub seed: Option<u64>,
+
+    /// Whether to analyze the token distribution in every interval report.
+    pub distribution_per_interval: Option<bool>,
+
+    /// Rounding policy.
+    pub rounding: Option<RoundingPolicy>,
+
+    /// Market regime preset.
+    pub market_regime: Option<MarketRegime>,
+
+    /// Market regime switching.
+    pub regime_switching: Option<RegimeSwitching>,
+
+    /// Initial balances of the holders.
+    pub holder_balances: Option<Vec<Decimal>>,
+
+    /// Historical price feed.
+    pub price_feed: Option<PriceFeed>,
+
+    /// Fee congestion.
+    pub fee_congestion: Option<FeeCongestion>,
+
+    /// Demand series.
+    pub demand_series: Option<DemandSeries>,
+
+    /// Utility sink.
+    pub utility: Option<UtilitySink>,
+
+    /// Subscription.
+    pub subscription: Option<Subscription>,
+
+    /// NFT market.
+    pub nft_market: Option<NftMarket>,
+
+    /// Game economy.
+    pub game_economy: Option<GameEconomy>,
+
+    /// Gauge voting.
+    pub gauge_voting: Option<GaugeVoting>,
+
+    /// Insurance fund.
+    pub insurance_fund: Option<InsuranceFund>,
+
+    /// Supply controller.
+    pub supply_controller: Option<SupplyController>,
+
+    /// Fee switch.
+    pub fee_switch: Option<FeeSwitch>,
+
+    /// Competitor.
+    pub competitor: Option<Competitor>,
+
+    /// Interest rates.
+    pub interest_rates: Option<InterestRates>,
+
+    /// Tax policy.
+    pub tax_policy: Option<TaxPolicy>,
+
+    /// Holder cap.
+    pub holder_cap: Option<HolderCap>,
+
+    /// Market parameters of the user behaviours.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub behaviour_profiles: BTreeMap<UserBehaviour, BehaviourProfile>,
+
+    /// Sentiment.
+    pub sentiment: Option<Sentiment>,
+
+    /// Network effects.
+    pub network_effects: Option<NetworkEffects>,
+
+    /// Duration of the simulation in real time.
+    /// If set, the number of intervals is computed from the interval type and overrides `duration`.
+    pub time_span: Option<TimeSpan>,
+
+    /// Whether to reject invalid or unused combinations of parameters, instead of applying the permissive defaults.
+    pub strict: Option<bool>,
 }
 
 /// Valuation model for the token.
@@ -91,6 +412,136 @@ pub enum ValuationModel {
     /// The factor is a parameter that controls the rate of growth.
     /// A higher factor will result in a slower growth rate.
     Exponential(f64),
+
+    /// Random walk valuation model: the price follows a geometric random walk from the initial price,
+    /// with the market volatility as annualized volatility, and the drift of the market regime.
+    RandomWalk,
+}
+
+/// Duration of the simulation in real time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum TimeSpan {
+    /// Number of hours.
+    Hours(u64),
+
+    /// Number of days.
+    Days(u64),
+
+    /// Number of weeks.
+    Weeks(u64),
+
+    /// Number of months, counted as 30 days.
+    Months(u64),
+
+    /// Number of years, counted as 365 days.
+    Years(u64),
+}
+
+impl TimeSpan {
+    /// Get the duration of the time span, in hours.
+    ///
+    /// # Returns
+    ///
+    /// The duration of the time span, in hours.
+    pub fn hours(&self) -> u64 {
+        match self {
+            TimeSpan::Hours(hours) => *hours,
+            TimeSpan::Days(days) => days * 24,
+            TimeSpan::Weeks(weeks) => weeks * 24 * 7,
+            TimeSpan::Months(months) => months * 24 * 30,
+            TimeSpan::Years(years) => years * 24 * 365,
+        }
+    }
+
+    /// Get the number of intervals needed to cover the time span.
+    /// A partial last interval is counted as a full interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval_type` - Interval type of the simulation.
+    ///
+    /// # Returns
+    ///
+    /// The number of intervals.
+    pub fn intervals(&self, interval_type: &SimulationInterval) -> u64 {
+        self.hours().div_ceil(interval_type.hours())
+    }
+}
+
+/// Input parameters of a simulation as the engine applies them, with the defaults and derived values resolved.
+/// Is used to disclose the exact assumptions of a run.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct EffectiveOptions {
+    /// Input parameters, with the optional values that have an implicit default filled in.
+    pub options: SimulationOptions,
+
+    /// Number of intervals of the simulation.
+    pub intervals: u64,
+
+    /// Duration of an interval, in hours.
+    pub interval_hours: u64,
+
+    /// Number of trading rounds per interval.
+    pub rounds_per_interval: u64,
+
+    /// Network fee per transaction, in fiat, e.g. the median fee of a window of gas prices.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, with = "rust_decimal::serde::float_option")
+    )]
+    pub network_fee: Option<Decimal>,
+}
+
+impl SimulationOptions {
+    /// Get the market parameters of a user behaviour.
+    ///
+    /// # Arguments
+    ///
+    /// * `behaviour` - User behaviour.
+    ///
+    /// # Returns
+    ///
+    /// Configured profile of the behaviour, or its default profile.
+    pub fn behaviour_profile(&self, behaviour: UserBehaviour) -> BehaviourProfile {
+        self.behaviour_profiles
+            .get(&behaviour)
+            .copied()
+            .unwrap_or_else(|| behaviour.profile())
+    }
+
+    /// Resolve the options as the engine applies them.
+    ///
+    /// # Returns
+    ///
+    /// Effective options, or an error if the network fee depends on live data that is not resolved.
+    pub fn effective(&self) -> Result<EffectiveOptions, SimulationError> {
+        let interval_hours = self.interval_type.hours();
+        let rounds_per_interval = self.rounds_per_interval.unwrap_or(interval_hours);
+        let network_fee = match &self.transaction_fee {
+            Some(fee) => Some(fee.per_transaction()?.value()),
+            None => None,
+        };
+
+        let mut options = self.clone();
+        options.transaction_fee_percentage =
+            Some(self.transaction_fee_percentage.unwrap_or_default());
+        options.adoption_rate = Some(self.adoption_rate.unwrap_or_default());
+        options.rounds_per_interval = Some(rounds_per_interval);
+        options.rounding.price_precision = Some(self.precision(ValueKind::Price));
+        options.rounding.balance_precision = Some(self.precision(ValueKind::Balance));
+        options.rounding.rate_precision = Some(self.precision(ValueKind::Rate));
+
+        Ok(EffectiveOptions {
+            options,
+            intervals: self.duration,
+            interval_hours,
+            rounds_per_interval,
+            network_fee,
+        })
+    }
 }
 
 impl SimulationOptionsBuilder {
@@ -215,100 +666,1141 @@ impl SimulationOptionsBuilder {
         self
     }
 
-    /// Build the simulation options.
+    /// Set the population mix of user behaviours.
+    ///
+    /// # Arguments
+    ///
+    /// * `behaviour_mix` - Relative weight of each user behaviour in the population.
     ///
     /// # Returns
     ///
-    /// Built simulation options or an error if required fields are missing.
-    pub fn build(self) -> Result<SimulationOptions, SimulationError> {
-        Ok(SimulationOptions {
-            duration: self.duration.unwrap_or(7),
-            total_users: self.total_users.ok_or(SimulationError::MissingTotalUsers)?,
-            market_volatility: Decimal::from_f64(self.market_volatility.unwrap_or(0.5)).unwrap(),
-            decimal_precision: self.decimal_precision.unwrap_or(4),
-            interval_type: self.interval_type.unwrap_or(SimulationInterval::Daily),
-            transaction_fee_percentage: match self.transaction_fee_percentage {
-                Some(fee) => Some(Decimal::from_f64(fee).ok_or(SimulationError::InvalidDecimal)?),
-                None => None,
-            },
-            adoption_rate: match self.adoption_rate {
-                Some(rate) => Some(Decimal::from_f64(rate).ok_or(SimulationError::InvalidDecimal)?),
-                None => None,
-            },
-            valuation_model: self.valuation_model,
-        })
+    /// The simulation options builder.
+    pub fn behaviour_mix(mut self, behaviour_mix: BTreeMap<UserBehaviour, f64>) -> Self {
+        self.behaviour_mix = Some(behaviour_mix);
+        self
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use crate::SimulationInterval;
-    use rust_decimal::Decimal;
 
-    use super::*;
+    /// Set the transition probabilities of the user lifecycle.
+    ///
+    /// # Arguments
+    ///
+    /// * `lifecycle` - Transition probabilities of the user lifecycle.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn lifecycle(mut self, lifecycle: UserLifecycle) -> Self {
+        self.lifecycle = Some(lifecycle);
+        self
+    }
 
-    #[test]
-    fn test_new_simulation_options_builder() {
-        let builder = SimulationOptionsBuilder::new();
+    /// Set the strategy used to assign IDs to generated users.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id_strategy` - Strategy used to assign IDs.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn user_id_strategy(mut self, user_id_strategy: UserIdStrategy) -> Self {
+        self.user_id_strategy = Some(user_id_strategy);
+        self
+    }
 
-        assert_eq!(builder.duration, None);
-        assert_eq!(builder.total_users, None);
-        assert_eq!(builder.market_volatility, None);
-        assert_eq!(builder.decimal_precision, None);
-        assert_eq!(builder.interval_type, None);
-        assert_eq!(builder.transaction_fee_percentage, None);
-        assert_eq!(builder.adoption_rate, None);
-        assert_eq!(builder.valuation_model, None);
+    /// Set the minimum security budget per interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `security_budget_threshold` - Minimum security budget (block rewards, fees, and gas, in fiat).
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn security_budget_threshold(mut self, security_budget_threshold: f64) -> Self {
+        self.security_budget_threshold = Some(security_budget_threshold);
+        self
     }
 
-    #[test]
-    fn test_build_simulation_options_with_only_required() {
-        let builder = SimulationOptionsBuilder::new();
-        let options = builder
-            .total_users(100)
-            .market_volatility(0.5)
-            .build()
-            .unwrap();
+    /// Set the number of trade rounds simulated in each interval.
+    /// This decouples the number of trades from the length of the interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `rounds_per_interval` - Number of trade rounds in each interval.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn rounds_per_interval(mut self, rounds_per_interval: u64) -> Self {
+        self.rounds_per_interval = Some(rounds_per_interval);
+        self
+    }
 
-        assert_eq!(options.duration, 7);
-        assert_eq!(options.total_users, 100);
-        assert_eq!(options.decimal_precision, 4);
-        assert_eq!(options.market_volatility, Decimal::new(5, 1));
-        assert_eq!(options.interval_type, SimulationInterval::Daily);
-        assert_eq!(options.transaction_fee_percentage, None);
-        assert_eq!(options.adoption_rate, None);
-        assert_eq!(options.valuation_model, None);
+    /// Set the network fee paid on each transaction, e.g. the gas fee of a blockchain.
+    /// Fees depending on live data must be resolved before the simulation is run.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction_fee` - Network fee paid on each transaction.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn transaction_fee(mut self, transaction_fee: SimulationTransactionFee) -> Self {
+        self.transaction_fee = Some(transaction_fee);
+        self
     }
-    #[test]
-    fn test_build_simulation_options() {
-        let builder = SimulationOptionsBuilder::new();
-        let options = builder
-            .adoption_rate(1.0)
-            .duration(10)
-            .decimal_precision(2)
-            .interval_type(SimulationInterval::Daily)
-            .transaction_fee_percentage(0.01)
-            .valuation_model(ValuationModel::Linear)
-            .total_users(100)
-            .market_volatility(0.5)
-            .build()
-            .unwrap();
 
-        assert_eq!(options.duration, 10);
-        assert_eq!(options.total_users, 100);
-        assert_eq!(options.decimal_precision, 2);
-        assert_eq!(options.market_volatility, Decimal::new(5, 1));
-        assert_eq!(options.interval_type, SimulationInterval::Daily);
-        assert_eq!(options.transaction_fee_percentage, Some(Decimal::new(1, 2)));
-        assert_eq!(options.adoption_rate, Some(Decimal::new(1, 0)));
-        assert_eq!(options.valuation_model, Some(ValuationModel::Linear));
+    /// Set the external capital entering the system, from new users and top-ups.
+    ///
+    /// # Arguments
+    ///
+    /// * `capital_inflow` - Model of the capital inflow.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn capital_inflow(mut self, capital_inflow: CapitalInflow) -> Self {
+        self.capital_inflow = Some(capital_inflow);
+        self
     }
 
-    #[test]
-    fn test_build_simulation_options_missing_total_users() {
-        let builder = SimulationOptionsBuilder::new();
+    /// Set the trading venues of the token, splitting the volume between the DEX and the listed exchanges.
+    ///
+    /// # Arguments
+    ///
+    /// * `venues` - Trading venues of the token.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn venues(mut self, venues: VenueConfig) -> Self {
+        self.venues = Some(venues);
+        self
+    }
+
+    /// Set the maximum slippage users accept on a trade.
+    /// Trades with a higher slippage against the depth of their venue are aborted.
+    ///
+    /// # Arguments
+    ///
+    /// * `slippage_tolerance` - Maximum slippage, in percentage.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn slippage_tolerance(mut self, slippage_tolerance: f64) -> Self {
+        self.slippage_tolerance = Some(slippage_tolerance);
+        self
+    }
+
+    /// Set the market model used to execute trades.
+    /// With `MarketModel::OrderBook`, the price is discovered by the order book instead of the valuation model.
+    ///
+    /// # Arguments
+    ///
+    /// * `market_model` - Market model used to execute trades.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn market_model(mut self, market_model: MarketModel) -> Self {
+        self.market_model = Some(market_model);
+        self
+    }
+
+    /// Set the MEV agent sandwiching large trades on the AMM.
+    ///
+    /// # Arguments
+    ///
+    /// * `mev` - MEV agent.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn mev(mut self, mev: MevAgent) -> Self {
+        self.mev = Some(mev);
+        self
+    }
+
+    /// Set the bulky report fields to produce, e.g. to leave out the users of the final report.
+    ///
+    /// # Arguments
+    ///
+    /// * `report_fields` - Selection of the report fields.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn report_fields(mut self, report_fields: ReportFields) -> Self {
+        self.report_fields = Some(report_fields);
+        self
+    }
+
+    /// Set the population model, e.g. to group millions of users into weighted cohorts.
+    /// Cohorts trade memory for precision: memory grows with the number of cohorts instead of users.
+    ///
+    /// # Arguments
+    ///
+    /// * `population_model` - Population model used to represent the users.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn population_model(mut self, population_model: PopulationModel) -> Self {
+        self.population_model = Some(population_model);
+        self
+    }
+
+    /// Set the webhook notified with the final report when the simulation completes, or with the error when it fails.
+    ///
+    /// # Arguments
+    ///
+    /// * `webhook` - Webhook to notify.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn webhook(mut self, webhook: Webhook) -> Self {
+        self.webhook = Some(webhook);
+        self
+    }
+
+    /// Set the seed of the random number generator, to make the runs reproducible.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - Seed of the random number generator.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Set whether to analyze the token distribution in every interval report, not only in the final report.
+    ///
+    /// # Arguments
+    ///
+    /// * `distribution_per_interval` - Whether to analyze the distribution every interval.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn distribution_per_interval(mut self, distribution_per_interval: bool) -> Self {
+        self.distribution_per_interval = Some(distribution_per_interval);
+        self
+    }
+
+    /// Set the rounding policy: rounding mode, and precision of the prices, balances, and rates.
+    ///
+    /// # Arguments
+    ///
+    /// * `rounding` - Rounding policy.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn rounding(mut self, rounding: RoundingPolicy) -> Self {
+        self.rounding = Some(rounding);
+        self
+    }
+
+    /// Set the market regime preset: bull, bear, or crab.
+    ///
+    /// # Arguments
+    ///
+    /// * `market_regime` - Market regime preset.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn market_regime(mut self, market_regime: MarketRegime) -> Self {
+        self.market_regime = Some(market_regime);
+        self
+    }
+
+    /// Set the Markov chain switching the market regime at each interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `regime_switching` - Regime switching chain.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn regime_switching(mut self, regime_switching: RegimeSwitching) -> Self {
+        self.regime_switching = Some(regime_switching);
+        self
+    }
+
+    /// Set the initial balances of the holders, replacing the random balances of the first users.
+    ///
+    /// # Arguments
+    ///
+    /// * `holder_balances` - Initial balance of each holder.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn holder_balances(mut self, holder_balances: Vec<Decimal>) -> Self {
+        self.holder_balances = Some(holder_balances);
+        self
+    }
+
+    /// Set the historical prices whose returns the token price follows.
+    ///
+    /// # Arguments
+    ///
+    /// * `price_feed` - Historical price feed.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn price_feed(mut self, price_feed: PriceFeed) -> Self {
+        self.price_feed = Some(price_feed);
+        self
+    }
+
+    /// Set the congestion of the simulated network, feeding the network activity back into the network fee.
+    ///
+    /// # Arguments
+    ///
+    /// * `fee_congestion` - Fee congestion.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn fee_congestion(mut self, fee_congestion: FeeCongestion) -> Self {
+        self.fee_congestion = Some(fee_congestion);
+        self
+    }
+
+    /// Set the exogenous multiplier of the trading demand of each interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `demand_series` - Demand series.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn demand_series(mut self, demand_series: DemandSeries) -> Self {
+        self.demand_series = Some(demand_series);
+        self
+    }
+
+    /// Set the utility sink, modeling the tokens spent or locked by the users to use the product.
+    ///
+    /// # Arguments
+    ///
+    /// * `utility` - Utility sink.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn utility(mut self, utility: UtilitySink) -> Self {
+        self.utility = Some(utility);
+        self
+    }
+
+    /// Set the subscription, modeling the recurring token payments of the users.
+    ///
+    /// # Arguments
+    ///
+    /// * `subscription` - Subscription.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn subscription(mut self, subscription: Subscription) -> Self {
+        self.subscription = Some(subscription);
+        self
+    }
+
+    /// Set the NFT side market, modeling the tokens spent by the users on NFT mints and resales.
+    ///
+    /// # Arguments
+    ///
+    /// * `nft_market` - NFT market.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn nft_market(mut self, nft_market: NftMarket) -> Self {
+        self.nft_market = Some(nft_market);
+        self
+    }
+
+    /// Set the play-to-earn game economy, modeling the faucets and sinks of a game.
+    ///
+    /// # Arguments
+    ///
+    /// * `game_economy` - Game economy.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn game_economy(mut self, game_economy: GameEconomy) -> Self {
+        self.game_economy = Some(game_economy);
+        self
+    }
+
+    /// Set the gauge voting, modeling the emission directed to pools by the votes of the vote-escrow holders.
+    ///
+    /// # Arguments
+    ///
+    /// * `gauge_voting` - Gauge voting.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn gauge_voting(mut self, gauge_voting: GaugeVoting) -> Self {
+        self.gauge_voting = Some(gauge_voting);
+        self
+    }
+
+    /// Set the insurance fund, modeling a backstop of the protocol against shortfall events.
+    ///
+    /// # Arguments
+    ///
+    /// * `insurance_fund` - Insurance fund.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn insurance_fund(mut self, insurance_fund: InsuranceFund) -> Self {
+        self.insurance_fund = Some(insurance_fund);
+        self
+    }
+
+    /// Set the supply controller, adjusting the supply with a PID loop towards a target price.
+    ///
+    /// # Arguments
+    ///
+    /// * `supply_controller` - Supply controller.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn supply_controller(mut self, supply_controller: SupplyController) -> Self {
+        self.supply_controller = Some(supply_controller);
+        self
+    }
+
+    /// Set the fee switch, redirecting a share of the fees to the token holders once activated.
+    ///
+    /// # Arguments
+    ///
+    /// * `fee_switch` - Fee switch.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn fee_switch(mut self, fee_switch: FeeSwitch) -> Self {
+        self.fee_switch = Some(fee_switch);
+        self
+    }
+
+    /// Set the competitor, a rival token draining the users by the gap between its yield and the yield of the token.
+    ///
+    /// # Arguments
+    ///
+    /// * `competitor` - Competitor.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn competitor(mut self, competitor: Competitor) -> Self {
+        self.competitor = Some(competitor);
+        self
+    }
+
+    /// Set the interest rates, layering a macro scenario of risk-free rates on the simulation.
+    ///
+    /// # Arguments
+    ///
+    /// * `interest_rates` - Interest rates.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn interest_rates(mut self, interest_rates: InterestRates) -> Self {
+        self.interest_rates = Some(interest_rates);
+        self
+    }
+
+    /// Set the tax policy, tracking the tax records of the users and the tax-sell events at the end of each year.
+    ///
+    /// # Arguments
+    ///
+    /// * `tax_policy` - Tax policy.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn tax_policy(mut self, tax_policy: TaxPolicy) -> Self {
+        self.tax_policy = Some(tax_policy);
+        self
+    }
+
+    /// Set the holder cap, forcing the holders above it to reduce their positions over time.
+    ///
+    /// # Arguments
+    ///
+    /// * `holder_cap` - Holder cap.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn holder_cap(mut self, holder_cap: HolderCap) -> Self {
+        self.holder_cap = Some(holder_cap);
+        self
+    }
+
+    /// Set the market parameters of a user behaviour, overriding its default profile.
+    ///
+    /// # Arguments
+    ///
+    /// * `behaviour` - User behaviour.
+    /// * `profile` - Market parameters of the behaviour.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn behaviour_profile(
+        mut self,
+        behaviour: UserBehaviour,
+        profile: BehaviourProfile,
+    ) -> Self {
+        self.behaviour_profiles.insert(behaviour, profile);
+        self
+    }
+
+    /// Set the sentiment model, feeding the momentum of the price back into the behaviour of the users.
+    ///
+    /// # Arguments
+    ///
+    /// * `sentiment` - Sentiment model.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn sentiment(mut self, sentiment: Sentiment) -> Self {
+        self.sentiment = Some(sentiment);
+        self
+    }
+
+    /// Set the network effects, making the adoption growth reflexive to the price and the network activity.
+    ///
+    /// # Arguments
+    ///
+    /// * `network_effects` - Network effects.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn network_effects(mut self, network_effects: NetworkEffects) -> Self {
+        self.network_effects = Some(network_effects);
+        self
+    }
+
+    /// Set the duration of the simulation in real time, e.g. 18 months.
+    /// The number of intervals is computed from the interval type and overrides `duration`.
+    ///
+    /// # Arguments
+    ///
+    /// * `time_span` - Duration of the simulation in real time.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn time_span(mut self, time_span: TimeSpan) -> Self {
+        self.time_span = Some(time_span);
+        self
+    }
+
+    /// Set the strict mode, which rejects invalid or unused combinations of parameters when building,
+    /// e.g. a non-positive exponential factor or an adoption rate without users.
+    ///
+    /// # Arguments
+    ///
+    /// * `strict` - Whether to reject invalid or unused combinations of parameters.
+    ///
+    /// # Returns
+    ///
+    /// The simulation options builder.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = Some(strict);
+        self
+    }
+
+    /// Check the parameters in strict mode.
+    ///
+    /// # Returns
+    ///
+    /// Error describing the first invalid or unused combination of parameters.
+    fn check_strict(&self) -> Result<(), SimulationError> {
+        let reject = |reason: &str| Err(SimulationError::StrictViolation(reason.to_string()));
+        let out_of = |value: Option<f64>, min: f64, max: f64| {
+            value.is_some_and(|value| !(min..=max).contains(&value))
+        };
+
+        if let Some(ValuationModel::Exponential(factor)) = self.valuation_model {
+            if !(factor > 0.0 && factor.is_finite()) {
+                return reject("exponential factor must be positive");
+            }
+        }
+        if self.adoption_rate.is_some() && self.total_users == Some(0) {
+            return reject("adoption rate is set without users");
+        }
+        if self.adoption_rate.is_some_and(|rate| rate < 0.0) {
+            return reject("adoption rate must be non-negative");
+        }
+        if out_of(self.market_volatility, 0.0, 1.0) {
+            return reject("market volatility must be between 0 and 1");
+        }
+        if out_of(self.transaction_fee_percentage, 0.0, 100.0) {
+            return reject("transaction fee percentage must be between 0 and 100");
+        }
+        if out_of(self.slippage_tolerance, 0.0, 100.0) {
+            return reject("slippage tolerance must be between 0 and 100");
+        }
+        if self.duration.is_some() && self.time_span.is_some() {
+            return reject("duration is overridden by the time span");
+        }
+        if self.duration == Some(0) || self.rounds_per_interval == Some(0) {
+            return reject("duration and rounds per interval must be positive");
+        }
+        if self.market_regime.is_some() && self.regime_switching.is_some() {
+            return reject("market regime and regime switching cannot both be set");
+        }
+        if let Some(mix) = &self.behaviour_mix {
+            if mix.values().any(|weight| *weight < 0.0) || mix.values().sum::<f64>() <= 0.0 {
+                return reject("behaviour mix must have non-negative weights with a positive sum");
+            }
+        }
+        if let (Some(balances), Some(total_users)) = (&self.holder_balances, self.total_users) {
+            if balances.len() as u64 > total_users {
+                return reject("more holder balances than users");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Build the simulation options.
+    ///
+    /// # Returns
+    ///
+    /// Built simulation options or an error if required fields are missing,
+    /// or if the parameters are rejected in strict mode.
+    pub fn build(self) -> Result<SimulationOptions, SimulationError> {
+        if self.strict.unwrap_or_default() {
+            self.check_strict()?;
+        }
+        let interval_type = self.interval_type.unwrap_or(SimulationInterval::Daily);
+
+        Ok(SimulationOptions {
+            duration: match self.time_span {
+                Some(time_span) => time_span.intervals(&interval_type),
+                None => self.duration.unwrap_or(7),
+            },
+            total_users: self.total_users.ok_or(SimulationError::MissingTotalUsers)?,
+            market_volatility: Decimal::from_f64(self.market_volatility.unwrap_or(0.5)).unwrap(),
+            decimal_precision: self.decimal_precision.unwrap_or(4),
+            interval_type,
+            transaction_fee_percentage: match self.transaction_fee_percentage {
+                Some(fee) => Some(Percentage(
+                    Decimal::from_f64(fee).ok_or(SimulationError::InvalidDecimal)?,
+                )),
+                None => None,
+            },
+            adoption_rate: match self.adoption_rate {
+                Some(rate) => Some(Decimal::from_f64(rate).ok_or(SimulationError::InvalidDecimal)?),
+                None => None,
+            },
+            valuation_model: self.valuation_model,
+            behaviour_mix: self.behaviour_mix,
+            lifecycle: self.lifecycle,
+            user_id_strategy: self.user_id_strategy.unwrap_or_default(),
+            security_budget_threshold: match self.security_budget_threshold {
+                Some(threshold) => Some(FiatAmount(
+                    Decimal::from_f64(threshold).ok_or(SimulationError::InvalidDecimal)?,
+                )),
+                None => None,
+            },
+            rounds_per_interval: self.rounds_per_interval,
+            transaction_fee: self.transaction_fee,
+            capital_inflow: self.capital_inflow,
+            venues: self.venues,
+            slippage_tolerance: match self.slippage_tolerance {
+                Some(tolerance) => Some(Percentage(
+                    Decimal::from_f64(tolerance).ok_or(SimulationError::InvalidDecimal)?,
+                )),
+                None => None,
+            },
+            market_model: self.market_model.unwrap_or_default(),
+            mev: self.mev,
+            report_fields: self.report_fields.unwrap_or_default(),
+            population_model: self.population_model.unwrap_or_default(),
+            webhook: self.webhook,
+            seed: self.seed,
+            distribution_per_interval: self.distribution_per_interval.unwrap_or_default(),
+            rounding: self.rounding.unwrap_or_default(),
+            market_regime: self.market_regime,
+            regime_switching: self.regime_switching,
+            holder_balances: self.holder_balances,
+            price_feed: self.price_feed,
+            fee_congestion: self.fee_congestion,
+            demand_series: self.demand_series,
+            utility: self.utility,
+            subscription: self.subscription,
+            nft_market: self.nft_market,
+            game_economy: self.game_economy,
+            gauge_voting: self.gauge_voting,
+            insurance_fund: self.insurance_fund,
+            supply_controller: self.supply_controller,
+            fee_switch: self.fee_switch,
+            competitor: self.competitor,
+            interest_rates: self.interest_rates,
+            tax_policy: self.tax_policy,
+            holder_cap: self.holder_cap,
+            behaviour_profiles: self.behaviour_profiles,
+            sentiment: self.sentiment,
+            network_effects: self.network_effects,
+        })
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for SimulationOptions {
+    /// Generate arbitrary valid input parameters.
+    /// The number of users and the duration are kept small, so the generated options can be run.
+    ///
+    /// # Arguments
+    ///
+    /// * `u` - Unstructured data to generate the input parameters from.
+    ///
+    /// # Returns
+    ///
+    /// Arbitrary input parameters.
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut builder = SimulationOptionsBuilder::new()
+            .duration(u.int_in_range(1..=365)?)
+            .total_users(u.int_in_range(1..=10_000)?)
+            .market_volatility(u.int_in_range(0..=200)? as f64 / 100.0)
+            .decimal_precision(u.int_in_range(0..=18)?)
+            .interval_type(SimulationInterval::arbitrary(u)?);
+
+        if bool::arbitrary(u)? {
+            builder = builder.transaction_fee_percentage(u.int_in_range(0..=1_000)? as f64 / 100.0);
+        }
+        if bool::arbitrary(u)? {
+            builder = builder.adoption_rate(u.int_in_range(0..=1_000)? as f64 / 10_000.0);
+        }
+        if bool::arbitrary(u)? {
+            builder = builder.valuation_model(match bool::arbitrary(u)? {
+                true => ValuationModel::Linear,
+                false => ValuationModel::Exponential(u.int_in_range(1..=100)? as f64 / 100.0),
+            });
+        }
+
+        builder
+            .build()
+            .map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SimulationInterval;
+    use rust_decimal::Decimal;
+
+    use super::*;
+
+    #[test]
+    fn test_new_simulation_options_builder() {
+        let builder = SimulationOptionsBuilder::new();
+
+        assert_eq!(builder.duration, None);
+        assert_eq!(builder.total_users, None);
+        assert_eq!(builder.market_volatility, None);
+        assert_eq!(builder.decimal_precision, None);
+        assert_eq!(builder.interval_type, None);
+        assert_eq!(builder.transaction_fee_percentage, None);
+        assert_eq!(builder.adoption_rate, None);
+        assert_eq!(builder.valuation_model, None);
+        assert_eq!(builder.behaviour_mix, None);
+        assert_eq!(builder.lifecycle, None);
+        assert_eq!(builder.user_id_strategy, None);
+        assert_eq!(builder.security_budget_threshold, None);
+        assert_eq!(builder.time_span, None);
+        assert_eq!(builder.strict, None);
+        assert_eq!(builder.rounds_per_interval, None);
+        assert_eq!(builder.transaction_fee, None);
+        assert_eq!(builder.capital_inflow, None);
+        assert_eq!(builder.venues, None);
+        assert_eq!(builder.slippage_tolerance, None);
+        assert_eq!(builder.market_model, None);
+        assert_eq!(builder.mev, None);
+        assert_eq!(builder.report_fields, None);
+        assert_eq!(builder.population_model, None);
+        assert_eq!(builder.webhook, None);
+        assert_eq!(builder.seed, None);
+        assert_eq!(builder.distribution_per_interval, None);
+        assert_eq!(builder.rounding, None);
+        assert_eq!(builder.market_regime, None);
+        assert_eq!(builder.regime_switching, None);
+        assert_eq!(builder.holder_balances, None);
+        assert_eq!(builder.price_feed, None);
+        assert_eq!(builder.fee_congestion, None);
+        assert_eq!(builder.demand_series, None);
+        assert_eq!(builder.utility, None);
+        assert_eq!(builder.subscription, None);
+        assert_eq!(builder.nft_market, None);
+        assert_eq!(builder.game_economy, None);
+        assert_eq!(builder.gauge_voting, None);
+        assert_eq!(builder.insurance_fund, None);
+        assert_eq!(builder.supply_controller, None);
+        assert_eq!(builder.fee_switch, None);
+        assert_eq!(builder.competitor, None);
+        assert_eq!(builder.interest_rates, None);
+        assert_eq!(builder.tax_policy, None);
+        assert_eq!(builder.holder_cap, None);
+        assert_eq!(builder.sentiment, None);
+        assert_eq!(builder.network_effects, None);
+        assert!(builder.behaviour_profiles.is_empty());
+    }
+
+    #[test]
+    fn test_behaviour_profile() {
+        use crate::Simulation;
+
+        let profile = BehaviourProfile {
+            trade_probability: 0.0,
+            ..UserBehaviour::Trader.profile()
+        };
+        let options = SimulationOptionsBuilder::new()
+            .total_users(10)
+            .behaviour_profile(UserBehaviour::Trader, profile)
+            .build()
+            .unwrap();
+
+        assert_eq!(options.behaviour_profile(UserBehaviour::Trader), profile);
+        assert_eq!(
+            options.behaviour_profile(UserBehaviour::Whale),
+            UserBehaviour::Whale.profile()
+        );
+
+        // Users who never trade leave the intervals without trades
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let mut simulation = Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token.clone())
+            .options(options.clone())
+            .build()
+            .unwrap();
+        simulation.run().unwrap();
+        assert_eq!(simulation.report.successful_trades, 0);
+
+        let mut options = options;
+        options.behaviour_profiles.insert(
+            UserBehaviour::Trader,
+            BehaviourProfile {
+                hold_intervals: 0,
+                ..profile
+            },
+        );
+        let mut simulation = Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap();
+        assert!(matches!(
+            simulation.run(),
+            Err(SimulationError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn test_build_simulation_options_with_only_required() {
+        let builder = SimulationOptionsBuilder::new();
+        let options = builder
+            .total_users(100)
+            .market_volatility(0.5)
+            .build()
+            .unwrap();
+
+        assert_eq!(options.duration, 7);
+        assert_eq!(options.total_users, 100);
+        assert_eq!(options.decimal_precision, 4);
+        assert_eq!(options.market_volatility, Decimal::new(5, 1));
+        assert_eq!(options.interval_type, SimulationInterval::Daily);
+        assert_eq!(options.transaction_fee_percentage, None);
+        assert_eq!(options.adoption_rate, None);
+        assert_eq!(options.valuation_model, None);
+        assert_eq!(options.behaviour_mix, None);
+        assert_eq!(options.lifecycle, None);
+        assert_eq!(options.user_id_strategy, UserIdStrategy::Random);
+        assert_eq!(options.security_budget_threshold, None);
+        assert_eq!(options.rounds_per_interval, None);
+        assert_eq!(options.transaction_fee, None);
+        assert_eq!(options.capital_inflow, None);
+        assert_eq!(options.venues, None);
+        assert_eq!(options.slippage_tolerance, None);
+        assert_eq!(options.market_model, MarketModel::Amm);
+        assert_eq!(options.mev, None);
+        assert_eq!(options.report_fields, ReportFields::all());
+        assert_eq!(options.population_model, PopulationModel::Agents);
+        assert_eq!(options.webhook, None);
+        assert_eq!(options.seed, None);
+        assert!(!options.distribution_per_interval);
+        assert_eq!(options.rounding, RoundingPolicy::default());
+        assert_eq!(options.market_regime, None);
+        assert_eq!(options.regime_switching, None);
+        assert_eq!(options.holder_balances, None);
+        assert_eq!(options.price_feed, None);
+        assert_eq!(options.fee_congestion, None);
+        assert_eq!(options.demand_series, None);
+        assert_eq!(options.utility, None);
+        assert_eq!(options.subscription, None);
+        assert_eq!(options.nft_market, None);
+        assert_eq!(options.game_economy, None);
+        assert_eq!(options.gauge_voting, None);
+        assert_eq!(options.insurance_fund, None);
+        assert_eq!(options.supply_controller, None);
+        assert_eq!(options.fee_switch, None);
+        assert_eq!(options.competitor, None);
+        assert_eq!(options.interest_rates, None);
+        assert_eq!(options.tax_policy, None);
+        assert_eq!(options.holder_cap, None);
+        assert!(options.behaviour_profiles.is_empty());
+        assert_eq!(options.sentiment, None);
+        assert_eq!(options.network_effects, None);
+    }
+    #[test]
+    fn test_build_simulation_options() {
+        let builder = SimulationOptionsBuilder::new();
+        let options = builder
+            .adoption_rate(1.0)
+            .duration(10)
+            .decimal_precision(2)
+            .interval_type(SimulationInterval::Daily)
+            .transaction_fee_percentage(0.01)
+            .valuation_model(ValuationModel::Linear)
+            .behaviour_mix(BTreeMap::from([(UserBehaviour::Whale, 1.0)]))
+            .lifecycle(UserLifecycle {
+                active_to_dormant: 0.1,
+                dormant_to_churned: 0.2,
+                dormant_to_active: 0.3,
+            })
+            .user_id_strategy(UserIdStrategy::Sequential)
+            .security_budget_threshold(1_000.0)
+            .rounds_per_interval(4)
+            .transaction_fee(SimulationTransactionFee::Fixed(Decimal::ONE))
+            .total_users(100)
+            .market_volatility(0.5)
+            .build()
+            .unwrap();
+
+        assert_eq!(options.duration, 10);
+        assert_eq!(options.total_users, 100);
+        assert_eq!(options.decimal_precision, 2);
+        assert_eq!(options.market_volatility, Decimal::new(5, 1));
+        assert_eq!(options.interval_type, SimulationInterval::Daily);
+        assert_eq!(
+            options.transaction_fee_percentage,
+            Some(Percentage(Decimal::new(1, 2)))
+        );
+        assert_eq!(options.adoption_rate, Some(Decimal::new(1, 0)));
+        assert_eq!(options.valuation_model, Some(ValuationModel::Linear));
+        assert_eq!(
+            options.behaviour_mix,
+            Some(BTreeMap::from([(UserBehaviour::Whale, 1.0)]))
+        );
+        assert_eq!(options.lifecycle.unwrap().dormant_to_active, 0.3);
+        assert_eq!(options.user_id_strategy, UserIdStrategy::Sequential);
+        assert_eq!(
+            options.security_budget_threshold,
+            Some(FiatAmount(Decimal::new(1_000, 0)))
+        );
+        assert_eq!(options.rounds_per_interval, Some(4));
+        assert_eq!(
+            options.transaction_fee,
+            Some(SimulationTransactionFee::Fixed(Decimal::ONE))
+        );
+    }
+
+    #[test]
+    fn test_build_simulation_options_missing_total_users() {
+        let builder = SimulationOptionsBuilder::new();
         let result = builder.build();
 
         assert_eq!(result, Err(SimulationError::MissingTotalUsers));
     }
+
+    #[test]
+    fn test_time_span_intervals() {
+        assert_eq!(TimeSpan::Days(2).hours(), 48);
+        assert_eq!(TimeSpan::Years(1).hours(), 8_760);
+        assert_eq!(
+            TimeSpan::Months(18).intervals(&SimulationInterval::Monthly),
+            18
+        );
+        assert_eq!(
+            TimeSpan::Months(18).intervals(&SimulationInterval::Daily),
+            540
+        );
+        assert_eq!(TimeSpan::Days(10).intervals(&SimulationInterval::Weekly), 2);
+    }
+
+    #[test]
+    fn test_build_simulation_options_with_time_span() {
+        let options = SimulationOptionsBuilder::new()
+            .total_users(100)
+            .duration(3)
+            .interval_type(SimulationInterval::Weekly)
+            .time_span(TimeSpan::Years(1))
+            .build()
+            .unwrap();
+
+        assert_eq!(options.duration, 53);
+    }
+
+    #[test]
+    fn test_build_simulation_options_strict() {
+        let builder = || {
+            SimulationOptionsBuilder::new()
+                .total_users(100)
+                .strict(true)
+        };
+        assert!(builder().duration(10).adoption_rate(0.1).build().is_ok());
+
+        for invalid in [
+            builder().valuation_model(ValuationModel::Exponential(0.0)),
+            builder().total_users(0).adoption_rate(0.1),
+            builder().market_volatility(1.5),
+            builder().transaction_fee_percentage(-1.0),
+            builder().duration(10).time_span(TimeSpan::Days(10)),
+            builder().rounds_per_interval(0),
+            builder().behaviour_mix(BTreeMap::from([(UserBehaviour::Trader, 0.0)])),
+            builder().holder_balances(vec![Decimal::ONE; 101]),
+        ] {
+            assert!(matches!(
+                invalid.build(),
+                Err(SimulationError::StrictViolation(_))
+            ));
+        }
+
+        // The permissive mode keeps the defaults
+        assert!(builder()
+            .strict(false)
+            .valuation_model(ValuationModel::Exponential(0.0))
+            .duration(10)
+            .time_span(TimeSpan::Days(10))
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_effective_options() {
+        let options = SimulationOptionsBuilder::new()
+            .total_users(100)
+            .duration(3)
+            .interval_type(SimulationInterval::Weekly)
+            .decimal_precision(2)
+            .transaction_fee(SimulationTransactionFee::Fixed(Decimal::new(5, 1)))
+            .build()
+            .unwrap();
+
+        let effective = options.effective().unwrap();
+        assert_eq!(effective.intervals, 3);
+        assert_eq!(effective.interval_hours, 168);
+        assert_eq!(effective.rounds_per_interval, 168);
+        assert_eq!(effective.network_fee, Some(Decimal::new(5, 1)));
+        assert_eq!(effective.options.rounds_per_interval, Some(168));
+        assert_eq!(
+            effective.options.transaction_fee_percentage,
+            Some(Percentage::default())
+        );
+        assert_eq!(effective.options.adoption_rate, Some(Decimal::ZERO));
+        assert_eq!(effective.options.rounding.balance_precision, Some(2));
+        assert_eq!(effective.options.effective().unwrap(), effective);
+
+        let unresolved = SimulationOptions {
+            transaction_fee: Some(SimulationTransactionFee::Ethereum(crate::EthereumFee {
+                gas_used: 21_000,
+                gas_price: None,
+                ether_price: None,
+            })),
+            ..options
+        };
+        assert!(unresolved.effective().is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let builder = SimulationOptionsBuilder::new()
+            .total_users(100)
+            .valuation_model(ValuationModel::Exponential(0.5))
+            .time_span(TimeSpan::Months(18));
+        let json = serde_json::to_string(&builder).unwrap();
+        assert_eq!(
+            serde_json::from_str::<SimulationOptionsBuilder>(&json).unwrap(),
+            builder
+        );
+
+        let options = builder
+            .behaviour_mix(BTreeMap::from([(UserBehaviour::Trader, 1.0)]))
+            .build()
+            .unwrap();
+        let json = serde_json::to_string(&options).unwrap();
+        assert_eq!(
+            serde_json::from_str::<SimulationOptions>(&json).unwrap(),
+            options
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_unknown_field() {
+        let mut json = serde_json::to_value(
+            SimulationOptionsBuilder::new()
+                .total_users(100)
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+        json["market_volatilty"] = serde_json::json!(0.3);
+
+        // Unknown fields are only rejected with the strict serde feature
+        assert_eq!(
+            serde_json::from_value::<SimulationOptions>(json).is_err(),
+            cfg!(feature = "strict_serde")
+        );
+        assert_eq!(
+            serde_json::from_str::<SimulationOptionsBuilder>(r#"{"total_user": 100}"#).is_err(),
+            cfg!(feature = "strict_serde")
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_builder_without_behaviour_profiles() {
+        // Builders saved before the behaviour profiles existed still deserialize
+        let builder =
+            serde_json::from_str::<SimulationOptionsBuilder>(r#"{"total_users": 100}"#).unwrap();
+
+        assert!(builder.behaviour_profiles.is_empty());
+        assert_eq!(builder.build().unwrap().total_users, 100);
+    }
 }
@@ -0,0 +1,256 @@
+//! # Engine trait module
+//!
+//! This module contains the engine trait, which runs the steps of a simulation interval.
+//!
+//! The `Simulation` facade drives the run (users, unlocks, emissions, reports, observers),
+//! and delegates user adoption, token valuation, and the trades of an interval to its engine.
+//! `DefaultEngine` is the built-in agent-based engine; experimental engines (e.g. cohort-based or order-book)
+//! can be swapped in with `Simulation::set_engine`, and delegate to `DefaultEngine` for the steps they keep.
+
+use std::{collections::HashSet, fmt::Debug};
+
+use rand::RngCore;
+use rust_decimal::{prelude::*, Decimal, MathematicalOps};
+use uuid::Uuid;
+
+use crate::{
+    IntervalContext, Simulation, SimulationError, SimulationReport, Token, UserStore,
+    ValuationModel,
+};
+
+/// Engine running the steps of a simulation interval.
+pub trait SimulationEngine: Debug + Send + Sync {
+    /// Simulate user adoption based on the current number of users.
+    ///
+    /// # Arguments
+    ///
+    /// * `simulation` - The simulation being run.
+    /// * `current_users` - The current number of users.
+    ///
+    /// # Returns
+    ///
+    /// The new number of users after adoption.
+    fn simulate_adoption(
+        &self,
+        simulation: &Simulation,
+        current_users: u64,
+    ) -> Result<u64, SimulationError>;
+
+    /// Calculate the valuation of the token based on the number of users.
+    ///
+    /// # Arguments
+    ///
+    /// * `simulation` - The simulation being run.
+    /// * `token` - The token used in the simulation.
+    /// * `users` - The current number of users.
+    ///
+    /// # Returns
+    ///
+    /// The calculated token valuation.
+    fn calculate_valuation(&self, simulation: &Simulation, token: &Token, users: u64) -> Decimal;
+
+    /// Simulate the trades of an interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `simulation` - The simulation being run.
+    /// * `context` - Context of the interval, with its duration, price, and fees.
+    /// * `users` - Users taking part in the simulation, updated with the trades.
+    /// * `rng` - Random number generator of the run.
+    ///
+    /// # Returns
+    ///
+    /// A report of the interval, and the IDs of the users with an executed trade.
+    fn process_interval(
+        &self,
+        simulation: &Simulation,
+        context: &IntervalContext,
+        users: &mut UserStore,
+        rng: &mut dyn RngCore,
+    ) -> Result<(SimulationReport, HashSet<Uuid>), SimulationError>;
+}
+
+/// Built-in agent-based engine.
+/// Used when no engine is set on the simulation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DefaultEngine;
+
+impl SimulationEngine for DefaultEngine {
+    /// Grow the users by the adoption rate of the options.
+    /// If the adoption rate is not set, the current number of users is returned.
+    fn simulate_adoption(
+        &self,
+        simulation: &Simulation,
+        current_users: u64,
+    ) -> Result<u64, SimulationError> {
+        match simulation.options.adoption_rate {
+            Some(rate) => {
+                #[cfg(feature = "log")]
+                log::debug!(
+                    "Simulating user adoption for simulation: {}",
+                    simulation.name
+                );
+
+                let multiplier = simulation
+                    .options
+                    .market_regime
+                    .map_or(1.0, |regime| regime.adoption_multiplier());
+                let new_users = (current_users as f64
+                    * rate.to_f64().ok_or(SimulationError::InvalidDecimal)?
+                    * multiplier)
+                    .round() as u64;
+
+                let total = current_users + new_users;
+
+                #[cfg(feature = "log")]
+                log::debug!("User adoption simulated: {}", total);
+
+                Ok(total)
+            }
+            None => Ok(current_users),
+        }
+    }
+
+    /// Value the token with the valuation model of the options.
+    /// If the valuation model is not set, or is the random walk simulated over the run, the default valuation is returned.
+    fn calculate_valuation(&self, simulation: &Simulation, token: &Token, users: u64) -> Decimal {
+        match simulation.options.valuation_model {
+            Some(ValuationModel::Linear) => {
+                #[cfg(feature = "log")]
+                log::debug!(
+                    "Calculating linear valuation for simulation: {}",
+                    simulation.name
+                );
+
+                let valuation = Decimal::from(users) * token.initial_price.value();
+
+                #[cfg(feature = "log")]
+                log::debug!("Linear valuation calculated: {}", valuation);
+
+                valuation
+            }
+            Some(ValuationModel::Exponential(factor)) => {
+                #[cfg(feature = "log")]
+                log::debug!("Calculating exponential valuation with factor: {}", factor);
+
+                let exponent = match Decimal::from_f64(factor) {
+                    Some(factor) => Decimal::from(users) / factor,
+                    None => Decimal::from(users),
+                };
+
+                let valuation = match exponent.checked_exp() {
+                    Some(exp) => token.initial_price.value() * exp,
+                    None => token.initial_price.value(),
+                };
+
+                #[cfg(feature = "log")]
+                log::debug!("Exponential valuation calculated: {}", valuation);
+
+                valuation
+            }
+            _ => Decimal::default(),
+        }
+    }
+
+    /// Trade every agent against the market model of the options.
+    fn process_interval(
+        &self,
+        simulation: &Simulation,
+        context: &IntervalContext,
+        users: &mut UserStore,
+        rng: &mut dyn RngCore,
+    ) -> Result<(SimulationReport, HashSet<Uuid>), SimulationError> {
+        simulation.trade_interval(context, users, rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    };
+
+    use super::*;
+
+    /// Engine doubling the users every interval, and counting the processed intervals.
+    #[derive(Debug, Default)]
+    struct DoublingEngine {
+        intervals: AtomicU64,
+    }
+
+    impl SimulationEngine for Arc<DoublingEngine> {
+        fn simulate_adoption(
+            &self,
+            _simulation: &Simulation,
+            current_users: u64,
+        ) -> Result<u64, SimulationError> {
+            Ok(current_users * 2)
+        }
+
+        fn calculate_valuation(
+            &self,
+            simulation: &Simulation,
+            token: &Token,
+            users: u64,
+        ) -> Decimal {
+            DefaultEngine.calculate_valuation(simulation, token, users)
+        }
+
+        fn process_interval(
+            &self,
+            simulation: &Simulation,
+            context: &IntervalContext,
+            users: &mut UserStore,
+            rng: &mut dyn RngCore,
+        ) -> Result<(SimulationReport, HashSet<Uuid>), SimulationError> {
+            self.intervals.fetch_add(1, Ordering::Relaxed);
+
+            DefaultEngine.process_interval(simulation, context, users, rng)
+        }
+    }
+
+    fn setup() -> Simulation {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(10)
+            .duration(3)
+            .build()
+            .unwrap();
+
+        Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_run_with_custom_engine() {
+        let engine = Arc::new(DoublingEngine::default());
+        let mut simulation = setup();
+        simulation.set_engine(Arc::clone(&engine));
+
+        simulation.run().unwrap();
+
+        assert_eq!(engine.intervals.load(Ordering::Relaxed), 3);
+        assert_eq!(simulation.simulate_adoption(10).unwrap(), 20);
+        assert_eq!(simulation.report.users.as_ref().unwrap().len(), 80);
+    }
+
+    #[test]
+    fn test_default_engine_matches_facade() {
+        let mut simulation = setup();
+        simulation.options.adoption_rate = Some(Decimal::new(5, 1));
+
+        assert_eq!(
+            DefaultEngine.simulate_adoption(&simulation, 100).unwrap(),
+            simulation.simulate_adoption(100).unwrap()
+        );
+        assert_eq!(simulation.simulate_adoption(100).unwrap(), 150);
+    }
+}
@@ -0,0 +1,423 @@
+//! # Ensemble module
+//!
+//! This module contains the ensemble of Monte Carlo runs.
+//!
+//! An ensemble holds many completed simulations of the same setup, summarizes their outcomes,
+//! extracts percentiles per interval, and compares the outcome distributions of two ensembles,
+//! e.g. a baseline and a variant.
+
+use rust_decimal::{prelude::*, Decimal};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{stats, Simulation, SimulationError, SimulationReport};
+
+/// Metric of a simulation report used to compare outcomes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum EnsembleMetric {
+    /// Token price.
+    TokenPrice,
+
+    /// Profit or loss of the users.
+    ProfitLoss,
+
+    /// Number of trades.
+    Trades,
+
+    /// Liquidity of the token.
+    Liquidity,
+
+    /// Adoption rate of the token.
+    AdoptionRate,
+
+    /// Burn rate of the token.
+    BurnRate,
+
+    /// Inflation rate of the token.
+    InflationRate,
+
+    /// User retention rate.
+    UserRetention,
+
+    /// Network activity.
+    NetworkActivity,
+
+    /// Number of users at the end of the simulation.
+    FinalUserCount,
+}
+
+/// Summary statistics of a metric across the runs of an ensemble.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct MetricSummary {
+    /// Summarized metric.
+    pub metric: EnsembleMetric,
+
+    /// Mean value.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub mean: Decimal,
+
+    /// Lowest value.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub min: Decimal,
+
+    /// Highest value.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub max: Decimal,
+
+    /// 5th percentile.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub p5: Decimal,
+
+    /// Median.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub p50: Decimal,
+
+    /// 95th percentile.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub p95: Decimal,
+}
+
+/// Kolmogorov-Smirnov comparison of the outcome distributions of two ensembles.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct EnsembleComparison {
+    /// Compared metric.
+    pub metric: EnsembleMetric,
+
+    /// Largest distance between the empirical distribution functions, between 0 and 1.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub statistic: Decimal,
+
+    /// Asymptotic probability of a statistic at least as large, if both distributions are the same.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub p_value: Decimal,
+}
+
+/// Ensemble of completed simulations.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Ensemble {
+    /// Completed simulations of the ensemble.
+    pub simulations: Vec<Simulation>,
+}
+
+impl EnsembleMetric {
+    /// All metrics, in declaration order.
+    pub const ALL: [EnsembleMetric; 10] = [
+        EnsembleMetric::TokenPrice,
+        EnsembleMetric::ProfitLoss,
+        EnsembleMetric::Trades,
+        EnsembleMetric::Liquidity,
+        EnsembleMetric::AdoptionRate,
+        EnsembleMetric::BurnRate,
+        EnsembleMetric::InflationRate,
+        EnsembleMetric::UserRetention,
+        EnsembleMetric::NetworkActivity,
+        EnsembleMetric::FinalUserCount,
+    ];
+
+    /// Get the value of the metric from a report.
+    ///
+    /// # Arguments
+    ///
+    /// * `report` - Simulation report.
+    ///
+    /// # Returns
+    ///
+    /// Value of the metric.
+    pub fn value(&self, report: &SimulationReport) -> Decimal {
+        match self {
+            EnsembleMetric::TokenPrice => report.token_price.value(),
+            EnsembleMetric::ProfitLoss => report.profit_loss.value(),
+            EnsembleMetric::Trades => Decimal::from(report.trades),
+            EnsembleMetric::Liquidity => report.liquidity,
+            EnsembleMetric::AdoptionRate => report.adoption_rate,
+            EnsembleMetric::BurnRate => report.burn_rate,
+            EnsembleMetric::InflationRate => report.inflation_rate,
+            EnsembleMetric::UserRetention => report.user_retention,
+            EnsembleMetric::NetworkActivity => Decimal::from(report.network_activity),
+            EnsembleMetric::FinalUserCount => Decimal::from(report.final_user_count),
+        }
+    }
+}
+
+impl MetricSummary {
+    /// Summarize the values of a metric.
+    ///
+    /// # Arguments
+    ///
+    /// * `metric` - Summarized metric.
+    /// * `values` - Values of the metric, in any order.
+    ///
+    /// # Returns
+    ///
+    /// Summary statistics of the values.
+    pub fn from_values(metric: EnsembleMetric, mut values: Vec<Decimal>) -> Self {
+        values.sort();
+
+        MetricSummary {
+            metric,
+            mean: stats::mean(&values),
+            min: values.first().copied().unwrap_or_default(),
+            max: values.last().copied().unwrap_or_default(),
+            p5: stats::percentile(&values, Decimal::new(5, 0)),
+            p50: stats::percentile(&values, Decimal::new(50, 0)),
+            p95: stats::percentile(&values, Decimal::new(95, 0)),
+        }
+    }
+}
+
+impl Ensemble {
+    /// Create a new ensemble from completed simulations.
+    ///
+    /// # Arguments
+    ///
+    /// * `simulations` - Completed simulations.
+    ///
+    /// # Returns
+    ///
+    /// New ensemble.
+    pub fn new(simulations: Vec<Simulation>) -> Self {
+        Ensemble { simulations }
+    }
+
+    /// Merge the runs of another ensemble into this one.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Ensemble to merge.
+    pub fn merge(&mut self, other: Ensemble) {
+        self.simulations.extend(other.simulations);
+    }
+
+    /// Get the values of a metric in the final reports, sorted in ascending order.
+    ///
+    /// # Arguments
+    ///
+    /// * `metric` - Metric to extract.
+    ///
+    /// # Returns
+    ///
+    /// Sorted values of the metric, one per run.
+    pub fn values(&self, metric: EnsembleMetric) -> Vec<Decimal> {
+        let mut values: Vec<Decimal> = self
+            .simulations
+            .iter()
+            .map(|simulation| metric.value(&simulation.report))
+            .collect();
+        values.sort();
+
+        values
+    }
+
+    /// Summarize a metric of the final reports across the runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `metric` - Metric to summarize.
+    ///
+    /// # Returns
+    ///
+    /// Summary statistics of the metric.
+    pub fn summary(&self, metric: EnsembleMetric) -> MetricSummary {
+        MetricSummary::from_values(metric, self.values(metric))
+    }
+
+    /// Extract a percentile of a metric for each interval, across the runs.
+    /// Runs shorter than others only contribute to the intervals they have.
+    ///
+    /// # Arguments
+    ///
+    /// * `metric` - Metric to extract.
+    /// * `percentile` - Percentile to extract, between 0 and 100.
+    ///
+    /// # Returns
+    ///
+    /// Value of the percentile for each interval.
+    pub fn interval_percentile(&self, metric: EnsembleMetric, percentile: Decimal) -> Vec<Decimal> {
+        let intervals = self
+            .simulations
+            .iter()
+            .map(|simulation| simulation.interval_reports.len())
+            .max()
+            .unwrap_or_default();
+
+        (0..intervals)
+            .map(|index| {
+                let mut values: Vec<Decimal> = self
+                    .simulations
+                    .iter()
+                    .filter_map(|simulation| simulation.interval_reports.get(index))
+                    .map(|report| metric.value(report))
+                    .collect();
+                values.sort();
+
+                stats::percentile(&values, percentile)
+            })
+            .collect()
+    }
+
+    /// Compare the distribution of a metric with another ensemble, using a two-sample Kolmogorov-Smirnov test.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Ensemble to compare with, e.g. a variant of the baseline.
+    /// * `metric` - Metric to compare.
+    ///
+    /// # Returns
+    ///
+    /// Comparison of the outcome distributions.
+    pub fn compare(&self, other: &Ensemble, metric: EnsembleMetric) -> EnsembleComparison {
+        let (a, b) = (self.values(metric), other.values(metric));
+        let statistic = stats::ks_statistic(&a, &b);
+        let p_value = stats::ks_p_value(statistic.to_f64().unwrap_or_default(), a.len(), b.len());
+
+        EnsembleComparison {
+            metric,
+            statistic,
+            p_value: Decimal::from_f64(p_value).unwrap_or(Decimal::ONE),
+        }
+    }
+}
+
+impl Simulation {
+    /// Run the simulation many times and collect the runs in an ensemble.
+    /// Each run starts from the current token and options of the simulation, the simulation itself is not modified.
+    ///
+    /// # Arguments
+    ///
+    /// * `runs` - Number of Monte Carlo runs.
+    ///
+    /// # Returns
+    ///
+    /// Ensemble of the completed runs.
+    pub fn ensemble(&self, runs: u64) -> Result<Ensemble, SimulationError> {
+        if runs == 0 {
+            return Err(SimulationError::InvalidRuns);
+        }
+
+        let variants = (0..runs)
+            .map(|_| (self.token.clone(), self.options.clone()))
+            .collect();
+
+        Ok(Ensemble::new(self.run_variants(variants)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup(total_users: u64) -> Simulation {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .total_supply(1_000_000)
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(total_users)
+            .duration(5)
+            .build()
+            .unwrap();
+
+        Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_ensemble() {
+        let ensemble = setup(10).ensemble(4).unwrap();
+
+        assert_eq!(ensemble.simulations.len(), 4);
+        assert_eq!(
+            setup(10).ensemble(0).unwrap_err(),
+            SimulationError::InvalidRuns
+        );
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut ensemble = setup(10).ensemble(2).unwrap();
+        ensemble.merge(setup(10).ensemble(3).unwrap());
+
+        assert_eq!(ensemble.simulations.len(), 5);
+    }
+
+    #[test]
+    fn test_summary() {
+        let ensemble = setup(10).ensemble(5).unwrap();
+        let summary = ensemble.summary(EnsembleMetric::FinalUserCount);
+
+        assert_eq!(summary.mean, Decimal::new(10, 0));
+        assert_eq!(summary.min, Decimal::new(10, 0));
+        assert_eq!(summary.max, Decimal::new(10, 0));
+        assert_eq!(summary.p50, Decimal::new(10, 0));
+
+        let summary = ensemble.summary(EnsembleMetric::TokenPrice);
+        assert!(summary.min <= summary.p5);
+        assert!(summary.p5 <= summary.p50);
+        assert!(summary.p50 <= summary.p95);
+        assert!(summary.p95 <= summary.max);
+    }
+
+    #[test]
+    fn test_interval_percentile() {
+        let ensemble = setup(10).ensemble(3).unwrap();
+        let percentiles =
+            ensemble.interval_percentile(EnsembleMetric::FinalUserCount, Decimal::new(50, 0));
+
+        assert_eq!(percentiles, vec![Decimal::new(10, 0); 5]);
+        assert!(Ensemble::default()
+            .interval_percentile(EnsembleMetric::TokenPrice, Decimal::new(50, 0))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_compare() {
+        let baseline = setup(10).ensemble(5).unwrap();
+        let variant = setup(20).ensemble(5).unwrap();
+
+        let same = baseline.compare(&baseline, EnsembleMetric::FinalUserCount);
+        assert_eq!(same.statistic, Decimal::default());
+        assert_eq!(same.p_value, Decimal::ONE);
+
+        let different = baseline.compare(&variant, EnsembleMetric::FinalUserCount);
+        assert_eq!(different.statistic, Decimal::ONE);
+        assert!(different.p_value < Decimal::new(5, 2));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let ensemble = setup(10).ensemble(2).unwrap();
+        let json = serde_json::to_string(&ensemble).unwrap();
+        let decoded = serde_json::from_str::<Ensemble>(&json).unwrap();
+        assert_eq!(decoded.simulations.len(), 2);
+        assert_eq!(decoded.simulations[0].id, ensemble.simulations[0].id);
+        assert_eq!(
+            decoded.simulations[0].report.trades,
+            ensemble.simulations[0].report.trades
+        );
+
+        let summary = ensemble.summary(EnsembleMetric::FinalUserCount);
+        let json = serde_json::to_string(&summary).unwrap();
+        assert_eq!(
+            serde_json::from_str::<MetricSummary>(&json).unwrap(),
+            summary
+        );
+
+        let comparison = ensemble.compare(&ensemble, EnsembleMetric::Trades);
+        let json = serde_json::to_string(&comparison).unwrap();
+        assert_eq!(
+            serde_json::from_str::<EnsembleComparison>(&json).unwrap(),
+            comparison
+        );
+    }
+}
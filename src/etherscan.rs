@@ -0,0 +1,284 @@
+//! # Etherscan module
+//!
+//! This module contains the client of the Etherscan API, which fetches a snapshot of the holders of an existing ERC-20 token
+//! to start a simulation from its real distribution.
+//!
+//! The client pages through the `tokenholderlist` endpoint, which requires an API key,
+//! and converts the raw quantities of the holders to token units with the decimals of the token.
+
+use rust_decimal::Decimal;
+
+use crate::{SimulationError, SimulationOptionsBuilder};
+
+/// Base URL of the Etherscan API.
+pub const ETHERSCAN_API_URL: &str = "https://api.etherscan.io/v2/api";
+
+/// Number of holders fetched per page.
+const PAGE_SIZE: usize = 1_000;
+
+/// Client of the Etherscan API.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Etherscan {
+    /// Base URL of the API.
+    pub base_url: String,
+
+    /// API key, sent as the `apikey` parameter.
+    pub api_key: String,
+
+    /// ID of the chain, 1 for the Ethereum mainnet.
+    pub chain_id: u64,
+}
+
+/// Holder of a token.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenHolder {
+    /// Address of the holder.
+    pub address: String,
+
+    /// Balance of the holder, in token units.
+    pub balance: Decimal,
+}
+
+/// Snapshot of the holders of a token, largest balance first.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HolderSnapshot {
+    /// Holders of the token.
+    pub holders: Vec<TokenHolder>,
+}
+
+impl Etherscan {
+    /// Create a new client of the Ethereum mainnet.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - Etherscan API key.
+    ///
+    /// # Returns
+    ///
+    /// New client.
+    pub fn new(api_key: &str) -> Self {
+        Etherscan {
+            base_url: ETHERSCAN_API_URL.to_string(),
+            api_key: api_key.to_string(),
+            chain_id: 1,
+        }
+    }
+
+    /// Fetch the holders of a token.
+    ///
+    /// # Arguments
+    ///
+    /// * `contract_address` - Address of the ERC-20 contract.
+    /// * `decimals` - Decimals of the token, e.g. 18.
+    /// * `max_holders` - Maximum number of holders to fetch, the largest first.
+    ///
+    /// # Returns
+    ///
+    /// Snapshot of the holders, or an error if a request fails or a response is invalid.
+    pub fn holder_snapshot(
+        &self,
+        contract_address: &str,
+        decimals: u32,
+        max_holders: usize,
+    ) -> Result<HolderSnapshot, SimulationError> {
+        let mut holders = vec![];
+
+        for page in 1.. {
+            let offset = PAGE_SIZE.min(max_holders - holders.len());
+            if offset == 0 {
+                break;
+            }
+
+            let body = ureq::get(&self.base_url)
+                .query("chainid", &self.chain_id.to_string())
+                .query("module", "token")
+                .query("action", "tokenholderlist")
+                .query("contractaddress", contract_address)
+                .query("page", &page.to_string())
+                .query("offset", &offset.to_string())
+                .query("apikey", &self.api_key)
+                .call()
+                .and_then(|response| Ok(response.into_string()?))
+                .map_err(|err| SimulationError::FetchFailed(format!("Etherscan: {err}")))?;
+
+            let page = parse_holder_list(&body, decimals)?;
+            let last = page.len() < offset;
+            holders.extend(page);
+            if last {
+                break;
+            }
+        }
+
+        let mut snapshot = HolderSnapshot { holders };
+        snapshot.holders.sort_by(|a, b| b.balance.cmp(&a.balance));
+
+        Ok(snapshot)
+    }
+}
+
+impl HolderSnapshot {
+    /// Get the balances of the holders.
+    ///
+    /// # Returns
+    ///
+    /// Balance of each holder, largest first.
+    pub fn balances(&self) -> Vec<Decimal> {
+        self.holders.iter().map(|holder| holder.balance).collect()
+    }
+
+    /// Start the users of a simulation from the snapshot: one user per holder, each with its balance.
+    ///
+    /// # Arguments
+    ///
+    /// * `builder` - Options builder to complete.
+    ///
+    /// # Returns
+    ///
+    /// The options builder with the holders.
+    pub fn apply_holders(&self, builder: SimulationOptionsBuilder) -> SimulationOptionsBuilder {
+        builder
+            .total_users(self.holders.len() as u64)
+            .holder_balances(self.balances())
+    }
+}
+
+/// Parse a page of the `tokenholderlist` endpoint.
+///
+/// # Arguments
+///
+/// * `json` - Response, with the holders as `TokenHolderAddress` and raw `TokenHolderQuantity`.
+/// * `decimals` - Decimals of the token.
+///
+/// # Returns
+///
+/// Holders of the page, or an error if the response is an error or has invalid quantities.
+pub fn parse_holder_list(json: &str, decimals: u32) -> Result<Vec<TokenHolder>, SimulationError> {
+    let invalid = |reason: &str| SimulationError::InvalidImport(format!("Etherscan: {reason}"));
+    let value: serde_json::Value =
+        serde_json::from_str(json).map_err(|err| invalid(&err.to_string()))?;
+
+    let result = value.get("result");
+    let holders = match (
+        value.get("status").and_then(|status| status.as_str()),
+        result,
+    ) {
+        (Some("1"), Some(serde_json::Value::Array(holders))) => holders,
+        // An empty page is reported as an error without holders
+        (Some("0"), _)
+            if value.get("message").and_then(|message| message.as_str())
+                == Some("No data found") =>
+        {
+            return Ok(vec![])
+        }
+        (_, Some(serde_json::Value::String(message))) => return Err(invalid(message)),
+        _ => return Err(invalid("expected a list of holders")),
+    };
+
+    holders
+        .iter()
+        .map(|holder| {
+            let address = holder
+                .get("TokenHolderAddress")
+                .and_then(|address| address.as_str());
+            let quantity = holder
+                .get("TokenHolderQuantity")
+                .and_then(|quantity| quantity.as_str())
+                .and_then(|quantity| quantity.parse::<i128>().ok());
+
+            match (address, quantity) {
+                (Some(address), Some(quantity)) => Ok(TokenHolder {
+                    address: address.to_string(),
+                    balance: Decimal::try_from_i128_with_scale(quantity, decimals)
+                        .map_err(|_| invalid("quantity out of range"))?
+                        .normalize(),
+                }),
+                _ => Err(invalid("expected holders with an address and a quantity")),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    use crate::Simulation;
+
+    use super::*;
+
+    const HOLDER_LIST: &str = r#"{"status": "1", "message": "OK", "result": [
+        {"TokenHolderAddress": "0xa", "TokenHolderQuantity": "1500000000000000000"},
+        {"TokenHolderAddress": "0xb", "TokenHolderQuantity": "250000000000000000000"}
+    ]}"#;
+
+    #[test]
+    fn test_parse_holder_list() {
+        let holders = parse_holder_list(HOLDER_LIST, 18).unwrap();
+
+        assert_eq!(holders[0].address, "0xa");
+        assert_eq!(holders[0].balance, Decimal::new(15, 1));
+        assert_eq!(holders[1].balance, Decimal::new(250, 0));
+
+        assert!(parse_holder_list(
+            r#"{"status": "0", "message": "No data found", "result": []}"#,
+            18
+        )
+        .unwrap()
+        .is_empty());
+        for invalid in [
+            r#"{"status": "0", "message": "NOTOK", "result": "Invalid API Key"}"#,
+            r#"{"status": "1", "result": [{"TokenHolderAddress": "0xa", "TokenHolderQuantity": "1.5"}]}"#,
+        ] {
+            assert!(matches!(
+                parse_holder_list(invalid, 18),
+                Err(SimulationError::InvalidImport(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_holder_snapshot() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = vec![0; 1024];
+            let read = stream.read(&mut request).unwrap();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{HOLDER_LIST}",
+                HOLDER_LIST.len()
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+
+            String::from_utf8_lossy(&request[..read]).to_string()
+        });
+
+        let client = Etherscan {
+            base_url: format!("http://127.0.0.1:{port}/v2/api"),
+            ..Etherscan::new("secret")
+        };
+        let snapshot = client.holder_snapshot("0xtoken", 18, 10).unwrap();
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET /v2/api?chainid=1&module=token&action=tokenholderlist&contractaddress=0xtoken&page=1&offset=10&apikey=secret HTTP/1.1\r\n"));
+
+        // The page is shorter than requested, so it is the last one
+        assert_eq!(snapshot.holders.len(), 2);
+        assert_eq!(snapshot.holders[0].address, "0xb");
+
+        let options = snapshot
+            .apply_holders(Simulation::options_builder())
+            .build()
+            .unwrap();
+        assert_eq!(options.total_users, 2);
+        assert_eq!(
+            options.holder_balances,
+            Some(vec![Decimal::new(250, 0), Decimal::new(15, 1)])
+        );
+    }
+}
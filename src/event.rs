@@ -0,0 +1,483 @@
+//! # Event module
+//!
+//! This module contains the event sinks publishing the simulation events to message brokers, enabled by the `serde` feature.
+//!
+//! Add an `EventPublisher` to a simulation as an observer: it publishes the start, the interval reports,
+//! and the outcome of every run as JSON to a topic, so large sweeps can be consumed by stream processors.
+//! Events are keyed by the ID of the simulation, so the events of one simulation stay in order.
+//! The NATS sink only relies on the standard library, the Kafka sink is enabled by the `kafka` feature.
+
+use std::{
+    fmt::Debug,
+    io::{BufRead, BufReader, Write},
+    net::TcpStream,
+    sync::{Mutex, PoisonError},
+    time::Duration,
+};
+
+#[cfg(feature = "kafka")]
+use rdkafka::{
+    config::ClientConfig,
+    producer::{BaseProducer, BaseRecord, Producer},
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{IntervalContext, Simulation, SimulationError, SimulationObserver, SimulationReport};
+
+/// Timeout of the NATS sink, for connecting, writing, and reading.
+const NATS_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Timeout of the Kafka sink, for delivering the pending events when it is dropped.
+#[cfg(feature = "kafka")]
+const KAFKA_FLUSH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Event of a simulation run.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SimulationEvent {
+    /// Simulation has started.
+    Started {
+        /// ID of the simulation.
+        simulation_id: Uuid,
+
+        /// Name of the simulation.
+        name: String,
+    },
+
+    /// Interval has been processed.
+    Interval {
+        /// ID of the simulation.
+        simulation_id: Uuid,
+
+        /// Report of the interval.
+        report: SimulationReport,
+    },
+
+    /// Simulation has completed.
+    Completed {
+        /// ID of the simulation.
+        simulation_id: Uuid,
+
+        /// Final report of the simulation.
+        report: SimulationReport,
+    },
+
+    /// Simulation has failed or was cancelled.
+    Failed {
+        /// ID of the simulation.
+        simulation_id: Uuid,
+
+        /// Error of the simulation.
+        error: String,
+    },
+}
+
+/// Sink publishing the events to a message broker.
+pub trait EventSink: Debug + Send + Sync {
+    /// Publish an event.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - Topic, or subject, to publish to.
+    /// * `key` - Key of the event, the ID of the simulation.
+    /// * `payload` - JSON of the event.
+    ///
+    /// # Returns
+    ///
+    /// Whether the event was published.
+    fn publish(&self, topic: &str, key: &str, payload: &[u8]) -> Result<(), SimulationError>;
+}
+
+/// Observer publishing the events of the simulation to a sink.
+/// Publishing failures do not change the outcome of the simulation, they are only logged.
+#[derive(Debug)]
+pub struct EventPublisher<S: EventSink> {
+    /// Sink publishing the events.
+    sink: S,
+
+    /// Topic the events are published to.
+    topic: String,
+
+    /// ID of the running simulation, set when the run starts.
+    simulation_id: Mutex<Uuid>,
+}
+
+impl<S: EventSink> EventPublisher<S> {
+    /// Create a new event publisher.
+    ///
+    /// # Arguments
+    ///
+    /// * `sink` - Sink publishing the events.
+    /// * `topic` - Topic the events are published to.
+    ///
+    /// # Returns
+    ///
+    /// New event publisher.
+    pub fn new(sink: S, topic: impl Into<String>) -> Self {
+        EventPublisher {
+            sink,
+            topic: topic.into(),
+            simulation_id: Mutex::new(Uuid::nil()),
+        }
+    }
+
+    /// Get the ID of the running simulation.
+    ///
+    /// # Returns
+    ///
+    /// ID of the simulation.
+    fn simulation_id(&self) -> Uuid {
+        *self
+            .simulation_id
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Publish an event to the sink.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - Event to publish.
+    fn publish(&self, event: SimulationEvent) {
+        let result = serde_json::to_vec(&event)
+            .map_err(|error| SimulationError::EventPublishFailed(error.to_string()))
+            .and_then(|payload| {
+                self.sink
+                    .publish(&self.topic, &self.simulation_id().to_string(), &payload)
+            });
+
+        if let Err(_error) = result {
+            #[cfg(feature = "log")]
+            log::debug!("Failed to publish event to {}: {}", self.topic, _error);
+        }
+    }
+}
+
+impl<S: EventSink> SimulationObserver for EventPublisher<S> {
+    fn on_start(&self, simulation: &Simulation) {
+        *self
+            .simulation_id
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = simulation.id;
+
+        self.publish(SimulationEvent::Started {
+            simulation_id: simulation.id,
+            name: simulation.name.clone(),
+        });
+    }
+
+    fn on_interval(&self, _context: &IntervalContext, report: &SimulationReport) {
+        self.publish(SimulationEvent::Interval {
+            simulation_id: self.simulation_id(),
+            report: report.clone(),
+        });
+    }
+
+    fn on_complete(&self, report: &SimulationReport) {
+        self.publish(SimulationEvent::Completed {
+            simulation_id: self.simulation_id(),
+            report: report.clone(),
+        });
+    }
+
+    fn on_fail(&self, error: &SimulationError) {
+        self.publish(SimulationEvent::Failed {
+            simulation_id: self.simulation_id(),
+            error: error.to_string(),
+        });
+    }
+}
+
+/// Sink publishing the events to a NATS server, over plain TCP.
+/// The connection is opened on the first event and reopened once if it was closed.
+#[derive(Debug)]
+pub struct NatsSink {
+    /// Address of the server, e.g. `127.0.0.1:4222`.
+    address: String,
+
+    /// Open connection to the server.
+    connection: Mutex<Option<TcpStream>>,
+}
+
+impl NatsSink {
+    /// Create a new NATS sink.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - Address of the server, e.g. `127.0.0.1:4222`.
+    ///
+    /// # Returns
+    ///
+    /// New NATS sink.
+    pub fn new(address: impl Into<String>) -> Self {
+        NatsSink {
+            address: address.into(),
+            connection: Mutex::new(None),
+        }
+    }
+
+    /// Open a connection to the server: wait for its `INFO` and introduce the client.
+    ///
+    /// # Returns
+    ///
+    /// Open connection.
+    fn connect(&self) -> std::io::Result<TcpStream> {
+        let mut stream = TcpStream::connect(&self.address)?;
+        stream.set_read_timeout(Some(NATS_TIMEOUT))?;
+        stream.set_write_timeout(Some(NATS_TIMEOUT))?;
+
+        let mut info = String::new();
+        BufReader::new(&stream).read_line(&mut info)?;
+        if !info.starts_with("INFO") {
+            return Err(std::io::Error::other(format!(
+                "unexpected greeting: {}",
+                info.trim_end()
+            )));
+        }
+
+        stream.write_all(
+            b"CONNECT {\"verbose\":false,\"pedantic\":false,\"name\":\"tokenomics-simulator\"}\r\n",
+        )?;
+
+        Ok(stream)
+    }
+}
+
+impl EventSink for NatsSink {
+    fn publish(&self, topic: &str, _key: &str, payload: &[u8]) -> Result<(), SimulationError> {
+        let mut message = format!("PUB {} {}\r\n", topic, payload.len()).into_bytes();
+        message.extend_from_slice(payload);
+        message.extend_from_slice(b"\r\n");
+
+        let mut connection = self
+            .connection
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        // The server closes idle connections, so retry once on a fresh connection
+        for attempt in 0..2 {
+            let stream = match connection.as_mut() {
+                Some(stream) => stream,
+                None => connection.insert(
+                    self.connect()
+                        .map_err(|error| SimulationError::EventPublishFailed(error.to_string()))?,
+                ),
+            };
+
+            match stream.write_all(&message) {
+                Ok(()) => return Ok(()),
+                Err(error) => {
+                    *connection = None;
+
+                    if attempt == 1 {
+                        return Err(SimulationError::EventPublishFailed(error.to_string()));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Sink publishing the events to Kafka.
+/// Events are queued and delivered in the background, pending events are flushed when the sink is dropped.
+#[cfg(feature = "kafka")]
+pub struct KafkaSink {
+    /// Producer delivering the events.
+    producer: BaseProducer,
+}
+
+#[cfg(feature = "kafka")]
+impl KafkaSink {
+    /// Create a new Kafka sink.
+    ///
+    /// # Arguments
+    ///
+    /// * `brokers` - Comma-separated list of the bootstrap brokers, e.g. `localhost:9092`.
+    ///
+    /// # Returns
+    ///
+    /// New Kafka sink, or an error if the producer cannot be created.
+    pub fn new(brokers: &str) -> Result<Self, SimulationError> {
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .create()
+            .map_err(|error| SimulationError::EventPublishFailed(error.to_string()))?;
+
+        Ok(KafkaSink { producer })
+    }
+}
+
+#[cfg(feature = "kafka")]
+impl Debug for KafkaSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KafkaSink").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "kafka")]
+impl EventSink for KafkaSink {
+    fn publish(&self, topic: &str, key: &str, payload: &[u8]) -> Result<(), SimulationError> {
+        self.producer
+            .send(BaseRecord::to(topic).key(key).payload(payload))
+            .map_err(|(error, _)| SimulationError::EventPublishFailed(error.to_string()))?;
+
+        // Serve the delivery callbacks of the events sent so far
+        self.producer.poll(Duration::ZERO);
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "kafka")]
+impl Drop for KafkaSink {
+    /// Deliver the pending events.
+    fn drop(&mut self) {
+        let _ = self.producer.flush(KAFKA_FLUSH_TIMEOUT);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::Read,
+        net::TcpListener,
+        sync::{Arc, Mutex},
+        thread,
+    };
+
+    use super::*;
+    use crate::{EthereumFee, SimulationTransactionFee};
+
+    #[derive(Debug, Default)]
+    struct RecordingSink {
+        events: Mutex<Vec<(String, String, SimulationEvent)>>,
+    }
+
+    impl EventSink for Arc<RecordingSink> {
+        fn publish(&self, topic: &str, key: &str, payload: &[u8]) -> Result<(), SimulationError> {
+            self.events.lock().unwrap().push((
+                topic.to_string(),
+                key.to_string(),
+                serde_json::from_slice(payload).unwrap(),
+            ));
+            Ok(())
+        }
+    }
+
+    fn setup() -> Simulation {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(10)
+            .duration(2)
+            .build()
+            .unwrap();
+
+        Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_event_publisher() {
+        let sink = Arc::new(RecordingSink::default());
+        let mut simulation = setup();
+        simulation.add_observer(EventPublisher::new(Arc::clone(&sink), "simulations"));
+
+        simulation.run().unwrap();
+
+        let events = sink.events.lock().unwrap();
+        assert_eq!(events.len(), 4);
+        assert!(events.iter().all(|(topic, key, _)| {
+            topic == "simulations" && key == &simulation.id.to_string()
+        }));
+        assert_eq!(
+            events[0].2,
+            SimulationEvent::Started {
+                simulation_id: simulation.id,
+                name: "Test Simulation".to_string(),
+            }
+        );
+        assert!(matches!(events[1].2, SimulationEvent::Interval { .. }));
+        assert!(matches!(
+            &events[3].2,
+            SimulationEvent::Completed { simulation_id, report }
+                if *simulation_id == simulation.id && report.trades == simulation.report.trades
+        ));
+    }
+
+    #[test]
+    fn test_event_publisher_on_failure() {
+        let sink = Arc::new(RecordingSink::default());
+        let mut simulation = setup();
+        simulation.options.transaction_fee =
+            Some(SimulationTransactionFee::Ethereum(EthereumFee {
+                gas_used: 21_000,
+                gas_price: None,
+                ether_price: None,
+            }));
+        simulation.add_observer(EventPublisher::new(Arc::clone(&sink), "simulations"));
+
+        assert!(simulation.run().is_err());
+
+        let events = sink.events.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            events[1].2,
+            SimulationEvent::Failed {
+                simulation_id: simulation.id,
+                error: SimulationError::UnresolvedTransactionFee.to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_nats_sink() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            stream.write_all(b"INFO {}\r\n").unwrap();
+
+            let mut received = String::new();
+            stream.read_to_string(&mut received).unwrap();
+            received
+        });
+
+        let sink = NatsSink::new(address);
+        sink.publish("simulations", "key", b"{}").unwrap();
+        sink.publish("simulations", "key", b"[]").unwrap();
+        drop(sink);
+
+        let received = server.join().unwrap();
+        assert!(received.starts_with("CONNECT {"));
+        assert!(received.ends_with("PUB simulations 2\r\n{}\r\nPUB simulations 2\r\n[]\r\n"));
+    }
+
+    #[test]
+    fn test_nats_sink_unreachable() {
+        let sink = NatsSink::new("127.0.0.1:1");
+
+        assert!(matches!(
+            sink.publish("simulations", "key", b"{}"),
+            Err(SimulationError::EventPublishFailed(_))
+        ));
+    }
+
+    #[cfg(feature = "kafka")]
+    #[test]
+    fn test_kafka_sink() {
+        let sink = KafkaSink::new("127.0.0.1:9092").unwrap();
+
+        assert_eq!(format!("{:?}", sink), "KafkaSink { .. }");
+    }
+}
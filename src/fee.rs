@@ -0,0 +1,714 @@
+//! # Fee module
+//!
+//! This module contains the network fees paid on each transaction, such as the gas fee of a blockchain.
+//!
+//! Network fees may depend on live data, e.g. the current gas price.
+//! Such fees are resolved, synchronously or asynchronously, before the simulation is run.
+//!
+//! A fee can also follow a recent window of gas prices, e.g. fetched with `eth_feeHistory`:
+//! each trade then pays a gas price sampled from the window, so the fee varies from trade to trade.
+//!
+//! The fee can also respond to the congestion of the simulated network: the busier the previous interval,
+//! the higher the fee of the next one, which prices out marginal trades and dampens the activity in turn.
+
+use std::future::Future;
+
+use rust_decimal::{prelude::*, Decimal};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{stats::percentile, FiatAmount, GweiAmount, Simulation, SimulationError};
+
+/// Network fee paid on each transaction.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum SimulationTransactionFee {
+    /// Fixed fee per transaction, in fiat.
+    Fixed(#[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))] Decimal),
+
+    /// Gas fee of an Ethereum transaction.
+    Ethereum(EthereumFee),
+
+    /// Gas fee of an Ethereum transaction, sampled from a window of recent gas prices.
+    EthereumHistory(GasPriceHistory),
+}
+
+/// Gas fee of an Ethereum transaction.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct EthereumFee {
+    /// Gas used by a transaction.
+    pub gas_used: u64,
+
+    /// Gas price, in gwei.
+    /// If not set, the gas price must be resolved before the simulation is run.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, with = "rust_decimal::serde::float_option")
+    )]
+    pub gas_price: Option<Decimal>,
+
+    /// Price of ether, in fiat.
+    /// If not set, the price must be resolved before the simulation is run.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, with = "rust_decimal::serde::float_option")
+    )]
+    pub ether_price: Option<Decimal>,
+}
+
+/// Window of recent gas prices of Ethereum, sampled for the gas fee of each trade.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct GasPriceHistory {
+    /// Gas used by a transaction.
+    pub gas_used: u64,
+
+    /// Recent gas prices, in gwei.
+    /// If empty, the current gas price is used when the fee is resolved.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub gas_prices: Vec<Decimal>,
+
+    /// Price of ether, in fiat.
+    /// If not set, the price must be resolved before the simulation is run.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, with = "rust_decimal::serde::float_option")
+    )]
+    pub ether_price: Option<Decimal>,
+}
+
+/// Live prices of a blockchain network.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct NetworkPrices {
+    /// Gas price, in gwei.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub gas_price: Decimal,
+
+    /// Price of the native token of the network, in fiat.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub native_token_price: Decimal,
+}
+
+/// Congestion of the simulated network, scaling the network fee with the network activity.
+///
+/// The fee of an interval is multiplied by `(activity / target_activity) ^ elasticity`,
+/// where `activity` is the network activity of the previous interval, in transactions per hour.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct FeeCongestion {
+    /// Network activity at which the fee is unchanged, in transactions per hour.
+    pub target_activity: u64,
+
+    /// Elasticity of the fee to the network activity, e.g. 1 for a fee proportional to the activity.
+    pub elasticity: f64,
+
+    /// Lowest multiplier of the fee, for a quiet network.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default = "FeeCongestion::default_min_multiplier")
+    )]
+    pub min_multiplier: f64,
+
+    /// Highest multiplier of the fee, for a congested network.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default = "FeeCongestion::default_max_multiplier")
+    )]
+    pub max_multiplier: f64,
+}
+
+/// Source of live network prices, e.g. a node or a price API.
+pub trait NetworkPriceSource {
+    /// Fetch the current network prices.
+    ///
+    /// # Returns
+    ///
+    /// Current network prices.
+    fn prices(&self) -> Result<NetworkPrices, SimulationError>;
+}
+
+impl SimulationTransactionFee {
+    /// Check whether the fee can be calculated without live data.
+    ///
+    /// # Returns
+    ///
+    /// Whether the fee is resolved.
+    pub fn is_resolved(&self) -> bool {
+        match self {
+            SimulationTransactionFee::Fixed(_) => true,
+            SimulationTransactionFee::Ethereum(fee) => {
+                fee.gas_price.is_some() && fee.ether_price.is_some()
+            }
+            SimulationTransactionFee::EthereumHistory(history) => {
+                !history.gas_prices.is_empty() && history.ether_price.is_some()
+            }
+        }
+    }
+
+    /// Fill the missing live data of the fee with the given network prices.
+    /// Values that are already set are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `prices` - Current network prices.
+    ///
+    /// # Returns
+    ///
+    /// Resolved fee.
+    pub fn with_prices(self, prices: &NetworkPrices) -> Self {
+        match self {
+            SimulationTransactionFee::Ethereum(fee) => {
+                SimulationTransactionFee::Ethereum(EthereumFee {
+                    gas_used: fee.gas_used,
+                    gas_price: fee.gas_price.or(Some(prices.gas_price)),
+                    ether_price: fee.ether_price.or(Some(prices.native_token_price)),
+                })
+            }
+            SimulationTransactionFee::EthereumHistory(mut history) => {
+                if history.gas_prices.is_empty() {
+                    history.gas_prices.push(prices.gas_price);
+                }
+                history.ether_price = history.ether_price.or(Some(prices.native_token_price));
+
+                SimulationTransactionFee::EthereumHistory(history)
+            }
+            fee => fee,
+        }
+    }
+
+    /// Resolve the missing live data of the fee from a network price source.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Source of live network prices.
+    ///
+    /// # Returns
+    ///
+    /// Resolved fee.
+    pub fn resolve<S: NetworkPriceSource + ?Sized>(
+        self,
+        source: &S,
+    ) -> Result<Self, SimulationError> {
+        if self.is_resolved() {
+            return Ok(self);
+        }
+
+        Ok(self.with_prices(&source.prices()?))
+    }
+
+    /// Resolve the missing live data of the fee asynchronously.
+    /// The prices are only awaited if the fee is not resolved yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `prices` - Future fetching the current network prices.
+    ///
+    /// # Returns
+    ///
+    /// Resolved fee.
+    pub async fn resolve_async<F>(self, prices: F) -> Result<Self, SimulationError>
+    where
+        F: Future<Output = Result<NetworkPrices, SimulationError>>,
+    {
+        if self.is_resolved() {
+            return Ok(self);
+        }
+
+        Ok(self.with_prices(&prices.await?))
+    }
+
+    /// Calculate the fee paid on each transaction.
+    /// A fee following a window of gas prices uses the median gas price.
+    ///
+    /// # Returns
+    ///
+    /// Fee per transaction, in fiat, or an error if the fee is not resolved.
+    pub fn per_transaction(&self) -> Result<FiatAmount, SimulationError> {
+        match self {
+            SimulationTransactionFee::EthereumHistory(_) => {
+                let mut samples: Vec<_> =
+                    self.samples()?.into_iter().map(FiatAmount::value).collect();
+                samples.sort();
+
+                Ok(FiatAmount(percentile(&samples, Decimal::from(50))))
+            }
+            fee => fee.spot(),
+        }
+    }
+
+    /// Calculate the fees a transaction can pay, to sample the fee of each trade.
+    ///
+    /// # Returns
+    ///
+    /// Fee for each gas price of the window, or the single fee of a fee without a window,
+    /// or an error if the fee is not resolved.
+    pub fn samples(&self) -> Result<Vec<FiatAmount>, SimulationError> {
+        match self {
+            SimulationTransactionFee::EthereumHistory(GasPriceHistory {
+                gas_used,
+                gas_prices,
+                ether_price: Some(ether_price),
+            }) if !gas_prices.is_empty() => Ok(gas_prices
+                .iter()
+                .map(|gas_price| {
+                    GweiAmount(*gas_price).gas_cost(*gas_used, FiatAmount(*ether_price))
+                })
+                .collect()),
+            SimulationTransactionFee::EthereumHistory(_) => {
+                Err(SimulationError::UnresolvedTransactionFee)
+            }
+            fee => Ok(vec![fee.spot()?]),
+        }
+    }
+
+    /// Calculate the fee of a fee without a window of gas prices.
+    ///
+    /// # Returns
+    ///
+    /// Fee per transaction, in fiat, or an error if the fee is not resolved.
+    fn spot(&self) -> Result<FiatAmount, SimulationError> {
+        match self {
+            SimulationTransactionFee::Fixed(fee) => Ok(FiatAmount(*fee)),
+            SimulationTransactionFee::Ethereum(EthereumFee {
+                gas_used,
+                gas_price: Some(gas_price),
+                ether_price: Some(ether_price),
+            }) => Ok(GweiAmount(*gas_price).gas_cost(*gas_used, FiatAmount(*ether_price))),
+            SimulationTransactionFee::Ethereum(_)
+            | SimulationTransactionFee::EthereumHistory(_) => {
+                Err(SimulationError::UnresolvedTransactionFee)
+            }
+        }
+    }
+}
+
+impl Simulation {
+    /// Resolve the transaction fee of the simulation from a network price source.
+    /// Does nothing if the simulation has no transaction fee, or the fee is already resolved.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Source of live network prices.
+    pub fn resolve_transaction_fee<S: NetworkPriceSource + ?Sized>(
+        &mut self,
+        source: &S,
+    ) -> Result<(), SimulationError> {
+        if let Some(fee) = self.options.transaction_fee.take() {
+            self.options.transaction_fee = Some(fee.resolve(source)?);
+        }
+
+        Ok(())
+    }
+
+    /// Resolve the transaction fee of the simulation asynchronously.
+    /// Does nothing if the simulation has no transaction fee, or the fee is already resolved.
+    ///
+    /// # Arguments
+    ///
+    /// * `prices` - Future fetching the current network prices.
+    pub async fn resolve_transaction_fee_async<F>(
+        &mut self,
+        prices: F,
+    ) -> Result<(), SimulationError>
+    where
+        F: Future<Output = Result<NetworkPrices, SimulationError>>,
+    {
+        if let Some(fee) = self.options.transaction_fee.take() {
+            self.options.transaction_fee = Some(fee.resolve_async(prices).await?);
+        }
+
+        Ok(())
+    }
+}
+
+impl FeeCongestion {
+    /// Create a new fee congestion, with a multiplier between 0.1 and 10.
+    ///
+    /// # Arguments
+    ///
+    /// * `target_activity` - Network activity at which the fee is unchanged, in transactions per hour.
+    /// * `elasticity` - Elasticity of the fee to the network activity.
+    ///
+    /// # Returns
+    ///
+    /// New fee congestion.
+    pub fn new(target_activity: u64, elasticity: f64) -> Self {
+        FeeCongestion {
+            target_activity,
+            elasticity,
+            min_multiplier: FeeCongestion::default_min_multiplier(),
+            max_multiplier: FeeCongestion::default_max_multiplier(),
+        }
+    }
+
+    /// Validate the fee congestion.
+    ///
+    /// # Returns
+    ///
+    /// Error if the target activity is zero, the elasticity is negative or the bounds of the multiplier are invalid.
+    pub fn validate(&self) -> Result<(), SimulationError> {
+        let invalid = |reason: &str| {
+            Err(SimulationError::InvalidData(format!(
+                "fee congestion: {reason}"
+            )))
+        };
+
+        if self.target_activity == 0 {
+            return invalid("target activity must be positive");
+        }
+        if !self.elasticity.is_finite() || self.elasticity < 0.0 {
+            return invalid("elasticity must be non-negative");
+        }
+        if self.min_multiplier <= 0.0
+            || self.min_multiplier > self.max_multiplier
+            || !self.max_multiplier.is_finite()
+        {
+            return invalid("multiplier bounds must be positive and ordered");
+        }
+
+        Ok(())
+    }
+
+    /// Get the multiplier of the network fee for a network activity.
+    ///
+    /// # Arguments
+    ///
+    /// * `activity` - Network activity of the previous interval, in transactions per hour.
+    ///
+    /// # Returns
+    ///
+    /// Multiplier of the network fee, within the bounds.
+    pub fn multiplier(&self, activity: u64) -> Decimal {
+        let ratio = activity as f64 / self.target_activity.max(1) as f64;
+        let multiplier = ratio
+            .powf(self.elasticity)
+            .clamp(self.min_multiplier, self.max_multiplier);
+
+        Decimal::from_f64(multiplier).unwrap_or(Decimal::ONE)
+    }
+
+    /// Default lowest multiplier of the fee.
+    fn default_min_multiplier() -> f64 {
+        0.1
+    }
+
+    /// Default highest multiplier of the fee.
+    fn default_max_multiplier() -> f64 {
+        10.0
+    }
+}
+
+#[cfg(feature = "network")]
+impl GasPriceHistory {
+    /// Fetch the gas prices of recent blocks from an Ethereum JSON-RPC node, with `eth_feeHistory`.
+    /// The gas price of a block is its base fee and its median priority fee.
+    ///
+    /// # Arguments
+    ///
+    /// * `rpc_url` - URL of the JSON-RPC node.
+    /// * `gas_used` - Gas used by a transaction.
+    /// * `blocks` - Number of recent blocks, at most 1024.
+    ///
+    /// # Returns
+    ///
+    /// Window of gas prices, without the price of ether, or an error if the request fails or the response is invalid.
+    pub fn fetch(rpc_url: &str, gas_used: u64, blocks: u64) -> Result<Self, SimulationError> {
+        let request = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"eth_feeHistory","params":["{blocks:#x}","latest",[50]]}}"#
+        );
+        let body = ureq::post(rpc_url)
+            .set("Content-Type", "application/json")
+            .send_string(&request)
+            .and_then(|response| Ok(response.into_string()?))
+            .map_err(|err| SimulationError::FetchFailed(format!("eth_feeHistory: {err}")))?;
+
+        Ok(GasPriceHistory {
+            gas_used,
+            gas_prices: parse_fee_history(&body)?,
+            ether_price: None,
+        })
+    }
+}
+
+/// Parse the response of `eth_feeHistory` into the gas price of each block.
+///
+/// # Arguments
+///
+/// * `json` - JSON-RPC response, with the base fees and the median priority fees in hexadecimal wei.
+///
+/// # Returns
+///
+/// Gas price of each block, in gwei, or an error if the response is an error or is invalid.
+#[cfg(feature = "network")]
+pub fn parse_fee_history(json: &str) -> Result<Vec<Decimal>, SimulationError> {
+    let invalid =
+        |reason: &str| SimulationError::InvalidImport(format!("eth_feeHistory: {reason}"));
+    let value: serde_json::Value =
+        serde_json::from_str(json).map_err(|err| invalid(&err.to_string()))?;
+    if let Some(error) = value
+        .pointer("/error/message")
+        .and_then(|message| message.as_str())
+    {
+        return Err(invalid(error));
+    }
+
+    let wei = |value: Option<&serde_json::Value>| {
+        value
+            .and_then(|value| value.as_str())
+            .and_then(|value| u128::from_str_radix(value.trim_start_matches("0x"), 16).ok())
+            .and_then(|wei| Decimal::try_from_i128_with_scale(wei as i128, 9).ok())
+            .map(|gwei| gwei.normalize())
+            .ok_or_else(|| invalid("expected hexadecimal fees"))
+    };
+    let base_fees = value
+        .pointer("/result/baseFeePerGas")
+        .and_then(|fees| fees.as_array())
+        .ok_or_else(|| invalid("missing base fees"))?;
+    let rewards = value
+        .pointer("/result/reward")
+        .and_then(|rewards| rewards.as_array())
+        .ok_or_else(|| invalid("missing priority fees"))?;
+
+    // The base fees include the next block, which has no priority fee yet
+    rewards
+        .iter()
+        .zip(base_fees)
+        .map(|(reward, base_fee)| Ok(wei(Some(base_fee))? + wei(reward.get(0))?))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ValuationModel;
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct StaticSource;
+
+    impl NetworkPriceSource for StaticSource {
+        fn prices(&self) -> Result<NetworkPrices, SimulationError> {
+            Ok(NetworkPrices {
+                gas_price: Decimal::new(20, 0),
+                native_token_price: Decimal::new(2_000, 0),
+            })
+        }
+    }
+
+    fn ethereum_fee() -> SimulationTransactionFee {
+        SimulationTransactionFee::Ethereum(EthereumFee {
+            gas_used: 21_000,
+            gas_price: None,
+            ether_price: None,
+        })
+    }
+
+    #[test]
+    fn test_per_transaction() {
+        assert_eq!(
+            SimulationTransactionFee::Fixed(Decimal::ONE).per_transaction(),
+            Ok(FiatAmount(Decimal::ONE))
+        );
+        assert_eq!(
+            ethereum_fee().per_transaction(),
+            Err(SimulationError::UnresolvedTransactionFee)
+        );
+    }
+
+    #[test]
+    fn test_resolve() {
+        let fee = ethereum_fee().resolve(&StaticSource).unwrap();
+
+        assert!(fee.is_resolved());
+        // 21,000 gas * 20 gwei = 0.00042 ETH, at 2,000 per ETH
+        assert_eq!(fee.per_transaction(), Ok(FiatAmount(Decimal::new(84, 2))));
+    }
+
+    #[test]
+    fn test_resolve_keeps_set_values() {
+        let fee = SimulationTransactionFee::Ethereum(EthereumFee {
+            gas_used: 21_000,
+            gas_price: Some(Decimal::new(10, 0)),
+            ether_price: None,
+        })
+        .resolve(&StaticSource)
+        .unwrap();
+
+        assert_eq!(fee.per_transaction(), Ok(FiatAmount(Decimal::new(42, 2))));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_async() {
+        let fee = ethereum_fee()
+            .resolve_async(async { StaticSource.prices() })
+            .await
+            .unwrap();
+
+        assert_eq!(fee.per_transaction(), Ok(FiatAmount(Decimal::new(84, 2))));
+    }
+
+    #[test]
+    fn test_gas_price_history() {
+        let history = SimulationTransactionFee::EthereumHistory(GasPriceHistory {
+            gas_used: 21_000,
+            gas_prices: vec![
+                Decimal::new(40, 0),
+                Decimal::new(10, 0),
+                Decimal::new(20, 0),
+            ],
+            ether_price: None,
+        });
+        assert_eq!(
+            history.samples(),
+            Err(SimulationError::UnresolvedTransactionFee)
+        );
+
+        let fee = history.resolve(&StaticSource).unwrap();
+        assert_eq!(fee.samples().unwrap().len(), 3);
+        // The median gas price is 20 gwei
+        assert_eq!(fee.per_transaction(), Ok(FiatAmount(Decimal::new(84, 2))));
+
+        // An empty window takes the current gas price
+        let fee = SimulationTransactionFee::EthereumHistory(GasPriceHistory {
+            gas_used: 21_000,
+            gas_prices: vec![],
+            ether_price: Some(Decimal::new(2_000, 0)),
+        })
+        .resolve(&StaticSource)
+        .unwrap();
+        assert_eq!(fee.samples(), Ok(vec![FiatAmount(Decimal::new(84, 2))]));
+    }
+
+    #[test]
+    fn test_run_with_gas_price_history() {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .valuation_model(ValuationModel::RandomWalk)
+            .total_users(50)
+            .duration(3)
+            .seed(3)
+            .transaction_fee(SimulationTransactionFee::EthereumHistory(GasPriceHistory {
+                gas_used: 21_000,
+                gas_prices: vec![Decimal::ONE, Decimal::new(100_000, 0)],
+                ether_price: Some(Decimal::new(2_000, 0)),
+            }))
+            .build()
+            .unwrap();
+        let mut simulation = Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap();
+
+        simulation.run().unwrap();
+
+        // Trades sampling the spike of the gas price are not worth it for small holders
+        assert!(simulation.report.priced_out_trades > 0);
+        assert!(simulation.report.successful_trades > 0);
+        assert!(simulation.report.failed_trades >= simulation.report.priced_out_trades);
+    }
+
+    #[test]
+    fn test_fee_congestion() {
+        let congestion = FeeCongestion::new(10, 2.0);
+
+        assert_eq!(congestion.validate(), Ok(()));
+        assert_eq!(congestion.multiplier(10), Decimal::ONE);
+        assert_eq!(congestion.multiplier(20), Decimal::new(4, 0));
+        assert_eq!(congestion.multiplier(1_000), Decimal::new(10, 0));
+        assert_eq!(congestion.multiplier(0), Decimal::new(1, 1));
+
+        for invalid in [
+            FeeCongestion::new(0, 1.0),
+            FeeCongestion::new(10, -1.0),
+            FeeCongestion {
+                min_multiplier: 2.0,
+                max_multiplier: 1.0,
+                ..FeeCongestion::new(10, 1.0)
+            },
+        ] {
+            assert!(matches!(
+                invalid.validate(),
+                Err(SimulationError::InvalidData(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_run_with_fee_congestion() {
+        let run = |congestion: Option<FeeCongestion>| {
+            let token = Simulation::token_builder()
+                .name("Test Token".to_string())
+                .build()
+                .unwrap();
+            let mut options = Simulation::options_builder()
+                .valuation_model(ValuationModel::RandomWalk)
+                .total_users(50)
+                .duration(5)
+                .seed(3)
+                .transaction_fee(SimulationTransactionFee::Fixed(Decimal::new(1, 1)))
+                .build()
+                .unwrap();
+            options.fee_congestion = congestion;
+            let mut simulation = Simulation::builder()
+                .name("Test Simulation".to_string())
+                .token(token)
+                .options(options)
+                .build()
+                .unwrap();
+
+            simulation.run().unwrap();
+            simulation
+        };
+
+        let constant = run(None);
+        let congestion = FeeCongestion {
+            max_multiplier: 1_000.0,
+            ..FeeCongestion::new(1, 2.0)
+        };
+        let congested = run(Some(congestion.clone()));
+
+        assert!(constant
+            .interval_reports
+            .iter()
+            .all(|report| report.network_fee_multiplier.is_none()));
+
+        // The first interval has no previous activity, the next ones pay for the congestion
+        let reports = &congested.interval_reports;
+        assert_eq!(reports[0].network_fee_multiplier, Some(Decimal::ONE));
+        for pair in reports.windows(2) {
+            assert_eq!(
+                pair[1].network_fee_multiplier,
+                Some(congestion.multiplier(pair[0].network_activity))
+            );
+        }
+        assert!(congested.report.priced_out_trades > constant.report.priced_out_trades);
+    }
+
+    #[cfg(feature = "network")]
+    #[test]
+    fn test_parse_fee_history() {
+        let json = r#"{"jsonrpc": "2.0", "id": 1, "result": {
+            "oldestBlock": "0x10",
+            "baseFeePerGas": ["0x3b9aca00", "0x77359400", "0x77359400"],
+            "gasUsedRatio": [0.5, 0.6],
+            "reward": [["0x3b9aca00"], ["0x0"]]
+        }}"#;
+
+        assert_eq!(
+            parse_fee_history(json).unwrap(),
+            vec![Decimal::new(2, 0), Decimal::new(2, 0)]
+        );
+        assert!(matches!(
+            parse_fee_history(r#"{"error": {"code": -32601, "message": "method not found"}}"#),
+            Err(SimulationError::InvalidImport(_))
+        ));
+    }
+}
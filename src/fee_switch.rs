@@ -0,0 +1,285 @@
+//! # Fee switch module
+//!
+//! This module contains the fee switch, which redirects a share of the fees from the treasury to the token holders once activated.
+//!
+//! Before the activation, the treasury keeps all the fees. From the activation interval on,
+//! the holders receive their share of the fees, split by their balances.
+//! The timing analysis reruns a completed simulation with the switch activated at different intervals,
+//! and compares the price, the retention and the fees of the treasury under each timing with a rerun that never activates it.
+
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    EnsembleMetric, FiatAmount, Simulation, SimulationComparison, SimulationError,
+    SimulationStatus, TokenAmount, UserStore,
+};
+
+/// Protocol fee switch.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct FeeSwitch {
+    /// Index of the interval the switch is activated in.
+    pub activation_interval: u64,
+
+    /// Share of the fees redirected to the holders once activated, in percentage.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub holder_share: Decimal,
+}
+
+/// Outcome of a fee switch activation timing.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct FeeSwitchTiming {
+    /// Index of the interval the switch is activated in.
+    pub activation_interval: u64,
+
+    /// Final price of the token.
+    pub token_price: FiatAmount,
+
+    /// Retention of the users.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub user_retention: Decimal,
+
+    /// Fees kept by the treasury, in tokens.
+    pub treasury_fees: TokenAmount,
+
+    /// Fees redirected to the holders, in tokens.
+    pub holder_fees: TokenAmount,
+
+    /// Comparison of the rerun without the switch, as the baseline, with the rerun activating it, as the variant.
+    pub comparison: SimulationComparison,
+}
+
+impl FeeSwitch {
+    /// Create a new fee switch.
+    ///
+    /// # Arguments
+    ///
+    /// * `activation_interval` - Index of the interval the switch is activated in.
+    /// * `holder_share` - Share of the fees redirected to the holders, in percentage.
+    ///
+    /// # Returns
+    ///
+    /// New fee switch.
+    pub fn new(activation_interval: u64, holder_share: Decimal) -> Self {
+        FeeSwitch {
+            activation_interval,
+            holder_share,
+        }
+    }
+
+    /// Validate the fee switch.
+    ///
+    /// # Returns
+    ///
+    /// Result of the validation, or an error if the share of the holders is out of range.
+    pub fn validate(&self) -> Result<(), SimulationError> {
+        if !(Decimal::ZERO..=Decimal::ONE_HUNDRED).contains(&self.holder_share) {
+            return Err(SimulationError::InvalidData(
+                "fee switch: holder share must be between 0 and 100".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Redirect the share of the fees of an interval to the holders, once the switch is activated.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the interval.
+    /// * `fees` - Fees paid in the interval.
+    /// * `users` - Users of the simulation.
+    /// * `precision` - Decimal precision of the balances.
+    ///
+    /// # Returns
+    ///
+    /// Fees paid to the holders.
+    pub(crate) fn distribute(
+        &self,
+        index: u64,
+        fees: Decimal,
+        users: &mut UserStore,
+        precision: u32,
+    ) -> Decimal {
+        if index < self.activation_interval {
+            return Decimal::default();
+        }
+
+        let share = (fees * self.holder_share / Decimal::ONE_HUNDRED).round_dp(precision);
+        let held: Decimal = users.balances.iter().sum();
+        let Some(rate) = share.checked_div(held) else {
+            return Decimal::default();
+        };
+
+        let mut paid = Decimal::default();
+        for balance in users.balances.iter_mut() {
+            let amount = (*balance * rate).round_dp(precision);
+            *balance += amount;
+            paid += amount;
+        }
+
+        paid
+    }
+}
+
+impl Simulation {
+    /// Rerun the completed simulation with a fee switch activated at each of the intervals,
+    /// and compare each rerun with a rerun that never activates it.
+    /// Observers and metric plugins are not attached to the reruns.
+    ///
+    /// # Arguments
+    ///
+    /// * `holder_share` - Share of the fees redirected to the holders, in percentage.
+    /// * `activations` - Activation intervals to evaluate.
+    /// * `metrics` - Metrics to compare.
+    ///
+    /// # Returns
+    ///
+    /// Outcome of each activation timing, in the order of the activations, or an error if the simulation is not completed,
+    /// has no seed, or a rerun fails.
+    pub fn fee_switch_timings(
+        &self,
+        holder_share: Decimal,
+        activations: &[u64],
+        metrics: &[EnsembleMetric],
+    ) -> Result<Vec<FeeSwitchTiming>, SimulationError> {
+        if self.options.seed.is_none() {
+            return Err(SimulationError::NotReproducible(
+                "the simulation has no seed".to_string(),
+            ));
+        }
+        let (SimulationStatus::Completed, Some(token)) = (&self.status, &self.initial_token) else {
+            return Err(SimulationError::NotReproducible(
+                "the simulation is not completed".to_string(),
+            ));
+        };
+
+        let rerun = |fee_switch: Option<FeeSwitch>| {
+            let mut options = self.options.clone();
+            options.fee_switch = fee_switch;
+
+            let mut simulation = Simulation::builder()
+                .name(self.name.clone())
+                .token(token.clone())
+                .options(options)
+                .build()?;
+            simulation.run()?;
+
+            Ok::<Simulation, SimulationError>(simulation)
+        };
+
+        let baseline = rerun(None)?;
+        activations
+            .iter()
+            .map(|activation| {
+                let variant = rerun(Some(FeeSwitch::new(*activation, holder_share)))?;
+
+                Ok(FeeSwitchTiming {
+                    activation_interval: *activation,
+                    token_price: variant.report.token_price,
+                    user_retention: variant.report.user_retention,
+                    treasury_fees: variant.report.treasury_fees,
+                    holder_fees: variant.report.holder_fees,
+                    comparison: baseline.compare(&variant, metrics),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::User;
+
+    use super::*;
+
+    #[test]
+    fn test_distribute() {
+        let mut users = UserStore::from(vec![User::new(Uuid::new_v4(), Decimal::new(30, 0)); 2]);
+        users.balances[1] = Decimal::new(10, 0);
+        let switch = FeeSwitch::new(2, Decimal::new(50, 0));
+
+        assert_eq!(
+            switch.distribute(1, Decimal::new(8, 0), &mut users, 4),
+            Decimal::ZERO
+        );
+        assert_eq!(
+            switch.distribute(2, Decimal::new(8, 0), &mut users, 4),
+            Decimal::new(4, 0)
+        );
+        assert_eq!(users.balances[0], Decimal::new(33, 0));
+        assert_eq!(users.balances[1], Decimal::new(11, 0));
+    }
+
+    #[test]
+    fn test_fee_switch_timings() {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(20)
+            .duration(6)
+            .seed(4)
+            .transaction_fee_percentage(1.0)
+            .build()
+            .unwrap();
+        let mut simulation = Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap();
+        simulation.run().unwrap();
+
+        let timings = simulation
+            .fee_switch_timings(
+                Decimal::new(50, 0),
+                &[0, 3, 6],
+                &[EnsembleMetric::TokenPrice, EnsembleMetric::UserRetention],
+            )
+            .unwrap();
+
+        assert_eq!(timings.len(), 3);
+        assert_eq!(timings[1].activation_interval, 3);
+        assert_eq!(timings[1].comparison.deltas.len(), 2);
+        // An earlier activation redirects more fees to the holders, a switch after the run none
+        assert!(timings[0].holder_fees > timings[1].holder_fees);
+        assert!(timings[1].holder_fees > TokenAmount::default());
+        assert_eq!(timings[2].holder_fees, TokenAmount::default());
+        assert!(timings[2]
+            .comparison
+            .deltas
+            .iter()
+            .all(|delta| delta.delta == Decimal::ZERO));
+        assert_eq!(timings[2].treasury_fees, simulation.report.treasury_fees);
+    }
+
+    #[test]
+    fn test_fee_switch_timings_without_seed() {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(5)
+            .build()
+            .unwrap();
+        let simulation = Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap();
+
+        assert!(matches!(
+            simulation.fee_switch_timings(Decimal::new(50, 0), &[1], &[]),
+            Err(SimulationError::NotReproducible(_))
+        ));
+    }
+}
@@ -0,0 +1,79 @@
+//! # Float option module
+//!
+//! This module contains the serialization of optional decimals as floats, for the optional values of the reports.
+//!
+//! It writes the same numbers as `rust_decimal::serde::float_option`, but also reads a missing value
+//! that serde buffers as a unit, such as in the internally tagged simulation events.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serializer};
+
+/// Decimal read from a float.
+#[derive(Deserialize)]
+struct Float(#[serde(with = "rust_decimal::serde::float")] Decimal);
+
+/// Serialize an optional decimal as a float.
+///
+/// # Arguments
+///
+/// * `value` - Optional decimal.
+/// * `serializer` - Serializer.
+///
+/// # Returns
+///
+/// Result of the serialization.
+pub fn serialize<S>(value: &Option<Decimal>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    rust_decimal::serde::float_option::serialize(value, serializer)
+}
+
+/// Deserialize an optional decimal from a float.
+///
+/// # Arguments
+///
+/// * `deserializer` - Deserializer.
+///
+/// # Returns
+///
+/// Optional decimal, `None` for a missing value.
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Decimal>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<Float>::deserialize(deserializer)?.map(|Float(value)| value))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Serialize;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    #[serde(tag = "type")]
+    enum Tagged {
+        Value {
+            #[serde(default, with = "crate::float_option")]
+            value: Option<Decimal>,
+        },
+    }
+
+    #[test]
+    fn test_float_option() {
+        for value in [Some(Decimal::new(125, 2)), None] {
+            let tagged = Tagged::Value { value };
+            let json = serde_json::to_string(&tagged).unwrap();
+
+            assert_eq!(serde_json::from_str::<Tagged>(&json).unwrap(), tagged);
+        }
+        assert_eq!(
+            serde_json::to_string(&Tagged::Value {
+                value: Some(Decimal::new(125, 2))
+            })
+            .unwrap(),
+            r#"{"type":"Value","value":1.25}"#
+        );
+    }
+}
@@ -0,0 +1,337 @@
+//! # Format module
+//!
+//! This module contains the number formatting used to render reports for people, e.g. as text or Markdown.
+//!
+//! Numbers can be grouped with the thousands separator of a locale, limited to a number of decimal places
+//! or significant digits, and shortened with compact notation, e.g. 1.2M for 1,234,567.
+//! Formatting is only for display; the reports keep their exact values.
+
+use rust_decimal::{Decimal, RoundingStrategy};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Suffixes of the compact notation, from the largest.
+const COMPACT_SUFFIXES: [(u32, &str); 4] = [(12, "T"), (9, "B"), (6, "M"), (3, "K")];
+
+/// Locale of the number separators.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Locale {
+    /// English, e.g. 1,234.5.
+    #[default]
+    En,
+
+    /// German, e.g. 1.234,5.
+    De,
+
+    /// French, e.g. 1 234,5 with a narrow no-break space.
+    Fr,
+
+    /// Swiss, e.g. 1'234.5.
+    Ch,
+}
+
+impl Locale {
+    /// Get the separators of the locale.
+    ///
+    /// # Returns
+    ///
+    /// Thousands separator and decimal separator.
+    fn separators(&self) -> (char, char) {
+        match self {
+            Locale::En => (',', '.'),
+            Locale::De => ('.', ','),
+            Locale::Fr => ('\u{202f}', ','),
+            Locale::Ch => ('\'', '.'),
+        }
+    }
+}
+
+/// Formatting of the numbers of a rendered report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct NumberFormat {
+    /// Locale of the separators.
+    /// Default is English.
+    pub locale: Locale,
+
+    /// Whether to group the thousands with the separator of the locale.
+    /// Default is true.
+    pub group_thousands: bool,
+
+    /// Number of significant digits to keep, if set.
+    /// Takes precedence over `decimals`.
+    pub significant_digits: Option<u32>,
+
+    /// Maximum number of decimal places, if set.
+    /// If neither this nor `significant_digits` is set, every digit is kept.
+    pub decimals: Option<u32>,
+
+    /// Whether to shorten large numbers with a suffix, e.g. 1.2M.
+    /// Default is false.
+    pub compact: bool,
+}
+
+impl Default for NumberFormat {
+    /// Create a number format that groups the thousands and keeps every digit.
+    ///
+    /// # Returns
+    ///
+    /// Default number format.
+    fn default() -> Self {
+        NumberFormat {
+            locale: Locale::default(),
+            group_thousands: true,
+            significant_digits: None,
+            decimals: None,
+            compact: false,
+        }
+    }
+}
+
+impl NumberFormat {
+    /// Create a number format for a locale.
+    ///
+    /// # Arguments
+    ///
+    /// * `locale` - Locale of the separators.
+    ///
+    /// # Returns
+    ///
+    /// Number format that groups the thousands and keeps every digit.
+    pub fn new(locale: Locale) -> Self {
+        NumberFormat {
+            locale,
+            ..Default::default()
+        }
+    }
+
+    /// Create a number format for non-technical readers: compact, with 3 significant digits.
+    ///
+    /// # Arguments
+    ///
+    /// * `locale` - Locale of the separators.
+    ///
+    /// # Returns
+    ///
+    /// Readable number format.
+    pub fn readable(locale: Locale) -> Self {
+        NumberFormat::new(locale)
+            .compact(true)
+            .significant_digits(3)
+    }
+
+    /// Set whether to shorten large numbers with a suffix.
+    ///
+    /// # Arguments
+    ///
+    /// * `compact` - Whether to use compact notation.
+    ///
+    /// # Returns
+    ///
+    /// The number format.
+    pub fn compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
+
+    /// Set the number of significant digits to keep.
+    ///
+    /// # Arguments
+    ///
+    /// * `significant_digits` - Number of significant digits, at least 1.
+    ///
+    /// # Returns
+    ///
+    /// The number format.
+    pub fn significant_digits(mut self, significant_digits: u32) -> Self {
+        self.significant_digits = Some(significant_digits.max(1));
+        self
+    }
+
+    /// Set the maximum number of decimal places.
+    ///
+    /// # Arguments
+    ///
+    /// * `decimals` - Maximum number of decimal places.
+    ///
+    /// # Returns
+    ///
+    /// The number format.
+    pub fn decimals(mut self, decimals: u32) -> Self {
+        self.decimals = Some(decimals);
+        self
+    }
+
+    /// Set whether to group the thousands.
+    ///
+    /// # Arguments
+    ///
+    /// * `group_thousands` - Whether to group the thousands.
+    ///
+    /// # Returns
+    ///
+    /// The number format.
+    pub fn group_thousands(mut self, group_thousands: bool) -> Self {
+        self.group_thousands = group_thousands;
+        self
+    }
+
+    /// Format a number.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Number to format.
+    ///
+    /// # Returns
+    ///
+    /// Formatted number, without trailing zeros.
+    pub fn format(&self, value: Decimal) -> String {
+        let rounded = self.round(value);
+        let (value, suffix) = match self.compact {
+            // Rounding first lets a value reach the next suffix, e.g. 999,999 to 1M instead of 1000K
+            true => COMPACT_SUFFIXES
+                .iter()
+                .map(|(exponent, suffix)| (rounded / Decimal::from(10i64.pow(*exponent)), *suffix))
+                .find(|(scaled, _)| scaled.abs() >= Decimal::ONE)
+                .map_or((rounded, ""), |(scaled, suffix)| {
+                    (self.round(scaled), suffix)
+                }),
+            false => (rounded, ""),
+        };
+
+        let (thousands_separator, decimal_separator) = self.locale.separators();
+        let digits = value.abs().normalize().to_string();
+        let (whole, fraction) = digits.split_once('.').unwrap_or((&digits, ""));
+
+        let mut formatted = String::with_capacity(digits.len() + digits.len() / 3 + 2);
+        if value.is_sign_negative() && !value.is_zero() {
+            formatted.push('-');
+        }
+        for (index, digit) in whole.chars().enumerate() {
+            if self.group_thousands && index > 0 && (whole.len() - index) % 3 == 0 {
+                formatted.push(thousands_separator);
+            }
+            formatted.push(digit);
+        }
+        if !fraction.is_empty() {
+            formatted.push(decimal_separator);
+            formatted.push_str(fraction);
+        }
+        formatted.push_str(suffix);
+
+        formatted
+    }
+
+    /// Format a percentage, with a percent sign.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Percentage to format, e.g. 12.5 for 12.5%.
+    ///
+    /// # Returns
+    ///
+    /// Formatted percentage.
+    pub fn format_percentage(&self, value: Decimal) -> String {
+        format!("{}%", self.format(value))
+    }
+
+    /// Round a number to the significant digits or the decimal places of the format.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Number to round.
+    ///
+    /// # Returns
+    ///
+    /// Rounded number.
+    fn round(&self, value: Decimal) -> Decimal {
+        match (self.significant_digits, self.decimals) {
+            (Some(digits), _) if !value.is_zero() => {
+                // Position of the first significant digit, 0 for the units
+                let magnitude = match value.abs().trunc() {
+                    whole if whole.is_zero() => {
+                        let fraction = value.abs().normalize().to_string();
+                        let zeros = fraction[2..].chars().take_while(|c| *c == '0').count();
+                        -(zeros as i64) - 1
+                    }
+                    whole => whole.to_string().len() as i64 - 1,
+                };
+                let decimals = digits as i64 - 1 - magnitude;
+
+                match decimals {
+                    decimals if decimals >= 0 => value.round_dp_with_strategy(
+                        decimals as u32,
+                        RoundingStrategy::MidpointAwayFromZero,
+                    ),
+                    decimals => {
+                        let scale = Decimal::from(10i64.pow((-decimals) as u32));
+                        (value / scale)
+                            .round_dp_with_strategy(0, RoundingStrategy::MidpointAwayFromZero)
+                            * scale
+                    }
+                }
+            }
+            (None, Some(decimals)) => {
+                value.round_dp_with_strategy(decimals, RoundingStrategy::MidpointAwayFromZero)
+            }
+            _ => value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_locales() {
+        let value = Decimal::new(12_345_675, 4);
+
+        assert_eq!(NumberFormat::default().format(value), "1,234.5675");
+        assert_eq!(NumberFormat::new(Locale::De).format(value), "1.234,5675");
+        assert_eq!(
+            NumberFormat::new(Locale::Fr).format(value),
+            "1\u{202f}234,5675"
+        );
+        assert_eq!(NumberFormat::new(Locale::Ch).format(value), "1'234.5675");
+        assert_eq!(
+            NumberFormat::default()
+                .group_thousands(false)
+                .decimals(2)
+                .format(-value),
+            "-1234.57"
+        );
+        assert_eq!(
+            NumberFormat::default().format(Decimal::new(1_000_000, 0)),
+            "1,000,000"
+        );
+    }
+
+    #[test]
+    fn test_format_significant_digits() {
+        let format = NumberFormat::default().significant_digits(3);
+
+        assert_eq!(format.format(Decimal::new(1_234_567, 0)), "1,230,000");
+        assert_eq!(format.format(Decimal::new(12_345, 3)), "12.3");
+        assert_eq!(format.format(Decimal::new(123_456, 8)), "0.00123");
+        assert_eq!(format.format(Decimal::default()), "0");
+    }
+
+    #[test]
+    fn test_format_compact() {
+        let format = NumberFormat::readable(Locale::En);
+
+        assert_eq!(format.format(Decimal::new(1_234_567, 0)), "1.23M");
+        assert_eq!(format.format(Decimal::new(-45_600, 0)), "-45.6K");
+        assert_eq!(format.format(Decimal::new(999_999, 0)), "1M");
+        assert_eq!(format.format(Decimal::new(2_500_000_000_000, 0)), "2.5T");
+        assert_eq!(format.format(Decimal::new(12_345, 2)), "123");
+        assert_eq!(
+            NumberFormat::readable(Locale::De).format_percentage(Decimal::new(125, 1)),
+            "12,5%"
+        );
+    }
+}
@@ -0,0 +1,427 @@
+//! # Fuzz module
+//!
+//! This module contains the scenario fuzzer.
+//!
+//! The fuzzer generates randomized but valid token and option combinations within the given bounds,
+//! runs them, and reports any errors, panics, or invariant violations.
+//! It is useful both for the development of the crate and for robustness checks of custom configurations.
+
+use std::{
+    ops::RangeInclusive,
+    panic::{self, AssertUnwindSafe},
+};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{FiatAmount, Simulation, SimulationError, SimulationOptions, Token};
+
+/// Bounds of the generated scenarios.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct FuzzBounds {
+    /// Bounds of the total supply of the token.
+    pub total_supply: RangeInclusive<i64>,
+
+    /// Bounds of the initial price of the token.
+    pub initial_price: RangeInclusive<f64>,
+
+    /// Bounds of the burn rate of the token.
+    pub burn_rate: RangeInclusive<f64>,
+
+    /// Bounds of the inflation rate of the token.
+    pub inflation_rate: RangeInclusive<f64>,
+
+    /// Bounds of the airdrop percentage of the token.
+    pub airdrop_percentage: RangeInclusive<f64>,
+
+    /// Bounds of the buy and sell taxes of the token, in percentage.
+    pub tax: RangeInclusive<f64>,
+
+    /// Bounds of the number of users.
+    pub total_users: RangeInclusive<u64>,
+
+    /// Bounds of the duration of the simulation.
+    pub duration: RangeInclusive<u64>,
+
+    /// Bounds of the market volatility.
+    pub market_volatility: RangeInclusive<f64>,
+
+    /// Bounds of the transaction fee, in percentage.
+    pub transaction_fee_percentage: RangeInclusive<f64>,
+
+    /// Bounds of the adoption rate.
+    pub adoption_rate: RangeInclusive<f64>,
+}
+
+/// Scenario fuzzer.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct ScenarioFuzzer {
+    /// Bounds of the generated scenarios.
+    pub bounds: FuzzBounds,
+
+    /// Number of scenarios to generate and run.
+    pub iterations: u64,
+
+    /// Seed used to generate the scenarios.
+    /// The same seed always generates the same scenarios.
+    pub seed: u64,
+}
+
+/// Kind of a fuzzing failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum FuzzFailureKind {
+    /// The simulation returned an error.
+    Error,
+
+    /// The simulation panicked, e.g. on a decimal overflow.
+    Panic,
+
+    /// The simulation completed, but an invariant was violated.
+    InvariantViolation,
+}
+
+/// Failure found by the fuzzer.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct FuzzFailure {
+    /// Iteration of the failing scenario.
+    pub iteration: u64,
+
+    /// Kind of the failure.
+    pub kind: FuzzFailureKind,
+
+    /// Description of the failure.
+    pub message: String,
+
+    /// Token of the failing scenario.
+    pub token: Token,
+
+    /// Input parameters of the failing scenario.
+    pub options: SimulationOptions,
+}
+
+/// Report of a fuzzing session.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct FuzzReport {
+    /// Number of scenarios run.
+    pub runs: u64,
+
+    /// Failures found by the fuzzer.
+    pub failures: Vec<FuzzFailure>,
+}
+
+impl Default for FuzzBounds {
+    /// Create bounds covering common configurations, sized to run quickly.
+    ///
+    /// # Returns
+    ///
+    /// Default fuzzing bounds.
+    fn default() -> Self {
+        FuzzBounds {
+            total_supply: 1_000..=1_000_000_000,
+            initial_price: 0.0001..=1_000.0,
+            burn_rate: 0.0..=0.1,
+            inflation_rate: 0.0..=0.1,
+            airdrop_percentage: 0.0..=50.0,
+            tax: 0.0..=10.0,
+            total_users: 1..=200,
+            duration: 1..=30,
+            market_volatility: 0.0..=1.0,
+            transaction_fee_percentage: 0.0..=5.0,
+            adoption_rate: 0.0..=0.05,
+        }
+    }
+}
+
+impl ScenarioFuzzer {
+    /// Create a new scenario fuzzer.
+    ///
+    /// # Arguments
+    ///
+    /// * `bounds` - Bounds of the generated scenarios.
+    /// * `iterations` - Number of scenarios to generate and run.
+    /// * `seed` - Seed used to generate the scenarios.
+    ///
+    /// # Returns
+    ///
+    /// New scenario fuzzer.
+    pub fn new(bounds: FuzzBounds, iterations: u64, seed: u64) -> Self {
+        ScenarioFuzzer {
+            bounds,
+            iterations,
+            seed,
+        }
+    }
+
+    /// Generate a random scenario within the bounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - Random number generator.
+    ///
+    /// # Returns
+    ///
+    /// Generated token and input parameters.
+    pub fn generate<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+    ) -> Result<(Token, SimulationOptions), SimulationError> {
+        let bounds = &self.bounds;
+
+        let token = Simulation::token_builder()
+            .name("Fuzz Token".to_string())
+            .total_supply(rng.random_range(bounds.total_supply.clone()))
+            .initial_price(rng.random_range(bounds.initial_price.clone()))
+            .burn_rate(rng.random_range(bounds.burn_rate.clone()))
+            .inflation_rate(rng.random_range(bounds.inflation_rate.clone()))
+            .airdrop_percentage(rng.random_range(bounds.airdrop_percentage.clone()))
+            .buy_tax(rng.random_range(bounds.tax.clone()))
+            .sell_tax(rng.random_range(bounds.tax.clone()))
+            .build()?;
+
+        let options = Simulation::options_builder()
+            .total_users(rng.random_range(bounds.total_users.clone()))
+            .duration(rng.random_range(bounds.duration.clone()))
+            .market_volatility(rng.random_range(bounds.market_volatility.clone()))
+            .transaction_fee_percentage(rng.random_range(bounds.transaction_fee_percentage.clone()))
+            .adoption_rate(rng.random_range(bounds.adoption_rate.clone()))
+            .build()?;
+
+        Ok((token, options))
+    }
+
+    /// Generate and run the scenarios.
+    /// Panics of the simulation are caught and reported as failures.
+    ///
+    /// # Returns
+    ///
+    /// Report of the fuzzing session.
+    pub fn run(&self) -> Result<FuzzReport, SimulationError> {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut failures = vec![];
+
+        for iteration in 0..self.iterations {
+            #[cfg(feature = "log")]
+            log::debug!("Running fuzz iteration: {}", iteration);
+
+            let (token, options) = self.generate(&mut rng)?;
+
+            let mut simulation = Simulation::builder()
+                .name(format!("Fuzz Simulation {}", iteration))
+                .token(token.clone())
+                .options(options.clone())
+                .build()?;
+
+            let failure = match panic::catch_unwind(AssertUnwindSafe(|| simulation.run())) {
+                Ok(Ok(())) => check_invariants(&simulation)
+                    .map(|message| (FuzzFailureKind::InvariantViolation, message)),
+                Ok(Err(err)) => Some((FuzzFailureKind::Error, err.to_string())),
+                Err(panic) => Some((FuzzFailureKind::Panic, panic_message(panic.as_ref()))),
+            };
+
+            if let Some((kind, message)) = failure {
+                failures.push(FuzzFailure {
+                    iteration,
+                    kind,
+                    message,
+                    token,
+                    options,
+                });
+            }
+        }
+
+        Ok(FuzzReport {
+            runs: self.iterations,
+            failures,
+        })
+    }
+}
+
+/// Check the invariants of a completed simulation.
+///
+/// # Arguments
+///
+/// * `simulation` - A completed simulation.
+///
+/// # Returns
+///
+/// Description of the first violated invariant, if any.
+pub fn check_invariants(simulation: &Simulation) -> Option<String> {
+    let token = &simulation.token;
+    let report = &simulation.report;
+
+    if token.current_supply > token.total_supply {
+        return Some(format!(
+            "Current supply {} exceeds total supply {}.",
+            token.current_supply, token.total_supply
+        ));
+    }
+
+    if simulation.interval_reports.len() as u64 != simulation.options.duration {
+        return Some(format!(
+            "Expected {} interval reports, got {}.",
+            simulation.options.duration,
+            simulation.interval_reports.len()
+        ));
+    }
+
+    for (index, interval) in simulation.interval_reports.iter().enumerate() {
+        if interval.trades != interval.successful_trades + interval.failed_trades {
+            return Some(format!("Trade counts do not add up in interval {}.", index));
+        }
+
+        if interval.token_price < FiatAmount::default() {
+            return Some(format!("Negative token price in interval {}.", index));
+        }
+    }
+
+    if let Some(user) = report
+        .users
+        .iter()
+        .flatten()
+        .find(|user| user.balance < Decimal::default())
+    {
+        return Some(format!(
+            "User {} has a negative balance {}.",
+            user.id, user.balance
+        ));
+    }
+
+    None
+}
+
+/// Extract the message of a caught panic.
+///
+/// # Arguments
+///
+/// * `panic` - Payload of the panic.
+///
+/// # Returns
+///
+/// Message of the panic.
+fn panic_message(panic: &(dyn std::any::Any + Send)) -> String {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Unknown panic.".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TokenAmount;
+
+    use super::*;
+
+    #[test]
+    fn test_generate_within_bounds() {
+        let fuzzer = ScenarioFuzzer::new(FuzzBounds::default(), 1, 1);
+        let mut rng = StdRng::seed_from_u64(1);
+
+        for _ in 0..20 {
+            let (token, options) = fuzzer.generate(&mut rng).unwrap();
+
+            assert!(token.total_supply >= TokenAmount(Decimal::new(1_000, 0)));
+            assert!(token.burn_rate.unwrap() <= Decimal::new(1, 1));
+            assert!((1..=200).contains(&options.total_users));
+            assert!((1..=30).contains(&options.duration));
+        }
+    }
+
+    #[test]
+    fn test_generate_is_deterministic() {
+        let fuzzer = ScenarioFuzzer::new(FuzzBounds::default(), 1, 1);
+
+        let (first, _) = fuzzer.generate(&mut StdRng::seed_from_u64(7)).unwrap();
+        let (second, _) = fuzzer.generate(&mut StdRng::seed_from_u64(7)).unwrap();
+
+        assert_eq!(first.total_supply, second.total_supply);
+        assert_eq!(first.burn_rate, second.burn_rate);
+    }
+
+    #[test]
+    fn test_run() {
+        let bounds = FuzzBounds {
+            total_users: 1..=20,
+            duration: 1..=5,
+            ..Default::default()
+        };
+        let report = ScenarioFuzzer::new(bounds, 10, 42).run().unwrap();
+
+        assert_eq!(report.runs, 10);
+        assert!(report.failures.is_empty(), "{:?}", report.failures);
+
+        // Valid scenarios within the default bounds run clean
+        let report = ScenarioFuzzer::new(FuzzBounds::default(), 10, 7)
+            .run()
+            .unwrap();
+        assert_eq!(report.runs, 10);
+        assert!(report.failures.is_empty(), "{:?}", report.failures);
+    }
+
+    #[test]
+    fn test_check_invariants() {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(10)
+            .duration(2)
+            .build()
+            .unwrap();
+        let mut simulation = Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap();
+        simulation.run().unwrap();
+
+        assert_eq!(check_invariants(&simulation), None);
+
+        simulation.token.current_supply = simulation.token.total_supply + TokenAmount(Decimal::ONE);
+        assert!(check_invariants(&simulation).is_some());
+    }
+
+    #[test]
+    fn test_panic_message() {
+        assert_eq!(panic_message(&"boom"), "boom");
+        assert_eq!(panic_message(&"boom".to_string()), "boom");
+        assert_eq!(panic_message(&42), "Unknown panic.");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let fuzzer = ScenarioFuzzer::new(FuzzBounds::default(), 1, 1);
+        let json = serde_json::to_string(&fuzzer).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ScenarioFuzzer>(&json).unwrap(),
+            fuzzer
+        );
+
+        let (token, options) = fuzzer.generate(&mut StdRng::seed_from_u64(1)).unwrap();
+        let report = FuzzReport {
+            runs: 1,
+            failures: vec![FuzzFailure {
+                iteration: 0,
+                kind: FuzzFailureKind::Panic,
+                message: "boom".to_string(),
+                token,
+                options,
+            }],
+        };
+        let json = serde_json::to_string(&report).unwrap();
+        assert_eq!(serde_json::from_str::<FuzzReport>(&json).unwrap(), report);
+    }
+}
@@ -0,0 +1,310 @@
+//! # Gaming module
+//!
+//! This module contains the game economy, a play-to-earn loop of faucets and sinks.
+//!
+//! In each interval, the faucet mints gameplay rewards to every active user, decaying over time.
+//! Active users then spend tokens in the sinks of the game, e.g. crafting or upgrades, and the spent tokens are burned or sent to the treasury.
+//! The ratio of the sinks to the faucet tells whether the sinks absorb the emissions of the game.
+
+use rand::Rng;
+use rust_decimal::{prelude::*, Decimal};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{SimulationError, SinkDestination, UserStore};
+
+/// Sink of a game economy, e.g. crafting or upgrades.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct GameSink {
+    /// Name of the sink, e.g. `crafting`.
+    pub name: String,
+
+    /// Cost of one use of the sink, in tokens.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub cost: Decimal,
+
+    /// Expected uses of the sink by an active user per interval.
+    pub uses_per_user: f64,
+
+    /// Destination of the spent tokens.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub destination: SinkDestination,
+}
+
+/// Play-to-earn game economy of faucets and sinks.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct GameEconomy {
+    /// Gameplay rewards minted to an active user per interval, in tokens.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub reward_per_user: Decimal,
+
+    /// Decay of the rewards per interval, e.g. 0.05 for 5% less rewards each interval.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub reward_decay: f64,
+
+    /// Sinks of the game.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub sinks: Vec<GameSink>,
+}
+
+/// Flows of a game economy in an interval.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct GameFlows {
+    /// Tokens minted by the faucet.
+    pub(crate) rewards: Decimal,
+
+    /// Tokens spent in the sinks.
+    pub(crate) spent: Decimal,
+
+    /// Tokens spent in the sinks and burned.
+    pub(crate) burned: Decimal,
+}
+
+impl GameSink {
+    /// Create a new sink, burning the spent tokens.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the sink.
+    /// * `cost` - Cost of one use, in tokens.
+    /// * `uses_per_user` - Expected uses by an active user per interval.
+    ///
+    /// # Returns
+    ///
+    /// New game sink.
+    pub fn new(name: &str, cost: Decimal, uses_per_user: f64) -> Self {
+        GameSink {
+            name: name.to_string(),
+            cost,
+            uses_per_user,
+            destination: SinkDestination::default(),
+        }
+    }
+}
+
+impl GameEconomy {
+    /// Create a new game economy with a faucet and no sinks.
+    ///
+    /// # Arguments
+    ///
+    /// * `reward_per_user` - Gameplay rewards minted to an active user per interval, in tokens.
+    ///
+    /// # Returns
+    ///
+    /// New game economy.
+    pub fn new(reward_per_user: Decimal) -> Self {
+        GameEconomy {
+            reward_per_user,
+            ..Default::default()
+        }
+    }
+
+    /// Add a sink to the game economy.
+    ///
+    /// # Arguments
+    ///
+    /// * `sink` - Sink of the game.
+    ///
+    /// # Returns
+    ///
+    /// The game economy.
+    pub fn sink(mut self, sink: GameSink) -> Self {
+        self.sinks.push(sink);
+        self
+    }
+
+    /// Get the rewards minted to an active user in an interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the interval.
+    ///
+    /// # Returns
+    ///
+    /// Rewards per active user, in tokens.
+    pub fn reward_at(&self, index: u64) -> Decimal {
+        let factor = (1.0 - self.reward_decay).powi(index.min(i32::MAX as u64) as i32);
+
+        self.reward_per_user * Decimal::from_f64(factor.max(0.0)).unwrap_or_default()
+    }
+
+    /// Run the faucet and the sinks of an interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the interval.
+    /// * `users` - Users of the simulation.
+    /// * `mintable` - Tokens left to mint within the total supply.
+    /// * `precision` - Decimal precision of the balances.
+    /// * `rng` - Random number generator.
+    ///
+    /// # Returns
+    ///
+    /// Flows of the interval, or an error if the expected uses of a sink cannot be converted.
+    pub(crate) fn play<R: Rng + ?Sized>(
+        &self,
+        index: u64,
+        users: &mut UserStore,
+        mintable: Decimal,
+        precision: u32,
+        rng: &mut R,
+    ) -> Result<GameFlows, SimulationError> {
+        let mut flows = GameFlows::default();
+        let reward = self.reward_at(index).round_dp(precision);
+
+        for index in 0..users.len() {
+            if !users.is_active(index) {
+                continue;
+            }
+
+            let weight = users.weights[index];
+            let balance = &mut users.balances[index];
+            let amount = (reward * Decimal::from(weight))
+                .min(mintable - flows.rewards)
+                .max(Decimal::ZERO);
+            *balance += amount;
+            flows.rewards += amount;
+
+            for sink in &self.sinks {
+                if sink.cost <= Decimal::ZERO {
+                    continue;
+                }
+
+                // The fractional use is taken with its probability, and only the affordable uses are taken
+                let expected = sink.uses_per_user.max(0.0) * weight as f64;
+                let mut uses = expected.trunc();
+                if rng.random_bool(expected.fract()) {
+                    uses += 1.0;
+                }
+                let uses = Decimal::from_f64(uses)
+                    .ok_or(SimulationError::InvalidDecimal)?
+                    .min((*balance / sink.cost).floor());
+                let amount = uses * sink.cost;
+
+                *balance -= amount;
+                flows.spent += amount;
+                if sink.destination == SinkDestination::Burn {
+                    flows.burned += amount;
+                }
+            }
+        }
+
+        Ok(flows)
+    }
+}
+
+/// Get the ratio of the tokens spent in the sinks to the tokens minted by the faucet.
+/// Above 1, the sinks absorb all the emissions of the game.
+///
+/// # Arguments
+///
+/// * `rewards` - Tokens minted by the faucet.
+/// * `spent` - Tokens spent in the sinks.
+/// * `precision` - Decimal precision of the ratio.
+///
+/// # Returns
+///
+/// Sink ratio, or `None` if the faucet minted no tokens.
+pub(crate) fn sink_ratio(rewards: Decimal, spent: Decimal, precision: u32) -> Option<Decimal> {
+    spent
+        .checked_div(rewards)
+        .map(|ratio| ratio.round_dp(precision))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+    use uuid::Uuid;
+
+    use crate::{Simulation, TokenAmount, User};
+
+    use super::*;
+
+    #[test]
+    fn test_play() {
+        let mut users = UserStore::from(vec![User::new(Uuid::new_v4(), Decimal::new(3, 0)); 3]);
+        users.balances[1] = Decimal::ZERO;
+        users.states[2] = crate::UserState::Dormant;
+        let economy = GameEconomy::new(Decimal::new(5, 0))
+            .sink(GameSink::new("crafting", Decimal::new(2, 0), 2.0))
+            .sink(GameSink {
+                destination: SinkDestination::Treasury,
+                ..GameSink::new("upgrades", Decimal::new(3, 0), 1.0)
+            });
+        let mut rng = StdRng::seed_from_u64(1);
+
+        // Only 8 tokens are left to mint, the second user is rewarded 3 and can only craft once
+        let flows = economy
+            .play(0, &mut users, Decimal::new(8, 0), 4, &mut rng)
+            .unwrap();
+        assert_eq!(flows.rewards, Decimal::new(8, 0));
+        assert_eq!(flows.spent, Decimal::new(9, 0));
+        assert_eq!(flows.burned, Decimal::new(6, 0));
+        assert_eq!(users.balances[0], Decimal::ONE);
+        assert_eq!(users.balances[1], Decimal::ONE);
+        assert_eq!(users.balances[2], Decimal::new(3, 0));
+
+        assert_eq!(
+            sink_ratio(flows.rewards, flows.spent, 3),
+            Some(Decimal::new(1125, 3))
+        );
+        assert_eq!(sink_ratio(Decimal::ZERO, flows.spent, 3), None);
+    }
+
+    #[test]
+    fn test_reward_at() {
+        let economy = GameEconomy {
+            reward_decay: 0.5,
+            ..GameEconomy::new(Decimal::new(8, 0))
+        };
+
+        assert_eq!(economy.reward_at(0), Decimal::new(8, 0));
+        assert_eq!(economy.reward_at(2), Decimal::new(2, 0));
+    }
+
+    #[test]
+    fn test_run_with_game_economy() {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .total_supply(1_000_000)
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(20)
+            .duration(3)
+            .seed(3)
+            .game_economy(GameEconomy::new(Decimal::new(10, 0)).sink(GameSink::new(
+                "crafting",
+                Decimal::new(4, 0),
+                1.5,
+            )))
+            .build()
+            .unwrap();
+        let mut simulation = Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap();
+
+        simulation.run().unwrap();
+
+        let report = &simulation.interval_reports[0];
+        assert!(report.game_rewards > TokenAmount::default());
+        assert!(report.game_sinks > TokenAmount::default());
+        assert_eq!(
+            report.sink_ratio,
+            sink_ratio(report.game_rewards.value(), report.game_sinks.value(), 4)
+        );
+
+        let rewards: TokenAmount = simulation
+            .interval_reports
+            .iter()
+            .map(|report| report.game_rewards)
+            .sum();
+        assert_eq!(simulation.report.game_rewards, rewards);
+        assert!(simulation.report.total_burned >= simulation.report.game_sinks);
+    }
+}
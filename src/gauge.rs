@@ -0,0 +1,420 @@
+//! # Gauge module
+//!
+//! This module contains the gauge voting, which directs the emission of the token to pools by the votes of the vote-escrow holders.
+//!
+//! Active users lock a share of their balance as vote-escrowed tokens, and vote each interval on the gauges of the pools.
+//! Without bribes, the votes follow the base weight of each gauge. Pools paying bribes sway a share of the votes towards them.
+//! The emission of the interval is minted within the total supply, split between the gauges by their votes,
+//! and paid to the active users providing liquidity.
+//! The return of a bribe is the value of the emission it directed to its pool, against its cost.
+
+use std::collections::BTreeMap;
+
+use rust_decimal::{prelude::*, Decimal};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{SimulationError, SimulationReport, UserBehaviour, UserStore};
+
+/// Gauge of a pool, receiving the emission voted on it.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Gauge {
+    /// Name of the pool, e.g. `ETH/TOKEN`.
+    pub name: String,
+
+    /// Weight of the gauge in the votes without bribes.
+    pub base_weight: f64,
+
+    /// Bribe paid by the pool to the voters per interval, in fiat.
+    #[cfg_attr(feature = "serde", serde(default, with = "rust_decimal::serde::float"))]
+    pub bribe: Decimal,
+}
+
+/// Emission of the token directed by gauge votes.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct GaugeVoting {
+    /// Tokens emitted per interval.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub emission_per_interval: Decimal,
+
+    /// Share of the balance of the active users locked as vote-escrowed tokens, between 0 and 1.
+    /// Scaled for each user by the stake propensity of their behaviour.
+    pub lock_share: f64,
+
+    /// Share of the votes following the bribes, between 0 and 1.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub bribe_sensitivity: f64,
+
+    /// Gauges of the pools.
+    pub gauges: Vec<Gauge>,
+}
+
+/// Emission directed to a gauge.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct GaugeReport {
+    /// Name of the pool.
+    pub name: String,
+
+    /// Votes on the gauge, in vote-escrowed tokens.
+    /// In the final report, the mean votes per interval.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub votes: Decimal,
+
+    /// Share of the emission directed to the gauge, between 0 and 1.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub emission_share: Decimal,
+
+    /// Tokens emitted to the gauge.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub emission: Decimal,
+
+    /// Bribe paid by the pool, in fiat.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub bribe: Decimal,
+
+    /// Value of the emission directed to the gauge by the bribes, in fiat.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub bribe_value: Decimal,
+
+    /// Return on the bribe, the value it directed net of its cost, per unit of bribe.
+    /// `None` if the pool paid no bribe.
+    #[cfg_attr(feature = "serde", serde(default, with = "crate::float_option"))]
+    pub bribe_roi: Option<Decimal>,
+}
+
+impl Gauge {
+    /// Create a new gauge without bribes.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the pool.
+    /// * `base_weight` - Weight of the gauge in the votes without bribes.
+    ///
+    /// # Returns
+    ///
+    /// New gauge.
+    pub fn new(name: &str, base_weight: f64) -> Self {
+        Gauge {
+            name: name.to_string(),
+            base_weight,
+            bribe: Decimal::default(),
+        }
+    }
+}
+
+impl GaugeVoting {
+    /// Validate the gauge voting.
+    ///
+    /// # Returns
+    ///
+    /// Result of the validation, or an error if there are no gauges, or a share or a weight is out of range.
+    pub fn validate(&self) -> Result<(), SimulationError> {
+        let invalid = |reason: &str| Err(SimulationError::InvalidData(format!("gauges: {reason}")));
+
+        if self.gauges.is_empty() {
+            return invalid("at least one gauge is required");
+        }
+        if !(0.0..=1.0).contains(&self.lock_share) || !(0.0..=1.0).contains(&self.bribe_sensitivity)
+        {
+            return invalid("lock share and bribe sensitivity must be between 0 and 1");
+        }
+        if self
+            .gauges
+            .iter()
+            .any(|gauge| gauge.base_weight < 0.0 || gauge.bribe.is_sign_negative())
+        {
+            return invalid("base weights and bribes must be non-negative");
+        }
+
+        Ok(())
+    }
+
+    /// Get the share of the votes of each gauge.
+    ///
+    /// # Arguments
+    ///
+    /// * `with_bribes` - Whether the bribes sway the votes.
+    ///
+    /// # Returns
+    ///
+    /// Share of the votes of each gauge, summing to 1.
+    pub fn vote_shares(&self, with_bribes: bool) -> Vec<f64> {
+        let count = self.gauges.len() as f64;
+        let total_weight: f64 = self.gauges.iter().map(|gauge| gauge.base_weight).sum();
+        let total_bribes: Decimal = self.gauges.iter().map(|gauge| gauge.bribe).sum();
+        let sensitivity = match with_bribes && total_bribes > Decimal::ZERO {
+            true => self.bribe_sensitivity,
+            false => 0.0,
+        };
+
+        self.gauges
+            .iter()
+            .map(|gauge| {
+                let base = match total_weight > 0.0 {
+                    true => gauge.base_weight / total_weight,
+                    false => 1.0 / count,
+                };
+                let bribed = gauge
+                    .bribe
+                    .checked_div(total_bribes)
+                    .and_then(|share| share.to_f64())
+                    .unwrap_or_default();
+
+                (1.0 - sensitivity) * base + sensitivity * bribed
+            })
+            .collect()
+    }
+
+    /// Vote on the gauges and pay the emission of an interval to the active users.
+    ///
+    /// # Arguments
+    ///
+    /// * `users` - Users of the simulation.
+    /// * `stake_propensity` - Share of the balance each behaviour is inclined to lock.
+    /// * `price` - Price of the token in the interval.
+    /// * `mintable` - Tokens left to mint within the total supply.
+    /// * `precision` - Decimal precision of the balances.
+    ///
+    /// # Returns
+    ///
+    /// Report of each gauge, or an error if a share cannot be converted.
+    pub(crate) fn allocate(
+        &self,
+        users: &mut UserStore,
+        stake_propensity: impl Fn(UserBehaviour) -> f64,
+        price: Decimal,
+        mintable: Decimal,
+        precision: u32,
+    ) -> Result<Vec<GaugeReport>, SimulationError> {
+        let to_decimal =
+            |value: f64| Decimal::from_f64(value).ok_or(SimulationError::InvalidDecimal);
+        let lock_share = to_decimal(self.lock_share)?;
+        let mut voting_power = Decimal::default();
+        for index in (0..users.len()).filter(|&index| users.is_active(index)) {
+            voting_power += users.balances[index]
+                * lock_share
+                * to_decimal(stake_propensity(users.behaviours[index]))?;
+        }
+
+        // The emission is paid to the active users providing liquidity, by their number of members
+        let members: u64 = (0..users.len())
+            .filter(|&index| users.is_active(index))
+            .map(|index| users.weights[index])
+            .sum();
+        let per_member = self
+            .emission_per_interval
+            .min(mintable.max(Decimal::ZERO))
+            .checked_div(Decimal::from(members))
+            .unwrap_or_default()
+            .round_dp(precision);
+        let mut emission = Decimal::default();
+        for index in 0..users.len() {
+            if users.is_active(index) {
+                let amount = per_member * Decimal::from(users.weights[index]);
+                users.balances[index] += amount;
+                emission += amount;
+            }
+        }
+
+        let organic = self.vote_shares(false);
+        let mut reports = vec![];
+        for ((gauge, share), base) in self.gauges.iter().zip(self.vote_shares(true)).zip(organic) {
+            let bribe_value = (emission * to_decimal(share - base)? * price).max(Decimal::ZERO);
+            reports.push(GaugeReport {
+                name: gauge.name.clone(),
+                votes: (voting_power * to_decimal(share)?).round_dp(precision),
+                emission_share: to_decimal(share)?.round_dp(precision),
+                emission: (emission * to_decimal(share)?).round_dp(precision),
+                bribe: gauge.bribe,
+                bribe_value: bribe_value.round_dp(precision),
+                bribe_roi: bribe_roi(bribe_value, gauge.bribe, precision),
+            });
+        }
+
+        Ok(reports)
+    }
+}
+
+/// Calculate the return on a bribe.
+///
+/// # Arguments
+///
+/// * `value` - Value of the emission directed by the bribe, in fiat.
+/// * `bribe` - Cost of the bribe, in fiat.
+/// * `decimals` - Number of decimal places to round to.
+///
+/// # Returns
+///
+/// Return on the bribe, `None` without a bribe.
+fn bribe_roi(value: Decimal, bribe: Decimal, decimals: u32) -> Option<Decimal> {
+    (value - bribe)
+        .checked_div(bribe)
+        .map(|roi| roi.round_dp(decimals))
+}
+
+/// Aggregate the gauges over the intervals of a simulation.
+///
+/// # Arguments
+///
+/// * `reports` - Interval reports of the simulation.
+/// * `decimals` - Number of decimal places to round to.
+///
+/// # Returns
+///
+/// Total emission and bribes of each gauge, with the mean votes.
+pub(crate) fn aggregate_gauges(reports: &[SimulationReport], decimals: u32) -> Vec<GaugeReport> {
+    let mut totals: BTreeMap<&str, (usize, GaugeReport, Decimal)> = BTreeMap::new();
+    for (position, gauge) in reports
+        .iter()
+        .flat_map(|report| report.gauges.iter().enumerate())
+    {
+        let (_, total, intervals) = totals.entry(&gauge.name).or_insert((
+            position,
+            GaugeReport {
+                name: gauge.name.clone(),
+                votes: Decimal::default(),
+                emission_share: Decimal::default(),
+                emission: Decimal::default(),
+                bribe: Decimal::default(),
+                bribe_value: Decimal::default(),
+                bribe_roi: None,
+            },
+            Decimal::default(),
+        ));
+        total.votes += gauge.votes;
+        total.emission += gauge.emission;
+        total.bribe += gauge.bribe;
+        total.bribe_value += gauge.bribe_value;
+        *intervals += Decimal::ONE;
+    }
+
+    let total_emission: Decimal = totals.values().map(|(_, gauge, _)| gauge.emission).sum();
+    let mut gauges: Vec<(usize, GaugeReport)> = totals
+        .into_values()
+        .map(|(position, mut gauge, intervals)| {
+            gauge.votes = (gauge.votes / intervals).round_dp(decimals);
+            gauge.emission_share = gauge
+                .emission
+                .checked_div(total_emission)
+                .unwrap_or_default()
+                .round_dp(decimals);
+            gauge.bribe_roi = bribe_roi(gauge.bribe_value, gauge.bribe, decimals);
+            (position, gauge)
+        })
+        .collect();
+
+    // Keep the order of the configuration, as in the interval reports
+    gauges.sort_by_key(|(position, _)| *position);
+
+    gauges.into_iter().map(|(_, gauge)| gauge).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::{Simulation, TokenAmount, User};
+
+    use super::*;
+
+    fn voting() -> GaugeVoting {
+        GaugeVoting {
+            emission_per_interval: Decimal::new(100, 0),
+            lock_share: 0.5,
+            bribe_sensitivity: 0.5,
+            gauges: vec![
+                Gauge::new("ETH/TOKEN", 3.0),
+                Gauge {
+                    bribe: Decimal::new(10, 0),
+                    ..Gauge::new("USDC/TOKEN", 1.0)
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_vote_shares() {
+        let voting = voting();
+
+        assert_eq!(voting.vote_shares(false), vec![0.75, 0.25]);
+        // Half of the votes follow the only bribe
+        assert_eq!(voting.vote_shares(true), vec![0.375, 0.625]);
+    }
+
+    #[test]
+    fn test_allocate() {
+        let mut users = UserStore::from(vec![User::new(Uuid::new_v4(), Decimal::new(10, 0)); 2]);
+        let reports = voting()
+            .allocate(
+                &mut users,
+                |behaviour| behaviour.profile().stake_propensity,
+                Decimal::ONE,
+                Decimal::new(1_000, 0),
+                4,
+            )
+            .unwrap();
+
+        assert_eq!(users.balances[0], Decimal::new(60, 0));
+        // Traders lock 30% of the lock share
+        assert_eq!(reports[0].votes, Decimal::new(1125, 3));
+        assert_eq!(reports[1].emission, Decimal::new(625, 1));
+        // The bribe directed 37.5 tokens more to the pool, for a cost of 10
+        assert_eq!(reports[1].bribe_value, Decimal::new(375, 1));
+        assert_eq!(reports[1].bribe_roi, Some(Decimal::new(275, 2)));
+        assert_eq!(reports[0].bribe_roi, None);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(voting().validate().is_ok());
+
+        let voting = GaugeVoting {
+            gauges: vec![],
+            ..voting()
+        };
+        assert!(matches!(
+            voting.validate(),
+            Err(SimulationError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn test_run_with_gauges() {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(20)
+            .duration(3)
+            .seed(6)
+            .gauge_voting(voting())
+            .build()
+            .unwrap();
+        let mut simulation = Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap();
+
+        simulation.run().unwrap();
+
+        let emission: TokenAmount = simulation
+            .interval_reports
+            .iter()
+            .map(|report| report.gauge_emission)
+            .sum();
+        assert!(emission > TokenAmount::default());
+        assert_eq!(simulation.report.gauge_emission, emission);
+
+        let gauges = &simulation.report.gauges;
+        assert_eq!(gauges.len(), 2);
+        assert_eq!(gauges[0].name, "ETH/TOKEN");
+        assert_eq!(gauges[1].bribe, Decimal::new(30, 0));
+        assert!(gauges[1].emission_share > gauges[0].emission_share);
+    }
+}
@@ -0,0 +1,600 @@
+//! # gRPC module
+//!
+//! This module contains the gRPC service of the simulator, enabled by the `grpc` feature.
+//!
+//! The service is built on tonic and matches `proto/simulator.proto`, for teams whose infrastructure is gRPC-first.
+//! Simulations are created first, then run, streamed, or queried by ID: the service keeps them in memory.
+//! Token and options are exchanged as JSON in the schema of the crate, reports carry their headline metrics
+//! as typed fields next to the full JSON.
+
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    task::{Context, Poll},
+    thread,
+};
+
+use tonic::{codegen::tokio_stream::Stream, Request, Response, Status};
+use uuid::Uuid;
+
+use crate::{
+    report_stream, CancellationToken, ReportReceiver, Simulation, SimulationError,
+    SimulationOptions, SimulationReport, SimulationStatus, StreamEvent, Token,
+};
+
+/// Protobuf messages and generated server and client of the simulator service.
+pub mod proto {
+    /// Request to create a simulation.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct CreateSimulationRequest {
+        /// Name of the simulation.
+        #[prost(string, tag = "1")]
+        pub name: String,
+
+        /// Description of the simulation.
+        #[prost(string, optional, tag = "2")]
+        pub description: Option<String>,
+
+        /// JSON of the token.
+        #[prost(string, tag = "3")]
+        pub token: String,
+
+        /// JSON of the input parameters.
+        #[prost(string, tag = "4")]
+        pub options: String,
+    }
+
+    /// Request targeting a simulation.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SimulationRequest {
+        /// ID of the simulation.
+        #[prost(string, tag = "1")]
+        pub id: String,
+    }
+
+    /// Status of a simulation.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+    #[repr(i32)]
+    pub enum SimulationStatus {
+        /// Simulation has not started.
+        Pending = 0,
+
+        /// Simulation is currently running.
+        Running = 1,
+
+        /// Simulation has completed.
+        Completed = 2,
+
+        /// Simulation has failed.
+        Failed = 3,
+
+        /// Simulation was cancelled.
+        Cancelled = 4,
+    }
+
+    /// Created simulation.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SimulationHandle {
+        /// ID of the simulation.
+        #[prost(string, tag = "1")]
+        pub id: String,
+
+        /// Status of the simulation.
+        #[prost(enumeration = "SimulationStatus", tag = "2")]
+        pub status: i32,
+    }
+
+    /// Report of an interval, or final report of a simulation.
+    /// Decimal values are encoded as strings, to keep their precision.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Report {
+        /// ID of the simulation.
+        #[prost(string, tag = "1")]
+        pub simulation_id: String,
+
+        /// Whether this is the final report of the simulation.
+        #[prost(bool, tag = "2")]
+        pub completed: bool,
+
+        /// Timestamp of the interval of the report.
+        #[prost(int64, tag = "3")]
+        pub interval: i64,
+
+        /// Number of trades.
+        #[prost(uint64, tag = "4")]
+        pub trades: u64,
+
+        /// Number of successful trades.
+        #[prost(uint64, tag = "5")]
+        pub successful_trades: u64,
+
+        /// Number of failed trades.
+        #[prost(uint64, tag = "6")]
+        pub failed_trades: u64,
+
+        /// Number of active users.
+        #[prost(uint64, tag = "7")]
+        pub active_users: u64,
+
+        /// Number of users at the end of the simulation.
+        #[prost(uint64, tag = "8")]
+        pub final_user_count: u64,
+
+        /// Number of holders.
+        #[prost(uint64, tag = "9")]
+        pub holders: u64,
+
+        /// Price of the token.
+        #[prost(string, tag = "10")]
+        pub token_price: String,
+
+        /// Profit or loss.
+        #[prost(string, tag = "11")]
+        pub profit_loss: String,
+
+        /// Liquidity of the token.
+        #[prost(string, tag = "12")]
+        pub liquidity: String,
+
+        /// Number of tokens burned.
+        #[prost(string, tag = "13")]
+        pub total_burned: String,
+
+        /// Fees collected.
+        #[prost(string, tag = "14")]
+        pub fees: String,
+
+        /// JSON of the full report.
+        #[prost(string, tag = "15")]
+        pub json: String,
+    }
+
+    #[allow(missing_docs, clippy::missing_docs_in_private_items)]
+    mod generated {
+        include!(concat!(
+            env!("OUT_DIR"),
+            "/tokenomics.simulator.Simulator.rs"
+        ));
+    }
+
+    pub use generated::{simulator_client, simulator_server};
+}
+
+/// Simulations kept by the service, `None` while the simulation is running.
+type Store = Arc<Mutex<HashMap<Uuid, Option<Simulation>>>>;
+
+/// gRPC service of the simulator.
+/// Serve it with `proto::simulator_server::SimulatorServer::new(SimulatorService::default())`.
+#[derive(Debug, Default, Clone)]
+pub struct SimulatorService {
+    /// Simulations created through the service.
+    simulations: Store,
+}
+
+impl SimulatorService {
+    /// Lock the simulations, recovering them if a run panicked while holding the lock.
+    ///
+    /// # Returns
+    ///
+    /// Guard of the simulations.
+    fn simulations(&self) -> MutexGuard<'_, HashMap<Uuid, Option<Simulation>>> {
+        self.simulations
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Check out a simulation to run it, until the returned guard is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of the simulation.
+    ///
+    /// # Returns
+    ///
+    /// Checked out simulation, or an error if it does not exist or is already running.
+    fn checkout(&self, id: &str) -> Result<Checkout, Status> {
+        let id = parse_id(id)?;
+        let simulation = self
+            .simulations()
+            .get_mut(&id)
+            .ok_or_else(|| Status::not_found(format!("simulation not found: {}", id)))?
+            .take()
+            .ok_or_else(|| Status::failed_precondition("simulation is running"))?;
+
+        Ok(Checkout {
+            simulations: Arc::clone(&self.simulations),
+            simulation: Some(simulation),
+        })
+    }
+}
+
+/// Simulation checked out of the service, returned to it on drop.
+#[derive(Debug)]
+struct Checkout {
+    /// Simulations of the service.
+    simulations: Store,
+
+    /// Checked out simulation, always set until dropped.
+    simulation: Option<Simulation>,
+}
+
+impl Checkout {
+    /// Get the checked out simulation.
+    ///
+    /// # Returns
+    ///
+    /// Checked out simulation.
+    fn simulation(&mut self) -> &mut Simulation {
+        self.simulation
+            .as_mut()
+            .expect("simulation is set until dropped")
+    }
+}
+
+impl Drop for Checkout {
+    /// Return the simulation to the service, even if the run was interrupted.
+    fn drop(&mut self) {
+        if let Some(simulation) = self.simulation.take() {
+            self.simulations
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(simulation.id, Some(simulation));
+        }
+    }
+}
+
+/// Stream of the reports of a running simulation.
+/// Dropping the stream, e.g. when the client disconnects, cancels the simulation.
+#[derive(Debug)]
+pub struct IntervalStream {
+    /// ID of the simulation.
+    simulation_id: Uuid,
+
+    /// Receiver of the reports.
+    receiver: ReportReceiver,
+
+    /// Token used to cancel the simulation.
+    cancellation: CancellationToken,
+}
+
+impl Stream for IntervalStream {
+    type Item = Result<proto::Report, Status>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let simulation_id = self.simulation_id;
+
+        self.receiver.poll_recv(cx).map(|event| {
+            event.map(|event| match event {
+                StreamEvent::Interval(report) => encode_report(simulation_id, &report, false),
+                StreamEvent::Completed(report) => encode_report(simulation_id, &report, true),
+            })
+        })
+    }
+}
+
+impl Drop for IntervalStream {
+    /// Cancel the simulation, which is a no-op once it completed.
+    fn drop(&mut self) {
+        self.cancellation.cancel();
+    }
+}
+
+#[tonic::async_trait]
+impl proto::simulator_server::Simulator for SimulatorService {
+    async fn create_simulation(
+        &self,
+        request: Request<proto::CreateSimulationRequest>,
+    ) -> Result<Response<proto::SimulationHandle>, Status> {
+        let request = request.into_inner();
+        let token = serde_json::from_str::<Token>(&request.token)
+            .map_err(|error| Status::invalid_argument(format!("invalid token: {}", error)))?;
+        let options = serde_json::from_str::<SimulationOptions>(&request.options)
+            .map_err(|error| Status::invalid_argument(format!("invalid options: {}", error)))?;
+
+        let mut builder = Simulation::builder()
+            .name(request.name)
+            .token(token)
+            .options(options);
+        if let Some(description) = request.description {
+            builder = builder.description(description);
+        }
+        let simulation = builder.build()?;
+
+        #[cfg(feature = "log")]
+        log::debug!("Created simulation over gRPC: {}", simulation.id);
+
+        let handle = proto::SimulationHandle {
+            id: simulation.id.to_string(),
+            status: encode_status(&simulation.status) as i32,
+        };
+        self.simulations().insert(simulation.id, Some(simulation));
+
+        Ok(Response::new(handle))
+    }
+
+    async fn run(
+        &self,
+        request: Request<proto::SimulationRequest>,
+    ) -> Result<Response<proto::Report>, Status> {
+        let mut checkout = self.checkout(&request.into_inner().id)?;
+        let simulation = checkout.simulation();
+
+        simulation.run_async(&CancellationToken::new()).await?;
+
+        Ok(Response::new(encode_report(
+            simulation.id,
+            &simulation.report,
+            true,
+        )?))
+    }
+
+    type StreamIntervalsStream = IntervalStream;
+
+    async fn stream_intervals(
+        &self,
+        request: Request<proto::SimulationRequest>,
+    ) -> Result<Response<Self::StreamIntervalsStream>, Status> {
+        let mut checkout = self.checkout(&request.into_inner().id)?;
+        let (sender, receiver) = report_stream();
+        let cancellation = CancellationToken::new();
+        let simulation_id = checkout.simulation().id;
+
+        let token = cancellation.clone();
+        thread::spawn(move || {
+            let simulation = checkout.simulation();
+            simulation.add_observer(sender);
+
+            // Failures close the stream early, the client sees it ending without a final report
+            let _ = simulation.run_with_cancellation(&token);
+            simulation.observers.pop();
+        });
+
+        Ok(Response::new(IntervalStream {
+            simulation_id,
+            receiver,
+            cancellation,
+        }))
+    }
+
+    async fn get_report(
+        &self,
+        request: Request<proto::SimulationRequest>,
+    ) -> Result<Response<proto::Report>, Status> {
+        let id = parse_id(&request.into_inner().id)?;
+        let simulations = self.simulations();
+        let simulation = simulations
+            .get(&id)
+            .ok_or_else(|| Status::not_found(format!("simulation not found: {}", id)))?
+            .as_ref()
+            .ok_or_else(|| Status::failed_precondition("simulation is running"))?;
+
+        if simulation.status != SimulationStatus::Completed {
+            return Err(Status::failed_precondition("simulation has not completed"));
+        }
+
+        Ok(Response::new(encode_report(
+            simulation.id,
+            &simulation.report,
+            true,
+        )?))
+    }
+}
+
+impl From<SimulationError> for Status {
+    /// Convert the `SimulationError` into a gRPC status.
+    ///
+    /// # Returns
+    ///
+    /// `Status` with the code matching the error.
+    fn from(error: SimulationError) -> Self {
+        match error {
+            SimulationError::Cancelled => Status::cancelled(error.to_string()),
+            _ => Status::invalid_argument(error.to_string()),
+        }
+    }
+}
+
+/// Parse the ID of a simulation.
+///
+/// # Arguments
+///
+/// * `id` - ID of the simulation.
+///
+/// # Returns
+///
+/// Parsed ID, or an error if it is not a UUID.
+fn parse_id(id: &str) -> Result<Uuid, Status> {
+    Uuid::parse_str(id).map_err(|_| Status::invalid_argument(format!("invalid ID: {}", id)))
+}
+
+/// Encode the status of a simulation.
+///
+/// # Arguments
+///
+/// * `status` - Status of the simulation.
+///
+/// # Returns
+///
+/// Protobuf status.
+fn encode_status(status: &SimulationStatus) -> proto::SimulationStatus {
+    match status {
+        SimulationStatus::Pending => proto::SimulationStatus::Pending,
+        SimulationStatus::Running => proto::SimulationStatus::Running,
+        SimulationStatus::Completed => proto::SimulationStatus::Completed,
+        SimulationStatus::Failed { .. } => proto::SimulationStatus::Failed,
+        SimulationStatus::Cancelled { .. } => proto::SimulationStatus::Cancelled,
+    }
+}
+
+/// Encode a report.
+///
+/// # Arguments
+///
+/// * `simulation_id` - ID of the simulation.
+/// * `report` - Report to encode.
+/// * `completed` - Whether this is the final report of the simulation.
+///
+/// # Returns
+///
+/// Protobuf report.
+fn encode_report(
+    simulation_id: Uuid,
+    report: &SimulationReport,
+    completed: bool,
+) -> Result<proto::Report, Status> {
+    Ok(proto::Report {
+        simulation_id: simulation_id.to_string(),
+        completed,
+        interval: report.interval,
+        trades: report.trades,
+        successful_trades: report.successful_trades,
+        failed_trades: report.failed_trades,
+        active_users: report.active_users,
+        final_user_count: report.final_user_count,
+        holders: report.holders,
+        token_price: report.token_price.to_string(),
+        profit_loss: report.profit_loss.to_string(),
+        liquidity: report.liquidity.to_string(),
+        total_burned: report.total_burned.to_string(),
+        fees: report.fees.to_string(),
+        json: serde_json::to_string(report).map_err(|error| Status::internal(error.to_string()))?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use proto::simulator_server::Simulator;
+
+    use super::*;
+
+    fn create_request() -> proto::CreateSimulationRequest {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .total_supply(1_000_000)
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(10)
+            .duration(3)
+            .build()
+            .unwrap();
+
+        proto::CreateSimulationRequest {
+            name: "Test Simulation".to_string(),
+            description: None,
+            token: serde_json::to_string(&token).unwrap(),
+            options: serde_json::to_string(&options).unwrap(),
+        }
+    }
+
+    async fn create(service: &SimulatorService) -> String {
+        service
+            .create_simulation(Request::new(create_request()))
+            .await
+            .unwrap()
+            .into_inner()
+            .id
+    }
+
+    async fn next(stream: &mut IntervalStream) -> Option<Result<proto::Report, Status>> {
+        std::future::poll_fn(|cx| Pin::new(&mut *stream).poll_next(cx)).await
+    }
+
+    #[tokio::test]
+    async fn test_create_and_run() {
+        let service = SimulatorService::default();
+        let id = create(&service).await;
+
+        let error = service
+            .get_report(Request::new(proto::SimulationRequest { id: id.clone() }))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), tonic::Code::FailedPrecondition);
+
+        let report = service
+            .run(Request::new(proto::SimulationRequest { id: id.clone() }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(report.simulation_id, id);
+        assert!(report.completed);
+        assert_eq!(report.final_user_count, 10);
+
+        let fetched = service
+            .get_report(Request::new(proto::SimulationRequest { id }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(fetched, report);
+        assert_eq!(
+            SimulationReport::from_json(&fetched.json).unwrap().trades,
+            fetched.trades
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stream_intervals() {
+        let service = SimulatorService::default();
+        let id = create(&service).await;
+
+        let mut stream = service
+            .stream_intervals(Request::new(proto::SimulationRequest { id: id.clone() }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        let mut reports = vec![];
+        while let Some(report) = next(&mut stream).await {
+            reports.push(report.unwrap());
+        }
+
+        assert_eq!(reports.len(), 4);
+        assert!(reports[..3].iter().all(|report| !report.completed));
+        assert!(reports[3].completed);
+
+        // The simulation is returned to the service once the run finishes
+        let mut report = service
+            .get_report(Request::new(proto::SimulationRequest { id: id.clone() }))
+            .await;
+        while report
+            .as_ref()
+            .is_err_and(|error| error.message() == "simulation is running")
+        {
+            thread::yield_now();
+            report = service
+                .get_report(Request::new(proto::SimulationRequest { id: id.clone() }))
+                .await;
+        }
+        assert_eq!(report.unwrap().into_inner(), reports[3]);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_requests() {
+        let service = SimulatorService::default();
+
+        let mut request = create_request();
+        request.token = "{}".to_string();
+        let error = service
+            .create_simulation(Request::new(request))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), tonic::Code::InvalidArgument);
+
+        let error = service
+            .run(Request::new(proto::SimulationRequest {
+                id: "invalid".to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), tonic::Code::InvalidArgument);
+
+        let error = service
+            .run(Request::new(proto::SimulationRequest {
+                id: Uuid::new_v4().to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), tonic::Code::NotFound);
+    }
+}
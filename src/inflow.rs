@@ -0,0 +1,192 @@
+//! # Inflow module
+//!
+//! This module contains the model of external capital entering the system.
+//!
+//! Without inflows every simulation is a closed system, where value can only leave through sells.
+//! New users buy tokens with fiat when they join, and existing users top up their balance from time to time.
+
+use rand::Rng;
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{population::sample_members, Simulation, SimulationError, UserStore, ValueKind};
+
+/// Model of external capital entering the system each interval.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct CapitalInflow {
+    /// Amount of fiat each new user spends on tokens when joining.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub new_user_purchase: Decimal,
+
+    /// Probability of an existing active user topping up in an interval, between 0 and 1.
+    pub top_up_probability: f64,
+
+    /// Amount of fiat spent on tokens by each top-up.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub top_up_amount: Decimal,
+}
+
+impl Simulation {
+    /// Apply the capital inflow of an interval.
+    /// The last `new_users` users are the ones who joined in the interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `users` - A list of users.
+    /// * `new_users` - Number of users who joined in the interval.
+    /// * `price` - Price of the token in the interval.
+    ///
+    /// # Returns
+    ///
+    /// Capital that entered the system in the interval, in fiat.
+    pub fn apply_capital_inflow(
+        &self,
+        users: &mut UserStore,
+        new_users: u64,
+        price: Decimal,
+    ) -> Result<Decimal, SimulationError> {
+        self.apply_capital_inflow_with_rng(users, new_users, price, &mut rand::rng())
+    }
+
+    /// Apply the capital inflow of an interval, drawing from the given random number generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `users` - A list of users.
+    /// * `new_users` - Number of users who joined in the interval.
+    /// * `price` - Price of the token in the interval.
+    /// * `rng` - Random number generator.
+    ///
+    /// # Returns
+    ///
+    /// Capital that entered the system in the interval, in fiat.
+    pub(crate) fn apply_capital_inflow_with_rng<R: Rng + ?Sized>(
+        &self,
+        users: &mut UserStore,
+        new_users: u64,
+        price: Decimal,
+        rng: &mut R,
+    ) -> Result<Decimal, SimulationError> {
+        let inflow = match &self.options.capital_inflow {
+            Some(inflow) => inflow,
+            None => return Ok(Decimal::default()),
+        };
+
+        if price <= Decimal::default() {
+            return Ok(Decimal::default());
+        }
+
+        #[cfg(feature = "log")]
+        log::debug!("Applying capital inflow for simulation: {}", self.name);
+
+        let top_up_probability = inflow.top_up_probability.clamp(0.0, 1.0);
+        let mut total = Decimal::default();
+
+        // New users are the last ones generated, cohorts at the end may hold several of them
+        let mut first_new_user = users.len();
+        let mut covered = 0;
+        while first_new_user > 0 && covered < new_users {
+            first_new_user -= 1;
+            covered += users.weights[first_new_user];
+        }
+
+        for index in 0..users.len() {
+            let weight = users.weights[index];
+            let amount = if index >= first_new_user {
+                inflow.new_user_purchase * Decimal::from(weight)
+            } else if users.is_active(index) {
+                let members = match weight {
+                    1 => rng.random_bool(top_up_probability) as u64,
+                    weight => sample_members(weight, top_up_probability, rng),
+                };
+                if members == 0 {
+                    continue;
+                }
+
+                inflow.top_up_amount * Decimal::from(members)
+            } else {
+                continue;
+            };
+
+            users.balances[index] += self.options.round(amount / price, ValueKind::Balance);
+            total += amount;
+        }
+
+        Ok(total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FiatAmount, User, ValuationModel};
+
+    use super::*;
+    use uuid::Uuid;
+
+    fn setup(capital_inflow: CapitalInflow) -> Simulation {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .valuation_model(ValuationModel::RandomWalk)
+            .total_users(10)
+            .duration(5)
+            .capital_inflow(capital_inflow)
+            .build()
+            .unwrap();
+
+        Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_apply_capital_inflow() {
+        let simulation = setup(CapitalInflow {
+            new_user_purchase: Decimal::new(100, 0),
+            top_up_probability: 1.0,
+            top_up_amount: Decimal::new(10, 0),
+        });
+        let mut users: UserStore = (0..4)
+            .map(|_| User::new(Uuid::new_v4(), Decimal::ONE))
+            .collect();
+
+        let inflow = simulation
+            .apply_capital_inflow(&mut users, 1, Decimal::TWO)
+            .unwrap();
+
+        assert_eq!(inflow, Decimal::new(130, 0));
+        assert_eq!(users.balances[0], Decimal::new(6, 0));
+        assert_eq!(users.balances[3], Decimal::new(51, 0));
+    }
+
+    #[test]
+    fn test_run_with_capital_inflow() {
+        let mut simulation = setup(CapitalInflow {
+            new_user_purchase: Decimal::default(),
+            top_up_probability: 1.0,
+            top_up_amount: Decimal::new(10, 0),
+        });
+
+        simulation.run().unwrap();
+
+        assert_eq!(
+            simulation.report.capital_inflow,
+            FiatAmount(Decimal::new(500, 0))
+        );
+        assert_eq!(
+            simulation.report.net_inflow,
+            simulation
+                .interval_reports
+                .iter()
+                .map(|report| report.net_inflow)
+                .sum::<FiatAmount>()
+        );
+    }
+}
@@ -0,0 +1,342 @@
+//! # Insurance module
+//!
+//! This module contains the insurance fund, a protocol backstop accumulating a share of the fees.
+//!
+//! Shortfall events, random exploits or scheduled exploits and bad debt, take a share of the insured tokens from the active users.
+//! The fund compensates the users for the shortfall up to its balance, and the rest of the loss is left uncovered.
+//! The coverage ratio is the balance of the fund against the insured tokens, the circulating supply share covered by the fund.
+
+use rand::Rng;
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{SimulationError, UserState, UserStore};
+
+/// Kind of a shortfall event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ShortfallKind {
+    /// Exploit of the protocol.
+    Exploit,
+
+    /// Bad debt, e.g. undercollateralized loans.
+    BadDebt,
+}
+
+/// Shortfall event scheduled in an interval.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct ShortfallEvent {
+    /// Index of the interval of the event.
+    pub interval: u64,
+
+    /// Kind of the event.
+    pub kind: ShortfallKind,
+
+    /// Loss of the event, in percentage of the insured tokens.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub loss: Decimal,
+}
+
+/// Insurance fund of the protocol.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct InsuranceFund {
+    /// Share of the fees paid into the fund, in percentage.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub fee_share: Decimal,
+
+    /// Balance of the fund at the start of the simulation, in tokens.
+    #[cfg_attr(feature = "serde", serde(default, with = "rust_decimal::serde::float"))]
+    pub initial_balance: Decimal,
+
+    /// Insured tokens, in percentage of the circulating supply.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub insured_share: Decimal,
+
+    /// Probability of a random exploit in each interval, between 0 and 1.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub exploit_probability: f64,
+
+    /// Loss of a random exploit, in percentage of the insured tokens.
+    #[cfg_attr(feature = "serde", serde(default, with = "rust_decimal::serde::float"))]
+    pub exploit_loss: Decimal,
+
+    /// Scheduled shortfall events.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub events: Vec<ShortfallEvent>,
+}
+
+/// Activity of the insurance fund in an interval.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct InsuranceActivity {
+    /// Tokens lost in the shortfall events.
+    pub(crate) losses: Decimal,
+
+    /// Losses paid out by the fund.
+    pub(crate) payouts: Decimal,
+}
+
+impl InsuranceFund {
+    /// Create a new insurance fund, without shortfall events.
+    ///
+    /// # Arguments
+    ///
+    /// * `fee_share` - Share of the fees paid into the fund, in percentage.
+    /// * `insured_share` - Insured tokens, in percentage of the circulating supply.
+    ///
+    /// # Returns
+    ///
+    /// New insurance fund.
+    pub fn new(fee_share: Decimal, insured_share: Decimal) -> Self {
+        InsuranceFund {
+            fee_share,
+            initial_balance: Decimal::default(),
+            insured_share,
+            exploit_probability: 0.0,
+            exploit_loss: Decimal::default(),
+            events: vec![],
+        }
+    }
+
+    /// Validate the insurance fund.
+    ///
+    /// # Returns
+    ///
+    /// Result of the validation, or an error if a percentage or the probability is out of range.
+    pub fn validate(&self) -> Result<(), SimulationError> {
+        let percentage = |value: Decimal| (Decimal::ZERO..=Decimal::ONE_HUNDRED).contains(&value);
+
+        if !percentage(self.fee_share)
+            || !percentage(self.insured_share)
+            || !percentage(self.exploit_loss)
+            || self.events.iter().any(|event| !percentage(event.loss))
+        {
+            return Err(SimulationError::InvalidData(
+                "insurance fund: percentages must be between 0 and 100".to_string(),
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.exploit_probability) {
+            return Err(SimulationError::InvalidData(
+                "insurance fund: exploit probability must be between 0 and 1".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Get the insured tokens.
+    ///
+    /// # Arguments
+    ///
+    /// * `circulating_supply` - Circulating supply of the token.
+    ///
+    /// # Returns
+    ///
+    /// Insured tokens.
+    pub fn insured(&self, circulating_supply: Decimal) -> Decimal {
+        circulating_supply * self.insured_share / Decimal::ONE_HUNDRED
+    }
+
+    /// Get the coverage ratio of the fund, its balance against the insured tokens.
+    ///
+    /// # Arguments
+    ///
+    /// * `balance` - Balance of the fund.
+    /// * `circulating_supply` - Circulating supply of the token.
+    ///
+    /// # Returns
+    ///
+    /// Coverage ratio, `None` if no tokens are insured.
+    pub fn coverage_ratio(&self, balance: Decimal, circulating_supply: Decimal) -> Option<Decimal> {
+        balance.checked_div(self.insured(circulating_supply))
+    }
+
+    /// Get the contribution of the fees to the fund.
+    ///
+    /// # Arguments
+    ///
+    /// * `fees` - Fees paid in an interval.
+    ///
+    /// # Returns
+    ///
+    /// Tokens paid into the fund.
+    pub fn fee_contribution(&self, fees: Decimal) -> Decimal {
+        fees * self.fee_share / Decimal::ONE_HUNDRED
+    }
+
+    /// Cover the shortfall events of an interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the interval.
+    /// * `balance` - Balance of the fund, updated in place.
+    /// * `users` - Users of the simulation.
+    /// * `circulating_supply` - Circulating supply of the token.
+    /// * `precision` - Decimal precision of the balances.
+    /// * `rng` - Random number generator.
+    ///
+    /// # Returns
+    ///
+    /// Activity of the fund in the interval.
+    pub(crate) fn cover<R: Rng + ?Sized>(
+        &self,
+        index: u64,
+        balance: &mut Decimal,
+        users: &mut UserStore,
+        circulating_supply: Decimal,
+        precision: u32,
+        rng: &mut R,
+    ) -> InsuranceActivity {
+        let mut activity = InsuranceActivity::default();
+
+        let mut loss: Decimal = self
+            .events
+            .iter()
+            .filter(|event| event.interval == index)
+            .map(|event| event.loss)
+            .sum();
+        if rng.random_bool(self.exploit_probability.clamp(0.0, 1.0)) {
+            loss += self.exploit_loss;
+        }
+        let loss = (self.insured(circulating_supply) * loss.min(Decimal::ONE_HUNDRED)
+            / Decimal::ONE_HUNDRED)
+            .round_dp(precision);
+        let payout = loss.min(*balance);
+        *balance -= payout;
+
+        // The uncovered loss is taken from the active users, by their share of the balances
+        let uncovered = loss - payout;
+        let held: Decimal = (0..users.len())
+            .filter(|&index| users.is_active(index))
+            .map(|index| users.balances[index])
+            .sum();
+        if let Some(rate) = uncovered.checked_div(held) {
+            for (balance, state) in users.balances.iter_mut().zip(&users.states) {
+                if *state == UserState::Active {
+                    let taken = (*balance * rate.min(Decimal::ONE)).round_dp(precision);
+                    *balance -= taken;
+                    activity.losses += taken;
+                }
+            }
+        }
+        activity.losses += payout;
+        activity.payouts = payout;
+
+        activity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+    use uuid::Uuid;
+
+    use crate::{Simulation, TokenAmount, User};
+
+    use super::*;
+
+    #[test]
+    fn test_cover() {
+        let mut users = UserStore::from(vec![User::new(Uuid::new_v4(), Decimal::new(100, 0)); 2]);
+        let fund = InsuranceFund {
+            events: vec![ShortfallEvent {
+                interval: 1,
+                kind: ShortfallKind::Exploit,
+                loss: Decimal::new(50, 0),
+            }],
+            ..InsuranceFund::new(Decimal::new(10, 0), Decimal::new(20, 0))
+        };
+        let mut balance = Decimal::new(5, 0) + fund.fee_contribution(Decimal::new(50, 0));
+        let mut rng = StdRng::seed_from_u64(1);
+
+        // No event in the first interval, the fund keeps 10% of the fees
+        let activity = fund.cover(
+            0,
+            &mut balance,
+            &mut users,
+            Decimal::new(1_000, 0),
+            4,
+            &mut rng,
+        );
+        assert_eq!(activity, InsuranceActivity::default());
+        assert_eq!(balance, Decimal::new(10, 0));
+        assert_eq!(
+            fund.coverage_ratio(balance, Decimal::new(1_000, 0)),
+            Some(Decimal::new(5, 2))
+        );
+
+        // The exploit takes half of the 200 insured tokens, the fund covers 10
+        let activity = fund.cover(
+            1,
+            &mut balance,
+            &mut users,
+            Decimal::new(1_000, 0),
+            4,
+            &mut rng,
+        );
+        assert_eq!(activity.losses, Decimal::new(100, 0));
+        assert_eq!(activity.payouts, Decimal::new(10, 0));
+        assert_eq!(balance, Decimal::ZERO);
+        assert_eq!(users.balances[0], Decimal::new(55, 0));
+    }
+
+    #[test]
+    fn test_validate() {
+        let fund = InsuranceFund::new(Decimal::new(10, 0), Decimal::new(20, 0));
+        assert!(fund.validate().is_ok());
+
+        let fund = InsuranceFund {
+            exploit_probability: 2.0,
+            ..fund
+        };
+        assert!(matches!(
+            fund.validate(),
+            Err(SimulationError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn test_run_with_insurance_fund() {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(20)
+            .duration(4)
+            .seed(8)
+            .transaction_fee_percentage(1.0)
+            .insurance_fund(InsuranceFund {
+                events: vec![ShortfallEvent {
+                    interval: 2,
+                    kind: ShortfallKind::BadDebt,
+                    loss: Decimal::new(1, 0),
+                }],
+                ..InsuranceFund::new(Decimal::new(50, 0), Decimal::new(10, 0))
+            })
+            .build()
+            .unwrap();
+        let mut simulation = Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap();
+
+        simulation.run().unwrap();
+
+        let reports = &simulation.interval_reports;
+        assert!(reports[1].insurance_fund > reports[0].insurance_fund);
+        assert!(reports[1].coverage_ratio.is_some());
+        assert!(reports[2].shortfall_losses > TokenAmount::default());
+        assert_eq!(reports[3].shortfall_losses, TokenAmount::default());
+        assert_eq!(
+            simulation.report.shortfall_losses,
+            reports[2].shortfall_losses
+        );
+        assert_eq!(simulation.report.insurance_fund, reports[3].insurance_fund);
+    }
+}
@@ -0,0 +1,398 @@
+//! # Job module
+//!
+//! This module contains the background job queue used to serve simulations, enabled by the `async` feature.
+//!
+//! Simulations are enqueued and run in the background by a bounded pool of worker threads.
+//! Callers get the ID of the job back immediately, and poll its status and result by ID,
+//! e.g. from the handlers of a web service, without blocking while the simulation runs.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Condvar, Mutex, MutexGuard, PoisonError,
+    },
+    thread::{self, JoinHandle},
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{CancellationToken, Simulation, SimulationError};
+
+/// Status of a job.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum JobStatus {
+    /// Job is waiting for a worker.
+    Queued,
+
+    /// Simulation of the job is running.
+    Running,
+
+    /// Simulation of the job has completed.
+    Completed,
+
+    /// Simulation of the job has failed with the given error.
+    Failed(SimulationError),
+
+    /// Job was cancelled before the simulation completed.
+    Cancelled,
+}
+
+impl JobStatus {
+    /// Check whether the job is finished: completed, failed, or cancelled.
+    ///
+    /// # Returns
+    ///
+    /// Whether the job is finished.
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, JobStatus::Queued | JobStatus::Running)
+    }
+}
+
+/// Job tracked by the queue.
+#[derive(Debug)]
+struct Job {
+    /// Status of the job.
+    status: JobStatus,
+
+    /// Simulation of the job, available once the job is finished.
+    simulation: Option<Simulation>,
+
+    /// Token used to cancel the job.
+    cancellation: CancellationToken,
+}
+
+/// State shared between the queue and its workers.
+#[derive(Debug, Default)]
+struct Shared {
+    /// Jobs by ID.
+    jobs: Mutex<HashMap<Uuid, Job>>,
+
+    /// Notified every time a job finishes.
+    finished: Condvar,
+}
+
+impl Shared {
+    /// Lock the jobs, recovering them if a worker panicked while holding the lock.
+    ///
+    /// # Returns
+    ///
+    /// Guard of the jobs.
+    fn jobs(&self) -> MutexGuard<'_, HashMap<Uuid, Job>> {
+        self.jobs.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Queue running simulations in the background with bounded concurrency.
+/// Dropping the queue waits for the enqueued jobs to finish.
+#[derive(Debug)]
+pub struct JobQueue {
+    /// State shared with the workers.
+    shared: Arc<Shared>,
+
+    /// Sender of the enqueued simulations, dropped to stop the workers.
+    sender: Option<Sender<(Uuid, Simulation)>>,
+
+    /// Worker threads running the simulations.
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl JobQueue {
+    /// Create a new job queue and start its workers.
+    ///
+    /// # Arguments
+    ///
+    /// * `concurrency` - Maximum number of simulations running at the same time, at least 1.
+    ///
+    /// # Returns
+    ///
+    /// New job queue.
+    pub fn new(concurrency: usize) -> Self {
+        let shared = Arc::new(Shared::default());
+        let (sender, receiver) = mpsc::channel();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        let workers = (0..concurrency.max(1))
+            .map(|_| {
+                let shared = Arc::clone(&shared);
+                let receiver = Arc::clone(&receiver);
+
+                thread::spawn(move || work(&shared, &receiver))
+            })
+            .collect();
+
+        JobQueue {
+            shared,
+            sender: Some(sender),
+            workers,
+        }
+    }
+
+    /// Enqueue a simulation.
+    /// The ID of the job is the ID of the simulation.
+    ///
+    /// # Arguments
+    ///
+    /// * `simulation` - Simulation to run.
+    ///
+    /// # Returns
+    ///
+    /// ID of the job.
+    pub fn enqueue(&self, simulation: Simulation) -> Uuid {
+        let id = simulation.id;
+
+        #[cfg(feature = "log")]
+        log::debug!("Enqueuing simulation job: {}", id);
+
+        self.shared.jobs().insert(
+            id,
+            Job {
+                status: JobStatus::Queued,
+                simulation: None,
+                cancellation: CancellationToken::new(),
+            },
+        );
+
+        if let Some(sender) = &self.sender {
+            // Workers only stop once the sender is dropped, so the queue is always open here
+            let _ = sender.send((id, simulation));
+        }
+
+        id
+    }
+
+    /// Get the status of a job.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of the job.
+    ///
+    /// # Returns
+    ///
+    /// Status of the job, or `None` if there is no such job.
+    pub fn status(&self, id: Uuid) -> Option<JobStatus> {
+        self.shared.jobs().get(&id).map(|job| job.status.clone())
+    }
+
+    /// Cancel a job.
+    /// A queued job is cancelled before it starts, a running job stops before its next interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of the job.
+    ///
+    /// # Returns
+    ///
+    /// Whether the job exists and was not finished yet.
+    pub fn cancel(&self, id: Uuid) -> bool {
+        match self.shared.jobs().get(&id) {
+            Some(job) if !job.status.is_finished() => {
+                job.cancellation.cancel();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Block until a job is finished.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of the job.
+    ///
+    /// # Returns
+    ///
+    /// Final status of the job, or `None` if there is no such job.
+    pub fn wait(&self, id: Uuid) -> Option<JobStatus> {
+        let mut jobs = self.shared.jobs();
+
+        loop {
+            match jobs.get(&id) {
+                Some(job) if job.status.is_finished() => return Some(job.status.clone()),
+                Some(_) => {
+                    jobs = self
+                        .shared
+                        .finished
+                        .wait(jobs)
+                        .unwrap_or_else(PoisonError::into_inner);
+                }
+                None => return None,
+            }
+        }
+    }
+
+    /// Take the simulation of a finished job, removing the job from the queue.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of the job.
+    ///
+    /// # Returns
+    ///
+    /// Simulation of the job, or `None` if there is no such job or it is not finished yet.
+    pub fn take(&self, id: Uuid) -> Option<Simulation> {
+        let mut jobs = self.shared.jobs();
+
+        if !jobs.get(&id)?.status.is_finished() {
+            return None;
+        }
+
+        jobs.remove(&id).and_then(|job| job.simulation)
+    }
+
+    /// Get the number of jobs tracked by the queue, finished or not.
+    ///
+    /// # Returns
+    ///
+    /// Number of jobs.
+    pub fn len(&self) -> usize {
+        self.shared.jobs().len()
+    }
+
+    /// Check whether the queue tracks no jobs.
+    ///
+    /// # Returns
+    ///
+    /// Whether the queue is empty.
+    pub fn is_empty(&self) -> bool {
+        self.shared.jobs().is_empty()
+    }
+}
+
+impl Drop for JobQueue {
+    /// Stop the workers once the enqueued jobs are finished.
+    fn drop(&mut self) {
+        self.sender = None;
+
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Run the enqueued simulations until the queue is dropped.
+///
+/// # Arguments
+///
+/// * `shared` - State shared with the queue.
+/// * `receiver` - Receiver of the enqueued simulations.
+fn work(shared: &Shared, receiver: &Mutex<Receiver<(Uuid, Simulation)>>) {
+    loop {
+        let next = receiver
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .recv();
+        let Ok((id, mut simulation)) = next else {
+            break;
+        };
+
+        let cancellation = match shared.jobs().get_mut(&id) {
+            Some(job) => {
+                job.status = JobStatus::Running;
+                job.cancellation.clone()
+            }
+            None => continue,
+        };
+
+        let status = match simulation.run_with_cancellation(&cancellation) {
+            Ok(()) => JobStatus::Completed,
+            Err(SimulationError::Cancelled) => JobStatus::Cancelled,
+            Err(error) => JobStatus::Failed(error),
+        };
+
+        #[cfg(feature = "log")]
+        log::debug!("Simulation job finished: {} {:?}", id, status);
+
+        if let Some(job) = shared.jobs().get_mut(&id) {
+            job.status = status;
+            job.simulation = Some(simulation);
+        }
+        shared.finished.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EthereumFee, SimulationStatus, SimulationTransactionFee};
+
+    fn setup() -> Simulation {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .total_supply(1_000_000)
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(10)
+            .duration(5)
+            .build()
+            .unwrap();
+
+        Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_job_queue() {
+        let queue = JobQueue::new(2);
+        let ids = (0..4).map(|_| queue.enqueue(setup())).collect::<Vec<_>>();
+        assert_eq!(queue.len(), 4);
+
+        for id in ids {
+            assert_eq!(queue.wait(id), Some(JobStatus::Completed));
+
+            let simulation = queue.take(id).unwrap();
+            assert_eq!(simulation.id, id);
+            assert_eq!(simulation.status, SimulationStatus::Completed);
+        }
+
+        assert!(queue.is_empty());
+        assert_eq!(queue.status(Uuid::new_v4()), None);
+        assert_eq!(queue.wait(Uuid::new_v4()), None);
+    }
+
+    #[test]
+    fn test_job_queue_failed() {
+        let queue = JobQueue::new(1);
+        let mut simulation = setup();
+        simulation.options.transaction_fee =
+            Some(SimulationTransactionFee::Ethereum(EthereumFee {
+                gas_used: 21_000,
+                gas_price: None,
+                ether_price: None,
+            }));
+
+        let id = queue.enqueue(simulation);
+
+        assert_eq!(
+            queue.wait(id),
+            Some(JobStatus::Failed(SimulationError::UnresolvedTransactionFee))
+        );
+        assert!(!queue.cancel(id));
+    }
+
+    #[test]
+    fn test_job_queue_cancel() {
+        let queue = JobQueue::new(1);
+        let mut long = setup();
+        long.options.duration = 1_000;
+        long.options.total_users = 1_000;
+
+        let running = queue.enqueue(long);
+        let queued = queue.enqueue(setup());
+
+        assert!(queue.cancel(queued));
+        assert!(queue.cancel(running));
+        assert_eq!(queue.wait(running), Some(JobStatus::Cancelled));
+        assert_eq!(queue.wait(queued), Some(JobStatus::Cancelled));
+        assert!(queue.take(queued).is_some());
+    }
+}
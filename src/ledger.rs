@@ -0,0 +1,270 @@
+//! # Ledger module
+//!
+//! This module contains the supply ledger, the audit trail of the token supply.
+//!
+//! Every supply-changing event of a run (airdrop, unlock, mint, burn) is recorded on the token
+//! with its simulated date, amount, and cause, so the supply numbers of the reports can be audited line by line.
+//! The ledger can be queried after the run, and exported as CSV or, with the `serde` feature, as JSON.
+
+use std::fmt::Write;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Kind of a supply-changing event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum SupplyEventKind {
+    /// New tokens were minted, e.g. by inflation or emissions.
+    Mint,
+
+    /// Tokens were destroyed.
+    Burn,
+
+    /// Locked tokens were released by the unlock schedule.
+    Unlock,
+
+    /// Tokens were airdropped to the users.
+    Airdrop,
+}
+
+impl SupplyEventKind {
+    /// Get the name of the kind, as exported.
+    ///
+    /// # Returns
+    ///
+    /// Snake case name of the kind.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SupplyEventKind::Mint => "mint",
+            SupplyEventKind::Burn => "burn",
+            SupplyEventKind::Unlock => "unlock",
+            SupplyEventKind::Airdrop => "airdrop",
+        }
+    }
+}
+
+/// Supply-changing event.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct SupplyEvent {
+    /// Simulated date of the event.
+    pub date: DateTime<Utc>,
+
+    /// Kind of the event.
+    pub kind: SupplyEventKind,
+
+    /// Amount of tokens, always positive.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub amount: Decimal,
+
+    /// Cause of the event, e.g. `block rewards` or `transaction burn`.
+    pub cause: String,
+}
+
+/// Audit trail of the token supply.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct SupplyLedger {
+    /// Events, in the order they were recorded.
+    pub events: Vec<SupplyEvent>,
+}
+
+impl SupplyLedger {
+    /// Record a supply-changing event.
+    /// Events of a zero amount are not recorded.
+    ///
+    /// # Arguments
+    ///
+    /// * `date` - Simulated date of the event.
+    /// * `kind` - Kind of the event.
+    /// * `amount` - Amount of tokens.
+    /// * `cause` - Cause of the event.
+    pub fn record(
+        &mut self,
+        date: DateTime<Utc>,
+        kind: SupplyEventKind,
+        amount: Decimal,
+        cause: &str,
+    ) {
+        if amount.is_zero() {
+            return;
+        }
+
+        self.events.push(SupplyEvent {
+            date,
+            kind,
+            amount,
+            cause: cause.to_string(),
+        });
+    }
+
+    /// Get the events of the given kind.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - Kind of the events.
+    ///
+    /// # Returns
+    ///
+    /// Events of the kind, in the order they were recorded.
+    pub fn events_of(&self, kind: SupplyEventKind) -> impl Iterator<Item = &SupplyEvent> {
+        self.events.iter().filter(move |event| event.kind == kind)
+    }
+
+    /// Get the events between two dates, inclusive.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - First date.
+    /// * `to` - Last date.
+    ///
+    /// # Returns
+    ///
+    /// Events between the dates, in the order they were recorded.
+    pub fn events_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> impl Iterator<Item = &SupplyEvent> {
+        self.events
+            .iter()
+            .filter(move |event| event.date >= from && event.date <= to)
+    }
+
+    /// Get the total amount of the events of the given kind.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - Kind of the events.
+    ///
+    /// # Returns
+    ///
+    /// Total amount of tokens.
+    pub fn total(&self, kind: SupplyEventKind) -> Decimal {
+        self.events_of(kind).map(|event| event.amount).sum()
+    }
+
+    /// Get the net change of the supply: minted, unlocked, and airdropped tokens, minus burned tokens.
+    ///
+    /// # Returns
+    ///
+    /// Net change of the supply.
+    pub fn net_change(&self) -> Decimal {
+        self.events
+            .iter()
+            .map(|event| match event.kind {
+                SupplyEventKind::Burn => -event.amount,
+                _ => event.amount,
+            })
+            .sum()
+    }
+
+    /// Export the ledger as CSV, one event per line.
+    ///
+    /// # Returns
+    ///
+    /// CSV with a `date,kind,amount,cause` header.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("date,kind,amount,cause\n");
+
+        for event in &self.events {
+            let _ = writeln!(
+                csv,
+                "{},{},{},\"{}\"",
+                event.date.to_rfc3339(),
+                event.kind.name(),
+                event.amount,
+                event.cause.replace('"', "\"\"")
+            );
+        }
+
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ContributorEmission, Simulation, TokenAmount, UnlockEvent};
+
+    use super::*;
+
+    #[test]
+    fn test_ledger() {
+        let date = Utc::now();
+        let mut ledger = SupplyLedger::default();
+
+        ledger.record(
+            date,
+            SupplyEventKind::Airdrop,
+            Decimal::new(100, 0),
+            "airdrop",
+        );
+        ledger.record(
+            date,
+            SupplyEventKind::Burn,
+            Decimal::new(30, 0),
+            "transaction burn",
+        );
+        ledger.record(date, SupplyEventKind::Mint, Decimal::default(), "inflation");
+
+        assert_eq!(ledger.events.len(), 2);
+        assert_eq!(ledger.total(SupplyEventKind::Burn), Decimal::new(30, 0));
+        assert_eq!(ledger.net_change(), Decimal::new(70, 0));
+        assert_eq!(ledger.events_between(date, date).count(), 2);
+        assert_eq!(
+            ledger.to_csv().lines().nth(2).unwrap(),
+            format!("{},burn,30,\"transaction burn\"", date.to_rfc3339())
+        );
+    }
+
+    #[test]
+    fn test_run_records_supply_events() {
+        let mut token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .airdrop_percentage(5.0)
+            .burn_rate(1.0)
+            .contributor_emission(ContributorEmission {
+                amount_per_interval: Decimal::new(1_000, 0),
+                sell_through: Decimal::default(),
+            })
+            .build()
+            .unwrap();
+        token.add_unlock_event(Utc::now(), TokenAmount(Decimal::new(500, 0)));
+        let options = Simulation::options_builder()
+            .total_users(20)
+            .duration(3)
+            .build()
+            .unwrap();
+        let mut simulation = Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap();
+
+        simulation.run().unwrap();
+
+        let ledger = &simulation.token.ledger;
+        assert_eq!(
+            ledger.total(SupplyEventKind::Airdrop),
+            Decimal::new(50_000, 0)
+        );
+        assert_eq!(ledger.total(SupplyEventKind::Unlock), Decimal::new(500, 0));
+        assert_eq!(ledger.total(SupplyEventKind::Mint), Decimal::new(3_000, 0));
+        assert_eq!(
+            ledger.total(SupplyEventKind::Burn),
+            simulation.report.total_burned.value()
+        );
+        assert!(ledger
+            .events_of(SupplyEventKind::Mint)
+            .all(|event| event.cause == "contributor emission"));
+        assert_eq!(
+            simulation.token.unlock_schedule,
+            Some(Vec::<UnlockEvent>::new())
+        );
+    }
+}
@@ -114,8 +114,80 @@
 //!
 //! We have a [contributing guide](https://github.com/slavik-pastushenko/tokenomics-simulator-rs/blob/main/CONTRIBUTING.md) to help you get involved in the project.
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+/// Activity module.
+/// Is used to count rolling active users.
+pub(crate) mod activity;
+
+/// Amount module.
+/// Is used to type the amounts in tokens, fiat, gwei, and percentages so they cannot be mixed.
+pub mod amount;
+
+/// Analytics module.
+/// Is used to import query results exported from analytics platforms, such as Dune or Flipside.
+pub mod analytics;
+
+/// Async runner module.
+/// Is used to run simulations on an async runtime without blocking it.
+#[cfg(feature = "async")]
+pub mod async_runner;
+
+/// Bench module.
+/// Is used to build benchmark workloads and estimate the cost of a run.
+pub mod bench;
+
+/// Calibration module.
+/// Is used to fit the adoption rate and the user lifecycle to historical active users.
+pub mod calibration;
+
+/// Cancellation module.
+/// Is used to cancel running simulations.
+pub mod cancellation;
+
+/// CoinGecko module.
+/// Is used to fetch the price history of a token from the CoinGecko API.
+#[cfg(feature = "network")]
+pub mod coingecko;
+
+/// Comparison module.
+/// Is used to compare the outcomes of two simulations side by side.
+pub mod comparison;
+
+/// Competition module.
+/// Is used to model a rival token draining the users of the token.
+pub mod competition;
+
+/// Compliance module.
+/// Is used to enforce a cap on the share of the supply held by a single holder.
+pub mod compliance;
+
+/// Context module.
+/// Is used to share the state of an interval with the extension points.
+pub mod context;
+
+/// Controller module.
+/// Is used to adjust the supply with a PID loop towards a target price.
+pub mod controller;
+
+/// Counterfactual module.
+/// Is used to measure the contribution of a mechanism by rerunning a simulation without it.
+pub mod counterfactual;
+
+/// Demand module.
+/// Is used to inject an exogenous multiplier of the trading demand of each interval.
+pub mod demand;
+
+/// Distribution module.
+/// Is used to analyze the token distribution among the users.
+pub mod distribution;
+
+/// Dual token module.
+/// Is used to run a utility token and a governance token together.
+pub mod dual_token;
+
 /// Engine module.
 /// Is used to run the simulation with the desired configuration.
 pub mod engine;
@@ -128,10 +200,211 @@ pub mod engine_builder;
 /// Is used to create a new engine configuration.
 pub mod engine_config;
 
+/// Engine trait module.
+/// Is used to swap the engine running the steps of the intervals.
+pub mod engine_trait;
+
+/// Ensemble module.
+/// Is used to summarize and compare many Monte Carlo runs.
+pub mod ensemble;
+
+/// Etherscan module.
+/// Is used to fetch a snapshot of the holders of an ERC-20 token from the Etherscan API.
+#[cfg(feature = "network")]
+pub mod etherscan;
+
+/// Event module.
+/// Is used to publish the simulation events to message brokers.
+#[cfg(feature = "serde")]
+pub mod event;
+
+/// Fee module.
+/// Is used to model the network fees paid on each transaction.
+pub mod fee;
+
+/// Fee switch module.
+/// Is used to model a protocol fee switch and evaluate the timing of its activation.
+pub mod fee_switch;
+
+/// Float option module.
+/// Is used to serialize the optional decimals of the reports as floats.
+#[cfg(feature = "serde")]
+pub(crate) mod float_option;
+
+/// Format module.
+/// Is used to format the numbers of rendered reports for people.
+pub mod format;
+
+/// Fuzz module.
+/// Is used to run randomized scenarios and report failures.
+pub mod fuzz;
+
+/// Gaming module.
+/// Is used to model the faucets and sinks of a play-to-earn game economy.
+pub mod gaming;
+
+/// Gauge module.
+/// Is used to direct the emission to pools by the votes of the vote-escrow holders.
+pub mod gauge;
+
+/// gRPC module.
+/// Is used to serve the simulator over gRPC.
+#[cfg(feature = "grpc")]
+pub mod grpc;
+
+/// Inflow module.
+/// Is used to model external capital entering the system.
+pub mod inflow;
+
+/// Insurance module.
+/// Is used to model a protocol insurance fund covering shortfall events.
+pub mod insurance;
+
+/// Job module.
+/// Is used to run simulations in the background and poll their results.
+#[cfg(feature = "async")]
+pub mod job;
+
+/// Ledger module.
+/// Is used to audit the supply-changing events of a run.
+pub mod ledger;
+
+/// Market module.
+/// Is used to select how trades are matched and priced.
+pub mod market;
+
+/// MEV module.
+/// Is used to model value extracted from users by sandwich attacks.
+pub mod mev;
+
+/// Network effects module.
+/// Is used to couple the adoption of the token to its price and network activity.
+pub mod network_effects;
+
+/// NFT module.
+/// Is used to model the tokens spent by the users on an NFT side market.
+pub mod nft;
+
+/// Observer module.
+/// Is used to hook into the simulation events.
+pub mod observer;
+
+/// Overrides module.
+/// Is used to override scenario values from environment variables or command-line flags.
+#[cfg(feature = "serde")]
+pub mod overrides;
+
+/// Pipeline module.
+/// Is used to run the phases of a token launch sequentially over the same evolving state.
+pub mod pipeline;
+
+/// Plugin module.
+/// Is used to add custom metrics to the reports.
+pub mod plugin;
+
+/// Population module.
+/// Is used to represent the users as individual agents or weighted cohorts.
+pub mod population;
+
+/// Price feed module.
+/// Is used to replay historical prices in a simulation.
+pub mod price_feed;
+
+/// Projection module.
+/// Is used to project the circulating supply without running trades.
+pub mod projection;
+
+/// Random module.
+/// Is used to set the source of the random numbers drawn by the runs.
+pub mod random;
+
+/// Rates module.
+/// Is used to layer a series of risk-free interest rates on the simulation.
+pub mod rates;
+
+/// Regime module.
+/// Is used to configure the market regime presets: bull, bear, and crab markets.
+pub mod regime;
+
+/// Replay module.
+/// Is used to record the trades of a run and replay them against modified parameters.
+pub mod replay;
+
 /// Report module.
 /// Is used to generate reports.
 pub mod report;
 
+/// Retention module.
+/// Is used to track the retention of user cohorts over time.
+pub mod retention;
+
+/// ROI module.
+/// Is used to report the return on investment of the holder cohorts.
+pub mod roi;
+
+/// Rounding module.
+/// Is used to round the values of the simulation consistently.
+pub mod rounding;
+
+/// Runner module.
+/// Is used to run many simulations concurrently.
+pub mod runner;
+
+/// Runway module.
+/// Is used to analyse the treasury runway under a spending plan.
+pub mod runway;
+
+/// Schema module.
+/// Is used to version the serialization format and migrate older data.
+pub mod schema;
+
+/// Sentiment module.
+/// Is used to feed the momentum of the price back into the behaviour of the users.
+pub mod sentiment;
+
+/// Statistics module.
+/// Is used to summarize simulation outcomes.
+pub(crate) mod stats;
+
+/// Stepper module.
+/// Is used to run a simulation one interval at a time.
+pub mod stepper;
+
+/// Strategy module.
+/// Is used to generate valid core types in property tests.
+#[cfg(feature = "proptest")]
+pub mod strategy;
+
+/// Stream module.
+/// Is used to stream the reports of a running simulation.
+#[cfg(feature = "async")]
+pub mod stream;
+
+/// Stress test module.
+/// Is used to check named scenarios against pass/fail thresholds.
+pub mod stress;
+
+/// Subscription module.
+/// Is used to model the recurring token payments of the users.
+pub mod subscription;
+
+/// Summary module.
+/// Is used to digest the headline outcomes of a simulation.
+pub mod summary;
+
+/// Sweep module.
+/// Is used to run a scenario for every combination of parameter values.
+pub mod sweep;
+
+/// Tax module.
+/// Is used to model the tax of the users and export their tax records.
+pub mod tax;
+
+/// Test utilities module.
+/// Is used to detect behavioral changes of the engine with golden files.
+#[cfg(feature = "test_utils")]
+pub mod test_utils;
+
 /// Token module.
 /// Is used to apply token related operations for the simulation.
 pub mod token;
@@ -140,21 +413,124 @@ pub mod token;
 /// Is used to create a new token with the desired configuration.
 pub mod token_builder;
 
+/// Trade module.
+/// Is used to describe the trades made during the simulation.
+pub mod trade;
+
+/// Units module.
+/// Is used to represent token amounts in smallest units, beyond the precision of the simulation.
+pub mod units;
+
 /// User module.
 /// Is used to apply user related operations for the simulation.
 pub mod user;
 
+/// User store module.
+/// Is used to store the users as contiguous columns for the trade loop.
+pub mod user_store;
+
+/// Utility module.
+/// Is used to model the tokens spent or locked by the users to use the product.
+pub mod utility;
+
+/// Venue module.
+/// Is used to split trading volume between exchange venues.
+pub mod venue;
+
+/// Vesting module.
+/// Is used to build the unlock events of an allocation from a vesting schedule.
+pub mod vesting;
+
+/// Webhook module.
+/// Is used to notify external systems when a simulation completes or fails.
+pub mod webhook;
+
+pub use amount::*;
+pub use analytics::*;
+pub use bench::*;
+pub use calibration::*;
+pub use cancellation::*;
+#[cfg(feature = "network")]
+pub use coingecko::*;
+pub use comparison::*;
+pub use competition::*;
+pub use compliance::*;
+pub use context::*;
+pub use controller::*;
+pub use counterfactual::*;
+pub use demand::*;
+pub use distribution::*;
+pub use dual_token::*;
 pub use engine::*;
 pub use engine_builder::*;
 pub use engine_config::*;
+pub use engine_trait::*;
+pub use ensemble::*;
+#[cfg(feature = "network")]
+pub use etherscan::*;
+#[cfg(feature = "serde")]
+pub use event::*;
+pub use fee::*;
+pub use fee_switch::*;
+pub use format::*;
+pub use fuzz::*;
+pub use gaming::*;
+pub use gauge::*;
+#[cfg(feature = "grpc")]
+pub use grpc::*;
+pub use inflow::*;
+pub use insurance::*;
+#[cfg(feature = "async")]
+pub use job::*;
+pub use ledger::*;
+pub use market::*;
+pub use mev::*;
+pub use network_effects::*;
+pub use nft::*;
+pub use observer::*;
+#[cfg(feature = "serde")]
+pub use overrides::*;
+pub use pipeline::*;
+pub use plugin::*;
+pub use population::*;
+pub use price_feed::*;
+pub use projection::*;
+pub use random::*;
+pub use rates::*;
+pub use regime::*;
+pub use replay::*;
 pub use report::*;
+pub use retention::*;
+pub use roi::*;
+pub use rounding::*;
+pub use runway::*;
+pub use schema::*;
+pub use sentiment::*;
+pub use stepper::*;
+#[cfg(feature = "async")]
+pub use stream::*;
+pub use stress::*;
+pub use subscription::*;
+pub use summary::*;
+pub use sweep::*;
+pub use tax::*;
+#[cfg(feature = "test_utils")]
+pub use test_utils::*;
 pub use token::*;
 pub use token_builder::*;
+pub use trade::*;
+pub use units::*;
 pub use user::*;
+pub use user_store::*;
+pub use utility::*;
+pub use venue::*;
+pub use vesting::*;
+pub use webhook::*;
 
 /// Simulation error.
 /// A list of possible errors that can occur during the simulation.
-#[derive(Debug, Error, PartialEq, Eq)]
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum SimulationError {
     /// Missing required field: name.
     #[error("Missing required field: name.")]
@@ -175,4 +551,92 @@ pub enum SimulationError {
     /// Invalid decimal value.
     #[error("Invalid decimal value.")]
     InvalidDecimal,
+
+    /// Invalid number of runs.
+    #[error("Invalid number of runs.")]
+    InvalidRuns,
+
+    /// Transaction fee depends on live data that was not resolved before the run.
+    #[error("Transaction fee is not resolved.")]
+    UnresolvedTransactionFee,
+
+    /// Serialized data is written in a newer schema version than supported.
+    #[error("Unsupported schema version: {0}.")]
+    UnsupportedSchemaVersion(u32),
+
+    /// Serialized data is invalid.
+    #[error("Invalid serialized data: {0}.")]
+    InvalidData(String),
+
+    /// Simulation was cancelled before it completed.
+    #[error("Simulation was cancelled.")]
+    Cancelled,
+
+    /// Webhook could not be notified.
+    #[error("Webhook notification failed: {0}.")]
+    WebhookFailed(String),
+
+    /// Event could not be published.
+    #[error("Event publishing failed: {0}.")]
+    EventPublishFailed(String),
+
+    /// Vesting schedule is invalid.
+    #[error("Invalid vesting schedule: {0}.")]
+    InvalidVestingSchedule(String),
+
+    /// Allocations of the total supply are invalid.
+    #[error("Invalid allocation: {0}.")]
+    InvalidAllocation(String),
+
+    /// Token amount cannot be represented.
+    #[error("Unrepresentable amount: {0}.")]
+    UnrepresentableAmount(String),
+
+    /// Metric name is not known.
+    #[error("Unknown metric: {0}.")]
+    UnknownMetric(String),
+
+    /// Parameter sweep is invalid or cannot write its results.
+    #[error("Invalid sweep: {0}.")]
+    InvalidSweep(String),
+
+    /// Override of a scenario value is invalid.
+    #[error("Invalid override: {0}.")]
+    InvalidOverride(String),
+
+    /// Simulation cannot be rerun with the same outcome, e.g. it has no seed.
+    #[error("Not reproducible: {0}.")]
+    NotReproducible(String),
+
+    /// Historical data used for a calibration is invalid.
+    #[error("Invalid history: {0}.")]
+    InvalidHistory(String),
+
+    /// Export of an analytics platform cannot be imported.
+    #[error("Invalid import: {0}.")]
+    InvalidImport(String),
+
+    /// External data could not be fetched.
+    #[error("Fetch failed: {0}.")]
+    FetchFailed(String),
+
+    /// Parameters are rejected by the strict mode of a builder.
+    #[error("Rejected by strict mode: {0}.")]
+    StrictViolation(String),
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serde_round_trip() {
+        let error = SimulationError::MissingTotalUsers;
+
+        let json = serde_json::to_string(&error).unwrap();
+        assert_eq!(
+            serde_json::from_str::<SimulationError>(&json).unwrap(),
+            error
+        );
+    }
 }
@@ -0,0 +1,449 @@
+//! # Market module
+//!
+//! This module contains the market models used to execute trades.
+//!
+//! By default, trades are executed against pooled liquidity, as on an AMM.
+//! The order book model matches market orders against resting limit orders instead,
+//! for tokens that mostly trade on centralized exchanges.
+
+use std::collections::{BTreeMap, VecDeque};
+
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::TradeSide;
+
+/// Market model used to execute trades.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum MarketModel {
+    /// Trades are executed against pooled liquidity.
+    /// The price follows the valuation model or the market volatility.
+    #[default]
+    Amm,
+
+    /// Trades are matched in a limit order book.
+    /// The price is discovered by the book: it is the mid price at the end of each interval.
+    OrderBook(OrderBookConfig),
+}
+
+/// Configuration of the limit order book.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct OrderBookConfig {
+    /// Minimum price increment between two price levels.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub tick_size: Decimal,
+
+    /// Number of price levels quoted by market makers on each side of the book.
+    pub levels: u32,
+
+    /// Amount of tokens quoted by market makers at each price level.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub level_size: Decimal,
+
+    /// Probability that a user posts a limit order instead of a market order, between 0 and 1.
+    pub limit_order_probability: f64,
+}
+
+/// Limit order resting in the book.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Order {
+    /// ID of the user who placed the order.
+    /// Orders quoted by market makers have a nil ID.
+    pub user_id: Uuid,
+
+    /// Direction of the order.
+    pub side: TradeSide,
+
+    /// Limit price of the order.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub price: Decimal,
+
+    /// Amount of tokens left to fill.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub amount: Decimal,
+}
+
+/// Fill of a resting order.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Fill {
+    /// ID of the user whose resting order was filled.
+    pub maker_id: Uuid,
+
+    /// Direction of the resting order.
+    pub side: TradeSide,
+
+    /// Price of the fill.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub price: Decimal,
+
+    /// Amount of tokens filled.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub amount: Decimal,
+}
+
+/// Result of matching an order against the book.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Execution {
+    /// Amount of tokens filled.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub amount: Decimal,
+
+    /// Average price of the fills.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub average_price: Decimal,
+
+    /// Resting orders filled by the execution.
+    pub fills: Vec<Fill>,
+}
+
+impl Execution {
+    /// Calculate the slippage of the execution.
+    ///
+    /// # Arguments
+    ///
+    /// * `reference_price` - Price before the execution, usually the mid price.
+    ///
+    /// # Returns
+    ///
+    /// Slippage of the average price from the reference price, in percentage.
+    pub fn slippage(&self, reference_price: Decimal) -> Decimal {
+        ((self.average_price - reference_price).abs() * Decimal::new(100, 0))
+            .checked_div(reference_price)
+            .unwrap_or_default()
+    }
+}
+
+/// Limit order book with price-time priority.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderBook {
+    /// Minimum price increment between two price levels.
+    tick_size: Decimal,
+
+    /// Resting buy orders, per price level.
+    bids: BTreeMap<Decimal, VecDeque<Order>>,
+
+    /// Resting sell orders, per price level.
+    asks: BTreeMap<Decimal, VecDeque<Order>>,
+
+    /// Price of the last fill.
+    last_price: Decimal,
+}
+
+impl OrderBook {
+    /// Create a new order book quoted by market makers around a price.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Configuration of the order book.
+    /// * `price` - Reference price of the token.
+    ///
+    /// # Returns
+    ///
+    /// New order book.
+    pub fn new(config: &OrderBookConfig, price: Decimal) -> Self {
+        let mut book = OrderBook {
+            tick_size: config.tick_size,
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+            last_price: price,
+        };
+
+        for level in 1..=config.levels {
+            let offset = config.tick_size * Decimal::from(level);
+
+            if price - offset > Decimal::default() {
+                book.place(Order {
+                    user_id: Uuid::nil(),
+                    side: TradeSide::Buy,
+                    price: price - offset,
+                    amount: config.level_size,
+                });
+            }
+
+            book.place(Order {
+                user_id: Uuid::nil(),
+                side: TradeSide::Sell,
+                price: price + offset,
+                amount: config.level_size,
+            });
+        }
+
+        book
+    }
+
+    /// Get the minimum price increment between two price levels.
+    ///
+    /// # Returns
+    ///
+    /// Tick size of the book.
+    pub fn tick_size(&self) -> Decimal {
+        self.tick_size
+    }
+
+    /// Get the highest price of the resting buy orders.
+    ///
+    /// # Returns
+    ///
+    /// Best bid, if any.
+    pub fn best_bid(&self) -> Option<Decimal> {
+        self.bids.keys().next_back().copied()
+    }
+
+    /// Get the lowest price of the resting sell orders.
+    ///
+    /// # Returns
+    ///
+    /// Best ask, if any.
+    pub fn best_ask(&self) -> Option<Decimal> {
+        self.asks.keys().next().copied()
+    }
+
+    /// Get the mid price of the book.
+    /// Falls back to the last fill price when a side of the book is empty.
+    ///
+    /// # Returns
+    ///
+    /// Mid price of the book.
+    pub fn mid_price(&self) -> Decimal {
+        match (self.best_bid(), self.best_ask()) {
+            (Some(bid), Some(ask)) => (bid + ask) / Decimal::TWO,
+            _ => self.last_price,
+        }
+    }
+
+    /// Place a limit order.
+    /// The order is matched against the opposite side up to its limit price, and the remainder rests in the book.
+    ///
+    /// # Arguments
+    ///
+    /// * `order` - Limit order to place.
+    ///
+    /// # Returns
+    ///
+    /// Execution of the crossing part of the order.
+    pub fn place(&mut self, mut order: Order) -> Execution {
+        let execution = self.match_order(order.side, order.amount, Some(order.price));
+        order.amount -= execution.amount;
+
+        if order.amount > Decimal::default() {
+            let side = match order.side {
+                TradeSide::Buy => &mut self.bids,
+                TradeSide::Sell => &mut self.asks,
+            };
+            side.entry(order.price).or_default().push_back(order);
+        }
+
+        execution
+    }
+
+    /// Execute a market order against the book.
+    ///
+    /// # Arguments
+    ///
+    /// * `side` - Direction of the order.
+    /// * `amount` - Amount of tokens to trade.
+    ///
+    /// # Returns
+    ///
+    /// Execution of the order. The filled amount is lower than requested when the book runs out of orders.
+    pub fn execute(&mut self, side: TradeSide, amount: Decimal) -> Execution {
+        self.match_order(side, amount, None)
+    }
+
+    /// Quote a market order without executing it.
+    ///
+    /// # Arguments
+    ///
+    /// * `side` - Direction of the order.
+    /// * `amount` - Amount of tokens to trade.
+    ///
+    /// # Returns
+    ///
+    /// Execution the order would have, without the fills.
+    pub fn quote(&self, side: TradeSide, amount: Decimal) -> Execution {
+        let levels: Box<dyn Iterator<Item = (&Decimal, &VecDeque<Order>)>> = match side {
+            TradeSide::Buy => Box::new(self.asks.iter()),
+            TradeSide::Sell => Box::new(self.bids.iter().rev()),
+        };
+
+        let mut filled = Decimal::default();
+        let mut cost = Decimal::default();
+        for (price, orders) in levels {
+            if filled >= amount {
+                break;
+            }
+
+            let available: Decimal = orders.iter().map(|order| order.amount).sum();
+            let take = available.min(amount - filled);
+            filled += take;
+            cost += take * price;
+        }
+
+        Execution {
+            amount: filled,
+            average_price: cost.checked_div(filled).unwrap_or_default(),
+            fills: vec![],
+        }
+    }
+
+    /// Match an order against the opposite side of the book.
+    ///
+    /// # Arguments
+    ///
+    /// * `side` - Direction of the order.
+    /// * `amount` - Amount of tokens to trade.
+    /// * `limit` - Limit price of the order, if any.
+    ///
+    /// # Returns
+    ///
+    /// Execution of the order.
+    fn match_order(
+        &mut self,
+        side: TradeSide,
+        amount: Decimal,
+        limit: Option<Decimal>,
+    ) -> Execution {
+        let mut execution = Execution::default();
+        let mut cost = Decimal::default();
+
+        while execution.amount < amount {
+            let best = match side {
+                TradeSide::Buy => self.asks.first_entry(),
+                TradeSide::Sell => self.bids.last_entry(),
+            };
+            let Some(mut level) = best else {
+                break;
+            };
+
+            let price = *level.key();
+            let crosses = match (side, limit) {
+                (_, None) => true,
+                (TradeSide::Buy, Some(limit)) => price <= limit,
+                (TradeSide::Sell, Some(limit)) => price >= limit,
+            };
+            if !crosses {
+                break;
+            }
+
+            let orders = level.get_mut();
+            while let Some(resting) = orders.front_mut() {
+                let take = resting.amount.min(amount - execution.amount);
+                resting.amount -= take;
+                execution.amount += take;
+                cost += take * price;
+                execution.fills.push(Fill {
+                    maker_id: resting.user_id,
+                    side: resting.side,
+                    price,
+                    amount: take,
+                });
+
+                if resting.amount.is_zero() {
+                    orders.pop_front();
+                }
+                if execution.amount >= amount {
+                    break;
+                }
+            }
+
+            if orders.is_empty() {
+                level.remove();
+            }
+            self.last_price = price;
+        }
+
+        execution.average_price = cost.checked_div(execution.amount).unwrap_or_default();
+
+        execution
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> OrderBookConfig {
+        OrderBookConfig {
+            tick_size: Decimal::new(1, 2),
+            levels: 3,
+            level_size: Decimal::new(100, 0),
+            limit_order_probability: 0.5,
+        }
+    }
+
+    #[test]
+    fn test_new_order_book() {
+        let book = OrderBook::new(&config(), Decimal::ONE);
+
+        assert_eq!(book.best_bid(), Some(Decimal::new(99, 2)));
+        assert_eq!(book.best_ask(), Some(Decimal::new(101, 2)));
+        assert_eq!(book.mid_price(), Decimal::ONE);
+        assert_eq!(book.tick_size(), Decimal::new(1, 2));
+    }
+
+    #[test]
+    fn test_execute_market_order() {
+        let mut book = OrderBook::new(&config(), Decimal::ONE);
+
+        let quote = book.quote(TradeSide::Buy, Decimal::new(150, 0));
+        let execution = book.execute(TradeSide::Buy, Decimal::new(150, 0));
+
+        assert_eq!(execution.amount, Decimal::new(150, 0));
+        assert_eq!(execution.average_price, quote.average_price);
+        assert_eq!(execution.fills.len(), 2);
+        assert_eq!(book.best_ask(), Some(Decimal::new(102, 2)));
+        assert!(execution.slippage(Decimal::ONE) > Decimal::ONE);
+
+        // The book runs out of sell orders
+        let execution = book.execute(TradeSide::Buy, Decimal::new(1_000, 0));
+        assert_eq!(execution.amount, Decimal::new(150, 0));
+        assert_eq!(book.best_ask(), None);
+        assert_eq!(book.mid_price(), Decimal::new(103, 2));
+    }
+
+    #[test]
+    fn test_place_limit_order() {
+        let mut book = OrderBook::new(&config(), Decimal::ONE);
+        let user_id = Uuid::new_v4();
+
+        // A passive order rests in the book
+        let execution = book.place(Order {
+            user_id,
+            side: TradeSide::Sell,
+            price: Decimal::new(1005, 3),
+            amount: Decimal::new(10, 0),
+        });
+        assert_eq!(execution.amount, Decimal::default());
+        assert_eq!(book.best_ask(), Some(Decimal::new(1005, 3)));
+
+        let execution = book.execute(TradeSide::Buy, Decimal::new(5, 0));
+        assert_eq!(execution.fills[0].maker_id, user_id);
+
+        // A crossing order is matched up to its limit price, and the remainder rests
+        let execution = book.place(Order {
+            user_id,
+            side: TradeSide::Buy,
+            price: Decimal::new(101, 2),
+            amount: Decimal::new(200, 0),
+        });
+        assert_eq!(execution.amount, Decimal::new(105, 0));
+        assert_eq!(book.best_bid(), Some(Decimal::new(101, 2)));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let model = MarketModel::OrderBook(config());
+
+        let json = serde_json::to_string(&model).unwrap();
+        assert_eq!(serde_json::from_str::<MarketModel>(&json).unwrap(), model);
+    }
+}
@@ -0,0 +1,127 @@
+//! # MEV module
+//!
+//! This module contains the MEV agent that sandwiches trades on the AMM.
+//!
+//! The agent front-runs large trades and back-runs them once they are executed,
+//! extracting the price movement from the user. The value it can extract is bounded by the slippage the user accepts,
+//! and by the configured maximum extraction.
+
+use rand::Rng;
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// MEV agent sandwiching large trades.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct MevAgent {
+    /// Minimum amount of tokens traded for a trade to be targeted.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub min_trade_amount: Decimal,
+
+    /// Probability that a targeted trade is sandwiched, between 0 and 1.
+    pub capture_probability: f64,
+
+    /// Maximum value extracted from a trade, in percentage of the amount traded.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub max_extraction_percentage: Decimal,
+
+    /// Probability that a sandwiched user leaves the market for good, between 0 and 1.
+    pub victim_churn_probability: f64,
+}
+
+impl MevAgent {
+    /// Sandwich a trade, if it is targeted.
+    /// The agent pushes the price up to the slippage tolerance of the user, or to the maximum extraction without a tolerance.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - Amount of tokens traded.
+    /// * `slippage` - Slippage of the trade without the sandwich, in percentage.
+    /// * `tolerance` - Maximum slippage the user accepts, in percentage.
+    /// * `rng` - Random number generator.
+    ///
+    /// # Returns
+    ///
+    /// Amount of tokens extracted from the trade.
+    pub fn extract<R: Rng + ?Sized>(
+        &self,
+        amount: Decimal,
+        slippage: Decimal,
+        tolerance: Option<Decimal>,
+        rng: &mut R,
+    ) -> Decimal {
+        if amount < self.min_trade_amount || !rng.random_bool(self.capture_probability) {
+            return Decimal::default();
+        }
+
+        let room = match tolerance {
+            Some(tolerance) => (tolerance - slippage).min(self.max_extraction_percentage),
+            None => self.max_extraction_percentage,
+        };
+
+        amount * room.max(Decimal::default()) / Decimal::new(100, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agent() -> MevAgent {
+        MevAgent {
+            min_trade_amount: Decimal::new(100, 0),
+            capture_probability: 1.0,
+            max_extraction_percentage: Decimal::new(2, 0),
+            victim_churn_probability: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_extract() {
+        let mut rng = rand::rng();
+        let agent = agent();
+        let amount = Decimal::new(1_000, 0);
+
+        assert_eq!(
+            agent.extract(amount, Decimal::default(), None, &mut rng),
+            Decimal::new(20, 0)
+        );
+        assert_eq!(
+            agent.extract(amount, Decimal::ONE, Some(Decimal::new(15, 1)), &mut rng),
+            Decimal::new(5, 0)
+        );
+        assert_eq!(
+            agent.extract(amount, Decimal::TWO, Some(Decimal::ONE), &mut rng),
+            Decimal::default()
+        );
+    }
+
+    #[test]
+    fn test_extract_skips_small_trades() {
+        let mut rng = rand::rng();
+
+        assert_eq!(
+            agent().extract(Decimal::new(99, 0), Decimal::default(), None, &mut rng),
+            Decimal::default()
+        );
+
+        let agent = MevAgent {
+            capture_probability: 0.0,
+            ..agent()
+        };
+        assert_eq!(
+            agent.extract(Decimal::new(1_000, 0), Decimal::default(), None, &mut rng),
+            Decimal::default()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let agent = agent();
+
+        let json = serde_json::to_string(&agent).unwrap();
+        assert_eq!(serde_json::from_str::<MevAgent>(&json).unwrap(), agent);
+    }
+}
@@ -0,0 +1,186 @@
+//! # Network effects module
+//!
+//! This module contains the network effects, coupling the adoption of the token to its own performance.
+//!
+//! Without them, the users grow at the exogenous adoption rate. With them, the growth of each interval is scaled
+//! by the momentum of the price and of the network activity over a window of past intervals, each weighted by a coupling strength.
+//! Rising prices and busy networks attract new users, who trade and raise the activity further,
+//! while falling prices and quiet networks slow the growth down, down to no new users at all.
+
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{sentiment::momentum, SimulationError};
+
+/// Coupling of the adoption to the recent price performance and network activity.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct NetworkEffects {
+    /// Number of past intervals the momentum is measured over.
+    pub window: u64,
+
+    /// Elasticity of the adoption growth to the price momentum, in percentage.
+    /// At 100%, a price up 10% over the window grows the adoption 10% faster.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub price_coupling: Decimal,
+
+    /// Elasticity of the adoption growth to the activity momentum, in percentage.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub activity_coupling: Decimal,
+}
+
+impl NetworkEffects {
+    /// Create new network effects.
+    ///
+    /// # Arguments
+    ///
+    /// * `window` - Number of past intervals the momentum is measured over.
+    /// * `price_coupling` - Elasticity of the growth to the price momentum, in percentage.
+    /// * `activity_coupling` - Elasticity of the growth to the activity momentum, in percentage.
+    ///
+    /// # Returns
+    ///
+    /// New network effects.
+    pub fn new(window: u64, price_coupling: Decimal, activity_coupling: Decimal) -> Self {
+        NetworkEffects {
+            window,
+            price_coupling,
+            activity_coupling,
+        }
+    }
+
+    /// Validate the network effects.
+    ///
+    /// # Returns
+    ///
+    /// Result of the validation, or an error if the window is empty or a coupling is negative.
+    pub fn validate(&self) -> Result<(), SimulationError> {
+        if self.window == 0 {
+            return Err(SimulationError::InvalidData(
+                "network effects: window must be at least one interval".to_string(),
+            ));
+        }
+        if self.price_coupling < Decimal::ZERO || self.activity_coupling < Decimal::ZERO {
+            return Err(SimulationError::InvalidData(
+                "network effects: couplings must not be negative".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Get the multiplier of the adoption growth in an interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `price` - Price of the token at the start of the interval.
+    /// * `past_prices` - Prices of the past intervals, oldest first.
+    /// * `activity` - Network activity of the past intervals, oldest first.
+    ///
+    /// # Returns
+    ///
+    /// Multiplier of the adoption growth, 1 without momentum, never negative.
+    pub fn multiplier(
+        &self,
+        price: Decimal,
+        past_prices: &[Decimal],
+        activity: &[Decimal],
+    ) -> Decimal {
+        // The last activity is compared to the activity of the window before it
+        let activity_momentum = match activity.split_last() {
+            Some((current, past)) => momentum(*current, past, self.window),
+            None => Decimal::ZERO,
+        };
+        let price_momentum = momentum(price, past_prices, self.window);
+
+        (Decimal::ONE
+            + (price_momentum * self.price_coupling + activity_momentum * self.activity_coupling)
+                / Decimal::ONE_HUNDRED
+                / Decimal::ONE_HUNDRED)
+            .max(Decimal::ZERO)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Simulation;
+
+    use super::*;
+
+    #[test]
+    fn test_multiplier() {
+        let effects = NetworkEffects::new(1, Decimal::new(100, 0), Decimal::new(50, 0));
+        let prices = [Decimal::ONE, Decimal::new(2, 0)];
+        let activity = [Decimal::new(10, 0), Decimal::new(20, 0)];
+
+        assert_eq!(effects.multiplier(Decimal::ONE, &[], &[]), Decimal::ONE);
+        // A flat price and a doubled activity grow the adoption by half
+        assert_eq!(
+            effects.multiplier(Decimal::new(2, 0), &prices, &activity),
+            Decimal::new(15, 1)
+        );
+        // A halved price halves the growth
+        assert_eq!(
+            effects.multiplier(Decimal::ONE, &prices, &activity[..1]),
+            Decimal::new(5, 1)
+        );
+        assert_eq!(
+            effects.multiplier(Decimal::new(5, 1), &prices, &[]),
+            Decimal::new(25, 2)
+        );
+        assert!(NetworkEffects::new(1, -Decimal::ONE, Decimal::ONE)
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_run_with_network_effects() {
+        let run = |effects: Option<NetworkEffects>| {
+            let token = Simulation::token_builder()
+                .name("Test Token".to_string())
+                .build()
+                .unwrap();
+            let mut options = Simulation::options_builder()
+                .total_users(50)
+                .duration(5)
+                .seed(9)
+                .adoption_rate(0.1)
+                .build()
+                .unwrap();
+            options.network_effects = effects;
+            let mut simulation = Simulation::builder()
+                .name("Test Simulation".to_string())
+                .token(token)
+                .options(options)
+                .build()
+                .unwrap();
+
+            simulation.run().unwrap();
+            simulation
+        };
+
+        let usual = run(None);
+        let simulation = run(Some(NetworkEffects::new(
+            2,
+            Decimal::new(200, 0),
+            Decimal::new(100, 0),
+        )));
+        let reports = &simulation.interval_reports;
+
+        // Without a past interval, the first interval grows at the exogenous rate
+        assert_eq!(reports[0].adoption_multiplier, Some(Decimal::ONE));
+        assert_eq!(
+            reports[0].successful_trades,
+            usual.interval_reports[0].successful_trades
+        );
+        assert!(reports
+            .iter()
+            .all(|report| report.adoption_multiplier >= Some(Decimal::ZERO)));
+        assert_eq!(
+            simulation.report.adoption_multiplier,
+            reports[4].adoption_multiplier
+        );
+        assert_eq!(usual.report.adoption_multiplier, None);
+    }
+}
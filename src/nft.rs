@@ -0,0 +1,325 @@
+//! # NFT module
+//!
+//! This module contains the NFT side market, where the users spend tokens on NFT mints and resales.
+//!
+//! In each interval, active users mint NFTs at a fiat price paid in tokens, and the mint proceeds are burned or sent to the treasury.
+//! Users also buy NFTs from each other: the seller receives the price, minus a royalty paid to the treasury or to a creator.
+//! Users only mint or buy the NFTs they can afford.
+
+use rand::Rng;
+use rust_decimal::{prelude::*, Decimal};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{SimulationError, SinkDestination, UserStore};
+
+/// Recipient of the royalties of the NFT resales.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum RoyaltyRecipient {
+    /// Royalties are sent to the treasury of the project.
+    #[default]
+    Treasury,
+
+    /// Royalties are paid to a creator, a random active user, and stay in circulation.
+    Creators,
+}
+
+/// NFT side market of the token ecosystem.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct NftMarket {
+    /// Price of a mint, in fiat.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub mint_price: Decimal,
+
+    /// Expected mints of an active user per interval.
+    pub mints_per_user: f64,
+
+    /// Destination of the mint proceeds.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub mint_destination: SinkDestination,
+
+    /// Price of a resale, in fiat.
+    #[cfg_attr(feature = "serde", serde(default, with = "rust_decimal::serde::float"))]
+    pub resale_price: Decimal,
+
+    /// Expected resales bought by an active user per interval.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub resales_per_user: f64,
+
+    /// Royalty of each resale, in percentage of the resale price.
+    #[cfg_attr(feature = "serde", serde(default, with = "rust_decimal::serde::float"))]
+    pub royalty: Decimal,
+
+    /// Recipient of the royalties.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub royalty_recipient: RoyaltyRecipient,
+}
+
+/// Activity of the NFT market in an interval.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct NftActivity {
+    /// Number of mints.
+    pub(crate) mints: u64,
+
+    /// Tokens spent on mints.
+    pub(crate) mint_volume: Decimal,
+
+    /// Tokens spent on resales.
+    pub(crate) resale_volume: Decimal,
+
+    /// Royalties paid, in tokens.
+    pub(crate) royalties: Decimal,
+}
+
+impl NftMarket {
+    /// Create a new NFT market with mints only, burning the proceeds.
+    ///
+    /// # Arguments
+    ///
+    /// * `mint_price` - Price of a mint, in fiat.
+    /// * `mints_per_user` - Expected mints of an active user per interval.
+    ///
+    /// # Returns
+    ///
+    /// New NFT market.
+    pub fn new(mint_price: Decimal, mints_per_user: f64) -> Self {
+        NftMarket {
+            mint_price,
+            mints_per_user,
+            mint_destination: SinkDestination::default(),
+            resale_price: Decimal::default(),
+            resales_per_user: 0.0,
+            royalty: Decimal::default(),
+            royalty_recipient: RoyaltyRecipient::default(),
+        }
+    }
+
+    /// Validate the NFT market.
+    ///
+    /// # Returns
+    ///
+    /// Result of the validation, or an error if a price, a rate or the royalty is out of range.
+    pub fn validate(&self) -> Result<(), SimulationError> {
+        let invalid = |reason: &str| {
+            Err(SimulationError::InvalidData(format!(
+                "nft market: {reason}"
+            )))
+        };
+
+        if self.mint_price.is_sign_negative() || self.resale_price.is_sign_negative() {
+            return invalid("prices must be non-negative");
+        }
+        if !(self.mints_per_user >= 0.0 && self.resales_per_user >= 0.0) {
+            return invalid("mints and resales per user must be non-negative");
+        }
+        if self.royalty.is_sign_negative() || self.royalty > Decimal::ONE_HUNDRED {
+            return invalid("royalty must be between 0 and 100");
+        }
+
+        Ok(())
+    }
+
+    /// Trade the NFTs of an interval: mints first, then resales between the users.
+    ///
+    /// # Arguments
+    ///
+    /// * `users` - Users of the simulation.
+    /// * `price` - Price of the token in the interval.
+    /// * `precision` - Decimal precision of the balances.
+    /// * `rng` - Random number generator.
+    ///
+    /// # Returns
+    ///
+    /// Activity of the interval.
+    pub(crate) fn trade<R: Rng + ?Sized>(
+        &self,
+        users: &mut UserStore,
+        price: Decimal,
+        precision: u32,
+        rng: &mut R,
+    ) -> NftActivity {
+        let mut activity = NftActivity::default();
+        if price <= Decimal::ZERO {
+            return activity;
+        }
+
+        let active: Vec<usize> = (0..users.len())
+            .filter(|&position| users.is_active(position))
+            .collect();
+        let mint_price = (self.mint_price / price).round_dp(precision);
+        let resale_price = (self.resale_price / price).round_dp(precision);
+        let royalty = (resale_price * self.royalty / Decimal::ONE_HUNDRED).round_dp(precision);
+
+        for &position in &active {
+            let weight = users.weights[position];
+            let mints = Self::sample(
+                weight,
+                users.balances[position],
+                self.mints_per_user,
+                mint_price,
+                rng,
+            );
+            let amount = mint_price * Decimal::from(mints);
+            users.balances[position] -= amount;
+            activity.mints += mints;
+            activity.mint_volume += amount;
+
+            // A resale needs a seller other than the buyer
+            if active.len() < 2 {
+                continue;
+            }
+            let resales = Self::sample(
+                weight,
+                users.balances[position],
+                self.resales_per_user,
+                resale_price,
+                rng,
+            );
+            for _ in 0..resales {
+                let seller = loop {
+                    let seller = active[rng.random_range(0..active.len())];
+                    if seller != position {
+                        break seller;
+                    }
+                };
+                users.balances[position] -= resale_price;
+                users.balances[seller] += resale_price - royalty;
+                activity.resale_volume += resale_price;
+                activity.royalties += royalty;
+
+                // Royalties sent to the treasury leave the balances of the users
+                if self.royalty_recipient == RoyaltyRecipient::Creators {
+                    users.balances[active[rng.random_range(0..active.len())]] += royalty;
+                }
+            }
+        }
+
+        activity
+    }
+
+    /// Sample the NFTs a user buys in an interval, the fractional one with its probability.
+    ///
+    /// # Arguments
+    ///
+    /// * `weight` - Number of users represented by the buyer.
+    /// * `balance` - Balance of the buyer.
+    /// * `rate` - Expected NFTs bought per member of the user.
+    /// * `unit` - Price of an NFT, in tokens.
+    /// * `rng` - Random number generator.
+    ///
+    /// # Returns
+    ///
+    /// Number of NFTs bought, capped at the affordable ones.
+    fn sample<R: Rng + ?Sized>(
+        weight: u64,
+        balance: Decimal,
+        rate: f64,
+        unit: Decimal,
+        rng: &mut R,
+    ) -> u64 {
+        if unit <= Decimal::ZERO || rate <= 0.0 {
+            return 0;
+        }
+
+        let expected = rate * weight as f64;
+        let mut count = expected.trunc() as u64;
+        if rng.random_bool(expected.fract()) {
+            count += 1;
+        }
+        let affordable = (balance / unit).floor().to_u64().unwrap_or(u64::MAX);
+
+        count.min(affordable)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+    use uuid::Uuid;
+
+    use crate::{Simulation, TokenAmount, User, ValuationModel};
+
+    use super::*;
+
+    #[test]
+    fn test_trade() {
+        let mut users = UserStore::from(vec![User::new(Uuid::new_v4(), Decimal::new(10, 0)); 2]);
+        users.balances[1] = Decimal::new(1, 1);
+        let market = NftMarket {
+            resale_price: Decimal::new(1, 0),
+            resales_per_user: 1.0,
+            royalty: Decimal::new(10, 0),
+            ..NftMarket::new(Decimal::new(2, 0), 1.0)
+        };
+        let mut rng = StdRng::seed_from_u64(1);
+
+        // A mint costs 4 tokens and a resale 2 tokens at a price of 0.5, the second user can afford neither
+        let activity = market.trade(&mut users, Decimal::new(5, 1), 4, &mut rng);
+        assert_eq!(activity.mints, 1);
+        assert_eq!(activity.mint_volume, Decimal::new(4, 0));
+        assert_eq!(activity.resale_volume, Decimal::new(2, 0));
+        assert_eq!(activity.royalties, Decimal::new(2, 1));
+        assert_eq!(users.balances[0], Decimal::new(4, 0));
+        assert_eq!(users.balances[1], Decimal::new(19, 1));
+    }
+
+    #[test]
+    fn test_validate() {
+        let market = NftMarket::new(Decimal::new(2, 0), 1.0);
+        assert!(market.validate().is_ok());
+
+        let market = NftMarket {
+            royalty: Decimal::new(101, 0),
+            ..market
+        };
+        assert!(matches!(
+            market.validate(),
+            Err(SimulationError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn test_run_with_nft_market() {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .valuation_model(ValuationModel::RandomWalk)
+            .total_users(20)
+            .duration(3)
+            .seed(4)
+            .nft_market(NftMarket {
+                resale_price: Decimal::new(1, 2),
+                resales_per_user: 0.5,
+                royalty: Decimal::new(5, 0),
+                royalty_recipient: RoyaltyRecipient::Creators,
+                ..NftMarket::new(Decimal::new(1, 2), 0.5)
+            })
+            .build()
+            .unwrap();
+        let mut simulation = Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap();
+
+        simulation.run().unwrap();
+
+        let mints: u64 = simulation
+            .interval_reports
+            .iter()
+            .map(|report| report.nft_mints)
+            .sum();
+        assert!(mints > 0);
+        assert_eq!(simulation.report.nft_mints, mints);
+        assert!(simulation.report.nft_volume > TokenAmount::default());
+        assert!(simulation.report.nft_royalties > TokenAmount::default());
+        // The mint proceeds are burned
+        assert!(simulation.report.total_burned > TokenAmount::default());
+    }
+}
@@ -0,0 +1,150 @@
+//! # Observer module
+//!
+//! This module contains the observer trait, which allows integrators to hook into the simulation.
+//!
+//! Observers are notified about the start and the outcome of the run, every trade, and every completed interval,
+//! so custom accounting (e.g. royalties or taxes) can be implemented without reimplementing the engine.
+
+use std::{fmt::Debug, sync::Arc};
+
+use crate::{IntervalContext, Simulation, SimulationError, SimulationReport, Trade, User};
+
+/// Observer of the simulation events.
+/// All methods have empty default implementations, so only the relevant ones need to be implemented.
+///
+/// Observers receive shared references, use interior mutability (e.g. atomics or a mutex) to record state.
+pub trait SimulationObserver: Debug + Send + Sync {
+    /// Called before the first interval of a run.
+    ///
+    /// # Arguments
+    ///
+    /// * `simulation` - The simulation being run.
+    fn on_start(&self, _simulation: &Simulation) {}
+
+    /// Called after a user made a trade.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The user who made the trade, with the balance already updated.
+    /// * `trade` - The trade and its side effects.
+    fn on_trade(&self, _user: &User, _trade: &Trade) {}
+
+    /// Called after an interval has been processed.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - The context of the interval.
+    /// * `report` - The report of the interval.
+    fn on_interval(&self, _context: &IntervalContext, _report: &SimulationReport) {}
+
+    /// Called after the simulation has completed.
+    ///
+    /// # Arguments
+    ///
+    /// * `report` - The final report of the simulation.
+    fn on_complete(&self, _report: &SimulationReport) {}
+
+    /// Called after the simulation has failed or was cancelled.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The error of the run.
+    fn on_fail(&self, _error: &SimulationError) {}
+}
+
+/// Shared observers, so the caller can keep a handle to inspect the recorded state after the run.
+impl<T: SimulationObserver + ?Sized> SimulationObserver for Arc<T> {
+    fn on_start(&self, simulation: &Simulation) {
+        (**self).on_start(simulation);
+    }
+
+    fn on_trade(&self, user: &User, trade: &Trade) {
+        (**self).on_trade(user, trade);
+    }
+
+    fn on_interval(&self, context: &IntervalContext, report: &SimulationReport) {
+        (**self).on_interval(context, report);
+    }
+
+    fn on_complete(&self, report: &SimulationReport) {
+        (**self).on_complete(report);
+    }
+
+    fn on_fail(&self, error: &SimulationError) {
+        (**self).on_fail(error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    };
+
+    use rust_decimal::Decimal;
+
+    use crate::Simulation;
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct RoyaltyObserver {
+        started: AtomicU64,
+        trades: AtomicU64,
+        intervals: AtomicU64,
+        completed: AtomicU64,
+        royalties: Mutex<Decimal>,
+    }
+
+    impl SimulationObserver for RoyaltyObserver {
+        fn on_start(&self, _simulation: &Simulation) {
+            self.started.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn on_trade(&self, _user: &User, trade: &Trade) {
+            self.trades.fetch_add(1, Ordering::Relaxed);
+            *self.royalties.lock().unwrap() += trade.amount * Decimal::new(1, 2);
+        }
+
+        fn on_interval(&self, _context: &IntervalContext, _report: &SimulationReport) {
+            self.intervals.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn on_complete(&self, _report: &SimulationReport) {
+            self.completed.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn test_observer_receives_events() {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(10)
+            .duration(3)
+            .build()
+            .unwrap();
+        let mut simulation = Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap();
+
+        let observer = Arc::new(RoyaltyObserver::default());
+        simulation.add_observer(observer.clone());
+        simulation.run().unwrap();
+
+        assert_eq!(observer.started.load(Ordering::Relaxed), 1);
+        assert_eq!(observer.intervals.load(Ordering::Relaxed), 3);
+        assert_eq!(observer.completed.load(Ordering::Relaxed), 1);
+        assert_eq!(
+            observer.trades.load(Ordering::Relaxed),
+            simulation.report.successful_trades
+        );
+        assert!(*observer.royalties.lock().unwrap() > Decimal::default());
+    }
+}
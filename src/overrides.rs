@@ -0,0 +1,232 @@
+//! # Overrides module
+//!
+//! This module contains the overrides of scenario values, e.g. from environment variables or command-line flags,
+//! so pipelines can vary parameters without templating scenario files.
+//!
+//! An override sets the value at a dotted path of the serialized simulation, e.g. `options.total_users=5000`.
+//! Overrides are applied in order, so a later override of the same path wins. The documented precedence is:
+//! scenario file, then environment variables, then command-line flags.
+
+use std::str::FromStr;
+
+use serde_json::Value;
+
+use crate::{Simulation, SimulationError};
+
+/// Prefix of the environment variables read as overrides, e.g. `TOKENOMICS_OPTIONS__TOTAL_USERS=5000`.
+pub const ENV_PREFIX: &str = "TOKENOMICS_";
+
+/// Override of one value of a scenario.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigOverride {
+    /// Dotted path of the value, e.g. `options.total_users`.
+    pub path: String,
+
+    /// New value.
+    pub value: Value,
+}
+
+impl ConfigOverride {
+    /// Create a new override.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Dotted path of the value.
+    /// * `value` - New value, parsed as JSON, or taken as a string if it is not valid JSON.
+    ///
+    /// # Returns
+    ///
+    /// New override.
+    pub fn new(path: &str, value: &str) -> Self {
+        ConfigOverride {
+            path: path.trim().to_string(),
+            value: serde_json::from_str(value.trim())
+                .unwrap_or_else(|_| Value::String(value.to_string())),
+        }
+    }
+
+    /// Read the overrides from environment variables with the `TOKENOMICS_` prefix.
+    /// The rest of the name is lowercased, with a double underscore separating the segments of the path,
+    /// e.g. `TOKENOMICS_OPTIONS__TOTAL_USERS` overrides `options.total_users`.
+    ///
+    /// # Arguments
+    ///
+    /// * `vars` - Environment variables, e.g. `std::env::vars()`.
+    ///
+    /// # Returns
+    ///
+    /// Overrides, sorted by path so the order does not depend on the environment.
+    pub fn from_env<I: IntoIterator<Item = (String, String)>>(vars: I) -> Vec<ConfigOverride> {
+        let mut overrides: Vec<_> = vars
+            .into_iter()
+            .filter_map(|(name, value)| {
+                let path = name
+                    .strip_prefix(ENV_PREFIX)?
+                    .to_lowercase()
+                    .replace("__", ".");
+                Some(ConfigOverride::new(&path, &value))
+            })
+            .collect();
+        overrides.sort_by(|a, b| a.path.cmp(&b.path));
+
+        overrides
+    }
+
+    /// Set the value of the override in a serialized simulation.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - Serialized simulation.
+    ///
+    /// # Returns
+    ///
+    /// Error if the path does not name an existing field, to catch typos.
+    pub fn apply(&self, target: &mut Value) -> Result<(), SimulationError> {
+        let unknown = || SimulationError::InvalidOverride(format!("unknown path {}", self.path));
+
+        let mut slot = target;
+        for segment in self.path.split('.') {
+            // An optional section that is not set yet, e.g. `options.fee_model`, is created with any field
+            let created = slot.is_null();
+            if created {
+                *slot = Value::Object(Default::default());
+            }
+            slot = match slot {
+                Value::Object(fields) if created || fields.contains_key(segment) => {
+                    fields.entry(segment).or_insert(Value::Null)
+                }
+                Value::Array(items) => segment
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| items.get_mut(index))
+                    .ok_or_else(unknown)?,
+                _ => return Err(unknown()),
+            };
+        }
+        *slot = self.value.clone();
+
+        Ok(())
+    }
+}
+
+impl FromStr for ConfigOverride {
+    type Err = SimulationError;
+
+    /// Parse an override written as `path=value`, e.g. `options.total_users=5000`.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.split_once('=') {
+            Some((path, value)) if !path.trim().is_empty() => Ok(ConfigOverride::new(path, value)),
+            _ => Err(SimulationError::InvalidOverride(format!(
+                "{value}: expected path=value"
+            ))),
+        }
+    }
+}
+
+impl Simulation {
+    /// Create a copy of the simulation with overridden values.
+    ///
+    /// # Arguments
+    ///
+    /// * `overrides` - Overrides, applied in order.
+    ///
+    /// # Returns
+    ///
+    /// Copy of the simulation, or an error if a path does not exist or a value is invalid for its field.
+    pub fn with_overrides(
+        &self,
+        overrides: &[ConfigOverride],
+    ) -> Result<Simulation, SimulationError> {
+        let invalid = |err: serde_json::Error| SimulationError::InvalidOverride(err.to_string());
+        let mut value = serde_json::to_value(self).map_err(invalid)?;
+
+        for config_override in overrides {
+            config_override.apply(&mut value)?;
+        }
+
+        serde_json::from_value(value).map_err(invalid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> Simulation {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(10)
+            .build()
+            .unwrap();
+
+        Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_override() {
+        let config_override: ConfigOverride = "options.total_users=5000".parse().unwrap();
+        assert_eq!(config_override.path, "options.total_users");
+        assert_eq!(config_override.value, Value::from(5000));
+        assert_eq!(
+            "name=Launch".parse::<ConfigOverride>().unwrap().value,
+            Value::from("Launch")
+        );
+        assert!(matches!(
+            "=5000".parse::<ConfigOverride>(),
+            Err(SimulationError::InvalidOverride(_))
+        ));
+
+        let overrides = ConfigOverride::from_env([
+            (
+                "TOKENOMICS_TOKEN__BURN_RATE".to_string(),
+                "0.01".to_string(),
+            ),
+            ("PATH".to_string(), "/usr/bin".to_string()),
+            (
+                "TOKENOMICS_OPTIONS__TOTAL_USERS".to_string(),
+                "20".to_string(),
+            ),
+        ]);
+        assert_eq!(overrides.len(), 2);
+        assert_eq!(overrides[0].path, "options.total_users");
+        assert_eq!(overrides[1].path, "token.burn_rate");
+    }
+
+    #[test]
+    fn test_with_overrides() {
+        let simulation = setup();
+
+        // The flag comes after the environment variable, so it wins
+        let overridden = simulation
+            .with_overrides(&[
+                ConfigOverride::new("options.total_users", "20"),
+                ConfigOverride::new("options.total_users", "5000"),
+                ConfigOverride::new("token.burn_rate", "0.01"),
+            ])
+            .unwrap();
+
+        assert_eq!(overridden.options.total_users, 5000);
+        assert_eq!(
+            overridden.token.burn_rate,
+            Some(rust_decimal::Decimal::new(1, 2))
+        );
+        assert_eq!(overridden.token.name, simulation.token.name);
+
+        assert!(matches!(
+            simulation.with_overrides(&[ConfigOverride::new("options.total_user", "20")]),
+            Err(SimulationError::InvalidOverride(_))
+        ));
+        assert!(matches!(
+            simulation.with_overrides(&[ConfigOverride::new("options.total_users", "many")]),
+            Err(SimulationError::InvalidOverride(_))
+        ));
+    }
+}
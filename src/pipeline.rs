@@ -0,0 +1,315 @@
+//! # Pipeline module
+//!
+//! This module contains the simulation pipeline, which runs ordered phases of a token launch over the same evolving state,
+//! e.g. a private sale, the token generation event, an incentive program and a steady state.
+//!
+//! Each phase has its own input parameters, overrides of the token and duration.
+//! A phase continues from the final state of the previous one: the price, the circulating supply and the balances of the users.
+//! The pipeline reports each phase on its own, and all the phases combined.
+
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Percentage, Simulation, SimulationError, SimulationOptions, SimulationReport, SimulationStatus,
+    Token, UnlockEvent, UserStore,
+};
+
+/// Overrides of the token at the start of a phase.
+/// Values that are not set are carried over from the previous phase.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct TokenOverrides {
+    /// Annual percentage increase in supply.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, with = "rust_decimal::serde::float_option")
+    )]
+    pub inflation_rate: Option<Decimal>,
+
+    /// Percentage of tokens burned during each transaction.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, with = "rust_decimal::serde::float_option")
+    )]
+    pub burn_rate: Option<Decimal>,
+
+    /// Airdrop at the start of the phase, in percentage of total supply.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub airdrop_percentage: Option<Percentage>,
+
+    /// Tax charged on each buy, in percentage of the trade amount.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub buy_tax: Option<Percentage>,
+
+    /// Tax charged on each sell, in percentage of the trade amount.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub sell_tax: Option<Percentage>,
+
+    /// Unlock schedule of the phase.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub unlock_schedule: Option<Vec<UnlockEvent>>,
+}
+
+/// Phase of a simulation pipeline.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct PipelinePhase {
+    /// Name of the phase, e.g. `TGE`.
+    pub name: String,
+
+    /// Input parameters of the phase.
+    /// The number of users is taken from the previous phase, except in the first one.
+    pub options: SimulationOptions,
+
+    /// Overrides of the token at the start of the phase.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub token_overrides: TokenOverrides,
+
+    /// Duration of the phase, in intervals of the phase.
+    /// Overrides the duration of the input parameters.
+    pub duration: u64,
+}
+
+/// Ordered phases run sequentially over the same evolving state.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct SimulationPipeline {
+    /// Name of the pipeline.
+    pub name: String,
+
+    /// Token at the start of the first phase.
+    pub token: Token,
+
+    /// Phases of the pipeline, in order.
+    pub phases: Vec<PipelinePhase>,
+}
+
+/// Results of a simulation pipeline.
+#[derive(Debug)]
+pub struct PipelineRun {
+    /// Completed simulation of each phase, in order.
+    pub phases: Vec<Simulation>,
+
+    /// Interval reports of all the phases, in order.
+    pub interval_reports: Vec<SimulationReport>,
+
+    /// Final report over all the phases.
+    pub report: SimulationReport,
+}
+
+impl TokenOverrides {
+    /// Apply the overrides to a token.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - Token to override.
+    pub fn apply(&self, token: &mut Token) {
+        token.inflation_rate = self.inflation_rate.or(token.inflation_rate);
+        token.burn_rate = self.burn_rate.or(token.burn_rate);
+        token.airdrop_percentage = self.airdrop_percentage.or(token.airdrop_percentage);
+        token.buy_tax = self.buy_tax.or(token.buy_tax);
+        token.sell_tax = self.sell_tax.or(token.sell_tax);
+        if let Some(unlock_schedule) = &self.unlock_schedule {
+            token.unlock_schedule = Some(unlock_schedule.clone());
+        }
+    }
+}
+
+impl SimulationPipeline {
+    /// Create a new simulation pipeline, without phases.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the pipeline.
+    /// * `token` - Token at the start of the first phase.
+    ///
+    /// # Returns
+    ///
+    /// New simulation pipeline.
+    pub fn new(name: &str, token: Token) -> Self {
+        SimulationPipeline {
+            name: name.to_string(),
+            token,
+            phases: vec![],
+        }
+    }
+
+    /// Add a phase at the end of the pipeline.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the phase.
+    /// * `options` - Input parameters of the phase.
+    /// * `token_overrides` - Overrides of the token at the start of the phase.
+    /// * `duration` - Duration of the phase, in intervals.
+    ///
+    /// # Returns
+    ///
+    /// The simulation pipeline.
+    pub fn phase(
+        mut self,
+        name: &str,
+        options: SimulationOptions,
+        token_overrides: TokenOverrides,
+        duration: u64,
+    ) -> Self {
+        self.phases.push(PipelinePhase {
+            name: name.to_string(),
+            options,
+            token_overrides,
+            duration,
+        });
+        self
+    }
+
+    /// Run the phases in order, each continuing from the final state of the previous one.
+    ///
+    /// # Returns
+    ///
+    /// Results of the pipeline, or an error if the pipeline has no phases or a phase fails.
+    pub fn run(&self) -> Result<PipelineRun, SimulationError> {
+        if self.phases.is_empty() {
+            return Err(SimulationError::InvalidData(
+                "pipeline has no phases".to_string(),
+            ));
+        }
+
+        let mut phases: Vec<Simulation> = Vec::with_capacity(self.phases.len());
+        for phase in &self.phases {
+            let mut options = phase.options.clone();
+            options.duration = phase.duration;
+            // The next phase continues from the users of the final report
+            options.report_fields.users = true;
+
+            let builder = Simulation::builder()
+                .name(format!("{} - {}", self.name, phase.name))
+                .options(options);
+            let mut simulation = match phases.last() {
+                Some(previous) => builder.continue_from(previous)?,
+                None => builder.token(self.token.clone()),
+            }
+            .map_token(|token| phase.token_overrides.apply(token))
+            .build()?;
+
+            simulation.run()?;
+            phases.push(simulation);
+        }
+
+        // The combined report is generated over the interval reports of all the phases
+        let last = &phases[phases.len() - 1];
+        let interval_reports: Vec<SimulationReport> = phases
+            .iter()
+            .flat_map(|phase| phase.interval_reports.clone())
+            .collect();
+        let mut combined = Simulation::builder()
+            .name(self.name.clone())
+            .token(last.token.clone())
+            .options(SimulationOptions {
+                duration: interval_reports.len() as u64,
+                ..last.options.clone()
+            })
+            .build()?;
+        combined.interval_reports = interval_reports;
+        combined.generate_final_report(UserStore::from(
+            last.report.users.clone().unwrap_or_default(),
+        ));
+        combined.update_status(SimulationStatus::Completed);
+
+        Ok(PipelineRun {
+            phases,
+            interval_reports: combined.interval_reports,
+            report: combined.report,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pipeline() -> SimulationPipeline {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = |adoption_rate: f64| {
+            Simulation::options_builder()
+                .total_users(20)
+                .adoption_rate(adoption_rate)
+                .seed(1)
+                .build()
+                .unwrap()
+        };
+
+        SimulationPipeline::new("Launch", token)
+            .phase("Private sale", options(0.0), TokenOverrides::default(), 2)
+            .phase(
+                "TGE",
+                options(0.2),
+                TokenOverrides {
+                    airdrop_percentage: Some(Percentage(Decimal::new(5, 0))),
+                    sell_tax: Some(Percentage(Decimal::new(2, 0))),
+                    ..Default::default()
+                },
+                3,
+            )
+            .phase("Steady state", options(0.05), TokenOverrides::default(), 4)
+    }
+
+    #[test]
+    fn test_run_pipeline() {
+        let run = pipeline().run().unwrap();
+
+        assert_eq!(run.phases.len(), 3);
+        assert_eq!(run.phases[1].interval_reports.len(), 3);
+        assert_eq!(run.interval_reports.len(), 9);
+        assert!(run
+            .phases
+            .iter()
+            .all(|phase| phase.status == SimulationStatus::Completed));
+
+        // Each phase starts from the final state of the previous one
+        for pair in run.phases.windows(2) {
+            let users = pair[0].report.users.as_ref().unwrap();
+            assert_eq!(pair[1].options.total_users, users.len() as u64);
+            assert_eq!(
+                pair[1].initial_token.as_ref().unwrap().initial_price,
+                pair[0].interval_reports.last().unwrap().token_price
+            );
+        }
+
+        // The overrides apply from their phase on, the airdrop only once
+        assert_eq!(
+            run.phases[1].token.sell_tax,
+            Some(Percentage(Decimal::new(2, 0)))
+        );
+        assert_eq!(
+            run.phases[2].token.sell_tax,
+            Some(Percentage(Decimal::new(2, 0)))
+        );
+        assert!(run.phases[1].token.airdrop_percentage.is_some());
+        assert_eq!(run.phases[2].token.airdrop_percentage, None);
+
+        let trades: u64 = run.phases.iter().map(|phase| phase.report.trades).sum();
+        assert_eq!(run.report.trades, trades);
+        assert_eq!(
+            run.report.final_user_count,
+            run.phases[2].report.final_user_count
+        );
+    }
+
+    #[test]
+    fn test_run_pipeline_without_phases() {
+        let pipeline = SimulationPipeline {
+            phases: vec![],
+            ..pipeline()
+        };
+
+        assert!(matches!(
+            pipeline.run(),
+            Err(SimulationError::InvalidData(_))
+        ));
+    }
+}
@@ -0,0 +1,110 @@
+//! # Plugin module
+//!
+//! This module contains the metric plugin trait, which allows integrators to add custom metrics to the reports.
+//!
+//! Registered plugins are computed at the end of every interval and for the final report,
+//! and the values are stored by name in the `custom_metrics` of the report.
+
+use std::{fmt::Debug, sync::Arc};
+
+use rust_decimal::Decimal;
+
+use crate::{IntervalContext, SimulationReport, UserStore};
+
+/// Custom metric computed for every report.
+pub trait MetricPlugin: Debug + Send + Sync {
+    /// Name of the metric, used as the key in the custom metrics of the report.
+    ///
+    /// # Returns
+    ///
+    /// Name of the metric.
+    fn name(&self) -> &str;
+
+    /// Compute the metric.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - Context of the interval, or of the end of the simulation.
+    /// * `users` - Users at the end of the interval or simulation.
+    /// * `report` - Report of the interval or simulation, with the built-in metrics already set.
+    ///
+    /// # Returns
+    ///
+    /// Value of the metric.
+    fn compute(
+        &self,
+        context: &IntervalContext,
+        users: &UserStore,
+        report: &SimulationReport,
+    ) -> Decimal;
+}
+
+/// Shared plugins, so the same plugin can be registered in many simulations.
+impl<T: MetricPlugin + ?Sized> MetricPlugin for Arc<T> {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn compute(
+        &self,
+        context: &IntervalContext,
+        users: &UserStore,
+        report: &SimulationReport,
+    ) -> Decimal {
+        (**self).compute(context, users, report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Simulation;
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct Velocity;
+
+    impl MetricPlugin for Velocity {
+        fn name(&self) -> &str {
+            "velocity"
+        }
+
+        fn compute(
+            &self,
+            _context: &IntervalContext,
+            users: &UserStore,
+            report: &SimulationReport,
+        ) -> Decimal {
+            let supply: Decimal = users.balances.iter().sum();
+
+            ((report.buy_volume + report.sell_volume).value() / supply).round_dp(4)
+        }
+    }
+
+    #[test]
+    fn test_plugin_metrics_in_reports() {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(50)
+            .build()
+            .unwrap();
+        let mut simulation = Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap();
+        simulation.add_metric_plugin(Arc::new(Velocity));
+
+        simulation.run().unwrap();
+
+        assert!(simulation
+            .interval_reports
+            .iter()
+            .all(|report| report.custom_metrics.contains_key("velocity")));
+        assert!(simulation.report.custom_metrics["velocity"] > Decimal::default());
+    }
+}
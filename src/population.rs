@@ -0,0 +1,211 @@
+//! # Population module
+//!
+//! This module contains the population models used to represent the users of the simulation.
+//!
+//! By default, every user is simulated as an individual agent.
+//! For very large populations, users can be grouped into weighted cohorts instead:
+//! each cohort holds the aggregate balance of its members and trades as a group,
+//! so memory grows with the number of cohorts rather than the number of users.
+
+use rand::Rng;
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::User;
+
+/// Largest cohort for which members are sampled one by one.
+/// Larger cohorts use the normal approximation of the binomial distribution.
+const EXACT_SAMPLING_LIMIT: u64 = 64;
+
+/// Population model used to represent the users.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum PopulationModel {
+    /// Every user is an individual agent.
+    #[default]
+    Agents,
+
+    /// Users are grouped into cohorts of the given size, the last cohort holds the remainder.
+    /// Each cohort trades with the aggregate balance and behaviour of its members.
+    /// Metrics tracking individual addresses over time, such as rolling active users and the retention curve,
+    /// count each cohort once.
+    Cohorts(u64),
+}
+
+impl PopulationModel {
+    /// Get the number of agents needed to represent the users.
+    ///
+    /// # Arguments
+    ///
+    /// * `total_users` - Total number of users.
+    ///
+    /// # Returns
+    ///
+    /// Number of agents: one per user, or one per cohort.
+    pub fn agents(&self, total_users: u64) -> u64 {
+        match self {
+            PopulationModel::Agents => total_users,
+            PopulationModel::Cohorts(size) => total_users.div_ceil((*size).max(1)),
+        }
+    }
+
+    /// Generate the agents representing the users, with random balances.
+    ///
+    /// # Arguments
+    ///
+    /// * `total_users` - Total number of users.
+    /// * `supply` - Initial supply of the token.
+    /// * `price` - Initial price of the token.
+    /// * `decimals` - Number of decimal places for the token.
+    ///
+    /// # Returns
+    ///
+    /// List of agents, weighted by the number of users they represent.
+    pub fn generate(
+        &self,
+        total_users: u64,
+        supply: Decimal,
+        price: Decimal,
+        decimals: u32,
+    ) -> Vec<User> {
+        self.generate_with_rng(total_users, supply, price, decimals, &mut rand::rng())
+    }
+
+    /// Generate the agents representing the given number of users, drawn from the given random number generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `total_users` - Total number of users.
+    /// * `supply` - Initial supply of the token.
+    /// * `price` - Initial price of the token.
+    /// * `decimals` - Number of decimal places for the token.
+    /// * `rng` - Random number generator.
+    ///
+    /// # Returns
+    ///
+    /// List of agents, weighted by the number of users they represent.
+    pub(crate) fn generate_with_rng<R: Rng + ?Sized>(
+        &self,
+        total_users: u64,
+        supply: Decimal,
+        price: Decimal,
+        decimals: u32,
+        rng: &mut R,
+    ) -> Vec<User> {
+        let mut users =
+            User::generate_with_rng(self.agents(total_users), supply, price, decimals, rng);
+
+        if let PopulationModel::Cohorts(size) = self {
+            let size = (*size).max(1);
+            let mut remaining = total_users;
+
+            for user in users.iter_mut() {
+                user.weight = remaining.min(size);
+                remaining -= user.weight;
+            }
+        }
+
+        users
+    }
+}
+
+/// Sample how many members of a cohort take an action, each with the same probability.
+///
+/// # Arguments
+///
+/// * `weight` - Number of members in the cohort.
+/// * `probability` - Probability that a member takes the action, between 0 and 1.
+/// * `rng` - Random number generator.
+///
+/// # Returns
+///
+/// Number of members taking the action, between 0 and the weight.
+pub(crate) fn sample_members<R: Rng + ?Sized>(weight: u64, probability: f64, rng: &mut R) -> u64 {
+    let probability = probability.clamp(0.0, 1.0);
+
+    if weight <= EXACT_SAMPLING_LIMIT {
+        return (0..weight).filter(|_| rng.random_bool(probability)).count() as u64;
+    }
+
+    // Box-Muller transform for a standard normal sample
+    let u1: f64 = rng.random_range(f64::EPSILON..1.0);
+    let u2: f64 = rng.random_range(0.0..1.0);
+    let normal = (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos();
+
+    let mean = weight as f64 * probability;
+    let deviation = (mean * (1.0 - probability)).sqrt();
+
+    (mean + deviation * normal)
+        .round()
+        .clamp(0.0, weight as f64) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_agents() {
+        assert_eq!(PopulationModel::Agents.agents(1_000), 1_000);
+        assert_eq!(PopulationModel::Cohorts(100).agents(1_000), 10);
+        assert_eq!(PopulationModel::Cohorts(300).agents(1_000), 4);
+        assert_eq!(PopulationModel::Cohorts(0).agents(10), 10);
+    }
+
+    #[test]
+    fn test_generate_cohorts() {
+        let users = PopulationModel::Cohorts(300).generate(
+            1_000,
+            Decimal::new(1_000_000, 0),
+            Decimal::new(1, 0),
+            4,
+        );
+
+        assert_eq!(users.len(), 4);
+        assert_eq!(
+            users.iter().map(|user| user.weight).collect::<Vec<_>>(),
+            vec![300, 300, 300, 100]
+        );
+        assert_eq!(User::count(&users), 1_000);
+        assert_eq!(
+            users.iter().map(|user| user.balance).sum::<Decimal>(),
+            Decimal::new(1_000_000, 0)
+        );
+    }
+
+    #[test]
+    fn test_generate_agents() {
+        let users =
+            PopulationModel::Agents.generate(10, Decimal::new(1_000, 0), Decimal::new(1, 0), 4);
+
+        assert_eq!(users.len(), 10);
+        assert!(users.iter().all(|user| user.weight == 1));
+    }
+
+    #[test]
+    fn test_sample_members() {
+        let mut rng = rand::rng();
+
+        assert_eq!(sample_members(10, 0.0, &mut rng), 0);
+        assert_eq!(sample_members(10, 1.0, &mut rng), 10);
+        assert_eq!(sample_members(1_000_000, 1.0, &mut rng), 1_000_000);
+
+        let sampled = sample_members(1_000_000, 0.5, &mut rng);
+        assert!((490_000..=510_000).contains(&sampled));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let model = PopulationModel::Cohorts(1_000);
+        let json = serde_json::to_string(&model).unwrap();
+
+        assert_eq!(json, r#"{"cohorts":1000}"#);
+        assert_eq!(
+            serde_json::from_str::<PopulationModel>(&json).unwrap(),
+            model
+        );
+    }
+}
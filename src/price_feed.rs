@@ -0,0 +1,142 @@
+//! # Price feed module
+//!
+//! This module contains the price feed, a historical series of prices replayed by a simulation instead of the random walk.
+//!
+//! The feed is usually the history of a comparable token, so the simulation replays its returns rather than its prices:
+//! each interval moves the price of the simulated token by the change between two consecutive prices of the feed.
+//! Intervals after the end of the feed fall back to the random walk.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Price of a feed at a date.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct PricePoint {
+    /// Date of the price.
+    pub date: DateTime<Utc>,
+
+    /// Price, in fiat.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub price: Decimal,
+}
+
+/// Historical series of prices, one per interval, oldest first.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct PriceFeed {
+    /// Prices of the feed.
+    pub prices: Vec<PricePoint>,
+}
+
+impl PriceFeed {
+    /// Create a new price feed.
+    ///
+    /// # Arguments
+    ///
+    /// * `prices` - Prices of the feed, in any order.
+    ///
+    /// # Returns
+    ///
+    /// New price feed, sorted by date.
+    pub fn new(mut prices: Vec<PricePoint>) -> Self {
+        prices.sort_by_key(|point| point.date);
+
+        PriceFeed { prices }
+    }
+
+    /// Get the change of the price over an interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the interval.
+    ///
+    /// # Returns
+    ///
+    /// Ratio of the price at the end of the interval to the price at its start,
+    /// `None` after the end of the feed or if the start price is zero.
+    pub fn change_at(&self, index: u64) -> Option<Decimal> {
+        let index = usize::try_from(index).ok()?;
+        let start = self.prices.get(index)?.price;
+        let end = self.prices.get(index.checked_add(1)?)?.price;
+
+        end.checked_div(start)
+    }
+
+    /// Get the number of intervals covered by the feed.
+    ///
+    /// # Returns
+    ///
+    /// Number of price changes of the feed.
+    pub fn intervals(&self) -> u64 {
+        self.prices.len().saturating_sub(1) as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use crate::{FiatAmount, Simulation};
+
+    use super::*;
+
+    fn feed(prices: &[i64]) -> PriceFeed {
+        PriceFeed::new(
+            prices
+                .iter()
+                .enumerate()
+                .rev()
+                .map(|(day, price)| PricePoint {
+                    date: Utc.timestamp_opt(day as i64 * 86_400, 0).unwrap(),
+                    price: Decimal::new(*price, 0),
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_change_at() {
+        let feed = feed(&[10, 20, 5]);
+
+        assert_eq!(feed.prices[0].price, Decimal::new(10, 0));
+        assert_eq!(feed.intervals(), 2);
+        assert_eq!(feed.change_at(0), Some(Decimal::new(2, 0)));
+        assert_eq!(feed.change_at(1), Some(Decimal::new(25, 2)));
+        assert_eq!(feed.change_at(2), None);
+    }
+
+    #[test]
+    fn test_run_with_price_feed() {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(10)
+            .duration(3)
+            .price_feed(feed(&[100, 200, 150]))
+            .build()
+            .unwrap();
+        let mut simulation = Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap();
+
+        simulation.run().unwrap();
+
+        // The token follows the returns of the feed, from its own initial price
+        let prices: Vec<_> = simulation
+            .interval_reports
+            .iter()
+            .map(|report| report.token_price)
+            .collect();
+        assert_eq!(prices[0], FiatAmount(Decimal::new(2, 0)));
+        assert_eq!(prices[1], FiatAmount(Decimal::new(15, 1)));
+        assert_eq!(prices.len(), 3);
+    }
+}
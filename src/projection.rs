@@ -0,0 +1,254 @@
+//! # Projection module
+//!
+//! This module contains the supply projection, the circulating supply curve computed without trades.
+//!
+//! The projection applies the airdrop, the unlock schedule, the contributor emission, and the block rewards of a token
+//! interval by interval, the same way a run does, but without generating users or simulating trades.
+//! It produces the classic unlock chart in milliseconds, to iterate quickly on the allocations and vesting.
+//! Supply changes caused by trades, such as burns and inflation, are not projected.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Simulation, SimulationInterval, Token, TokenAmount};
+
+/// Supply of the token at an interval of the projection.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct SupplyPoint {
+    /// Index of the interval, starting at 0.
+    pub index: u64,
+
+    /// Date of the interval.
+    pub date: DateTime<Utc>,
+
+    /// Circulating supply of the token.
+    pub circulating_supply: TokenAmount,
+
+    /// Tokens released by the unlock schedule so far, including the allocations.
+    pub unlocked: TokenAmount,
+
+    /// Tokens emitted to contributors and as block rewards so far.
+    pub emitted: TokenAmount,
+
+    /// Tokens of each allocation vested so far, by name of the allocation.
+    pub allocations: BTreeMap<String, Decimal>,
+}
+
+/// Projection of the circulating supply over time.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct SupplyProjection {
+    /// Date of the first interval.
+    pub start: DateTime<Utc>,
+
+    /// Interval between two points.
+    pub interval_type: SimulationInterval,
+
+    /// Number of intervals to project.
+    pub duration: u64,
+}
+
+impl SupplyProjection {
+    /// Create a new supply projection, starting now.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval_type` - Interval between two points.
+    /// * `duration` - Number of intervals to project.
+    ///
+    /// # Returns
+    ///
+    /// New supply projection.
+    pub fn new(interval_type: SimulationInterval, duration: u64) -> Self {
+        SupplyProjection {
+            start: Utc::now(),
+            interval_type,
+            duration,
+        }
+    }
+
+    /// Set the date of the first interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - Date of the first interval.
+    ///
+    /// # Returns
+    ///
+    /// The supply projection.
+    pub fn start(mut self, start: DateTime<Utc>) -> Self {
+        self.start = start;
+        self
+    }
+
+    /// Project the circulating supply of a token.
+    /// The token is not modified.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The token to project.
+    ///
+    /// # Returns
+    ///
+    /// Supply of the token at each interval.
+    pub fn project(&self, token: &Token) -> Vec<SupplyPoint> {
+        let mut token = token.clone();
+        let vesting: Vec<_> = token
+            .allocations
+            .iter()
+            .map(|allocation| (allocation.name.clone(), allocation.vesting.unlock_events()))
+            .collect();
+
+        if let Some(percentage) = token.airdrop_percentage {
+            token.airdrop(percentage);
+        }
+
+        let hours = self.interval_type.hours();
+        let mut unlocked = TokenAmount::default();
+        let mut emitted = TokenAmount::default();
+        let mut points = Vec::with_capacity(self.duration as usize);
+
+        for index in 0..self.duration {
+            let date = self.start + chrono::Duration::hours((index * hours) as i64);
+
+            let supply_before_unlocks = token.current_supply;
+            token.process_unlocks(date);
+            unlocked += token.current_supply - supply_before_unlocks;
+
+            let (contributor_emission, _) = token.emit_to_contributors();
+            emitted += contributor_emission + token.emit_block_rewards(index);
+
+            let allocations = vesting
+                .iter()
+                .map(|(name, events)| {
+                    let vested = events
+                        .iter()
+                        .filter(|event| event.date <= date)
+                        .map(|event| event.amount)
+                        .sum();
+
+                    (name.clone(), vested)
+                })
+                .collect();
+
+            points.push(SupplyPoint {
+                index,
+                date,
+                circulating_supply: token.current_supply,
+                unlocked,
+                emitted,
+                allocations,
+            });
+        }
+
+        points
+    }
+}
+
+impl Simulation {
+    /// Project the circulating supply of the token over the duration of the simulation, without running trades.
+    ///
+    /// # Returns
+    ///
+    /// Supply of the token at each interval, starting now.
+    pub fn project_supply(&self) -> Vec<SupplyPoint> {
+        SupplyProjection::new(self.options.interval_type.clone(), self.options.duration)
+            .project(&self.token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Months;
+
+    use crate::{ContributorEmission, VestingScheduleBuilder};
+
+    use super::*;
+
+    fn setup() -> Token {
+        Simulation::token_builder()
+            .name("Test Token".to_string())
+            .airdrop_percentage(5.0)
+            .contributor_emission(ContributorEmission {
+                amount_per_interval: Decimal::new(100, 0),
+                sell_through: Decimal::default(),
+            })
+            .allocation(
+                "Team",
+                15.0,
+                VestingScheduleBuilder::four_year_linear_with_one_year_cliff(),
+            )
+            .allocation(
+                "Investors",
+                20.0,
+                VestingScheduleBuilder::two_year_quarterly(),
+            )
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_project() {
+        let token = setup();
+        let start = token.allocations[0].vesting.start;
+
+        let points = SupplyProjection::new(SimulationInterval::Monthly, 60)
+            .start(start)
+            .project(&token);
+
+        assert_eq!(points.len(), 60);
+        assert_eq!(
+            points[0].circulating_supply,
+            TokenAmount(Decimal::new(50_100, 0))
+        );
+        assert_eq!(points[0].allocations["Team"], Decimal::default());
+        assert!(points
+            .windows(2)
+            .all(|pair| pair[0].circulating_supply <= pair[1].circulating_supply));
+
+        // Every allocation is vested after 4 years
+        let last = points.last().unwrap();
+        assert!(last.date > start + Months::new(48));
+        assert_eq!(last.allocations["Team"], Decimal::new(150_000, 0));
+        assert_eq!(last.allocations["Investors"], Decimal::new(200_000, 0));
+        assert_eq!(last.unlocked, TokenAmount(Decimal::new(350_000, 0)));
+        assert_eq!(last.emitted, TokenAmount(Decimal::new(6_000, 0)));
+        assert_eq!(
+            last.circulating_supply,
+            TokenAmount(Decimal::new(50_000, 0)) + last.unlocked + last.emitted
+        );
+
+        // The token is not modified
+        assert_eq!(token.current_supply, TokenAmount::default());
+    }
+
+    #[test]
+    fn test_project_supply_matches_run() {
+        let token = setup();
+        let options = Simulation::options_builder()
+            .total_users(10)
+            .duration(5)
+            .build()
+            .unwrap();
+        let mut simulation = Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap();
+
+        let points = simulation.project_supply();
+        simulation.run().unwrap();
+
+        assert_eq!(points.len(), 5);
+        assert_eq!(
+            points.last().unwrap().circulating_supply,
+            simulation.token.current_supply
+        );
+    }
+}
@@ -0,0 +1,137 @@
+//! # Random module
+//!
+//! This module contains the randomness source of the simulation.
+//!
+//! By default, every run draws from a `StdRng`, seeded from the `seed` option or from the thread RNG.
+//! A custom source can be set on the simulation instead, e.g. a scripted sequence to test edge cases,
+//! or a hardware RNG where regulated environments require one. The source is kept across runs.
+
+use std::fmt::Debug;
+
+use rand::{RngCore, SeedableRng};
+
+use crate::Simulation;
+
+/// Source of the random numbers drawn by a simulation run.
+/// Implemented for every `RngCore` that can be shared across threads.
+pub trait RandomSource: RngCore + Debug + Send + Sync {}
+
+impl<T: RngCore + Debug + Send + Sync> RandomSource for T {}
+
+impl Simulation {
+    /// Set the randomness source of the runs.
+    /// The `seed` option is ignored while a source is set, and the source is not reseeded between runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The randomness source to use.
+    pub fn set_rng<R: RandomSource + 'static>(&mut self, rng: R) {
+        self.rng = Some(Box::new(rng));
+    }
+
+    /// Set the randomness source of the runs, seeded with the given seed.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - Seed of the randomness source.
+    pub fn set_rng_from_seed<R: RandomSource + SeedableRng + 'static>(&mut self, seed: u64) {
+        self.set_rng(R::seed_from_u64(seed));
+    }
+
+    /// Clear the randomness source, so the runs draw from the default source again.
+    ///
+    /// # Returns
+    ///
+    /// The randomness source, if set.
+    pub fn take_rng(&mut self) -> Option<Box<dyn RandomSource>> {
+        self.rng.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+
+    use crate::SimulationStatus;
+
+    use super::*;
+
+    /// Source returning the same value forever.
+    #[derive(Debug)]
+    struct ConstantRng(u64);
+
+    impl RngCore for ConstantRng {
+        fn next_u32(&mut self) -> u32 {
+            self.0 as u32
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            self.0
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            dest.fill(self.0 as u8);
+        }
+    }
+
+    fn setup() -> Simulation {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(20)
+            .duration(3)
+            .build()
+            .unwrap();
+
+        Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_run_with_seeded_rng_is_reproducible() {
+        let mut first = setup();
+        first.set_rng_from_seed::<StdRng>(7);
+        first.run().unwrap();
+
+        let mut second = setup();
+        second.set_rng(StdRng::seed_from_u64(7));
+        second.run().unwrap();
+
+        second.report.interval = first.report.interval;
+        assert_eq!(first.report, second.report);
+
+        // The source is kept for the next run
+        assert!(first.rng.is_some());
+        assert!(first.take_rng().is_some());
+        assert!(first.rng.is_none());
+    }
+
+    #[test]
+    fn test_run_with_constant_rng() {
+        let mut simulation = setup();
+        simulation.set_rng(ConstantRng(0));
+
+        simulation.run().unwrap();
+
+        assert_eq!(simulation.status, SimulationStatus::Completed);
+        assert!(simulation.rng.is_some());
+    }
+
+    #[test]
+    fn test_rng_is_kept_after_failed_run() {
+        let mut simulation = setup();
+        simulation.set_rng(ConstantRng(u64::MAX));
+
+        let cancellation = crate::CancellationToken::new();
+        cancellation.cancel();
+
+        assert!(simulation.run_with_cancellation(&cancellation).is_err());
+        assert!(simulation.rng.is_some());
+    }
+}
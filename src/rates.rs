@@ -0,0 +1,216 @@
+//! # Rates module
+//!
+//! This module contains the interest rates, an exogenous series of the risk-free rate of each interval.
+//!
+//! The series layers a macro scenario on the tokenomics: the gap of the rate to a neutral rate,
+//! scaled by a sensitivity coefficient, sets the appeal of the token against the risk-free rate.
+//! High rates lower the appeal, reducing the trading of the speculative users and the share of the balances locked for the gauge votes,
+//! low rates raise it. Intervals after the end of the series run at the neutral rate.
+
+use rust_decimal::{prelude::*, Decimal};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{SimulationError, UserBehaviour};
+
+/// Risk-free interest rate of each interval, oldest first.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct InterestRates {
+    /// Rate of each interval, in percentage.
+    pub rates: Vec<Decimal>,
+
+    /// Rate leaving the behaviour of the users unchanged, in percentage.
+    #[cfg_attr(feature = "serde", serde(default, with = "rust_decimal::serde::float"))]
+    pub neutral_rate: Decimal,
+
+    /// Change of the appeal of the token per percentage point of rate above the neutral rate, in percentage.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub sensitivity: Decimal,
+}
+
+impl InterestRates {
+    /// Create a new series of interest rates, with a neutral rate of 0%.
+    ///
+    /// # Arguments
+    ///
+    /// * `rates` - Rate of each interval, in percentage, oldest first.
+    /// * `sensitivity` - Change of the appeal per percentage point of rate, in percentage.
+    ///
+    /// # Returns
+    ///
+    /// New interest rates.
+    pub fn new(rates: Vec<Decimal>, sensitivity: Decimal) -> Self {
+        InterestRates {
+            rates,
+            neutral_rate: Decimal::default(),
+            sensitivity,
+        }
+    }
+
+    /// Validate the interest rates.
+    ///
+    /// # Returns
+    ///
+    /// Result of the validation, or an error if the sensitivity is negative.
+    pub fn validate(&self) -> Result<(), SimulationError> {
+        if self.sensitivity < Decimal::ZERO {
+            return Err(SimulationError::InvalidData(
+                "interest rates: sensitivity must not be negative".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Get the rate of an interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the interval.
+    ///
+    /// # Returns
+    ///
+    /// Rate of the interval, the neutral rate after the end of the series.
+    pub fn rate_at(&self, index: u64) -> Decimal {
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| self.rates.get(index))
+            .copied()
+            .unwrap_or(self.neutral_rate)
+    }
+
+    /// Get the appeal of the token in an interval, against the risk-free rate.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the interval.
+    ///
+    /// # Returns
+    ///
+    /// Multiplier of the appeal, 1 at the neutral rate, never negative.
+    pub fn appeal_at(&self, index: u64) -> Decimal {
+        (Decimal::ONE
+            - (self.rate_at(index) - self.neutral_rate) * self.sensitivity / Decimal::ONE_HUNDRED)
+            .max(Decimal::ZERO)
+    }
+
+    /// Scale a trade probability with the appeal of an interval.
+    /// Only the speculative users, speculators, traders and bots, react to the rate.
+    ///
+    /// # Arguments
+    ///
+    /// * `probability` - Usual trade probability.
+    /// * `behaviour` - Behaviour of the user.
+    /// * `index` - Index of the interval.
+    ///
+    /// # Returns
+    ///
+    /// Scaled trade probability, between 0 and 1.
+    pub(crate) fn scale(&self, probability: f64, behaviour: UserBehaviour, index: u64) -> f64 {
+        match behaviour {
+            UserBehaviour::Speculator | UserBehaviour::Trader | UserBehaviour::Bot => {
+                let appeal = self.appeal_at(index).to_f64().unwrap_or(1.0);
+
+                (probability * appeal).clamp(0.0, 1.0)
+            }
+            _ => probability,
+        }
+    }
+
+    /// Scale the share of the balances locked for the gauge votes with the appeal of an interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `lock_share` - Usual share of the balances locked, between 0 and 1.
+    /// * `index` - Index of the interval.
+    ///
+    /// # Returns
+    ///
+    /// Scaled share of the balances locked, between 0 and 1.
+    pub fn lock_share(&self, lock_share: f64, index: u64) -> f64 {
+        let appeal = self.appeal_at(index).to_f64().unwrap_or(1.0);
+
+        (lock_share * appeal).clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Percentage, Simulation};
+
+    use super::*;
+
+    #[test]
+    fn test_appeal() {
+        let rates = InterestRates {
+            neutral_rate: Decimal::new(2, 0),
+            ..InterestRates::new(
+                vec![Decimal::new(5, 0), Decimal::ZERO, Decimal::new(20, 0)],
+                Decimal::new(10, 0),
+            )
+        };
+
+        assert_eq!(rates.rate_at(1), Decimal::ZERO);
+        assert_eq!(rates.rate_at(3), Decimal::new(2, 0));
+        assert_eq!(rates.appeal_at(0), Decimal::new(7, 1));
+        assert_eq!(rates.appeal_at(1), Decimal::new(12, 1));
+        assert_eq!(rates.appeal_at(2), Decimal::ZERO);
+        assert_eq!(rates.appeal_at(3), Decimal::ONE);
+
+        assert_eq!(rates.scale(0.5, UserBehaviour::Speculator, 0), 0.35);
+        assert_eq!(rates.scale(0.9, UserBehaviour::Bot, 1), 1.0);
+        // Holders do not trade on the rate
+        assert_eq!(rates.scale(0.1, UserBehaviour::Holder, 2), 0.1);
+        assert_eq!(rates.lock_share(0.5, 2), 0.0);
+    }
+
+    #[test]
+    fn test_run_with_interest_rates() {
+        let run = |rates: Option<InterestRates>| {
+            let token = Simulation::token_builder()
+                .name("Test Token".to_string())
+                .build()
+                .unwrap();
+            let mut options = Simulation::options_builder()
+                .total_users(50)
+                .duration(3)
+                .seed(7)
+                .build()
+                .unwrap();
+            options.interest_rates = rates;
+            let mut simulation = Simulation::builder()
+                .name("Test Simulation".to_string())
+                .token(token)
+                .options(options)
+                .build()
+                .unwrap();
+
+            simulation.run().unwrap();
+            simulation
+        };
+
+        let usual = run(None);
+        let simulation = run(Some(InterestRates::new(
+            vec![Decimal::ZERO, Decimal::new(10, 0)],
+            Decimal::new(10, 0),
+        )));
+        let reports = &simulation.interval_reports;
+
+        // The neutral rate keeps the interval as is, a rate of 10% stops the speculation
+        assert_eq!(
+            reports[0].successful_trades,
+            usual.interval_reports[0].successful_trades
+        );
+        assert!(reports[1].successful_trades < usual.interval_reports[1].successful_trades);
+        assert_eq!(
+            reports[1].risk_free_rate,
+            Some(Percentage(Decimal::new(10, 0)))
+        );
+        assert_eq!(reports[2].risk_free_rate, Some(Percentage::default()));
+        assert_eq!(
+            simulation.report.risk_free_rate,
+            Some(Percentage::default())
+        );
+    }
+}
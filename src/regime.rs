@@ -0,0 +1,337 @@
+//! # Regime module
+//!
+//! This module contains the market regime presets: bull, bear, and crab (sideways) markets.
+//!
+//! A regime sets the drift of the price process, scales its volatility, and scales the adoption rate,
+//! so an analysis can report its results across the three standard regimes.
+//!
+//! Regime switching moves the market between the regimes with a Markov chain:
+//! at the start of each interval, the next regime is drawn from the transition probabilities of the current one.
+
+use rand::Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Simulation, SimulationError};
+
+/// Market regime preset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum MarketRegime {
+    /// Rising market: positive drift, faster adoption.
+    Bull,
+
+    /// Falling market: negative drift, higher volatility, slower adoption.
+    Bear,
+
+    /// Sideways market: no drift, lower volatility.
+    Crab,
+}
+
+/// Markov chain switching the market between the regimes.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct RegimeSwitching {
+    /// Regime of the first interval.
+    pub initial: MarketRegime,
+
+    /// Probability of moving from each regime, by row, to each regime, by column, in the order of `MarketRegime::ALL`.
+    /// Each row sums to 1.
+    pub transitions: [[f64; 3]; 3],
+}
+
+/// Regime with the completed simulation of its run, or the error of the run.
+pub type RegimeRun = (MarketRegime, Result<Simulation, SimulationError>);
+
+impl MarketRegime {
+    /// All regimes, in the order of the enum.
+    pub const ALL: [MarketRegime; 3] = [MarketRegime::Bull, MarketRegime::Bear, MarketRegime::Crab];
+
+    /// Get the index of the regime in `MarketRegime::ALL`.
+    ///
+    /// # Returns
+    ///
+    /// Index of the regime.
+    fn index(&self) -> usize {
+        match self {
+            MarketRegime::Bull => 0,
+            MarketRegime::Bear => 1,
+            MarketRegime::Crab => 2,
+        }
+    }
+
+    /// Get the name of the regime.
+    ///
+    /// # Returns
+    ///
+    /// Name of the regime, in snake case.
+    pub fn name(&self) -> &'static str {
+        match self {
+            MarketRegime::Bull => "bull",
+            MarketRegime::Bear => "bear",
+            MarketRegime::Crab => "crab",
+        }
+    }
+
+    /// Get the annualized drift of the price, added to the log return of the price process.
+    ///
+    /// # Returns
+    ///
+    /// Drift per year, e.g. 0.8 for an expected log return of 80% a year.
+    pub fn drift(&self) -> f64 {
+        match self {
+            MarketRegime::Bull => 0.8,
+            MarketRegime::Bear => -0.7,
+            MarketRegime::Crab => 0.0,
+        }
+    }
+
+    /// Get the multiplier of the market volatility.
+    ///
+    /// # Returns
+    ///
+    /// Factor applied to the market volatility of the options.
+    pub fn volatility_multiplier(&self) -> f64 {
+        match self {
+            MarketRegime::Bull => 1.0,
+            MarketRegime::Bear => 1.25,
+            MarketRegime::Crab => 0.6,
+        }
+    }
+
+    /// Get the multiplier of the adoption rate.
+    ///
+    /// # Returns
+    ///
+    /// Factor applied to the adoption rate of the options, if set.
+    pub fn adoption_multiplier(&self) -> f64 {
+        match self {
+            MarketRegime::Bull => 1.5,
+            MarketRegime::Bear => 0.5,
+            MarketRegime::Crab => 1.0,
+        }
+    }
+}
+
+impl RegimeSwitching {
+    /// Create a new regime switching chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial` - Regime of the first interval.
+    /// * `transitions` - Transition probabilities, by row from each regime of `MarketRegime::ALL`.
+    ///
+    /// # Returns
+    ///
+    /// New regime switching chain, or an error if a probability is negative or a row does not sum to 1.
+    pub fn new(initial: MarketRegime, transitions: [[f64; 3]; 3]) -> Result<Self, SimulationError> {
+        let switching = RegimeSwitching {
+            initial,
+            transitions,
+        };
+        switching.validate()?;
+
+        Ok(switching)
+    }
+
+    /// Validate the transition probabilities.
+    ///
+    /// # Returns
+    ///
+    /// Error if a probability is not between 0 and 1, or a row does not sum to 1.
+    pub fn validate(&self) -> Result<(), SimulationError> {
+        for (regime, row) in MarketRegime::ALL.iter().zip(&self.transitions) {
+            if row
+                .iter()
+                .any(|probability| !(0.0..=1.0).contains(probability))
+                || (row.iter().sum::<f64>() - 1.0).abs() > 1e-9
+            {
+                return Err(SimulationError::InvalidData(format!(
+                    "transition probabilities from the {} regime must be between 0 and 1 and sum to 1",
+                    regime.name()
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Create a regime switching chain that stays in the current regime with a probability,
+    /// and otherwise moves to one of the other regimes with equal probability.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial` - Regime of the first interval.
+    /// * `persistence` - Probability of staying in the current regime each interval, between 0 and 1.
+    ///
+    /// # Returns
+    ///
+    /// New regime switching chain, or an error if the persistence is not a probability.
+    pub fn persistent(initial: MarketRegime, persistence: f64) -> Result<Self, SimulationError> {
+        let switch = (1.0 - persistence) / 2.0;
+        let mut transitions = [[switch; 3]; 3];
+        for (index, row) in transitions.iter_mut().enumerate() {
+            row[index] = persistence;
+        }
+
+        RegimeSwitching::new(initial, transitions)
+    }
+
+    /// Draw the regime of the next interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `current` - Regime of the current interval.
+    /// * `rng` - Random number generator.
+    ///
+    /// # Returns
+    ///
+    /// Regime of the next interval.
+    pub(crate) fn next<R: Rng + ?Sized>(&self, current: MarketRegime, rng: &mut R) -> MarketRegime {
+        let draw: f64 = rng.random_range(0.0..1.0);
+        let mut cumulative = 0.0;
+
+        for (regime, probability) in MarketRegime::ALL
+            .iter()
+            .zip(self.transitions[current.index()])
+        {
+            cumulative += probability;
+            if draw < cumulative {
+                return *regime;
+            }
+        }
+
+        current
+    }
+}
+
+impl Simulation {
+    /// Run a copy of the simulation in each market regime.
+    /// The copies are forked from the simulation, so fork it before running it to start from the same supply.
+    ///
+    /// # Arguments
+    ///
+    /// * `concurrency` - Maximum number of simulations running at the same time, 0 to use the available parallelism.
+    ///
+    /// # Returns
+    ///
+    /// Each regime with its completed simulation, or the error of its run.
+    pub fn run_regimes(&self, concurrency: usize) -> Result<Vec<RegimeRun>, SimulationError> {
+        let simulations = MarketRegime::ALL
+            .iter()
+            .map(|regime| {
+                let mut simulation = self.fork().build()?;
+                simulation.name = format!("{} ({})", self.name, regime.name());
+                simulation.options.market_regime = Some(*regime);
+
+                Ok(simulation)
+            })
+            .collect::<Result<Vec<_>, SimulationError>>()?;
+
+        Ok(MarketRegime::ALL
+            .into_iter()
+            .zip(Simulation::run_many(simulations, concurrency))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+
+    use crate::{FiatAmount, ValuationModel};
+
+    use super::*;
+
+    fn setup() -> Simulation {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .valuation_model(ValuationModel::RandomWalk)
+            .total_users(10)
+            .duration(30)
+            .market_volatility(0.0)
+            .adoption_rate(0.1)
+            .seed(1)
+            .build()
+            .unwrap();
+
+        Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_run_regimes() {
+        let simulation = setup();
+
+        let runs: Vec<_> = simulation
+            .run_regimes(0)
+            .unwrap()
+            .into_iter()
+            .map(|(regime, run)| (regime, run.unwrap()))
+            .collect();
+
+        assert_eq!(runs.len(), 3);
+        assert_eq!(runs[0].1.name, "Test Simulation (bull)");
+        assert_eq!(runs[1].1.options.market_regime, Some(MarketRegime::Bear));
+
+        // Without volatility, the price follows the drift of the regime
+        let price = |index: usize| runs[index].1.interval_reports.last().unwrap().token_price;
+        assert!(price(0) > FiatAmount(Decimal::ONE));
+        assert!(price(1) < FiatAmount(Decimal::ONE));
+        assert_eq!(price(2), FiatAmount(Decimal::ONE));
+
+        let users = |index: usize| runs[index].1.report.final_user_count;
+        assert!(users(0) > users(2));
+        assert!(users(2) > users(1));
+    }
+
+    #[test]
+    fn test_regime_switching() {
+        assert!(RegimeSwitching::persistent(MarketRegime::Bull, 1.5).is_err());
+        assert!(RegimeSwitching::new(MarketRegime::Bull, [[0.5, 0.5, 0.5]; 3]).is_err());
+
+        // A fully persistent chain never leaves the initial regime
+        let mut simulation = setup();
+        simulation.options.regime_switching =
+            Some(RegimeSwitching::persistent(MarketRegime::Bear, 1.0).unwrap());
+        simulation.run().unwrap();
+        assert!(simulation
+            .interval_reports
+            .iter()
+            .all(|report| report.market_regime == Some(MarketRegime::Bear)));
+        assert!(simulation.report.token_price < FiatAmount(Decimal::ONE));
+
+        // A chain that always switches never stays in the same regime
+        let mut simulation = setup();
+        simulation.options.regime_switching =
+            Some(RegimeSwitching::persistent(MarketRegime::Crab, 0.0).unwrap());
+        simulation.run().unwrap();
+        let path: Vec<_> = simulation
+            .interval_reports
+            .iter()
+            .map(|report| report.market_regime.unwrap())
+            .collect();
+        assert_eq!(path.len(), 30);
+        assert_eq!(path[0], MarketRegime::Crab);
+        assert!(path.windows(2).all(|pair| pair[0] != pair[1]));
+
+        // The switching regime replaces the preset
+        let mut simulation = setup();
+        simulation.options.market_regime = Some(MarketRegime::Bull);
+        simulation.options.regime_switching =
+            Some(RegimeSwitching::persistent(MarketRegime::Bull, 0.9).unwrap());
+        assert!(matches!(
+            simulation.run(),
+            Err(SimulationError::InvalidData(_))
+        ));
+    }
+}
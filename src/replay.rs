@@ -0,0 +1,324 @@
+//! # Replay module
+//!
+//! This module contains the trade ledger, which records the trades of a run, and the replay of a ledger.
+//!
+//! A replay reruns the exact sequence of recorded trades against modified token parameters or options,
+//! e.g. a different burn rate or fee, and recomputes their side effects: burns, mints, fees, and taxes.
+//! The users, sides, and amounts of the trades are kept, so the difference between the ledger and its replay
+//! is the mechanical effect of the parameter change, isolated from the randomness of the run.
+
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, PoisonError,
+    },
+};
+
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    rounding::RoundAs, IntervalContext, Simulation, SimulationObserver, SimulationOptions,
+    SimulationReport, Token, TokenAmount, Trade, User, ValueKind,
+};
+
+/// Trade recorded during a run.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct RecordedTrade {
+    /// Index of the interval of the trade.
+    pub interval: u64,
+
+    /// The trade and its side effects.
+    pub trade: Trade,
+}
+
+/// Ledger of the trades of a run, in the order they were made.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct TradeLedger {
+    /// Recorded trades.
+    pub trades: Vec<RecordedTrade>,
+}
+
+/// Totals of the trades of a ledger, or of one of its intervals.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct TradeTotals {
+    /// Number of trades.
+    pub trades: u64,
+
+    /// Amount of tokens traded.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub volume: Decimal,
+
+    /// Amount of tokens burned.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub burned: Decimal,
+
+    /// Amount of new tokens created.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub minted: Decimal,
+
+    /// Protocol and exchange fees paid.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub fees: Decimal,
+
+    /// Buy and sell taxes paid.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub tax: Decimal,
+}
+
+impl TradeTotals {
+    /// Add a trade to the totals.
+    ///
+    /// # Arguments
+    ///
+    /// * `trade` - Trade to add.
+    fn add(&mut self, trade: &Trade) {
+        self.trades += 1;
+        self.volume += trade.amount;
+        self.burned += trade.burned;
+        self.minted += trade.minted;
+        self.fees += trade.fee;
+        self.tax += trade.tax;
+    }
+}
+
+impl TradeLedger {
+    /// Record a trade at the end of the ledger.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - Index of the interval of the trade.
+    /// * `trade` - The trade and its side effects.
+    pub fn record(&mut self, interval: u64, trade: Trade) {
+        self.trades.push(RecordedTrade { interval, trade });
+    }
+
+    /// Get the totals of all the trades.
+    ///
+    /// # Returns
+    ///
+    /// Totals of the ledger.
+    pub fn totals(&self) -> TradeTotals {
+        let mut totals = TradeTotals::default();
+        for recorded in &self.trades {
+            totals.add(&recorded.trade);
+        }
+
+        totals
+    }
+
+    /// Get the totals of the trades of each interval.
+    ///
+    /// # Returns
+    ///
+    /// Totals by index of the interval, for the intervals with trades.
+    pub fn interval_totals(&self) -> BTreeMap<u64, TradeTotals> {
+        let mut totals: BTreeMap<u64, TradeTotals> = BTreeMap::new();
+        for recorded in &self.trades {
+            totals
+                .entry(recorded.interval)
+                .or_default()
+                .add(&recorded.trade);
+        }
+
+        totals
+    }
+
+    /// Rerun the recorded trades against other token parameters and options.
+    /// The users, sides, amounts, slippage, gas, and MEV of the trades are kept; burns, mints,
+    /// on-chain fees, and taxes are recomputed the way the engine computes them.
+    /// Trades on external exchanges keep the fee of their exchange.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - Token with the modified parameters.
+    /// * `options` - Options with the modified fee and rounding.
+    ///
+    /// # Returns
+    ///
+    /// Ledger of the replayed trades, in the same order.
+    pub fn replay(&self, token: &Token, options: &SimulationOptions) -> TradeLedger {
+        let trades = self
+            .trades
+            .iter()
+            .map(|recorded| {
+                let mut trade = recorded.trade.clone();
+                let amount = TokenAmount(trade.amount);
+
+                trade.burned = token
+                    .calculate_burn(amount)
+                    .round_as(options, ValueKind::Balance)
+                    .value();
+                trade.minted = token
+                    .calculate_mint(amount)
+                    .round_as(options, ValueKind::Balance)
+                    .value();
+                if trade.venue.is_none() {
+                    trade.fee = options
+                        .transaction_fee_percentage
+                        .map(|fee| fee.of(amount).round_as(options, ValueKind::Balance).value())
+                        .unwrap_or_default();
+                }
+                trade.tax = token
+                    .calculate_tax(trade.side, amount)
+                    .round_as(options, ValueKind::Balance)
+                    .value();
+
+                RecordedTrade {
+                    interval: recorded.interval,
+                    trade,
+                }
+            })
+            .collect();
+
+        TradeLedger { trades }
+    }
+}
+
+/// Observer recording the trades of a run in a ledger.
+/// Add it to a simulation wrapped in an `Arc`, and keep a clone to read the ledger after the run.
+#[derive(Debug, Default)]
+pub struct TradeRecorder {
+    /// Index of the current interval.
+    interval: AtomicU64,
+
+    /// Trades recorded so far.
+    ledger: Mutex<TradeLedger>,
+}
+
+impl TradeRecorder {
+    /// Create a new trade recorder.
+    ///
+    /// # Returns
+    ///
+    /// New trade recorder, with an empty ledger.
+    pub fn new() -> Self {
+        TradeRecorder::default()
+    }
+
+    /// Get the trades recorded so far.
+    ///
+    /// # Returns
+    ///
+    /// Copy of the ledger.
+    pub fn ledger(&self) -> TradeLedger {
+        self.ledger
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+impl SimulationObserver for TradeRecorder {
+    fn on_start(&self, _simulation: &Simulation) {
+        self.interval.store(0, Ordering::Relaxed);
+        *self.ledger.lock().unwrap_or_else(PoisonError::into_inner) = TradeLedger::default();
+    }
+
+    fn on_trade(&self, _user: &User, trade: &Trade) {
+        self.ledger
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .record(self.interval.load(Ordering::Relaxed), trade.clone());
+    }
+
+    fn on_interval(&self, context: &IntervalContext, _report: &SimulationReport) {
+        self.interval.store(context.index + 1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::Percentage;
+
+    use super::*;
+
+    fn setup(burn_rate: f64) -> Simulation {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .burn_rate(burn_rate)
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(20)
+            .duration(3)
+            .seed(42)
+            .build()
+            .unwrap();
+
+        Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_record_trades() {
+        let recorder = Arc::new(TradeRecorder::new());
+        let mut simulation = setup(0.01);
+        simulation.add_observer(recorder.clone());
+
+        simulation.run().unwrap();
+
+        let ledger = recorder.ledger();
+        let successful: u64 = simulation
+            .interval_reports
+            .iter()
+            .map(|report| report.successful_trades)
+            .sum();
+        assert!(!ledger.trades.is_empty());
+        assert_eq!(ledger.totals().trades, successful);
+        assert!(ledger
+            .interval_totals()
+            .keys()
+            .all(|interval| *interval < 3));
+
+        let burned: TokenAmount = simulation
+            .interval_reports
+            .iter()
+            .map(|report| report.total_burned)
+            .sum();
+        assert_eq!(ledger.totals().burned, burned.value());
+    }
+
+    #[test]
+    fn test_replay() {
+        let recorder = Arc::new(TradeRecorder::new());
+        let mut simulation = setup(0.01);
+        simulation.add_observer(recorder.clone());
+        simulation.run().unwrap();
+        let ledger = recorder.ledger();
+
+        // Replaying with the same parameters reproduces the ledger
+        assert_eq!(
+            ledger.replay(&simulation.token, &simulation.options),
+            ledger
+        );
+
+        // Doubling the burn rate doubles the burns of the same trades, and nothing else
+        let mut token = simulation.token.clone();
+        token.burn_rate = Some(Decimal::new(2, 2));
+        let replay = ledger.replay(&token, &simulation.options);
+
+        let (before, after) = (ledger.totals(), replay.totals());
+        assert_eq!(after.trades, before.trades);
+        assert_eq!(after.volume, before.volume);
+        assert!(after.burned > before.burned);
+        assert_eq!(after.fees, before.fees);
+
+        let mut options = simulation.options.clone();
+        options.transaction_fee_percentage = Some(Percentage(Decimal::ONE));
+        let with_fee = ledger.replay(&simulation.token, &options).totals();
+        assert!(with_fee.fees > before.fees);
+        assert_eq!(with_fee.burned, before.burned);
+    }
+}
@@ -3,17 +3,28 @@
 //! This module contains the simulation report struct and its methods.
 //! The simulation report contains the results of a simulation.
 
+use std::collections::BTreeMap;
+
 use chrono::Utc;
 use rust_decimal::Decimal;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::User;
+use crate::{
+    CohortRoi, DistributionAnalysis, EnsembleMetric, FiatAmount, GaugeReport, MarketRegime,
+    MetricSummary, Percentage, RetentionCurve, TaxDestination, TaxReport, TokenAmount,
+    TokenMetadata, User, UserState, UserStore, VenueReport, SCHEMA_VERSION,
+};
 
 /// Report containing the results of a simulation.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct SimulationReport {
+    /// Version of the serialization format.
+    /// Missing in data written before versioning, which is treated as version 0.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub schema_version: u32,
+
     /// Timestamp of the simulation interval.
     pub interval: i64,
 
@@ -24,8 +35,7 @@ pub struct SimulationReport {
 
     /// Profit or loss for the interval.
     /// Positive value indicates profit, negative value indicates loss.
-    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
-    pub profit_loss: Decimal,
+    pub profit_loss: TokenAmount,
 
     /// Number of trades made in the interval.
     /// This includes both successful and failed trades.
@@ -55,8 +65,7 @@ pub struct SimulationReport {
     pub adoption_rate: Decimal,
 
     /// Total number of tokens burned during the simulation.
-    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
-    pub total_burned: Decimal,
+    pub total_burned: TokenAmount,
 
     /// Burn rate of the token.
     /// Burn rate is the number of tokens burned per user.
@@ -69,22 +78,454 @@ pub struct SimulationReport {
     pub inflation_rate: Decimal,
 
     /// User retention rate.
-    /// User retention rate is the percentage of users who have a positive balance.
+    /// User retention rate is the percentage of users who have a positive balance and did not churn.
     #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
     pub user_retention: Decimal,
 
+    /// Number of active users at the end of the interval.
+    pub active_users: u64,
+
+    /// Number of churned users at the end of the interval.
+    pub churned_users: u64,
+
+    /// Number of users at the end of the interval, including adoption growth.
+    pub final_user_count: u64,
+
+    /// Highest number of users reached during the interval or simulation.
+    pub peak_user_count: u64,
+
+    /// Number of addresses holding tokens (balance above zero) at the end of the interval.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub holders: u64,
+
+    /// Number of addresses with at least one executed trade in the interval.
+    /// In the final report, this is the average per interval.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub active_addresses: u64,
+
+    /// Number of addresses seen for the first time in the interval.
+    /// In the final report, this is the total over the simulation.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub new_addresses: u64,
+
+    /// Number of unique users with an executed trade in the last day, including the interval.
+    /// In the final report, this is the average per interval.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub daily_active_users: u64,
+
+    /// Number of unique users with an executed trade in the last week, including the interval.
+    /// In the final report, this is the average per interval.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub weekly_active_users: u64,
+
+    /// Number of unique users with an executed trade in the last 30 days, including the interval.
+    /// In the final report, this is the average per interval.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub monthly_active_users: u64,
+
+    /// Stickiness of the token: daily active users divided by monthly active users.
+    #[cfg_attr(feature = "serde", serde(default, with = "rust_decimal::serde::float"))]
+    pub stickiness: Decimal,
+
+    /// Retention curve of the user cohorts.
+    /// Only set in the final report.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub retention_curve: Option<RetentionCurve>,
+
+    /// Distribution of the interval metrics over the simulation, e.g. the worst price interval.
+    /// Only set in the final report.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub interval_distribution: Vec<MetricSummary>,
+
+    /// Custom metrics computed by the registered plugins, by name.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub custom_metrics: BTreeMap<String, Decimal>,
+
     /// Network activity (e.g., transactions per second).
     /// This is the number of transactions made in the interval.
     pub network_activity: u64,
 
     /// Actual token price during the simulation.
     /// This is the price of the token at the end of the simulation.
-    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
-    pub token_price: Decimal,
+    pub token_price: FiatAmount,
 
     /// Total number of new tokens created during the simulation.
-    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
-    pub total_new_tokens: Decimal,
+    pub total_new_tokens: TokenAmount,
+
+    /// Amount of tokens bought by users in the interval.
+    pub buy_volume: TokenAmount,
+
+    /// Amount of tokens sold by users in the interval.
+    pub sell_volume: TokenAmount,
+
+    /// Amount of tokens traded by organic users in the interval.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub organic_volume: TokenAmount,
+
+    /// Amount of tokens traded by bots and sybil accounts in the interval.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub inorganic_volume: TokenAmount,
+
+    /// Estimated real volume, exchange-style: the organic volume without round trips,
+    /// i.e. tokens bought and sold back by the same user within the interval.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub real_volume: TokenAmount,
+
+    /// Amount of tokens emitted to contributors in the interval.
+    /// Not included in the inflation rate.
+    pub contributor_emission: TokenAmount,
+
+    /// Amount of emitted tokens sold immediately by contributors in the interval.
+    pub contributor_sold: TokenAmount,
+
+    /// Amount of unlocked tokens sold right away by the holders receiving them in the interval.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub unlock_sold: TokenAmount,
+
+    /// Total percentage protocol fees paid by users in the interval.
+    pub fees: TokenAmount,
+
+    /// Total network fees (gas) spent by users in the interval, in tokens.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub gas_spent: TokenAmount,
+
+    /// External capital that entered the system in the interval, in fiat.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub capital_inflow: FiatAmount,
+
+    /// Net capital flow in the interval, in fiat: the capital inflow minus the value of the tokens sold.
+    /// In the final report, this is the cumulative net inflow.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub net_inflow: FiatAmount,
+
+    /// Average slippage of the executed trades, in percentage.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub average_slippage: Percentage,
+
+    /// Number of trades aborted because their slippage exceeded the tolerance.
+    /// Aborted trades are also counted as failed trades.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub aborted_trades: u64,
+
+    /// Number of on-chain trades skipped because their network fee exceeded the traded amount.
+    /// Skipped trades are also counted as failed trades.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub priced_out_trades: u64,
+
+    /// Amount of tokens extracted from users by sandwich attacks.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub mev_extracted: TokenAmount,
+
+    /// Number of trades sandwiched by the MEV agent.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub sandwiched_trades: u64,
+
+    /// Activity of each trading venue, when venues are configured.
+    /// In the final report, the totals per venue, with the mean price and mean absolute price divergence.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub venues: Vec<VenueReport>,
+
+    /// Amount of tokens emitted as block rewards in the interval.
+    pub block_rewards: TokenAmount,
+
+    /// Security budget of the interval: block rewards and fees, in fiat.
+    pub security_budget: FiatAmount,
+
+    /// Whether the security budget fell below the configured threshold.
+    /// In the final report, whether it happened in any interval.
+    pub security_budget_below_threshold: bool,
+
+    /// Total buy and sell taxes collected in the interval.
+    pub tax_revenue: TokenAmount,
+
+    /// Collected taxes, per destination.
+    pub tax_distribution: BTreeMap<TaxDestination, Decimal>,
+
+    /// Metadata of the simulated token, to cross-reference the report with a real deployment.
+    /// Only available in the final report.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub token_metadata: Option<TokenMetadata>,
+
+    /// Value of the balance of the average holder, at the price of the interval.
+    /// In the final report, at the price of the last interval.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub holder_value: FiatAmount,
+
+    /// Price of the token adjusted for the supply growth since the start of the run.
+    /// In the final report, the average over the intervals.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub real_token_price: FiatAmount,
+
+    /// Value of the balance of the average holder, adjusted for the supply growth since the start of the run.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub real_holder_value: FiatAmount,
+
+    /// Growth of the circulating supply since the start of the run, in percentage.
+    /// Negative when more tokens were burned than released.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub cumulative_dilution: Percentage,
+
+    /// Return on investment of the holder cohorts, by joining interval and sale round.
+    /// Only available in the final report.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub cohort_roi: Vec<CohortRoi>,
+
+    /// Analysis of the token distribution among the users, with the Lorenz curve and the Gini coefficient.
+    /// Available in the interval reports when `distribution_per_interval` is set.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub distribution: Option<DistributionAnalysis>,
+
+    /// Market regime of the interval, when the regime switches.
+    /// Only available in the interval reports.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub market_regime: Option<MarketRegime>,
+
+    /// Multiplier of the network fee from the congestion of the previous interval, when the fee congestion is set.
+    /// Only available in the interval reports.
+    #[cfg_attr(feature = "serde", serde(default, with = "crate::float_option"))]
+    pub network_fee_multiplier: Option<Decimal>,
+
+    /// Tokens spent or locked by the users to use the product.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub utility_consumed: TokenAmount,
+
+    /// Tokens locked by the users to use the product, at the end of the interval.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub utility_locked: TokenAmount,
+
+    /// Recurring revenue: tokens paid by the subscribers.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub recurring_revenue: TokenAmount,
+
+    /// Recurring revenue in fiat, at the price of each interval.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub recurring_revenue_fiat: FiatAmount,
+
+    /// Number of users paying the subscription.
+    /// In the final report, the subscribers of the last interval.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub subscribers: u64,
+
+    /// Number of NFT mints.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub nft_mints: u64,
+
+    /// Tokens spent on NFT mints and resales.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub nft_volume: TokenAmount,
+
+    /// Royalties of the NFT resales, in tokens.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub nft_royalties: TokenAmount,
+
+    /// Gameplay rewards minted by the faucet of the game economy.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub game_rewards: TokenAmount,
+
+    /// Tokens spent in the sinks of the game economy.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub game_sinks: TokenAmount,
+
+    /// Ratio of the tokens spent in the sinks to the gameplay rewards.
+    /// Above 1, the sinks absorb all the emissions of the game.
+    #[cfg_attr(feature = "serde", serde(default, with = "crate::float_option"))]
+    pub sink_ratio: Option<Decimal>,
+
+    /// Tokens emitted by the gauges.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub gauge_emission: TokenAmount,
+
+    /// Emission directed to each gauge, when gauge voting is configured.
+    /// In the final report, the totals per gauge, with the mean votes.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub gauges: Vec<GaugeReport>,
+
+    /// Balance of the insurance fund at the end of the interval, in tokens.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub insurance_fund: TokenAmount,
+
+    /// Tokens lost in the shortfall events.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub shortfall_losses: TokenAmount,
+
+    /// Shortfall losses paid out by the insurance fund.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub insurance_payouts: TokenAmount,
+
+    /// Balance of the insurance fund against the insured tokens, at the end of the interval.
+    #[cfg_attr(feature = "serde", serde(default, with = "crate::float_option"))]
+    pub coverage_ratio: Option<Decimal>,
+
+    /// Tokens emitted by the supply controller, negative when burned.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub supply_adjustment: TokenAmount,
+
+    /// Relative gap of the price to the target of the supply controller, positive below the target.
+    /// In the final report, the mean absolute error over the intervals.
+    #[cfg_attr(feature = "serde", serde(default, with = "crate::float_option"))]
+    pub controller_error: Option<Decimal>,
+
+    /// Fees redirected to the token holders by the fee switch.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub holder_fees: TokenAmount,
+
+    /// Fees kept by the treasury, not redirected to the token holders.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub treasury_fees: TokenAmount,
+
+    /// Number of users drained by the competitor.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub users_drained: u64,
+
+    /// Yield of the competitor above the yield of the token, in percentage points, once the competitor launched.
+    /// In the final report, the gap of the last interval.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub yield_gap: Option<Percentage>,
+
+    /// Risk-free interest rate of the interval, in percentage.
+    /// In the final report, the rate of the last interval.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub risk_free_rate: Option<Percentage>,
+
+    /// Sentiment index at the start of the interval, between -1 and 1.
+    /// The index of each interval report forms the sentiment path, in the final report, the index of the last interval.
+    #[cfg_attr(feature = "serde", serde(default, with = "crate::float_option"))]
+    pub sentiment: Option<Decimal>,
+
+    /// Multiplier of the adoption growth from the network effects.
+    /// In the final report, the multiplier of the last interval.
+    #[cfg_attr(feature = "serde", serde(default, with = "crate::float_option"))]
+    pub adoption_multiplier: Option<Decimal>,
+
+    /// Tokens sold by the users to cover their tax.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tax_sold: TokenAmount,
+
+    /// Number of holders above the holder cap, forced to sell.
+    /// In the final report, the holders above the cap in the last interval.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub capped_holders: u64,
+
+    /// Tokens sold by the holders above the holder cap, included in the sell volume.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub forced_sales: TokenAmount,
+
+    /// Tax records of the users, if a tax policy is set.
+    /// Only available in the final report.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tax_records: Option<TaxReport>,
+}
+
+/// Selection of the bulky report fields to produce.
+/// Disabled fields are left empty, and are not computed when possible, to reduce the run time and output size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ReportFields {
+    /// List of users in the final report.
+    pub users: bool,
+
+    /// Collected taxes, per destination.
+    pub tax_distribution: bool,
+
+    /// Activity of each trading venue.
+    pub venues: bool,
+
+    /// Custom metrics computed by the registered plugins.
+    pub custom_metrics: bool,
+
+    /// Retention curve of the user cohorts, in the final report.
+    pub retention_curve: bool,
+
+    /// Distribution of the interval metrics, in the final report.
+    pub interval_distribution: bool,
+
+    /// Return on investment of the holder cohorts, in the final report.
+    pub cohort_roi: bool,
+
+    /// Analysis of the token distribution.
+    pub distribution: bool,
+}
+
+impl Default for ReportFields {
+    /// Select all report fields.
+    ///
+    /// # Returns
+    ///
+    /// Selection of all report fields.
+    fn default() -> Self {
+        ReportFields::all()
+    }
+}
+
+impl ReportFields {
+    /// Select all report fields.
+    ///
+    /// # Returns
+    ///
+    /// Selection of all report fields.
+    pub fn all() -> Self {
+        ReportFields {
+            users: true,
+            tax_distribution: true,
+            venues: true,
+            custom_metrics: true,
+            retention_curve: true,
+            interval_distribution: true,
+            cohort_roi: true,
+            distribution: true,
+        }
+    }
+
+    /// Select none of the bulky report fields, only the scalar metrics are produced.
+    ///
+    /// # Returns
+    ///
+    /// Empty selection of report fields.
+    pub fn none() -> Self {
+        ReportFields {
+            users: false,
+            tax_distribution: false,
+            venues: false,
+            custom_metrics: false,
+            retention_curve: false,
+            interval_distribution: false,
+            cohort_roi: false,
+            distribution: false,
+        }
+    }
+
+    /// Clear the fields of a report that are not selected.
+    ///
+    /// # Arguments
+    ///
+    /// * `report` - Report to clear.
+    pub fn apply(&self, report: &mut SimulationReport) {
+        if !self.users {
+            report.users = None;
+        }
+        if !self.tax_distribution {
+            report.tax_distribution.clear();
+        }
+        if !self.venues {
+            report.venues.clear();
+        }
+        if !self.custom_metrics {
+            report.custom_metrics.clear();
+        }
+        if !self.retention_curve {
+            report.retention_curve = None;
+        }
+        if !self.interval_distribution {
+            report.interval_distribution.clear();
+        }
+        if !self.cohort_roi {
+            report.cohort_roi.clear();
+        }
+        if !self.distribution {
+            report.distribution = None;
+        }
+    }
 }
 
 impl Default for SimulationReport {
@@ -95,27 +536,164 @@ impl Default for SimulationReport {
     /// A new simulation report with default values.
     fn default() -> Self {
         Self {
+            schema_version: SCHEMA_VERSION,
             users: None,
             interval: Utc::now().timestamp(),
-            profit_loss: Decimal::default(),
+            profit_loss: TokenAmount::default(),
             trades: 0,
             successful_trades: 0,
             failed_trades: 0,
             market_volatility: Decimal::default(),
             liquidity: Decimal::default(),
             adoption_rate: Decimal::default(),
-            total_burned: Decimal::default(),
+            total_burned: TokenAmount::default(),
             burn_rate: Decimal::default(),
             inflation_rate: Decimal::default(),
             user_retention: Decimal::default(),
-            token_price: Decimal::default(),
-            total_new_tokens: Decimal::default(),
+            token_price: FiatAmount::default(),
+            total_new_tokens: TokenAmount::default(),
             network_activity: 0,
+            active_users: 0,
+            churned_users: 0,
+            final_user_count: 0,
+            peak_user_count: 0,
+            holders: 0,
+            active_addresses: 0,
+            new_addresses: 0,
+            daily_active_users: 0,
+            weekly_active_users: 0,
+            monthly_active_users: 0,
+            stickiness: Decimal::default(),
+            retention_curve: None,
+            interval_distribution: vec![],
+            custom_metrics: BTreeMap::new(),
+            buy_volume: TokenAmount::default(),
+            sell_volume: TokenAmount::default(),
+            organic_volume: TokenAmount::default(),
+            inorganic_volume: TokenAmount::default(),
+            real_volume: TokenAmount::default(),
+            contributor_emission: TokenAmount::default(),
+            contributor_sold: TokenAmount::default(),
+            unlock_sold: TokenAmount::default(),
+            fees: TokenAmount::default(),
+            gas_spent: TokenAmount::default(),
+            capital_inflow: FiatAmount::default(),
+            net_inflow: FiatAmount::default(),
+            average_slippage: Percentage::default(),
+            aborted_trades: 0,
+            priced_out_trades: 0,
+            mev_extracted: TokenAmount::default(),
+            sandwiched_trades: 0,
+            venues: vec![],
+            block_rewards: TokenAmount::default(),
+            security_budget: FiatAmount::default(),
+            security_budget_below_threshold: false,
+            tax_revenue: TokenAmount::default(),
+            tax_distribution: BTreeMap::new(),
+            token_metadata: None,
+            holder_value: FiatAmount::default(),
+            real_token_price: FiatAmount::default(),
+            real_holder_value: FiatAmount::default(),
+            cumulative_dilution: Percentage::default(),
+            cohort_roi: vec![],
+            distribution: None,
+            market_regime: None,
+            network_fee_multiplier: None,
+            utility_consumed: TokenAmount::default(),
+            utility_locked: TokenAmount::default(),
+            recurring_revenue: TokenAmount::default(),
+            recurring_revenue_fiat: FiatAmount::default(),
+            subscribers: 0,
+            nft_mints: 0,
+            nft_volume: TokenAmount::default(),
+            nft_royalties: TokenAmount::default(),
+            game_rewards: TokenAmount::default(),
+            game_sinks: TokenAmount::default(),
+            sink_ratio: None,
+            gauge_emission: TokenAmount::default(),
+            gauges: vec![],
+            insurance_fund: TokenAmount::default(),
+            shortfall_losses: TokenAmount::default(),
+            insurance_payouts: TokenAmount::default(),
+            coverage_ratio: None,
+            supply_adjustment: TokenAmount::default(),
+            controller_error: None,
+            holder_fees: TokenAmount::default(),
+            treasury_fees: TokenAmount::default(),
+            users_drained: 0,
+            yield_gap: None,
+            risk_free_rate: None,
+            sentiment: None,
+            adoption_multiplier: None,
+            tax_sold: TokenAmount::default(),
+            capped_holders: 0,
+            forced_sales: TokenAmount::default(),
+            tax_records: None,
         }
     }
 }
 
 impl SimulationReport {
+    /// Calculate the total sell pressure.
+    /// Sell pressure is the amount of tokens sold by users and contributors, including the unlock and tax sales.
+    /// The forced sales of the holder cap are part of the sell volume.
+    ///
+    /// # Returns
+    ///
+    /// The total amount of tokens sold.
+    pub fn sell_pressure(&self) -> TokenAmount {
+        self.sell_volume + self.contributor_sold + self.unlock_sold + self.tax_sold
+    }
+
+    /// Get the distribution of an interval metric over the simulation.
+    ///
+    /// # Arguments
+    ///
+    /// * `metric` - Interval metric.
+    ///
+    /// # Returns
+    ///
+    /// Summary statistics of the metric over the intervals, if set.
+    pub fn interval_summary(&self, metric: EnsembleMetric) -> Option<&MetricSummary> {
+        self.interval_distribution
+            .iter()
+            .find(|summary| summary.metric == metric)
+    }
+
+    /// Calculate the share of the volume traded by organic users.
+    ///
+    /// # Arguments
+    ///
+    /// * `decimals` - Number of decimal places to round to.
+    ///
+    /// # Returns
+    ///
+    /// Share of the organic volume, between 0 and 1.
+    pub fn organic_volume_share(&self, decimals: u32) -> Decimal {
+        self.organic_volume
+            .value()
+            .checked_div((self.organic_volume + self.inorganic_volume).value())
+            .unwrap_or_default()
+            .round_dp(decimals)
+    }
+
+    /// Calculate the security budget.
+    /// Security budget is the value of the block rewards, protocol fees, and gas, in fiat.
+    ///
+    /// # Arguments
+    ///
+    /// * `price` - Price of the token.
+    /// * `decimals` - Number of decimal places to round to.
+    ///
+    /// # Returns
+    ///
+    /// The security budget, in fiat.
+    pub fn calculate_security_budget(&self, price: FiatAmount, decimals: u32) -> FiatAmount {
+        (self.block_rewards + self.fees + self.gas_spent)
+            .value_at(price)
+            .round_dp(decimals)
+    }
+
     /// Calculate the liquidity of the token.
     /// Liquidity is the number of trades per second.
     ///
@@ -141,7 +719,10 @@ impl SimulationReport {
             interval_duration
         );
 
-        (trades / interval_duration).round_dp(decimals)
+        trades
+            .checked_div(interval_duration)
+            .unwrap_or_default()
+            .round_dp(decimals)
     }
 
     /// Calculate the adoption rate.
@@ -155,20 +736,17 @@ impl SimulationReport {
     /// # Returns
     ///
     /// The adoption rate as a percentage.
-    pub fn calculate_adoption_rate(&self, users: &[User], decimals: u32) -> Decimal {
+    pub fn calculate_adoption_rate(&self, users: &UserStore, decimals: u32) -> Decimal {
         #[cfg(feature = "log")]
         log::debug!("Calculating adoption rate: users={:?}", users.len());
 
-        let total_users = Decimal::new(users.len() as i64, 0);
-        let new_users = Decimal::new(
-            users
-                .iter()
-                .filter(|u| u.balance > Decimal::default())
-                .count() as i64,
-            0,
-        );
+        let total_users = Decimal::from(users.count());
+        let new_users = Decimal::from(self.calculate_holders(users));
 
-        (new_users / total_users).round_dp(decimals)
+        new_users
+            .checked_div(total_users)
+            .unwrap_or_default()
+            .round_dp(decimals)
     }
 
     /// Calculate the burn rate.
@@ -185,7 +763,7 @@ impl SimulationReport {
     /// The burn rate as a percentage.
     pub fn calculate_burn_rate(
         &self,
-        total_burned: Decimal,
+        total_burned: TokenAmount,
         total_users: Decimal,
         decimals: u32,
     ) -> Decimal {
@@ -196,7 +774,11 @@ impl SimulationReport {
             total_users
         );
 
-        (total_burned / total_users).round_dp(decimals)
+        total_burned
+            .value()
+            .checked_div(total_users)
+            .unwrap_or_default()
+            .round_dp(decimals)
     }
 
     /// Calculate the inflation rate.
@@ -213,7 +795,7 @@ impl SimulationReport {
     /// The inflation rate as a percentage.
     pub fn calculate_inflation_rate(
         &self,
-        total_new_tokens: Decimal,
+        total_new_tokens: TokenAmount,
         total_users: Decimal,
         decimals: u32,
     ) -> Decimal {
@@ -224,11 +806,15 @@ impl SimulationReport {
             total_users
         );
 
-        (total_new_tokens / total_users).round_dp(decimals)
+        total_new_tokens
+            .value()
+            .checked_div(total_users)
+            .unwrap_or_default()
+            .round_dp(decimals)
     }
 
     /// Calculate the user retention rate.
-    /// User retention rate is the percentage of users who have a positive balance.
+    /// User retention rate is the percentage of users who have a positive balance and did not churn.
     ///
     /// # Arguments
     ///
@@ -238,20 +824,161 @@ impl SimulationReport {
     /// # Returns
     ///
     /// The user retention rate as a percentage.
-    pub fn calculate_user_retention(&self, users: &[User], decimals: u32) -> Decimal {
+    pub fn calculate_user_retention(&self, users: &UserStore, decimals: u32) -> Decimal {
         #[cfg(feature = "log")]
         log::debug!("Calculating user retention rate: users={:?}", users.len());
 
-        let total_users = Decimal::new(users.len() as i64, 0);
-        let retained_users = Decimal::new(
-            users
-                .iter()
-                .filter(|u| u.balance > Decimal::default())
-                .count() as i64,
-            0,
+        let total_users = Decimal::from(users.count());
+        let retained_users = Decimal::from(
+            (0..users.len())
+                .filter(|&i| {
+                    users.balances[i] > Decimal::default() && users.states[i] != UserState::Churned
+                })
+                .map(|i| users.weights[i])
+                .sum::<u64>(),
         );
 
-        (retained_users / total_users).round_dp(decimals)
+        retained_users
+            .checked_div(total_users)
+            .unwrap_or_default()
+            .round_dp(decimals)
+    }
+
+    /// Calculate the number of active users.
+    ///
+    /// # Arguments
+    ///
+    /// * `users` - A list of users.
+    ///
+    /// # Returns
+    ///
+    /// The number of active users.
+    pub fn calculate_active_users(&self, users: &UserStore) -> u64 {
+        (0..users.len())
+            .filter(|&i| users.is_active(i))
+            .map(|i| users.weights[i])
+            .sum()
+    }
+
+    /// Calculate the number of churned users.
+    ///
+    /// # Arguments
+    ///
+    /// * `users` - A list of users.
+    ///
+    /// # Returns
+    ///
+    /// The number of churned users.
+    pub fn calculate_churned_users(&self, users: &UserStore) -> u64 {
+        users
+            .states
+            .iter()
+            .zip(&users.weights)
+            .filter(|(state, _)| **state == UserState::Churned)
+            .map(|(_, weight)| weight)
+            .sum()
+    }
+
+    /// Calculate the number of token holders.
+    ///
+    /// # Arguments
+    ///
+    /// * `users` - A list of users.
+    ///
+    /// # Returns
+    ///
+    /// The number of users with a balance above zero.
+    pub fn calculate_holders(&self, users: &UserStore) -> u64 {
+        users
+            .balances
+            .iter()
+            .zip(&users.weights)
+            .filter(|(balance, _)| **balance > Decimal::default())
+            .map(|(_, weight)| weight)
+            .sum()
+    }
+
+    /// Calculate the value of the balance of the average holder.
+    ///
+    /// # Arguments
+    ///
+    /// * `users` - A list of users.
+    /// * `price` - Price of the token.
+    /// * `decimals` - Number of decimal places to round to.
+    ///
+    /// # Returns
+    ///
+    /// The value of the average holder, or zero if there is no holder.
+    pub fn calculate_holder_value(
+        &self,
+        users: &UserStore,
+        price: FiatAmount,
+        decimals: u32,
+    ) -> FiatAmount {
+        let balance: TokenAmount = users
+            .balances
+            .iter()
+            .filter(|balance| **balance > Decimal::default())
+            .map(|balance| TokenAmount(*balance))
+            .sum();
+
+        FiatAmount(
+            balance
+                .value_at(price)
+                .value()
+                .checked_div(Decimal::from(self.calculate_holders(users)))
+                .unwrap_or_default()
+                .round_dp(decimals),
+        )
+    }
+
+    /// Calculate the growth of the circulating supply since the start of the run.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial_supply` - Circulating supply at the start of the run.
+    /// * `circulating_supply` - Current circulating supply.
+    /// * `decimals` - Number of decimal places to round to.
+    ///
+    /// # Returns
+    ///
+    /// The cumulative dilution, in percentage, or zero if the initial supply is zero.
+    pub fn calculate_dilution(
+        &self,
+        initial_supply: TokenAmount,
+        circulating_supply: TokenAmount,
+        decimals: u32,
+    ) -> Percentage {
+        let dilution = (circulating_supply - initial_supply)
+            .value()
+            .checked_div(initial_supply.value())
+            .unwrap_or_default();
+
+        Percentage::from_fraction(dilution).round_dp(decimals)
+    }
+
+    /// Adjust a nominal value for the cumulative dilution of the report.
+    /// A value of a constant share of the supply keeps the same real value.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Nominal value.
+    /// * `decimals` - Number of decimal places to round to.
+    ///
+    /// # Returns
+    ///
+    /// The real value, or the nominal value if the whole supply was burned.
+    pub fn calculate_real_value(&self, value: FiatAmount, decimals: u32) -> FiatAmount {
+        let hundred = Decimal::new(100, 0);
+        let dilution = self.cumulative_dilution.value();
+
+        FiatAmount(
+            (value.value() * hundred)
+                .checked_div(hundred + dilution)
+                .filter(|_| dilution > -hundred)
+                .unwrap_or(value.value())
+                .round_dp(decimals),
+        )
     }
 }
 
@@ -266,7 +993,7 @@ mod tests {
         let report = SimulationReport::default();
 
         assert!(report.users.is_none());
-        assert_eq!(report.profit_loss, Decimal::default());
+        assert_eq!(report.profit_loss, TokenAmount::default());
         assert_eq!(report.trades, 0);
         assert_eq!(report.successful_trades, 0);
         assert_eq!(report.failed_trades, 0);
@@ -277,6 +1004,88 @@ mod tests {
         assert_eq!(report.inflation_rate, Decimal::default());
         assert_eq!(report.user_retention, Decimal::default());
         assert_eq!(report.network_activity, 0);
+        assert_eq!(report.active_users, 0);
+        assert_eq!(report.churned_users, 0);
+        assert_eq!(report.buy_volume, TokenAmount::default());
+        assert_eq!(report.sell_volume, TokenAmount::default());
+        assert_eq!(report.contributor_emission, TokenAmount::default());
+        assert_eq!(report.contributor_sold, TokenAmount::default());
+        assert_eq!(report.fees, TokenAmount::default());
+        assert_eq!(report.block_rewards, TokenAmount::default());
+        assert_eq!(report.security_budget, FiatAmount::default());
+        assert!(!report.security_budget_below_threshold);
+        assert_eq!(report.tax_revenue, TokenAmount::default());
+        assert!(report.tax_distribution.is_empty());
+    }
+
+    #[test]
+    fn test_sell_pressure() {
+        let report = SimulationReport {
+            sell_volume: TokenAmount(Decimal::new(30, 0)),
+            contributor_sold: TokenAmount(Decimal::new(12, 0)),
+            unlock_sold: TokenAmount(Decimal::new(8, 0)),
+            ..Default::default()
+        };
+
+        assert_eq!(report.sell_pressure(), TokenAmount(Decimal::new(50, 0)));
+    }
+
+    #[test]
+    fn test_report_fields() {
+        let mut report = SimulationReport {
+            users: Some(vec![User::new(Uuid::new_v4(), Decimal::ONE)]),
+            tax_distribution: BTreeMap::from([(TaxDestination::Burn, Decimal::ONE)]),
+            custom_metrics: BTreeMap::from([("velocity".to_string(), Decimal::ONE)]),
+            retention_curve: Some(RetentionCurve::default()),
+            ..Default::default()
+        };
+
+        ReportFields::all().apply(&mut report);
+        assert!(report.users.is_some());
+        assert!(!report.custom_metrics.is_empty());
+
+        ReportFields {
+            custom_metrics: false,
+            ..Default::default()
+        }
+        .apply(&mut report);
+        assert!(report.custom_metrics.is_empty());
+        assert!(!report.tax_distribution.is_empty());
+
+        ReportFields::none().apply(&mut report);
+        assert!(report.users.is_none());
+        assert!(report.tax_distribution.is_empty());
+        assert!(report.retention_curve.is_none());
+    }
+
+    #[test]
+    fn test_organic_volume_share() {
+        let report = SimulationReport {
+            organic_volume: TokenAmount(Decimal::new(30, 0)),
+            inorganic_volume: TokenAmount(Decimal::new(90, 0)),
+            ..Default::default()
+        };
+
+        assert_eq!(report.organic_volume_share(4), Decimal::new(25, 2));
+        assert_eq!(
+            SimulationReport::default().organic_volume_share(4),
+            Decimal::default()
+        );
+    }
+
+    #[test]
+    fn test_calculate_security_budget() {
+        let report = SimulationReport {
+            block_rewards: TokenAmount(Decimal::new(50, 0)),
+            fees: TokenAmount(Decimal::new(10, 0)),
+            gas_spent: TokenAmount(Decimal::new(5, 0)),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            report.calculate_security_budget(FiatAmount(Decimal::new(2, 0)), 4),
+            FiatAmount(Decimal::new(130, 0))
+        );
     }
 
     #[test]
@@ -302,7 +1111,7 @@ mod tests {
         ];
 
         assert_eq!(
-            report.calculate_adoption_rate(&users, 4),
+            report.calculate_adoption_rate(&UserStore::from_users(&users), 4),
             Decimal::new(5, 1),
         );
     }
@@ -310,7 +1119,7 @@ mod tests {
     #[test]
     fn test_calculate_burn_rate() {
         let report = SimulationReport::default();
-        let total_burned = Decimal::new(100, 0);
+        let total_burned = TokenAmount(Decimal::new(100, 0));
         let total_users = Decimal::new(10, 0);
 
         assert_eq!(
@@ -319,10 +1128,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_calculate_rates_with_zero_denominator() {
+        let report = SimulationReport::default();
+
+        assert_eq!(
+            report.calculate_burn_rate(TokenAmount(Decimal::new(100, 0)), Decimal::default(), 4),
+            Decimal::default()
+        );
+        assert_eq!(
+            report.calculate_inflation_rate(
+                TokenAmount(Decimal::new(100, 0)),
+                Decimal::default(),
+                4
+            ),
+            Decimal::default()
+        );
+        assert_eq!(
+            report.calculate_adoption_rate(&UserStore::default(), 4),
+            Decimal::default()
+        );
+    }
+
     #[test]
     fn test_calculate_inflation_rate() {
         let report = SimulationReport::default();
-        let total_new_tokens = Decimal::new(100, 0);
+        let total_new_tokens = TokenAmount(Decimal::new(100, 0));
         let total_users = Decimal::new(10, 0);
 
         assert_eq!(
@@ -342,8 +1173,138 @@ mod tests {
         ];
 
         assert_eq!(
-            report.calculate_user_retention(&users, 4),
+            report.calculate_user_retention(&UserStore::from_users(&users), 4),
             Decimal::new(5, 1),
         );
     }
+
+    #[test]
+    fn test_calculate_user_retention_excludes_churned() {
+        let report = SimulationReport::default();
+        let mut users = vec![
+            User::new(Uuid::new_v4(), Decimal::new(10, 0)),
+            User::new(Uuid::new_v4(), Decimal::new(5, 0)),
+        ];
+        users[1].state = UserState::Churned;
+
+        assert_eq!(
+            report.calculate_user_retention(&UserStore::from_users(&users), 4),
+            Decimal::new(5, 1),
+        );
+    }
+
+    #[test]
+    fn test_calculate_active_and_churned_users() {
+        let report = SimulationReport::default();
+        let mut users = vec![
+            User::new(Uuid::new_v4(), Decimal::new(10, 0)),
+            User::new(Uuid::new_v4(), Decimal::new(5, 0)),
+            User::new(Uuid::new_v4(), Decimal::new(5, 0)),
+        ];
+        users[1].state = UserState::Dormant;
+        users[2].state = UserState::Churned;
+
+        assert_eq!(
+            report.calculate_active_users(&UserStore::from_users(&users)),
+            1
+        );
+        assert_eq!(
+            report.calculate_churned_users(&UserStore::from_users(&users)),
+            1
+        );
+    }
+
+    #[test]
+    fn test_calculate_holders() {
+        let report = SimulationReport::default();
+        let users = vec![
+            User::new(Uuid::new_v4(), Decimal::new(10, 0)),
+            User::new(Uuid::new_v4(), Decimal::default()),
+        ];
+
+        assert_eq!(report.calculate_holders(&UserStore::from_users(&users)), 1);
+    }
+
+    #[test]
+    fn test_calculate_holder_value() {
+        let report = SimulationReport::default();
+        let users = vec![
+            User::new(Uuid::new_v4(), Decimal::new(10, 0)),
+            User::new(Uuid::new_v4(), Decimal::new(30, 0)),
+            User::new(Uuid::new_v4(), Decimal::default()),
+        ];
+
+        assert_eq!(
+            report.calculate_holder_value(
+                &UserStore::from_users(&users),
+                FiatAmount(Decimal::new(2, 0)),
+                4
+            ),
+            FiatAmount(Decimal::new(40, 0))
+        );
+        assert_eq!(
+            report.calculate_holder_value(&UserStore::default(), FiatAmount(Decimal::new(2, 0)), 4),
+            FiatAmount::default()
+        );
+    }
+
+    #[test]
+    fn test_calculate_dilution_and_real_value() {
+        let mut report = SimulationReport::default();
+
+        let dilution = report.calculate_dilution(
+            TokenAmount(Decimal::new(1_000, 0)),
+            TokenAmount(Decimal::new(1_250, 0)),
+            4,
+        );
+        assert_eq!(dilution, Percentage(Decimal::new(25, 0)));
+        report.cumulative_dilution = dilution;
+        assert_eq!(
+            report.calculate_real_value(FiatAmount(Decimal::new(10, 0)), 4),
+            FiatAmount(Decimal::new(8, 0))
+        );
+
+        let dilution = report.calculate_dilution(
+            TokenAmount::default(),
+            TokenAmount(Decimal::new(1_250, 0)),
+            4,
+        );
+        assert_eq!(dilution, Percentage::default());
+
+        report.cumulative_dilution = Percentage(Decimal::new(-100, 0));
+        assert_eq!(
+            report.calculate_real_value(FiatAmount(Decimal::new(10, 0)), 4),
+            FiatAmount(Decimal::new(10, 0))
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let report = SimulationReport {
+            profit_loss: TokenAmount(Decimal::new(-125, 1)),
+            trades: 10,
+            tax_distribution: BTreeMap::from([(TaxDestination::Burn, Decimal::new(3, 0))]),
+            ..Default::default()
+        };
+
+        let json = serde_json::to_string(&report).unwrap();
+        assert_eq!(
+            serde_json::from_str::<SimulationReport>(&json).unwrap(),
+            report
+        );
+
+        // Users are not serialized
+        let report = SimulationReport {
+            users: Some(vec![User::new(Uuid::new_v4(), Decimal::new(10, 0))]),
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&report).unwrap();
+        assert_eq!(
+            serde_json::from_str::<SimulationReport>(&json)
+                .unwrap()
+                .users,
+            None
+        );
+    }
 }
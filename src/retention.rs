@@ -0,0 +1,255 @@
+//! # Retention module
+//!
+//! This module contains the retention curve of the simulation.
+//!
+//! Users are grouped in cohorts by the interval they were first seen in.
+//! The curve tracks the share of each cohort still active after each number of intervals,
+//! which shows the decay shape hidden by the averaged user retention.
+
+use std::collections::{hash_map::Entry, HashMap};
+
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{UserState, UserStore};
+
+/// Retention of a cohort of users.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct CohortRetention {
+    /// Interval in which the users of the cohort were first seen, starting at 0.
+    pub interval: u64,
+
+    /// Number of users in the cohort.
+    pub size: u64,
+
+    /// Percentage of the cohort still active after `k` intervals, at index `k`.
+    pub retention: Vec<Decimal>,
+}
+
+/// Retention curve of the simulation, per cohort.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct RetentionCurve {
+    /// Retention of each cohort, the initial users first.
+    pub cohorts: Vec<CohortRetention>,
+}
+
+impl RetentionCurve {
+    /// Get the retention curve of the initial users.
+    ///
+    /// # Returns
+    ///
+    /// Percentage of the initial users still active after `k` intervals, at index `k`.
+    pub fn initial(&self) -> &[Decimal] {
+        self.cohorts
+            .first()
+            .map(|cohort| cohort.retention.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Get the average retention curve over all cohorts, weighted by cohort size.
+    /// Each point only includes the cohorts old enough to have reached it.
+    ///
+    /// # Arguments
+    ///
+    /// * `decimals` - Number of decimal places to round to.
+    ///
+    /// # Returns
+    ///
+    /// Percentage of users still active after `k` intervals, at index `k`.
+    pub fn average(&self, decimals: u32) -> Vec<Decimal> {
+        let length = self
+            .cohorts
+            .iter()
+            .map(|cohort| cohort.retention.len())
+            .max()
+            .unwrap_or_default();
+
+        (0..length)
+            .map(|k| {
+                let (retained, size) = self
+                    .cohorts
+                    .iter()
+                    .filter_map(|cohort| {
+                        let size = Decimal::from(cohort.size);
+                        cohort
+                            .retention
+                            .get(k)
+                            .map(|retention| (retention * size, size))
+                    })
+                    .fold((Decimal::default(), Decimal::default()), |acc, item| {
+                        (acc.0 + item.0, acc.1 + item.1)
+                    });
+
+                retained
+                    .checked_div(size)
+                    .unwrap_or_default()
+                    .round_dp(decimals)
+            })
+            .collect()
+    }
+}
+
+/// Tracker of the cohorts during a simulation.
+#[derive(Debug, Default)]
+pub(crate) struct RetentionTracker {
+    /// Cohort of each user seen so far.
+    first_seen: HashMap<Uuid, usize>,
+
+    /// Interval, size, and number of active users after `k` intervals, per cohort.
+    cohorts: Vec<(u64, u64, Vec<u64>)>,
+
+    /// Number of intervals recorded.
+    intervals: u64,
+}
+
+impl RetentionTracker {
+    /// Record the users at the end of an interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `users` - Users at the end of the interval.
+    pub(crate) fn record(&mut self, users: &UserStore) {
+        let interval = self.intervals;
+        let cohort = self.cohorts.len();
+
+        let mut size = 0;
+        for id in &users.ids {
+            if let Entry::Vacant(entry) = self.first_seen.entry(*id) {
+                entry.insert(cohort);
+                size += 1;
+            }
+        }
+        if size > 0 {
+            self.cohorts.push((interval, size, vec![]));
+        }
+
+        for (_, _, active) in self.cohorts.iter_mut() {
+            active.push(0);
+        }
+        for (id, _) in users
+            .ids
+            .iter()
+            .zip(&users.states)
+            .filter(|(_, state)| **state == UserState::Active)
+        {
+            let (_, _, active) = &mut self.cohorts[self.first_seen[id]];
+            if let Some(count) = active.last_mut() {
+                *count += 1;
+            }
+        }
+
+        self.intervals += 1;
+    }
+
+    /// Build the retention curve of the recorded cohorts.
+    ///
+    /// # Arguments
+    ///
+    /// * `decimals` - Number of decimal places to round to.
+    ///
+    /// # Returns
+    ///
+    /// Retention curve, per cohort.
+    pub(crate) fn curve(&self, decimals: u32) -> RetentionCurve {
+        RetentionCurve {
+            cohorts: self
+                .cohorts
+                .iter()
+                .map(|(interval, size, active)| CohortRetention {
+                    interval: *interval,
+                    size: *size,
+                    retention: active
+                        .iter()
+                        .map(|count| {
+                            (Decimal::from(*count) * Decimal::new(100, 0) / Decimal::from(*size))
+                                .round_dp(decimals)
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::User;
+
+    use super::*;
+
+    #[test]
+    fn test_retention_tracker() {
+        let mut users: UserStore = (0..4)
+            .map(|_| User::new(Uuid::new_v4(), Decimal::ONE))
+            .collect();
+        let mut tracker = RetentionTracker::default();
+
+        tracker.record(&users);
+
+        users.states[0] = UserState::Churned;
+        users.push(User::new(Uuid::new_v4(), Decimal::ONE));
+        users.push(User::new(Uuid::new_v4(), Decimal::ONE));
+        tracker.record(&users);
+
+        users.states[1] = UserState::Dormant;
+        users.states[4] = UserState::Dormant;
+        tracker.record(&users);
+
+        let curve = tracker.curve(2);
+        assert_eq!(curve.cohorts.len(), 2);
+        assert_eq!(
+            curve.initial(),
+            &[
+                Decimal::new(100, 0),
+                Decimal::new(75, 0),
+                Decimal::new(50, 0)
+            ]
+        );
+        assert_eq!(curve.cohorts[1].interval, 1);
+        assert_eq!(curve.cohorts[1].size, 2);
+        assert_eq!(
+            curve.cohorts[1].retention,
+            vec![Decimal::new(100, 0), Decimal::new(50, 0)]
+        );
+
+        // Weighted by cohort size: (75 * 4 + 50 * 2) / 6
+        assert_eq!(
+            curve.average(2),
+            vec![
+                Decimal::new(100, 0),
+                Decimal::new(6667, 2),
+                Decimal::new(50, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_empty_curve() {
+        let curve = RetentionCurve::default();
+
+        assert!(curve.initial().is_empty());
+        assert!(curve.average(2).is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let curve = RetentionCurve {
+            cohorts: vec![CohortRetention {
+                interval: 0,
+                size: 10,
+                retention: vec![Decimal::new(100, 0), Decimal::new(80, 0)],
+            }],
+        };
+
+        let json = serde_json::to_string(&curve).unwrap();
+        assert_eq!(
+            serde_json::from_str::<RetentionCurve>(&json).unwrap(),
+            curve
+        );
+    }
+}
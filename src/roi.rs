@@ -0,0 +1,290 @@
+//! # ROI module
+//!
+//! This module contains the return on investment of the holder cohorts.
+//!
+//! Users are grouped in cohorts by the interval they joined in, and sale-round participants by allocation.
+//! Each cohort enters at the price of its interval, or at the sale price, and is valued at the price of the last interval,
+//! which shows whether early participants are underwater in a scenario.
+
+use std::ops::Range;
+
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Token, UserStore};
+
+/// Origin of a holder cohort.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CohortSource {
+    /// Users who joined in the given interval, starting at 0 for the initial users.
+    Interval(u64),
+
+    /// Participants of the sale round of the given allocation.
+    SaleRound(String),
+}
+
+/// Return on investment of a holder cohort.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct CohortRoi {
+    /// Origin of the cohort.
+    pub source: CohortSource,
+
+    /// Number of users in the cohort, zero for sale rounds.
+    pub size: u64,
+
+    /// Average price paid per token on entry.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub entry_price: Decimal,
+
+    /// Value of the balance of the cohort on entry.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub invested: Decimal,
+
+    /// Current balance of the cohort.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub balance: Decimal,
+
+    /// Value of the current balance, at the price of the last interval.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub current_value: Decimal,
+
+    /// Return on investment, in percentage.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub roi: Decimal,
+
+    /// Price at which the current balance is worth the invested value.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub breakeven_price: Decimal,
+}
+
+impl CohortRoi {
+    /// Value a cohort at the given price.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Origin of the cohort.
+    /// * `size` - Number of users in the cohort.
+    /// * `invested` - Value of the balance of the cohort on entry.
+    /// * `entry_balance` - Balance of the cohort on entry.
+    /// * `balance` - Current balance of the cohort.
+    /// * `price` - Current price of the token.
+    /// * `decimals` - Number of decimal places to round to.
+    ///
+    /// # Returns
+    ///
+    /// Return on investment of the cohort.
+    fn new(
+        source: CohortSource,
+        size: u64,
+        invested: Decimal,
+        entry_balance: Decimal,
+        balance: Decimal,
+        price: Decimal,
+        decimals: u32,
+    ) -> Self {
+        let current_value = balance * price;
+
+        CohortRoi {
+            source,
+            size,
+            entry_price: invested
+                .checked_div(entry_balance)
+                .unwrap_or_default()
+                .round_dp(decimals),
+            invested: invested.round_dp(decimals),
+            balance: balance.round_dp(decimals),
+            current_value: current_value.round_dp(decimals),
+            roi: ((current_value - invested) * Decimal::new(100, 0))
+                .checked_div(invested)
+                .unwrap_or_default()
+                .round_dp(decimals),
+            breakeven_price: invested
+                .checked_div(balance)
+                .unwrap_or_default()
+                .round_dp(decimals),
+        }
+    }
+
+    /// Check whether the cohort is worth less than it invested.
+    ///
+    /// # Returns
+    ///
+    /// Whether the current value is below the invested value.
+    pub fn is_underwater(&self) -> bool {
+        self.current_value < self.invested
+    }
+}
+
+/// Tracker of the holder cohorts during a simulation.
+#[derive(Debug, Default)]
+pub(crate) struct RoiTracker {
+    /// Interval, range of the users, invested value, and balance on entry, per cohort.
+    cohorts: Vec<(u64, Range<usize>, Decimal, Decimal)>,
+}
+
+impl RoiTracker {
+    /// Record the users who joined since the last record, entering at the given price.
+    /// Users joining in the same interval are merged into one cohort.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - Index of the interval.
+    /// * `users` - Users of the simulation, new users last.
+    /// * `price` - Price of the token on entry.
+    pub(crate) fn record(&mut self, interval: u64, users: &UserStore, price: Decimal) {
+        let start = self.cohorts.last().map_or(0, |(_, range, _, _)| range.end);
+        if start >= users.len() {
+            return;
+        }
+
+        let balance: Decimal = users.balances[start..].iter().sum();
+
+        match self.cohorts.last_mut() {
+            Some((last, range, invested, entry_balance)) if *last == interval => {
+                range.end = users.len();
+                *invested += balance * price;
+                *entry_balance += balance;
+            }
+            _ => self
+                .cohorts
+                .push((interval, start..users.len(), balance * price, balance)),
+        }
+    }
+
+    /// Value the recorded cohorts and the sale rounds of the token.
+    ///
+    /// # Arguments
+    ///
+    /// * `users` - Users at the end of the simulation.
+    /// * `token` - Token of the simulation, with its sale rounds.
+    /// * `price` - Price of the token in the last interval.
+    /// * `decimals` - Number of decimal places to round to.
+    ///
+    /// # Returns
+    ///
+    /// Return on investment of each cohort, the user cohorts first.
+    pub(crate) fn roi(
+        &self,
+        users: &UserStore,
+        token: &Token,
+        price: Decimal,
+        decimals: u32,
+    ) -> Vec<CohortRoi> {
+        let cohorts = self
+            .cohorts
+            .iter()
+            .map(|(interval, range, invested, entry_balance)| {
+                CohortRoi::new(
+                    CohortSource::Interval(*interval),
+                    users.weights[range.clone()].iter().sum(),
+                    *invested,
+                    *entry_balance,
+                    users.balances[range.clone()].iter().sum(),
+                    price,
+                    decimals,
+                )
+            });
+
+        let sale_rounds = token.allocations.iter().filter_map(|allocation| {
+            let amount = allocation.vesting.total_amount;
+
+            allocation.price.map(|sale_price| {
+                CohortRoi::new(
+                    CohortSource::SaleRound(allocation.name.clone()),
+                    0,
+                    amount * sale_price,
+                    amount,
+                    amount,
+                    price,
+                    decimals,
+                )
+            })
+        });
+
+        cohorts.chain(sale_rounds).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::{Simulation, User, VestingScheduleBuilder};
+
+    use super::*;
+
+    #[test]
+    fn test_roi_tracker() {
+        let mut users: UserStore = (0..2)
+            .map(|_| User::new(Uuid::new_v4(), Decimal::new(10, 0)))
+            .collect();
+        let mut tracker = RoiTracker::default();
+
+        tracker.record(0, &users, Decimal::ONE);
+        users.push(User::new(Uuid::new_v4(), Decimal::new(10, 0)));
+        tracker.record(0, &users, Decimal::new(4, 0));
+        users.push(User::new(Uuid::new_v4(), Decimal::new(10, 0)));
+        tracker.record(1, &users, Decimal::new(4, 0));
+        tracker.record(2, &users, Decimal::new(8, 0));
+
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let roi = tracker.roi(&users, &token, Decimal::new(2, 0), 4);
+
+        assert_eq!(roi.len(), 2);
+        assert_eq!(roi[0].source, CohortSource::Interval(0));
+        assert_eq!(roi[0].size, 3);
+        assert_eq!(roi[0].entry_price, Decimal::new(2, 0));
+        assert_eq!(roi[0].roi, Decimal::default());
+        assert!(!roi[0].is_underwater());
+        assert_eq!(roi[1].source, CohortSource::Interval(1));
+        assert_eq!(roi[1].roi, Decimal::new(-50, 0));
+        assert_eq!(roi[1].breakeven_price, Decimal::new(4, 0));
+        assert!(roi[1].is_underwater());
+    }
+
+    #[test]
+    fn test_run_reports_cohort_roi() {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .sale_round(
+                "Seed",
+                10.0,
+                0.1,
+                VestingScheduleBuilder::four_year_linear_with_one_year_cliff(),
+            )
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(20)
+            .duration(5)
+            .adoption_rate(0.1)
+            .build()
+            .unwrap();
+        let mut simulation = Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap();
+
+        simulation.run().unwrap();
+
+        let roi = &simulation.report.cohort_roi;
+        assert_eq!(roi[0].source, CohortSource::Interval(0));
+        assert!(roi[0].size > 20);
+        assert!(roi[0].entry_price > Decimal::default());
+        assert!(roi.len() > 2);
+        let seed = roi.last().unwrap();
+        assert_eq!(seed.source, CohortSource::SaleRound("Seed".to_string()));
+        assert_eq!(seed.entry_price, Decimal::new(1, 1));
+        assert_eq!(seed.balance, Decimal::new(100_000, 0));
+        assert!(simulation.interval_reports[0].cohort_roi.is_empty());
+    }
+}
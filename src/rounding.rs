@@ -0,0 +1,327 @@
+//! # Rounding module
+//!
+//! This module contains the rounding policy of the simulation.
+//!
+//! Values are computed at full precision and rounded once, when they are stored on a user or reported,
+//! at the precision of their kind: prices, balances (token amounts and their value), or rates (percentages and ratios).
+//! Each kind defaults to the `decimal_precision` option.
+//! The rounding mode defaults to half-even (bankers' rounding), which does not bias sums of many rounded values.
+//! Nested breakdowns of the reports (venues, cohorts, curves) are rounded half-even at the precision of their kind.
+
+use rust_decimal::{Decimal, RoundingStrategy};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{FiatAmount, Percentage, SimulationOptions, SimulationReport, TokenAmount};
+
+/// Precision passed to the report calculations whose result is rounded afterwards by the policy.
+pub(crate) const UNROUNDED: u32 = 28;
+
+/// Rounding mode applied to the midpoint between two representable values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum RoundingMode {
+    /// Round the midpoint to the even neighbour, e.g. 2.5 to 2 and 3.5 to 4 (bankers' rounding).
+    #[default]
+    HalfEven,
+
+    /// Round the midpoint away from zero, e.g. 2.5 to 3 and -2.5 to -3.
+    HalfUp,
+
+    /// Truncate towards zero, e.g. 2.9 to 2.
+    Down,
+}
+
+impl RoundingMode {
+    /// Get the rounding strategy of the mode.
+    ///
+    /// # Returns
+    ///
+    /// Rounding strategy of `rust_decimal`.
+    fn strategy(&self) -> RoundingStrategy {
+        match self {
+            RoundingMode::HalfEven => RoundingStrategy::MidpointNearestEven,
+            RoundingMode::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+            RoundingMode::Down => RoundingStrategy::ToZero,
+        }
+    }
+}
+
+/// Kind of a rounded value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueKind {
+    /// Price of the token.
+    Price,
+
+    /// Amount of tokens, or value of an amount of tokens.
+    Balance,
+
+    /// Percentage or ratio.
+    Rate,
+}
+
+/// Rounding policy of the simulation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct RoundingPolicy {
+    /// Rounding mode.
+    /// Default is half-even.
+    pub mode: RoundingMode,
+
+    /// Number of decimal places of the prices.
+    /// If not set, the `decimal_precision` option is used.
+    pub price_precision: Option<u32>,
+
+    /// Number of decimal places of the balances.
+    /// If not set, the `decimal_precision` option is used.
+    pub balance_precision: Option<u32>,
+
+    /// Number of decimal places of the rates.
+    /// If not set, the `decimal_precision` option is used.
+    pub rate_precision: Option<u32>,
+}
+
+impl SimulationOptions {
+    /// Get the number of decimal places of a kind of value.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - Kind of the value.
+    ///
+    /// # Returns
+    ///
+    /// Number of decimal places.
+    pub fn precision(&self, kind: ValueKind) -> u32 {
+        let precision = match kind {
+            ValueKind::Price => self.rounding.price_precision,
+            ValueKind::Balance => self.rounding.balance_precision,
+            ValueKind::Rate => self.rounding.rate_precision,
+        };
+
+        precision.unwrap_or(self.decimal_precision)
+    }
+
+    /// Round a value with the rounding policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Value to round.
+    /// * `kind` - Kind of the value.
+    ///
+    /// # Returns
+    ///
+    /// Rounded value.
+    pub fn round(&self, value: Decimal, kind: ValueKind) -> Decimal {
+        value.round_dp_with_strategy(self.precision(kind), self.rounding.mode.strategy())
+    }
+
+    /// Round the scalar metrics of a report with the rounding policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `report` - Report to round.
+    pub(crate) fn round_report(&self, report: &mut SimulationReport) {
+        for price in [&mut report.token_price, &mut report.real_token_price] {
+            *price = price.round_as(self, ValueKind::Price);
+        }
+
+        for balance in [
+            &mut report.profit_loss,
+            &mut report.total_burned,
+            &mut report.total_new_tokens,
+            &mut report.buy_volume,
+            &mut report.sell_volume,
+            &mut report.organic_volume,
+            &mut report.inorganic_volume,
+            &mut report.real_volume,
+            &mut report.contributor_emission,
+            &mut report.contributor_sold,
+            &mut report.unlock_sold,
+            &mut report.fees,
+            &mut report.gas_spent,
+            &mut report.mev_extracted,
+            &mut report.block_rewards,
+            &mut report.tax_revenue,
+            &mut report.utility_consumed,
+            &mut report.utility_locked,
+            &mut report.recurring_revenue,
+            &mut report.nft_volume,
+            &mut report.nft_royalties,
+            &mut report.game_rewards,
+            &mut report.game_sinks,
+            &mut report.gauge_emission,
+            &mut report.insurance_fund,
+            &mut report.shortfall_losses,
+            &mut report.insurance_payouts,
+            &mut report.supply_adjustment,
+            &mut report.holder_fees,
+            &mut report.treasury_fees,
+            &mut report.tax_sold,
+            &mut report.forced_sales,
+        ] {
+            *balance = balance.round_as(self, ValueKind::Balance);
+        }
+
+        for value in [
+            &mut report.capital_inflow,
+            &mut report.net_inflow,
+            &mut report.security_budget,
+            &mut report.holder_value,
+            &mut report.real_holder_value,
+            &mut report.recurring_revenue_fiat,
+        ] {
+            *value = value.round_as(self, ValueKind::Balance);
+        }
+
+        for rate in [
+            &mut report.market_volatility,
+            &mut report.liquidity,
+            &mut report.adoption_rate,
+            &mut report.burn_rate,
+            &mut report.inflation_rate,
+            &mut report.user_retention,
+            &mut report.stickiness,
+        ] {
+            *rate = self.round(*rate, ValueKind::Rate);
+        }
+
+        for percentage in [
+            &mut report.average_slippage,
+            &mut report.cumulative_dilution,
+        ] {
+            *percentage = percentage.round_as(self, ValueKind::Rate);
+        }
+    }
+}
+
+/// Rounding of a value with the rounding policy of the options.
+pub(crate) trait RoundAs {
+    /// Round the value with the rounding policy of the options.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Options of the simulation.
+    /// * `kind` - Kind of the value.
+    ///
+    /// # Returns
+    ///
+    /// Rounded value.
+    fn round_as(self, options: &SimulationOptions, kind: ValueKind) -> Self;
+}
+
+impl RoundAs for Decimal {
+    fn round_as(self, options: &SimulationOptions, kind: ValueKind) -> Self {
+        options.round(self, kind)
+    }
+}
+
+impl RoundAs for TokenAmount {
+    fn round_as(self, options: &SimulationOptions, kind: ValueKind) -> Self {
+        TokenAmount(options.round(self.0, kind))
+    }
+}
+
+impl RoundAs for FiatAmount {
+    fn round_as(self, options: &SimulationOptions, kind: ValueKind) -> Self {
+        FiatAmount(options.round(self.0, kind))
+    }
+}
+
+impl RoundAs for Percentage {
+    fn round_as(self, options: &SimulationOptions, kind: ValueKind) -> Self {
+        Percentage(options.round(self.0, kind))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Simulation;
+
+    use super::*;
+
+    #[test]
+    fn test_round() {
+        let mut options = Simulation::options_builder()
+            .total_users(10)
+            .decimal_precision(1)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            options.round(Decimal::new(25, 2), ValueKind::Rate),
+            Decimal::new(2, 1)
+        );
+
+        options.rounding.mode = RoundingMode::HalfUp;
+        assert_eq!(
+            options.round(Decimal::new(25, 2), ValueKind::Rate),
+            Decimal::new(3, 1)
+        );
+        assert_eq!(
+            options.round(Decimal::new(-25, 2), ValueKind::Rate),
+            Decimal::new(-3, 1)
+        );
+
+        options.rounding.mode = RoundingMode::Down;
+        assert_eq!(
+            options.round(Decimal::new(29, 2), ValueKind::Rate),
+            Decimal::new(2, 1)
+        );
+
+        options.rounding.price_precision = Some(3);
+        assert_eq!(options.precision(ValueKind::Price), 3);
+        assert_eq!(options.precision(ValueKind::Balance), 1);
+        assert_eq!(
+            options.round(Decimal::new(123_456, 5), ValueKind::Price),
+            Decimal::new(1_234, 3)
+        );
+    }
+
+    #[test]
+    fn test_run_with_rounding_policy() {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(20)
+            .duration(3)
+            .market_volatility(0.5)
+            .rounding(RoundingPolicy {
+                mode: RoundingMode::Down,
+                price_precision: Some(6),
+                balance_precision: Some(2),
+                rate_precision: Some(1),
+            })
+            .build()
+            .unwrap();
+        let mut simulation = Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap();
+
+        simulation.run().unwrap();
+
+        for report in simulation
+            .interval_reports
+            .iter()
+            .chain([&simulation.report])
+        {
+            assert!(report.token_price.value().scale() <= 6);
+            assert!(report.buy_volume.value().scale() <= 2);
+            assert!(report.burn_rate.scale() <= 1);
+            assert!(report.user_retention.scale() <= 1);
+        }
+        assert!(simulation
+            .report
+            .users
+            .as_ref()
+            .unwrap()
+            .iter()
+            .all(|user| user.balance.scale() <= 2));
+    }
+}
@@ -0,0 +1,171 @@
+//! # Runner module
+//!
+//! This module contains the helpers to run many simulations concurrently.
+//!
+//! Simulations are `Send + Sync`: observers and metric plugins are required to be thread-safe,
+//! and every run only mutates its own simulation. The runner spreads the simulations over a
+//! bounded pool of scoped threads, so sweeps and Monte Carlo runs use all available cores.
+
+use std::{
+    num::NonZeroUsize,
+    sync::{Mutex, PoisonError},
+    thread,
+};
+
+use crate::{Simulation, SimulationError, SimulationOptions, Token};
+
+impl Simulation {
+    /// Run many simulations concurrently.
+    /// Each simulation runs on one of at most `concurrency` worker threads, results are returned in the input order.
+    ///
+    /// # Arguments
+    ///
+    /// * `simulations` - Simulations to run.
+    /// * `concurrency` - Maximum number of simulations running at the same time, 0 to use the available parallelism.
+    ///
+    /// # Returns
+    ///
+    /// The completed simulations, or the error of each failed simulation.
+    pub fn run_many(
+        simulations: Vec<Simulation>,
+        concurrency: usize,
+    ) -> Vec<Result<Simulation, SimulationError>> {
+        let total = simulations.len();
+        let concurrency = match concurrency {
+            0 => thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            concurrency => concurrency,
+        };
+        let workers = concurrency.min(total);
+
+        #[cfg(feature = "log")]
+        log::debug!("Running {} simulations on {} workers", total, workers);
+
+        let queue = Mutex::new(simulations.into_iter().enumerate());
+        let results = Mutex::new((0..total).map(|_| None).collect::<Vec<_>>());
+
+        thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| loop {
+                    let next = queue.lock().unwrap_or_else(PoisonError::into_inner).next();
+                    let Some((index, mut simulation)) = next else {
+                        break;
+                    };
+
+                    let result = simulation.run().map(|_| simulation);
+                    results.lock().unwrap_or_else(PoisonError::into_inner)[index] = Some(result);
+                });
+            }
+        });
+
+        results
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+            .into_iter()
+            .map(|result| result.expect("every simulation is run by a worker"))
+            .collect()
+    }
+
+    /// Run fresh copies of the simulation concurrently, with the given tokens and options.
+    /// The copies start in the pending state, the simulation itself is not modified.
+    ///
+    /// # Arguments
+    ///
+    /// * `variants` - Token and input parameters used in each copy.
+    ///
+    /// # Returns
+    ///
+    /// The completed copies of the simulation, in order, or the first error.
+    pub(crate) fn run_variants(
+        &self,
+        variants: Vec<(Token, SimulationOptions)>,
+    ) -> Result<Vec<Simulation>, SimulationError> {
+        let simulations = variants
+            .into_iter()
+            .map(|(token, options)| self.fork().token(token).options(options).build())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Simulation::run_many(simulations, 0).into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EthereumFee, SimulationStatus, SimulationTransactionFee};
+
+    fn setup(total_users: u64) -> Simulation {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .total_supply(1_000_000)
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(total_users)
+            .duration(5)
+            .build()
+            .unwrap();
+
+        Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_simulation_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<Simulation>();
+    }
+
+    #[test]
+    fn test_run_many() {
+        let simulations = (1..=5).map(setup).collect::<Vec<_>>();
+        let ids = simulations
+            .iter()
+            .map(|simulation| simulation.id)
+            .collect::<Vec<_>>();
+
+        let results = Simulation::run_many(simulations, 2);
+
+        assert_eq!(results.len(), 5);
+        for (index, result) in results.into_iter().enumerate() {
+            let simulation = result.unwrap();
+            assert_eq!(simulation.id, ids[index]);
+            assert_eq!(simulation.status, SimulationStatus::Completed);
+            assert_eq!(simulation.report.final_user_count, index as u64 + 1);
+        }
+    }
+
+    #[test]
+    fn test_run_many_reports_errors() {
+        let mut failing = setup(10);
+        failing.options.transaction_fee = Some(SimulationTransactionFee::Ethereum(EthereumFee {
+            gas_used: 21_000,
+            gas_price: None,
+            ether_price: None,
+        }));
+
+        let results = Simulation::run_many(vec![setup(10), failing], 0);
+
+        assert!(results[0].is_ok());
+        assert_eq!(
+            results[1].as_ref().unwrap_err(),
+            &SimulationError::UnresolvedTransactionFee
+        );
+        assert!(Simulation::run_many(vec![], 4).is_empty());
+    }
+
+    #[test]
+    fn test_run_variants() {
+        let simulation = setup(10);
+        let variants = vec![(simulation.token.clone(), simulation.options.clone()); 3];
+
+        let simulations = simulation.run_variants(variants).unwrap();
+
+        assert_eq!(simulations.len(), 3);
+        assert_eq!(simulation.status, SimulationStatus::Pending);
+    }
+}
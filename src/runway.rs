@@ -0,0 +1,426 @@
+//! # Runway module
+//!
+//! This module contains the treasury runway analysis.
+//!
+//! Given a treasury balance and a monthly spending plan, the runway is the number of months
+//! the treasury can fund the plan under the simulated price path.
+//! Running the analysis over many Monte Carlo runs gives a distribution of runways.
+
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{stats, Simulation, SimulationError, TaxDestination};
+
+/// Maximum runway, in months, when extrapolating beyond the simulated horizon.
+const MAX_RUNWAY_MONTHS: u64 = 1200;
+
+/// Number of hours in a month, used to convert intervals to months.
+const HOURS_PER_MONTH: u64 = 24 * 30;
+
+/// Currency of the treasury spending.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum SpendingCurrency {
+    /// Spending is denominated in tokens.
+    Token,
+
+    /// Spending is denominated in fiat, and paid by selling tokens at the simulated price.
+    Fiat,
+}
+
+/// Treasury spending plan.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct TreasuryPlan {
+    /// Initial balance of the treasury, in tokens.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub initial_balance: Decimal,
+
+    /// Monthly spending schedule.
+    /// The first entry is the spending of the first month, the last entry is repeated once the schedule ends.
+    pub monthly_spending: Vec<Decimal>,
+
+    /// Currency of the spending.
+    pub currency: SpendingCurrency,
+
+    /// Whether the taxes routed to the treasury are added to the balance.
+    pub include_tax_revenue: bool,
+}
+
+/// Runway of the treasury for a single simulation run.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Runway {
+    /// Runway, in months.
+    /// Runway beyond the simulated horizon is extrapolated with the last simulated price, capped at 100 years.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub months: Decimal,
+
+    /// Whether the treasury was depleted within the simulated horizon.
+    pub depleted: bool,
+
+    /// Treasury balance at the end of each interval, in tokens.
+    pub balances: Vec<Decimal>,
+}
+
+/// Distribution of the treasury runway across Monte Carlo runs.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct RunwayReport {
+    /// Runway of each run, in months, sorted in ascending order.
+    pub runways: Vec<Decimal>,
+
+    /// Mean runway, in months.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub mean: Decimal,
+
+    /// 5th percentile of the runway, in months.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub p5: Decimal,
+
+    /// Median runway, in months.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub p50: Decimal,
+
+    /// 95th percentile of the runway, in months.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub p95: Decimal,
+
+    /// Share of runs where the treasury was depleted within the simulated horizon, between 0 and 1.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub depletion_probability: Decimal,
+}
+
+impl TreasuryPlan {
+    /// Create a new treasury spending plan.
+    /// Taxes routed to the treasury are added to the balance.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial_balance` - Initial balance of the treasury, in tokens.
+    /// * `monthly_spending` - Monthly spending schedule.
+    /// * `currency` - Currency of the spending.
+    ///
+    /// # Returns
+    ///
+    /// New treasury spending plan.
+    pub fn new(
+        initial_balance: Decimal,
+        monthly_spending: Vec<Decimal>,
+        currency: SpendingCurrency,
+    ) -> Self {
+        TreasuryPlan {
+            initial_balance,
+            monthly_spending,
+            currency,
+            include_tax_revenue: true,
+        }
+    }
+
+    /// Get the spending for the given month.
+    ///
+    /// # Arguments
+    ///
+    /// * `month` - Index of the month, starting at 0.
+    ///
+    /// # Returns
+    ///
+    /// Spending of the month, in the currency of the plan.
+    pub fn spending_at(&self, month: u64) -> Decimal {
+        let index = usize::try_from(month).unwrap_or(usize::MAX);
+
+        self.monthly_spending
+            .get(index)
+            .or(self.monthly_spending.last())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Calculate the runway of the treasury for a completed simulation.
+    ///
+    /// # Arguments
+    ///
+    /// * `simulation` - A completed simulation.
+    ///
+    /// # Returns
+    ///
+    /// Runway of the treasury.
+    pub fn runway(&self, simulation: &Simulation) -> Runway {
+        let months_per_interval =
+            Decimal::from(simulation.get_interval()) / Decimal::from(HOURS_PER_MONTH);
+
+        let mut balance = self.initial_balance;
+        let mut elapsed = Decimal::default();
+        let mut balances = vec![];
+        let mut price = simulation.token.initial_price.value();
+
+        for report in simulation.interval_reports.iter() {
+            price = report.token_price.value();
+
+            if self.include_tax_revenue {
+                if let Some(tax) = report.tax_distribution.get(&TaxDestination::Treasury) {
+                    balance += tax;
+                }
+            }
+
+            let month = u64::try_from(elapsed.floor()).unwrap_or_default();
+            let spending = self
+                .to_tokens(self.spending_at(month), price)
+                .map(|spending| spending * months_per_interval);
+
+            match spending {
+                Some(spending) if spending <= balance => {
+                    balance -= spending;
+                    elapsed += months_per_interval;
+                    balances.push(balance);
+                }
+                spending => {
+                    let covered = spending
+                        .and_then(|spending| balance.checked_div(spending))
+                        .unwrap_or_default();
+                    balances.push(Decimal::default());
+
+                    return Runway {
+                        months: elapsed + covered * months_per_interval,
+                        depleted: true,
+                        balances,
+                    };
+                }
+            }
+        }
+
+        // Extrapolate the runway beyond the simulated horizon with the last price
+        let mut months = elapsed;
+        while months < Decimal::from(MAX_RUNWAY_MONTHS) {
+            let month = u64::try_from(months.floor()).unwrap_or_default();
+
+            match self.to_tokens(self.spending_at(month), price) {
+                Some(spending) if spending < balance => {
+                    balance -= spending;
+                    months += Decimal::ONE;
+                }
+                Some(spending) => {
+                    months += balance.checked_div(spending).unwrap_or_default();
+                    break;
+                }
+                None => break,
+            }
+        }
+
+        Runway {
+            months: months.min(Decimal::from(MAX_RUNWAY_MONTHS)),
+            depleted: false,
+            balances,
+        }
+    }
+
+    /// Convert the spending to tokens.
+    ///
+    /// # Arguments
+    ///
+    /// * `spending` - Spending, in the currency of the plan.
+    /// * `price` - Price of the token.
+    ///
+    /// # Returns
+    ///
+    /// Spending in tokens, or `None` if fiat spending cannot be paid at a zero price.
+    fn to_tokens(&self, spending: Decimal, price: Decimal) -> Option<Decimal> {
+        match self.currency {
+            SpendingCurrency::Token => Some(spending),
+            SpendingCurrency::Fiat if spending.is_zero() => Some(spending),
+            SpendingCurrency::Fiat => spending.checked_div(price),
+        }
+    }
+}
+
+impl RunwayReport {
+    /// Summarize the runways of many simulation runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `runways` - Runway of each run.
+    ///
+    /// # Returns
+    ///
+    /// Distribution of the treasury runway.
+    pub fn from_runways(runways: &[Runway]) -> Self {
+        let mut months = runways.iter().map(|r| r.months).collect::<Vec<_>>();
+        months.sort();
+
+        let depleted = runways.iter().filter(|r| r.depleted).count();
+
+        RunwayReport {
+            mean: stats::mean(&months),
+            p5: stats::percentile(&months, Decimal::new(5, 0)),
+            p50: stats::percentile(&months, Decimal::new(50, 0)),
+            p95: stats::percentile(&months, Decimal::new(95, 0)),
+            depletion_probability: Decimal::from(depleted)
+                .checked_div(Decimal::from(runways.len()))
+                .unwrap_or_default(),
+            runways: months,
+        }
+    }
+}
+
+impl Simulation {
+    /// Run the treasury runway analysis over many Monte Carlo runs.
+    /// Each run starts from the current token and options of the simulation, the simulation itself is not modified.
+    ///
+    /// # Arguments
+    ///
+    /// * `plan` - Treasury spending plan.
+    /// * `runs` - Number of Monte Carlo runs.
+    ///
+    /// # Returns
+    ///
+    /// Distribution of the treasury runway.
+    pub fn runway_report(
+        &self,
+        plan: &TreasuryPlan,
+        runs: u64,
+    ) -> Result<RunwayReport, SimulationError> {
+        if runs == 0 {
+            return Err(SimulationError::InvalidRuns);
+        }
+
+        let variants = (0..runs)
+            .map(|_| (self.token.clone(), self.options.clone()))
+            .collect();
+        let runways = self
+            .run_variants(variants)?
+            .iter()
+            .map(|simulation| plan.runway(simulation))
+            .collect::<Vec<_>>();
+
+        Ok(RunwayReport::from_runways(&runways))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ValuationModel;
+
+    use super::*;
+
+    fn setup(total_supply: i64) -> Simulation {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .total_supply(total_supply)
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .valuation_model(ValuationModel::RandomWalk)
+            .total_users(10)
+            .duration(30)
+            .market_volatility(0.0)
+            .build()
+            .unwrap();
+
+        Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_spending_at() {
+        let plan = TreasuryPlan::new(
+            Decimal::new(100, 0),
+            vec![Decimal::new(10, 0), Decimal::new(20, 0)],
+            SpendingCurrency::Token,
+        );
+
+        assert_eq!(plan.spending_at(0), Decimal::new(10, 0));
+        assert_eq!(plan.spending_at(1), Decimal::new(20, 0));
+        assert_eq!(plan.spending_at(12), Decimal::new(20, 0));
+    }
+
+    #[test]
+    fn test_runway_extrapolated() {
+        let mut simulation = setup(1_000);
+        simulation.run().unwrap();
+
+        let plan = TreasuryPlan::new(
+            Decimal::new(1200, 0),
+            vec![Decimal::new(100, 0)],
+            SpendingCurrency::Fiat,
+        );
+        let runway = plan.runway(&simulation);
+
+        assert!(!runway.depleted);
+        assert_eq!(runway.months.round_dp(4), Decimal::new(12, 0));
+        assert_eq!(runway.balances.len(), 30);
+    }
+
+    #[test]
+    fn test_runway_depleted() {
+        let mut simulation = setup(1_000);
+        simulation.run().unwrap();
+
+        let plan = TreasuryPlan::new(
+            Decimal::new(50, 0),
+            vec![Decimal::new(100, 0)],
+            SpendingCurrency::Token,
+        );
+        let runway = plan.runway(&simulation);
+
+        assert!(runway.depleted);
+        assert_eq!(runway.months.round_dp(4), Decimal::new(5, 1));
+    }
+
+    #[test]
+    fn test_runway_report() {
+        let simulation = setup(1_000);
+        let plan = TreasuryPlan::new(
+            Decimal::new(1200, 0),
+            vec![Decimal::new(100, 0)],
+            SpendingCurrency::Token,
+        );
+
+        let report = simulation.runway_report(&plan, 5).unwrap();
+
+        assert_eq!(report.runways.len(), 5);
+        assert_eq!(report.p50.round_dp(4), Decimal::new(12, 0));
+        assert_eq!(report.depletion_probability, Decimal::default());
+        assert!(simulation.interval_reports.is_empty());
+    }
+
+    #[test]
+    fn test_runway_report_invalid_runs() {
+        let simulation = setup(1_000);
+        let plan = TreasuryPlan::new(Decimal::new(1, 0), vec![], SpendingCurrency::Token);
+
+        assert_eq!(
+            simulation.runway_report(&plan, 0),
+            Err(SimulationError::InvalidRuns)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let plan = TreasuryPlan::new(
+            Decimal::new(1_000, 0),
+            vec![Decimal::new(100, 0)],
+            SpendingCurrency::Fiat,
+        );
+        let json = serde_json::to_string(&plan).unwrap();
+        assert_eq!(serde_json::from_str::<TreasuryPlan>(&json).unwrap(), plan);
+
+        let runway = Runway {
+            months: Decimal::new(125, 1),
+            depleted: true,
+            balances: vec![Decimal::new(500, 0), Decimal::default()],
+        };
+        let json = serde_json::to_string(&runway).unwrap();
+        assert_eq!(serde_json::from_str::<Runway>(&json).unwrap(), runway);
+
+        let report = RunwayReport::from_runways(&[runway]);
+        let json = serde_json::to_string(&report).unwrap();
+        assert_eq!(serde_json::from_str::<RunwayReport>(&json).unwrap(), report);
+    }
+}
@@ -0,0 +1,355 @@
+//! # Schema module
+//!
+//! This module contains the versioning of the serialization format.
+//!
+//! Serialized simulations and reports carry a `schema_version` field.
+//! Data written by older versions of the crate is migrated step by step to the current schema before it is deserialized,
+//! so checkpoints and stored scenarios can still be loaded after struct changes.
+
+#[cfg(feature = "serde")]
+use serde_json::{json, Map, Value};
+
+#[cfg(feature = "serde")]
+use crate::{Simulation, SimulationError, SimulationReport};
+
+/// Current version of the serialization format.
+/// Data without a `schema_version` field was written before versioning and is treated as version 0.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Migrate a serialized simulation to the current schema.
+///
+/// # Arguments
+///
+/// * `value` - Serialized simulation, in any supported schema version.
+///
+/// # Returns
+///
+/// Serialized simulation in the current schema, or an error if the schema version is not supported.
+#[cfg(feature = "serde")]
+pub fn migrate_simulation(mut value: Value) -> Result<Value, SimulationError> {
+    let version = schema_version(&value)?;
+
+    if version < 1 {
+        let simulation = object(&mut value)?;
+
+        if let Some(options) = simulation.get_mut("options").and_then(Value::as_object_mut) {
+            migrate_options_v0(options);
+        }
+
+        if let Some(reports) = simulation
+            .get_mut("interval_reports")
+            .and_then(Value::as_array_mut)
+        {
+            for report in reports.iter_mut() {
+                *report = migrate_report(report.take())?;
+            }
+        }
+
+        if let Some(report) = simulation.get_mut("report") {
+            *report = migrate_report(report.take())?;
+        }
+    }
+
+    if version < 2 {
+        // Statuses are snake case since version 2
+        if let Some(Value::String(status)) = object(&mut value)?.get_mut("status") {
+            *status = status.to_lowercase();
+        }
+    }
+
+    object(&mut value)?.insert("schema_version".to_string(), json!(SCHEMA_VERSION));
+
+    Ok(value)
+}
+
+/// Migrate a serialized report to the current schema.
+///
+/// # Arguments
+///
+/// * `value` - Serialized report, in any supported schema version.
+///
+/// # Returns
+///
+/// Serialized report in the current schema, or an error if the schema version is not supported.
+#[cfg(feature = "serde")]
+pub fn migrate_report(mut value: Value) -> Result<Value, SimulationError> {
+    let version = schema_version(&value)?;
+    let report = object(&mut value)?;
+
+    if version < 1 {
+        // Metrics added in version 1
+        for field in [
+            "active_users",
+            "churned_users",
+            "final_user_count",
+            "peak_user_count",
+            "buy_volume",
+            "sell_volume",
+            "contributor_emission",
+            "contributor_sold",
+            "fees",
+            "gas_spent",
+            "block_rewards",
+            "security_budget",
+            "tax_revenue",
+        ] {
+            report.entry(field).or_insert(json!(0));
+        }
+        report
+            .entry("security_budget_below_threshold")
+            .or_insert(json!(false));
+        report.entry("tax_distribution").or_insert(json!({}));
+    }
+
+    report.insert("schema_version".to_string(), json!(SCHEMA_VERSION));
+
+    Ok(value)
+}
+
+/// Migrate serialized input parameters from version 0 to version 1.
+///
+/// # Arguments
+///
+/// * `options` - Serialized input parameters.
+#[cfg(feature = "serde")]
+fn migrate_options_v0(options: &mut Map<String, Value>) {
+    if let Some(fee) = options.remove("transaction_fee") {
+        options.entry("transaction_fee_percentage").or_insert(fee);
+    }
+
+    for field in [
+        "transaction_fee_percentage",
+        "adoption_rate",
+        "valuation_model",
+        "behaviour_mix",
+        "lifecycle",
+    ] {
+        options.entry(field).or_insert(Value::Null);
+    }
+    options.entry("user_id_strategy").or_insert(json!("random"));
+}
+
+/// Get the schema version of serialized data.
+///
+/// # Arguments
+///
+/// * `value` - Serialized data.
+///
+/// # Returns
+///
+/// Schema version, or an error if it is newer than the current schema.
+#[cfg(feature = "serde")]
+fn schema_version(value: &Value) -> Result<u32, SimulationError> {
+    let version = match value.get("schema_version") {
+        Some(version) => version
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| SimulationError::InvalidData("invalid schema_version".to_string()))?,
+        None => 0,
+    };
+
+    if version > SCHEMA_VERSION {
+        return Err(SimulationError::UnsupportedSchemaVersion(version));
+    }
+
+    Ok(version)
+}
+
+/// Get serialized data as a JSON object.
+///
+/// # Arguments
+///
+/// * `value` - Serialized data.
+///
+/// # Returns
+///
+/// JSON object, or an error if the data is not an object.
+#[cfg(feature = "serde")]
+fn object(value: &mut Value) -> Result<&mut Map<String, Value>, SimulationError> {
+    value
+        .as_object_mut()
+        .ok_or_else(|| SimulationError::InvalidData("expected a JSON object".to_string()))
+}
+
+#[cfg(feature = "serde")]
+impl Simulation {
+    /// Load a simulation from JSON, migrating data written by older versions of the crate.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - Serialized simulation.
+    ///
+    /// # Returns
+    ///
+    /// Loaded simulation, or an error if the data is invalid or its schema version is not supported.
+    pub fn from_json(json: &str) -> Result<Simulation, SimulationError> {
+        let value = serde_json::from_str(json)
+            .map_err(|err| SimulationError::InvalidData(err.to_string()))?;
+
+        serde_json::from_value(migrate_simulation(value)?)
+            .map_err(|err| SimulationError::InvalidData(err.to_string()))
+    }
+
+    /// Serialize the simulation to JSON, in the current schema.
+    ///
+    /// # Returns
+    ///
+    /// Serialized simulation.
+    pub fn to_json(&self) -> Result<String, SimulationError> {
+        serde_json::to_string(self).map_err(|err| SimulationError::InvalidData(err.to_string()))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl SimulationReport {
+    /// Load a report from JSON, migrating data written by older versions of the crate.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - Serialized report.
+    ///
+    /// # Returns
+    ///
+    /// Loaded report, or an error if the data is invalid or its schema version is not supported.
+    pub fn from_json(json: &str) -> Result<SimulationReport, SimulationError> {
+        let value = serde_json::from_str(json)
+            .map_err(|err| SimulationError::InvalidData(err.to_string()))?;
+
+        serde_json::from_value(migrate_report(value)?)
+            .map_err(|err| SimulationError::InvalidData(err.to_string()))
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    /// Report serialized before versioning.
+    const REPORT_V0: &str = r#"{
+        "interval": 0,
+        "profit_loss": 1.5,
+        "trades": 10,
+        "successful_trades": 6,
+        "failed_trades": 4,
+        "market_volatility": 0.5,
+        "liquidity": 1.0,
+        "adoption_rate": 0.0,
+        "total_burned": 0.0,
+        "burn_rate": 0.0,
+        "inflation_rate": 0.0,
+        "user_retention": 1.0,
+        "network_activity": 10,
+        "token_price": 1.0,
+        "total_new_tokens": 0.0
+    }"#;
+
+    fn simulation_v0() -> String {
+        format!(
+            r#"{{
+                "id": "00000000-0000-0000-0000-000000000001",
+                "name": "Legacy Simulation",
+                "token": {{
+                    "id": "00000000-0000-0000-0000-000000000002",
+                    "name": "Legacy Token",
+                    "symbol": "LGC",
+                    "total_supply": 1000000,
+                    "current_supply": 0.0,
+                    "initial_supply_percentage": 100.0,
+                    "inflation_rate": null,
+                    "burn_rate": null,
+                    "initial_price": 1.0,
+                    "airdrop_percentage": null,
+                    "unlock_schedule": null
+                }},
+                "description": null,
+                "status": "Completed",
+                "options": {{
+                    "duration": 1,
+                    "total_users": 10,
+                    "market_volatility": 0.5,
+                    "decimal_precision": 4,
+                    "interval_type": "Daily",
+                    "transaction_fee": 0.5
+                }},
+                "interval_reports": [{report}],
+                "report": {report},
+                "created_at": "2025-01-01T00:00:00Z",
+                "updated_at": "2025-01-01T00:00:00Z"
+            }}"#,
+            report = REPORT_V0
+        )
+    }
+
+    #[test]
+    fn test_migrate_report() {
+        let report = SimulationReport::from_json(REPORT_V0).unwrap();
+
+        assert_eq!(report.schema_version, SCHEMA_VERSION);
+        assert_eq!(report.trades, 10);
+        assert_eq!(report.active_users, 0);
+        assert!(report.tax_distribution.is_empty());
+    }
+
+    #[test]
+    fn test_migrate_simulation() {
+        let simulation = Simulation::from_json(&simulation_v0()).unwrap();
+
+        assert_eq!(simulation.schema_version, SCHEMA_VERSION);
+        assert_eq!(simulation.name, "Legacy Simulation");
+        assert_eq!(
+            simulation.options.transaction_fee_percentage,
+            Some(crate::Percentage(rust_decimal::Decimal::new(5, 1)))
+        );
+        assert_eq!(simulation.interval_reports.len(), 1);
+        assert_eq!(simulation.report.schema_version, SCHEMA_VERSION);
+        assert_eq!(simulation.status, crate::SimulationStatus::Completed);
+    }
+
+    #[test]
+    fn test_status_names() {
+        let failed = crate::SimulationStatus::Failed {
+            at_interval: 2,
+            error: SimulationError::InvalidDecimal,
+            failed_at: "2025-01-01T00:00:00Z".parse().unwrap(),
+        };
+
+        assert_eq!(
+            serde_json::to_value(crate::SimulationStatus::Completed).unwrap(),
+            json!("completed")
+        );
+        assert_eq!(
+            serde_json::to_value(&failed).unwrap(),
+            json!({
+                "failed": {
+                    "at_interval": 2,
+                    "error": "InvalidDecimal",
+                    "failed_at": "2025-01-01T00:00:00Z",
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_round_trip_current_schema() {
+        let simulation = Simulation::from_json(&simulation_v0()).unwrap();
+        let json = simulation.to_json().unwrap();
+
+        let loaded = Simulation::from_json(&json).unwrap();
+        assert_eq!(loaded.options, simulation.options);
+        assert_eq!(loaded.report, simulation.report);
+    }
+
+    #[test]
+    fn test_unsupported_schema_version() {
+        let json = format!(r#"{{"schema_version": {}}}"#, SCHEMA_VERSION + 1);
+
+        assert_eq!(
+            SimulationReport::from_json(&json).unwrap_err(),
+            SimulationError::UnsupportedSchemaVersion(SCHEMA_VERSION + 1)
+        );
+        assert!(matches!(
+            SimulationReport::from_json("[]").unwrap_err(),
+            SimulationError::InvalidData(_)
+        ));
+    }
+}
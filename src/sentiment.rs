@@ -0,0 +1,195 @@
+//! # Sentiment module
+//!
+//! This module contains the sentiment model, a global mood of the market driven by the recent performance of the price.
+//!
+//! The sentiment index follows the momentum of the price, its change over a window of past intervals,
+//! scaled by a responsiveness coefficient and bounded between -1 (panic) and 1 (euphoria).
+//! The index shifts the buy probability of every user: rallies attract buyers, pushing the price further up,
+//! while crashes turn the users into sellers, a capitulation deepening the fall.
+
+use rust_decimal::{prelude::*, Decimal};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::SimulationError;
+
+/// Market sentiment driven by the momentum of the price.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Sentiment {
+    /// Number of past intervals the momentum of the price is measured over.
+    pub window: u64,
+
+    /// Change of the sentiment index per percentage point of momentum, in percentage.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub responsiveness: Decimal,
+
+    /// Shift of the buy probability at the extremes of the sentiment index, in percentage points.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub buy_bias: Decimal,
+}
+
+impl Sentiment {
+    /// Create a new sentiment model.
+    ///
+    /// # Arguments
+    ///
+    /// * `window` - Number of past intervals the momentum is measured over.
+    /// * `responsiveness` - Change of the index per percentage point of momentum, in percentage.
+    /// * `buy_bias` - Shift of the buy probability at the extremes of the index, in percentage points.
+    ///
+    /// # Returns
+    ///
+    /// New sentiment model.
+    pub fn new(window: u64, responsiveness: Decimal, buy_bias: Decimal) -> Self {
+        Sentiment {
+            window,
+            responsiveness,
+            buy_bias,
+        }
+    }
+
+    /// Validate the sentiment model.
+    ///
+    /// # Returns
+    ///
+    /// Result of the validation, or an error if the window is empty or a coefficient is out of range.
+    pub fn validate(&self) -> Result<(), SimulationError> {
+        if self.window == 0 {
+            return Err(SimulationError::InvalidData(
+                "sentiment: window must be at least one interval".to_string(),
+            ));
+        }
+        if self.responsiveness < Decimal::ZERO {
+            return Err(SimulationError::InvalidData(
+                "sentiment: responsiveness must not be negative".to_string(),
+            ));
+        }
+        if !(Decimal::ZERO..=Decimal::ONE_HUNDRED).contains(&self.buy_bias) {
+            return Err(SimulationError::InvalidData(
+                "sentiment: buy bias must be between 0 and 100".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Get the sentiment index from the momentum of the price.
+    ///
+    /// # Arguments
+    ///
+    /// * `price` - Price of the token at the start of the interval.
+    /// * `past_prices` - Prices of the past intervals, oldest first.
+    ///
+    /// # Returns
+    ///
+    /// Sentiment index between -1 and 1, neutral without a past price.
+    pub fn index(&self, price: Decimal, past_prices: &[Decimal]) -> Decimal {
+        let momentum = momentum(price, past_prices, self.window);
+
+        (momentum * self.responsiveness / Decimal::ONE_HUNDRED).clamp(-Decimal::ONE, Decimal::ONE)
+    }
+
+    /// Shift a buy probability with the sentiment index.
+    ///
+    /// # Arguments
+    ///
+    /// * `probability` - Usual buy probability.
+    /// * `index` - Sentiment index between -1 and 1.
+    ///
+    /// # Returns
+    ///
+    /// Shifted buy probability, between 0 and 1.
+    pub(crate) fn bias(&self, probability: f64, index: Decimal) -> f64 {
+        let shift = (index * self.buy_bias / Decimal::ONE_HUNDRED)
+            .to_f64()
+            .unwrap_or_default();
+
+        (probability + shift).clamp(0.0, 1.0)
+    }
+}
+
+/// Get the momentum of a series, its change over a window of past values, in percentage.
+///
+/// # Arguments
+///
+/// * `current` - Current value of the series.
+/// * `past` - Past values of the series, oldest first.
+/// * `window` - Number of past values the change is measured over.
+///
+/// # Returns
+///
+/// Momentum in percentage, zero without a past value.
+pub(crate) fn momentum(current: Decimal, past: &[Decimal], window: u64) -> Decimal {
+    let start = past.len().saturating_sub(window as usize);
+
+    past.get(start)
+        .filter(|value| !value.is_zero())
+        .map_or(Decimal::ZERO, |value| {
+            (current - value) / value * Decimal::ONE_HUNDRED
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Simulation;
+
+    use super::*;
+
+    #[test]
+    fn test_index() {
+        let sentiment = Sentiment::new(2, Decimal::new(5, 0), Decimal::new(20, 0));
+        let prices = [Decimal::ONE, Decimal::new(2, 0), Decimal::new(2, 0)];
+
+        assert_eq!(sentiment.index(Decimal::ONE, &[]), Decimal::ZERO);
+        assert_eq!(
+            momentum(Decimal::new(3, 0), &prices, 1),
+            Decimal::new(50, 0)
+        );
+        assert_eq!(momentum(Decimal::ONE, &[Decimal::ZERO], 1), Decimal::ZERO);
+        // Measured against the price two intervals ago
+        assert_eq!(
+            sentiment.index(Decimal::new(21, 1), &prices),
+            Decimal::new(25, 2)
+        );
+        assert_eq!(sentiment.index(Decimal::new(4, 0), &prices), Decimal::ONE);
+        assert_eq!(sentiment.index(Decimal::ONE, &prices), -Decimal::ONE);
+
+        assert_eq!(sentiment.bias(0.5, Decimal::new(5, 1)), 0.6);
+        assert_eq!(sentiment.bias(0.1, -Decimal::ONE), 0.0);
+        assert!(Sentiment::new(0, Decimal::ONE, Decimal::ONE)
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_run_with_sentiment() {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(50)
+            .duration(6)
+            .seed(5)
+            .sentiment(Sentiment::new(3, Decimal::new(10, 0), Decimal::new(30, 0)))
+            .build()
+            .unwrap();
+        let mut simulation = Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap();
+
+        simulation.run().unwrap();
+
+        // The first interval has no past price, the path follows the momentum afterwards
+        let reports = &simulation.interval_reports;
+        assert_eq!(reports[0].sentiment, Some(Decimal::ZERO));
+        assert!(reports.iter().all(|report| report
+            .sentiment
+            .is_some_and(|index| index.abs() <= Decimal::ONE)));
+        assert_eq!(simulation.report.sentiment, reports[5].sentiment);
+    }
+}
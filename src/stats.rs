@@ -0,0 +1,175 @@
+//! # Statistics module
+//!
+//! This module contains helpers to summarize simulation outcomes, such as means, percentiles,
+//! and the comparison of outcome distributions.
+
+use rust_decimal::Decimal;
+
+/// Calculate the mean of the given values.
+///
+/// # Arguments
+///
+/// * `values` - A list of values.
+///
+/// # Returns
+///
+/// Mean of the values, or zero if the list is empty.
+pub(crate) fn mean(values: &[Decimal]) -> Decimal {
+    values
+        .iter()
+        .sum::<Decimal>()
+        .checked_div(Decimal::from(values.len()))
+        .unwrap_or_default()
+}
+
+/// Calculate the percentile of the given sorted values, using linear interpolation between ranks.
+///
+/// # Arguments
+///
+/// * `sorted` - A list of values, sorted in ascending order.
+/// * `percentile` - Percentile to calculate, between 0 and 100.
+///
+/// # Returns
+///
+/// Value at the percentile, or zero if the list is empty.
+pub(crate) fn percentile(sorted: &[Decimal], percentile: Decimal) -> Decimal {
+    if sorted.is_empty() {
+        return Decimal::default();
+    }
+
+    let rank = percentile.clamp(Decimal::ZERO, Decimal::ONE_HUNDRED) / Decimal::ONE_HUNDRED
+        * Decimal::from(sorted.len() - 1);
+    let lower = rank.floor();
+    let index = usize::try_from(lower).unwrap_or_default();
+
+    match sorted.get(index + 1) {
+        Some(upper) => sorted[index] + (upper - sorted[index]) * (rank - lower),
+        None => sorted[index],
+    }
+}
+
+/// Calculate the two-sample Kolmogorov-Smirnov statistic,
+/// the largest distance between the empirical distribution functions of the samples.
+///
+/// # Arguments
+///
+/// * `a` - First sample, sorted in ascending order.
+/// * `b` - Second sample, sorted in ascending order.
+///
+/// # Returns
+///
+/// Statistic between 0 and 1, or zero if any sample is empty.
+pub(crate) fn ks_statistic(a: &[Decimal], b: &[Decimal]) -> Decimal {
+    if a.is_empty() || b.is_empty() {
+        return Decimal::default();
+    }
+
+    let (n, m) = (Decimal::from(a.len()), Decimal::from(b.len()));
+    let (mut i, mut j) = (0, 0);
+    let mut statistic = Decimal::default();
+
+    while i < a.len() && j < b.len() {
+        let value = a[i].min(b[j]);
+        while i < a.len() && a[i] == value {
+            i += 1;
+        }
+        while j < b.len() && b[j] == value {
+            j += 1;
+        }
+
+        let distance = (Decimal::from(i) / n - Decimal::from(j) / m).abs();
+        statistic = statistic.max(distance);
+    }
+
+    statistic
+}
+
+/// Calculate the asymptotic p-value of the two-sample Kolmogorov-Smirnov statistic.
+///
+/// # Arguments
+///
+/// * `statistic` - Kolmogorov-Smirnov statistic.
+/// * `n` - Size of the first sample.
+/// * `m` - Size of the second sample.
+///
+/// # Returns
+///
+/// Probability of a statistic at least as large, if both samples come from the same distribution.
+pub(crate) fn ks_p_value(statistic: f64, n: usize, m: usize) -> f64 {
+    if n == 0 || m == 0 {
+        return 1.0;
+    }
+
+    let effective = ((n * m) as f64 / (n + m) as f64).sqrt();
+    let lambda = (effective + 0.12 + 0.11 / effective) * statistic;
+
+    // Kolmogorov distribution: Q(lambda) = 2 * sum((-1)^(k-1) * exp(-2 * k^2 * lambda^2))
+    let mut sum = 0.0;
+    for k in 1..=100 {
+        let k = k as f64;
+        let sign = if k % 2.0 == 1.0 { 1.0 } else { -1.0 };
+        let term = sign * (-2.0 * k * k * lambda * lambda).exp();
+        sum += term;
+
+        if term.abs() < 1e-10 {
+            return (2.0 * sum).clamp(0.0, 1.0);
+        }
+    }
+
+    // The series does not converge for very small statistics, where the samples are indistinguishable
+    1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mean() {
+        let values = [Decimal::new(1, 0), Decimal::new(2, 0), Decimal::new(6, 0)];
+
+        assert_eq!(mean(&values), Decimal::new(3, 0));
+        assert_eq!(mean(&[]), Decimal::default());
+    }
+
+    #[test]
+    fn test_percentile() {
+        let values = [
+            Decimal::new(1, 0),
+            Decimal::new(2, 0),
+            Decimal::new(3, 0),
+            Decimal::new(4, 0),
+            Decimal::new(5, 0),
+        ];
+
+        assert_eq!(percentile(&values, Decimal::ZERO), Decimal::new(1, 0));
+        assert_eq!(percentile(&values, Decimal::new(50, 0)), Decimal::new(3, 0));
+        assert_eq!(
+            percentile(&values, Decimal::ONE_HUNDRED),
+            Decimal::new(5, 0)
+        );
+        assert_eq!(
+            percentile(&values, Decimal::new(95, 0)),
+            Decimal::new(48, 1)
+        );
+        assert_eq!(percentile(&[], Decimal::new(50, 0)), Decimal::default());
+    }
+
+    #[test]
+    fn test_ks_statistic() {
+        let a = [Decimal::new(1, 0), Decimal::new(2, 0), Decimal::new(3, 0)];
+        let b = [Decimal::new(4, 0), Decimal::new(5, 0), Decimal::new(6, 0)];
+
+        assert_eq!(ks_statistic(&a, &a), Decimal::default());
+        assert_eq!(ks_statistic(&a, &b), Decimal::ONE);
+        assert_eq!(ks_statistic(&a, &[]), Decimal::default());
+    }
+
+    #[test]
+    fn test_ks_p_value() {
+        assert_eq!(ks_p_value(0.0, 10, 10), 1.0);
+        assert!(ks_p_value(1.0, 50, 50) < 0.001);
+        assert!(ks_p_value(0.1, 50, 50) > 0.9);
+        assert_eq!(ks_p_value(0.5, 0, 10), 1.0);
+    }
+}
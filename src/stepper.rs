@@ -0,0 +1,188 @@
+//! # Stepper module
+//!
+//! This module contains the stepper, which runs a simulation one interval at a time.
+//!
+//! The caller decides when to run the next interval, so a run can be paused, stepped, and inspected between intervals,
+//! e.g. by an interactive terminal UI. A run driven to its end by the stepper is the same as a run with `run`.
+
+use crate::{
+    engine::RunState, CancellationToken, Simulation, SimulationError, SimulationReport, TokenAmount,
+};
+
+/// Run of a simulation, one interval at a time.
+/// Dropping the stepper before the last interval cancels the run, keeping the reports of the intervals already run.
+#[derive(Debug)]
+pub struct SimulationStepper<'a> {
+    /// Simulation being run.
+    simulation: &'a mut Simulation,
+
+    /// State of the run, `None` once the run is finished or failed.
+    state: Option<RunState>,
+}
+
+impl Simulation {
+    /// Start a run of the simulation, to be driven one interval at a time.
+    ///
+    /// # Returns
+    ///
+    /// Stepper before the first interval, or the error of the start of the run.
+    pub fn stepper(&mut self) -> Result<SimulationStepper<'_>, SimulationError> {
+        let state = self
+            .start_run()
+            .map_err(|error| self.fail_run(None, error))?;
+
+        Ok(SimulationStepper {
+            simulation: self,
+            state: Some(state),
+        })
+    }
+}
+
+impl SimulationStepper<'_> {
+    /// Run the next interval, and complete the simulation after the last one.
+    ///
+    /// # Returns
+    ///
+    /// Report of the interval, `None` if the run is already finished, or the error of the interval.
+    pub fn step(&mut self) -> Result<Option<&SimulationReport>, SimulationError> {
+        let Some(mut state) = self.state.take() else {
+            return Ok(None);
+        };
+
+        if let Err(error) = self.simulation.run_interval(&mut state) {
+            return Err(self.simulation.fail_run(Some(state), error));
+        }
+
+        match state.index < self.simulation.options.duration {
+            true => self.state = Some(state),
+            false => self.simulation.finish_run(state),
+        }
+
+        Ok(self.simulation.interval_reports.last())
+    }
+
+    /// Check whether the run is finished, completed or failed.
+    ///
+    /// # Returns
+    ///
+    /// Whether there is no interval left to run.
+    pub fn is_finished(&self) -> bool {
+        self.state.is_none()
+    }
+
+    /// Get the number of intervals already run.
+    ///
+    /// # Returns
+    ///
+    /// Number of intervals run.
+    pub fn intervals_run(&self) -> u64 {
+        self.simulation.interval_reports.len() as u64
+    }
+
+    /// Get the circulating supply at the end of the last interval.
+    ///
+    /// # Returns
+    ///
+    /// Circulating supply, or the current supply of the token once the run is finished.
+    pub fn circulating_supply(&self) -> TokenAmount {
+        self.state
+            .as_ref()
+            .map_or(self.simulation.token.current_supply, |state| {
+                TokenAmount(state.circulating_supply)
+            })
+    }
+
+    /// Get the simulation being run.
+    ///
+    /// # Returns
+    ///
+    /// The simulation, with the reports of the intervals already run.
+    pub fn simulation(&self) -> &Simulation {
+        self.simulation
+    }
+
+    /// Get the simulation and the state of the run, to adjust them between intervals.
+    ///
+    /// # Returns
+    ///
+    /// The simulation and the state of the run, `None` once the run is finished.
+    pub(crate) fn parts_mut(&mut self) -> Option<(&mut Simulation, &mut RunState)> {
+        let state = self.state.as_mut()?;
+
+        Some((&mut *self.simulation, state))
+    }
+}
+
+impl Drop for SimulationStepper<'_> {
+    fn drop(&mut self) {
+        if let Some(state) = self.state.take() {
+            let cancellation = CancellationToken::new();
+            cancellation.cancel_with_reason("stepper dropped before the last interval");
+            self.simulation.cancel_run(state, &cancellation);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SimulationStatus;
+
+    use super::*;
+
+    fn setup(duration: u64) -> Simulation {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(10)
+            .duration(duration)
+            .seed(7)
+            .build()
+            .unwrap();
+
+        Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_step_matches_run() {
+        let mut expected = setup(3);
+        expected.run().unwrap();
+
+        let mut simulation = setup(3);
+        let mut stepper = simulation.stepper().unwrap();
+        let mut intervals = 0;
+        while stepper.step().unwrap().is_some() {
+            intervals += 1;
+            assert_eq!(stepper.intervals_run(), intervals);
+        }
+
+        assert_eq!(intervals, 3);
+        assert!(stepper.is_finished());
+        assert!(stepper.step().unwrap().is_none());
+        drop(stepper);
+
+        assert_eq!(simulation.status, SimulationStatus::Completed);
+        assert_eq!(simulation.report.token_price, expected.report.token_price);
+    }
+
+    #[test]
+    fn test_drop_cancels_run() {
+        let mut simulation = setup(3);
+
+        let mut stepper = simulation.stepper().unwrap();
+        stepper.step().unwrap();
+        drop(stepper);
+
+        assert!(matches!(
+            simulation.status,
+            SimulationStatus::Cancelled { at_interval: 1, .. }
+        ));
+        assert_eq!(simulation.interval_reports.len(), 1);
+    }
+}
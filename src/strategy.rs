@@ -0,0 +1,205 @@
+//! # Strategy module
+//!
+//! This module contains the proptest strategies for the core types.
+//! The strategies generate valid values only, so downstream users can property-test their own wrappers and validators.
+
+use chrono::{DateTime, Utc};
+use proptest::{option, prelude::*};
+use rust_decimal::Decimal;
+
+use crate::{
+    SimulationInterval, SimulationOptions, SimulationOptionsBuilder, Token, TokenBuilder,
+    UnlockEvent, ValuationModel,
+};
+
+/// Strategy to generate unlock events, dated between 1970 and 2100.
+///
+/// # Returns
+///
+/// Strategy generating valid unlock events.
+pub fn unlock_event() -> impl Strategy<Value = UnlockEvent> {
+    (0..=4_102_444_800i64, 0..=1_000_000_000_000i64).prop_map(|(timestamp, amount)| UnlockEvent {
+        date: DateTime::<Utc>::from_timestamp(timestamp, 0).unwrap(),
+        amount: Decimal::new(amount, 0),
+    })
+}
+
+/// Strategy to generate tokens.
+///
+/// # Returns
+///
+/// Strategy generating valid tokens.
+pub fn token() -> impl Strategy<Value = Token> {
+    (
+        "[A-Za-z ]{1,16}",
+        "[A-Z]{3,4}",
+        1..=1_000_000_000_000i64,
+        0.0..=100.0f64,
+        0.0001..=10_000.0f64,
+        option::of(0.0..=0.1f64),
+        option::of(0.0..=0.1f64),
+        option::of(0.0..=100.0f64),
+        option::of(0.0..=25.0f64),
+        option::of(0.0..=25.0f64),
+        proptest::collection::vec(unlock_event(), 0..4),
+    )
+        .prop_map(
+            |(
+                name,
+                symbol,
+                total_supply,
+                initial_supply_percentage,
+                initial_price,
+                inflation_rate,
+                burn_rate,
+                airdrop_percentage,
+                buy_tax,
+                sell_tax,
+                unlock_schedule,
+            )| {
+                let mut builder = TokenBuilder::new()
+                    .name(name)
+                    .symbol(symbol)
+                    .total_supply(total_supply)
+                    .initial_supply_percentage(initial_supply_percentage)
+                    .initial_price(initial_price)
+                    .unlock_schedule(unlock_schedule);
+
+                if let Some(inflation_rate) = inflation_rate {
+                    builder = builder.inflation_rate(inflation_rate);
+                }
+                if let Some(burn_rate) = burn_rate {
+                    builder = builder.burn_rate(burn_rate);
+                }
+                if let Some(airdrop_percentage) = airdrop_percentage {
+                    builder = builder.airdrop_percentage(airdrop_percentage);
+                }
+                if let Some(buy_tax) = buy_tax {
+                    builder = builder.buy_tax(buy_tax);
+                }
+                if let Some(sell_tax) = sell_tax {
+                    builder = builder.sell_tax(sell_tax);
+                }
+
+                builder.build().unwrap()
+            },
+        )
+}
+
+/// Strategy to generate interval types.
+///
+/// # Returns
+///
+/// Strategy generating interval types.
+pub fn simulation_interval() -> impl Strategy<Value = SimulationInterval> {
+    prop_oneof![
+        Just(SimulationInterval::Hourly),
+        Just(SimulationInterval::Daily),
+        Just(SimulationInterval::Weekly),
+        Just(SimulationInterval::Monthly),
+    ]
+}
+
+/// Strategy to generate valuation models.
+///
+/// # Returns
+///
+/// Strategy generating valuation models.
+pub fn valuation_model() -> impl Strategy<Value = ValuationModel> {
+    prop_oneof![
+        Just(ValuationModel::Linear),
+        (0.01..=1.0f64).prop_map(ValuationModel::Exponential),
+    ]
+}
+
+/// Strategy to generate input parameters.
+/// The number of users and the duration are kept small, so the generated options can be run.
+///
+/// # Returns
+///
+/// Strategy generating valid input parameters.
+pub fn simulation_options() -> impl Strategy<Value = SimulationOptions> {
+    (
+        1..=365u64,
+        1..=10_000u64,
+        0.0..=2.0f64,
+        0..=18u32,
+        simulation_interval(),
+        option::of(0.0..=10.0f64),
+        option::of(0.0..=0.1f64),
+        option::of(valuation_model()),
+    )
+        .prop_map(
+            |(
+                duration,
+                total_users,
+                market_volatility,
+                decimal_precision,
+                interval_type,
+                transaction_fee_percentage,
+                adoption_rate,
+                valuation_model,
+            )| {
+                let mut builder = SimulationOptionsBuilder::new()
+                    .duration(duration)
+                    .total_users(total_users)
+                    .market_volatility(market_volatility)
+                    .decimal_precision(decimal_precision)
+                    .interval_type(interval_type);
+
+                if let Some(fee) = transaction_fee_percentage {
+                    builder = builder.transaction_fee_percentage(fee);
+                }
+                if let Some(rate) = adoption_rate {
+                    builder = builder.adoption_rate(rate);
+                }
+                if let Some(model) = valuation_model {
+                    builder = builder.valuation_model(model);
+                }
+
+                builder.build().unwrap()
+            },
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FiatAmount, TokenAmount};
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn test_token(token in token()) {
+            prop_assert!(token.total_supply > TokenAmount::default());
+            prop_assert!(token.initial_price > FiatAmount::default());
+            prop_assert!(token.current_supply <= token.total_supply);
+        }
+
+        #[test]
+        fn test_unlock_event(event in unlock_event()) {
+            prop_assert!(event.amount >= Decimal::default());
+        }
+
+        #[test]
+        fn test_simulation_options(options in simulation_options()) {
+            prop_assert!(options.total_users >= 1);
+            prop_assert!((1..=365).contains(&options.duration));
+        }
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn test_arbitrary() {
+        use arbitrary::{Arbitrary, Unstructured};
+
+        let data: Vec<u8> = (0..=255).cycle().take(4096).collect();
+        let mut u = Unstructured::new(&data);
+
+        let token = Token::arbitrary(&mut u).unwrap();
+        let options = SimulationOptions::arbitrary(&mut u).unwrap();
+
+        assert!(token.total_supply > TokenAmount::default());
+        assert!(options.total_users >= 1);
+    }
+}
@@ -0,0 +1,263 @@
+//! # Stream module
+//!
+//! This module contains the report stream, enabled by the `async` feature.
+//!
+//! The stream forwards the report of every interval as soon as it completes, followed by the final report,
+//! so front-ends can animate a simulation live, e.g. over Server-Sent Events or WebSocket,
+//! instead of waiting for the whole run. The receiving end is runtime agnostic.
+
+use std::{
+    collections::VecDeque,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    task::{Context, Poll, Waker},
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{IntervalContext, SimulationObserver, SimulationReport};
+
+/// Event of a report stream.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "type", content = "report", rename_all = "snake_case")
+)]
+pub enum StreamEvent {
+    /// Report of a completed interval.
+    Interval(SimulationReport),
+
+    /// Final report of the completed simulation, the last event of the stream.
+    Completed(SimulationReport),
+}
+
+/// State shared between the sender and the receiver.
+#[derive(Debug, Default)]
+struct Channel {
+    /// Events not received yet.
+    events: VecDeque<StreamEvent>,
+
+    /// Whether no more events will be sent.
+    closed: bool,
+
+    /// Waker of the receiver waiting for the next event.
+    waker: Option<Waker>,
+}
+
+/// Sending end of a report stream.
+/// Add it to a simulation as an observer, the stream is closed once the simulation completes or the sender is dropped.
+#[derive(Debug)]
+pub struct ReportSender {
+    /// State shared with the receiver.
+    channel: Arc<Mutex<Channel>>,
+}
+
+/// Receiving end of a report stream.
+#[derive(Debug)]
+pub struct ReportReceiver {
+    /// State shared with the sender.
+    channel: Arc<Mutex<Channel>>,
+}
+
+/// Create a new report stream.
+///
+/// # Returns
+///
+/// Sending and receiving ends of the stream.
+pub fn report_stream() -> (ReportSender, ReportReceiver) {
+    let channel = Arc::new(Mutex::new(Channel::default()));
+
+    (
+        ReportSender {
+            channel: Arc::clone(&channel),
+        },
+        ReportReceiver { channel },
+    )
+}
+
+/// Lock the channel, recovering it if the other end panicked while holding the lock.
+///
+/// # Arguments
+///
+/// * `channel` - Channel to lock.
+///
+/// # Returns
+///
+/// Guard of the channel.
+fn lock(channel: &Mutex<Channel>) -> MutexGuard<'_, Channel> {
+    channel.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl ReportSender {
+    /// Send an event, unless the stream is closed.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - Event to send.
+    /// * `close` - Whether the event is the last one.
+    fn send(&self, event: StreamEvent, close: bool) {
+        let mut channel = lock(&self.channel);
+        if channel.closed {
+            return;
+        }
+
+        channel.events.push_back(event);
+        channel.closed = close;
+
+        if let Some(waker) = channel.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl SimulationObserver for ReportSender {
+    fn on_interval(&self, _context: &IntervalContext, report: &SimulationReport) {
+        self.send(StreamEvent::Interval(report.clone()), false);
+    }
+
+    fn on_complete(&self, report: &SimulationReport) {
+        self.send(StreamEvent::Completed(report.clone()), true);
+    }
+}
+
+impl Drop for ReportSender {
+    /// Close the stream, so the receiver does not wait forever.
+    fn drop(&mut self) {
+        let mut channel = lock(&self.channel);
+        channel.closed = true;
+
+        if let Some(waker) = channel.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl ReportReceiver {
+    /// Receive the next event without waiting.
+    ///
+    /// # Returns
+    ///
+    /// Next event, or `None` if no event is available yet or the stream is closed.
+    pub fn try_recv(&mut self) -> Option<StreamEvent> {
+        lock(&self.channel).events.pop_front()
+    }
+
+    /// Wait for the next event.
+    ///
+    /// # Returns
+    ///
+    /// Next event, or `None` once the stream is closed and all events were received.
+    pub fn recv(&mut self) -> impl Future<Output = Option<StreamEvent>> + '_ {
+        Recv { receiver: self }
+    }
+
+    /// Poll for the next event, registering the waker of the task if none is available yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `cx` - Context of the polling task.
+    ///
+    /// # Returns
+    ///
+    /// Next event, `None` once the stream is closed and all events were received, or pending.
+    pub(crate) fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<StreamEvent>> {
+        let mut channel = lock(&self.channel);
+
+        if let Some(event) = channel.events.pop_front() {
+            return Poll::Ready(Some(event));
+        }
+        if channel.closed {
+            return Poll::Ready(None);
+        }
+
+        channel.waker = Some(cx.waker().clone());
+
+        Poll::Pending
+    }
+}
+
+/// Future waiting for the next event of a report stream.
+#[derive(Debug)]
+struct Recv<'a> {
+    /// Receiver waiting for the event.
+    receiver: &'a mut ReportReceiver,
+}
+
+impl Future for Recv<'_> {
+    type Output = Option<StreamEvent>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CancellationToken, Simulation};
+
+    fn setup() -> Simulation {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .total_supply(1_000_000)
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(10)
+            .duration(3)
+            .build()
+            .unwrap();
+
+        Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_report_stream() {
+        let mut simulation = setup();
+        let (sender, mut receiver) = report_stream();
+        simulation.add_observer(sender);
+
+        let run = tokio::spawn(async move {
+            simulation
+                .run_async(&CancellationToken::new())
+                .await
+                .unwrap();
+            simulation
+        });
+
+        let mut intervals = 0;
+        let mut completed = None;
+        while let Some(event) = receiver.recv().await {
+            match event {
+                StreamEvent::Interval(_) => intervals += 1,
+                StreamEvent::Completed(report) => completed = Some(report),
+            }
+        }
+
+        let simulation = run.await.unwrap();
+        assert_eq!(intervals, 3);
+        assert_eq!(completed, Some(simulation.report));
+    }
+
+    #[tokio::test]
+    async fn test_report_stream_closed_on_drop() {
+        let (sender, mut receiver) = report_stream();
+        sender.on_interval(&IntervalContext::default(), &SimulationReport::default());
+        drop(sender);
+
+        assert!(matches!(
+            receiver.recv().await,
+            Some(StreamEvent::Interval(_))
+        ));
+        assert_eq!(receiver.recv().await, None);
+        assert_eq!(receiver.try_recv(), None);
+    }
+}
@@ -0,0 +1,420 @@
+//! # Stress test module
+//!
+//! This module contains the stress test API.
+//!
+//! A stress test runs a list of named scenarios and checks the outcome of each of them against
+//! pass/fail thresholds, producing a structured outcome that can be consumed by CI.
+
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Simulation, SimulationError, SimulationOptions, Token};
+
+/// Named scenario of a stress test.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Scenario {
+    /// Name of the scenario.
+    pub name: String,
+
+    /// Token used in the scenario.
+    /// If not set, the token of the simulation is used.
+    pub token: Option<Token>,
+
+    /// Input parameters used in the scenario.
+    /// If not set, the options of the simulation are used.
+    pub options: Option<SimulationOptions>,
+}
+
+/// Pass/fail thresholds of a stress test.
+/// Only the configured thresholds are checked.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct StressThresholds {
+    /// Minimum token price in any interval, relative to the price of the first interval.
+    /// For example, 0.5 means the price never falls below 50% of the start.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, with = "rust_decimal::serde::float_option")
+    )]
+    pub min_price_ratio: Option<Decimal>,
+
+    /// Minimum user retention in the final report.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, with = "rust_decimal::serde::float_option")
+    )]
+    pub min_user_retention: Option<Decimal>,
+
+    /// Minimum liquidity in the final report.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, with = "rust_decimal::serde::float_option")
+    )]
+    pub min_liquidity: Option<Decimal>,
+
+    /// Minimum network activity in the final report.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub min_network_activity: Option<u64>,
+}
+
+/// Metric checked by a stress test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum StressMetric {
+    /// Minimum token price, relative to the price of the first interval.
+    MinPriceRatio,
+
+    /// User retention in the final report.
+    UserRetention,
+
+    /// Liquidity in the final report.
+    Liquidity,
+
+    /// Network activity in the final report.
+    NetworkActivity,
+}
+
+/// Result of a single metric check.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct MetricCheck {
+    /// Checked metric.
+    pub metric: StressMetric,
+
+    /// Minimum value of the metric.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub threshold: Decimal,
+
+    /// Actual value of the metric.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub actual: Decimal,
+
+    /// Whether the actual value meets the threshold.
+    pub passed: bool,
+}
+
+/// Outcome of a single scenario.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct ScenarioOutcome {
+    /// Name of the scenario.
+    pub name: String,
+
+    /// Whether all checks of the scenario passed.
+    pub passed: bool,
+
+    /// Result of each metric check.
+    pub checks: Vec<MetricCheck>,
+}
+
+/// Outcome of a stress test.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct StressTestOutcome {
+    /// Whether all scenarios passed.
+    pub passed: bool,
+
+    /// Outcome of each scenario.
+    pub scenarios: Vec<ScenarioOutcome>,
+}
+
+impl Scenario {
+    /// Create a new scenario with the token and options of the simulation.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the scenario.
+    ///
+    /// # Returns
+    ///
+    /// New scenario.
+    pub fn new(name: String) -> Self {
+        Scenario {
+            name,
+            token: None,
+            options: None,
+        }
+    }
+
+    /// Set the token used in the scenario.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - Token used in the scenario.
+    ///
+    /// # Returns
+    ///
+    /// The scenario.
+    pub fn token(mut self, token: Token) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    /// Set the input parameters used in the scenario.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Input parameters used in the scenario.
+    ///
+    /// # Returns
+    ///
+    /// The scenario.
+    pub fn options(mut self, options: SimulationOptions) -> Self {
+        self.options = Some(options);
+        self
+    }
+}
+
+impl StressThresholds {
+    /// Check a completed simulation against the thresholds.
+    ///
+    /// # Arguments
+    ///
+    /// * `simulation` - A completed simulation.
+    ///
+    /// # Returns
+    ///
+    /// Result of each configured metric check.
+    pub fn check(&self, simulation: &Simulation) -> Vec<MetricCheck> {
+        let report = &simulation.report;
+        let mut checks = vec![];
+
+        if let Some(threshold) = self.min_price_ratio {
+            let start = simulation
+                .interval_reports
+                .first()
+                .map(|report| report.token_price.value())
+                .unwrap_or_default();
+            let actual = simulation
+                .interval_reports
+                .iter()
+                .map(|report| {
+                    report
+                        .token_price
+                        .value()
+                        .checked_div(start)
+                        .unwrap_or_default()
+                })
+                .min()
+                .unwrap_or_default();
+
+            checks.push(MetricCheck::new(
+                StressMetric::MinPriceRatio,
+                threshold,
+                actual,
+            ));
+        }
+
+        if let Some(threshold) = self.min_user_retention {
+            checks.push(MetricCheck::new(
+                StressMetric::UserRetention,
+                threshold,
+                report.user_retention,
+            ));
+        }
+
+        if let Some(threshold) = self.min_liquidity {
+            checks.push(MetricCheck::new(
+                StressMetric::Liquidity,
+                threshold,
+                report.liquidity,
+            ));
+        }
+
+        if let Some(threshold) = self.min_network_activity {
+            checks.push(MetricCheck::new(
+                StressMetric::NetworkActivity,
+                Decimal::from(threshold),
+                Decimal::from(report.network_activity),
+            ));
+        }
+
+        checks
+    }
+}
+
+impl MetricCheck {
+    /// Create a new metric check.
+    ///
+    /// # Arguments
+    ///
+    /// * `metric` - Checked metric.
+    /// * `threshold` - Minimum value of the metric.
+    /// * `actual` - Actual value of the metric.
+    ///
+    /// # Returns
+    ///
+    /// New metric check.
+    pub fn new(metric: StressMetric, threshold: Decimal, actual: Decimal) -> Self {
+        MetricCheck {
+            metric,
+            threshold,
+            actual,
+            passed: actual >= threshold,
+        }
+    }
+}
+
+impl Simulation {
+    /// Run a stress test.
+    /// Each scenario runs on a fresh copy of the simulation, the simulation itself is not modified.
+    ///
+    /// # Arguments
+    ///
+    /// * `scenarios` - A list of named scenarios.
+    /// * `thresholds` - Pass/fail thresholds checked for each scenario.
+    ///
+    /// # Returns
+    ///
+    /// Outcome of the stress test.
+    pub fn stress_test(
+        &self,
+        scenarios: &[Scenario],
+        thresholds: &StressThresholds,
+    ) -> Result<StressTestOutcome, SimulationError> {
+        let mut outcomes = vec![];
+
+        #[cfg(feature = "log")]
+        log::debug!("Running {} stress test scenarios", scenarios.len());
+
+        // Scenarios run concurrently, outcomes keep the order of the scenarios
+        let variants = scenarios
+            .iter()
+            .map(|scenario| {
+                (
+                    scenario.token.clone().unwrap_or_else(|| self.token.clone()),
+                    scenario
+                        .options
+                        .clone()
+                        .unwrap_or_else(|| self.options.clone()),
+                )
+            })
+            .collect();
+        let simulations = self.run_variants(variants)?;
+
+        for (scenario, simulation) in scenarios.iter().zip(simulations) {
+            let checks = thresholds.check(&simulation);
+
+            outcomes.push(ScenarioOutcome {
+                name: scenario.name.clone(),
+                passed: checks.iter().all(|check| check.passed),
+                checks,
+            });
+        }
+
+        Ok(StressTestOutcome {
+            passed: outcomes.iter().all(|outcome| outcome.passed),
+            scenarios: outcomes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ValuationModel;
+
+    use super::*;
+
+    fn setup() -> Simulation {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .valuation_model(ValuationModel::RandomWalk)
+            .total_users(10)
+            .duration(5)
+            .market_volatility(0.0)
+            .build()
+            .unwrap();
+
+        Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_metric_check() {
+        assert!(MetricCheck::new(StressMetric::Liquidity, Decimal::ONE, Decimal::ONE).passed);
+        assert!(!MetricCheck::new(StressMetric::Liquidity, Decimal::TWO, Decimal::ONE).passed);
+    }
+
+    #[test]
+    fn test_stress_test() {
+        let simulation = setup();
+        let mut volatile = simulation.options.clone();
+        volatile.market_volatility = Decimal::new(5, 1);
+
+        let scenarios = vec![
+            Scenario::new("Baseline".to_string()),
+            Scenario::new("Volatile".to_string()).options(volatile),
+        ];
+        let thresholds = StressThresholds {
+            min_price_ratio: Some(Decimal::new(5, 1)),
+            min_user_retention: Some(Decimal::new(4, 1)),
+            ..Default::default()
+        };
+
+        let outcome = simulation.stress_test(&scenarios, &thresholds).unwrap();
+
+        assert_eq!(outcome.scenarios.len(), 2);
+        assert_eq!(outcome.scenarios[0].name, "Baseline");
+        assert_eq!(outcome.scenarios[0].checks.len(), 2);
+        assert!(outcome.scenarios[0].passed);
+        assert_eq!(
+            outcome.scenarios[0].checks[0].metric,
+            StressMetric::MinPriceRatio
+        );
+        assert_eq!(outcome.scenarios[0].checks[0].actual, Decimal::ONE);
+        assert!(simulation.interval_reports.is_empty());
+    }
+
+    #[test]
+    fn test_stress_test_failure() {
+        let simulation = setup();
+        let thresholds = StressThresholds {
+            min_network_activity: Some(u64::MAX),
+            ..Default::default()
+        };
+
+        let outcome = simulation
+            .stress_test(&[Scenario::new("Baseline".to_string())], &thresholds)
+            .unwrap();
+
+        assert!(!outcome.passed);
+        assert!(!outcome.scenarios[0].passed);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let simulation = setup();
+        let scenario = Scenario::new("Baseline".to_string()).options(simulation.options.clone());
+        let json = serde_json::to_string(&scenario).unwrap();
+        assert_eq!(serde_json::from_str::<Scenario>(&json).unwrap(), scenario);
+
+        let thresholds = StressThresholds {
+            min_liquidity: Some(Decimal::ONE),
+            min_network_activity: Some(1),
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&thresholds).unwrap();
+        assert_eq!(
+            serde_json::from_str::<StressThresholds>(&json).unwrap(),
+            thresholds
+        );
+
+        let outcome = simulation.stress_test(&[scenario], &thresholds).unwrap();
+        let json = serde_json::to_string(&outcome).unwrap();
+        assert_eq!(
+            serde_json::from_str::<StressTestOutcome>(&json).unwrap(),
+            outcome
+        );
+    }
+}
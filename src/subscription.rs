@@ -0,0 +1,215 @@
+//! # Subscription module
+//!
+//! This module contains the subscription, a recurring token payment of the users to the protocol, e.g. in work-token designs.
+//!
+//! In each interval, a fraction of the active users pays the subscription, priced in tokens or in fiat at the current price.
+//! Users who cannot afford the subscription skip the interval.
+//! The payments are burned or sent to the treasury, and reported as recurring revenue in tokens and in fiat.
+
+use rand::Rng;
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{population::sample_members, SinkDestination, UserStore};
+
+/// Price of a subscription per user per interval.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum SubscriptionPrice {
+    /// Fixed amount of tokens.
+    Tokens(#[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))] Decimal),
+
+    /// Fixed amount of fiat, paid in tokens at the current price.
+    Fiat(#[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))] Decimal),
+}
+
+/// Recurring token payment of the users.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Subscription {
+    /// Price of the subscription per user per interval.
+    pub price: SubscriptionPrice,
+
+    /// Fraction of the active users paying the subscription in each interval, between 0 and 1.
+    pub payer_fraction: f64,
+
+    /// Destination of the payments.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub destination: SinkDestination,
+}
+
+/// Payments of the subscription in an interval.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct SubscriptionPayments {
+    /// Number of paying users.
+    pub(crate) subscribers: u64,
+
+    /// Tokens paid.
+    pub(crate) revenue: Decimal,
+}
+
+impl Subscription {
+    /// Create a new subscription, burned.
+    ///
+    /// # Arguments
+    ///
+    /// * `price` - Price of the subscription per user per interval.
+    /// * `payer_fraction` - Fraction of the active users paying the subscription.
+    ///
+    /// # Returns
+    ///
+    /// New subscription.
+    pub fn new(price: SubscriptionPrice, payer_fraction: f64) -> Self {
+        Subscription {
+            price,
+            payer_fraction,
+            destination: SinkDestination::default(),
+        }
+    }
+
+    /// Get the price of the subscription in tokens.
+    ///
+    /// # Arguments
+    ///
+    /// * `token_price` - Price of the token.
+    ///
+    /// # Returns
+    ///
+    /// Price in tokens, `None` if it is priced in fiat and the token has no price.
+    pub fn price_in_tokens(&self, token_price: Decimal) -> Option<Decimal> {
+        match self.price {
+            SubscriptionPrice::Tokens(amount) => Some(amount),
+            SubscriptionPrice::Fiat(amount) => amount.checked_div(token_price),
+        }
+    }
+
+    /// Collect the payments of the subscribers in an interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `users` - Users of the simulation.
+    /// * `token_price` - Price of the token in the interval.
+    /// * `precision` - Decimal precision of the balances.
+    /// * `rng` - Random number generator.
+    ///
+    /// # Returns
+    ///
+    /// Payments of the interval.
+    pub(crate) fn collect<R: Rng + ?Sized>(
+        &self,
+        users: &mut UserStore,
+        token_price: Decimal,
+        precision: u32,
+        rng: &mut R,
+    ) -> SubscriptionPayments {
+        let mut payments = SubscriptionPayments::default();
+        let Some(price) = self
+            .price_in_tokens(token_price)
+            .map(|price| price.round_dp(precision))
+            .filter(|price| *price > Decimal::ZERO)
+        else {
+            return payments;
+        };
+
+        for index in 0..users.len() {
+            if !users.can_trade(index) {
+                continue;
+            }
+
+            // Members of a cohort who cannot afford the subscription skip the interval
+            let payers = sample_members(users.weights[index], self.payer_fraction, rng);
+            let affordable = (users.balances[index] / price)
+                .floor()
+                .try_into()
+                .unwrap_or(u64::MAX);
+            let payers = payers.min(affordable);
+            let amount = price * Decimal::from(payers);
+
+            users.balances[index] -= amount;
+            payments.subscribers += payers;
+            payments.revenue += amount;
+        }
+
+        payments
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+    use uuid::Uuid;
+
+    use crate::{Simulation, TokenAmount, User};
+
+    use super::*;
+
+    #[test]
+    fn test_collect() {
+        let mut users = UserStore::from(vec![User::new(Uuid::new_v4(), Decimal::new(10, 0)); 3]);
+        users.balances[1] = Decimal::new(1, 0);
+        users.states[2] = crate::UserState::Dormant;
+        let subscription = Subscription::new(SubscriptionPrice::Fiat(Decimal::new(2, 0)), 1.0);
+        let mut rng = StdRng::seed_from_u64(1);
+
+        // The subscription costs 4 tokens at a price of 0.5, the second user cannot afford it
+        let payments = subscription.collect(&mut users, Decimal::new(5, 1), 4, &mut rng);
+        assert_eq!(payments.subscribers, 1);
+        assert_eq!(payments.revenue, Decimal::new(4, 0));
+        assert_eq!(users.balances[0], Decimal::new(6, 0));
+        assert_eq!(users.balances[1], Decimal::new(1, 0));
+        assert_eq!(users.balances[2], Decimal::new(10, 0));
+
+        assert_eq!(subscription.price_in_tokens(Decimal::ZERO), None);
+    }
+
+    #[test]
+    fn test_run_with_subscription() {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(20)
+            .duration(3)
+            .seed(2)
+            .subscription(Subscription {
+                destination: SinkDestination::Treasury,
+                ..Subscription::new(SubscriptionPrice::Tokens(Decimal::new(1, 1)), 0.5)
+            })
+            .build()
+            .unwrap();
+        let mut simulation = Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap();
+
+        simulation.run().unwrap();
+
+        let report = &simulation.interval_reports[0];
+        assert!(report.subscribers > 0 && report.subscribers <= 20);
+        assert_eq!(
+            report.recurring_revenue,
+            TokenAmount(Decimal::new(1, 1)) * Decimal::from(report.subscribers)
+        );
+        assert_eq!(
+            report.recurring_revenue_fiat,
+            report
+                .recurring_revenue
+                .value_at(report.token_price)
+                .round_dp(4)
+        );
+
+        let revenue: TokenAmount = simulation
+            .interval_reports
+            .iter()
+            .map(|report| report.recurring_revenue)
+            .sum();
+        assert_eq!(simulation.report.recurring_revenue, revenue);
+        // The treasury keeps the payments in circulation
+        assert_eq!(simulation.report.total_burned, TokenAmount::default());
+    }
+}
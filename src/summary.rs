@@ -0,0 +1,275 @@
+//! # Summary module
+//!
+//! This module contains the headline outcomes of a simulation.
+//!
+//! The summary is a compact digest of the reports, suitable for dashboards and automated summaries.
+//! It is the lightweight representation of a simulation for APIs and storage, converted from the engine type
+//! with `Simulation::summary` or `From<&Simulation>`, and can be rendered as text with `Display`.
+
+use std::fmt::{self, Write};
+
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    FiatAmount, NumberFormat, RoundAs, Simulation, SimulationStatus, TokenAmount, ValueKind,
+};
+
+/// Headline outcomes of a simulation.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct SimulationSummary {
+    /// Name of the simulation.
+    pub name: String,
+
+    /// Status of the simulation.
+    pub status: SimulationStatus,
+
+    /// Number of intervals simulated.
+    pub intervals: u64,
+
+    /// Initial price of the token.
+    pub initial_price: FiatAmount,
+
+    /// Price of the token in the last interval.
+    pub final_price: FiatAmount,
+
+    /// Change of the final price from the initial price, in percentage.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub price_change: Decimal,
+
+    /// Worst peak-to-trough decline of the price, in percentage.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub max_drawdown: Decimal,
+
+    /// Total amount of tokens burned.
+    pub total_burned: TokenAmount,
+
+    /// Highest number of users reached.
+    pub peak_users: u64,
+
+    /// User retention rate in the last interval, between 0 and 1.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub final_retention: Decimal,
+}
+
+impl Simulation {
+    /// Summarize the headline outcomes of the simulation.
+    /// Before the simulation is run, the outcomes are empty and the final price is the initial price.
+    ///
+    /// # Returns
+    ///
+    /// Summary of the simulation.
+    pub fn summary(&self) -> SimulationSummary {
+        let initial_price = self.token.initial_price;
+        let last = self.interval_reports.last();
+        let final_price = last.map_or(initial_price, |report| report.token_price);
+
+        // Largest decline from the running peak, including the initial price
+        let mut peak = initial_price;
+        let mut max_drawdown = Decimal::default();
+        for report in self.interval_reports.iter() {
+            peak = peak.max(report.token_price);
+            let drawdown = ((peak - report.token_price).value() * Decimal::ONE_HUNDRED)
+                .checked_div(peak.value())
+                .unwrap_or_default();
+            max_drawdown = max_drawdown.max(drawdown);
+        }
+
+        SimulationSummary {
+            name: self.name.clone(),
+            status: self.status.clone(),
+            intervals: self.interval_reports.len() as u64,
+            initial_price,
+            final_price,
+            price_change: ((final_price - initial_price).value() * Decimal::ONE_HUNDRED)
+                .checked_div(initial_price.value())
+                .unwrap_or_default()
+                .round_as(&self.options, ValueKind::Rate),
+            max_drawdown: max_drawdown.round_as(&self.options, ValueKind::Rate),
+            total_burned: self.report.total_burned,
+            peak_users: self.report.peak_user_count,
+            final_retention: last.map_or(Decimal::default(), |report| report.user_retention),
+        }
+    }
+}
+
+impl From<&Simulation> for SimulationSummary {
+    /// Summarize the headline outcomes of the simulation.
+    fn from(simulation: &Simulation) -> Self {
+        simulation.summary()
+    }
+}
+
+impl SimulationSummary {
+    /// Get the labelled outcomes of the summary, with the numbers formatted.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - Formatting of the numbers.
+    ///
+    /// # Returns
+    ///
+    /// Label and formatted value of each outcome.
+    fn outcomes(&self, format: &NumberFormat) -> [(&'static str, String); 5] {
+        let sign = match self.price_change.is_sign_negative() {
+            true => "",
+            false => "+",
+        };
+
+        [
+            (
+                "Price",
+                format!(
+                    "{} -> {} ({sign}{})",
+                    format.format(self.initial_price.value()),
+                    format.format(self.final_price.value()),
+                    format.format_percentage(self.price_change)
+                ),
+            ),
+            ("Max drawdown", format.format_percentage(self.max_drawdown)),
+            ("Total burned", format.format(self.total_burned.value())),
+            ("Peak users", format.format(Decimal::from(self.peak_users))),
+            ("Final retention", format.format(self.final_retention)),
+        ]
+    }
+
+    /// Render the summary as text, one outcome per line.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - Formatting of the numbers.
+    ///
+    /// # Returns
+    ///
+    /// Summary as text.
+    pub fn render(&self, format: &NumberFormat) -> String {
+        let mut text = format!(
+            "{} ({:?}, {} intervals)",
+            self.name, self.status, self.intervals
+        );
+        for (label, value) in self.outcomes(format) {
+            let _ = write!(text, "\n{label}: {value}");
+        }
+
+        text
+    }
+
+    /// Render the summary as a Markdown table, one outcome per row.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - Formatting of the numbers.
+    ///
+    /// # Returns
+    ///
+    /// Summary as Markdown.
+    pub fn to_markdown(&self, format: &NumberFormat) -> String {
+        let mut markdown = format!(
+            "### {}\n\n| Outcome | Value |\n| --- | --- |\n| Status | {:?} |\n| Intervals | {} |\n",
+            self.name, self.status, self.intervals
+        );
+        for (label, value) in self.outcomes(format) {
+            let _ = writeln!(markdown, "| {label} | {value} |");
+        }
+
+        markdown
+    }
+}
+
+impl fmt::Display for SimulationSummary {
+    /// Render the summary as text, one outcome per line, with every digit and no grouping.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(&NumberFormat::default().group_thousands(false)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Locale, SimulationReport};
+
+    use super::*;
+
+    fn setup() -> Simulation {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .initial_price(1.0)
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(10)
+            .build()
+            .unwrap();
+
+        Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_summary() {
+        let mut simulation = setup();
+        simulation.interval_reports = [2, 1, 3, 1]
+            .into_iter()
+            .map(|price| SimulationReport {
+                token_price: FiatAmount(Decimal::new(price, 0)),
+                user_retention: Decimal::new(8, 1),
+                ..Default::default()
+            })
+            .collect();
+        simulation.report.total_burned = TokenAmount(Decimal::new(100, 0));
+        simulation.report.peak_user_count = 12;
+
+        let summary = simulation.summary();
+
+        assert_eq!(summary.intervals, 4);
+        assert_eq!(summary.final_price, FiatAmount(Decimal::ONE));
+        assert_eq!(summary.price_change, Decimal::default());
+        assert_eq!(summary.max_drawdown, Decimal::new(666_667, 4));
+        assert_eq!(summary.peak_users, 12);
+        assert_eq!(summary.final_retention, Decimal::new(8, 1));
+        assert!(summary.to_string().contains("Max drawdown: 66.6667%"));
+    }
+
+    #[test]
+    fn test_render_formatted() {
+        let mut summary = setup().summary();
+        summary.total_burned = TokenAmount(Decimal::new(1_234_567, 0));
+        summary.price_change = Decimal::new(-125, 1);
+
+        let text = summary.render(&NumberFormat::readable(Locale::En));
+        assert!(text.contains("Total burned: 1.23M"));
+        assert!(text.contains("(-12.5%)"));
+        assert!(summary
+            .to_markdown(&NumberFormat::default())
+            .contains("| Total burned | 1,234,567 |"));
+        assert!(summary.to_string().contains("Total burned: 1234567"));
+    }
+
+    #[test]
+    fn test_summary_before_run() {
+        let simulation = setup();
+        let summary = SimulationSummary::from(&simulation);
+
+        assert_eq!(summary, simulation.summary());
+        assert_eq!(summary.status, SimulationStatus::Pending);
+        assert_eq!(summary.final_price, summary.initial_price);
+        assert_eq!(summary.max_drawdown, Decimal::default());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let summary = setup().summary();
+
+        let json = serde_json::to_string(&summary).unwrap();
+        assert_eq!(
+            serde_json::from_str::<SimulationSummary>(&json).unwrap(),
+            summary
+        );
+    }
+}
@@ -0,0 +1,731 @@
+//! # Sweep module
+//!
+//! This module contains the parameter sweeps, which run a scenario for every combination of parameter values.
+//!
+//! A parameter is a field of the token or of the options, e.g. `burn_rate`, swept over an inclusive range with a step.
+//! Each combination writes one result file, named after its parameter values, to an output directory.
+//! Combinations whose result file already exists are skipped, so an interrupted sweep resumes where it stopped.
+//!
+//! A full grid grows exponentially with the number of parameters, so a sweep can instead sample the combinations
+//! with a Latin hypercube or a Sobol sequence, covering the parameter space evenly with far fewer runs.
+//! Sampled points are snapped to the values of each range, so integer parameters stay integers.
+
+use std::str::FromStr;
+#[cfg(feature = "serde")]
+use std::{fs, path::Path};
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "serde")]
+use crate::Simulation;
+use crate::SimulationError;
+
+/// Parameter swept over a range of values.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct SweepParameter {
+    /// Name of the field, e.g. `burn_rate`, or its path, e.g. `options.market_volatility`.
+    pub name: String,
+
+    /// First value of the range.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub start: Decimal,
+
+    /// Last value of the range, included if reached by the steps.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub end: Decimal,
+
+    /// Step between two values.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub step: Decimal,
+}
+
+/// Value of each swept parameter in one run of a sweep.
+pub type SweepCombination = Vec<(String, Decimal)>;
+
+/// Maximum number of parameters of a Sobol sampling.
+pub const SOBOL_MAX_DIMENSIONS: usize = 10;
+
+/// Primitive polynomials and initial direction numbers of the Sobol sequence, from Joe and Kuo,
+/// for the dimensions after the first: degree, inner coefficients, and direction numbers.
+const SOBOL_DIRECTIONS: [(u32, u32, &[u32]); SOBOL_MAX_DIMENSIONS - 1] = [
+    (1, 0, &[1]),
+    (2, 1, &[1, 3]),
+    (3, 1, &[1, 3, 1]),
+    (3, 2, &[1, 1, 1]),
+    (4, 1, &[1, 1, 3, 3]),
+    (4, 4, &[1, 3, 5, 13]),
+    (5, 2, &[1, 1, 5, 5, 17]),
+    (5, 4, &[1, 1, 5, 5, 5]),
+    (5, 7, &[1, 1, 7, 11, 19]),
+];
+
+/// Strategy choosing the combinations of a sweep.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum SweepSampling {
+    /// Every combination of the values of the parameters.
+    #[default]
+    Grid,
+
+    /// Latin hypercube: each range is split in as many strata as samples, and each stratum is sampled once.
+    LatinHypercube {
+        /// Number of samples.
+        samples: usize,
+
+        /// Seed of the random placement of the samples.
+        seed: u64,
+    },
+
+    /// Sobol low-discrepancy sequence, balanced when the number of samples is a power of two.
+    Sobol {
+        /// Number of samples.
+        samples: usize,
+    },
+}
+
+/// Progress of a sweep, reported after each combination.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SweepProgress {
+    /// Number of combinations of the sweep.
+    pub total: usize,
+
+    /// Number of combinations run in this call.
+    pub completed: usize,
+
+    /// Number of combinations skipped because their result already existed.
+    pub skipped: usize,
+}
+
+impl SweepProgress {
+    /// Get the number of combinations done, run or skipped.
+    ///
+    /// # Returns
+    ///
+    /// Number of combinations done.
+    pub fn done(&self) -> usize {
+        self.completed + self.skipped
+    }
+}
+
+impl SweepParameter {
+    /// Get the values of the range, from the start to the end.
+    ///
+    /// # Returns
+    ///
+    /// Values of the parameter.
+    pub fn values(&self) -> Vec<Decimal> {
+        let mut values = vec![];
+        let mut value = self.start;
+
+        while value <= self.end {
+            values.push(value.normalize());
+            value += self.step;
+        }
+
+        values
+    }
+}
+
+impl FromStr for SweepParameter {
+    type Err = SimulationError;
+
+    /// Parse a parameter written as `name=start..end:step`, e.g. `burn_rate=0..5:0.5`.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| SimulationError::InvalidSweep(format!("{value}: {reason}"));
+
+        let (name, range) = value
+            .split_once('=')
+            .ok_or_else(|| invalid("expected name=start..end:step"))?;
+        let (range, step) = range.split_once(':').unwrap_or((range, "1"));
+        let (start, end) = range
+            .split_once("..")
+            .ok_or_else(|| invalid("expected a range start..end"))?;
+        let number = |number: &str| {
+            Decimal::from_str(number.trim()).map_err(|_| invalid("expected numbers"))
+        };
+
+        let parameter = SweepParameter {
+            name: name.trim().to_string(),
+            start: number(start)?,
+            end: number(end)?,
+            step: number(step)?,
+        };
+        if parameter.name.is_empty() {
+            return Err(invalid("missing name"));
+        }
+        if parameter.step <= Decimal::default() || parameter.end < parameter.start {
+            return Err(invalid("expected a positive step and an increasing range"));
+        }
+
+        Ok(parameter)
+    }
+}
+
+impl FromStr for SweepSampling {
+    type Err = SimulationError;
+
+    /// Parse a sampling written as `grid`, `lhs:samples[:seed]`, or `sobol:samples`, e.g. `lhs:100:7`.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| SimulationError::InvalidSweep(format!("{value}: {reason}"));
+        let number = |number: &str| {
+            number
+                .trim()
+                .parse::<u64>()
+                .map_err(|_| invalid("expected a number of samples and a seed"))
+        };
+
+        let mut parts = value.split(':');
+        let sampling = match (parts.next().map(str::trim), parts.next(), parts.next()) {
+            (Some("grid"), None, None) => SweepSampling::Grid,
+            (Some("lhs"), Some(samples), seed) => SweepSampling::LatinHypercube {
+                samples: number(samples)? as usize,
+                seed: seed.map(number).transpose()?.unwrap_or_default(),
+            },
+            (Some("sobol"), Some(samples), None) => SweepSampling::Sobol {
+                samples: number(samples)? as usize,
+            },
+            _ => {
+                return Err(invalid(
+                    "expected grid, lhs:samples[:seed], or sobol:samples",
+                ))
+            }
+        };
+        if parts.next().is_some() {
+            return Err(invalid("too many parts"));
+        }
+
+        Ok(sampling)
+    }
+}
+
+impl SweepSampling {
+    /// Draw points of the unit hypercube.
+    ///
+    /// # Arguments
+    ///
+    /// * `dimensions` - Number of coordinates of each point.
+    ///
+    /// # Returns
+    ///
+    /// Points with coordinates in `[0, 1)`, or an error if the sampling does not support the dimensions.
+    fn points(&self, dimensions: usize) -> Result<Vec<Vec<f64>>, SimulationError> {
+        match *self {
+            SweepSampling::Grid => Ok(vec![]),
+            SweepSampling::LatinHypercube { samples, seed } => {
+                let mut rng = StdRng::seed_from_u64(seed);
+                let mut points = vec![Vec::with_capacity(dimensions); samples];
+
+                for _ in 0..dimensions {
+                    let mut strata: Vec<usize> = (0..samples).collect();
+                    strata.shuffle(&mut rng);
+                    for (point, stratum) in points.iter_mut().zip(strata) {
+                        point.push((stratum as f64 + rng.random::<f64>()) / samples as f64);
+                    }
+                }
+
+                Ok(points)
+            }
+            SweepSampling::Sobol { samples } => {
+                if dimensions > SOBOL_MAX_DIMENSIONS {
+                    return Err(SimulationError::InvalidSweep(format!(
+                        "Sobol sampling supports up to {SOBOL_MAX_DIMENSIONS} parameters"
+                    )));
+                }
+
+                let directions: Vec<[u32; 32]> = (0..dimensions).map(sobol_directions).collect();
+                let mut state = vec![0u32; dimensions];
+                let mut points = Vec::with_capacity(samples);
+
+                for index in 0..samples {
+                    if index > 0 {
+                        // The rightmost zero bit of the previous index selects the direction number
+                        let bit = (index - 1).trailing_ones() as usize;
+                        for (value, direction) in state.iter_mut().zip(&directions) {
+                            *value ^= direction[bit];
+                        }
+                    }
+                    points.push(
+                        state
+                            .iter()
+                            .map(|value| *value as f64 / 2f64.powi(32))
+                            .collect(),
+                    );
+                }
+
+                Ok(points)
+            }
+        }
+    }
+}
+
+/// Get the direction numbers of a dimension of the Sobol sequence.
+///
+/// # Arguments
+///
+/// * `dimension` - Index of the dimension, from 0.
+///
+/// # Returns
+///
+/// Direction number of each bit, scaled to 32 bits.
+fn sobol_directions(dimension: usize) -> [u32; 32] {
+    let mut directions = [0u32; 32];
+
+    // The first dimension is the van der Corput sequence in base 2
+    let Some(&(degree, coefficients, initial)) = dimension
+        .checked_sub(1)
+        .and_then(|index| SOBOL_DIRECTIONS.get(index))
+    else {
+        for (bit, direction) in directions.iter_mut().enumerate() {
+            *direction = 1 << (31 - bit);
+        }
+        return directions;
+    };
+
+    let degree = degree as usize;
+    for bit in 0..32 {
+        directions[bit] = match bit < degree {
+            true => initial[bit] << (31 - bit),
+            false => {
+                let mut direction = directions[bit - degree] ^ (directions[bit - degree] >> degree);
+                for term in 1..degree {
+                    if (coefficients >> (degree - 1 - term)) & 1 == 1 {
+                        direction ^= directions[bit - term];
+                    }
+                }
+                direction
+            }
+        };
+    }
+
+    directions
+}
+
+/// Sweep of a scenario over the combinations of the values of its parameters.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Sweep {
+    /// Swept parameters.
+    pub parameters: Vec<SweepParameter>,
+
+    /// Strategy choosing the combinations, every combination by default.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub sampling: SweepSampling,
+}
+
+impl Sweep {
+    /// Create a new sweep.
+    ///
+    /// # Arguments
+    ///
+    /// * `parameters` - Swept parameters.
+    ///
+    /// # Returns
+    ///
+    /// New sweep.
+    pub fn new(parameters: Vec<SweepParameter>) -> Self {
+        Sweep {
+            parameters,
+            sampling: SweepSampling::default(),
+        }
+    }
+
+    /// Set the strategy choosing the combinations.
+    ///
+    /// # Arguments
+    ///
+    /// * `sampling` - Sampling strategy.
+    ///
+    /// # Returns
+    ///
+    /// The sweep with the sampling strategy.
+    pub fn sampling(mut self, sampling: SweepSampling) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
+    /// Get the combinations of the sweep.
+    /// A grid has every combination of the values of the parameters, the last parameter varying fastest.
+    /// A sampled sweep snaps each point to the nearest values of the ranges, and drops the repeated combinations,
+    /// so it can have fewer combinations than samples when the ranges have few values.
+    ///
+    /// # Returns
+    ///
+    /// Combinations of the sweep, a single empty combination if there is no parameter,
+    /// or an error if the sampling does not support the number of parameters.
+    pub fn combinations(&self) -> Result<Vec<SweepCombination>, SimulationError> {
+        if self.sampling == SweepSampling::Grid || self.parameters.is_empty() {
+            return Ok(self.grid());
+        }
+
+        let values: Vec<_> = self
+            .parameters
+            .iter()
+            .map(|parameter| parameter.values())
+            .collect();
+        let mut combinations: Vec<SweepCombination> = vec![];
+
+        for point in self.sampling.points(self.parameters.len())? {
+            let combination = self
+                .parameters
+                .iter()
+                .zip(&values)
+                .zip(point)
+                .map(|((parameter, values), coordinate)| {
+                    let index = ((coordinate * values.len() as f64) as usize).min(values.len() - 1);
+                    (parameter.name.clone(), values[index])
+                })
+                .collect();
+            if !combinations.contains(&combination) {
+                combinations.push(combination);
+            }
+        }
+
+        Ok(combinations)
+    }
+
+    /// Get every combination of the values of the parameters, the last parameter varying fastest.
+    ///
+    /// # Returns
+    ///
+    /// Combinations of the grid.
+    fn grid(&self) -> Vec<SweepCombination> {
+        self.parameters
+            .iter()
+            .fold(vec![vec![]], |combinations, parameter| {
+                combinations
+                    .into_iter()
+                    .flat_map(|combination| {
+                        parameter.values().into_iter().map(move |value| {
+                            let mut combination = combination.clone();
+                            combination.push((parameter.name.clone(), value));
+                            combination
+                        })
+                    })
+                    .collect()
+            })
+    }
+
+    /// Get the name of the result file of a combination.
+    ///
+    /// # Arguments
+    ///
+    /// * `combination` - Value of each parameter.
+    ///
+    /// # Returns
+    ///
+    /// File name, e.g. `burn_rate=0.5,total_users=100.json`.
+    pub fn file_name(combination: &SweepCombination) -> String {
+        let name = combination
+            .iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        match name.is_empty() {
+            true => "baseline.json".to_string(),
+            false => format!("{name}.json"),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Sweep {
+    /// Run the sweep, writing the completed simulation of each combination to the output directory.
+    /// Combinations with an existing result file are skipped, to resume an interrupted sweep.
+    /// Results are written to a temporary file first, so an interruption never leaves a partial result.
+    ///
+    /// # Arguments
+    ///
+    /// * `scenario` - Simulation to run with each combination of parameters.
+    /// * `directory` - Output directory, created if missing.
+    /// * `concurrency` - Maximum number of simulations running at the same time, 0 to use the available parallelism.
+    /// * `progress` - Called after each batch of combinations, and once before the first.
+    ///
+    /// # Returns
+    ///
+    /// Final progress of the sweep, or the first error.
+    pub fn run<F: FnMut(&SweepProgress)>(
+        &self,
+        scenario: &Simulation,
+        directory: &Path,
+        concurrency: usize,
+        mut progress: F,
+    ) -> Result<SweepProgress, SimulationError> {
+        let io_error = |err: std::io::Error| SimulationError::InvalidSweep(err.to_string());
+        fs::create_dir_all(directory).map_err(io_error)?;
+
+        let combinations = self.combinations()?;
+        let mut state = SweepProgress {
+            total: combinations.len(),
+            ..Default::default()
+        };
+        let pending: Vec<_> = combinations
+            .into_iter()
+            .filter(|combination| !directory.join(Sweep::file_name(combination)).exists())
+            .collect();
+        state.skipped = state.total - pending.len();
+        progress(&state);
+
+        let batch_size = match concurrency {
+            0 => std::thread::available_parallelism().map_or(1, |parallelism| parallelism.get()),
+            concurrency => concurrency,
+        };
+        for batch in pending.chunks(batch_size) {
+            let simulations = batch
+                .iter()
+                .map(|combination| scenario.with_parameters(combination))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            for (combination, result) in batch
+                .iter()
+                .zip(Simulation::run_many(simulations, concurrency))
+            {
+                let path = directory.join(Sweep::file_name(combination));
+                let temporary = path.with_extension("json.tmp");
+                fs::write(&temporary, result?.to_json()?).map_err(io_error)?;
+                fs::rename(&temporary, &path).map_err(io_error)?;
+                state.completed += 1;
+            }
+            progress(&state);
+        }
+
+        Ok(state)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Simulation {
+    /// Create a pending copy of the simulation with the given parameter values.
+    /// A parameter is looked up by path, e.g. `token.burn_rate`, or by name in the token first, then the options.
+    ///
+    /// # Arguments
+    ///
+    /// * `parameters` - Value of each parameter.
+    ///
+    /// # Returns
+    ///
+    /// Copy of the simulation, or an error if a parameter does not exist or the value is invalid for it.
+    pub fn with_parameters(
+        &self,
+        parameters: &[(String, Decimal)],
+    ) -> Result<Simulation, SimulationError> {
+        let invalid = |err: serde_json::Error| SimulationError::InvalidSweep(err.to_string());
+        let mut value = serde_json::to_value(self).map_err(invalid)?;
+
+        for (name, parameter) in parameters {
+            let (sections, field) = match name.split_once('.') {
+                Some((section, field)) => (vec![section], field),
+                None => (vec!["token", "options"], name.as_str()),
+            };
+            let section = sections
+                .into_iter()
+                .find(|section| value[section].get(field).is_some());
+            let slot = section
+                .and_then(|section| value[section].get_mut(field))
+                .ok_or_else(|| {
+                    SimulationError::InvalidSweep(format!("unknown parameter {name}"))
+                })?;
+
+            // Integer fields, such as the number of users, keep an integer value
+            *slot = match (slot.is_u64() || slot.is_i64(), parameter.fract().is_zero()) {
+                (true, true) => {
+                    serde_json::Value::from(i64::try_from(parameter.trunc().mantissa()).map_err(
+                        |_| SimulationError::InvalidSweep(format!("{name} is out of range")),
+                    )?)
+                }
+                _ => serde_json::to_value(parameter.to_string().parse::<f64>().unwrap_or_default())
+                    .map_err(invalid)?,
+            };
+        }
+
+        let mut simulation: Simulation = serde_json::from_value(value).map_err(invalid)?;
+        simulation.id = uuid::Uuid::new_v4();
+        simulation.status = crate::SimulationStatus::Pending;
+        simulation.initial_token = None;
+        simulation.effective_options = None;
+        simulation.interval_reports = vec![];
+        simulation.report = Default::default();
+
+        Ok(simulation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_parameter() {
+        let parameter: SweepParameter = "burn_rate=0..5:0.5".parse().unwrap();
+
+        assert_eq!(parameter.name, "burn_rate");
+        assert_eq!(parameter.values().len(), 11);
+        assert_eq!(parameter.values()[10], Decimal::new(5, 0));
+        assert_eq!(
+            "total_users=10..12"
+                .parse::<SweepParameter>()
+                .unwrap()
+                .values(),
+            vec![
+                Decimal::new(10, 0),
+                Decimal::new(11, 0),
+                Decimal::new(12, 0)
+            ]
+        );
+
+        for invalid in [
+            "burn_rate",
+            "burn_rate=0:1",
+            "=0..1",
+            "x=1..0",
+            "x=0..1:0",
+            "x=a..b",
+        ] {
+            assert!(matches!(
+                invalid.parse::<SweepParameter>(),
+                Err(SimulationError::InvalidSweep(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_combinations() {
+        let sweep = Sweep::new(vec![
+            "burn_rate=0..0.1:0.05".parse().unwrap(),
+            "total_users=10..20:10".parse().unwrap(),
+        ]);
+
+        let combinations = sweep.combinations().unwrap();
+
+        assert_eq!(combinations.len(), 6);
+        assert_eq!(
+            Sweep::file_name(&combinations[1]),
+            "burn_rate=0,total_users=20.json"
+        );
+        assert_eq!(Sweep::new(vec![]).combinations().unwrap(), vec![vec![]]);
+        assert_eq!(Sweep::file_name(&vec![]), "baseline.json");
+    }
+
+    #[test]
+    fn test_sampling() {
+        assert_eq!(
+            "lhs:100:7".parse::<SweepSampling>().unwrap(),
+            SweepSampling::LatinHypercube {
+                samples: 100,
+                seed: 7
+            }
+        );
+        assert_eq!(
+            "sobol:64".parse::<SweepSampling>().unwrap(),
+            SweepSampling::Sobol { samples: 64 }
+        );
+        for invalid in ["lhs", "sobol:x", "grid:1", "random:10", "lhs:1:2:3"] {
+            assert!(invalid.parse::<SweepSampling>().is_err());
+        }
+
+        assert_eq!(
+            SweepSampling::Sobol { samples: 4 }.points(2).unwrap(),
+            vec![
+                vec![0.0, 0.0],
+                vec![0.5, 0.5],
+                vec![0.75, 0.25],
+                vec![0.25, 0.75]
+            ]
+        );
+
+        // Each value of each range is sampled once, with far fewer runs than the grid of 100 combinations
+        for sampling in [
+            SweepSampling::LatinHypercube {
+                samples: 10,
+                seed: 1,
+            },
+            SweepSampling::Sobol { samples: 16 },
+        ] {
+            let sweep = Sweep::new(vec![
+                "burn_rate=0..0.09:0.01".parse().unwrap(),
+                "total_users=1..10".parse().unwrap(),
+            ])
+            .sampling(sampling);
+
+            let combinations = sweep.combinations().unwrap();
+            assert!(combinations.len() >= 10 && combinations.len() <= 16);
+            for parameter in 0..2 {
+                let mut values: Vec<_> = combinations
+                    .iter()
+                    .map(|combination| combination[parameter].1)
+                    .collect();
+                values.sort();
+                values.dedup();
+                assert_eq!(values.len(), 10);
+            }
+        }
+
+        let sweep = Sweep::new(vec!["burn_rate=0..1:0.1".parse().unwrap(); 11])
+            .sampling(SweepSampling::Sobol { samples: 8 });
+        assert!(matches!(
+            sweep.combinations(),
+            Err(SimulationError::InvalidSweep(_))
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_run_resumes() {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(10)
+            .duration(2)
+            .build()
+            .unwrap();
+        let scenario = Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap();
+        let directory = std::env::temp_dir().join(format!("sweep-{}", uuid::Uuid::new_v4()));
+        let sweep = Sweep::new(vec![
+            "burn_rate=0..0.02:0.01".parse().unwrap(),
+            "total_users=10..20:10".parse().unwrap(),
+        ]);
+
+        // An interrupted sweep left one result
+        fs::create_dir_all(&directory).unwrap();
+        let first = Sweep::file_name(&sweep.combinations().unwrap()[0]);
+        fs::write(directory.join(&first), "{}").unwrap();
+
+        let mut reports = vec![];
+        let progress = sweep
+            .run(&scenario, &directory, 2, |progress| reports.push(*progress))
+            .unwrap();
+
+        assert_eq!(progress.total, 6);
+        assert_eq!(progress.skipped, 1);
+        assert_eq!(progress.completed, 5);
+        assert_eq!(reports.first().unwrap().done(), 1);
+        assert_eq!(reports.last().unwrap().done(), 6);
+
+        let json =
+            fs::read_to_string(directory.join("burn_rate=0.02,total_users=20.json")).unwrap();
+        let simulation = Simulation::from_json(&json).unwrap();
+        assert_eq!(simulation.token.burn_rate, Some(Decimal::new(2, 2)));
+        assert_eq!(simulation.options.total_users, 20);
+        assert!(!simulation.interval_reports.is_empty());
+        assert_eq!(fs::read_to_string(directory.join(first)).unwrap(), "{}");
+
+        assert!(matches!(
+            scenario.with_parameters(&[("unknown".to_string(), Decimal::ONE)]),
+            Err(SimulationError::InvalidSweep(_))
+        ));
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
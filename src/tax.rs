@@ -0,0 +1,479 @@
+//! # Tax module
+//!
+//! This module contains the tax policy of the users, and the tax records of their simulated flows.
+//!
+//! Tokens received for free, the airdrop, the gauge emission and the fees redirected to the holders, are income at their value on receipt,
+//! which also becomes their cost basis. Tokens bought are acquired at the price of their interval, and tokens sold or spent
+//! realize a gain or a loss against the average cost basis of the user.
+//! At the end of each tax year, the users owe tax on their positive gains and income of the year,
+//! and may sell tokens to cover it: the tax-sell event adds sell pressure, which pushes the price of the next interval down.
+
+use std::{cmp::Ordering, fmt::Write};
+
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{SimulationError, UserStore};
+
+/// Tax policy of the users.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct TaxPolicy {
+    /// Number of intervals in a tax year.
+    pub intervals_per_year: u64,
+
+    /// Tax rate on the gains and income of a year, in percentage.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub tax_rate: Decimal,
+
+    /// Share of the tax covered by selling tokens at the end of the year, in percentage.
+    #[cfg_attr(feature = "serde", serde(default, with = "rust_decimal::serde::float"))]
+    pub sell_share: Decimal,
+
+    /// Drop of the price, in percentage, per percentage of the circulating supply sold to cover the tax.
+    /// Whatever the price model, the later prices keep the drop.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub price_impact: Decimal,
+}
+
+/// Tax record of a user over the whole run.
+/// Values are in fiat, tokens sold in tokens.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct UserTaxRecord {
+    /// ID of the user.
+    pub user_id: Uuid,
+
+    /// Cost basis of the tokens the user holds at the end of the run.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub cost_basis: Decimal,
+
+    /// Gains realized on the tokens sold or spent, negative for losses.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub realized_gains: Decimal,
+
+    /// Value of the tokens received as income.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub income: Decimal,
+
+    /// Tax owed at the end of the tax years.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub tax_due: Decimal,
+
+    /// Tokens sold to cover the tax.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub tax_sold: Decimal,
+}
+
+/// Tax records of the users.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct TaxReport {
+    /// Record of each user, in the order the users joined.
+    pub records: Vec<UserTaxRecord>,
+}
+
+/// Tax position of a user during a run.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct TaxPosition {
+    /// Balance of the user at the last record.
+    balance: Decimal,
+
+    /// Cost basis of the balance.
+    cost: Decimal,
+
+    /// Gains realized over the run.
+    realized: Decimal,
+
+    /// Income over the run.
+    income: Decimal,
+
+    /// Gains and income of the current tax year.
+    taxable: Decimal,
+
+    /// Tax owed over the run.
+    tax_due: Decimal,
+
+    /// Tokens sold to cover the tax over the run.
+    tax_sold: Decimal,
+}
+
+/// Tracker of the tax positions of the users during a run.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct TaxTracker {
+    /// Position of each user, in the order of the users.
+    positions: Vec<TaxPosition>,
+}
+
+impl TaxPolicy {
+    /// Create a new tax policy, the users covering all their tax by selling tokens.
+    ///
+    /// # Arguments
+    ///
+    /// * `intervals_per_year` - Number of intervals in a tax year.
+    /// * `tax_rate` - Tax rate on the gains and income of a year, in percentage.
+    ///
+    /// # Returns
+    ///
+    /// New tax policy.
+    pub fn new(intervals_per_year: u64, tax_rate: Decimal) -> Self {
+        TaxPolicy {
+            intervals_per_year,
+            tax_rate,
+            sell_share: Decimal::ONE_HUNDRED,
+            price_impact: Decimal::ONE,
+        }
+    }
+
+    /// Validate the tax policy.
+    ///
+    /// # Returns
+    ///
+    /// Result of the validation, or an error if the tax year is empty, or a percentage is out of range.
+    pub fn validate(&self) -> Result<(), SimulationError> {
+        if self.intervals_per_year == 0 {
+            return Err(SimulationError::InvalidData(
+                "tax policy: intervals per year must be positive".to_string(),
+            ));
+        }
+        let percentage = |value: Decimal| (Decimal::ZERO..=Decimal::ONE_HUNDRED).contains(&value);
+        if !percentage(self.tax_rate) || !percentage(self.sell_share) {
+            return Err(SimulationError::InvalidData(
+                "tax policy: percentages must be between 0 and 100".to_string(),
+            ));
+        }
+        if self.price_impact < Decimal::ZERO {
+            return Err(SimulationError::InvalidData(
+                "tax policy: price impact must not be negative".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Check if an interval ends a tax year.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the interval.
+    ///
+    /// # Returns
+    ///
+    /// Whether the interval is the last one of a tax year.
+    pub fn is_year_end(&self, index: u64) -> bool {
+        self.intervals_per_year > 0 && (index + 1) % self.intervals_per_year == 0
+    }
+
+    /// Get the drop of the price caused by the tokens sold to cover the tax.
+    ///
+    /// # Arguments
+    ///
+    /// * `sold` - Tokens sold.
+    /// * `circulating_supply` - Circulating supply of the token.
+    ///
+    /// # Returns
+    ///
+    /// Drop of the price, between 0 and 1.
+    pub fn price_drop(&self, sold: Decimal, circulating_supply: Decimal) -> Decimal {
+        (sold * self.price_impact)
+            .checked_div(circulating_supply)
+            .unwrap_or_default()
+            .clamp(Decimal::ZERO, Decimal::ONE)
+    }
+}
+
+impl TaxReport {
+    /// Export the tax records as CSV, one user per line.
+    ///
+    /// # Returns
+    ///
+    /// CSV with a `user_id,cost_basis,realized_gains,income,tax_due,tax_sold` header.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("user_id,cost_basis,realized_gains,income,tax_due,tax_sold\n");
+
+        for record in &self.records {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{}",
+                record.user_id,
+                record.cost_basis,
+                record.realized_gains,
+                record.income,
+                record.tax_due,
+                record.tax_sold
+            );
+        }
+
+        csv
+    }
+}
+
+impl TaxTracker {
+    /// Create a new tracker, the users acquiring their balances at the given price.
+    ///
+    /// # Arguments
+    ///
+    /// * `users` - Users of the simulation.
+    /// * `price` - Price of the token.
+    ///
+    /// # Returns
+    ///
+    /// New tax tracker.
+    pub(crate) fn new(users: &UserStore, price: Decimal) -> Self {
+        let mut tracker = TaxTracker::default();
+        tracker.record(users, price, false);
+
+        tracker
+    }
+
+    /// Record the changes of the balances since the last record.
+    /// Increases are acquisitions, or income, and decreases are disposals realizing a gain or a loss.
+    ///
+    /// # Arguments
+    ///
+    /// * `users` - Users of the simulation.
+    /// * `price` - Price of the token.
+    /// * `income` - Whether the increases are income.
+    pub(crate) fn record(&mut self, users: &UserStore, price: Decimal, income: bool) {
+        if self.positions.len() < users.len() {
+            self.positions.resize(users.len(), TaxPosition::default());
+        }
+
+        for (position, balance) in self.positions.iter_mut().zip(&users.balances) {
+            let change = *balance - position.balance;
+            match change.cmp(&Decimal::ZERO) {
+                Ordering::Greater => {
+                    position.cost += change * price;
+                    if income {
+                        position.income += change * price;
+                        position.taxable += change * price;
+                    }
+                }
+                Ordering::Less => {
+                    let cost = position
+                        .cost
+                        .checked_div(position.balance)
+                        .unwrap_or_default()
+                        * -change;
+                    let gain = -change * price - cost;
+                    position.cost -= cost;
+                    position.realized += gain;
+                    position.taxable += gain;
+                }
+                Ordering::Equal => {}
+            }
+            position.balance = *balance;
+        }
+    }
+
+    /// Close a tax year: the users owe tax on their gains and income, and sell tokens to cover it.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - Tax policy of the users.
+    /// * `users` - Users of the simulation.
+    /// * `price` - Price of the token.
+    /// * `precision` - Decimal precision of the balances.
+    ///
+    /// # Returns
+    ///
+    /// Tokens sold to cover the tax.
+    pub(crate) fn close_year(
+        &mut self,
+        policy: &TaxPolicy,
+        users: &mut UserStore,
+        price: Decimal,
+        precision: u32,
+    ) -> Decimal {
+        self.record(users, price, false);
+
+        let mut sold = Decimal::default();
+        for (position, balance) in self.positions.iter_mut().zip(users.balances.iter_mut()) {
+            let tax = (position.taxable.max(Decimal::ZERO) * policy.tax_rate
+                / Decimal::ONE_HUNDRED)
+                .round_dp(precision);
+            position.tax_due += tax;
+            position.taxable = Decimal::default();
+
+            let amount = (tax * policy.sell_share / Decimal::ONE_HUNDRED)
+                .checked_div(price)
+                .unwrap_or_default()
+                .round_dp(precision)
+                .min(*balance);
+            *balance -= amount;
+            position.tax_sold += amount;
+            sold += amount;
+        }
+
+        // The tax sales realize gains of the next year
+        self.record(users, price, false);
+
+        sold
+    }
+
+    /// Get the tax records of the users.
+    ///
+    /// # Arguments
+    ///
+    /// * `users` - Users of the simulation.
+    /// * `precision` - Decimal precision of the values.
+    ///
+    /// # Returns
+    ///
+    /// Tax records of the users.
+    pub(crate) fn report(&self, users: &UserStore, precision: u32) -> TaxReport {
+        TaxReport {
+            records: self
+                .positions
+                .iter()
+                .zip(&users.ids)
+                .map(|(position, id)| UserTaxRecord {
+                    user_id: *id,
+                    cost_basis: position.cost.round_dp(precision),
+                    realized_gains: position.realized.round_dp(precision),
+                    income: position.income.round_dp(precision),
+                    tax_due: position.tax_due,
+                    tax_sold: position.tax_sold,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Simulation, TokenAmount, User, ValuationModel};
+
+    use super::*;
+
+    #[test]
+    fn test_tax_tracker() {
+        let mut users = UserStore::from(vec![User::new(Uuid::new_v4(), Decimal::new(10, 0))]);
+        let mut tracker = TaxTracker::new(&users, Decimal::ONE);
+
+        // 5 tokens received as income, 10 sold at twice the average cost
+        users.balances[0] += Decimal::new(5, 0);
+        tracker.record(&users, Decimal::ONE, true);
+        users.balances[0] -= Decimal::new(10, 0);
+        tracker.record(&users, Decimal::new(2, 0), false);
+
+        let policy = TaxPolicy {
+            sell_share: Decimal::new(50, 0),
+            ..TaxPolicy::new(12, Decimal::new(20, 0))
+        };
+        let sold = tracker.close_year(&policy, &mut users, Decimal::new(2, 0), 4);
+
+        // The tax is 20% of 5 of income and 10 of gains, half of it sold at a price of 2
+        assert_eq!(sold, Decimal::new(75, 2));
+        assert_eq!(users.balances[0], Decimal::new(425, 2));
+
+        let report = tracker.report(&users, 4);
+        let record = &report.records[0];
+        assert_eq!(record.income, Decimal::new(5, 0));
+        assert_eq!(record.realized_gains, Decimal::new(1075, 2));
+        assert_eq!(record.tax_due, Decimal::new(3, 0));
+        assert_eq!(record.tax_sold, Decimal::new(75, 2));
+        assert_eq!(record.cost_basis, Decimal::new(425, 2));
+        assert!(report
+            .to_csv()
+            .starts_with("user_id,cost_basis,realized_gains,income,tax_due,tax_sold\n"));
+    }
+
+    #[test]
+    fn test_policy() {
+        let policy = TaxPolicy::new(4, Decimal::new(30, 0));
+
+        assert!(policy.validate().is_ok());
+        assert!(!policy.is_year_end(2));
+        assert!(policy.is_year_end(3));
+        assert_eq!(
+            policy.price_drop(Decimal::new(10, 0), Decimal::new(1_000, 0)),
+            Decimal::new(1, 2)
+        );
+        assert!(TaxPolicy::new(0, Decimal::ONE).validate().is_err());
+    }
+
+    #[test]
+    fn test_run_with_tax_policy() {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .airdrop_percentage(5.0)
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .valuation_model(ValuationModel::RandomWalk)
+            .total_users(20)
+            .duration(6)
+            .seed(5)
+            .tax_policy(TaxPolicy::new(3, Decimal::new(25, 0)))
+            .build()
+            .unwrap();
+        let mut simulation = Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap();
+
+        simulation.run().unwrap();
+
+        let reports = &simulation.interval_reports;
+        assert_eq!(reports[0].tax_sold, TokenAmount::default());
+        // The airdrop is income taxed at the end of the first year
+        assert!(reports[2].tax_sold > TokenAmount::default());
+        assert_eq!(
+            simulation.report.tax_sold,
+            reports.iter().map(|report| report.tax_sold).sum()
+        );
+
+        let tax = simulation.report.tax_records.as_ref().unwrap();
+        assert_eq!(tax.records.len() as u64, simulation.report.final_user_count);
+        assert!(tax.records[0].income > Decimal::ZERO);
+        assert_eq!(
+            tax.records
+                .iter()
+                .map(|record| record.tax_sold)
+                .sum::<Decimal>(),
+            simulation.report.tax_sold.value()
+        );
+    }
+
+    #[test]
+    fn test_run_with_tax_policy_under_valuation_model() {
+        let run = |policy: Option<TaxPolicy>| {
+            let token = Simulation::token_builder()
+                .name("Test Token".to_string())
+                .airdrop_percentage(5.0)
+                .build()
+                .unwrap();
+            let mut options = Simulation::options_builder()
+                .valuation_model(ValuationModel::Linear)
+                .total_users(20)
+                .duration(6)
+                .seed(5)
+                .build()
+                .unwrap();
+            options.tax_policy = policy;
+            let mut simulation = Simulation::builder()
+                .name("Test Simulation".to_string())
+                .token(token)
+                .options(options)
+                .build()
+                .unwrap();
+
+            simulation.run().unwrap();
+            simulation
+        };
+
+        let usual = run(None);
+        let simulation = run(Some(TaxPolicy::new(3, Decimal::new(25, 0))));
+
+        // The tax sales at the end of the first year push the recalculated valuation down afterwards
+        let (reports, usual) = (&simulation.interval_reports, &usual.interval_reports);
+        assert!(reports[2].tax_sold > TokenAmount::default());
+        assert_eq!(reports[2].token_price, usual[2].token_price);
+        assert!(reports[3].token_price < usual[3].token_price);
+    }
+}
@@ -0,0 +1,421 @@
+//! # Test utilities module
+//!
+//! This module contains the golden-file regression harness, enabled by the `test_utils` feature.
+//!
+//! A seeded reference scenario is run and its reports are compared against a golden JSON file,
+//! so the crate and downstream users detect unintended behavioral changes of the engine.
+//! Numbers are compared with a tolerance, and fields like timestamps can be ignored.
+//! Set the `UPDATE_GOLDEN` environment variable to rewrite the golden files from the current behaviour.
+
+use std::{collections::BTreeMap, fs, path::Path};
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::{
+    CapitalInflow, Simulation, SimulationError, UserBehaviour, UserIdStrategy, UserLifecycle,
+    ValuationModel,
+};
+
+/// Seed of the reference scenario.
+pub const REFERENCE_SEED: u64 = 42;
+
+/// Environment variable rewriting the golden files instead of comparing against them.
+pub const UPDATE_GOLDEN_ENV: &str = "UPDATE_GOLDEN";
+
+/// Tolerance used to compare a value against its golden file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoldenTolerance {
+    /// Maximum absolute difference between two numbers.
+    pub absolute: f64,
+
+    /// Maximum difference between two numbers, relative to the largest of them.
+    pub relative: f64,
+
+    /// Names of the fields ignored wherever they appear, e.g. timestamps.
+    pub ignored_fields: Vec<String>,
+}
+
+impl Default for GoldenTolerance {
+    /// Create an exact tolerance, ignoring the timestamps of the reports.
+    ///
+    /// # Returns
+    ///
+    /// Default tolerance.
+    fn default() -> Self {
+        GoldenTolerance {
+            absolute: 0.0,
+            relative: 0.0,
+            ignored_fields: vec!["interval".to_string()],
+        }
+    }
+}
+
+/// Difference between a value and its golden file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoldenMismatch {
+    /// Path of the value, e.g. `report.trades` or `interval_reports[2].token_price`.
+    pub path: String,
+
+    /// Golden value, `None` if the field is missing from the golden file.
+    pub expected: Option<Value>,
+
+    /// Actual value, `None` if the field is missing from the actual value.
+    pub actual: Option<Value>,
+}
+
+/// Build the seeded reference scenario.
+/// It covers adoption, behaviours, lifecycle, burn, inflation, airdrop, capital inflow, and the random walk of the price.
+///
+/// # Returns
+///
+/// Reference simulation, ready to run.
+pub fn reference_simulation() -> Result<Simulation, SimulationError> {
+    let token = Simulation::token_builder()
+        .name("Reference Token".to_string())
+        .symbol("REF".to_string())
+        .total_supply(10_000_000)
+        .initial_price(1.0)
+        .airdrop_percentage(5.0)
+        .burn_rate(0.5)
+        .inflation_rate(1.0)
+        .build()?;
+
+    let mut options = Simulation::options_builder()
+        .duration(10)
+        .total_users(50)
+        .market_volatility(0.8)
+        .valuation_model(ValuationModel::RandomWalk)
+        .adoption_rate(0.02)
+        .transaction_fee_percentage(0.3)
+        .behaviour_mix(BTreeMap::from([
+            (UserBehaviour::Trader, 3.0),
+            (UserBehaviour::Holder, 1.0),
+            (UserBehaviour::Whale, 0.5),
+        ]))
+        .lifecycle(UserLifecycle {
+            active_to_dormant: 0.05,
+            dormant_to_churned: 0.1,
+            dormant_to_active: 0.2,
+        })
+        .capital_inflow(CapitalInflow {
+            new_user_purchase: Decimal::new(100, 0),
+            top_up_probability: 0.1,
+            top_up_amount: Decimal::new(50, 0),
+        })
+        .user_id_strategy(UserIdStrategy::Seeded(REFERENCE_SEED))
+        .seed(REFERENCE_SEED)
+        .build()?;
+    options.report_fields.users = false;
+
+    Simulation::builder()
+        .name("Reference Simulation".to_string())
+        .token(token)
+        .options(options)
+        .build()
+}
+
+/// Run the reference scenario.
+///
+/// # Returns
+///
+/// Snapshot of the completed reference simulation.
+pub fn run_reference() -> Result<Value, SimulationError> {
+    let mut simulation = reference_simulation()?;
+    simulation.run()?;
+
+    Ok(snapshot(&simulation))
+}
+
+/// Take a snapshot of the reports of a simulation, to compare against a golden file.
+///
+/// # Arguments
+///
+/// * `simulation` - Completed simulation.
+///
+/// # Returns
+///
+/// JSON with the interval reports and the final report.
+pub fn snapshot(simulation: &Simulation) -> Value {
+    json!({
+        "interval_reports": simulation.interval_reports,
+        "report": simulation.report,
+    })
+}
+
+/// Compare a value against its golden value.
+///
+/// # Arguments
+///
+/// * `expected` - Golden value.
+/// * `actual` - Actual value.
+/// * `tolerance` - Tolerance of the comparison.
+///
+/// # Returns
+///
+/// Differences between the values, empty if they match.
+pub fn compare_json(
+    expected: &Value,
+    actual: &Value,
+    tolerance: &GoldenTolerance,
+) -> Vec<GoldenMismatch> {
+    let mut mismatches = vec![];
+    compare_at(String::new(), expected, actual, tolerance, &mut mismatches);
+
+    mismatches
+}
+
+/// Compare a value against its golden file, and panic with the differences if they do not match.
+/// The golden file is written instead if it does not exist or `UPDATE_GOLDEN` is set.
+///
+/// # Arguments
+///
+/// * `path` - Path of the golden file.
+/// * `value` - Actual value.
+/// * `tolerance` - Tolerance of the comparison.
+pub fn assert_golden<T: Serialize>(path: impl AsRef<Path>, value: &T, tolerance: &GoldenTolerance) {
+    let path = path.as_ref();
+    let actual = serde_json::to_value(value).expect("value can be serialized");
+
+    if !path.exists() || std::env::var_os(UPDATE_GOLDEN_ENV).is_some() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("golden directory can be created");
+        }
+
+        let json = serde_json::to_string_pretty(&actual).expect("value can be serialized");
+        fs::write(path, json + "\n").expect("golden file can be written");
+
+        return;
+    }
+
+    let golden = fs::read_to_string(path).expect("golden file can be read");
+    let expected = serde_json::from_str(&golden).expect("golden file is valid JSON");
+    let mismatches = compare_json(&expected, &actual, tolerance);
+
+    if !mismatches.is_empty() {
+        let details = mismatches
+            .iter()
+            .take(20)
+            .map(|mismatch| {
+                format!(
+                    "  {}: expected {}, got {}",
+                    mismatch.path,
+                    display(&mismatch.expected),
+                    display(&mismatch.actual)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        panic!(
+            "{} differences with the golden file {}, set {} to update it:\n{}",
+            mismatches.len(),
+            path.display(),
+            UPDATE_GOLDEN_ENV,
+            details
+        );
+    }
+}
+
+/// Compare two values at the given path, recording the differences.
+///
+/// # Arguments
+///
+/// * `path` - Path of the values.
+/// * `expected` - Golden value.
+/// * `actual` - Actual value.
+/// * `tolerance` - Tolerance of the comparison.
+/// * `mismatches` - Differences found so far.
+fn compare_at(
+    path: String,
+    expected: &Value,
+    actual: &Value,
+    tolerance: &GoldenTolerance,
+    mismatches: &mut Vec<GoldenMismatch>,
+) {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            let keys = expected
+                .keys()
+                .chain(actual.keys())
+                .filter(|key| !tolerance.ignored_fields.contains(key))
+                .collect::<std::collections::BTreeSet<_>>();
+
+            for key in keys {
+                let field = match path.is_empty() {
+                    true => key.clone(),
+                    false => format!("{}.{}", path, key),
+                };
+
+                match (expected.get(key), actual.get(key)) {
+                    (Some(expected), Some(actual)) => {
+                        compare_at(field, expected, actual, tolerance, mismatches)
+                    }
+                    (expected, actual) => mismatches.push(GoldenMismatch {
+                        path: field,
+                        expected: expected.cloned(),
+                        actual: actual.cloned(),
+                    }),
+                }
+            }
+        }
+        (Value::Array(expected_items), Value::Array(actual_items))
+            if expected_items.len() == actual_items.len() =>
+        {
+            for (index, (expected, actual)) in expected_items.iter().zip(actual_items).enumerate() {
+                compare_at(
+                    format!("{}[{}]", path, index),
+                    expected,
+                    actual,
+                    tolerance,
+                    mismatches,
+                );
+            }
+        }
+        _ if expected == actual || numbers_match(expected, actual, tolerance) => {}
+        _ => mismatches.push(GoldenMismatch {
+            path,
+            expected: Some(expected.clone()),
+            actual: Some(actual.clone()),
+        }),
+    }
+}
+
+/// Check whether two values are numbers within the tolerance.
+/// Numbers encoded as strings, e.g. decimals, are compared as numbers too.
+///
+/// # Arguments
+///
+/// * `expected` - Golden value.
+/// * `actual` - Actual value.
+/// * `tolerance` - Tolerance of the comparison.
+///
+/// # Returns
+///
+/// Whether the values are matching numbers.
+fn numbers_match(expected: &Value, actual: &Value, tolerance: &GoldenTolerance) -> bool {
+    let number = |value: &Value| match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(string) => string.parse::<f64>().ok(),
+        _ => None,
+    };
+
+    match (number(expected), number(actual)) {
+        (Some(expected), Some(actual)) => {
+            let allowed = tolerance
+                .absolute
+                .max(tolerance.relative * expected.abs().max(actual.abs()));
+
+            (expected - actual).abs() <= allowed
+        }
+        _ => false,
+    }
+}
+
+/// Display a value of a mismatch.
+///
+/// # Arguments
+///
+/// * `value` - Value to display.
+///
+/// # Returns
+///
+/// JSON of the value, or `missing`.
+fn display(value: &Option<Value>) -> String {
+    match value {
+        Some(value) => value.to_string(),
+        None => "missing".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reference_scenario_matches_golden() {
+        let snapshot = run_reference().unwrap();
+
+        // Floating point functions may differ in the last digits across platforms
+        let tolerance = GoldenTolerance {
+            absolute: 1e-3,
+            relative: 1e-6,
+            ..GoldenTolerance::default()
+        };
+
+        assert_golden(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/reference.json"),
+            &snapshot,
+            &tolerance,
+        );
+    }
+
+    #[test]
+    fn test_reference_scenario_is_deterministic() {
+        let first = run_reference().unwrap();
+        let second = run_reference().unwrap();
+
+        assert!(compare_json(&first, &second, &GoldenTolerance::default()).is_empty());
+    }
+
+    #[test]
+    fn test_compare_json() {
+        let expected = json!({
+            "interval": 1,
+            "price": 1.0,
+            "supply": "100.5",
+            "reports": [{ "trades": 3 }, { "trades": 4 }],
+            "removed": true,
+        });
+        let actual = json!({
+            "interval": 2,
+            "price": 1.05,
+            "supply": "100.5",
+            "reports": [{ "trades": 3 }, { "trades": 5 }],
+            "added": true,
+        });
+
+        let mismatches = compare_json(&expected, &actual, &GoldenTolerance::default());
+        let paths = mismatches
+            .iter()
+            .map(|mismatch| mismatch.path.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            paths,
+            vec!["added", "price", "removed", "reports[1].trades"]
+        );
+        assert_eq!(mismatches[0].expected, None);
+        assert_eq!(mismatches[2].actual, None);
+
+        let tolerance = GoldenTolerance {
+            absolute: 1.0,
+            ignored_fields: vec![
+                "interval".to_string(),
+                "added".to_string(),
+                "removed".to_string(),
+            ],
+            ..GoldenTolerance::default()
+        };
+        assert!(compare_json(&expected, &actual, &tolerance).is_empty());
+    }
+
+    #[test]
+    fn test_compare_json_relative_tolerance() {
+        let tolerance = GoldenTolerance {
+            relative: 0.01,
+            ..GoldenTolerance::default()
+        };
+
+        assert!(compare_json(&json!(1000.0), &json!(1009.0), &tolerance).is_empty());
+        assert_eq!(
+            compare_json(&json!(1000.0), &json!(1011.0), &tolerance).len(),
+            1
+        );
+        assert_eq!(
+            compare_json(&json!([1]), &json!([1, 2]), &tolerance).len(),
+            1
+        );
+    }
+}
@@ -8,6 +8,10 @@ use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::{
+    FiatAmount, Percentage, SimulationError, SupplyLedger, TokenAmount, TradeSide, VestingSchedule,
+};
+
 /// Token.
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
@@ -25,45 +29,209 @@ pub struct Token {
 
     /// Total supply of the token.
     /// The total supply is the maximum number of tokens that can ever exist.
-    #[cfg_attr(
-        feature = "serde",
-        serde(with = "rust_decimal::serde::arbitrary_precision")
-    )]
-    pub total_supply: Decimal,
+    #[cfg_attr(feature = "serde", serde(with = "crate::amount::arbitrary_precision"))]
+    pub total_supply: TokenAmount,
 
     /// Current supply of the token.
     /// The current supply is the number of tokens that have been minted or airdropped.
-    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
-    pub current_supply: Decimal,
+    pub current_supply: TokenAmount,
 
     /// Initial supply of the token, in percentage of total supply.
     /// The initial supply is the number of tokens that are minted at the start of the simulation.
-    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
-    pub initial_supply_percentage: Decimal,
+    pub initial_supply_percentage: Percentage,
 
     /// Annual percentage increase in supply, if supply is inflationary.
     /// The inflation rate is the percentage by which the total supply increases each year.
     #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float_option"))]
     pub inflation_rate: Option<Decimal>,
 
-    /// Percentage of tokens burned during each transaction, if deflationary.
-    /// The burn rate is the percentage of tokens that are destroyed during each transaction.
+    /// Fraction of tokens burned during each transaction, if deflationary, e.g. 0.01 for 1%.
+    /// The burn rate is the fraction of tokens that are destroyed during each transaction.
     #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float_option"))]
     pub burn_rate: Option<Decimal>,
 
     /// Initial price of the token in simulation.
     /// The initial price is the price of the token at the start of the simulation.
-    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
-    pub initial_price: Decimal,
+    pub initial_price: FiatAmount,
 
     /// Airdrop amount of the token, in percentage of total supply.
     /// The airdrop percentage is the percentage of the total supply that is airdropped at the start of the simulation.
-    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float_option"))]
-    pub airdrop_percentage: Option<Decimal>,
+    pub airdrop_percentage: Option<Percentage>,
 
     /// Unlock schedule.
     /// The unlock schedule is a list of unlock events, each with a date and amount of tokens to unlock.
     pub unlock_schedule: Option<Vec<UnlockEvent>>,
+
+    /// Tax charged on each buy, in percentage of the trade amount.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub buy_tax: Option<Percentage>,
+
+    /// Tax charged on each sell, in percentage of the trade amount.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub sell_tax: Option<Percentage>,
+
+    /// Routing of the collected taxes.
+    /// If not set, all taxes are sent to the treasury.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tax_routing: Option<Vec<TaxRoute>>,
+
+    /// Stream of tokens emitted to contributors (grants, core team, bounties) each interval.
+    /// Tracked separately from inflation.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub contributor_emission: Option<ContributorEmission>,
+
+    /// Halving emission schedule of block rewards, for PoW/PoS-inspired tokens.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub halving_schedule: Option<HalvingSchedule>,
+
+    /// Metadata of the token, to cross-reference the simulation with a real deployment.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub metadata: Option<TokenMetadata>,
+
+    /// Ledger of the supply-changing events, recorded during the runs.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub ledger: SupplyLedger,
+
+    /// Allocations of the total supply, each unlocked by its own vesting schedule.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub allocations: Vec<TokenAllocation>,
+
+    /// Number of decimals of the token, used to convert amounts to smallest units.
+    #[cfg_attr(feature = "serde", serde(default = "default_decimals"))]
+    pub decimals: u32,
+}
+
+/// Get the default number of decimals of a token.
+///
+/// # Returns
+///
+/// Default number of decimals.
+#[cfg(feature = "serde")]
+fn default_decimals() -> u32 {
+    crate::DEFAULT_DECIMALS
+}
+
+/// Allocation of the total supply, e.g. to the team or the investors.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct TokenAllocation {
+    /// Name of the allocation.
+    pub name: String,
+
+    /// Share of the total supply, in percentage.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub percentage: Decimal,
+
+    /// Vesting schedule unlocking the allocation.
+    pub vesting: VestingSchedule,
+
+    /// Price paid per token, if the allocation was sold in a sale round.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub price: Option<Decimal>,
+}
+
+/// Metadata of a token deployment.
+/// It does not affect the simulation, and is carried to the final report.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct TokenMetadata {
+    /// ID of the chain the token is deployed on, e.g. 1 for Ethereum mainnet.
+    pub chain_id: Option<u64>,
+
+    /// Address of the token contract.
+    pub contract_address: Option<String>,
+
+    /// Category of the token, e.g. `defi` or `gaming`.
+    pub category: Option<String>,
+
+    /// Free-form tags of the token.
+    pub tags: Vec<String>,
+
+    /// Description of the token.
+    pub description: Option<String>,
+}
+
+/// Halving emission schedule of block rewards.
+/// The reward is halved every `halving_interval` intervals.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct HalvingSchedule {
+    /// Block rewards emitted each interval, before the first halving.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub initial_reward: Decimal,
+
+    /// Number of intervals between two halvings.
+    pub halving_interval: u64,
+}
+
+/// Stream of tokens emitted to contributors each interval.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct ContributorEmission {
+    /// Amount of tokens emitted to contributors each interval.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub amount_per_interval: Decimal,
+
+    /// Share of the emitted tokens contributors sell immediately, between 0 and 1.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub sell_through: Decimal,
+}
+
+/// Destination of the collected taxes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum TaxDestination {
+    /// Treasury wallet of the project.
+    Treasury,
+
+    /// Marketing wallet of the project.
+    Marketing,
+
+    /// Liquidity pool of the token.
+    Liquidity,
+
+    /// Taxes are burned, removing them from the circulating supply.
+    Burn,
+}
+
+/// Route of the collected taxes.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct TaxRoute {
+    /// Destination of the taxes.
+    pub destination: TaxDestination,
+
+    /// Share of the taxes sent to the destination, in percentage.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub share: Decimal,
+}
+
+impl TaxRoute {
+    /// Validate a list of tax routes, whose shares must split the whole tax.
+    ///
+    /// # Arguments
+    ///
+    /// * `routes` - List of tax routes.
+    ///
+    /// # Returns
+    ///
+    /// Result of the validation, or an error if a share is negative or the shares do not sum to 100.
+    pub fn validate_shares(routes: &[TaxRoute]) -> Result<(), SimulationError> {
+        if routes.iter().any(|route| route.share < Decimal::ZERO) {
+            return Err(SimulationError::InvalidData(
+                "tax routing: shares must not be negative".to_string(),
+            ));
+        }
+        if routes.iter().map(|route| route.share).sum::<Decimal>() != Decimal::ONE_HUNDRED {
+            return Err(SimulationError::InvalidData(
+                "tax routing: shares must sum to 100".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
 }
 
 /// Unlock event.
@@ -89,7 +257,7 @@ impl Token {
     /// # Returns
     ///
     /// The amount of tokens airdropped.
-    pub fn airdrop(&mut self, percentage: Decimal) -> Decimal {
+    pub fn airdrop(&mut self, percentage: Percentage) -> TokenAmount {
         #[cfg(feature = "log")]
         log::debug!(
             "Airdropping {}% of total supply for token {}",
@@ -97,17 +265,26 @@ impl Token {
             self.name
         );
 
-        let airdrop_amount = (self.total_supply * percentage / Decimal::new(100, 0)).round();
+        self.issue(percentage.of(self.total_supply).round())
+    }
+
+    /// Issue tokens from the supply not minted yet, e.g. to distribute them to the users.
+    /// The issued amount is capped by the remaining supply.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - The amount of tokens to issue.
+    ///
+    /// # Returns
+    ///
+    /// The amount of tokens issued.
+    pub fn issue(&mut self, amount: TokenAmount) -> TokenAmount {
         let remaining_supply = self.total_supply - self.current_supply;
-        let final_airdrop_amount = if airdrop_amount > remaining_supply {
-            remaining_supply
-        } else {
-            airdrop_amount
-        };
+        let issued = amount.min(remaining_supply);
 
-        self.current_supply += final_airdrop_amount;
+        self.current_supply += issued;
 
-        final_airdrop_amount
+        issued
     }
 
     /// Add an unlock event to the schedule.
@@ -117,7 +294,7 @@ impl Token {
     ///
     /// * `date` - The date and time of the unlock event.
     /// * `amount` - The amount of tokens to unlock.
-    pub fn add_unlock_event(&mut self, date: DateTime<Utc>, amount: Decimal) {
+    pub fn add_unlock_event(&mut self, date: DateTime<Utc>, amount: TokenAmount) {
         #[cfg(feature = "log")]
         log::debug!(
             "Adding unlock event for token {} on {} for {} tokens",
@@ -126,7 +303,10 @@ impl Token {
             amount
         );
 
-        let event = UnlockEvent { date, amount };
+        let event = UnlockEvent {
+            date,
+            amount: amount.value(),
+        };
 
         if let Some(schedule) = &mut self.unlock_schedule {
             schedule.push(event);
@@ -148,7 +328,7 @@ impl Token {
 
             schedule.retain(|event| {
                 if event.date <= current_date {
-                    self.current_supply += event.amount;
+                    self.current_supply += TokenAmount(event.amount);
                     false
                 } else {
                     true
@@ -157,14 +337,232 @@ impl Token {
         }
     }
 
+    /// Emit the contributor stream for one interval.
+    /// The emission is capped by the remaining supply.
+    ///
+    /// # Returns
+    ///
+    /// The amount of tokens emitted, and the amount of emitted tokens sold immediately.
+    pub fn emit_to_contributors(&mut self) -> (TokenAmount, TokenAmount) {
+        let Some(emission) = &self.contributor_emission else {
+            return (TokenAmount::default(), TokenAmount::default());
+        };
+
+        let remaining_supply = (self.total_supply - self.current_supply).value();
+        let emitted = emission
+            .amount_per_interval
+            .min(remaining_supply)
+            .max(Decimal::default());
+        let sold = emitted * emission.sell_through;
+
+        #[cfg(feature = "log")]
+        log::debug!(
+            "Emitting {} tokens to contributors of token {}, {} sold",
+            emitted,
+            self.name,
+            sold
+        );
+
+        self.current_supply += TokenAmount(emitted);
+
+        (TokenAmount(emitted), TokenAmount(sold))
+    }
+
+    /// Calculate the block rewards of the given interval, according to the halving schedule.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - Index of the interval, starting at 0.
+    ///
+    /// # Returns
+    ///
+    /// Block rewards of the interval, or zero if the token has no halving schedule.
+    pub fn block_reward(&self, interval: u64) -> TokenAmount {
+        let reward = match &self.halving_schedule {
+            Some(schedule) if schedule.halving_interval > 0 => {
+                let halvings = interval / schedule.halving_interval;
+
+                // After 96 halvings the reward is below the smallest representable decimal
+                if halvings >= 96 {
+                    return TokenAmount::default();
+                }
+
+                schedule.initial_reward / Decimal::from(1u128 << halvings)
+            }
+            Some(schedule) => schedule.initial_reward,
+            None => Decimal::default(),
+        };
+
+        TokenAmount(reward)
+    }
+
+    /// Emit the block rewards of the given interval.
+    /// The emission is capped by the remaining supply.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - Index of the interval, starting at 0.
+    ///
+    /// # Returns
+    ///
+    /// The amount of tokens emitted as block rewards.
+    pub fn emit_block_rewards(&mut self, interval: u64) -> TokenAmount {
+        let remaining_supply = self.total_supply - self.current_supply;
+        let emitted = self
+            .block_reward(interval)
+            .min(remaining_supply)
+            .max(TokenAmount::default());
+
+        self.current_supply += emitted;
+
+        emitted
+    }
+
+    /// Calculate the tax charged on a trade.
+    ///
+    /// # Arguments
+    ///
+    /// * `side` - Direction of the trade.
+    /// * `amount` - Amount of tokens traded.
+    ///
+    /// # Returns
+    ///
+    /// Tax charged on the trade, or zero if the token has no tax for this direction.
+    pub fn calculate_tax(&self, side: TradeSide, amount: TokenAmount) -> TokenAmount {
+        let rate = match side {
+            TradeSide::Buy => self.buy_tax,
+            TradeSide::Sell => self.sell_tax,
+        };
+
+        match rate {
+            Some(rate) => rate.of(amount),
+            None => TokenAmount::default(),
+        }
+    }
+
+    /// Calculate the tokens burned on a trade.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - Amount of tokens traded.
+    ///
+    /// # Returns
+    ///
+    /// Tokens burned on the trade, or zero if the token has no burn rate.
+    pub fn calculate_burn(&self, amount: TokenAmount) -> TokenAmount {
+        match self.burn_rate {
+            Some(rate) => Percentage::from_fraction(rate).of(amount),
+            None => TokenAmount::default(),
+        }
+    }
+
+    /// Calculate the tokens minted on a trade.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - Amount of tokens traded.
+    ///
+    /// # Returns
+    ///
+    /// Tokens minted on the trade, or zero if the token has no inflation rate.
+    pub fn calculate_mint(&self, amount: TokenAmount) -> TokenAmount {
+        match self.inflation_rate {
+            Some(rate) => Percentage::from_fraction(rate).of(amount),
+            None => TokenAmount::default(),
+        }
+    }
+
+    /// Split the collected taxes between the configured destinations.
+    ///
+    /// # Arguments
+    ///
+    /// * `tax` - Amount of collected taxes.
+    ///
+    /// # Returns
+    ///
+    /// Amount of taxes sent to each destination.
+    pub fn route_tax(&self, tax: TokenAmount) -> Vec<(TaxDestination, TokenAmount)> {
+        match &self.tax_routing {
+            Some(routes) => routes
+                .iter()
+                .map(|route| (route.destination, Percentage(route.share).of(tax)))
+                .collect(),
+            None => vec![(TaxDestination::Treasury, tax)],
+        }
+    }
+
     /// Calculate the initial supply based on the initial supply percentage.
     /// The initial supply is the number of tokens that are minted at the start of the simulation.
     ///
     /// # Returns
     ///
     /// Initial supply of the token.
-    pub fn initial_supply(&self) -> Decimal {
-        (self.total_supply * self.initial_supply_percentage / Decimal::new(100, 0)).round()
+    pub fn initial_supply(&self) -> TokenAmount {
+        self.initial_supply_percentage.of(self.total_supply).round()
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Token {
+    /// Generate an arbitrary valid token.
+    ///
+    /// # Arguments
+    ///
+    /// * `u` - Unstructured data to generate the token from.
+    ///
+    /// # Returns
+    ///
+    /// Arbitrary token.
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut builder = crate::TokenBuilder::new()
+            .name(String::arbitrary(u)?)
+            .symbol(String::arbitrary(u)?)
+            .total_supply(u.int_in_range(1..=1_000_000_000_000)?)
+            .initial_supply_percentage(u.int_in_range(0..=10_000)? as f64 / 100.0)
+            .initial_price(u.int_in_range(1..=100_000_000)? as f64 / 10_000.0)
+            .unlock_schedule(Vec::<UnlockEvent>::arbitrary(u)?);
+
+        if bool::arbitrary(u)? {
+            builder = builder.inflation_rate(u.int_in_range(0..=1_000)? as f64 / 10_000.0);
+        }
+        if bool::arbitrary(u)? {
+            builder = builder.burn_rate(u.int_in_range(0..=1_000)? as f64 / 10_000.0);
+        }
+        if bool::arbitrary(u)? {
+            builder = builder.airdrop_percentage(u.int_in_range(0..=10_000)? as f64 / 100.0);
+        }
+        if bool::arbitrary(u)? {
+            builder = builder.buy_tax(u.int_in_range(0..=2_500)? as f64 / 100.0);
+        }
+        if bool::arbitrary(u)? {
+            builder = builder.sell_tax(u.int_in_range(0..=2_500)? as f64 / 100.0);
+        }
+
+        builder
+            .build()
+            .map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for UnlockEvent {
+    /// Generate an arbitrary unlock event, dated between 1970 and 2100.
+    ///
+    /// # Arguments
+    ///
+    /// * `u` - Unstructured data to generate the unlock event from.
+    ///
+    /// # Returns
+    ///
+    /// Arbitrary unlock event.
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let timestamp = u.int_in_range(0..=4_102_444_800)?;
+
+        Ok(UnlockEvent {
+            date: DateTime::from_timestamp(timestamp, 0)
+                .ok_or(arbitrary::Error::IncorrectFormat)?,
+            amount: Decimal::new(u.int_in_range(0..=1_000_000_000_000)?, 0),
+        })
     }
 }
 
@@ -183,15 +581,187 @@ mod tests {
             .unwrap();
         let final_amount = Decimal::new(100000, 0);
 
-        let airdrop_amount = token.airdrop(Decimal::new(10, 0));
+        let airdrop_amount = token.airdrop(Percentage(Decimal::new(10, 0)));
 
-        assert_eq!(airdrop_amount, final_amount);
-        assert_eq!(token.current_supply, final_amount);
+        assert_eq!(airdrop_amount, TokenAmount(final_amount));
+        assert_eq!(token.current_supply, TokenAmount(final_amount));
+
+        let airdrop_amount = token.airdrop(Percentage(Decimal::new(100, 0)));
+
+        assert_eq!(airdrop_amount, TokenAmount(Decimal::new(900000, 0)));
+        assert_eq!(
+            token.current_supply,
+            TokenAmount(Decimal::new(1_000_000, 0))
+        );
+    }
 
-        let airdrop_amount = token.airdrop(Decimal::new(100, 0));
+    #[test]
+    fn test_token_issue() {
+        let mut token = TokenBuilder::new()
+            .name("Test Token".to_string())
+            .total_supply(1_000)
+            .current_supply(900.0)
+            .build()
+            .unwrap();
 
-        assert_eq!(airdrop_amount, Decimal::new(900000, 0));
-        assert_eq!(token.current_supply, Decimal::new(1_000_000, 0));
+        assert_eq!(
+            token.issue(TokenAmount(Decimal::new(60, 0))),
+            TokenAmount(Decimal::new(60, 0))
+        );
+        assert_eq!(
+            token.issue(TokenAmount(Decimal::new(60, 0))),
+            TokenAmount(Decimal::new(40, 0))
+        );
+        assert_eq!(token.current_supply, TokenAmount(Decimal::new(1_000, 0)));
+    }
+
+    #[test]
+    fn test_calculate_tax() {
+        let token = TokenBuilder::new()
+            .name("Test Token".to_string())
+            .buy_tax(2.0)
+            .sell_tax(5.0)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            token.calculate_tax(TradeSide::Buy, TokenAmount(Decimal::new(100, 0))),
+            TokenAmount(Decimal::new(2, 0))
+        );
+        assert_eq!(
+            token.calculate_tax(TradeSide::Sell, TokenAmount(Decimal::new(100, 0))),
+            TokenAmount(Decimal::new(5, 0))
+        );
+
+        let untaxed = TokenBuilder::new()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        assert_eq!(
+            untaxed.calculate_tax(TradeSide::Sell, TokenAmount(Decimal::new(100, 0))),
+            TokenAmount::default()
+        );
+    }
+
+    #[test]
+    fn test_calculate_burn_and_mint() {
+        let token = TokenBuilder::new()
+            .name("Test Token".to_string())
+            .burn_rate(0.01)
+            .inflation_rate(0.02)
+            .build()
+            .unwrap();
+        let amount = TokenAmount(Decimal::new(100, 0));
+
+        // Burn and inflation rates are fractions, unlike the taxes
+        assert_eq!(token.calculate_burn(amount), TokenAmount(Decimal::ONE));
+        assert_eq!(token.calculate_mint(amount), TokenAmount(Decimal::TWO));
+    }
+
+    #[test]
+    fn test_route_tax() {
+        let mut token = TokenBuilder::new()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            token.route_tax(TokenAmount(Decimal::new(10, 0))),
+            vec![(TaxDestination::Treasury, TokenAmount(Decimal::new(10, 0)))]
+        );
+
+        token.tax_routing = Some(vec![
+            TaxRoute {
+                destination: TaxDestination::Marketing,
+                share: Decimal::new(30, 0),
+            },
+            TaxRoute {
+                destination: TaxDestination::Burn,
+                share: Decimal::new(70, 0),
+            },
+        ]);
+
+        assert_eq!(
+            token.route_tax(TokenAmount(Decimal::new(10, 0))),
+            vec![
+                (TaxDestination::Marketing, TokenAmount(Decimal::new(3, 0))),
+                (TaxDestination::Burn, TokenAmount(Decimal::new(7, 0))),
+            ]
+        );
+        assert!(TaxRoute::validate_shares(token.tax_routing.as_ref().unwrap()).is_ok());
+
+        // Routes losing or inventing part of the tax are rejected
+        for share in [30, 150] {
+            let route = TaxRoute {
+                destination: TaxDestination::Treasury,
+                share: Decimal::new(share, 0),
+            };
+            assert!(matches!(
+                TaxRoute::validate_shares(&[route.clone(), route]),
+                Err(SimulationError::InvalidData(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_emit_to_contributors() {
+        let mut token = TokenBuilder::new()
+            .name("Test Token".to_string())
+            .total_supply(1_000)
+            .current_supply(950.0)
+            .contributor_emission(ContributorEmission {
+                amount_per_interval: Decimal::new(40, 0),
+                sell_through: Decimal::new(25, 2),
+            })
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            token.emit_to_contributors(),
+            (
+                TokenAmount(Decimal::new(40, 0)),
+                TokenAmount(Decimal::new(10, 0))
+            )
+        );
+        assert_eq!(token.current_supply, TokenAmount(Decimal::new(990, 0)));
+
+        let (emitted, _) = token.emit_to_contributors();
+        assert_eq!(emitted, TokenAmount(Decimal::new(10, 0)));
+        assert_eq!(token.current_supply, token.total_supply);
+
+        token.contributor_emission = None;
+        assert_eq!(
+            token.emit_to_contributors(),
+            (TokenAmount::default(), TokenAmount::default())
+        );
+    }
+
+    #[test]
+    fn test_block_reward() {
+        let mut token = TokenBuilder::new()
+            .name("Test Token".to_string())
+            .total_supply(1_000)
+            .halving_schedule(HalvingSchedule {
+                initial_reward: Decimal::new(100, 0),
+                halving_interval: 2,
+            })
+            .build()
+            .unwrap();
+
+        assert_eq!(token.block_reward(0), TokenAmount(Decimal::new(100, 0)));
+        assert_eq!(token.block_reward(1), TokenAmount(Decimal::new(100, 0)));
+        assert_eq!(token.block_reward(2), TokenAmount(Decimal::new(50, 0)));
+        assert_eq!(token.block_reward(5), TokenAmount(Decimal::new(25, 0)));
+        assert_eq!(token.block_reward(1_000), TokenAmount::default());
+
+        assert_eq!(
+            token.emit_block_rewards(4),
+            TokenAmount(Decimal::new(25, 0))
+        );
+        assert_eq!(token.current_supply, TokenAmount(Decimal::new(25, 0)));
+
+        token.halving_schedule = None;
+        assert_eq!(token.block_reward(0), TokenAmount::default());
     }
 
     #[test]
@@ -202,7 +772,7 @@ mod tests {
             .build()
             .unwrap();
         let date = Utc::now();
-        let amount = Decimal::new(100000, 0);
+        let amount = TokenAmount(Decimal::new(100000, 0));
 
         token.add_unlock_event(date, amount);
         token.add_unlock_event(date, amount);
@@ -219,12 +789,42 @@ mod tests {
             .unwrap();
         let date = Utc::now();
         let amount = Decimal::new(100000, 0);
-        token.add_unlock_event(date, amount);
+        token.add_unlock_event(date, TokenAmount(amount));
 
         let current_date = date + chrono::Duration::days(1);
         token.process_unlocks(current_date);
 
-        assert_eq!(token.current_supply, amount);
+        assert_eq!(token.current_supply, TokenAmount(amount));
         assert!(token.unlock_schedule.unwrap().is_empty());
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let token = TokenBuilder::new()
+            .name("Test Token".to_string())
+            .burn_rate(0.01)
+            .buy_tax(2.0)
+            .tax_routing(vec![TaxRoute {
+                destination: TaxDestination::Marketing,
+                share: Decimal::new(100, 0),
+            }])
+            .contributor_emission(ContributorEmission {
+                amount_per_interval: Decimal::new(100, 0),
+                sell_through: Decimal::new(5, 1),
+            })
+            .halving_schedule(HalvingSchedule {
+                initial_reward: Decimal::new(50, 0),
+                halving_interval: 10,
+            })
+            .unlock_schedule(vec![UnlockEvent {
+                date: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+                amount: Decimal::new(1_000, 0),
+            }])
+            .build()
+            .unwrap();
+
+        let json = serde_json::to_string(&token).unwrap();
+        assert_eq!(serde_json::from_str::<Token>(&json).unwrap(), token);
+    }
 }
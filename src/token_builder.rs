@@ -2,17 +2,23 @@
 //!
 //! The module provides a builder for creating a new token with the specified parameters.
 
+use chrono::Utc;
 use rust_decimal::{prelude::FromPrimitive, Decimal};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::{SimulationError, Token, UnlockEvent};
+use crate::{
+    BaseUnits, ContributorEmission, FiatAmount, HalvingSchedule, Percentage, SimulationError,
+    SupplyLedger, TaxRoute, Token, TokenAllocation, TokenAmount, TokenMetadata, UnlockEvent,
+    VestingScheduleBuilder, DEFAULT_DECIMALS,
+};
 
 /// Builder for creating a new token.
 /// The builder allows to configure the token with the following parameters.
 #[derive(Debug, Default, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "strict_serde", serde(deny_unknown_fields))]
 pub struct TokenBuilder {
     /// Name of the token.
     /// Required field.
@@ -53,6 +59,63 @@ pub struct TokenBuilder {
     /// Unlock schedule.
     /// Optional field.
     pub unlock_schedule: Option<Vec<UnlockEvent>>,
+
+    /// Tax charged on each buy, in percentage of the trade amount.
+    /// Optional field.
+    pub buy_tax: Option<f64>,
+
+    /// Tax charged on each sell, in percentage of the trade amount.
+    /// Optional field.
+    pub sell_tax: Option<f64>,
+
+    /// Routing of the collected taxes.
+    /// Optional field.
+    pub tax_routing: Option<Vec<TaxRoute>>,
+
+    /// Stream of tokens emitted to contributors each interval.
+    /// Optional field.
+    pub contributor_emission: Option<ContributorEmission>,
+
+    /// Halving emission schedule of block rewards.
+    /// Optional field.
+    pub halving_schedule: Option<HalvingSchedule>,
+
+    /// Metadata of the token deployment.
+    /// Optional field.
+    pub metadata: Option<TokenMetadata>,
+
+    /// Allocations of the total supply, each with its own vesting schedule.
+    /// Optional field, the percentages must sum to at most 100%.
+    pub allocations: Vec<AllocationConfig>,
+
+    /// Number of decimals of the token.
+    /// Default value: 18.
+    pub decimals: Option<u32>,
+
+    /// Total supply of the token in smallest units, for supplies that do not fit in `total_supply`.
+    /// Optional field, takes precedence over `total_supply`.
+    pub total_supply_units: Option<u128>,
+
+    /// Whether to reject invalid or unused combinations of parameters, instead of applying the permissive defaults.
+    /// Default value: false.
+    pub strict: Option<bool>,
+}
+
+/// Allocation of the total supply, as configured on the token builder.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct AllocationConfig {
+    /// Name of the allocation.
+    pub name: String,
+
+    /// Share of the total supply, in percentage.
+    pub percentage: f64,
+
+    /// Vesting schedule of the allocation, without its total amount.
+    pub vesting: VestingScheduleBuilder,
+
+    /// Price paid per token, if the allocation was sold in a sale round.
+    pub price: Option<f64>,
 }
 
 impl TokenBuilder {
@@ -107,6 +170,36 @@ impl TokenBuilder {
         self
     }
 
+    /// Set the total supply of the token in smallest units, e.g. wei.
+    /// The supply is converted to whole tokens with the decimals of the token,
+    /// and the build fails if it has more significant digits than the simulation can represent.
+    ///
+    /// # Arguments
+    ///
+    /// * `total_supply_units` - Total supply of the token in smallest units.
+    ///
+    /// # Returns
+    ///
+    /// The token builder.
+    pub fn total_supply_units(mut self, total_supply_units: u128) -> Self {
+        self.total_supply_units = Some(total_supply_units);
+        self
+    }
+
+    /// Set the number of decimals of the token.
+    ///
+    /// # Arguments
+    ///
+    /// * `decimals` - Number of decimals of the token, at most 38.
+    ///
+    /// # Returns
+    ///
+    /// The token builder.
+    pub fn decimals(mut self, decimals: u32) -> Self {
+        self.decimals = Some(decimals);
+        self
+    }
+
     /// Set the current supply of the token.
     ///
     /// # Arguments
@@ -206,29 +299,254 @@ impl TokenBuilder {
         self
     }
 
+    /// Set the tax charged on each buy, in percentage of the trade amount.
+    ///
+    /// # Arguments
+    ///
+    /// * `buy_tax` - Tax charged on each buy.
+    ///
+    /// # Returns
+    ///
+    /// The token builder.
+    pub fn buy_tax(mut self, buy_tax: f64) -> Self {
+        self.buy_tax = Some(buy_tax);
+        self
+    }
+
+    /// Set the tax charged on each sell, in percentage of the trade amount.
+    ///
+    /// # Arguments
+    ///
+    /// * `sell_tax` - Tax charged on each sell.
+    ///
+    /// # Returns
+    ///
+    /// The token builder.
+    pub fn sell_tax(mut self, sell_tax: f64) -> Self {
+        self.sell_tax = Some(sell_tax);
+        self
+    }
+
+    /// Set the routing of the collected taxes.
+    ///
+    /// # Arguments
+    ///
+    /// * `tax_routing` - List of tax routes.
+    ///
+    /// # Returns
+    ///
+    /// The token builder.
+    pub fn tax_routing(mut self, tax_routing: Vec<TaxRoute>) -> Self {
+        self.tax_routing = Some(tax_routing);
+        self
+    }
+
+    /// Set the stream of tokens emitted to contributors each interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `contributor_emission` - Contributor emission stream.
+    ///
+    /// # Returns
+    ///
+    /// The token builder.
+    pub fn contributor_emission(mut self, contributor_emission: ContributorEmission) -> Self {
+        self.contributor_emission = Some(contributor_emission);
+        self
+    }
+
+    /// Set the halving emission schedule of block rewards.
+    ///
+    /// # Arguments
+    ///
+    /// * `halving_schedule` - Halving emission schedule.
+    ///
+    /// # Returns
+    ///
+    /// The token builder.
+    pub fn halving_schedule(mut self, halving_schedule: HalvingSchedule) -> Self {
+        self.halving_schedule = Some(halving_schedule);
+        self
+    }
+
+    /// Set the metadata of the token deployment, e.g. chain ID and contract address.
+    ///
+    /// # Arguments
+    ///
+    /// * `metadata` - Metadata of the token.
+    ///
+    /// # Returns
+    ///
+    /// The token builder.
+    pub fn metadata(mut self, metadata: TokenMetadata) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Add an allocation of the total supply, unlocked by its own vesting schedule.
+    /// The unlock events of every allocation are combined into the unlock schedule when building.
+    /// Schedules without a start share the same start, the time of the build.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the allocation, e.g. `Team`.
+    /// * `percentage` - Share of the total supply, in percentage.
+    /// * `vesting` - Vesting schedule of the allocation, its total amount is derived from the percentage.
+    ///
+    /// # Returns
+    ///
+    /// The token builder.
+    pub fn allocation(
+        mut self,
+        name: &str,
+        percentage: f64,
+        vesting: VestingScheduleBuilder,
+    ) -> Self {
+        self.allocations.push(AllocationConfig {
+            name: name.to_string(),
+            percentage,
+            vesting,
+            price: None,
+        });
+        self
+    }
+
+    /// Add an allocation of the total supply sold in a sale round, unlocked by its own vesting schedule.
+    /// The return on investment of the participants is reported in the final report.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the sale round, e.g. `Seed`.
+    /// * `percentage` - Share of the total supply, in percentage.
+    /// * `price` - Price paid per token.
+    /// * `vesting` - Vesting schedule of the allocation, its total amount is derived from the percentage.
+    ///
+    /// # Returns
+    ///
+    /// The token builder.
+    pub fn sale_round(
+        mut self,
+        name: &str,
+        percentage: f64,
+        price: f64,
+        vesting: VestingScheduleBuilder,
+    ) -> Self {
+        self.allocations.push(AllocationConfig {
+            name: name.to_string(),
+            percentage,
+            vesting,
+            price: Some(price),
+        });
+        self
+    }
+
+    /// Set the strict mode, which rejects invalid or unused combinations of parameters when building,
+    /// e.g. an airdrop with zero supply or a tax routing without taxes.
+    ///
+    /// # Arguments
+    ///
+    /// * `strict` - Whether to reject invalid or unused combinations of parameters.
+    ///
+    /// # Returns
+    ///
+    /// The token builder.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = Some(strict);
+        self
+    }
+
+    /// Check the parameters in strict mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `total_supply` - Resolved total supply of the token.
+    ///
+    /// # Returns
+    ///
+    /// Error describing the first invalid or unused combination of parameters.
+    fn check_strict(&self, total_supply: Decimal) -> Result<(), SimulationError> {
+        let reject = |reason: &str| Err(SimulationError::StrictViolation(reason.to_string()));
+        let out_of = |value: Option<f64>, min: f64, max: f64| {
+            value.is_some_and(|value| !(min..=max).contains(&value))
+        };
+
+        if self.total_supply.is_some() && self.total_supply_units.is_some() {
+            return reject("total supply is overridden by the total supply in units");
+        }
+        if total_supply.is_sign_negative() {
+            return reject("total supply must be non-negative");
+        }
+        if self.airdrop_percentage.is_some() && total_supply.is_zero() {
+            return reject("airdrop is set with zero supply");
+        }
+        if out_of(self.airdrop_percentage, 0.0, 100.0)
+            || out_of(self.initial_supply_percentage, 0.0, 100.0)
+        {
+            return reject("supply percentages must be between 0 and 100");
+        }
+        if self
+            .current_supply
+            .and_then(Decimal::from_f64)
+            .is_some_and(|supply| supply.is_sign_negative() || supply > total_supply)
+        {
+            return reject("current supply must be between 0 and the total supply");
+        }
+        if self.initial_price.is_some_and(|price| price <= 0.0) {
+            return reject("initial price must be positive");
+        }
+        if self.inflation_rate.is_some_and(|rate| rate < 0.0) || out_of(self.burn_rate, 0.0, 100.0)
+        {
+            return reject("inflation rate must be non-negative and burn rate between 0 and 100");
+        }
+        if out_of(self.buy_tax, 0.0, 100.0) || out_of(self.sell_tax, 0.0, 100.0) {
+            return reject("taxes must be between 0 and 100");
+        }
+        if self.tax_routing.is_some() && self.buy_tax.is_none() && self.sell_tax.is_none() {
+            return reject("tax routing is set without taxes");
+        }
+
+        Ok(())
+    }
+
     /// Build the token.
     ///
     /// # Returns
     ///
-    /// Token with the configured parameters.
+    /// Token with the configured parameters, or an error if the parameters are rejected in strict mode.
     pub fn build(self) -> Result<Token, SimulationError> {
+        let decimals = self.decimals.unwrap_or(DEFAULT_DECIMALS);
+        let total_supply = match (self.total_supply_units, self.total_supply) {
+            (Some(units), _) => BaseUnits::new(units, decimals)?.to_tokens()?,
+            (None, Some(supply)) => {
+                Decimal::from_i64(supply).ok_or(SimulationError::InvalidDecimal)?
+            }
+            (None, None) => Decimal::new(1_000_000, 0),
+        };
+        if self.strict.unwrap_or_default() {
+            self.check_strict(total_supply)?;
+        }
+        if let Some(routes) = &self.tax_routing {
+            TaxRoute::validate_shares(routes)?;
+        }
+        let (allocations, unlock_schedule) =
+            build_allocations(self.allocations, total_supply, self.unlock_schedule)?;
+
         Ok(Token {
             id: Uuid::new_v4(),
             name: self.name.ok_or(SimulationError::MissingName)?,
             symbol: self.symbol.unwrap_or_else(|| "TKN".to_string()),
-            total_supply: match self.total_supply {
-                Some(supply) => Decimal::from_i64(supply).ok_or(SimulationError::InvalidDecimal)?,
-                None => Decimal::new(1_000_000, 0),
-            },
+            total_supply: TokenAmount(total_supply),
             current_supply: match self.current_supply {
-                Some(supply) => Decimal::from_f64(supply).ok_or(SimulationError::InvalidDecimal)?,
-                None => Decimal::default(),
+                Some(supply) => {
+                    TokenAmount(Decimal::from_f64(supply).ok_or(SimulationError::InvalidDecimal)?)
+                }
+                None => TokenAmount::default(),
             },
             initial_supply_percentage: match self.initial_supply_percentage {
-                Some(percentage) => {
-                    Decimal::from_f64(percentage).ok_or(SimulationError::InvalidDecimal)?
-                }
-                None => Decimal::new(100, 0),
+                Some(percentage) => Percentage(
+                    Decimal::from_f64(percentage).ok_or(SimulationError::InvalidDecimal)?,
+                ),
+                None => Percentage(Decimal::ONE_HUNDRED),
             },
             inflation_rate: match self.inflation_rate {
                 Some(rate) => Some(Decimal::from_f64(rate).ok_or(SimulationError::InvalidDecimal)?),
@@ -239,24 +557,115 @@ impl TokenBuilder {
                 None => None,
             },
             initial_price: match self.initial_price {
-                Some(price) => Decimal::from_f64(price).ok_or(SimulationError::InvalidDecimal)?,
-                None => Decimal::new(1, 0),
+                Some(price) => {
+                    FiatAmount(Decimal::from_f64(price).ok_or(SimulationError::InvalidDecimal)?)
+                }
+                None => FiatAmount(Decimal::ONE),
             },
             airdrop_percentage: match self.airdrop_percentage {
-                Some(percentage) => {
-                    Some(Decimal::from_f64(percentage).ok_or(SimulationError::InvalidDecimal)?)
-                }
+                Some(percentage) => Some(Percentage(
+                    Decimal::from_f64(percentage).ok_or(SimulationError::InvalidDecimal)?,
+                )),
+                None => None,
+            },
+            unlock_schedule,
+            buy_tax: match self.buy_tax {
+                Some(tax) => Some(Percentage(
+                    Decimal::from_f64(tax).ok_or(SimulationError::InvalidDecimal)?,
+                )),
+                None => None,
+            },
+            sell_tax: match self.sell_tax {
+                Some(tax) => Some(Percentage(
+                    Decimal::from_f64(tax).ok_or(SimulationError::InvalidDecimal)?,
+                )),
                 None => None,
             },
-            unlock_schedule: self.unlock_schedule,
+            tax_routing: self.tax_routing,
+            contributor_emission: self.contributor_emission,
+            halving_schedule: self.halving_schedule,
+            metadata: self.metadata,
+            ledger: SupplyLedger::default(),
+            allocations,
+            decimals,
         })
     }
 }
 
+/// Build the allocations of the total supply, and combine their unlock events with the unlock schedule.
+///
+/// # Arguments
+///
+/// * `configs` - Allocations configured on the builder.
+/// * `total_supply` - Total supply of the token.
+/// * `unlock_schedule` - Unlock schedule configured on the builder.
+///
+/// # Returns
+///
+/// Allocations, and the combined unlock schedule in chronological order,
+/// or an error if a percentage is not positive, the percentages sum to more than 100%, or a vesting schedule is invalid.
+fn build_allocations(
+    configs: Vec<AllocationConfig>,
+    total_supply: Decimal,
+    unlock_schedule: Option<Vec<UnlockEvent>>,
+) -> Result<(Vec<TokenAllocation>, Option<Vec<UnlockEvent>>), SimulationError> {
+    if configs.is_empty() {
+        return Ok((vec![], unlock_schedule));
+    }
+
+    let hundred = Decimal::new(100, 0);
+    let start = Utc::now();
+    let mut allocations = Vec::with_capacity(configs.len());
+    let mut schedule = unlock_schedule.unwrap_or_default();
+    let mut total_percentage = Decimal::default();
+
+    for config in configs {
+        let percentage =
+            Decimal::from_f64(config.percentage).ok_or(SimulationError::InvalidDecimal)?;
+        if percentage <= Decimal::default() {
+            return Err(SimulationError::InvalidAllocation(format!(
+                "allocation {} must have a positive percentage",
+                config.name
+            )));
+        }
+        total_percentage += percentage;
+
+        let mut vesting = config.vesting;
+        vesting.start = vesting.start.or(Some(start));
+        let vesting = vesting.build_with_total_amount(total_supply * percentage / hundred)?;
+        schedule.extend(vesting.unlock_events());
+
+        allocations.push(TokenAllocation {
+            name: config.name,
+            percentage,
+            vesting,
+            price: match config.price {
+                Some(price) => {
+                    Some(Decimal::from_f64(price).ok_or(SimulationError::InvalidDecimal)?)
+                }
+                None => None,
+            },
+        });
+    }
+
+    if total_percentage > hundred {
+        return Err(SimulationError::InvalidAllocation(format!(
+            "allocations sum to {}%, expected at most 100%",
+            total_percentage
+        )));
+    }
+
+    schedule.sort_by_key(|event| event.date);
+
+    Ok((allocations, Some(schedule)))
+}
+
 #[cfg(test)]
 mod tests {
     use chrono::Utc;
 
+    use crate::TaxDestination;
+
     use super::*;
 
     #[test]
@@ -268,14 +677,23 @@ mod tests {
 
         assert_eq!(token.name, "Test Token");
         assert_eq!(token.symbol, "TKN");
-        assert_eq!(token.total_supply, Decimal::new(1_000_000, 0));
-        assert_eq!(token.current_supply, Decimal::default());
-        assert_eq!(token.initial_supply_percentage, Decimal::new(100, 0));
+        assert_eq!(token.total_supply, TokenAmount(Decimal::new(1_000_000, 0)));
+        assert_eq!(token.current_supply, TokenAmount::default());
+        assert_eq!(
+            token.initial_supply_percentage,
+            Percentage(Decimal::new(100, 0))
+        );
         assert_eq!(token.inflation_rate, None);
         assert_eq!(token.burn_rate, None);
-        assert_eq!(token.initial_price, Decimal::new(1, 0));
+        assert_eq!(token.initial_price, FiatAmount(Decimal::new(1, 0)));
         assert_eq!(token.airdrop_percentage, None);
         assert!(token.unlock_schedule.is_none());
+        assert_eq!(token.buy_tax, None);
+        assert_eq!(token.sell_tax, None);
+        assert_eq!(token.tax_routing, None);
+        assert_eq!(token.contributor_emission, None);
+        assert_eq!(token.halving_schedule, None);
+        assert_eq!(token.metadata, None);
     }
 
     #[test]
@@ -296,19 +714,144 @@ mod tests {
             .initial_price(2.0)
             .airdrop_percentage(10.0)
             .unlock_schedule(vec![unlock_event])
+            .buy_tax(1.0)
+            .sell_tax(3.0)
+            .tax_routing(vec![TaxRoute {
+                destination: TaxDestination::Marketing,
+                share: Decimal::new(100, 0),
+            }])
+            .metadata(TokenMetadata {
+                chain_id: Some(1),
+                contract_address: Some("0x0000000000000000000000000000000000000001".to_string()),
+                category: Some("defi".to_string()),
+                ..Default::default()
+            })
             .build()
             .unwrap();
 
         assert_eq!(token.name, "Test Token");
         assert_eq!(token.symbol, "TT");
-        assert_eq!(token.total_supply, Decimal::new(1_000_000, 0));
-        assert_eq!(token.current_supply, Decimal::new(100_000, 0));
-        assert_eq!(token.initial_supply_percentage, Decimal::new(50, 0));
+        assert_eq!(token.total_supply, TokenAmount(Decimal::new(1_000_000, 0)));
+        assert_eq!(token.current_supply, TokenAmount(Decimal::new(100_000, 0)));
+        assert_eq!(
+            token.initial_supply_percentage,
+            Percentage(Decimal::new(50, 0))
+        );
         assert_eq!(token.inflation_rate, Some(Decimal::new(5, 0)));
         assert_eq!(token.burn_rate, Some(Decimal::new(1, 0)));
-        assert_eq!(token.initial_price, Decimal::new(2, 0));
-        assert_eq!(token.airdrop_percentage, Some(Decimal::new(10, 0)));
+        assert_eq!(token.initial_price, FiatAmount(Decimal::new(2, 0)));
+        assert_eq!(
+            token.airdrop_percentage,
+            Some(Percentage(Decimal::new(10, 0)))
+        );
         assert_eq!(token.unlock_schedule.unwrap().len(), 1);
+        assert_eq!(token.buy_tax, Some(Percentage(Decimal::new(1, 0))));
+        assert_eq!(token.sell_tax, Some(Percentage(Decimal::new(3, 0))));
+        assert_eq!(token.tax_routing.unwrap().len(), 1);
+        assert_eq!(token.metadata.unwrap().chain_id, Some(1));
+    }
+
+    #[test]
+    fn test_token_builder_with_allocations() {
+        let unlock_event = UnlockEvent {
+            date: Utc::now() + chrono::Duration::days(1),
+            amount: Decimal::new(1_000, 0),
+        };
+
+        let token = TokenBuilder::new()
+            .name("Test Token".to_string())
+            .unlock_schedule(vec![unlock_event])
+            .allocation(
+                "Team",
+                15.0,
+                VestingScheduleBuilder::four_year_linear_with_one_year_cliff(),
+            )
+            .allocation(
+                "Investors",
+                20.0,
+                VestingScheduleBuilder::two_year_quarterly(),
+            )
+            .build()
+            .unwrap();
+
+        let schedule = token.unlock_schedule.unwrap();
+        assert_eq!(token.allocations.len(), 2);
+        assert_eq!(
+            token.allocations[0].vesting.total_amount,
+            Decimal::new(150_000, 0)
+        );
+        assert_eq!(
+            token.allocations[0].vesting.start,
+            token.allocations[1].vesting.start
+        );
+        assert_eq!(schedule.len(), 1 + 37 + 8);
+        assert!(schedule.windows(2).all(|pair| pair[0].date <= pair[1].date));
+        assert_eq!(
+            schedule.iter().map(|event| event.amount).sum::<Decimal>(),
+            Decimal::new(351_000, 0)
+        );
+    }
+
+    #[test]
+    fn test_token_builder_with_invalid_allocations() {
+        let over_allocated = TokenBuilder::new()
+            .name("Test Token".to_string())
+            .allocation("Team", 60.0, VestingScheduleBuilder::two_year_quarterly())
+            .allocation(
+                "Investors",
+                50.0,
+                VestingScheduleBuilder::two_year_quarterly(),
+            )
+            .build();
+        let invalid_vesting = TokenBuilder::new()
+            .name("Test Token".to_string())
+            .allocation("Team", 10.0, VestingScheduleBuilder::new().cliff(12, 50.0))
+            .build();
+
+        assert!(matches!(
+            over_allocated,
+            Err(SimulationError::InvalidAllocation(_))
+        ));
+        assert!(matches!(
+            invalid_vesting,
+            Err(SimulationError::InvalidVestingSchedule(_))
+        ));
+    }
+
+    #[test]
+    fn test_token_builder_strict() {
+        let builder = || {
+            TokenBuilder::new()
+                .name("Test Token".to_string())
+                .strict(true)
+        };
+        assert!(builder()
+            .airdrop_percentage(5.0)
+            .sell_tax(2.0)
+            .build()
+            .is_ok());
+
+        for invalid in [
+            builder().total_supply(0).airdrop_percentage(5.0),
+            builder().total_supply(1_000).total_supply_units(1_000),
+            builder().current_supply(2_000_000.0),
+            builder().initial_price(0.0),
+            builder().burn_rate(150.0),
+            builder().tax_routing(vec![]),
+        ] {
+            assert!(matches!(
+                invalid.build(),
+                Err(SimulationError::StrictViolation(_))
+            ));
+        }
+
+        // The permissive mode keeps the defaults
+        assert!(TokenBuilder::new()
+            .name("Test Token".to_string())
+            .total_supply(0)
+            .airdrop_percentage(5.0)
+            .build()
+            .is_ok());
     }
 
     #[test]
@@ -317,4 +860,30 @@ mod tests {
 
         assert_eq!(token, Err(SimulationError::MissingName));
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let builder = TokenBuilder::new()
+            .name("Test Token".to_string())
+            .total_supply(1_000_000)
+            .sell_tax(5.0);
+
+        let json = serde_json::to_string(&builder).unwrap();
+        assert_eq!(
+            serde_json::from_str::<TokenBuilder>(&json).unwrap(),
+            builder
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_unknown_field() {
+        let builder = serde_json::from_str::<TokenBuilder>(
+            r#"{"name": "Test Token", "allocations": [], "total_suply": 1000}"#,
+        );
+
+        // Unknown fields are only rejected with the strict serde feature
+        assert_eq!(builder.is_err(), cfg!(feature = "strict_serde"));
+    }
 }
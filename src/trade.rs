@@ -0,0 +1,88 @@
+//! # Trade module
+//!
+//! This module contains the trade struct, which describes a single simulated trade and its side effects.
+
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Direction of a trade.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum TradeSide {
+    /// User buys tokens, increasing the balance.
+    Buy,
+
+    /// User sells tokens, decreasing the balance.
+    #[default]
+    Sell,
+}
+
+/// Single trade made by a user during the simulation.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Trade {
+    /// ID of the user who made the trade.
+    pub user_id: Uuid,
+
+    /// Direction of the trade.
+    pub side: TradeSide,
+
+    /// Amount of tokens traded.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub amount: Decimal,
+
+    /// Percentage protocol fee paid for the trade.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub fee: Decimal,
+
+    /// Fixed network fee (gas) paid for the trade, converted to tokens at the current price.
+    #[cfg_attr(feature = "serde", serde(default, with = "rust_decimal::serde::float"))]
+    pub gas_fee: Decimal,
+
+    /// Slippage of the trade against the depth of its venue, in percentage.
+    #[cfg_attr(feature = "serde", serde(default, with = "rust_decimal::serde::float"))]
+    pub slippage: Decimal,
+
+    /// Amount of tokens extracted from the trade by a sandwich attack.
+    #[cfg_attr(feature = "serde", serde(default, with = "rust_decimal::serde::float"))]
+    pub mev_extracted: Decimal,
+
+    /// Name of the external exchange the trade was made on.
+    /// `None` for trades made on-chain.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub venue: Option<String>,
+
+    /// Amount of tokens burned by the trade.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub burned: Decimal,
+
+    /// Buy or sell tax paid for the trade.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub tax: Decimal,
+
+    /// Amount of new tokens created by the trade.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub minted: Decimal,
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serde_round_trip() {
+        let trade = Trade {
+            user_id: Uuid::new_v4(),
+            side: TradeSide::Buy,
+            amount: Decimal::new(100, 0),
+            fee: Decimal::new(1, 0),
+            ..Default::default()
+        };
+
+        let json = serde_json::to_string(&trade).unwrap();
+        assert_eq!(serde_json::from_str::<Trade>(&json).unwrap(), trade);
+    }
+}
@@ -0,0 +1,280 @@
+//! # Units module
+//!
+//! This module contains the token amounts counted in smallest units, e.g. wei for a token with 18 decimals.
+//!
+//! The simulation computes with `Decimal`, whose 96-bit mantissa holds about 28 significant digits.
+//! A supply of 1e15 tokens with 18 decimals needs 34 digits, so amounts are kept as `u128` smallest units at the edges:
+//! they are converted to whole tokens when the token is built, and back to smallest units for the outputs.
+//! A conversion that cannot be represented exactly fails with an error instead of losing digits.
+
+use std::{fmt, str::FromStr};
+
+use rust_decimal::Decimal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{SimulationError, Token};
+
+/// Number of decimals of a token, when not set.
+pub const DEFAULT_DECIMALS: u32 = 18;
+
+/// Largest number of decimals of a token amount, the largest power of 10 that fits in `u128`.
+pub const MAX_DECIMALS: u32 = 38;
+
+/// Amount of a token in smallest units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct BaseUnits {
+    /// Amount in smallest units.
+    pub units: u128,
+
+    /// Number of decimals of the token, the smallest unit being 10^-decimals of a token.
+    pub decimals: u32,
+}
+
+impl BaseUnits {
+    /// Create a new token amount.
+    ///
+    /// # Arguments
+    ///
+    /// * `units` - Amount in smallest units.
+    /// * `decimals` - Number of decimals of the token.
+    ///
+    /// # Returns
+    ///
+    /// New token amount, or an error if the number of decimals is above the maximum.
+    pub fn new(units: u128, decimals: u32) -> Result<Self, SimulationError> {
+        if decimals > MAX_DECIMALS {
+            return Err(SimulationError::UnrepresentableAmount(format!(
+                "{decimals} decimals is above the maximum of {MAX_DECIMALS}"
+            )));
+        }
+
+        Ok(BaseUnits { units, decimals })
+    }
+
+    /// Parse an amount of whole tokens, e.g. `1000000000000000.000000000000000001`.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Amount of whole tokens, with at most `decimals` fractional digits.
+    /// * `decimals` - Number of decimals of the token.
+    ///
+    /// # Returns
+    ///
+    /// Amount in smallest units, or an error if the value is not a number or does not fit in smallest units.
+    pub fn parse(value: &str, decimals: u32) -> Result<Self, SimulationError> {
+        let invalid = || SimulationError::UnrepresentableAmount(format!("{value} tokens"));
+
+        let (whole, fraction) = value.trim().split_once('.').unwrap_or((value.trim(), ""));
+        if whole.is_empty() && fraction.is_empty()
+            || !whole
+                .chars()
+                .chain(fraction.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+        if fraction.len() > decimals as usize {
+            return Err(SimulationError::UnrepresentableAmount(format!(
+                "{value} tokens has more than {decimals} decimals"
+            )));
+        }
+
+        let digits = format!("{whole}{fraction:0<width$}", width = decimals as usize);
+        let units = match digits.trim_start_matches('0') {
+            "" => 0,
+            digits => u128::from_str(digits).map_err(|_| invalid())?,
+        };
+
+        BaseUnits::new(units, decimals)
+    }
+
+    /// Convert an amount of whole tokens to smallest units.
+    ///
+    /// # Arguments
+    ///
+    /// * `tokens` - Amount of whole tokens.
+    /// * `decimals` - Number of decimals of the token.
+    ///
+    /// # Returns
+    ///
+    /// Amount in smallest units, or an error if the amount is negative, has more than `decimals` fractional digits,
+    /// or does not fit in smallest units.
+    pub fn from_tokens(tokens: Decimal, decimals: u32) -> Result<Self, SimulationError> {
+        if tokens.is_sign_negative() && !tokens.is_zero() {
+            return Err(SimulationError::UnrepresentableAmount(format!(
+                "{tokens} tokens is negative"
+            )));
+        }
+
+        BaseUnits::parse(&tokens.normalize().to_string(), decimals)
+    }
+
+    /// Convert the amount to whole tokens.
+    ///
+    /// # Returns
+    ///
+    /// Amount of whole tokens, or an error if it has more significant digits than a `Decimal` holds.
+    pub fn to_tokens(&self) -> Result<Decimal, SimulationError> {
+        let mut units = self.units;
+        let mut scale = self.decimals;
+
+        // Trailing zeros of the fractional part do not need to be represented
+        while scale > 0 && units % 10 == 0 && units > 0 {
+            units /= 10;
+            scale -= 1;
+        }
+        if units == 0 {
+            return Ok(Decimal::default());
+        }
+
+        i128::try_from(units)
+            .ok()
+            .and_then(|units| Decimal::try_from_i128_with_scale(units, scale).ok())
+            .ok_or_else(|| {
+                SimulationError::UnrepresentableAmount(format!(
+                    "{self} tokens has more than 28 significant digits"
+                ))
+            })
+    }
+
+    /// Add another amount of the same token.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Amount to add, with the same number of decimals.
+    ///
+    /// # Returns
+    ///
+    /// Sum of the amounts, or `None` if the decimals differ or the sum overflows.
+    pub fn checked_add(&self, other: BaseUnits) -> Option<BaseUnits> {
+        (self.decimals == other.decimals).then_some(())?;
+
+        self.units
+            .checked_add(other.units)
+            .map(|units| BaseUnits { units, ..*self })
+    }
+
+    /// Subtract another amount of the same token.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Amount to subtract, with the same number of decimals.
+    ///
+    /// # Returns
+    ///
+    /// Difference of the amounts, or `None` if the decimals differ or the difference is negative.
+    pub fn checked_sub(&self, other: BaseUnits) -> Option<BaseUnits> {
+        (self.decimals == other.decimals).then_some(())?;
+
+        self.units
+            .checked_sub(other.units)
+            .map(|units| BaseUnits { units, ..*self })
+    }
+}
+
+impl fmt::Display for BaseUnits {
+    /// Format the amount in whole tokens, without trailing zeros.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let one = 10u128.pow(self.decimals);
+        let whole = self.units / one;
+        let fraction = self.units % one;
+
+        match fraction {
+            0 => write!(f, "{whole}"),
+            _ => {
+                let fraction = format!("{fraction:0>width$}", width = self.decimals as usize);
+                write!(f, "{whole}.{}", fraction.trim_end_matches('0'))
+            }
+        }
+    }
+}
+
+impl Token {
+    /// Convert an amount of whole tokens of the simulation, e.g. a balance, to smallest units of the token.
+    ///
+    /// # Arguments
+    ///
+    /// * `tokens` - Amount of whole tokens.
+    ///
+    /// # Returns
+    ///
+    /// Amount in smallest units, or an error if the amount cannot be represented in smallest units.
+    pub fn to_units(&self, tokens: Decimal) -> Result<BaseUnits, SimulationError> {
+        BaseUnits::from_tokens(tokens, self.decimals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Simulation, TokenAmount};
+
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display() {
+        let amount = BaseUnits::parse("1000000000000000.000000000000000001", 18).unwrap();
+        assert_eq!(amount.units, 10u128.pow(33) + 1);
+        assert_eq!(amount.to_string(), "1000000000000000.000000000000000001");
+        assert_eq!(BaseUnits::parse("0.5", 1).unwrap().units, 5);
+        assert_eq!(BaseUnits::parse("0", 18).unwrap().units, 0);
+
+        assert!(BaseUnits::parse("0.05", 1).is_err());
+        assert!(BaseUnits::parse("-1", 18).is_err());
+        assert!(BaseUnits::parse("1e3", 18).is_err());
+        assert!(BaseUnits::parse("", 18).is_err());
+        // Above u128
+        assert!(BaseUnits::parse("1000000000000000000000", 18).is_err());
+        assert!(BaseUnits::new(1, 39).is_err());
+    }
+
+    #[test]
+    fn test_to_tokens() {
+        let supply = BaseUnits::new(10u128.pow(33), 18).unwrap();
+        assert_eq!(
+            supply.to_tokens().unwrap(),
+            Decimal::new(1_000_000_000_000_000, 0)
+        );
+
+        // 34 significant digits do not fit in a decimal
+        let precise = supply.checked_add(BaseUnits::new(1, 18).unwrap()).unwrap();
+        assert!(matches!(
+            precise.to_tokens(),
+            Err(SimulationError::UnrepresentableAmount(_))
+        ));
+
+        let round_trip = BaseUnits::from_tokens(Decimal::new(12_345, 4), 18).unwrap();
+        assert_eq!(round_trip.units, 1_234_500_000_000_000_000);
+        assert_eq!(round_trip.to_tokens().unwrap(), Decimal::new(12_345, 4));
+        assert!(BaseUnits::from_tokens(Decimal::new(-1, 0), 18).is_err());
+        assert_eq!(precise.checked_sub(supply).unwrap().units, 1);
+        assert!(supply.checked_add(BaseUnits::new(1, 6).unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_large_supply_token() {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .decimals(18)
+            .total_supply_units(10u128.pow(33))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            token.total_supply,
+            TokenAmount(Decimal::new(1_000_000_000_000_000, 0))
+        );
+        assert_eq!(
+            token.to_units(token.total_supply.value()).unwrap().units,
+            10u128.pow(33)
+        );
+
+        let error = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .total_supply_units(10u128.pow(33) + 1)
+            .build()
+            .unwrap_err();
+        assert!(matches!(error, SimulationError::UnrepresentableAmount(_)));
+    }
+}
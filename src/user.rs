@@ -3,14 +3,18 @@
 //! This module provides functionality to create and manage users in the tokenomics simulator.
 //! Users are entities that interact with the tokenomics system by buying, selling, and holding tokens.
 
+use std::collections::BTreeMap;
+
 use rand::Rng;
 use rust_decimal::{prelude::*, Decimal};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::SimulationError;
+
 /// User.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct User {
     /// ID for the user.
@@ -21,11 +25,183 @@ pub struct User {
 
     /// Market behaviour of the user.
     pub behaviour: UserBehaviour,
+
+    /// Lifecycle state of the user.
+    /// Only active users trade.
+    pub state: UserState,
+
+    /// Number of users represented by this user.
+    /// 1 for an individual agent, the size of the cohort in the cohort population model.
+    #[cfg_attr(feature = "serde", serde(default = "default_weight"))]
+    pub weight: u64,
+}
+
+/// Default weight of a user: an individual agent.
+///
+/// # Returns
+///
+/// Weight of an individual agent.
+#[cfg(feature = "serde")]
+fn default_weight() -> u64 {
+    1
+}
+
+/// Lifecycle state of the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum UserState {
+    /// Active: Users who take part in the market.
+    Active,
+
+    /// Dormant: Users who stopped trading, but may come back.
+    Dormant,
+
+    /// Churned: Users who left the market for good.
+    Churned,
+}
+
+impl UserState {
+    /// Get the next lifecycle state.
+    /// Active users may become dormant, dormant users may churn or become active again.
+    /// Churned users never come back.
+    ///
+    /// # Arguments
+    ///
+    /// * `lifecycle` - Transition probabilities of the user lifecycle.
+    /// * `rng` - Random number generator.
+    ///
+    /// # Returns
+    ///
+    /// Next lifecycle state.
+    pub fn next<R: Rng + ?Sized>(self, lifecycle: &UserLifecycle, rng: &mut R) -> UserState {
+        match self {
+            UserState::Active if rng.random_bool(lifecycle.active_to_dormant) => UserState::Dormant,
+            UserState::Dormant => {
+                let roll = rng.random_range(0.0..1.0);
+
+                if roll < lifecycle.dormant_to_churned {
+                    UserState::Churned
+                } else if roll < lifecycle.dormant_to_churned + lifecycle.dormant_to_active {
+                    UserState::Active
+                } else {
+                    UserState::Dormant
+                }
+            }
+            state => state,
+        }
+    }
+}
+
+/// Strategy used to assign IDs to generated users.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum UserIdStrategy {
+    /// Random UUIDv4 for every generated user.
+    #[default]
+    Random,
+
+    /// Sequential IDs, starting at `00000000-0000-0000-0000-000000000001`.
+    Sequential,
+
+    /// UUIDv4-formatted IDs derived from the given seed and the user index.
+    /// The same seed always produces the same IDs, across intervals and runs.
+    Seeded(u64),
+}
+
+impl UserIdStrategy {
+    /// Get the ID of the user at the given index.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the user in the population.
+    ///
+    /// # Returns
+    ///
+    /// ID for the user.
+    pub fn id(&self, index: u64) -> Uuid {
+        match self {
+            UserIdStrategy::Random => Uuid::new_v4(),
+            UserIdStrategy::Sequential => Uuid::from_u128(index as u128 + 1),
+            UserIdStrategy::Seeded(seed) => {
+                let high = splitmix64(seed ^ splitmix64(index));
+                let low = splitmix64(high ^ index);
+                let bytes = ((high as u128) << 64 | low as u128).to_be_bytes();
+
+                uuid::Builder::from_random_bytes(bytes).into_uuid()
+            }
+        }
+    }
+}
+
+/// SplitMix64 mixing function, used to derive deterministic IDs from a seed.
+///
+/// # Arguments
+///
+/// * `value` - Value to mix.
+///
+/// # Returns
+///
+/// Mixed value.
+fn splitmix64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Transition probabilities of the user lifecycle, applied once per interval.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct UserLifecycle {
+    /// Probability that an active user becomes dormant.
+    pub active_to_dormant: f64,
+
+    /// Probability that a dormant user churns.
+    pub dormant_to_churned: f64,
+
+    /// Probability that a dormant user becomes active again.
+    pub dormant_to_active: f64,
+}
+
+impl UserLifecycle {
+    /// Validate the transition probabilities.
+    ///
+    /// # Returns
+    ///
+    /// Result of the validation, or an error if a probability is out of range
+    /// or if the transitions out of the dormant state exceed 1 together.
+    pub fn validate(&self) -> Result<(), SimulationError> {
+        let probabilities = [
+            self.active_to_dormant,
+            self.dormant_to_churned,
+            self.dormant_to_active,
+        ];
+        if probabilities
+            .iter()
+            .any(|probability| !(0.0..=1.0).contains(probability))
+        {
+            return Err(SimulationError::InvalidData(
+                "lifecycle: probabilities must be between 0 and 1".to_string(),
+            ));
+        }
+        if self.dormant_to_churned + self.dormant_to_active > 1.0 {
+            return Err(SimulationError::InvalidData(
+                "lifecycle: transitions out of the dormant state must not exceed 1 together"
+                    .to_string(),
+            ));
+        }
+
+        Ok(())
+    }
 }
 
 /// Market behaviour of the user.
-#[derive(Debug)]
+/// Serialized as a stable snake case string, e.g. `long_term_investor`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum UserBehaviour {
     /// Speculator: Users who buy and sell tokens frequently to make a profit.
     Speculator,
@@ -35,6 +211,182 @@ pub enum UserBehaviour {
 
     /// Trader: Users who trade tokens frequently but do not hold them for long.
     Trader,
+
+    /// Whale: Users who trade rarely, but move a large part of their balance when they do.
+    Whale,
+
+    /// Bot: Automated accounts that trade very frequently in small amounts.
+    Bot,
+
+    /// Long-term investor: Users who almost never trade and rarely sell unlocked tokens.
+    LongTermInvestor,
+
+    /// Airdrop farmer: Users who sell most of the tokens they receive as soon as possible.
+    AirdropFarmer,
+}
+
+/// Market parameters of a user behaviour.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct BehaviourProfile {
+    /// Probability of trading in a single trade round, between 0 and 1.
+    pub trade_probability: f64,
+
+    /// Probability that a trade is a buy rather than a sell, between 0 and 1.
+    pub buy_probability: f64,
+
+    /// Minimum and maximum fraction of the balance traded at once, between 0 and 1.
+    pub trade_size: (f64, f64),
+
+    /// Average number of intervals a user holds before selling: a drawn sell goes through with a probability of one over the hold time.
+    /// 1 keeps every drawn sell.
+    pub hold_intervals: u64,
+
+    /// Share of newly unlocked or received tokens sold right away, between 0 and 1.
+    pub unlock_sell_through: f64,
+
+    /// Share of the balance the user is inclined to stake, e.g. to lock for the gauge votes, between 0 and 1.
+    pub stake_propensity: f64,
+}
+
+impl BehaviourProfile {
+    /// Validate the profile.
+    ///
+    /// # Returns
+    ///
+    /// Result of the validation, or an error if a probability or a share is out of range, or the hold time is zero.
+    pub fn validate(&self) -> Result<(), SimulationError> {
+        let (min, max) = self.trade_size;
+        let shares = [
+            self.trade_probability,
+            self.buy_probability,
+            self.unlock_sell_through,
+            self.stake_propensity,
+        ];
+        if shares.iter().any(|share| !(0.0..=1.0).contains(share)) {
+            return Err(SimulationError::InvalidData(
+                "behaviour profile: probabilities and shares must be between 0 and 1".to_string(),
+            ));
+        }
+        if !(min > 0.0 && min < max && max <= 1.0) {
+            return Err(SimulationError::InvalidData(
+                "behaviour profile: trade size must be an increasing range within 0 and 1"
+                    .to_string(),
+            ));
+        }
+        if self.hold_intervals == 0 {
+            return Err(SimulationError::InvalidData(
+                "behaviour profile: hold time must be positive".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl UserBehaviour {
+    /// Default market parameters of the behaviour.
+    ///
+    /// # Returns
+    ///
+    /// Default profile of the behaviour.
+    pub fn profile(&self) -> BehaviourProfile {
+        // Traders, the behaviour of the users without a behaviour mix, only sell unless configured otherwise
+        let (trade_probability, buy_probability, trade_size, unlock_sell_through, stake_propensity) =
+            match self {
+                UserBehaviour::Speculator => (0.7, 0.5, (0.05, 0.25), 0.6, 0.2),
+                UserBehaviour::Holder => (0.1, 0.8, (0.01, 0.05), 0.1, 0.8),
+                UserBehaviour::Trader => (0.5, 0.0, (0.01, 0.1), 0.5, 0.3),
+                UserBehaviour::Whale => (0.2, 0.5, (0.1, 0.3), 0.4, 0.5),
+                UserBehaviour::Bot => (0.95, 0.5, (0.001, 0.01), 0.9, 0.0),
+                UserBehaviour::LongTermInvestor => (0.05, 0.8, (0.01, 0.03), 0.05, 0.9),
+                UserBehaviour::AirdropFarmer => (0.3, 0.1, (0.2, 0.5), 0.95, 0.1),
+            };
+
+        BehaviourProfile {
+            trade_probability,
+            buy_probability,
+            trade_size,
+            hold_intervals: 1,
+            unlock_sell_through,
+            stake_propensity,
+        }
+    }
+
+    /// Probability that a user with this behaviour trades in a single trade round.
+    ///
+    /// # Returns
+    ///
+    /// Trade probability between 0.0 and 1.0.
+    pub fn trade_probability(&self) -> f64 {
+        self.profile().trade_probability
+    }
+
+    /// Probability that a trade of a user with this behaviour is a buy rather than a sell.
+    ///
+    /// # Returns
+    ///
+    /// Buy probability between 0.0 and 1.0.
+    pub fn buy_probability(&self) -> f64 {
+        self.profile().buy_probability
+    }
+
+    /// Range of the trade size, as a fraction of the user's balance.
+    ///
+    /// # Returns
+    ///
+    /// Minimum and maximum fraction of the balance traded at once.
+    pub fn trade_size(&self) -> (f64, f64) {
+        self.profile().trade_size
+    }
+
+    /// Whether the trades of a user with this behaviour are organic demand.
+    /// Bots and airdrop farmers generate inorganic volume, e.g. to farm volume incentives.
+    ///
+    /// # Returns
+    ///
+    /// Whether the behaviour is organic.
+    pub fn is_organic(&self) -> bool {
+        !matches!(self, UserBehaviour::Bot | UserBehaviour::AirdropFarmer)
+    }
+
+    /// Share of newly unlocked or received tokens the user sells right away.
+    ///
+    /// # Returns
+    ///
+    /// Sell-on-unlock propensity between 0.0 and 1.0.
+    pub fn unlock_sell_propensity(&self) -> f64 {
+        self.profile().unlock_sell_through
+    }
+
+    /// Pick a behaviour at random, according to the given population mix.
+    /// Weights are relative and do not have to sum to 1.
+    ///
+    /// # Arguments
+    ///
+    /// * `mix` - Weight of each behaviour in the population.
+    /// * `rng` - Random number generator.
+    ///
+    /// # Returns
+    ///
+    /// Sampled behaviour, or `UserBehaviour::Trader` if the mix has no positive weights.
+    pub fn sample<R: Rng + ?Sized>(mix: &BTreeMap<UserBehaviour, f64>, rng: &mut R) -> Self {
+        let total = mix.values().filter(|w| **w > 0.0).sum::<f64>();
+        if total <= 0.0 {
+            return UserBehaviour::Trader;
+        }
+
+        let mut target = rng.random_range(0.0..total);
+        for (behaviour, weight) in mix.iter().filter(|(_, w)| **w > 0.0) {
+            if target < *weight {
+                return *behaviour;
+            }
+
+            target -= weight;
+        }
+
+        UserBehaviour::Trader
+    }
 }
 
 impl User {
@@ -53,6 +405,8 @@ impl User {
             id,
             balance,
             behaviour: UserBehaviour::Trader,
+            state: UserState::Active,
+            weight: 1,
         }
     }
 
@@ -62,13 +416,37 @@ impl User {
     ///
     /// * `total_users` - Total number of users to generate.
     /// * `supply` - Initial supply of the token.
-    /// * `price` - Initial price of the token.
+    /// * `price` - Initial price of the token, only logged as the balances are amounts of tokens.
     /// * `decimals` - Number of decimal places for the token.
     ///
     /// # Returns
     ///
     /// List of users with random balances.
     pub fn generate(total_users: u64, supply: Decimal, price: Decimal, decimals: u32) -> Vec<User> {
+        User::generate_with_rng(total_users, supply, price, decimals, &mut rand::rng())
+    }
+
+    /// Generate a list of users with random balances and IDs, drawn from the given random number generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `total_users` - Total number of users to generate.
+    /// * `supply` - Initial supply of the token.
+    /// * `price` - Initial price of the token, only logged as the balances are amounts of tokens.
+    /// * `decimals` - Number of decimal places for the token.
+    /// * `rng` - Random number generator.
+    ///
+    /// # Returns
+    ///
+    /// List of users with random balances.
+    #[cfg_attr(not(feature = "log"), allow(unused_variables))]
+    pub(crate) fn generate_with_rng<R: Rng + ?Sized>(
+        total_users: u64,
+        supply: Decimal,
+        price: Decimal,
+        decimals: u32,
+        rng: &mut R,
+    ) -> Vec<User> {
         #[cfg(feature = "log")]
         log::debug!(
             "Generating {} users with initial supply of {} and price of {}",
@@ -77,60 +455,157 @@ impl User {
             price
         );
 
-        let mut rng = rand::rng();
         let mut users = vec![];
 
+        // Without a supply to share, e.g. once the supply is fully issued, the users start empty
+        let max_balance = (supply / Decimal::new(total_users as i64, 0))
+            .to_f64()
+            .unwrap();
         let mut total_balance = Decimal::default();
         for _ in 0..total_users {
-            let balance = Decimal::from_f64(
-                rng.random_range(
-                    0.0..(supply / Decimal::new(total_users as i64, 0))
-                        .to_f64()
-                        .unwrap(),
-                ),
-            )
-            .unwrap()
-            .round_dp(decimals);
+            let balance = if max_balance > 0.0 {
+                Decimal::from_f64(rng.random_range(0.0..max_balance))
+                    .unwrap()
+                    .round_dp(decimals)
+            } else {
+                Decimal::default()
+            };
             total_balance += balance;
 
             users.push(User {
-                id: Uuid::new_v4(),
+                id: uuid::Builder::from_random_bytes(rng.random()).into_uuid(),
                 balance,
                 behaviour: UserBehaviour::Trader,
+                state: UserState::Active,
+                weight: 1,
             });
         }
 
-        // Normalize balances to ensure the total does not exceed initial supply
-        let normalization_factor = supply / total_balance;
-        for user in &mut users {
-            user.balance *= normalization_factor;
-            user.balance = user.balance.round_dp(decimals);
+        // Draws of zero only, e.g. from a scripted randomness source, share the supply equally
+        if total_balance.is_zero() {
+            for user in &mut users {
+                user.balance = Decimal::ONE;
+            }
+            total_balance = Decimal::from(users.len());
         }
 
-        // Adjust balances based on the initial price
+        // Normalize balances to ensure the total does not exceed initial supply
+        let normalization_factor = supply.checked_div(total_balance).unwrap_or_default();
         for user in &mut users {
-            user.balance *= price;
+            user.balance *= normalization_factor;
             user.balance = user.balance.round_dp(decimals);
         }
 
-        // Distribute any remaining balance to ensure total balance matches initial supply
+        // Distribute the rounding remainder of either sign to ensure total balance matches initial supply,
+        // one unit per user at most and never below a zero balance
+        let unit = Decimal::new(1, decimals);
         let mut remaining_balance = supply - users.iter().map(|u| u.balance).sum::<Decimal>();
         for user in &mut users {
             if remaining_balance.is_zero() {
                 break;
             }
 
-            let add_balance = Decimal::min(remaining_balance, Decimal::new(1, 4));
-            user.balance += add_balance;
-            remaining_balance -= add_balance;
+            let adjustment = remaining_balance.clamp(-unit.min(user.balance), unit);
+            user.balance += adjustment;
+            remaining_balance -= adjustment;
         }
 
         users
     }
+
+    /// Count the users represented by a list of users, taking cohort weights into account.
+    ///
+    /// # Arguments
+    ///
+    /// * `users` - A list of users.
+    ///
+    /// # Returns
+    ///
+    /// Number of represented users.
+    pub fn count(users: &[User]) -> u64 {
+        users.iter().map(|user| user.weight).sum()
+    }
+
+    /// Check if the user takes part in the market.
+    ///
+    /// # Returns
+    ///
+    /// Whether the user is active.
+    pub fn is_active(&self) -> bool {
+        self.state == UserState::Active
+    }
+
+    /// Move the user to the next lifecycle state.
+    /// Active users may become dormant, dormant users may churn or become active again.
+    /// Churned users never come back.
+    ///
+    /// # Arguments
+    ///
+    /// * `lifecycle` - Transition probabilities of the user lifecycle.
+    /// * `rng` - Random number generator.
+    pub fn transition<R: Rng + ?Sized>(&mut self, lifecycle: &UserLifecycle, rng: &mut R) {
+        self.state = self.state.next(lifecycle, rng);
+    }
+
+    /// Assign IDs to users using the given strategy.
+    ///
+    /// # Arguments
+    ///
+    /// * `users` - A list of users.
+    /// * `strategy` - Strategy used to assign IDs.
+    pub fn assign_ids(users: &mut [User], strategy: UserIdStrategy) {
+        User::assign_ids_from(users, strategy, 0);
+    }
+
+    /// Assign IDs to users using the given strategy, starting at the given index.
+    /// Is used for users joining an existing population, so that their IDs do not collide.
+    ///
+    /// # Arguments
+    ///
+    /// * `users` - A list of users.
+    /// * `strategy` - Strategy used to assign IDs.
+    /// * `first_index` - Index of the first user in the population.
+    pub fn assign_ids_from(users: &mut [User], strategy: UserIdStrategy, first_index: u64) {
+        for (index, user) in users.iter_mut().enumerate() {
+            user.id = strategy.id(first_index + index as u64);
+        }
+    }
+
+    /// Assign behaviours to users according to the given population mix.
+    ///
+    /// # Arguments
+    ///
+    /// * `users` - A list of users.
+    /// * `mix` - Weight of each behaviour in the population.
+    pub fn assign_behaviours(users: &mut [User], mix: &BTreeMap<UserBehaviour, f64>) {
+        User::assign_behaviours_with_rng(users, mix, &mut rand::rng());
+    }
+
+    /// Assign a behaviour to each user, sampled from the population mix with the given random number generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `users` - A list of users.
+    /// * `mix` - Weight of each behaviour in the population.
+    /// * `rng` - Random number generator.
+    pub(crate) fn assign_behaviours_with_rng<R: Rng + ?Sized>(
+        users: &mut [User],
+        mix: &BTreeMap<UserBehaviour, f64>,
+        rng: &mut R,
+    ) {
+        #[cfg(feature = "log")]
+        log::debug!("Assigning behaviours to {} users", users.len());
+
+        for user in users.iter_mut() {
+            user.behaviour = UserBehaviour::sample(mix, rng);
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
     use super::*;
 
     #[test]
@@ -142,6 +617,45 @@ mod tests {
 
         assert_eq!(user.id, id);
         assert_eq!(user.balance, balance);
+        assert_eq!(user.state, UserState::Active);
+        assert!(user.is_active());
+    }
+
+    #[test]
+    fn test_user_transition() {
+        let mut rng = rand::rng();
+        let mut user = User::new(Uuid::new_v4(), Decimal::new(100, 0));
+
+        let to_dormant = UserLifecycle {
+            active_to_dormant: 1.0,
+            dormant_to_churned: 0.0,
+            dormant_to_active: 0.0,
+        };
+        user.transition(&to_dormant, &mut rng);
+        assert_eq!(user.state, UserState::Dormant);
+
+        user.transition(&to_dormant, &mut rng);
+        assert_eq!(user.state, UserState::Dormant);
+
+        let reactivate = UserLifecycle {
+            active_to_dormant: 0.0,
+            dormant_to_churned: 0.0,
+            dormant_to_active: 1.0,
+        };
+        user.transition(&reactivate, &mut rng);
+        assert_eq!(user.state, UserState::Active);
+
+        let churn = UserLifecycle {
+            active_to_dormant: 1.0,
+            dormant_to_churned: 1.0,
+            dormant_to_active: 0.0,
+        };
+        user.transition(&churn, &mut rng);
+        user.transition(&churn, &mut rng);
+        assert_eq!(user.state, UserState::Churned);
+
+        user.transition(&reactivate, &mut rng);
+        assert_eq!(user.state, UserState::Churned);
     }
 
     #[test]
@@ -162,4 +676,175 @@ mod tests {
 
         assert_eq!(total_balance, initial_supply);
     }
+
+    #[test]
+    fn test_user_generate_above_unit_price() {
+        let initial_supply = Decimal::new(1_000_000, 0);
+
+        for seed in 0..20 {
+            let users = User::generate_with_rng(
+                50,
+                initial_supply,
+                Decimal::new(25, 1),
+                4,
+                &mut StdRng::seed_from_u64(seed),
+            );
+
+            // The price does not scale the balances, which share the supply exactly
+            assert!(users.iter().all(|user| user.balance >= Decimal::ZERO));
+            assert_eq!(
+                users.iter().map(|user| user.balance).sum::<Decimal>(),
+                initial_supply
+            );
+        }
+
+        let users = User::generate(5, Decimal::ZERO, Decimal::ONE, 4);
+        assert!(users.iter().all(|user| user.balance.is_zero()));
+    }
+
+    #[test]
+    fn test_user_id_strategy() {
+        assert_ne!(UserIdStrategy::Random.id(0), UserIdStrategy::Random.id(0));
+
+        assert_eq!(UserIdStrategy::Sequential.id(0), Uuid::from_u128(1));
+        assert_eq!(UserIdStrategy::Sequential.id(41), Uuid::from_u128(42));
+
+        let seeded = UserIdStrategy::Seeded(7);
+        assert_eq!(seeded.id(3), seeded.id(3));
+        assert_ne!(seeded.id(3), seeded.id(4));
+        assert_ne!(seeded.id(3), UserIdStrategy::Seeded(8).id(3));
+        assert_eq!(seeded.id(3).get_version_num(), 4);
+    }
+
+    #[test]
+    fn test_assign_ids() {
+        let mut first = User::generate(5, Decimal::new(1000, 0), Decimal::new(1, 0), 4);
+        let mut second = User::generate(5, Decimal::new(1000, 0), Decimal::new(1, 0), 4);
+
+        User::assign_ids(&mut first, UserIdStrategy::Seeded(42));
+        User::assign_ids(&mut second, UserIdStrategy::Seeded(42));
+
+        for (a, b) in first.iter().zip(second.iter()) {
+            assert_eq!(a.id, b.id);
+        }
+
+        User::assign_ids_from(&mut second, UserIdStrategy::Sequential, 5);
+        assert_eq!(second[0].id, Uuid::from_u128(6));
+        assert_eq!(second[4].id, Uuid::from_u128(10));
+    }
+
+    #[test]
+    fn test_behaviour_default_parameters() {
+        let behaviours = [
+            UserBehaviour::Speculator,
+            UserBehaviour::Holder,
+            UserBehaviour::Trader,
+            UserBehaviour::Whale,
+            UserBehaviour::Bot,
+            UserBehaviour::LongTermInvestor,
+            UserBehaviour::AirdropFarmer,
+        ];
+
+        for behaviour in behaviours {
+            let (min, max) = behaviour.trade_size();
+            assert!(behaviour.profile().validate().is_ok());
+
+            assert!((0.0..=1.0).contains(&behaviour.trade_probability()));
+            assert!((0.0..=1.0).contains(&behaviour.buy_probability()));
+            assert!((0.0..=1.0).contains(&behaviour.unlock_sell_propensity()));
+            assert!(min > 0.0 && min < max && max <= 1.0);
+        }
+
+        assert!(UserBehaviour::Bot.trade_probability() > UserBehaviour::Holder.trade_probability());
+        assert!(UserBehaviour::Whale.trade_size().1 > UserBehaviour::Trader.trade_size().1);
+        assert!(UserBehaviour::Whale.is_organic());
+        assert!(!UserBehaviour::Bot.is_organic());
+        assert!(!UserBehaviour::AirdropFarmer.is_organic());
+    }
+
+    #[test]
+    fn test_behaviour_sample() {
+        let mut rng = rand::rng();
+
+        let mix = BTreeMap::from([(UserBehaviour::Whale, 1.0), (UserBehaviour::Bot, 0.0)]);
+        assert_eq!(UserBehaviour::sample(&mix, &mut rng), UserBehaviour::Whale);
+
+        let empty = BTreeMap::new();
+        assert_eq!(
+            UserBehaviour::sample(&empty, &mut rng),
+            UserBehaviour::Trader
+        );
+    }
+
+    #[test]
+    fn test_assign_behaviours() {
+        let mut users = User::generate(10, Decimal::new(1000, 0), Decimal::new(1, 0), 4);
+        let mix = BTreeMap::from([(UserBehaviour::AirdropFarmer, 2.0)]);
+
+        User::assign_behaviours(&mut users, &mix);
+
+        assert!(users
+            .iter()
+            .all(|user| user.behaviour == UserBehaviour::AirdropFarmer));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_behaviour_serde_names() {
+        assert_eq!(
+            serde_json::to_string(&UserBehaviour::LongTermInvestor).unwrap(),
+            "\"long_term_investor\""
+        );
+        assert_eq!(
+            serde_json::from_str::<UserBehaviour>("\"airdrop_farmer\"").unwrap(),
+            UserBehaviour::AirdropFarmer
+        );
+    }
+
+    #[test]
+    fn test_count() {
+        let mut users = vec![
+            User::new(Uuid::new_v4(), Decimal::default()),
+            User::new(Uuid::new_v4(), Decimal::default()),
+        ];
+        assert_eq!(User::count(&users), 2);
+
+        users[1].weight = 1_000;
+        assert_eq!(User::count(&users), 1_001);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let user = User {
+            state: UserState::Dormant,
+            behaviour: UserBehaviour::Whale,
+            ..User::new(Uuid::new_v4(), Decimal::new(1_234, 2))
+        };
+        let json = serde_json::to_string(&user).unwrap();
+        assert_eq!(serde_json::from_str::<User>(&json).unwrap(), user);
+
+        let lifecycle = UserLifecycle {
+            active_to_dormant: 0.1,
+            dormant_to_churned: 0.2,
+            dormant_to_active: 0.3,
+        };
+        let json = serde_json::to_string(&lifecycle).unwrap();
+        assert_eq!(
+            serde_json::from_str::<UserLifecycle>(&json).unwrap(),
+            lifecycle
+        );
+
+        for strategy in [
+            UserIdStrategy::Random,
+            UserIdStrategy::Sequential,
+            UserIdStrategy::Seeded(42),
+        ] {
+            let json = serde_json::to_string(&strategy).unwrap();
+            assert_eq!(
+                serde_json::from_str::<UserIdStrategy>(&json).unwrap(),
+                strategy
+            );
+        }
+    }
 }
@@ -0,0 +1,307 @@
+//! # User store module
+//!
+//! This module contains the columnar storage of the users of a run.
+//!
+//! Users are stored as a structure of arrays: one contiguous column per field.
+//! The run keeps its users in the store from start to end, and the trade loop and the interval features
+//! only touch the columns they need, e.g. the balances and states, instead of whole structs.
+//! `User` values are only built as views when needed, e.g. for observers and the final report.
+
+use rust_decimal::{prelude::*, Decimal};
+use uuid::Uuid;
+
+use crate::{User, UserBehaviour, UserState};
+
+/// Users stored as a structure of arrays.
+/// All columns have the same length, the user at index `i` is made of the values at index `i`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UserStore {
+    /// IDs of the users.
+    pub ids: Vec<Uuid>,
+
+    /// Balances of the users.
+    pub balances: Vec<Decimal>,
+
+    /// Market behaviours of the users.
+    pub behaviours: Vec<UserBehaviour>,
+
+    /// Lifecycle states of the users.
+    pub states: Vec<UserState>,
+
+    /// Number of users represented by each user.
+    pub weights: Vec<u64>,
+}
+
+impl UserStore {
+    /// Create a new user store from a list of users.
+    ///
+    /// # Arguments
+    ///
+    /// * `users` - A list of users.
+    ///
+    /// # Returns
+    ///
+    /// User store holding the users, in the same order.
+    pub fn from_users(users: &[User]) -> Self {
+        UserStore {
+            ids: users.iter().map(|user| user.id).collect(),
+            balances: users.iter().map(|user| user.balance).collect(),
+            behaviours: users.iter().map(|user| user.behaviour).collect(),
+            states: users.iter().map(|user| user.state).collect(),
+            weights: users.iter().map(|user| user.weight).collect(),
+        }
+    }
+
+    /// Get the number of users in the store.
+    ///
+    /// # Returns
+    ///
+    /// Number of users.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Check whether the store has no users.
+    ///
+    /// # Returns
+    ///
+    /// Whether the store is empty.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Count the users represented by the store, taking cohort weights into account.
+    ///
+    /// # Returns
+    ///
+    /// Number of represented users.
+    pub fn count(&self) -> u64 {
+        self.weights.iter().sum()
+    }
+
+    /// Check whether a user takes part in the market.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the user.
+    ///
+    /// # Returns
+    ///
+    /// Whether the user is active.
+    pub fn is_active(&self, index: usize) -> bool {
+        self.states[index] == UserState::Active
+    }
+
+    /// Check whether a user can trade: the user is active and has a balance.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the user.
+    ///
+    /// # Returns
+    ///
+    /// Whether the user can trade.
+    pub fn can_trade(&self, index: usize) -> bool {
+        self.is_active(index) && !self.balances[index].is_zero()
+    }
+
+    /// Distribute newly unlocked tokens to the holders, pro rata to their balances.
+    /// Each holder sells right away the share of its tokens given by the sell-on-unlock propensity of its behaviour.
+    ///
+    /// # Arguments
+    ///
+    /// * `unlocked` - Amount of tokens unlocked.
+    /// * `sell_through` - Share of the unlocked tokens sold right away, by behaviour.
+    /// * `precision` - Decimal precision of the balances.
+    ///
+    /// # Returns
+    ///
+    /// Amount of unlocked tokens sold.
+    pub(crate) fn distribute_unlock(
+        &mut self,
+        unlocked: Decimal,
+        sell_through: impl Fn(UserBehaviour) -> f64,
+        precision: u32,
+    ) -> Decimal {
+        let total_balance: Decimal = self.balances.iter().sum();
+        if unlocked <= Decimal::ZERO || total_balance <= Decimal::ZERO {
+            return Decimal::ZERO;
+        }
+
+        // Rounded down, so that the holders never receive or sell more than was unlocked
+        let mut sold = Decimal::ZERO;
+        for (balance, behaviour) in self.balances.iter_mut().zip(&self.behaviours) {
+            let received = (unlocked * *balance / total_balance)
+                .round_dp_with_strategy(precision, RoundingStrategy::ToZero);
+            let share = Decimal::from_f64(sell_through(*behaviour)).unwrap_or_default();
+            let amount =
+                (received * share).round_dp_with_strategy(precision, RoundingStrategy::ToZero);
+
+            *balance += received - amount;
+            sold += amount;
+        }
+
+        sold
+    }
+
+    /// Add a user at the end of the store.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - User to add.
+    pub fn push(&mut self, user: User) {
+        self.ids.push(user.id);
+        self.balances.push(user.balance);
+        self.behaviours.push(user.behaviour);
+        self.states.push(user.state);
+        self.weights.push(user.weight);
+    }
+
+    /// Get a view of a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the user.
+    ///
+    /// # Returns
+    ///
+    /// The user at the index.
+    pub fn user(&self, index: usize) -> User {
+        User {
+            id: self.ids[index],
+            balance: self.balances[index],
+            behaviour: self.behaviours[index],
+            state: self.states[index],
+            weight: self.weights[index],
+        }
+    }
+
+    /// Iterate over views of the users.
+    ///
+    /// # Returns
+    ///
+    /// Iterator over the users, in order.
+    pub fn users(&self) -> impl Iterator<Item = User> + '_ {
+        (0..self.len()).map(|index| self.user(index))
+    }
+}
+
+impl Extend<User> for UserStore {
+    /// Add users at the end of the store.
+    ///
+    /// # Arguments
+    ///
+    /// * `users` - Users to add.
+    fn extend<I: IntoIterator<Item = User>>(&mut self, users: I) {
+        for user in users {
+            self.push(user);
+        }
+    }
+}
+
+impl FromIterator<User> for UserStore {
+    /// Create a new user store from users.
+    ///
+    /// # Arguments
+    ///
+    /// * `users` - Users to store.
+    ///
+    /// # Returns
+    ///
+    /// User store holding the users, in the same order.
+    fn from_iter<I: IntoIterator<Item = User>>(users: I) -> Self {
+        let mut store = UserStore::default();
+        store.extend(users);
+
+        store
+    }
+}
+
+impl From<Vec<User>> for UserStore {
+    /// Create a new user store from a list of users.
+    ///
+    /// # Arguments
+    ///
+    /// * `users` - A list of users.
+    ///
+    /// # Returns
+    ///
+    /// User store holding the users, in the same order.
+    fn from(users: Vec<User>) -> Self {
+        UserStore::from_users(&users)
+    }
+}
+
+impl From<UserStore> for Vec<User> {
+    /// Convert a user store back to a list of users.
+    ///
+    /// # Arguments
+    ///
+    /// * `store` - User store.
+    ///
+    /// # Returns
+    ///
+    /// List of the users, in order.
+    fn from(store: UserStore) -> Self {
+        store.users().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_store() {
+        let mut users = vec![
+            User::new(Uuid::new_v4(), Decimal::new(10, 0)),
+            User::new(Uuid::new_v4(), Decimal::default()),
+        ];
+        users[1].behaviour = UserBehaviour::Whale;
+        users[1].weight = 10;
+
+        let mut store = UserStore::from_users(&users);
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.count(), 11);
+        assert_eq!(store.weights, vec![1, 10]);
+        assert_eq!(store.user(1), users[1]);
+        assert!(store.can_trade(0));
+        assert!(!store.can_trade(1));
+        assert!(store.is_active(1));
+
+        store.balances[0] = Decimal::new(5, 0);
+        store.states[1] = UserState::Churned;
+        users[0].balance = Decimal::new(5, 0);
+        users[1].state = UserState::Churned;
+        assert!(!store.is_active(1));
+        assert_eq!(Vec::<User>::from(store.clone()), users);
+
+        store.push(User::new(Uuid::new_v4(), Decimal::ONE));
+        assert_eq!(store.len(), 3);
+        assert_eq!(store.user(2).balance, Decimal::ONE);
+        assert_eq!(users.iter().cloned().collect::<UserStore>().len(), 2);
+        assert!(UserStore::default().is_empty());
+    }
+
+    #[test]
+    fn test_distribute_unlock() {
+        let mut users = UserStore::from(vec![User::new(Uuid::new_v4(), Decimal::new(100, 0)); 2]);
+        users.behaviours = vec![
+            UserBehaviour::AirdropFarmer,
+            UserBehaviour::LongTermInvestor,
+        ];
+
+        let sold = users.distribute_unlock(
+            Decimal::new(200, 0),
+            |behaviour| behaviour.unlock_sell_propensity(),
+            4,
+        );
+
+        // The farmer sells 95% of its 100 unlocked tokens, the long-term investor 5%
+        assert_eq!(sold, Decimal::new(100, 0));
+        assert_eq!(
+            users.balances,
+            vec![Decimal::new(105, 0), Decimal::new(195, 0)]
+        );
+    }
+}
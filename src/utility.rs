@@ -0,0 +1,320 @@
+//! # Utility module
+//!
+//! This module contains the utility sink, which models the demand of users spending or locking tokens to use the product.
+//!
+//! Each active user takes a number of product actions per interval, growing with the product usage,
+//! and pays for them in tokens at the current price: a fee per action, and optionally a subscription fee per interval.
+//! The tokens are either spent, burned or sent to the treasury, or locked for a number of intervals and then returned.
+//! Users who cannot afford the full cost pay with their whole balance.
+
+use std::collections::VecDeque;
+
+use rand::Rng;
+use rust_decimal::{prelude::*, Decimal};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{SimulationError, UserStore};
+
+/// Destination of the tokens spent by the users.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum SinkDestination {
+    /// Tokens are burned, and leave the circulating supply.
+    #[default]
+    Burn,
+
+    /// Tokens are sent to the treasury of the project.
+    Treasury,
+}
+
+/// Utility sink: tokens spent or locked by the users to use the product.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct UtilitySink {
+    /// Fee of one product action, in fiat.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub cost_per_action: Decimal,
+
+    /// Subscription fee of an active user per interval, in fiat.
+    #[cfg_attr(feature = "serde", serde(default, with = "rust_decimal::serde::float"))]
+    pub subscription_fee: Decimal,
+
+    /// Product actions of an active user in the first interval.
+    pub actions_per_user: f64,
+
+    /// Growth of the product usage per interval, e.g. 0.05 for 5% more actions per user each interval.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub usage_growth: f64,
+
+    /// Destination of the spent tokens.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub destination: SinkDestination,
+
+    /// Number of intervals the tokens are locked for, and then returned to the user, instead of being spent.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub lock_intervals: Option<u64>,
+}
+
+/// Tokens collected by the utility sink in an interval.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct UtilityCollection {
+    /// Tokens spent or locked by the users.
+    pub(crate) consumed: Decimal,
+
+    /// Tokens unlocked and returned to the users.
+    pub(crate) released: Decimal,
+}
+
+/// Tokens locked by the utility sink, until their release.
+#[derive(Debug, Default)]
+pub(crate) struct UtilityLocks {
+    /// Locked amounts as interval of the release, index of the user and amount, the earliest release first.
+    locks: VecDeque<(u64, usize, Decimal)>,
+}
+
+impl UtilitySink {
+    /// Create a new utility sink with a fee per action, spent and burned, without usage growth.
+    ///
+    /// # Arguments
+    ///
+    /// * `cost_per_action` - Fee of one product action, in fiat.
+    /// * `actions_per_user` - Product actions of an active user per interval.
+    ///
+    /// # Returns
+    ///
+    /// New utility sink.
+    pub fn new(cost_per_action: Decimal, actions_per_user: f64) -> Self {
+        UtilitySink {
+            cost_per_action,
+            subscription_fee: Decimal::default(),
+            actions_per_user,
+            usage_growth: 0.0,
+            destination: SinkDestination::default(),
+            lock_intervals: None,
+        }
+    }
+
+    /// Get the expected product actions of an active user in an interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the interval.
+    ///
+    /// # Returns
+    ///
+    /// Expected actions per active user.
+    pub fn actions_at(&self, index: u64) -> f64 {
+        self.actions_per_user * (1.0 + self.usage_growth).powi(index.min(i32::MAX as u64) as i32)
+    }
+
+    /// Collect the tokens paid by the active users to use the product in an interval.
+    /// Tokens locked in earlier intervals are released first.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the interval.
+    /// * `users` - Users of the simulation.
+    /// * `price` - Price of the token in the interval.
+    /// * `precision` - Decimal precision of the balances.
+    /// * `locks` - Tokens locked by the sink.
+    /// * `rng` - Random number generator.
+    ///
+    /// # Returns
+    ///
+    /// Tokens collected in the interval, or an error if the price cannot be converted.
+    pub(crate) fn collect<R: Rng + ?Sized>(
+        &self,
+        index: u64,
+        users: &mut UserStore,
+        price: Decimal,
+        precision: u32,
+        locks: &mut UtilityLocks,
+        rng: &mut R,
+    ) -> Result<UtilityCollection, SimulationError> {
+        let mut collection = UtilityCollection {
+            released: locks.release(index, users),
+            ..Default::default()
+        };
+        if price <= Decimal::ZERO {
+            return Ok(collection);
+        }
+
+        let actions = self.actions_at(index).max(0.0);
+        for position in 0..users.len() {
+            if !users.can_trade(position) {
+                continue;
+            }
+
+            // The fractional action is taken with its probability
+            let weight = users.weights[position];
+            let expected = actions * weight as f64;
+            let mut taken = expected.trunc();
+            if rng.random_bool(expected.fract()) {
+                taken += 1.0;
+            }
+            let cost = Decimal::from_f64(taken).ok_or(SimulationError::InvalidDecimal)?
+                * self.cost_per_action
+                + self.subscription_fee * Decimal::from(weight);
+            let amount = (cost / price)
+                .round_dp(precision)
+                .min(users.balances[position]);
+            if amount <= Decimal::ZERO {
+                continue;
+            }
+
+            users.balances[position] -= amount;
+            collection.consumed += amount;
+            if let Some(intervals) = self.lock_intervals {
+                locks
+                    .locks
+                    .push_back((index + intervals.max(1), position, amount));
+            }
+        }
+
+        Ok(collection)
+    }
+}
+
+impl UtilityLocks {
+    /// Return the tokens whose lock ends in an interval to their users.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the interval.
+    /// * `users` - Users of the simulation.
+    ///
+    /// # Returns
+    ///
+    /// Tokens released.
+    fn release(&mut self, index: u64, users: &mut UserStore) -> Decimal {
+        let mut released = Decimal::default();
+
+        while let Some((release, position, amount)) = self.locks.front().copied() {
+            if release > index {
+                break;
+            }
+            if let Some(balance) = users.balances.get_mut(position) {
+                *balance += amount;
+            }
+            released += amount;
+            self.locks.pop_front();
+        }
+
+        released
+    }
+
+    /// Get the tokens currently locked.
+    ///
+    /// # Returns
+    ///
+    /// Total locked tokens.
+    pub(crate) fn locked(&self) -> Decimal {
+        self.locks.iter().map(|(_, _, amount)| amount).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::{Simulation, TokenAmount, User, ValuationModel};
+
+    use super::*;
+
+    fn run(utility: UtilitySink) -> Simulation {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .valuation_model(ValuationModel::RandomWalk)
+            .total_users(20)
+            .duration(4)
+            .seed(5)
+            .utility(utility)
+            .build()
+            .unwrap();
+        let mut simulation = Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap();
+
+        simulation.run().unwrap();
+        simulation
+    }
+
+    #[test]
+    fn test_collect() {
+        let mut users = UserStore::from(vec![
+            User::new(uuid::Uuid::new_v4(), Decimal::new(10, 0));
+            2
+        ]);
+        users.balances[1] = Decimal::new(1, 0);
+        let utility = UtilitySink {
+            lock_intervals: Some(2),
+            ..UtilitySink::new(Decimal::new(2, 0), 1.0)
+        };
+        let mut locks = UtilityLocks::default();
+        let mut rng = StdRng::seed_from_u64(1);
+
+        // An action costs 4 tokens at a price of 0.5, the second user can only pay 1
+        let collection = utility
+            .collect(0, &mut users, Decimal::new(5, 1), 4, &mut locks, &mut rng)
+            .unwrap();
+        assert_eq!(collection.consumed, Decimal::new(5, 0));
+        assert_eq!(users.balances[0], Decimal::new(6, 0));
+        assert_eq!(users.balances[1], Decimal::ZERO);
+        assert_eq!(locks.locked(), Decimal::new(5, 0));
+
+        // The locked tokens are returned after two intervals
+        let collection = utility
+            .collect(2, &mut users, Decimal::new(5, 1), 4, &mut locks, &mut rng)
+            .unwrap();
+        assert_eq!(collection.released, Decimal::new(5, 0));
+        assert_eq!(users.balances[1], Decimal::ZERO);
+        assert_eq!(users.balances[0], Decimal::new(6, 0));
+    }
+
+    #[test]
+    fn test_actions_at() {
+        let utility = UtilitySink {
+            usage_growth: 0.5,
+            ..UtilitySink::new(Decimal::ONE, 2.0)
+        };
+
+        assert_eq!(utility.actions_at(0), 2.0);
+        assert_eq!(utility.actions_at(2), 4.5);
+    }
+
+    #[test]
+    fn test_run_with_utility() {
+        let burned = run(UtilitySink {
+            usage_growth: 0.1,
+            ..UtilitySink::new(Decimal::new(1, 2), 1.0)
+        });
+        let consumed: Vec<_> = burned
+            .interval_reports
+            .iter()
+            .map(|report| report.utility_consumed)
+            .collect();
+        assert!(consumed
+            .iter()
+            .all(|amount| *amount > TokenAmount::default()));
+        assert_eq!(
+            burned.report.utility_consumed,
+            consumed.iter().copied().sum::<TokenAmount>()
+        );
+        assert!(burned.report.total_burned >= burned.report.utility_consumed);
+
+        let locked = run(UtilitySink {
+            lock_intervals: Some(10),
+            ..UtilitySink::new(Decimal::new(1, 2), 1.0)
+        });
+        assert!(locked.report.utility_locked > TokenAmount::default());
+        assert_eq!(locked.report.utility_locked, locked.report.utility_consumed);
+    }
+}
@@ -0,0 +1,409 @@
+//! # Venue module
+//!
+//! This module contains the trading venues of the token.
+//!
+//! The token always trades on-chain, on a DEX. Exchange listings add external venues, such as a CEX,
+//! each with its own fee and depth. Volume is split between venues by depth,
+//! and each venue drifts from the reference price with its own order flow.
+
+use std::collections::BTreeMap;
+
+use rand::Rng;
+use rust_decimal::{prelude::*, Decimal};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{SimulationReport, TradeSide};
+
+/// Name of the on-chain venue.
+pub const DEX_VENUE: &str = "dex";
+
+/// Listing of the token on an external exchange.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct ExchangeListing {
+    /// Name of the exchange.
+    pub name: String,
+
+    /// Interval in which the token is listed, starting at 0.
+    pub listing_interval: u64,
+
+    /// Fee charged by the exchange on each trade, in percentage.
+    /// Trades on the exchange pay this fee instead of the on-chain fees.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub fee_percentage: Decimal,
+
+    /// Depth of the exchange order book, in tokens.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub depth: Decimal,
+}
+
+/// Trading venues of the token.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct VenueConfig {
+    /// Depth of the on-chain liquidity pool, in tokens.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub dex_depth: Decimal,
+
+    /// Listings on external exchanges.
+    pub listings: Vec<ExchangeListing>,
+}
+
+/// Activity of a trading venue.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct VenueReport {
+    /// Name of the venue.
+    pub name: String,
+
+    /// Volume traded on the venue, in tokens.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub volume: Decimal,
+
+    /// Share of the total volume traded on the venue, between 0 and 1.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub volume_share: Decimal,
+
+    /// Price of the token on the venue.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub price: Decimal,
+
+    /// Relative divergence of the venue price from the DEX price.
+    /// In the final report, this is the mean absolute divergence.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub price_divergence: Decimal,
+
+    /// Fees collected by the venue, in tokens.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub fees: Decimal,
+}
+
+/// Order flow of the venues during an interval.
+#[derive(Debug)]
+pub(crate) struct VenueBook {
+    /// Active venues: name, depth, and fee in percentage. The first venue is the DEX.
+    venues: Vec<(String, Decimal, Decimal)>,
+
+    /// Volume traded on each venue.
+    volumes: Vec<Decimal>,
+
+    /// Net amount bought on each venue.
+    net_flows: Vec<Decimal>,
+
+    /// Fees collected by each venue.
+    fees: Vec<Decimal>,
+}
+
+impl VenueBook {
+    /// Open the venues active in the given interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Trading venues of the token.
+    /// * `interval` - Index of the interval, starting at 0.
+    ///
+    /// # Returns
+    ///
+    /// Order flow of the active venues.
+    pub(crate) fn new(config: &VenueConfig, interval: u64) -> Self {
+        let mut venues = vec![(DEX_VENUE.to_string(), config.dex_depth, Decimal::default())];
+        venues.extend(
+            config
+                .listings
+                .iter()
+                .filter(|listing| listing.listing_interval <= interval)
+                .map(|listing| (listing.name.clone(), listing.depth, listing.fee_percentage)),
+        );
+
+        let count = venues.len();
+        VenueBook {
+            venues,
+            volumes: vec![Decimal::default(); count],
+            net_flows: vec![Decimal::default(); count],
+            fees: vec![Decimal::default(); count],
+        }
+    }
+
+    /// Pick the venue of a trade, with a probability proportional to the depth of the venue.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - Random number generator.
+    ///
+    /// # Returns
+    ///
+    /// Index of the venue, 0 for the DEX.
+    pub(crate) fn pick<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        let depths: Vec<f64> = self
+            .venues
+            .iter()
+            .map(|(_, depth, _)| depth.to_f64().unwrap_or_default().max(0.0))
+            .collect();
+        let total: f64 = depths.iter().sum();
+        if total <= 0.0 {
+            return 0;
+        }
+
+        let mut target = rng.random_range(0.0..total);
+        for (index, depth) in depths.iter().enumerate() {
+            if target < *depth {
+                return index;
+            }
+            target -= depth;
+        }
+
+        0
+    }
+
+    /// Get the name and fee of a venue.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the venue.
+    ///
+    /// # Returns
+    ///
+    /// Name of the venue and its fee, in percentage.
+    pub(crate) fn venue(&self, index: usize) -> (&str, Decimal) {
+        let (name, _, fee) = &self.venues[index];
+
+        (name, *fee)
+    }
+
+    /// Calculate the slippage of a trade on a venue.
+    /// The venue is modelled as a constant product pool, with the depth as the token reserve.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the venue.
+    /// * `amount` - Amount of tokens traded.
+    ///
+    /// # Returns
+    ///
+    /// Slippage of the trade, in percentage.
+    pub(crate) fn slippage(&self, index: usize, amount: Decimal) -> Decimal {
+        let (_, depth, _) = &self.venues[index];
+
+        (amount * Decimal::new(100, 0))
+            .checked_div(*depth + amount)
+            .unwrap_or_default()
+    }
+
+    /// Record a trade made on a venue.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the venue.
+    /// * `side` - Direction of the trade.
+    /// * `amount` - Amount of tokens traded.
+    /// * `fee` - Fee collected by the venue.
+    pub(crate) fn record(&mut self, index: usize, side: TradeSide, amount: Decimal, fee: Decimal) {
+        self.volumes[index] += amount;
+        self.fees[index] += fee;
+        match side {
+            TradeSide::Buy => self.net_flows[index] += amount,
+            TradeSide::Sell => self.net_flows[index] -= amount,
+        }
+    }
+
+    /// Report the activity of the venues.
+    /// The price of each venue moves from the reference price with the net flow relative to its depth.
+    ///
+    /// # Arguments
+    ///
+    /// * `price` - Reference price of the token.
+    /// * `decimals` - Number of decimal places to round to.
+    ///
+    /// # Returns
+    ///
+    /// Activity of each venue, starting with the DEX.
+    pub(crate) fn reports(&self, price: Decimal, decimals: u32) -> Vec<VenueReport> {
+        let total_volume: Decimal = self.volumes.iter().sum();
+        let prices: Vec<Decimal> = self
+            .venues
+            .iter()
+            .zip(self.net_flows.iter())
+            .map(|((_, depth, _), flow)| {
+                let impact = flow.checked_div(*depth).unwrap_or_default();
+                (price * (Decimal::ONE + impact)).max(Decimal::default())
+            })
+            .collect();
+        let dex_price = prices[0];
+
+        self.venues
+            .iter()
+            .enumerate()
+            .map(|(index, (name, _, _))| VenueReport {
+                name: name.clone(),
+                volume: self.volumes[index],
+                volume_share: self.volumes[index]
+                    .checked_div(total_volume)
+                    .unwrap_or_default()
+                    .round_dp(decimals),
+                price: prices[index].round_dp(decimals),
+                price_divergence: (prices[index] - dex_price)
+                    .checked_div(dex_price)
+                    .unwrap_or_default()
+                    .round_dp(decimals),
+                fees: self.fees[index],
+            })
+            .collect()
+    }
+}
+
+/// Aggregate the activity of the venues over the intervals of a simulation.
+///
+/// # Arguments
+///
+/// * `reports` - Interval reports of the simulation.
+/// * `decimals` - Number of decimal places to round to.
+///
+/// # Returns
+///
+/// Total activity of each venue, with the mean price and mean absolute price divergence.
+pub(crate) fn aggregate_venues(reports: &[SimulationReport], decimals: u32) -> Vec<VenueReport> {
+    let mut totals: BTreeMap<&str, (VenueReport, Decimal)> = BTreeMap::new();
+    for venue in reports.iter().flat_map(|report| report.venues.iter()) {
+        let (total, intervals) = totals.entry(&venue.name).or_insert((
+            VenueReport {
+                name: venue.name.clone(),
+                volume: Decimal::default(),
+                volume_share: Decimal::default(),
+                price: Decimal::default(),
+                price_divergence: Decimal::default(),
+                fees: Decimal::default(),
+            },
+            Decimal::default(),
+        ));
+        total.volume += venue.volume;
+        total.fees += venue.fees;
+        total.price += venue.price;
+        total.price_divergence += venue.price_divergence.abs();
+        *intervals += Decimal::ONE;
+    }
+
+    let total_volume: Decimal = totals.values().map(|(venue, _)| venue.volume).sum();
+    let mut venues: Vec<VenueReport> = totals
+        .into_values()
+        .map(|(mut venue, intervals)| {
+            venue.volume_share = venue
+                .volume
+                .checked_div(total_volume)
+                .unwrap_or_default()
+                .round_dp(decimals);
+            venue.price = (venue.price / intervals).round_dp(decimals);
+            venue.price_divergence = (venue.price_divergence / intervals).round_dp(decimals);
+            venue
+        })
+        .collect();
+
+    // Keep the DEX first, as in the interval reports
+    venues.sort_by_key(|venue| venue.name != DEX_VENUE);
+
+    venues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> VenueConfig {
+        VenueConfig {
+            dex_depth: Decimal::new(1_000, 0),
+            listings: vec![ExchangeListing {
+                name: "cex".to_string(),
+                listing_interval: 2,
+                fee_percentage: Decimal::new(1, 1),
+                depth: Decimal::new(3_000, 0),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_listing_interval() {
+        assert_eq!(VenueBook::new(&config(), 1).venues.len(), 1);
+        assert_eq!(VenueBook::new(&config(), 2).venues.len(), 2);
+        assert_eq!(
+            VenueBook::new(&config(), 2).venue(1),
+            ("cex", Decimal::new(1, 1))
+        );
+    }
+
+    #[test]
+    fn test_pick() {
+        let mut rng = rand::rng();
+
+        let book = VenueBook::new(&config(), 0);
+        assert!((0..100).all(|_| book.pick(&mut rng) == 0));
+
+        let book = VenueBook::new(&config(), 2);
+        let cex = (0..1_000).filter(|_| book.pick(&mut rng) == 1).count();
+        assert!(cex > 600 && cex < 900);
+    }
+
+    #[test]
+    fn test_slippage() {
+        let book = VenueBook::new(&config(), 2);
+
+        assert_eq!(
+            book.slippage(0, Decimal::new(1_000, 0)),
+            Decimal::new(50, 0)
+        );
+        assert_eq!(
+            book.slippage(1, Decimal::new(1_000, 0)),
+            Decimal::new(25, 0)
+        );
+        assert_eq!(book.slippage(0, Decimal::default()), Decimal::default());
+    }
+
+    #[test]
+    fn test_reports() {
+        let mut book = VenueBook::new(&config(), 2);
+        book.record(0, TradeSide::Buy, Decimal::new(100, 0), Decimal::default());
+        book.record(1, TradeSide::Sell, Decimal::new(300, 0), Decimal::new(3, 1));
+
+        let reports = book.reports(Decimal::ONE, 4);
+
+        assert_eq!(reports[0].volume_share, Decimal::new(25, 2));
+        assert_eq!(reports[0].price, Decimal::new(11, 1));
+        assert_eq!(reports[1].volume_share, Decimal::new(75, 2));
+        assert_eq!(reports[1].price, Decimal::new(9, 1));
+        assert_eq!(reports[1].price_divergence, Decimal::new(-1818, 4));
+        assert_eq!(reports[1].fees, Decimal::new(3, 1));
+    }
+
+    #[test]
+    fn test_aggregate_venues() {
+        let mut book = VenueBook::new(&config(), 2);
+        book.record(0, TradeSide::Buy, Decimal::new(100, 0), Decimal::default());
+        book.record(1, TradeSide::Sell, Decimal::new(300, 0), Decimal::default());
+
+        let reports = vec![
+            SimulationReport {
+                venues: VenueBook::new(&config(), 0).reports(Decimal::ONE, 4),
+                ..Default::default()
+            },
+            SimulationReport {
+                venues: book.reports(Decimal::ONE, 4),
+                ..Default::default()
+            },
+        ];
+        let venues = aggregate_venues(&reports, 4);
+
+        assert_eq!(venues[0].name, DEX_VENUE);
+        assert_eq!(venues[0].volume, Decimal::new(100, 0));
+        assert_eq!(venues[0].price, Decimal::new(105, 2));
+        assert_eq!(venues[1].volume_share, Decimal::new(75, 2));
+        assert_eq!(venues[1].price_divergence, Decimal::new(1818, 4));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let config = config();
+
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(serde_json::from_str::<VenueConfig>(&json).unwrap(), config);
+    }
+}
@@ -0,0 +1,431 @@
+//! # Vesting module
+//!
+//! This module contains the vesting schedules, which turn an allocation into unlock events.
+//!
+//! A schedule is a list of cliffs, each releasing a percentage of the allocation a number of months after the start.
+//! A cliff can also vest linearly, releasing its percentage monthly since the previous cliff.
+//! The percentages of the cliffs must sum to 100%, so the whole allocation is released.
+
+use chrono::{DateTime, Months, Utc};
+use rust_decimal::{prelude::FromPrimitive, Decimal};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{SimulationError, Token, TokenAmount, UnlockEvent};
+
+/// Cliff of a vesting schedule.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct VestingCliff {
+    /// Number of months after the start of the schedule.
+    pub months: u32,
+
+    /// Percentage of the allocation released by the cliff.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub percentage: Decimal,
+
+    /// Whether the percentage is released monthly since the previous cliff, instead of at once.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub linear: bool,
+}
+
+/// Vesting schedule of an allocation.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct VestingSchedule {
+    /// Start of the schedule, e.g. the token generation event.
+    pub start: DateTime<Utc>,
+
+    /// Amount of tokens vested by the schedule.
+    #[cfg_attr(feature = "serde", serde(with = "rust_decimal::serde::float"))]
+    pub total_amount: Decimal,
+
+    /// Cliffs of the schedule, in chronological order.
+    pub cliffs: Vec<VestingCliff>,
+}
+
+impl VestingSchedule {
+    /// Create a new vesting schedule builder.
+    ///
+    /// # Returns
+    ///
+    /// New vesting schedule builder.
+    pub fn builder() -> VestingScheduleBuilder {
+        VestingScheduleBuilder::new()
+    }
+
+    /// Get the unlock events of the schedule.
+    /// The amounts are derived from the vested total at each date, so they sum exactly to the total amount.
+    ///
+    /// # Returns
+    ///
+    /// Unlock events, in chronological order.
+    pub fn unlock_events(&self) -> Vec<UnlockEvent> {
+        let hundred = Decimal::new(100, 0);
+        let mut events = vec![];
+        let mut previous_months = 0;
+        let mut vested_percentage = Decimal::default();
+        let mut vested = Decimal::default();
+
+        for cliff in &self.cliffs {
+            let steps = match cliff.linear {
+                true => cliff.months - previous_months,
+                false => 1,
+            };
+            let start_percentage = vested_percentage;
+
+            for step in 1..=steps {
+                let months = cliff.months - (steps - step);
+                let percentage = start_percentage
+                    + cliff.percentage * Decimal::from(step) / Decimal::from(steps);
+                let total = self.total_amount * percentage / hundred;
+
+                events.push(UnlockEvent {
+                    date: self.start + Months::new(months),
+                    amount: total - vested,
+                });
+                vested = total;
+            }
+
+            previous_months = cliff.months;
+            vested_percentage += cliff.percentage;
+        }
+
+        events
+    }
+}
+
+impl Token {
+    /// Add the unlock events of a vesting schedule to the unlock schedule.
+    ///
+    /// # Arguments
+    ///
+    /// * `schedule` - The vesting schedule to add.
+    pub fn add_vesting_schedule(&mut self, schedule: &VestingSchedule) {
+        for event in schedule.unlock_events() {
+            self.add_unlock_event(event.date, TokenAmount(event.amount));
+        }
+    }
+}
+
+/// Builder for creating a new vesting schedule.
+#[derive(Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct VestingScheduleBuilder {
+    /// Start of the schedule.
+    /// Default value: now.
+    pub start: Option<DateTime<Utc>>,
+
+    /// Amount of tokens vested by the schedule.
+    /// Required field.
+    pub total_amount: Option<f64>,
+
+    /// Cliffs of the schedule.
+    /// Required field, the percentages must sum to 100%.
+    pub cliffs: Vec<VestingCliff>,
+}
+
+impl VestingScheduleBuilder {
+    /// New vesting schedule builder.
+    pub fn new() -> Self {
+        VestingScheduleBuilder::default()
+    }
+
+    /// Template vesting 25% after a 1-year cliff, then the rest monthly over the next 3 years.
+    ///
+    /// # Returns
+    ///
+    /// The vesting schedule builder, with the cliffs of the template.
+    pub fn four_year_linear_with_one_year_cliff() -> Self {
+        VestingScheduleBuilder::new()
+            .cliff(12, 25.0)
+            .linear(48, 75.0)
+    }
+
+    /// Template vesting 12.5% every quarter over 2 years.
+    ///
+    /// # Returns
+    ///
+    /// The vesting schedule builder, with the cliffs of the template.
+    pub fn two_year_quarterly() -> Self {
+        (1..=8).fold(VestingScheduleBuilder::new(), |builder, quarter| {
+            builder.cliff(quarter * 3, 12.5)
+        })
+    }
+
+    /// Set the start of the schedule.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - Start of the schedule.
+    ///
+    /// # Returns
+    ///
+    /// The vesting schedule builder.
+    pub fn start(mut self, start: DateTime<Utc>) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    /// Set the amount of tokens vested by the schedule.
+    ///
+    /// # Arguments
+    ///
+    /// * `total_amount` - Amount of tokens.
+    ///
+    /// # Returns
+    ///
+    /// The vesting schedule builder.
+    pub fn total_amount(mut self, total_amount: f64) -> Self {
+        self.total_amount = Some(total_amount);
+        self
+    }
+
+    /// Add a cliff releasing a percentage of the allocation at once.
+    ///
+    /// # Arguments
+    ///
+    /// * `months` - Number of months after the start.
+    /// * `percentage` - Percentage of the allocation released.
+    ///
+    /// # Returns
+    ///
+    /// The vesting schedule builder.
+    pub fn cliff(self, months: u32, percentage: f64) -> Self {
+        self.push(months, percentage, false)
+    }
+
+    /// Add a cliff releasing a percentage of the allocation monthly since the previous cliff.
+    ///
+    /// # Arguments
+    ///
+    /// * `months` - Number of months after the start, when the release ends.
+    /// * `percentage` - Percentage of the allocation released.
+    ///
+    /// # Returns
+    ///
+    /// The vesting schedule builder.
+    pub fn linear(self, months: u32, percentage: f64) -> Self {
+        self.push(months, percentage, true)
+    }
+
+    /// Add a cliff to the schedule.
+    /// A percentage that cannot be represented as a decimal is kept as zero, and rejected when building.
+    ///
+    /// # Arguments
+    ///
+    /// * `months` - Number of months after the start.
+    /// * `percentage` - Percentage of the allocation released.
+    /// * `linear` - Whether the percentage is released monthly since the previous cliff.
+    ///
+    /// # Returns
+    ///
+    /// The vesting schedule builder.
+    fn push(mut self, months: u32, percentage: f64, linear: bool) -> Self {
+        self.cliffs.push(VestingCliff {
+            months,
+            percentage: Decimal::from_f64(percentage).unwrap_or_default(),
+            linear,
+        });
+        self
+    }
+
+    /// Build the vesting schedule.
+    ///
+    /// # Returns
+    ///
+    /// Vesting schedule with the configured parameters,
+    /// or an error if the cliffs are not in chronological order or their percentages do not sum to 100%.
+    pub fn build(self) -> Result<VestingSchedule, SimulationError> {
+        let total_amount = match self.total_amount {
+            Some(amount) => Decimal::from_f64(amount).ok_or(SimulationError::InvalidDecimal)?,
+            None => {
+                return Err(SimulationError::InvalidVestingSchedule(
+                    "total amount is not set".to_string(),
+                ))
+            }
+        };
+
+        self.build_with_total_amount(total_amount)
+    }
+
+    /// Build the vesting schedule of the given amount of tokens, ignoring the amount set on the builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `total_amount` - Amount of tokens vested by the schedule.
+    ///
+    /// # Returns
+    ///
+    /// Vesting schedule with the configured parameters, or an error if the cliffs are invalid.
+    pub(crate) fn build_with_total_amount(
+        self,
+        total_amount: Decimal,
+    ) -> Result<VestingSchedule, SimulationError> {
+        if total_amount <= Decimal::default() {
+            return Err(SimulationError::InvalidVestingSchedule(
+                "total amount must be positive".to_string(),
+            ));
+        }
+
+        let mut previous_months = None;
+        for cliff in &self.cliffs {
+            if cliff.percentage <= Decimal::default() {
+                return Err(SimulationError::InvalidVestingSchedule(format!(
+                    "cliff at month {} must release a positive percentage",
+                    cliff.months
+                )));
+            }
+
+            let after_previous = previous_months.is_none_or(|previous| cliff.months > previous);
+            if !after_previous || (cliff.linear && cliff.months == 0) {
+                return Err(SimulationError::InvalidVestingSchedule(format!(
+                    "cliff at month {} must come after the previous cliff",
+                    cliff.months
+                )));
+            }
+            previous_months = Some(cliff.months);
+        }
+
+        let total_percentage: Decimal = self.cliffs.iter().map(|cliff| cliff.percentage).sum();
+        if total_percentage != Decimal::new(100, 0) {
+            return Err(SimulationError::InvalidVestingSchedule(format!(
+                "cliff percentages sum to {}%, expected 100%",
+                total_percentage
+            )));
+        }
+
+        Ok(VestingSchedule {
+            start: self.start.unwrap_or_else(Utc::now),
+            total_amount,
+            cliffs: self.cliffs,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Simulation;
+
+    use super::*;
+
+    #[test]
+    fn test_four_year_linear_with_one_year_cliff() {
+        let start = Utc::now();
+        let schedule = VestingScheduleBuilder::four_year_linear_with_one_year_cliff()
+            .start(start)
+            .total_amount(480_000.0)
+            .build()
+            .unwrap();
+
+        let events = schedule.unlock_events();
+
+        assert_eq!(events.len(), 37);
+        assert_eq!(events[0].date, start + Months::new(12));
+        assert_eq!(events[0].amount, Decimal::new(120_000, 0));
+        assert_eq!(events[1].date, start + Months::new(13));
+        assert_eq!(events[1].amount, Decimal::new(10_000, 0));
+        assert_eq!(events[36].date, start + Months::new(48));
+        assert_eq!(
+            events.iter().map(|event| event.amount).sum::<Decimal>(),
+            schedule.total_amount
+        );
+    }
+
+    #[test]
+    fn test_two_year_quarterly() {
+        let schedule = VestingScheduleBuilder::two_year_quarterly()
+            .total_amount(1_000.0)
+            .build()
+            .unwrap();
+
+        let events = schedule.unlock_events();
+
+        assert_eq!(events.len(), 8);
+        assert!(events
+            .iter()
+            .all(|event| event.amount == Decimal::new(125, 0)));
+        assert_eq!(events[7].date, schedule.start + Months::new(24));
+    }
+
+    #[test]
+    fn test_linear_amounts_sum_to_total() {
+        let schedule = VestingSchedule::builder()
+            .linear(3, 100.0)
+            .total_amount(100.0)
+            .build()
+            .unwrap();
+
+        let events = schedule.unlock_events();
+
+        assert_eq!(events.len(), 3);
+        assert_eq!(
+            events.iter().map(|event| event.amount).sum::<Decimal>(),
+            Decimal::new(100, 0)
+        );
+    }
+
+    #[test]
+    fn test_invalid_schedules() {
+        let errors = [
+            VestingSchedule::builder().cliff(12, 100.0).build(),
+            VestingSchedule::builder()
+                .total_amount(100.0)
+                .cliff(12, 50.0)
+                .build(),
+            VestingSchedule::builder()
+                .total_amount(100.0)
+                .cliff(12, 50.0)
+                .cliff(6, 50.0)
+                .build(),
+            VestingSchedule::builder()
+                .total_amount(100.0)
+                .linear(0, 100.0)
+                .build(),
+            VestingSchedule::builder()
+                .total_amount(100.0)
+                .cliff(6, 0.0)
+                .cliff(12, 100.0)
+                .build(),
+        ];
+
+        for error in errors {
+            assert!(matches!(
+                error,
+                Err(SimulationError::InvalidVestingSchedule(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_add_vesting_schedule_to_token() {
+        let mut token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let schedule = VestingScheduleBuilder::two_year_quarterly()
+            .total_amount(1_000.0)
+            .build()
+            .unwrap();
+
+        token.add_vesting_schedule(&schedule);
+
+        assert_eq!(token.unlock_schedule.unwrap(), schedule.unlock_events());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let schedule = VestingScheduleBuilder::four_year_linear_with_one_year_cliff()
+            .total_amount(1_000.0)
+            .build()
+            .unwrap();
+
+        let json = serde_json::to_string(&schedule).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<VestingSchedule>(&json).unwrap(),
+            schedule
+        );
+    }
+}
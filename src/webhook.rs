@@ -0,0 +1,347 @@
+//! # Webhook module
+//!
+//! This module contains the webhook notified when a simulation completes or fails.
+//!
+//! The payload is the JSON of the outcome, with the final report of completed simulations,
+//! so the simulator can be plugged into existing pipelines without polling.
+//! Payloads are only sent with the `serde` feature, which is needed to encode them.
+//! Delivery goes through a `WebhookTransport`: the built-in transport posts over plain HTTP,
+//! custom transports can add TLS, retries, or signing.
+
+use std::{
+    fmt::Debug,
+    io::{Read, Write},
+    net::TcpStream,
+    time::Duration,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{Simulation, SimulationError, SimulationReport};
+
+/// Timeout of the built-in HTTP transport, for connecting, writing, and reading.
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Webhook notified when a simulation completes or fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Webhook {
+    /// URL the payload is posted to.
+    pub url: String,
+
+    /// Value of the `Authorization` header, e.g. `Bearer <token>`.
+    /// If not set, the header is not sent.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub auth_header: Option<String>,
+}
+
+/// Outcome of a simulation reported to the webhook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum WebhookEvent {
+    /// Simulation has completed.
+    Completed,
+
+    /// Simulation has failed or was cancelled.
+    Failed,
+}
+
+/// Payload posted to the webhook.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct WebhookPayload {
+    /// ID of the simulation.
+    pub simulation_id: Uuid,
+
+    /// Name of the simulation.
+    pub name: String,
+
+    /// Outcome of the simulation.
+    pub event: WebhookEvent,
+
+    /// Final report of the simulation, if it completed.
+    pub report: Option<SimulationReport>,
+
+    /// Error of the simulation, if it failed.
+    pub error: Option<String>,
+}
+
+/// Transport delivering the webhook payloads.
+pub trait WebhookTransport: Debug + Send + Sync {
+    /// Post a payload.
+    ///
+    /// # Arguments
+    ///
+    /// * `webhook` - Webhook to notify.
+    /// * `body` - JSON of the payload.
+    ///
+    /// # Returns
+    ///
+    /// Whether the payload was delivered.
+    fn post(&self, webhook: &Webhook, body: &str) -> Result<(), SimulationError>;
+}
+
+/// Built-in transport posting the payloads over plain HTTP/1.1.
+/// Only `http://` URLs are supported, use a custom transport for HTTPS.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HttpTransport;
+
+impl WebhookTransport for HttpTransport {
+    fn post(&self, webhook: &Webhook, body: &str) -> Result<(), SimulationError> {
+        let failed = |reason: String| SimulationError::WebhookFailed(reason);
+
+        let address = webhook
+            .url
+            .strip_prefix("http://")
+            .ok_or_else(|| failed(format!("unsupported URL: {}", webhook.url)))?;
+        let (host, path) = match address.find('/') {
+            Some(index) => address.split_at(index),
+            None => (address, "/"),
+        };
+        let authority = match host.contains(':') {
+            true => host.to_string(),
+            false => format!("{}:80", host),
+        };
+
+        let mut request = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+            path,
+            host,
+            body.len()
+        );
+        if let Some(auth_header) = &webhook.auth_header {
+            request.push_str(&format!("Authorization: {}\r\n", auth_header));
+        }
+        request.push_str("\r\n");
+        request.push_str(body);
+
+        let mut stream =
+            TcpStream::connect(&authority).map_err(|error| failed(error.to_string()))?;
+        stream
+            .set_read_timeout(Some(HTTP_TIMEOUT))
+            .and_then(|_| stream.set_write_timeout(Some(HTTP_TIMEOUT)))
+            .and_then(|_| stream.write_all(request.as_bytes()))
+            .map_err(|error| failed(error.to_string()))?;
+
+        // Only the status line matters, e.g. `HTTP/1.1 204 No Content`
+        let mut response = [0; 64];
+        let read = stream
+            .read(&mut response)
+            .map_err(|error| failed(error.to_string()))?;
+        let status = String::from_utf8_lossy(&response[..read]);
+
+        match status.split_whitespace().nth(1) {
+            Some(code) if code.starts_with('2') => Ok(()),
+            _ => Err(failed(format!(
+                "unexpected response: {}",
+                status.lines().next().unwrap_or_default()
+            ))),
+        }
+    }
+}
+
+impl Simulation {
+    /// Set the transport delivering the webhook payloads.
+    /// Default is `HttpTransport`.
+    ///
+    /// # Arguments
+    ///
+    /// * `transport` - The transport to use.
+    pub fn set_webhook_transport<T: WebhookTransport + 'static>(&mut self, transport: T) {
+        self.webhook_transport = Some(Box::new(transport));
+    }
+
+    /// Notify the webhook of the simulation about its outcome, if configured.
+    /// Delivery failures do not change the outcome of the simulation, they are only logged.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - Error of the simulation, if it failed.
+    pub(crate) fn notify_webhook(&self, error: Option<&SimulationError>) {
+        let Some(webhook) = &self.options.webhook else {
+            return;
+        };
+
+        let payload = WebhookPayload {
+            simulation_id: self.id,
+            name: self.name.clone(),
+            event: match error {
+                Some(_) => WebhookEvent::Failed,
+                None => WebhookEvent::Completed,
+            },
+            report: error.is_none().then(|| self.report.clone()),
+            error: error.map(|error| error.to_string()),
+        };
+
+        #[cfg(feature = "serde")]
+        {
+            let body = match serde_json::to_string(&payload) {
+                Ok(body) => body,
+                Err(_error) => {
+                    #[cfg(feature = "log")]
+                    log::debug!("Failed to encode webhook payload: {}", _error);
+
+                    return;
+                }
+            };
+
+            let result = match &self.webhook_transport {
+                Some(transport) => transport.post(webhook, &body),
+                None => HttpTransport.post(webhook, &body),
+            };
+
+            if let Err(_error) = result {
+                #[cfg(feature = "log")]
+                log::debug!("Failed to notify webhook {}: {}", webhook.url, _error);
+            }
+        }
+
+        #[cfg(not(feature = "serde"))]
+        let _ = (webhook, payload);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "serde")]
+    use std::sync::{Arc, Mutex};
+    use std::{net::TcpListener, thread};
+
+    use super::*;
+
+    #[cfg(feature = "serde")]
+    #[derive(Debug, Default)]
+    struct RecordingTransport {
+        bodies: Mutex<Vec<String>>,
+    }
+
+    #[cfg(feature = "serde")]
+    impl WebhookTransport for Arc<RecordingTransport> {
+        fn post(&self, _webhook: &Webhook, body: &str) -> Result<(), SimulationError> {
+            self.bodies.lock().unwrap().push(body.to_string());
+            Ok(())
+        }
+    }
+
+    fn setup() -> Simulation {
+        let token = Simulation::token_builder()
+            .name("Test Token".to_string())
+            .build()
+            .unwrap();
+        let options = Simulation::options_builder()
+            .total_users(10)
+            .duration(2)
+            .webhook(Webhook {
+                url: "http://127.0.0.1:1/hook".to_string(),
+                auth_header: None,
+            })
+            .build()
+            .unwrap();
+
+        Simulation::builder()
+            .name("Test Simulation".to_string())
+            .token(token)
+            .options(options)
+            .build()
+            .unwrap()
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_notify_webhook_on_completion() {
+        let transport = Arc::new(RecordingTransport::default());
+        let mut simulation = setup();
+        simulation.set_webhook_transport(Arc::clone(&transport));
+
+        simulation.run().unwrap();
+
+        let bodies = transport.bodies.lock().unwrap();
+        assert_eq!(bodies.len(), 1);
+
+        let payload = serde_json::from_str::<WebhookPayload>(&bodies[0]).unwrap();
+        assert_eq!(payload.simulation_id, simulation.id);
+        assert_eq!(payload.event, WebhookEvent::Completed);
+        assert!(payload.report.is_some());
+        assert_eq!(payload.error, None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_notify_webhook_on_failure() {
+        let transport = Arc::new(RecordingTransport::default());
+        let mut simulation = setup();
+        simulation.options.transaction_fee = Some(crate::SimulationTransactionFee::Ethereum(
+            crate::EthereumFee {
+                gas_used: 21_000,
+                gas_price: None,
+                ether_price: None,
+            },
+        ));
+        simulation.set_webhook_transport(Arc::clone(&transport));
+
+        assert!(simulation.run().is_err());
+
+        let bodies = transport.bodies.lock().unwrap();
+        let payload = serde_json::from_str::<WebhookPayload>(&bodies[0]).unwrap();
+        assert_eq!(payload.event, WebhookEvent::Failed);
+        assert_eq!(payload.report, None);
+        assert_eq!(
+            payload.error,
+            Some(SimulationError::UnresolvedTransactionFee.to_string())
+        );
+    }
+
+    #[test]
+    fn test_run_with_unreachable_webhook() {
+        let mut simulation = setup();
+
+        // Delivery failures do not fail the simulation
+        simulation.run().unwrap();
+
+        assert_eq!(simulation.status, crate::SimulationStatus::Completed);
+    }
+
+    #[test]
+    fn test_http_transport() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = vec![0; 1024];
+            let read = stream.read(&mut request).unwrap();
+            stream
+                .write_all(b"HTTP/1.1 204 No Content\r\n\r\n")
+                .unwrap();
+
+            String::from_utf8_lossy(&request[..read]).to_string()
+        });
+
+        let webhook = Webhook {
+            url: format!("http://127.0.0.1:{}/hooks/simulation", port),
+            auth_header: Some("Bearer secret".to_string()),
+        };
+        HttpTransport.post(&webhook, "{}").unwrap();
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /hooks/simulation HTTP/1.1\r\n"));
+        assert!(request.contains("Authorization: Bearer secret\r\n"));
+        assert!(request.ends_with("\r\n\r\n{}"));
+    }
+
+    #[test]
+    fn test_http_transport_unsupported_url() {
+        let webhook = Webhook {
+            url: "https://example.com/hook".to_string(),
+            auth_header: None,
+        };
+
+        assert!(matches!(
+            HttpTransport.post(&webhook, "{}"),
+            Err(SimulationError::WebhookFailed(_))
+        ));
+    }
+}